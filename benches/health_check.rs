@@ -0,0 +1,24 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use kdguard::prelude::HealthCheck;
+
+/// Passwords covering an exact hit, a substring hit and no hit at all
+const PASSWORDS: &[(&str, &str)] = &[
+    ("common", "dragon"),
+    ("contains_common", "MyDragon2024!"),
+    ("strong", "t7#Rq!vZ2m@Lp9$Wx4&k"),
+];
+
+fn analyze_password(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analyze_password");
+    for (name, password) in PASSWORDS {
+        group.bench_function(*name, |b| {
+            b.iter(|| HealthCheck::analyze_password(black_box(password)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, analyze_password);
+criterion_main!(benches);
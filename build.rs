@@ -0,0 +1,47 @@
+//! Captures the commit and the build date for `kdguard --version` and embeds the
+//! release signing key
+
+use std::process::Command;
+
+use chrono::{DateTime, Utc};
+
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // Reproducible builds pin the date, see https://reproducible-builds.org/docs/source-date-epoch/
+    let build_date = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .and_then(|epoch| DateTime::<Utc>::from_timestamp(epoch, 0))
+        .unwrap_or_else(Utc::now);
+
+    println!("cargo:rustc-env=KDGUARD_COMMIT={}", commit);
+    println!(
+        "cargo:rustc-env=KDGUARD_BUILD_DATE={}",
+        build_date.format("%Y-%m-%d")
+    );
+    println!(
+        "cargo:rustc-env=KDGUARD_TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
+    // Only the key line of a minisign public key file, env values cannot span lines
+    let public_key = std::env::var("KDGUARD_RELEASE_PUBLIC_KEY")
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty() && !line.contains("comment:"))
+        .unwrap_or_default()
+        .to_string();
+    println!("cargo:rustc-env=KDGUARD_RELEASE_PUBLIC_KEY={}", public_key);
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-env-changed=KDGUARD_RELEASE_PUBLIC_KEY");
+}
@@ -1,22 +1,488 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
-use crate::errors::ConfigError;
-use crate::logging::LoggingManager;
-use lingua_i18n_rs::prelude::Lingua;
+use crate::errors::{ConfigError, GeneratorError, InvalidValue};
+use crate::i18n::tr;
+use crate::logging::{DEFAULT_MAX_LOG_SIZE_KB, LogFormat, LoggingManager};
+use crate::password::policy::{NIST_POLICY_NAME, STRICT_POLICY_NAME};
+use crate::password::{
+    DEFAULT_ARGON2_ITERATIONS, DEFAULT_ARGON2_MEMORY_KIB, Generator, Kdf, Policy, ScoringWeights,
+};
+use crate::{PasswordMode, parse_password_mode};
+use ring::digest::{SHA256, digest};
 use serde::{Deserialize, Serialize};
 
+/// Language files shipped with the binary
+pub(crate) const EMBEDDED_LANGUAGES: [(&str, &str); 2] = [
+    ("en", include_str!("../languages/en.json")),
+    ("de", include_str!("../languages/de.json")),
+];
+
+/// File in the languages directory naming the embedded versions last written
+const LANGUAGES_MARKER: &str = ".embedded";
+
+/// Environment variable that moves the config directory
+pub const CONFIG_DIR_ENV: &str = "KDGUARD_CONFIG_DIR";
+
+/// Config directory given with `--config`, set once before the config is loaded
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set for runs that must not create or change any file, e.g. `uninstall --dry-run`
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// How `config show` prints the config
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum ConfigFormat {
+    /// A labeled listing for reading
+    #[default]
+    Pretty,
+    /// The config as it is written to the file
+    Toml,
+    /// The `Config` struct as JSON
+    Json,
+}
+
+/// Schema version written to new and migrated config files
+pub const CONFIG_VERSION: i64 = 2;
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version of the file, files without one are version 1
+    #[serde(default = "config_version")]
+    pub version: i64,
     pub general: GeneralConfig,
     pub language: LanguageConfig,
+    #[serde(default)]
+    pub check: CheckConfig,
+    /// Weights of the health check score components
+    #[serde(default)]
+    pub scoring: ScoringWeights,
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub presets: BTreeMap<String, Preset>,
+    #[serde(default)]
+    pub keybindings: KeyBindingsConfig,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    #[serde(default)]
+    pub update: UpdateConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub deterministic: DeterministicConfig,
+    /// Services of the deterministic mode with a note each, listed on the backup sheet
+    #[serde(default)]
+    pub services: BTreeMap<String, String>,
+    /// Named patterns for `--pattern @name`, next to the built-in ones
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>,
+    /// The profile merged into `general`, never written to the file
+    #[serde(skip)]
+    pub active_profile: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct GeneralConfig {
     pub default_length: usize,
     pub default_count: usize,
     pub default_mode: String,
     pub auto_save: bool,
+    /// Pattern used in pattern mode when none is given
+    #[serde(default)]
+    pub default_pattern: Option<String>,
+    /// Number of words used in phrase mode when none is given
+    #[serde(default = "default_words")]
+    pub default_words: usize,
+    /// Directory for passwords saved with `--save` and no `--output`, the working
+    /// directory when unset
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    /// File name for passwords saved with `--save` and no `--output`
+    #[serde(default = "default_output_filename_template")]
+    pub output_filename_template: String,
+}
+
+fn default_words() -> usize {
+    4
+}
+
+/// Output file name used when the config does not set one
+pub const DEFAULT_OUTPUT_FILENAME_TEMPLATE: &str = "kdguard.txt";
+
+/// Placeholders replaced in the output file name template
+pub const OUTPUT_PLACEHOLDERS: [&str; 3] = ["date", "mode", "count"];
+
+fn default_output_filename_template() -> String {
+    DEFAULT_OUTPUT_FILENAME_TEMPLATE.to_string()
+}
+
+/// Check that an output file name template is a plain file name with known placeholders
+///
+/// # Arguments
+///
+/// * `template`: The template to check
+///
+/// # Returns
+///
+/// Returns true if the template can be rendered to a file name, otherwise false
+fn is_valid_output_template(template: &str) -> bool {
+    if template.trim().is_empty() || template.contains(['/', '\\']) {
+        return false;
+    }
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if OUTPUT_PLACEHOLDERS.contains(&&after[..end]) => rest = &after[end + 1..],
+            _ => return false,
+        }
+    }
+    true
+}
+
+impl GeneralConfig {
+    /// Fill in the placeholders of an output file name template
+    ///
+    /// # Arguments
+    ///
+    /// * `template`: The template with `{date}`, `{mode}`, and `{count}` placeholders
+    /// * `date`: The date of the run
+    /// * `mode`: The password mode
+    /// * `count`: The number of saved passwords
+    ///
+    /// # Returns
+    ///
+    /// Returns the file name
+    pub fn render_output_filename(template: &str, date: &str, mode: &str, count: usize) -> String {
+        template
+            .replace("{date}", date)
+            .replace("{mode}", mode)
+            .replace("{count}", &count.to_string())
+    }
+
+    /// Get the file for passwords saved without an explicit output path
+    ///
+    /// The output directory is created when it does not exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode`: The password mode
+    /// * `count`: The number of saved passwords
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the file, otherwise an error
+    pub fn output_path(&self, mode: &str, count: usize) -> Result<PathBuf, ConfigError> {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let filename =
+            Self::render_output_filename(&self.output_filename_template, &date, mode, count);
+        let Some(dir) = &self.output_dir else {
+            return Ok(PathBuf::from(filename));
+        };
+
+        fs::create_dir_all(dir).map_err(|e| {
+            LoggingManager::error(&format!("Failed to create output directory {}: {}", dir, e));
+            ConfigError::CreateOutputDirectoryError(format!("{}: {}", dir, e))
+        })?;
+        Ok(Path::new(dir).join(filename))
+    }
+}
+
+/// Environment variable that selects a profile
+pub const PROFILE_ENV: &str = "KDGUARD_PROFILE";
+
+/// Profile given with `--profile`, set once before the config is loaded
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// A named set of `[general]` values from the `[profiles]` config table
+///
+/// Set keys replace the general value while the profile is active.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ProfileConfig {
+    pub default_length: Option<usize>,
+    pub default_count: Option<usize>,
+    pub default_mode: Option<String>,
+    pub auto_save: Option<bool>,
+    pub default_pattern: Option<String>,
+    pub default_words: Option<usize>,
+    pub output_dir: Option<String>,
+    pub output_filename_template: Option<String>,
+}
+
+impl From<&GeneralConfig> for ProfileConfig {
+    fn from(general: &GeneralConfig) -> Self {
+        Self {
+            default_length: Some(general.default_length),
+            default_count: Some(general.default_count),
+            default_mode: Some(general.default_mode.clone()),
+            auto_save: Some(general.auto_save),
+            default_pattern: general.default_pattern.clone(),
+            default_words: Some(general.default_words),
+            output_dir: general.output_dir.clone(),
+            output_filename_template: Some(general.output_filename_template.clone()),
+        }
+    }
+}
+
+impl ProfileConfig {
+    /// Replace the general values with the values set in the profile
+    ///
+    /// # Arguments
+    ///
+    /// * `general`: The general config to change
+    pub fn apply(&self, general: &mut GeneralConfig) {
+        if let Some(length) = self.default_length {
+            general.default_length = length;
+        }
+        if let Some(count) = self.default_count {
+            general.default_count = count;
+        }
+        if let Some(mode) = &self.default_mode {
+            general.default_mode = mode.clone();
+        }
+        if let Some(auto_save) = self.auto_save {
+            general.auto_save = auto_save;
+        }
+        if let Some(pattern) = &self.default_pattern {
+            general.default_pattern = Some(pattern.clone());
+        }
+        if let Some(words) = self.default_words {
+            general.default_words = words;
+        }
+        if let Some(dir) = &self.output_dir {
+            general.output_dir = Some(dir.clone());
+        }
+        if let Some(template) = &self.output_filename_template {
+            general.output_filename_template = template.clone();
+        }
+    }
+
+    /// Check the values that are set
+    ///
+    /// # Arguments
+    ///
+    /// * `section`: The config section the values are from, used in the field names
+    /// * `patterns`: The `[patterns]` table a default pattern may name
+    ///
+    /// # Returns
+    ///
+    /// Returns every invalid value
+    fn problems(&self, section: &str, patterns: &BTreeMap<String, String>) -> Vec<InvalidValue> {
+        let mut problems = Vec::new();
+        let mut check = |valid: bool, field: &str, value: String, expected: String| {
+            if !valid {
+                problems.push(InvalidValue {
+                    field: format!("{}.{}", section, field),
+                    value,
+                    expected,
+                });
+            }
+        };
+        let range = |range: &std::ops::RangeInclusive<usize>| {
+            format!("{} to {}", range.start(), range.end())
+        };
+
+        if let Some(length) = self.default_length {
+            check(
+                Generator::LENGTH_RANGE.contains(&length),
+                "default_length",
+                length.to_string(),
+                range(&Generator::LENGTH_RANGE),
+            );
+        }
+        if let Some(count) = self.default_count {
+            check(
+                count >= 1,
+                "default_count",
+                count.to_string(),
+                "at least 1".to_string(),
+            );
+        }
+        if let Some(mode) = &self.default_mode {
+            check(
+                parse_password_mode(mode).is_ok(),
+                "default_mode",
+                format!("\"{}\"", mode),
+                "random, pattern, phrase, or deterministic".to_string(),
+            );
+        }
+        if let Some(pattern) = &self.default_pattern {
+            check(
+                Generator::validate_pattern(pattern, patterns).is_ok(),
+                "default_pattern",
+                format!("\"{}\"", pattern),
+                "U, L, D, S, aliases such as {alnum}, or @name".to_string(),
+            );
+        }
+        if let Some(words) = self.default_words {
+            check(
+                Generator::WORDS_RANGE.contains(&words),
+                "default_words",
+                words.to_string(),
+                range(&Generator::WORDS_RANGE),
+            );
+        }
+        if let Some(template) = &self.output_filename_template {
+            check(
+                is_valid_output_template(template),
+                "output_filename_template",
+                format!("\"{}\"", template),
+                "a file name with {date}, {mode}, or {count}".to_string(),
+            );
+        }
+        problems
+    }
+}
+
+fn config_version() -> i64 {
+    CONFIG_VERSION
+}
+
+/// Changes to the config made by `config edit` and the TUI settings
+///
+/// Fields left as None keep their current value.
+#[derive(Default)]
+pub struct ConfigUpdate {
+    pub lang: Option<String>,
+    pub password_length: Option<usize>,
+    pub count: Option<usize>,
+    pub auto_save: Option<bool>,
+    pub default_mode: Option<String>,
+    pub default_pattern: Option<String>,
+    pub default_words: Option<usize>,
+    pub output_dir: Option<String>,
+    pub output_filename_template: Option<String>,
+    pub theme: Option<String>,
+}
+
+impl ConfigUpdate {
+    /// Validate the changes and apply them to a config
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The config to change
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise an error and the config is left unchanged
+    pub fn apply(self, config: &mut Config) -> Result<(), ConfigError> {
+        if let Some(default_mode) = &self.default_mode {
+            parse_password_mode(default_mode).map_err(|_| {
+                LoggingManager::error(&format!("Unknown password mode: {}", default_mode));
+                ConfigError::UnknownModeError(default_mode.clone())
+            })?;
+        }
+        if let Some(lang) = &self.lang
+            && !Config::available_languages()?.contains(lang)
+        {
+            LoggingManager::error(&format!("Unknown language: {}", lang));
+            return Err(ConfigError::UnknownLanguageError(lang.clone()));
+        }
+        if let Some(pattern) = &self.default_pattern
+            && !pattern.is_empty()
+        {
+            Generator::validate_pattern(pattern, &config.patterns).map_err(|e| {
+                LoggingManager::error(&format!("Invalid default pattern {}: {}", pattern, e));
+                ConfigError::InvalidDefaultError(e.to_string())
+            })?;
+        }
+        if let Some(words) = self.default_words
+            && !Generator::WORDS_RANGE.contains(&words)
+        {
+            LoggingManager::error(&format!("Invalid default word count: {}", words));
+            return Err(ConfigError::InvalidDefaultError(
+                GeneratorError::InvalidWordCount.to_string(),
+            ));
+        }
+        if let Some(dir) = &self.output_dir
+            && !dir.is_empty()
+            && Path::new(dir)
+                .ancestors()
+                .find(|path| path.exists())
+                .is_some_and(|path| !path.is_dir())
+        {
+            LoggingManager::error(&format!("Output directory cannot be created: {}", dir));
+            return Err(ConfigError::InvalidDefaultError(format!(
+                "{} is not a directory and cannot be created",
+                dir
+            )));
+        }
+        if let Some(template) = &self.output_filename_template
+            && !is_valid_output_template(template)
+        {
+            LoggingManager::error(&format!("Invalid output file name template: {}", template));
+            return Err(ConfigError::InvalidDefaultError(format!(
+                "{} is not a file name with {{date}}, {{mode}}, or {{count}}",
+                template
+            )));
+        }
+        if let Some(theme) = &self.theme
+            && !THEME_PRESETS.contains(&theme.as_str())
+        {
+            LoggingManager::error(&format!("Unknown theme: {}", theme));
+            return Err(ConfigError::UnknownThemeError(theme.clone()));
+        }
+
+        if let Some(default_mode) = self.default_mode {
+            LoggingManager::info(&format!("Setting default mode to: {}", default_mode));
+            config.general.default_mode = default_mode.to_lowercase();
+        }
+        if let Some(lang) = self.lang {
+            LoggingManager::info(&format!("Setting language to: {}", lang));
+            config.language.lang = lang;
+        }
+        if let Some(length) = self.password_length {
+            LoggingManager::info(&format!("Setting default password length to: {}", length));
+            config.general.default_length = length;
+        }
+        if let Some(count) = self.count {
+            LoggingManager::info(&format!("Setting default count to: {}", count));
+            config.general.default_count = count;
+        }
+        if let Some(auto_save) = self.auto_save {
+            LoggingManager::info(&format!("Setting auto_save to: {}", auto_save));
+            config.general.auto_save = auto_save;
+        }
+        if let Some(pattern) = self.default_pattern {
+            // An empty pattern clears the default
+            LoggingManager::info(&format!("Setting default pattern to: {}", pattern));
+            config.general.default_pattern = (!pattern.is_empty()).then_some(pattern);
+        }
+        if let Some(words) = self.default_words {
+            LoggingManager::info(&format!("Setting default word count to: {}", words));
+            config.general.default_words = words;
+        }
+        if let Some(dir) = self.output_dir {
+            // An empty directory saves to the working directory again
+            LoggingManager::info(&format!("Setting output directory to: {}", dir));
+            config.general.output_dir = (!dir.is_empty()).then_some(dir);
+        }
+        if let Some(template) = self.output_filename_template {
+            LoggingManager::info(&format!(
+                "Setting output file name template to: {}",
+                template
+            ));
+            config.general.output_filename_template = template;
+        }
+        if let Some(theme) = self.theme {
+            LoggingManager::info(&format!("Setting theme to: {}", theme));
+            config.theme.set_preset(&theme);
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -24,6 +490,401 @@ pub struct LanguageConfig {
     pub lang: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct CheckConfig {
+    pub banned_words_path: Option<String>,
+}
+
+/// When kdguard looks for a new release on its own
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Check at most once a day when generating passwords in a terminal
+    pub check_updates: bool,
+    /// Offer release candidates and betas as well
+    pub allow_prerelease: bool,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check_updates: true,
+            allow_prerelease: false,
+        }
+    }
+}
+
+/// How kdguard connects to the internet, for the update check and the breach check
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Proxy URL, wins over `HTTPS_PROXY` and `HTTP_PROXY`
+    pub proxy: Option<String>,
+    /// PEM file with CA certificates trusted in addition to the system ones
+    pub extra_ca_bundle: Option<String>,
+}
+
+/// How kdguard writes its log files
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Size in KB after which a new log file is started
+    pub max_log_size_kb: u64,
+    /// `text` or `json`, `--log-format` wins
+    pub log_format: LogFormat,
+    /// Write the hostname, CPU, and memory to `system_info.log` for bug reports
+    pub collect_system_info: bool,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            max_log_size_kb: DEFAULT_MAX_LOG_SIZE_KB,
+            log_format: LogFormat::default(),
+            collect_system_info: true,
+        }
+    }
+}
+
+/// How deterministic mode derives its passwords from the seed
+///
+/// The Argon2id parameters are part of the derivation, other machines need the same
+/// values to get the same passwords.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct DeterministicConfig {
+    /// `hkdf` or `argon2id`, `--kdf` wins
+    pub kdf: Kdf,
+    /// Memory of the Argon2id stretching in KiB
+    pub memory_kib: u32,
+    /// Passes of the Argon2id stretching
+    pub iterations: u32,
+    /// Fingerprint of the usual seed, a seed with another one is warned about
+    pub fingerprint: Option<String>,
+}
+
+impl Default for DeterministicConfig {
+    fn default() -> Self {
+        Self {
+            kdf: Kdf::default(),
+            memory_kib: DEFAULT_ARGON2_MEMORY_KIB,
+            iterations: DEFAULT_ARGON2_ITERATIONS,
+            fingerprint: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PolicyConfig {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Policy>,
+}
+
+/// A named generator configuration from the `[presets]` config table
+///
+/// Only the fields used by the mode are set, see `Preset::validate`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Preset {
+    pub mode: String,
+    pub length: Option<usize>,
+    pub count: Option<usize>,
+    pub pattern: Option<String>,
+    pub words: Option<usize>,
+    pub seed_env: Option<String>,
+    pub service: Option<String>,
+    pub salt: Option<String>,
+}
+
+impl Preset {
+    /// Check that the mode exists and only the fields of that mode are set
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the preset is coherent, otherwise an error naming the problem
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mode = parse_password_mode(&self.mode)
+            .map_err(|_| ConfigError::UnknownModeError(self.mode.clone()))?;
+        let invalid = |reason: &str| Err(ConfigError::InvalidPresetError(reason.to_string()));
+
+        if self.count == Some(0) {
+            return invalid("count must be at least 1");
+        }
+        match mode {
+            PasswordMode::Pattern if self.pattern.as_deref().is_none_or(str::is_empty) => {
+                return invalid("pattern mode needs a pattern");
+            }
+            PasswordMode::Pattern => {}
+            _ if self.pattern.is_some() => return invalid("a pattern needs pattern mode"),
+            _ => {}
+        }
+        if self.length.is_some() && mode != PasswordMode::Random {
+            return invalid("a length needs random mode");
+        }
+        if self.words.is_some() && mode != PasswordMode::Phrase {
+            return invalid("words need phrase mode");
+        }
+        if (self.seed_env.is_some() || self.service.is_some() || self.salt.is_some())
+            && mode != PasswordMode::Deterministic
+        {
+            return invalid("seed env, service, and salt need deterministic mode");
+        }
+
+        Ok(())
+    }
+}
+
+/// The names of the built-in TUI key binding presets, the first one is the default
+pub const KEYBINDING_PRESETS: [&str; 2] = ["default", "vim"];
+
+/// TUI key bindings from the `[keybindings]` config section
+///
+/// Each action takes a key or a list of keys and replaces the keys of the preset for that action.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct KeyBindingsConfig {
+    pub preset: Option<String>,
+    pub up: Option<KeyList>,
+    pub down: Option<KeyList>,
+    pub select: Option<KeyList>,
+    pub back: Option<KeyList>,
+    pub edit: Option<KeyList>,
+    pub copy: Option<KeyList>,
+    pub save: Option<KeyList>,
+    pub quit: Option<KeyList>,
+}
+
+/// A single key (`"k"`) or a list of keys (`["k", "Up"]`)
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    /// Get the keys as a slice
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+/// The names of the built-in TUI themes, the first one is the default
+pub const THEME_PRESETS: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// The TUI theme
+///
+/// Either a preset name (`theme = "light"`) or a `[theme]` table overriding
+/// single colors of its preset.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum ThemeConfig {
+    Preset(String),
+    Custom(ThemeColors),
+}
+
+/// Colors of a `[theme]` table, given as names (`"cyan"`) or hex values (`"#00afff"`)
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ThemeColors {
+    pub preset: Option<String>,
+    pub accent: Option<String>,
+    pub highlight: Option<String>,
+    pub error: Option<String>,
+    pub success: Option<String>,
+    pub border: Option<String>,
+    pub muted: Option<String>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig::Preset(THEME_PRESETS[0].to_string())
+    }
+}
+
+impl ThemeConfig {
+    /// Get the name of the preset the theme is based on
+    pub fn preset(&self) -> &str {
+        match self {
+            ThemeConfig::Preset(name) => name,
+            ThemeConfig::Custom(colors) => colors.preset.as_deref().unwrap_or(THEME_PRESETS[0]),
+        }
+    }
+
+    /// Switch to another preset, keeping the color overrides of a `[theme]` table
+    ///
+    /// # Arguments
+    ///
+    /// * `preset`: The name of the preset
+    pub fn set_preset(&mut self, preset: &str) {
+        match self {
+            ThemeConfig::Preset(name) => *name = preset.to_string(),
+            ThemeConfig::Custom(colors) => colors.preset = Some(preset.to_string()),
+        }
+    }
+}
+
+impl Default for Config {
+    /// The built-in config written on first start and by a config reset
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            general: GeneralConfig {
+                default_length: 16,
+                default_count: 1,
+                default_mode: "random".to_string(),
+                auto_save: false,
+                default_pattern: None,
+                default_words: default_words(),
+                output_dir: None,
+                output_filename_template: default_output_filename_template(),
+            },
+            language: LanguageConfig {
+                lang: "en".to_string(),
+            },
+            check: CheckConfig::default(),
+            scoring: ScoringWeights::default(),
+            policy: PolicyConfig::default(),
+            theme: ThemeConfig::default(),
+            presets: BTreeMap::new(),
+            keybindings: KeyBindingsConfig::default(),
+            profiles: BTreeMap::new(),
+            update: UpdateConfig::default(),
+            network: NetworkConfig::default(),
+            logging: LoggingConfig::default(),
+            deterministic: DeterministicConfig::default(),
+            services: BTreeMap::new(),
+            patterns: BTreeMap::new(),
+            active_profile: None,
+        }
+    }
+}
+
+/// Turn a TOML error into a parse error pointing at its line and column
+///
+/// # Arguments
+///
+/// * `config_str`: The TOML contents the error was found in
+/// * `error`: The TOML error
+///
+/// # Returns
+///
+/// Returns the parse error
+fn parse_error(config_str: &str, error: toml::de::Error) -> ConfigError {
+    let message = error.message().trim_end();
+    ConfigError::ParseConfigurationError(
+        match error.span().and_then(|span| config_str.get(..span.start)) {
+            Some(before) => {
+                let line = before.matches('\n').count() + 1;
+                let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+                format!("line {}, column {}: {}", line, column, message)
+            }
+            None => message.to_string(),
+        },
+    )
+}
+
+/// Get a short hash of an embedded language file for the languages marker
+///
+/// # Arguments
+///
+/// * `content`: The content of the language file
+///
+/// # Returns
+///
+/// Returns the first 16 hex digits of its SHA-256 hash
+fn language_hash(content: &str) -> String {
+    digest(&SHA256, content.as_bytes())
+        .as_ref()
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Flatten a table into its values by dotted key
+///
+/// # Arguments
+///
+/// * `table`: The table to flatten
+///
+/// # Returns
+///
+/// Returns the values as TOML text by dotted key
+fn flatten(table: toml::Table) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    let mut pending: Vec<(String, toml::Table)> = vec![(String::new(), table)];
+    while let Some((prefix, table)) = pending.pop() {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                toml::Value::Table(table) => pending.push((key, table)),
+                value => {
+                    values.insert(key, value.to_string());
+                }
+            }
+        }
+    }
+    values
+}
+
+/// Read the text of `config set` as the type of the current value
+///
+/// # Arguments
+///
+/// * `key`: The dotted key, used in errors
+/// * `current`: The current value, None if the key has no value
+/// * `value`: The new value as text
+///
+/// # Returns
+///
+/// Returns the typed value, or an error naming the expected type
+fn parse_key_value(
+    key: &str,
+    current: Option<&toml::Value>,
+    value: &str,
+) -> Result<toml::Value, ConfigError> {
+    let mismatch = |expected: &str| {
+        ConfigError::InvalidValue(vec![InvalidValue {
+            field: key.to_string(),
+            value: value.to_string(),
+            expected: expected.to_string(),
+        }])
+    };
+    let literal = || {
+        toml::from_str::<toml::Table>(&format!("value = {}", value))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+    };
+
+    match current {
+        Some(toml::Value::String(_)) => Ok(toml::Value::String(value.to_string())),
+        Some(toml::Value::Integer(_)) => value
+            .parse()
+            .map(toml::Value::Integer)
+            .map_err(|_| mismatch("a whole number")),
+        Some(toml::Value::Float(_)) => value
+            .parse()
+            .map(toml::Value::Float)
+            .map_err(|_| mismatch("a number")),
+        Some(toml::Value::Boolean(_)) => value
+            .parse()
+            .map(toml::Value::Boolean)
+            .map_err(|_| mismatch("true or false")),
+        Some(toml::Value::Array(_)) => literal()
+            .filter(toml::Value::is_array)
+            .ok_or_else(|| mismatch("a TOML array")),
+        Some(toml::Value::Table(_)) => Err(mismatch("a single value, set the keys of the table")),
+        Some(toml::Value::Datetime(_)) | None => {
+            Ok(literal().unwrap_or_else(|| toml::Value::String(value.to_string())))
+        }
+    }
+}
+
 impl Config {
     /// Load the config file
     ///
@@ -38,22 +899,21 @@ impl Config {
             "Invalid config path: no parent directory".to_string(),
         ))?;
 
+        // Neither created nor migrated on disk, the defaults or the migration stay in memory
+        if Self::is_read_only() {
+            return match fs::read_to_string(&config_path) {
+                Ok(config_str) => Self::parse_and_migrate(&config_str).map(|(config, _)| config),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+                Err(e) => Err(ConfigError::ReadConfigFileError(e.to_string())),
+            };
+        }
+
         fs::create_dir_all(config_dir)
             .map_err(|e| ConfigError::CreateConfigDirectoryError(e.to_string()))?;
 
         if !config_path.exists() {
             LoggingManager::info("Config file not found, creating default config");
-            let config = Config {
-                general: GeneralConfig {
-                    default_length: 16,
-                    default_count: 1,
-                    default_mode: "random".to_string(),
-                    auto_save: false,
-                },
-                language: LanguageConfig {
-                    lang: "en".to_string(),
-                },
-            };
+            let config = Config::default();
             Self::save_config(&config)?;
             LoggingManager::info("Default config created successfully");
             Ok(config)
@@ -63,10 +923,25 @@ impl Config {
                 LoggingManager::error(&format!("Failed to read config file: {}", e));
                 ConfigError::ReadConfigFileError(e.to_string())
             })?;
-            let config: Config = toml::from_str(&config_str).map_err(|e| {
-                LoggingManager::error(&format!("Failed to parse config file: {}", e));
-                ConfigError::ParseConfigurationError(e.to_string())
+            let (config, changes) = Self::parse_and_migrate(&config_str).inspect_err(|e| {
+                LoggingManager::error(&format!("Failed to load config file: {}", e));
             })?;
+            if !changes.is_empty() {
+                let backup_path = config_path.with_extension("toml.bak");
+                fs::copy(&config_path, &backup_path).map_err(|e| {
+                    LoggingManager::error(&format!("Failed to back up config file: {}", e));
+                    ConfigError::WriteConfigFileError(e.to_string())
+                })?;
+                for change in &changes {
+                    LoggingManager::info(&format!("Migrated config: {}", change));
+                }
+                Self::save_config(&config)?;
+                LoggingManager::info(&format!(
+                    "Config migrated to version {}, the old file was saved to {}",
+                    CONFIG_VERSION,
+                    backup_path.display()
+                ));
+            }
             LoggingManager::info(&format!(
                 "Config loaded successfully: language={}, default_length={}, default_count={}, default_mode={}, auto_save={}",
                 config.language.lang,
@@ -79,6 +954,175 @@ impl Config {
         }
     }
 
+    /// Parse and validate the contents of a config file
+    ///
+    /// # Arguments
+    ///
+    /// * `config_str`: The TOML contents of the config file
+    ///
+    /// # Returns
+    ///
+    /// Returns the config if it is valid, otherwise the position of the syntax error
+    /// or every invalid value
+    pub fn parse(config_str: &str) -> Result<Config, ConfigError> {
+        Self::parse_and_migrate(config_str).map(|(config, _)| config)
+    }
+
+    /// Parse the contents of a config file, migrating files of older versions
+    ///
+    /// # Arguments
+    ///
+    /// * `config_str`: The TOML contents of the config file
+    ///
+    /// # Returns
+    ///
+    /// Returns the valid config and the changes made by the migration, which are
+    /// empty if the file is up to date
+    pub(crate) fn parse_and_migrate(
+        config_str: &str,
+    ) -> Result<(Config, Vec<String>), ConfigError> {
+        let mut table: toml::Table =
+            toml::from_str(config_str).map_err(|e| parse_error(config_str, e))?;
+        let changes = Self::migrate(&mut table)?;
+
+        // Up to date files are parsed from the text so errors keep their position
+        let config: Config = if changes.is_empty() {
+            toml::from_str(config_str).map_err(|e| parse_error(config_str, e))?
+        } else {
+            table
+                .try_into()
+                .map_err(|e: toml::de::Error| parse_error(config_str, e))?
+        };
+        config.validate()?;
+        Ok((config, changes))
+    }
+
+    /// Upgrade a parsed config file to the current version
+    ///
+    /// # Arguments
+    ///
+    /// * `table`: The parsed config file, changed in place
+    ///
+    /// # Returns
+    ///
+    /// Returns a description of every change, or an error if the file is from a newer version
+    pub fn migrate(table: &mut toml::Table) -> Result<Vec<String>, ConfigError> {
+        let version = match table.get("version") {
+            None => 1,
+            Some(toml::Value::Integer(version)) => *version,
+            Some(value) => {
+                return Err(ConfigError::InvalidValue(vec![InvalidValue {
+                    field: "version".to_string(),
+                    value: value.to_string(),
+                    expected: "a number".to_string(),
+                }]));
+            }
+        };
+        if version > CONFIG_VERSION {
+            return Err(ConfigError::NewerVersionError(version, CONFIG_VERSION));
+        }
+
+        let mut changes = Vec::new();
+        if version < 2 {
+            // Version 2 added the default pattern and word count, the pattern stays unset
+            if let Some(toml::Value::Table(general)) = table.get_mut("general")
+                && !general.contains_key("default_words")
+            {
+                let words = default_words() as i64;
+                general.insert("default_words".to_string(), toml::Value::Integer(words));
+                changes.push(format!("added general.default_words = {}", words));
+            }
+        }
+
+        if version < CONFIG_VERSION {
+            table.insert("version".to_string(), toml::Value::Integer(CONFIG_VERSION));
+            changes.push(format!(
+                "version {} upgraded to {}",
+                version, CONFIG_VERSION
+            ));
+        }
+        Ok(changes)
+    }
+
+    /// Check the values a hand-edited config could get wrong
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the config is valid, otherwise an error listing every invalid value
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = ProfileConfig::from(&self.general).problems("general", &self.patterns);
+        if self.version != CONFIG_VERSION {
+            problems.push(InvalidValue {
+                field: "version".to_string(),
+                value: self.version.to_string(),
+                expected: CONFIG_VERSION.to_string(),
+            });
+        }
+        for (name, profile) in &self.profiles {
+            problems.extend(profile.problems(&format!("profiles.{}", name), &self.patterns));
+        }
+        // Named patterns cannot refer to each other
+        for (name, pattern) in &self.patterns {
+            if pattern.starts_with('@')
+                || Generator::validate_pattern(pattern, &BTreeMap::new()).is_err()
+            {
+                problems.push(InvalidValue {
+                    field: format!("patterns.{}", name),
+                    value: format!("\"{}\"", pattern),
+                    expected: "U, L, D, S, and aliases such as {alnum}".to_string(),
+                });
+            }
+        }
+        for (name, weight) in self.scoring.fields() {
+            if !(weight.is_finite() && weight > 0.0) {
+                problems.push(InvalidValue {
+                    field: format!("scoring.{}", name),
+                    value: weight.to_string(),
+                    expected: "a number above 0".to_string(),
+                });
+            }
+        }
+        if self.logging.max_log_size_kb == 0 {
+            problems.push(InvalidValue {
+                field: "logging.max_log_size_kb".to_string(),
+                value: "0".to_string(),
+                expected: "at least 1".to_string(),
+            });
+        }
+        // Argon2 needs 8 KiB per lane, more than 4 GiB would not fit on most machines
+        let deterministic = &self.deterministic;
+        if !(8..=4 * 1024 * 1024).contains(&deterministic.memory_kib) {
+            problems.push(InvalidValue {
+                field: "deterministic.memory_kib".to_string(),
+                value: deterministic.memory_kib.to_string(),
+                expected: "between 8 and 4194304".to_string(),
+            });
+        }
+        if deterministic.iterations == 0 {
+            problems.push(InvalidValue {
+                field: "deterministic.iterations".to_string(),
+                value: "0".to_string(),
+                expected: "at least 1".to_string(),
+            });
+        }
+        // The languages directory is checked on its own at startup
+        if let Ok(languages) = Self::available_languages()
+            && !languages.contains(&self.language.lang)
+        {
+            problems.push(InvalidValue {
+                field: "language.lang".to_string(),
+                value: format!("\"{}\"", self.language.lang),
+                expected: format!("one of {}", languages.join(", ")),
+            });
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::InvalidValue(problems))
+        }
+    }
+
     /// Save the config file
     ///
     /// # Arguments
@@ -115,98 +1159,591 @@ impl Config {
     ///
     /// Returns the path to the config file
     pub fn get_config_path() -> Result<PathBuf, ConfigError> {
+        let config_path = Self::get_config_dir()?.join("config.toml");
+
+        Ok(config_path)
+    }
+
+    /// Use another config directory for the rest of the process
+    ///
+    /// Must be called before the config, the languages, or the logging are set up,
+    /// later calls are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: The directory holding the config file, languages, and logs
+    pub fn set_config_dir(dir: PathBuf) {
+        let _ = CONFIG_DIR_OVERRIDE.set(dir);
+    }
+
+    /// Keep the config, the languages, and the config directory as they are for the rest
+    /// of the process
+    ///
+    /// Must be called before the config or the languages are set up.
+    pub fn set_read_only() {
+        READ_ONLY.store(true, Ordering::Relaxed);
+    }
+
+    /// Check whether this run must not create or change any file
+    pub fn is_read_only() -> bool {
+        READ_ONLY.load(Ordering::Relaxed)
+    }
+
+    /// Get the config directory set with `--config` or `KDGUARD_CONFIG_DIR`
+    ///
+    /// # Returns
+    ///
+    /// Returns the directory, or None if the default directory is used
+    pub fn config_dir_override() -> Option<PathBuf> {
+        CONFIG_DIR_OVERRIDE.get().cloned().or_else(|| {
+            std::env::var_os(CONFIG_DIR_ENV)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+        })
+    }
+
+    /// Get the directory holding the config file and the languages
+    ///
+    /// # Returns
+    ///
+    /// Returns the override if one is set, otherwise the kdguard directory in the
+    /// user's config directory
+    pub fn get_config_dir() -> Result<PathBuf, ConfigError> {
+        if let Some(dir) = Self::config_dir_override() {
+            return Ok(dir);
+        }
         let config_dir = dirs::config_dir().ok_or(ConfigError::GetConfigDirectoryError(
             "Failed to get config directory".to_string(),
         ))?;
-        let config_path = config_dir.join("kdguard").join("config.toml");
 
-        Ok(config_path)
+        Ok(config_dir.join("kdguard"))
+    }
+
+    /// Select a profile for the rest of the process
+    ///
+    /// Must be called before the config is loaded, later calls are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the profile
+    pub fn set_profile(name: String) {
+        let _ = PROFILE_OVERRIDE.set(name);
+    }
+
+    /// Get the profile selected with `--profile` or `KDGUARD_PROFILE`
+    ///
+    /// # Returns
+    ///
+    /// Returns the profile name, or None if no profile is selected
+    pub fn selected_profile() -> Option<String> {
+        PROFILE_OVERRIDE.get().cloned().or_else(|| {
+            std::env::var(PROFILE_ENV)
+                .ok()
+                .filter(|name| !name.is_empty())
+        })
+    }
+
+    /// Load the config file with the selected profile merged into the general values
+    ///
+    /// Use `load_config` for a config that is changed and saved again.
+    ///
+    /// # Returns
+    ///
+    /// Returns the merged config if successful, otherwise an error
+    pub fn load_active() -> Result<Config, ConfigError> {
+        let config = Self::load_config()?;
+        match Self::selected_profile() {
+            Some(name) => config.with_profile(&name),
+            None => Ok(config),
+        }
+    }
+
+    /// Get the general values with a profile merged in
+    ///
+    /// # Arguments
+    ///
+    /// * `profile`: The name of the profile, None for the plain general values
+    ///
+    /// # Returns
+    ///
+    /// Returns the merged general values, or an error listing the available profiles
+    pub fn general_for(&self, profile: Option<&str>) -> Result<GeneralConfig, ConfigError> {
+        let mut general = self.general.clone();
+        if let Some(name) = profile {
+            let profile = self.profiles.get(name).ok_or_else(|| {
+                let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                let available = if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                };
+                let error = format!("'{}' (available: {})", name, available);
+                LoggingManager::error(&format!("Unknown profile: {}", error));
+                ConfigError::UnknownProfileError(error)
+            })?;
+            profile.apply(&mut general);
+        }
+        Ok(general)
+    }
+
+    /// Merge a profile into the general values
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the profile
+    ///
+    /// # Returns
+    ///
+    /// Returns the config with the profile active, or an error listing the available profiles
+    pub fn with_profile(mut self, name: &str) -> Result<Config, ConfigError> {
+        LoggingManager::info(&format!("Using profile: {}", name));
+        self.general = self.general_for(Some(name))?;
+        self.active_profile = Some(name.to_string());
+        Ok(self)
     }
 
     /// Get the path to the languages directory
     ///
-    /// Languages are embedded in the binary, but this function returns
-    /// a temporary directory path for Lingua initialization.
+    /// The embedded languages are written to it when missing or outdated, other
+    /// language files placed there are left alone. A read-only run only uses a
+    /// directory that already exists.
     ///
     /// # Returns
     ///
     /// Returns the path to the languages directory
     pub fn get_languages_path() -> Result<PathBuf, ConfigError> {
-        const EN_JSON: &str = include_str!("../languages/en.json");
-        const DE_JSON: &str = include_str!("../languages/de.json");
+        let languages_dir = Self::get_config_dir()?.join("languages");
+        if Self::is_read_only() {
+            if !languages_dir.is_dir() {
+                return Err(ConfigError::GetLanguagesDirectoryError(format!(
+                    "{} does not exist",
+                    languages_dir.display()
+                )));
+            }
+            return Ok(languages_dir);
+        }
+        Self::install_languages(&languages_dir)?;
 
-        let config_dir = dirs::config_dir().ok_or(ConfigError::GetConfigDirectoryError(
-            "Failed to get config directory".to_string(),
-        ))?;
-        let languages_dir = config_dir.join("kdguard").join("languages");
+        Ok(languages_dir)
+    }
+
+    /// Write the embedded language files that are missing or older than the binary
+    ///
+    /// The marker file keeps a hash of each embedded file as it was last written, so a
+    /// file is only replaced when a new version of kdguard ships a changed one.
+    ///
+    /// # Arguments
+    ///
+    /// * `languages_dir`: The languages directory
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the files are up to date, otherwise an error
+    fn install_languages(languages_dir: &Path) -> Result<(), ConfigError> {
+        let marker_path = languages_dir.join(LANGUAGES_MARKER);
+        let marker = fs::read_to_string(&marker_path).unwrap_or_default();
+        let entries: Vec<String> = EMBEDDED_LANGUAGES
+            .iter()
+            .map(|(lang, content)| format!("{} {}", lang, language_hash(content)))
+            .collect();
+
+        let outdated: Vec<_> = EMBEDDED_LANGUAGES
+            .iter()
+            .zip(&entries)
+            .filter(|((lang, _), entry)| {
+                !languages_dir.join(format!("{}.json", lang)).exists()
+                    || !marker.lines().any(|line| line == entry.as_str())
+            })
+            .collect();
+        if outdated.is_empty() {
+            return Ok(());
+        }
 
         LoggingManager::info(&format!(
             "Setting up languages directory: {}",
             languages_dir.display()
         ));
-        fs::create_dir_all(&languages_dir)
+        fs::create_dir_all(languages_dir)
             .map_err(|e| ConfigError::CreateConfigDirectoryError(e.to_string()))?;
-        fs::write(languages_dir.join("en.json"), EN_JSON)
-            .map_err(|e| ConfigError::WriteConfigFileError(e.to_string()))?;
-        fs::write(languages_dir.join("de.json"), DE_JSON)
+        for ((lang, content), _) in outdated {
+            LoggingManager::info(&format!("Writing embedded language file: {}", lang));
+            fs::write(languages_dir.join(format!("{}.json", lang)), content)
+                .map_err(|e| ConfigError::WriteConfigFileError(e.to_string()))?;
+        }
+        fs::write(&marker_path, entries.join("\n") + "\n")
             .map_err(|e| ConfigError::WriteConfigFileError(e.to_string()))?;
         LoggingManager::info("Language files extracted successfully");
 
-        Ok(languages_dir)
+        Ok(())
+    }
+
+    /// Get the embedded languages whose file is missing or differs from the built-in one
+    ///
+    /// # Arguments
+    ///
+    /// * `languages_dir`: The languages directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the codes of the missing or changed languages
+    pub(crate) fn modified_languages(languages_dir: &Path) -> Vec<String> {
+        EMBEDDED_LANGUAGES
+            .iter()
+            .filter(|(lang, content)| {
+                fs::read_to_string(languages_dir.join(format!("{}.json", lang)))
+                    .map_or(true, |file| file != *content)
+            })
+            .map(|(lang, _)| lang.to_string())
+            .collect()
+    }
+
+    /// List the files kdguard itself places in the config directory
+    ///
+    /// # Arguments
+    ///
+    /// * `config_dir`: The config directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the config file and its backup, the languages and logs directories, and
+    /// the files of the update check and the breach index, whether they exist or not
+    pub fn owned_files(config_dir: &Path) -> Vec<PathBuf> {
+        [
+            "config.toml",
+            "config.toml.bak",
+            "languages",
+            "logs",
+            crate::update::LAST_CHECK_FILE,
+            crate::update::SKIPPED_VERSION_FILE,
+            crate::password::breach_db::INDEX_FILE,
+        ]
+        .iter()
+        .map(|name| config_dir.join(name))
+        .collect()
+    }
+
+    /// Get the languages that have a language file
+    ///
+    /// # Returns
+    ///
+    /// Returns the sorted language codes, otherwise an error
+    pub fn available_languages() -> Result<Vec<String>, ConfigError> {
+        let languages_dir = Self::get_languages_path()?;
+        let entries = fs::read_dir(&languages_dir)
+            .map_err(|e| ConfigError::GetLanguagesDirectoryError(e.to_string()))?;
+
+        let mut languages: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        languages.sort();
+
+        Ok(languages)
     }
 
     /// Update the config file
     ///
+    /// The language is only validated and stored, callers switch the active
+    /// language with `Lingua::set_language` once the update succeeded.
+    ///
     /// # Arguments
     ///
-    /// * `lang`: The language to set
-    /// * `password_length`: The password length to set
-    /// * `count`: The count to set
-    /// * `auto_save`: The auto save to set
+    /// * `update`: The values to change
     ///
     /// # Returns
     ///
     /// Returns Ok(()) if successful, otherwise an error
-    pub fn update_config(
-        lang: Option<String>,
-        password_length: Option<usize>,
-        count: Option<usize>,
-        auto_save: Option<bool>,
-    ) -> Result<(), ConfigError> {
+    pub fn update_config(update: ConfigUpdate) -> Result<(), ConfigError> {
         LoggingManager::info("Updating config");
         let mut new_config = Config::load_config()?;
+        update.apply(&mut new_config)?;
+        new_config.validate()?;
 
-        if let Some(lang) = lang {
-            LoggingManager::info(&format!("Setting language to: {}", lang));
-            new_config.language.lang = lang;
-            Lingua::set_language(&new_config.language.lang).map_err(|e| {
-                LoggingManager::error(&format!("Failed to set language: {}", e));
-                ConfigError::SetLanguageError(e)
-            })?;
+        Self::save_config(&new_config)?;
+        LoggingManager::info("Config updated successfully");
+
+        Ok(())
+    }
+
+    /// Get a single value by its dotted key, e.g. `general.default_length`
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The dotted key of the value
+    ///
+    /// # Returns
+    ///
+    /// Returns the value as plain text, strings without quotes and tables as TOML,
+    /// or an error if the key is unknown or unset
+    pub fn get_key(&self, key: &str) -> Result<String, ConfigError> {
+        let table = self.to_table()?;
+        let mut value = None;
+        let mut current = Some(&table);
+        for part in key.split('.') {
+            value = current.and_then(|table| table.get(part));
+            current = value.and_then(toml::Value::as_table);
         }
-        if let Some(length) = password_length {
-            LoggingManager::info(&format!("Setting default password length to: {}", length));
-            new_config.general.default_length = length;
+
+        match value {
+            Some(toml::Value::String(value)) => Ok(value.clone()),
+            Some(toml::Value::Table(table)) => toml::to_string(table)
+                .map(|text| text.trim_end().to_string())
+                .map_err(|e| ConfigError::SerializeConfigurationError(e.to_string())),
+            Some(value) => Ok(value.to_string()),
+            None => Err(ConfigError::UnknownKeyError(key.to_string())),
         }
-        if let Some(count) = count {
-            LoggingManager::info(&format!("Setting default count to: {}", count));
-            new_config.general.default_count = count;
+    }
+
+    /// Set a single value by its dotted key
+    ///
+    /// The text is read as the type of the current value. Keys without a value, like an
+    /// unset `general.default_pattern`, take a TOML literal or else a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The dotted key of the value
+    /// * `value`: The new value as text
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the key exists and the changed config is valid, otherwise an error
+    pub fn set_key(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let mut table = self.to_table()?;
+        let (parents, name) = match key.rsplit_once('.') {
+            Some((parents, name)) => (Some(parents), name),
+            None => (None, key),
+        };
+
+        let mut current = &mut table;
+        for part in parents.into_iter().flat_map(|parents| parents.split('.')) {
+            let entry = current
+                .entry(part)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            current = match entry {
+                toml::Value::Table(table) => table,
+                _ => return Err(ConfigError::UnknownKeyError(key.to_string())),
+            };
         }
-        if let Some(auto_save) = auto_save {
-            LoggingManager::info(&format!("Setting auto_save to: {}", auto_save));
-            new_config.general.auto_save = auto_save;
+        let parsed = parse_key_value(key, current.get(name), value)?;
+        current.insert(name.to_string(), parsed);
+
+        let config: Config = table.try_into().map_err(|e: toml::de::Error| {
+            ConfigError::InvalidValue(vec![InvalidValue {
+                field: key.to_string(),
+                value: value.to_string(),
+                expected: e.message().trim_end().to_string(),
+            }])
+        })?;
+        // Unknown keys are dropped when the config is read back
+        config.get_key(key)?;
+        config.validate()?;
+
+        *self = config;
+        Ok(())
+    }
+
+    /// Set a single value by its dotted key and save the config file
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The dotted key of the value
+    /// * `value`: The new value as text
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise an error
+    pub fn set_config_value(key: &str, value: &str) -> Result<(), ConfigError> {
+        let mut config = Config::load_config()?;
+        config.set_key(key, value).inspect_err(|e| {
+            LoggingManager::error(&format!("Failed to set {}: {}", key, e));
+        })?;
+        LoggingManager::info(&format!("Setting {} to: {}", key, value));
+        Self::save_config(&config)
+    }
+
+    /// Write the config for another machine
+    ///
+    /// The banned words file, the output directory, and the CA bundle are local paths and
+    /// left out.
+    ///
+    /// # Returns
+    ///
+    /// Returns the config as TOML, otherwise an error
+    pub fn export(&self) -> Result<String, ConfigError> {
+        let mut table = self.to_table()?;
+        if let Some(toml::Value::Table(check)) = table.get_mut("check") {
+            check.remove("banned_words_path");
         }
+        if let Some(toml::Value::Table(general)) = table.get_mut("general") {
+            general.remove("output_dir");
+        }
+        if let Some(toml::Value::Table(network)) = table.get_mut("network") {
+            network.remove("extra_ca_bundle");
+        }
+        toml::to_string_pretty(&table)
+            .map_err(|e| ConfigError::SerializeConfigurationError(e.to_string()))
+    }
 
-        Self::save_config(&new_config)?;
-        LoggingManager::info("Config updated successfully");
+    /// Read a config exported on another machine
+    ///
+    /// The file is migrated and validated like the config file, the local banned words
+    /// file, output directory, and CA bundle of this config are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_str`: The exported TOML
+    ///
+    /// # Returns
+    ///
+    /// Returns the config to save, otherwise an error
+    pub fn import(&self, config_str: &str) -> Result<Config, ConfigError> {
+        let mut config = Self::parse(config_str)?;
+        config.check = self.check.clone();
+        config.general.output_dir = self.general.output_dir.clone();
+        config.network.extra_ca_bundle = self.network.extra_ca_bundle.clone();
+        Ok(config)
+    }
 
-        println!(
-            "\n\x1b[1;32m{}\x1b[0m",
-            Lingua::t("config.edit.success", &[]).unwrap()
-        );
-        println!("{}", "=".repeat(50));
+    /// List the values that differ from another config
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: The config to compare with
+    ///
+    /// # Returns
+    ///
+    /// Returns one line per dotted key, `- key = value` for the value of this config and
+    /// `+ key = value` for the value of the other one
+    pub fn diff(&self, other: &Config) -> Result<Vec<String>, ConfigError> {
+        let (old, new) = (flatten(self.to_table()?), flatten(other.to_table()?));
+        let keys: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+        let mut lines = Vec::new();
+        for key in keys {
+            let (old, new) = (old.get(key), new.get(key));
+            if old == new {
+                continue;
+            }
+            if let Some(old) = old {
+                lines.push(format!("- {} = {}", key, old));
+            }
+            if let Some(new) = new {
+                lines.push(format!("+ {} = {}", key, new));
+            }
+        }
+        Ok(lines)
+    }
+
+    fn to_table(&self) -> Result<toml::Table, ConfigError> {
+        toml::Table::try_from(self)
+            .map_err(|e| ConfigError::SerializeConfigurationError(e.to_string()))
+    }
+
+    /// Replace the config file with the built-in defaults
+    ///
+    /// The previous file is kept as `config.toml.bak`, the languages directory is left alone.
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the backup if there was a config file, otherwise an error
+    pub fn reset_config() -> Result<Option<PathBuf>, ConfigError> {
+        LoggingManager::info("Resetting config to defaults");
+        let config_path = Self::get_config_path()?;
+
+        let backup_path = if config_path.exists() {
+            let backup_path = config_path.with_extension("toml.bak");
+            fs::copy(&config_path, &backup_path).map_err(|e| {
+                LoggingManager::error(&format!("Failed to back up config file: {}", e));
+                ConfigError::WriteConfigFileError(e.to_string())
+            })?;
+            Some(backup_path)
+        } else {
+            None
+        };
+        Self::save_config(&Config::default())?;
+        LoggingManager::info("Config reset successfully");
+
+        Ok(backup_path)
+    }
+
+    /// Save a generator preset, replacing one with the same name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the preset
+    /// * `preset`: The preset to save
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise an error
+    pub fn save_preset(name: &str, preset: Preset) -> Result<(), ConfigError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ConfigError::InvalidPresetError(
+                "the name must not be empty".to_string(),
+            ));
+        }
+        preset.validate().inspect_err(|e| {
+            LoggingManager::error(&format!("Invalid preset {}: {}", name, e));
+        })?;
+
+        let mut config = Config::load_config()?;
+        LoggingManager::info(&format!("Saving preset: {}", name));
+        config.presets.insert(name.to_string(), preset);
+        Self::save_config(&config)
+    }
+
+    /// Get a policy profile by name
+    ///
+    /// Profiles from the `[policy.profiles]` config section take precedence over the built-in ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the profile
+    ///
+    /// # Returns
+    ///
+    /// Returns the policy profile if found, otherwise an error listing the available profiles
+    pub fn get_policy(&self, name: &str) -> Result<Policy, ConfigError> {
+        if let Some(policy) = self.policy.profiles.get(name) {
+            return Ok(policy.clone());
+        }
+
+        Policy::builtin(name).ok_or_else(|| {
+            let mut available = vec![NIST_POLICY_NAME.to_string(), STRICT_POLICY_NAME.to_string()];
+            available.extend(self.policy.profiles.keys().cloned());
+            let error = format!("'{}' (available: {})", name, available.join(", "));
+            LoggingManager::error(&format!("Unknown policy: {}", error));
+            ConfigError::UnknownPolicyError(error)
+        })
+    }
 
-        Ok(())
+    /// Render the config in a machine readable format, without ANSI codes
+    ///
+    /// The config holds no secrets, seeds are only ever read from the environment, so
+    /// nothing is redacted.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The config to render
+    /// * `format`: The format, `Pretty` gives the same TOML as `Toml`
+    ///
+    /// # Returns
+    ///
+    /// Returns the rendered config, otherwise an error if it cannot be serialized
+    pub fn render(config: &Config, format: ConfigFormat) -> Result<String, ConfigError> {
+        let rendered = match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(config).map_err(|e| e.to_string()),
+            ConfigFormat::Pretty | ConfigFormat::Toml => {
+                toml::to_string_pretty(config).map_err(|e| e.to_string())
+            }
+        };
+        rendered.map_err(|e| {
+            LoggingManager::error(&format!("Failed to serialize config: {}", e));
+            ConfigError::SerializeConfigurationError(e)
+        })
     }
 
     /// Print the config to the console
@@ -219,47 +1756,114 @@ impl Config {
         let language = config.language.lang.to_string();
         let default_count = config.general.default_count.to_string();
         let auto_save = config.general.auto_save.to_string();
+        let default_pattern = config.general.default_pattern.as_deref().unwrap_or("-");
+        let default_words = config.general.default_words.to_string();
+        let output_dir = config.general.output_dir.as_deref().unwrap_or("-");
+        let banned_words_path = config
+            .check
+            .banned_words_path
+            .clone()
+            .unwrap_or_else(|| "-".to_string());
 
-        println!(
-            "\n\x1b[1;36m{}\x1b[0m",
-            Lingua::t("config.show.title", &[]).unwrap()
-        );
+        println!("\n\x1b[1;36m{}\x1b[0m", tr("config.show.title", &[]));
         println!("{}", "=".repeat(50));
+        if let Some(profile) = &config.active_profile {
+            println!(
+                "{}",
+                tr("config.show.profile", &[("profile", profile.as_str())])
+            );
+        }
         println!(
             "\x1b[1;33m{}\x1b[0m",
-            Lingua::t("config.show.subtitle_general", &[]).unwrap()
+            tr("config.show.subtitle_general", &[])
         );
         println!(
             "  {}",
-            Lingua::t(
+            tr(
                 "config.show.default_length",
                 &[("default_length", default_length.as_str())]
             )
-            .unwrap()
         );
         println!(
             "  {}",
-            Lingua::t(
+            tr(
                 "config.show.default_count",
                 &[("default_count", default_count.as_str())]
             )
-            .unwrap()
         );
         println!(
             "  {}",
-            Lingua::t(
+            tr(
+                "config.show.default_mode",
+                &[("default_mode", config.general.default_mode.as_str())]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
+                "config.show.default_pattern",
+                &[("default_pattern", default_pattern)]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
+                "config.show.default_words",
+                &[("default_words", default_words.as_str())]
+            )
+        );
+        println!(
+            "  {}",
+            tr("config.show.output_dir", &[("output_dir", output_dir)])
+        );
+        println!(
+            "  {}",
+            tr(
+                "config.show.output_filename_template",
+                &[(
+                    "output_filename_template",
+                    config.general.output_filename_template.as_str()
+                )]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
                 "config.show.auto_save",
                 &[("auto_save", auto_save.to_string().as_str())]
             )
-            .unwrap()
         );
         println!(
             "\n\x1b[1;33m{}\x1b[0m",
-            Lingua::t("config.show.subtitle_language", &[]).unwrap()
+            tr("config.show.subtitle_language", &[])
+        );
+        println!(
+            "  {}",
+            tr("config.show.language", &[("language", language.as_str())])
+        );
+        println!(
+            "\n\x1b[1;33m{}\x1b[0m",
+            tr("config.show.subtitle_check", &[])
+        );
+        println!(
+            "  {}",
+            tr(
+                "config.show.banned_words_path",
+                &[("banned_words_path", banned_words_path.as_str())]
+            )
         );
+        let weights = config.scoring;
         println!(
             "  {}",
-            Lingua::t("config.show.language", &[("language", language.as_str())]).unwrap()
+            tr(
+                "config.show.scoring_weights",
+                &[
+                    ("length", weights.length.to_string().as_str()),
+                    ("diversity", weights.diversity.to_string().as_str()),
+                    ("complexity", weights.complexity.to_string().as_str()),
+                    ("entropy", weights.entropy.to_string().as_str())
+                ]
+            )
         );
         println!("{}", "=".repeat(50));
     }
@@ -268,6 +1872,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::password::GenerationMetadata;
 
     #[test]
     fn test_get_config_path() {
@@ -279,6 +1884,14 @@ mod tests {
         assert!(path_str.contains("config.toml"));
     }
 
+    #[test]
+    fn test_available_languages() {
+        let languages = Config::available_languages().unwrap();
+        assert!(languages.contains(&"en".to_string()));
+        assert!(languages.contains(&"de".to_string()));
+        assert!(languages.is_sorted());
+    }
+
     #[test]
     fn test_get_languages_path() {
         let path = Config::get_languages_path();
@@ -291,15 +1904,39 @@ mod tests {
     #[test]
     fn test_config_serialization() {
         let config = Config {
+            version: CONFIG_VERSION,
             general: GeneralConfig {
                 default_length: 20,
                 default_count: 5,
                 default_mode: "phrase".to_string(),
                 auto_save: true,
+                default_pattern: Some("ULLLDDS".to_string()),
+                default_words: 6,
+                output_dir: Some("/home/user/passwords".to_string()),
+                output_filename_template: "{date}_{mode}.txt".to_string(),
             },
             language: LanguageConfig {
                 lang: "de".to_string(),
             },
+            check: CheckConfig {
+                banned_words_path: Some("/etc/kdguard/banned.txt".to_string()),
+            },
+            scoring: ScoringWeights::default(),
+            policy: PolicyConfig::default(),
+            theme: ThemeConfig::Preset("light".to_string()),
+            presets: BTreeMap::new(),
+            keybindings: KeyBindingsConfig::default(),
+            profiles: BTreeMap::new(),
+            update: UpdateConfig::default(),
+            network: NetworkConfig {
+                proxy: Some("http://proxy.corp:3128".to_string()),
+                extra_ca_bundle: None,
+            },
+            logging: LoggingConfig::default(),
+            deterministic: DeterministicConfig::default(),
+            services: BTreeMap::new(),
+            patterns: BTreeMap::new(),
+            active_profile: None,
         };
 
         let config_str = toml::to_string(&config);
@@ -313,6 +1950,863 @@ mod tests {
         assert!(parsed.is_ok());
         let parsed = parsed.unwrap();
         assert_eq!(parsed.general.default_length, 20);
+        assert_eq!(parsed.general.default_pattern.as_deref(), Some("ULLLDDS"));
+        assert_eq!(parsed.general.default_words, 6);
         assert_eq!(parsed.language.lang, "de");
+        assert_eq!(
+            parsed.check.banned_words_path.as_deref(),
+            Some("/etc/kdguard/banned.txt")
+        );
+        assert_eq!(parsed.theme, ThemeConfig::Preset("light".to_string()));
+        assert_eq!(parsed.network, config.network);
+    }
+
+    /// A coherent preset for every generator mode
+    fn presets() -> BTreeMap<String, Preset> {
+        BTreeMap::from([
+            (
+                "long-random".to_string(),
+                Preset {
+                    mode: "random".to_string(),
+                    length: Some(32),
+                    count: Some(3),
+                    ..Preset::default()
+                },
+            ),
+            (
+                "pin-like".to_string(),
+                Preset {
+                    mode: "pattern".to_string(),
+                    pattern: Some("ULDDSS".to_string()),
+                    ..Preset::default()
+                },
+            ),
+            (
+                "phrase".to_string(),
+                Preset {
+                    mode: "phrase".to_string(),
+                    words: Some(6),
+                    count: Some(1),
+                    ..Preset::default()
+                },
+            ),
+            (
+                "github".to_string(),
+                Preset {
+                    mode: "deterministic".to_string(),
+                    seed_env: Some("KDGUARD_SEED".to_string()),
+                    service: Some("github".to_string()),
+                    salt: Some("2024".to_string()),
+                    ..Preset::default()
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_presets_serialization() {
+        let mut config: Config = toml::from_str(
+            r#"
+[general]
+default_length = 16
+default_count = 1
+default_mode = "random"
+auto_save = false
+
+[language]
+lang = "en"
+"#,
+        )
+        .unwrap();
+        assert!(config.presets.is_empty());
+
+        config.presets = presets();
+        let config_str = toml::to_string_pretty(&config).unwrap();
+        assert!(config_str.contains("[presets.github]"));
+        assert!(config_str.contains("pattern = \"ULDDSS\""));
+
+        let parsed: Config = toml::from_str(&config_str).unwrap();
+        assert_eq!(parsed.presets, presets());
+        for (name, preset) in &parsed.presets {
+            assert!(preset.validate().is_ok(), "preset {} is invalid", name);
+        }
+    }
+
+    #[test]
+    fn test_incoherent_presets_are_rejected() {
+        let preset = |mode: &str| Preset {
+            mode: mode.to_string(),
+            ..Preset::default()
+        };
+
+        assert!(preset("pattern").validate().is_err());
+        assert!(
+            Preset {
+                pattern: Some("UL".to_string()),
+                ..preset("random")
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            Preset {
+                words: Some(5),
+                ..preset("random")
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            Preset {
+                length: Some(20),
+                ..preset("phrase")
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            Preset {
+                service: Some("github".to_string()),
+                ..preset("phrase")
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            Preset {
+                count: Some(0),
+                ..preset("random")
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(matches!(
+            preset("fancy").validate(),
+            Err(ConfigError::UnknownModeError(_))
+        ));
+        assert!(Config::save_preset("  ", preset("random")).is_err());
+    }
+
+    #[test]
+    fn test_keybindings_section() {
+        let config_str = r#"
+[general]
+default_length = 16
+default_count = 1
+default_mode = "random"
+auto_save = false
+
+[language]
+lang = "en"
+
+[keybindings]
+preset = "vim"
+copy = "y"
+quit = ["q", "F10"]
+"#;
+
+        let parsed: Config = toml::from_str(config_str).unwrap();
+        let bindings = &parsed.keybindings;
+        assert_eq!(bindings.preset.as_deref(), Some("vim"));
+        assert_eq!(bindings.copy.as_ref().unwrap().keys(), ["y"]);
+        assert_eq!(bindings.quit.as_ref().unwrap().keys(), ["q", "F10"]);
+        assert!(bindings.up.is_none());
+
+        let config_str = toml::to_string(&parsed).unwrap();
+        let reparsed: Config = toml::from_str(&config_str).unwrap();
+        assert_eq!(&reparsed.keybindings, bindings);
+    }
+
+    #[test]
+    fn test_theme_section() {
+        let config_str = r##"
+[general]
+default_length = 16
+default_count = 1
+default_mode = "random"
+auto_save = false
+
+[language]
+lang = "en"
+
+[theme]
+preset = "high-contrast"
+accent = "#00afff"
+error = "lightred"
+"##;
+
+        let mut parsed: Config = toml::from_str(config_str).unwrap();
+        assert_eq!(parsed.theme.preset(), "high-contrast");
+        let ThemeConfig::Custom(colors) = &parsed.theme else {
+            panic!("expected a theme table");
+        };
+        assert_eq!(colors.accent.as_deref(), Some("#00afff"));
+        assert!(colors.success.is_none());
+
+        parsed.theme.set_preset("light");
+        let config_str = toml::to_string(&parsed).unwrap();
+        let parsed: Config = toml::from_str(&config_str).unwrap();
+        assert_eq!(parsed.theme.preset(), "light");
+        let ThemeConfig::Custom(colors) = &parsed.theme else {
+            panic!("expected a theme table");
+        };
+        assert_eq!(colors.error.as_deref(), Some("lightred"));
+    }
+
+    #[test]
+    fn test_policy_profiles() {
+        let config_str = r#"
+[general]
+default_length = 16
+default_count = 1
+default_mode = "random"
+auto_save = false
+
+[language]
+lang = "en"
+
+[policy.profiles.corp]
+min_length = 14
+min_special = 2
+forbid_username = true
+username = "jdoe"
+"#;
+
+        let config: Config = toml::from_str(config_str).unwrap();
+        let corp = config.get_policy("corp").unwrap();
+        assert_eq!(corp.min_length, 14);
+        assert_eq!(corp.min_special, 2);
+        assert!(corp.forbid_username);
+        assert_eq!(corp.username(), Some("jdoe"));
+        assert!(!corp.forbid_common);
+
+        assert_eq!(config.get_policy("strict").unwrap(), Policy::strict());
+        let error = config.get_policy("missing").err().unwrap().to_string();
+        assert!(error.contains("corp"));
+        assert!(error.contains("strict"));
+    }
+
+    #[test]
+    fn test_config_without_check_section() {
+        let config_str = r#"
+[general]
+default_length = 16
+default_count = 1
+default_mode = "random"
+auto_save = false
+
+[language]
+lang = "en"
+"#;
+
+        let parsed: Config = toml::from_str(config_str).unwrap();
+        assert!(parsed.check.banned_words_path.is_none());
+        assert!(parsed.policy.profiles.is_empty());
+        assert_eq!(parsed.theme.preset(), "dark");
+        assert!(parsed.general.default_pattern.is_none());
+        assert_eq!(parsed.general.default_words, 4);
+    }
+
+    #[test]
+    fn test_default_config_round_trips() {
+        let defaults = Config::default();
+        let parsed: Config = toml::from_str(&toml::to_string(&defaults).unwrap()).unwrap();
+        assert_eq!(parsed.general.default_length, 16);
+        assert_eq!(parsed.general.default_mode, "random");
+        assert_eq!(parsed.language.lang, "en");
+        assert!(parsed.presets.is_empty());
+        assert_eq!(parsed.keybindings.preset, None);
+    }
+
+    #[test]
+    fn test_update_sets_generator_defaults() {
+        let mut config = Config::default();
+        ConfigUpdate {
+            default_mode: Some("Pattern".to_string()),
+            default_pattern: Some("UUDDS".to_string()),
+            default_words: Some(7),
+            ..Default::default()
+        }
+        .apply(&mut config)
+        .unwrap();
+        assert_eq!(config.general.default_mode, "pattern");
+        assert_eq!(config.general.default_pattern.as_deref(), Some("UUDDS"));
+        assert_eq!(config.general.default_words, 7);
+
+        // An empty pattern removes the default
+        ConfigUpdate {
+            default_pattern: Some(String::new()),
+            ..Default::default()
+        }
+        .apply(&mut config)
+        .unwrap();
+        assert!(config.general.default_pattern.is_none());
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_generator_defaults() {
+        let invalid = [
+            ConfigUpdate {
+                default_mode: Some("random-ish".to_string()),
+                ..Default::default()
+            },
+            ConfigUpdate {
+                default_pattern: Some("UUX".to_string()),
+                ..Default::default()
+            },
+            ConfigUpdate {
+                default_words: Some(2),
+                ..Default::default()
+            },
+            ConfigUpdate {
+                default_words: Some(21),
+                // Nothing is applied when one value is invalid
+                password_length: Some(30),
+                ..Default::default()
+            },
+            ConfigUpdate {
+                output_dir: Some("Cargo.toml/passwords".to_string()),
+                ..Default::default()
+            },
+            ConfigUpdate {
+                output_filename_template: Some("{date".to_string()),
+                ..Default::default()
+            },
+        ];
+        for update in invalid {
+            let mut config = Config::default();
+            assert!(update.apply(&mut config).is_err());
+            assert_eq!(config.general.default_mode, "random");
+            assert_eq!(config.general.default_length, 16);
+            assert_eq!(config.general.default_words, 4);
+            assert!(config.general.output_dir.is_none());
+            assert_eq!(config.general.output_filename_template, "kdguard.txt");
+        }
+    }
+
+    #[test]
+    fn test_render_output_filename() {
+        assert_eq!(
+            GeneralConfig::render_output_filename(
+                "kdguard_{date}_{mode}_{count}.txt",
+                "2026-10-16",
+                "phrase",
+                5
+            ),
+            "kdguard_2026-10-16_phrase_5.txt"
+        );
+        assert_eq!(
+            GeneralConfig::render_output_filename("{mode}-{mode}.txt", "2026-10-16", "random", 1),
+            "random-random.txt"
+        );
+        assert_eq!(
+            GeneralConfig::render_output_filename(
+                DEFAULT_OUTPUT_FILENAME_TEMPLATE,
+                "2026-10-16",
+                "random",
+                1
+            ),
+            "kdguard.txt"
+        );
+        assert!(is_valid_output_template("{date}_{mode}_{count}.txt"));
+        assert!(!is_valid_output_template(" "));
+        assert!(!is_valid_output_template("..\\{date}.txt"));
+        assert!(!is_valid_output_template("{Date}.txt"));
+    }
+
+    #[test]
+    fn test_output_path_creates_configured_directory() {
+        let dir = std::env::temp_dir().join(format!("kdguard-output-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut general = Config::default().general;
+        assert_eq!(
+            general.output_path("random", 1).unwrap(),
+            PathBuf::from("kdguard.txt")
+        );
+
+        let output_dir = dir.join("passwords");
+        ConfigUpdate {
+            output_dir: Some(output_dir.display().to_string()),
+            output_filename_template: Some("{mode}_{count}.txt".to_string()),
+            ..Default::default()
+        }
+        .apply(&mut Config::default())
+        .unwrap();
+        general.output_dir = Some(output_dir.display().to_string());
+        general.output_filename_template = "{mode}_{count}.txt".to_string();
+        let path = general.output_path("pattern", 3).unwrap();
+        assert_eq!(path, output_dir.join("pattern_3.txt"));
+        assert!(output_dir.is_dir());
+
+        let metadata = GenerationMetadata::new(&PasswordMode::Pattern, 1);
+        Generator::save_to_file(vec!["secret".to_string()], &path, &metadata).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("secret"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Config with the general values replaced, used as fixture for invalid values
+    fn fixture(general: &str, lang: &str) -> String {
+        format!(
+            "[general]\n{}\n\n[language]\nlang = \"{}\"\n",
+            general, lang
+        )
+    }
+
+    const VALID_GENERAL: &str =
+        "default_length = 16\ndefault_count = 1\ndefault_mode = \"random\"\nauto_save = false";
+
+    fn invalid_fields(config_str: &str) -> Vec<String> {
+        match Config::parse(config_str) {
+            Err(ConfigError::InvalidValue(problems)) => {
+                problems.into_iter().map(|problem| problem.field).collect()
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("config was accepted"),
+        }
+    }
+
+    #[test]
+    fn test_parse_valid_config() {
+        assert!(Config::parse(&fixture(VALID_GENERAL, "en")).is_ok());
+        assert!(Config::parse(&toml::to_string(&Config::default()).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_each_invalid_value() {
+        let cases = [
+            ("default_length = 4", "general.default_length"),
+            ("default_length = 65", "general.default_length"),
+            ("default_count = 0", "general.default_count"),
+            ("default_mode = \"ramdom\"", "general.default_mode"),
+            ("default_pattern = \"ULX\"", "general.default_pattern"),
+            ("default_words = 2", "general.default_words"),
+            (
+                "output_filename_template = \"out/{date}.txt\"",
+                "general.output_filename_template",
+            ),
+            (
+                "output_filename_template = \"{time}.txt\"",
+                "general.output_filename_template",
+            ),
+        ];
+        for (line, field) in cases {
+            let key = line.split(' ').next().unwrap();
+            let general = VALID_GENERAL
+                .lines()
+                .filter(|valid| !valid.starts_with(key))
+                .chain([line])
+                .collect::<Vec<_>>()
+                .join("\n");
+            assert_eq!(
+                invalid_fields(&fixture(&general, "en")),
+                [field],
+                "{}",
+                line
+            );
+        }
+
+        assert_eq!(
+            invalid_fields(&fixture(VALID_GENERAL, "xx")),
+            ["language.lang"]
+        );
+        let deterministic = "\n[deterministic]\nkdf = \"argon2\"\nmemory_kib = 4\niterations = 0\n";
+        assert_eq!(
+            invalid_fields(&(fixture(VALID_GENERAL, "en") + deterministic)),
+            ["deterministic.memory_kib", "deterministic.iterations"]
+        );
+        let patterns = "\n[patterns]\ndoor = \"DDDD\"\nloop = \"@door\"\ntypo = \"U{uper}\"\n";
+        assert_eq!(
+            invalid_fields(&(fixture(VALID_GENERAL, "en") + patterns)),
+            ["patterns.loop", "patterns.typo"]
+        );
+        let scoring = "\n[scoring]\nlength = 0\ndiversity = -1.5\nentropy = 2\n";
+        assert_eq!(
+            invalid_fields(&(fixture(VALID_GENERAL, "en") + scoring)),
+            ["scoring.length", "scoring.diversity"]
+        );
+    }
+
+    #[test]
+    fn test_parse_lists_every_problem() {
+        let general =
+            "default_length = 4\ndefault_count = 0\ndefault_mode = \"ramdom\"\nauto_save = false";
+        let error = Config::parse(&fixture(general, "xx")).err().unwrap();
+        let message = error.to_string();
+        assert!(
+            message.contains("general.default_length = 4 (expected 8 to 64)"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("general.default_mode = \"ramdom\""),
+            "{}",
+            message
+        );
+        let ConfigError::InvalidValue(problems) = error else {
+            panic!("unexpected error: {}", message);
+        };
+        assert_eq!(problems.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_reports_syntax_error_position() {
+        let config_str =
+            fixture(VALID_GENERAL, "en").replace("default_count = 1", "default_count = ");
+        let message = Config::parse(&config_str).err().unwrap().to_string();
+        assert!(message.contains("line 3, column"), "{}", message);
+        assert!(!message.contains('|'), "{}", message);
+
+        let message = Config::parse("[general]\ndefault_length = 16\n")
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(message.contains("missing field"), "{}", message);
+    }
+
+    const V1_CONFIG: &str = r#"
+[general]
+default_length = 20
+default_count = 3
+default_mode = "phrase"
+auto_save = true
+
+[language]
+lang = "de"
+
+[check]
+banned_words_path = "/etc/kdguard/banned.txt"
+"#;
+
+    #[test]
+    fn test_migrate_v1_config() {
+        let mut table: toml::Table = toml::from_str(V1_CONFIG).unwrap();
+        let changes = Config::migrate(&mut table).unwrap();
+        assert_eq!(
+            changes,
+            ["added general.default_words = 4", "version 1 upgraded to 2"]
+        );
+
+        let (config, changes) = Config::parse_and_migrate(V1_CONFIG).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.general.default_length, 20);
+        assert_eq!(config.general.default_mode, "phrase");
+        assert_eq!(config.general.default_words, 4);
+        assert!(config.general.default_pattern.is_none());
+        assert_eq!(config.language.lang, "de");
+        assert_eq!(
+            config.check.banned_words_path.as_deref(),
+            Some("/etc/kdguard/banned.txt")
+        );
+
+        // The migrated file is up to date
+        let migrated = toml::to_string_pretty(&config).unwrap();
+        assert!(migrated.starts_with("version = 2\n"), "{}", migrated);
+        let (_, changes) = Config::parse_and_migrate(&migrated).unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_keeps_v1_word_count() {
+        let v1 = V1_CONFIG.replace("auto_save = true", "auto_save = true\ndefault_words = 6");
+        let (config, changes) = Config::parse_and_migrate(&v1).unwrap();
+        assert_eq!(changes, ["version 1 upgraded to 2"]);
+        assert_eq!(config.general.default_words, 6);
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_version() {
+        let newer = format!("version = 3\n{}", V1_CONFIG);
+        let error = Config::parse(&newer).err().unwrap();
+        assert!(matches!(error, ConfigError::NewerVersionError(3, 2)));
+        assert!(error.to_string().contains("newer kdguard"));
+
+        let invalid = format!("version = \"two\"\n{}", V1_CONFIG);
+        assert_eq!(invalid_fields(&invalid), ["version"]);
+    }
+
+    const PROFILES_CONFIG: &str = r#"
+version = 2
+
+[general]
+default_length = 16
+default_count = 1
+default_mode = "random"
+auto_save = false
+
+[language]
+lang = "en"
+
+[profiles.work]
+default_length = 32
+auto_save = true
+
+[profiles.personal]
+default_mode = "phrase"
+default_words = 6
+"#;
+
+    #[test]
+    fn test_profile_overrides_only_set_values() {
+        let config = Config::parse(PROFILES_CONFIG).unwrap();
+        assert_eq!(config.profiles.len(), 2);
+
+        let work = config.general_for(Some("work")).unwrap();
+        assert_eq!(work.default_length, 32);
+        assert!(work.auto_save);
+        assert_eq!(work.default_count, 1);
+        assert_eq!(work.default_mode, "random");
+
+        let personal = config.general_for(Some("personal")).unwrap();
+        assert_eq!(personal.default_mode, "phrase");
+        assert_eq!(personal.default_words, 6);
+        assert_eq!(personal.default_length, 16);
+
+        let base = config.general_for(None).unwrap();
+        assert_eq!(base.default_length, 16);
+        assert!(!base.auto_save);
+    }
+
+    #[test]
+    fn test_with_profile_merges_and_is_not_saved() {
+        let config = Config::parse(PROFILES_CONFIG)
+            .unwrap()
+            .with_profile("work")
+            .unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("work"));
+        assert_eq!(config.general.default_length, 32);
+
+        let saved = toml::to_string(&config).unwrap();
+        assert!(!saved.contains("active_profile"), "{}", saved);
+        assert!(saved.contains("[profiles.work]"), "{}", saved);
+    }
+
+    #[test]
+    fn test_unknown_profile_lists_available() {
+        let error = Config::parse(PROFILES_CONFIG)
+            .unwrap()
+            .with_profile("home")
+            .err()
+            .unwrap();
+        assert!(matches!(error, ConfigError::UnknownProfileError(_)));
+        assert!(
+            error
+                .to_string()
+                .contains("'home' (available: personal, work)"),
+            "{}",
+            error
+        );
+
+        let error = Config::default().general_for(Some("work")).err().unwrap();
+        assert!(error.to_string().contains("available: none"), "{}", error);
+    }
+
+    #[test]
+    fn test_profile_values_are_validated() {
+        let config_str = PROFILES_CONFIG.replace("default_length = 32", "default_length = 4");
+        assert_eq!(
+            invalid_fields(&config_str),
+            ["profiles.work.default_length"]
+        );
+    }
+
+    fn key_error(config: &mut Config, key: &str, value: &str) -> String {
+        config.set_key(key, value).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_get_key() {
+        let config = Config::default();
+        assert_eq!(config.get_key("general.default_length").unwrap(), "16");
+        assert_eq!(config.get_key("general.auto_save").unwrap(), "false");
+        assert_eq!(config.get_key("language.lang").unwrap(), "en");
+        assert_eq!(config.get_key("version").unwrap(), "2");
+        assert!(
+            config
+                .get_key("general")
+                .unwrap()
+                .contains("default_mode = \"random\"")
+        );
+
+        for key in [
+            "general.missing",
+            "general.default_length.x",
+            "missing",
+            "general.default_pattern",
+        ] {
+            assert!(
+                matches!(config.get_key(key), Err(ConfigError::UnknownKeyError(_))),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_key() {
+        let mut config = Config::default();
+        config.set_key("general.default_length", "24").unwrap();
+        config.set_key("general.auto_save", "true").unwrap();
+        config.set_key("language.lang", "de").unwrap();
+        config.set_key("theme", "light").unwrap();
+        assert_eq!(config.general.default_length, 24);
+        assert!(config.general.auto_save);
+        assert_eq!(config.language.lang, "de");
+        assert_eq!(config.theme.preset(), "light");
+
+        // Unset keys and new profile entries are found through the config types
+        config.set_key("general.default_pattern", "ULLDS").unwrap();
+        assert_eq!(config.general.default_pattern.as_deref(), Some("ULLDS"));
+        config
+            .set_key("profiles.work.default_length", "32")
+            .unwrap();
+        assert_eq!(config.profiles["work"].default_length, Some(32));
+        assert_eq!(
+            config.get_key("profiles.work.default_length").unwrap(),
+            "32"
+        );
+    }
+
+    #[test]
+    fn test_set_key_rejects_mismatches() {
+        let mut config = Config::default();
+        let error = key_error(&mut config, "general.auto_save", "maybe");
+        assert!(
+            error.contains("general.auto_save = maybe (expected true or false)"),
+            "{}",
+            error
+        );
+        let error = key_error(&mut config, "general.default_length", "long");
+        assert!(error.contains("expected a whole number"), "{}", error);
+        let error = key_error(&mut config, "general.default_length", "4");
+        assert!(error.contains("expected 8 to 64"), "{}", error);
+        let error = key_error(&mut config, "general", "x");
+        assert!(error.contains("set the keys of the table"), "{}", error);
+        let error = key_error(&mut config, "general.default_words", "\"many\"");
+        assert!(error.contains("general.default_words"), "{}", error);
+        let error = key_error(&mut config, "language.lang", "xx");
+        assert!(error.contains("language.lang"), "{}", error);
+        let error = key_error(&mut config, "version", "3");
+        assert!(error.contains("version = 3"), "{}", error);
+
+        for key in ["general.color", "general.default_length.x", "colors.accent"] {
+            assert!(matches!(
+                config.set_key(key, "1"),
+                Err(ConfigError::UnknownKeyError(_))
+            ));
+        }
+
+        // A rejected value leaves the config as it was
+        assert_eq!(config.general.default_length, 16);
+        assert!(!config.general.auto_save);
+        assert_eq!(config.language.lang, "en");
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut config = Config::parse(PROFILES_CONFIG).unwrap();
+        config.set_key("theme", "light").unwrap();
+        config.check.banned_words_path = Some("/home/me/banned.txt".to_string());
+        config.general.output_dir = Some("/home/me/passwords".to_string());
+        config
+            .set_key("network.proxy", "http://proxy.corp:3128")
+            .unwrap();
+        config
+            .set_key("network.extra_ca_bundle", "/home/me/corp-ca.pem")
+            .unwrap();
+
+        let exported = config.export().unwrap();
+        assert!(!exported.contains("banned.txt"), "{}", exported);
+        assert!(!exported.contains("/home/me/passwords"), "{}", exported);
+        assert!(!exported.contains("corp-ca.pem"), "{}", exported);
+        assert!(exported.contains("proxy.corp"), "{}", exported);
+        assert!(exported.contains("version = 2\n"), "{}", exported);
+
+        let imported = Config::default().import(&exported).unwrap();
+        assert_eq!(imported.general.default_length, 16);
+        assert_eq!(imported.theme.preset(), "light");
+        assert_eq!(imported.profiles, config.profiles);
+        assert!(imported.check.banned_words_path.is_none());
+        assert!(imported.general.output_dir.is_none());
+        assert!(imported.network.extra_ca_bundle.is_none());
+
+        // The local banned words file, output directory, and CA bundle survive the import
+        let imported = config.import(&exported).unwrap();
+        assert!(config.diff(&imported).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_diff() {
+        let current = Config::default();
+        let exported = current
+            .export()
+            .unwrap()
+            .replace("default_length = 16", "default_length = 24");
+        let imported = current.import(&exported).unwrap();
+        assert_eq!(
+            current.diff(&imported).unwrap(),
+            [
+                "- general.default_length = 16",
+                "+ general.default_length = 24"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_validates_like_load() {
+        let current = Config::default();
+        let exported = current.export().unwrap();
+
+        let out_of_range = exported.replace("default_length = 16", "default_length = 100");
+        let error = current.import(&out_of_range).err().unwrap().to_string();
+        assert!(
+            error.contains("general.default_length = 100 (expected 8 to 64)"),
+            "{}",
+            error
+        );
+
+        let newer = exported.replace("version = 2", "version = 9");
+        assert!(matches!(
+            current.import(&newer),
+            Err(ConfigError::NewerVersionError(9, _))
+        ));
+
+        // Version 1 exports are migrated
+        let imported = current.import(V1_CONFIG).unwrap();
+        assert_eq!(imported.version, CONFIG_VERSION);
+        assert!(imported.check.banned_words_path.is_none());
+    }
+
+    #[test]
+    fn test_install_languages_keeps_user_files() {
+        let dir = std::env::temp_dir().join(format!("kdguard-languages-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let en = dir.join("en.json");
+
+        Config::install_languages(&dir).unwrap();
+        assert_eq!(fs::read_to_string(&en).unwrap(), EMBEDDED_LANGUAGES[0].1);
+        assert!(dir.join("de.json").exists());
+
+        // Up to date files are not written again, custom languages survive
+        fs::write(&en, "{}").unwrap();
+        fs::write(dir.join("fr.json"), "{\"cli\": {}}").unwrap();
+        Config::install_languages(&dir).unwrap();
+        assert_eq!(fs::read_to_string(&en).unwrap(), "{}");
+        assert_eq!(
+            fs::read_to_string(dir.join("fr.json")).unwrap(),
+            "{\"cli\": {}}"
+        );
+
+        // Missing files and files from an older version are written
+        fs::remove_file(dir.join("de.json")).unwrap();
+        Config::install_languages(&dir).unwrap();
+        assert!(dir.join("de.json").exists());
+        assert_eq!(fs::read_to_string(&en).unwrap(), "{}");
+        fs::write(dir.join(LANGUAGES_MARKER), "en 0000\n").unwrap();
+        Config::install_languages(&dir).unwrap();
+        assert_eq!(fs::read_to_string(&en).unwrap(), EMBEDDED_LANGUAGES[0].1);
+        assert!(dir.join("fr.json").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
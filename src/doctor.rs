@@ -0,0 +1,810 @@
+use std::{
+    fs,
+    io::Write,
+    net::{TcpStream, ToSocketAddrs},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::CONFIG;
+use crate::config::Config;
+use crate::i18n::{english, missing_keys, tr};
+use crate::logging::LoggingManager;
+use crate::update::UpdateManager;
+
+/// Host asked for the latest release by the update check
+const GITHUB_API_HOST: &str = "api.github.com";
+
+/// Time to wait for the GitHub API before it counts as unreachable
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// File written to probe whether a directory is writable
+const PROBE_FILE: &str = ".kdguard-doctor";
+
+/// Result of a single diagnostic check
+#[derive(Debug, PartialEq)]
+pub struct CheckOutcome {
+    pub passed: bool,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl CheckOutcome {
+    fn passed(detail: String) -> Self {
+        Self {
+            passed: true,
+            detail,
+            hint: None,
+        }
+    }
+
+    fn failed(detail: String, hint: String) -> Self {
+        Self {
+            passed: false,
+            detail,
+            hint: Some(hint),
+        }
+    }
+}
+
+/// A check of the environment run by `kdguard doctor`
+pub trait DiagnosticCheck {
+    /// Name printed in front of the result
+    fn name(&self) -> String;
+
+    /// Whether a failure makes `doctor` exit with an error
+    fn is_critical(&self) -> bool {
+        false
+    }
+
+    /// Run the check
+    fn run(&self) -> CheckOutcome;
+}
+
+/// Checks that the config file is valid and its directory writable
+pub struct ConfigCheck {
+    pub path: PathBuf,
+}
+
+impl DiagnosticCheck for ConfigCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.config.name", &[])
+    }
+
+    fn is_critical(&self) -> bool {
+        true
+    }
+
+    fn run(&self) -> CheckOutcome {
+        let path = self.path.display().to_string();
+        if let Some(dir) = self.path.parent()
+            && let Err(e) = probe_writable(dir)
+        {
+            return CheckOutcome::failed(
+                tr(
+                    "commands.doctor.not_writable",
+                    &[("path", &dir.display().to_string()), ("error", &e)],
+                ),
+                tr("commands.doctor.config.not_writable_hint", &[]),
+            );
+        }
+
+        let result = fs::read_to_string(&self.path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                Config::parse_and_migrate(&content)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => CheckOutcome::passed(tr("commands.doctor.config.ok", &[("path", &path)])),
+            Err(e) => CheckOutcome::failed(
+                tr(
+                    "commands.doctor.config.invalid",
+                    &[("path", &path), ("error", &e)],
+                ),
+                tr("commands.doctor.config.invalid_hint", &[]),
+            ),
+        }
+    }
+}
+
+/// Checks that the built-in language files match the ones shipped with this binary
+pub struct LanguagesCheck {
+    pub dir: PathBuf,
+}
+
+impl DiagnosticCheck for LanguagesCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.languages.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        let path = self.dir.display().to_string();
+        let mut languages: Vec<String> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                .collect(),
+            Err(e) => {
+                return CheckOutcome::failed(
+                    tr(
+                        "commands.doctor.languages.unreadable",
+                        &[("path", &path), ("error", &e.to_string())],
+                    ),
+                    tr("commands.doctor.languages.unreadable_hint", &[]),
+                );
+            }
+        };
+        languages.sort();
+
+        let modified = Config::modified_languages(&self.dir);
+        if modified.is_empty() {
+            CheckOutcome::passed(tr(
+                "commands.doctor.languages.ok",
+                &[("languages", &languages.join(", ")), ("path", &path)],
+            ))
+        } else {
+            CheckOutcome::failed(
+                tr(
+                    "commands.doctor.languages.modified",
+                    &[("languages", &modified.join(", "))],
+                ),
+                tr("commands.doctor.languages.modified_hint", &[]),
+            )
+        }
+    }
+}
+
+/// Most missing keys listed per language, the rest is counted
+const MAX_LISTED_KEYS: usize = 5;
+
+/// Checks that every language file has all texts of the built-in English one
+pub struct TranslationsCheck {
+    pub dir: PathBuf,
+}
+
+impl DiagnosticCheck for TranslationsCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.translations.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+
+        let mut checked = Vec::new();
+        let mut problems = Vec::new();
+        for file in files {
+            let Some(lang) = file.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            checked.push(lang.to_string());
+            let language = match fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<serde_json::Value>(&content).map_err(|e| e.to_string())
+                }) {
+                Ok(language) => language,
+                Err(e) => {
+                    problems.push(tr(
+                        "commands.doctor.translations.invalid",
+                        &[("language", lang), ("error", &e)],
+                    ));
+                    continue;
+                }
+            };
+
+            let missing = missing_keys(english(), &language);
+            if missing.is_empty() {
+                continue;
+            }
+            let mut keys = missing[..missing.len().min(MAX_LISTED_KEYS)].join(", ");
+            if missing.len() > MAX_LISTED_KEYS {
+                keys.push_str(&tr(
+                    "commands.doctor.translations.more",
+                    &[("count", &(missing.len() - MAX_LISTED_KEYS).to_string())],
+                ));
+            }
+            problems.push(tr(
+                "commands.doctor.translations.missing",
+                &[
+                    ("language", lang),
+                    ("count", &missing.len().to_string()),
+                    ("keys", &keys),
+                ],
+            ));
+        }
+
+        if problems.is_empty() {
+            CheckOutcome::passed(tr(
+                "commands.doctor.translations.ok",
+                &[("languages", &checked.join(", "))],
+            ))
+        } else {
+            CheckOutcome::failed(
+                problems.join("; "),
+                tr("commands.doctor.translations.hint", &[]),
+            )
+        }
+    }
+}
+
+/// Checks that the log directory is writable and reports its size
+pub struct LogDirCheck {
+    pub dir: Result<PathBuf, String>,
+}
+
+impl DiagnosticCheck for LogDirCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.logs.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        let dir = match &self.dir {
+            Ok(dir) => dir,
+            Err(e) => {
+                return CheckOutcome::failed(
+                    e.clone(),
+                    tr("commands.doctor.logs.not_writable_hint", &[]),
+                );
+            }
+        };
+        let path = dir.display().to_string();
+        if let Err(e) = probe_writable(dir) {
+            return CheckOutcome::failed(
+                tr(
+                    "commands.doctor.not_writable",
+                    &[("path", &path), ("error", &e)],
+                ),
+                tr("commands.doctor.logs.not_writable_hint", &[]),
+            );
+        }
+
+        let size: u64 = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok()?.metadata().ok())
+                    .filter(|metadata| metadata.is_file())
+                    .map(|metadata| metadata.len())
+                    .sum()
+            })
+            .unwrap_or(0);
+        CheckOutcome::passed(tr(
+            "commands.doctor.logs.ok",
+            &[("path", &path), ("size", &format_size(size))],
+        ))
+    }
+}
+
+/// Checks that the `kdg` alias next to the binary runs the binary
+pub struct AliasCheck {
+    pub exe: PathBuf,
+}
+
+impl DiagnosticCheck for AliasCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.alias.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        let alias_name = if cfg!(windows) { "kdg.exe" } else { "kdg" };
+        let alias = self
+            .exe
+            .parent()
+            .map(|dir| dir.join(alias_name))
+            .unwrap_or_else(|| PathBuf::from(alias_name));
+        let path = alias.display().to_string();
+
+        if fs::symlink_metadata(&alias).is_err() {
+            return CheckOutcome::failed(
+                tr("commands.doctor.alias.missing", &[("path", &path)]),
+                tr("commands.doctor.alias.missing_hint", &[]),
+            );
+        }
+        let resolves = match (fs::canonicalize(&alias), fs::canonicalize(&self.exe)) {
+            (Ok(alias), Ok(exe)) => alias == exe,
+            _ => false,
+        };
+        if resolves {
+            CheckOutcome::passed(tr("commands.doctor.alias.ok", &[("path", &path)]))
+        } else {
+            CheckOutcome::failed(
+                tr("commands.doctor.alias.mismatch", &[("path", &path)]),
+                tr("commands.doctor.alias.mismatch_hint", &[]),
+            )
+        }
+    }
+}
+
+/// Checks that the system clipboard can be opened
+pub struct ClipboardCheck;
+
+impl DiagnosticCheck for ClipboardCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.clipboard.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        match arboard::Clipboard::new() {
+            Ok(_) => CheckOutcome::passed(tr("commands.doctor.clipboard.ok", &[])),
+            Err(e) => CheckOutcome::failed(
+                tr(
+                    "commands.doctor.clipboard.failed",
+                    &[("error", &e.to_string())],
+                ),
+                tr("commands.doctor.clipboard.hint", &[]),
+            ),
+        }
+    }
+}
+
+/// Checks that a host can be reached within a timeout
+pub struct NetworkCheck {
+    pub host: String,
+    pub port: u16,
+    pub timeout: Duration,
+}
+
+impl DiagnosticCheck for NetworkCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.network.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        let result = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())
+            .and_then(|mut addrs| addrs.next().ok_or_else(|| "no address".to_string()))
+            .and_then(|addr| {
+                TcpStream::connect_timeout(&addr, self.timeout).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(_) => {
+                CheckOutcome::passed(tr("commands.doctor.network.ok", &[("host", &self.host)]))
+            }
+            Err(e) => CheckOutcome::failed(
+                tr(
+                    "commands.doctor.network.failed",
+                    &[("host", &self.host), ("error", &e)],
+                ),
+                tr("commands.doctor.network.hint", &[("host", &self.host)]),
+            ),
+        }
+    }
+}
+
+/// Shows which version `kdguard update --rollback` would reinstall
+pub struct RollbackCheck {
+    pub previous_version: Option<String>,
+}
+
+impl DiagnosticCheck for RollbackCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.rollback.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        match &self.previous_version {
+            Some(version) => {
+                CheckOutcome::passed(tr("commands.doctor.rollback.ok", &[("version", version)]))
+            }
+            None => CheckOutcome::passed(tr("commands.doctor.rollback.none", &[])),
+        }
+    }
+}
+
+/// Shows whether the hostname, CPU, and memory are written to the log directory
+pub struct SystemInfoCheck {
+    pub enabled: bool,
+}
+
+impl DiagnosticCheck for SystemInfoCheck {
+    fn name(&self) -> String {
+        tr("commands.doctor.system_info.name", &[])
+    }
+
+    fn run(&self) -> CheckOutcome {
+        if self.enabled {
+            CheckOutcome::passed(tr("commands.doctor.system_info.on", &[]))
+        } else {
+            CheckOutcome::passed(tr("commands.doctor.system_info.off", &[]))
+        }
+    }
+}
+
+pub struct DoctorManager;
+
+impl DoctorManager {
+    /// Get the checks run by `kdguard doctor`
+    ///
+    /// # Returns
+    ///
+    /// Returns the checks in the order they are printed
+    pub fn default_checks() -> Vec<Box<dyn DiagnosticCheck>> {
+        let config_dir = Config::get_config_dir().unwrap_or_default();
+        vec![
+            Box::new(ConfigCheck {
+                path: config_dir.join("config.toml"),
+            }),
+            Box::new(LanguagesCheck {
+                dir: config_dir.join("languages"),
+            }),
+            Box::new(TranslationsCheck {
+                dir: config_dir.join("languages"),
+            }),
+            Box::new(LogDirCheck {
+                dir: LoggingManager::get_logging_path().map_err(|e| e.to_string()),
+            }),
+            Box::new(SystemInfoCheck {
+                enabled: CONFIG.logging.collect_system_info,
+            }),
+            Box::new(AliasCheck {
+                exe: std::env::current_exe().unwrap_or_default(),
+            }),
+            Box::new(ClipboardCheck),
+            Box::new(NetworkCheck {
+                host: GITHUB_API_HOST.to_string(),
+                port: 443,
+                timeout: NETWORK_TIMEOUT,
+            }),
+            Box::new(RollbackCheck {
+                previous_version: UpdateManager::previous_version(),
+            }),
+        ]
+    }
+
+    /// Run checks and print a ✓ or ✗ line for each, with a hint for failures
+    ///
+    /// # Arguments
+    ///
+    /// * `checks`: The checks to run
+    /// * `output`: The writer receiving the report
+    ///
+    /// # Returns
+    ///
+    /// Returns true if no critical check failed, otherwise false
+    pub fn run_checks(
+        checks: &[Box<dyn DiagnosticCheck>],
+        output: &mut dyn Write,
+    ) -> std::io::Result<bool> {
+        LoggingManager::info(&format!("Running {} diagnostic checks", checks.len()));
+        writeln!(
+            output,
+            "\n\x1b[1;36m{}\x1b[0m",
+            tr("commands.doctor.title", &[])
+        )?;
+        writeln!(output, "{}", "=".repeat(50))?;
+
+        let mut failed = 0;
+        let mut critical_failed = 0;
+        for check in checks {
+            let outcome = check.run();
+            LoggingManager::info(&format!(
+                "Diagnostic check {}: passed={}, {}",
+                check.name(),
+                outcome.passed,
+                outcome.detail
+            ));
+            if outcome.passed {
+                writeln!(
+                    output,
+                    "\x1b[1;32m✓\x1b[0m {}: {}",
+                    check.name(),
+                    outcome.detail
+                )?;
+                continue;
+            }
+
+            failed += 1;
+            let color = if check.is_critical() {
+                critical_failed += 1;
+                "\x1b[1;31m"
+            } else {
+                "\x1b[1;33m"
+            };
+            writeln!(
+                output,
+                "{}✗\x1b[0m {}: {}",
+                color,
+                check.name(),
+                outcome.detail
+            )?;
+            if let Some(hint) = outcome.hint {
+                writeln!(output, "    → {}", hint)?;
+            }
+        }
+
+        writeln!(output, "{}", "=".repeat(50))?;
+        let summary = match (critical_failed, failed) {
+            (0, 0) => format!("\x1b[1;32m{}\x1b[0m", tr("commands.doctor.summary_ok", &[])),
+            (0, failed) => format!(
+                "\x1b[1;33m{}\x1b[0m",
+                tr(
+                    "commands.doctor.summary_warnings",
+                    &[("count", &failed.to_string())]
+                )
+            ),
+            (critical, _) => format!(
+                "\x1b[1;31m{}\x1b[0m",
+                tr(
+                    "commands.doctor.summary_critical",
+                    &[("count", &critical.to_string())]
+                )
+            ),
+        };
+        writeln!(output, "{}", summary)?;
+
+        Ok(critical_failed == 0)
+    }
+}
+
+/// Check that a file can be created in a directory
+///
+/// # Arguments
+///
+/// * `dir`: The directory to probe
+///
+/// # Returns
+///
+/// Returns Ok(()) if the directory is writable, otherwise the error message
+fn probe_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(PROBE_FILE);
+    fs::write(&probe, b"").map_err(|e| e.to_string())?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+/// Format a number of bytes for humans
+///
+/// # Arguments
+///
+/// * `bytes`: The size in bytes
+///
+/// # Returns
+///
+/// Returns the size with a binary unit
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password::health_check::tests::init_lingua_for_tests;
+    use crate::test_support::TempDir;
+
+    struct Fixed {
+        critical: bool,
+        passed: bool,
+    }
+
+    impl DiagnosticCheck for Fixed {
+        fn name(&self) -> String {
+            "fixed".to_string()
+        }
+
+        fn is_critical(&self) -> bool {
+            self.critical
+        }
+
+        fn run(&self) -> CheckOutcome {
+            if self.passed {
+                CheckOutcome::passed("fine".to_string())
+            } else {
+                CheckOutcome::failed("broken".to_string(), "fix it".to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_checks_fails_only_on_critical_checks() {
+        let run = |checks: Vec<Box<dyn DiagnosticCheck>>| {
+            let mut output = Vec::new();
+            let healthy = DoctorManager::run_checks(&checks, &mut output).unwrap();
+            (healthy, String::from_utf8(output).unwrap())
+        };
+
+        let (healthy, output) = run(vec![Box::new(Fixed {
+            critical: true,
+            passed: true,
+        })]);
+        assert!(healthy);
+        assert!(output.contains("✓\x1b[0m fixed: fine"));
+
+        let (healthy, output) = run(vec![Box::new(Fixed {
+            critical: false,
+            passed: false,
+        })]);
+        assert!(healthy);
+        assert!(output.contains("✗\x1b[0m fixed: broken"));
+        assert!(output.contains("→ fix it"));
+
+        let (healthy, _) = run(vec![
+            Box::new(Fixed {
+                critical: false,
+                passed: true,
+            }),
+            Box::new(Fixed {
+                critical: true,
+                passed: false,
+            }),
+        ]);
+        assert!(!healthy);
+    }
+
+    #[test]
+    fn test_config_check() {
+        let dir = TempDir::new("config");
+        let check = ConfigCheck {
+            path: dir.0.join("config.toml"),
+        };
+
+        fs::write(&check.path, toml::to_string(&Config::default()).unwrap()).unwrap();
+        assert!(check.run().passed);
+        assert!(!dir.0.join(PROBE_FILE).exists());
+
+        fs::write(&check.path, "[general\n").unwrap();
+        let outcome = check.run();
+        assert!(!outcome.passed);
+        assert!(outcome.hint.is_some());
+    }
+
+    #[test]
+    fn test_languages_check() {
+        init_lingua_for_tests();
+        let dir = TempDir::new("languages");
+        let check = LanguagesCheck { dir: dir.0.clone() };
+        fs::write(dir.0.join("en.json"), include_str!("../languages/en.json")).unwrap();
+        fs::write(dir.0.join("de.json"), include_str!("../languages/de.json")).unwrap();
+        assert!(check.run().passed);
+
+        fs::write(dir.0.join("de.json"), "{}").unwrap();
+        let outcome = check.run();
+        assert!(!outcome.passed);
+        assert!(outcome.detail.contains("de"));
+
+        let missing = LanguagesCheck {
+            dir: dir.0.join("missing"),
+        };
+        assert!(!missing.run().passed);
+    }
+
+    #[test]
+    fn test_translations_check_lists_missing_keys() {
+        init_lingua_for_tests();
+        let dir = TempDir::new("translations");
+        let check = TranslationsCheck { dir: dir.0.clone() };
+        fs::write(dir.0.join("en.json"), include_str!("../languages/en.json")).unwrap();
+        fs::write(dir.0.join("de.json"), include_str!("../languages/de.json")).unwrap();
+        let outcome = check.run();
+        assert!(outcome.passed, "{}", outcome.detail);
+        assert!(outcome.detail.contains("de, en"), "{}", outcome.detail);
+
+        // A partial custom translation
+        fs::write(
+            dir.0.join("fr.json"),
+            r#"{"tui": {"help": {"title": "Aide"}}}"#,
+        )
+        .unwrap();
+        let outcome = check.run();
+        assert!(!outcome.passed);
+        assert!(outcome.detail.contains("fr"), "{}", outcome.detail);
+        assert!(outcome.detail.contains("cli.about"), "{}", outcome.detail);
+        assert!(
+            !outcome.detail.contains("tui.help.title"),
+            "{}",
+            outcome.detail
+        );
+        assert!(outcome.hint.is_some());
+
+        fs::write(dir.0.join("fr.json"), "{").unwrap();
+        assert!(!check.run().passed);
+    }
+
+    #[test]
+    fn test_log_dir_check() {
+        init_lingua_for_tests();
+        let dir = TempDir::new("logs");
+        fs::write(dir.0.join("kdguard.log"), vec![b'x'; 2048]).unwrap();
+        let outcome = LogDirCheck {
+            dir: Ok(dir.0.clone()),
+        }
+        .run();
+        assert!(outcome.passed);
+        assert!(outcome.detail.contains("2.0 KiB"), "{}", outcome.detail);
+
+        let outcome = LogDirCheck {
+            dir: Ok(dir.0.join("kdguard.log")),
+        }
+        .run();
+        assert!(!outcome.passed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_alias_check() {
+        let dir = TempDir::new("alias");
+        let exe = dir.0.join("kdguard");
+        fs::write(&exe, "").unwrap();
+        let check = AliasCheck { exe: exe.clone() };
+        assert!(!check.run().passed);
+
+        std::os::unix::fs::symlink(&exe, dir.0.join("kdg")).unwrap();
+        assert!(check.run().passed);
+
+        // A dangling alias from an older install
+        fs::remove_file(dir.0.join("kdg")).unwrap();
+        std::os::unix::fs::symlink(dir.0.join("old"), dir.0.join("kdg")).unwrap();
+        let outcome = check.run();
+        assert!(!outcome.passed);
+        assert!(outcome.hint.is_some());
+    }
+
+    #[test]
+    fn test_network_check_times_out() {
+        let outcome = NetworkCheck {
+            host: "invalid.invalid".to_string(),
+            port: 443,
+            timeout: Duration::from_millis(100),
+        }
+        .run();
+        assert!(!outcome.passed);
+    }
+
+    #[test]
+    fn test_rollback_check() {
+        init_lingua_for_tests();
+        let outcome = RollbackCheck {
+            previous_version: Some("1.2.3".to_string()),
+        }
+        .run();
+        assert!(outcome.passed);
+        assert!(outcome.detail.contains("1.2.3"), "{}", outcome.detail);
+
+        let outcome = RollbackCheck {
+            previous_version: None,
+        }
+        .run();
+        assert!(outcome.passed);
+        assert_eq!(outcome.hint, None);
+    }
+
+    #[test]
+    fn test_system_info_check() {
+        init_lingua_for_tests();
+        let on = SystemInfoCheck { enabled: true }.run();
+        let off = SystemInfoCheck { enabled: false }.run();
+        assert!(on.passed && off.passed);
+        assert!(on.detail.contains("collect_system_info"), "{}", on.detail);
+        assert_ne!(on.detail, off.detail);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024 + 512 * 1024), "5.5 MiB");
+    }
+}
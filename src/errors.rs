@@ -1,6 +1,148 @@
+use std::error::Error as _;
+
+use inquire::InquireError;
 use lingua_i18n_rs::prelude::LinguaError;
 use thiserror::Error;
 
+use crate::i18n::tr;
+
+/// Exit code of errors without a category of their own
+pub const EXIT_OTHER: i32 = 1;
+/// Exit code of an invalid or unreadable config
+pub const EXIT_CONFIG: i32 = 10;
+/// Exit code of a failed password generation
+pub const EXIT_GENERATION: i32 = 20;
+/// Exit code of a failed password check
+pub const EXIT_CHECK: i32 = 21;
+/// Exit code when GitHub cannot be reached
+pub const EXIT_NETWORK: i32 = 30;
+/// Exit code of a failed update or uninstall
+pub const EXIT_INSTALL: i32 = 40;
+/// Exit code of a failure reading or writing the logs
+pub const EXIT_LOGGING: i32 = 50;
+
+/// Any error that ends a kdguard run
+///
+/// Each variant maps to an exit code, so scripts can tell the causes apart.
+#[derive(Error, Debug)]
+pub enum KdguardError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Generator(#[from] GeneratorError),
+    #[error(transparent)]
+    HealthCheck(#[from] HealthCheckError),
+    #[error(transparent)]
+    Update(#[from] UpdateError),
+    #[error(transparent)]
+    Uninstall(#[from] UninstallError),
+    #[error(transparent)]
+    Logging(#[from] LoggingError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Prompt(#[from] InquireError),
+    #[error(transparent)]
+    Language(#[from] LinguaError),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<&str> for KdguardError {
+    fn from(error: &str) -> Self {
+        Self::Other(error.to_string())
+    }
+}
+
+impl From<BreachError> for KdguardError {
+    fn from(error: BreachError) -> Self {
+        Self::HealthCheck(HealthCheckError::Breach(error))
+    }
+}
+
+impl KdguardError {
+    /// Get the exit code of the error
+    ///
+    /// # Returns
+    ///
+    /// Returns the code, listed in `kdguard --help`
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Config(_) => EXIT_CONFIG,
+            Self::Generator(_) => EXIT_GENERATION,
+            Self::HealthCheck(_) => EXIT_CHECK,
+            Self::Update(e) if e.is_network() => EXIT_NETWORK,
+            Self::Update(UpdateError::ConfirmationRequired)
+            | Self::Uninstall(UninstallError::ConfirmationRequired) => EXIT_OTHER,
+            Self::Update(_) | Self::Uninstall(_) => EXIT_INSTALL,
+            Self::Logging(_) => EXIT_LOGGING,
+            Self::Io(_) | Self::Prompt(_) | Self::Language(_) | Self::Other(_) => EXIT_OTHER,
+        }
+    }
+
+    /// Render the error for the terminal
+    ///
+    /// The message is prefixed with the localized kind of failure and followed by a
+    /// hint if there is one. The underlying causes are only listed from `-vv` on.
+    ///
+    /// # Arguments
+    ///
+    /// * `verbose`: How often `-v` was given
+    ///
+    /// # Returns
+    ///
+    /// Returns the lines to print to stderr
+    pub fn render(&self, verbose: u8) -> String {
+        let kind = match self.exit_code() {
+            EXIT_CONFIG => "errors.config",
+            EXIT_GENERATION => "errors.generation",
+            EXIT_CHECK => "errors.check",
+            EXIT_NETWORK => "errors.network",
+            EXIT_INSTALL if matches!(self, Self::Uninstall(_)) => "errors.uninstall",
+            EXIT_INSTALL => "errors.update",
+            EXIT_LOGGING => "errors.logging",
+            _ => "errors.other",
+        };
+        let mut rendered = format!("\x1b[1;31m{}:\x1b[0m {}", tr(kind, &[]), self);
+
+        if let Some(hint) = self.hint() {
+            rendered.push_str(&format!("\n{}", tr(hint, &[])));
+        }
+        // The wrapped error is the message, its source the first cause
+        let mut source = self.source();
+        if verbose >= 2 {
+            while let Some(cause) = source {
+                rendered.push_str(&format!("\n  {}: {}", tr("errors.caused_by", &[]), cause));
+                source = cause.source();
+            }
+        } else if source.is_some() {
+            rendered.push_str(&format!("\n{}", tr("errors.details_hint", &[])));
+        }
+        rendered
+    }
+
+    /// Get the language key of a hint to fix the error
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Config(ConfigError::UnknownKeyError(_)) => Some("errors.hints.config_key"),
+            Self::Config(
+                ConfigError::InvalidValue(_)
+                | ConfigError::LoadConfigurationError(_)
+                | ConfigError::ParseConfigurationError(_)
+                | ConfigError::ReadConfigFileError(_),
+            ) => Some("config.reset.hint"),
+            Self::Generator(
+                GeneratorError::MissingSeedEnv | GeneratorError::SeedEnvNotFound(_),
+            ) => Some("errors.hints.seed_env"),
+            Self::Update(e) if e.is_network() => Some("errors.hints.network"),
+            Self::Update(UpdateError::ChecksumMismatch { .. } | UpdateError::Signature(_)) => {
+                Some("errors.hints.verification")
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Invalid config path: {0}")]
@@ -29,10 +171,43 @@ pub enum ConfigError {
     ReadConfigFileError(String),
     #[error("Failed to write config file: {0}")]
     WriteConfigFileError(String),
+    #[error("Failed to create output directory: {0}")]
+    CreateOutputDirectoryError(String),
     #[error("Failed to serialize config: {0}")]
     SerializeConfigurationError(String),
     #[error("Failed to set language: {0}")]
     SetLanguageError(#[from] LinguaError),
+    #[error("Unknown policy: {0}")]
+    UnknownPolicyError(String),
+    #[error("Unknown language: {0}")]
+    UnknownLanguageError(String),
+    #[error("Unknown password mode: {0}")]
+    UnknownModeError(String),
+    #[error("Unknown or unset config key: {0}")]
+    UnknownKeyError(String),
+    #[error("Unknown profile: {0}")]
+    UnknownProfileError(String),
+    #[error("Unknown theme: {0}")]
+    UnknownThemeError(String),
+    #[error("Invalid preset: {0}")]
+    InvalidPresetError(String),
+    #[error("Invalid default: {0}")]
+    InvalidDefaultError(String),
+    #[error("Invalid config values:{}", .0.iter().map(|value| format!("\n  {}", value)).collect::<String>())]
+    InvalidValue(Vec<InvalidValue>),
+    #[error(
+        "Config version {0} was created by a newer kdguard, this version supports up to version {1}"
+    )]
+    NewerVersionError(i64, i64),
+}
+
+/// A config value outside of the allowed values, reported by `Config::validate`
+#[derive(Error, Debug, PartialEq)]
+#[error("{field} = {value} (expected {expected})")]
+pub struct InvalidValue {
+    pub field: String,
+    pub value: String,
+    pub expected: String,
 }
 
 #[derive(Error, Debug)]
@@ -49,6 +224,8 @@ pub enum LoggingError {
     CreateFile(String),
     #[error("Failed to cleanup old logs: {0}")]
     Cleanup(String),
+    #[error("Failed to read logs: {0}")]
+    Read(String),
 }
 
 #[derive(Error, Debug)]
@@ -59,12 +236,30 @@ pub enum GeneratorError {
     EmptyPattern,
     #[error("Invalid pattern character: {0}")]
     InvalidPatternCharacter(char),
+    #[error("Unknown pattern alias: {{{0}}}")]
+    UnknownPatternAlias(String),
+    #[error("Pattern alias {{{0}... is missing its closing brace")]
+    UnclosedPatternAlias(String),
+    #[error("Unknown named pattern: @{0}")]
+    UnknownNamedPattern(String),
     #[error("Word count must be between 3 and 20")]
     InvalidWordCount,
+    #[error("Phrase digits and symbols must be between 0 and 10")]
+    InvalidPhraseInsertions,
     #[error("Wordlist is empty")]
     EmptyWordlist,
     #[error("Seed cannot be empty")]
     EmptySeed,
+    #[error("Seed environment variable is required in deterministic mode")]
+    MissingSeedEnv,
+    #[error("Environment variable '{0}' not found or empty")]
+    SeedEnvNotFound(String),
+    #[error("--verify-policy is only supported in deterministic mode")]
+    VerifyPolicyMode,
+    #[error("A list of services is only supported in deterministic mode")]
+    ServicesMode,
+    #[error("Invalid password request: {0}")]
+    InvalidRequest(String),
     #[error("Failed to generate valid password after maximum retries")]
     MaxRetriesExceeded,
     #[error("Failed to fill random bytes: {0}")]
@@ -73,14 +268,52 @@ pub enum GeneratorError {
     HkdfExpandError,
     #[error("Failed to fill HKDF output")]
     HkdfFillError,
+    #[error("Failed to stretch the seed with Argon2id: {0}")]
+    Argon2Error(String),
     #[error("Failed to save passwords to file: {0}")]
     SaveFileError(String),
+    #[error("Failed to read saved passwords: {0}")]
+    ReadFileError(String),
 }
 
 #[derive(Error, Debug)]
 pub enum HealthCheckError {
     #[error("Failed to analyze password: {0}")]
     AnalysisError(String),
+    #[error("Failed to load banned words: {0}")]
+    LoadBannedWordsError(String),
+    #[error("Failed to read password: {0}")]
+    Prompt(String),
+    #[error("Failed to read password file: {0}")]
+    ReadPasswordFile(String),
+    #[error("Failed to write check results: {0}")]
+    WriteResults(String),
+    #[error(transparent)]
+    Breach(#[from] BreachError),
+}
+
+#[derive(Error, Debug)]
+pub enum HttpError {
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+    #[error("Failed to load the CA bundle {path}: {error}")]
+    CaBundle { path: String, error: String },
+}
+
+#[derive(Error, Debug)]
+pub enum BreachError {
+    #[error("Failed to query the breach API: {0}")]
+    Request(String),
+    #[error("Breach API returned error: {0}")]
+    Api(String),
+    #[error("Failed to parse breach API response: {0}")]
+    ParseResponse(String),
+    #[error(transparent)]
+    Http(#[from] HttpError),
+    #[error("Breach database error: {0}")]
+    Database(String),
+    #[error("Invalid breach dump: {0}")]
+    InvalidDump(String),
 }
 
 #[derive(Error, Debug)]
@@ -91,6 +324,8 @@ pub enum UninstallError {
     GetExecutablePathError(String),
     #[error("Invalid config path: no parent directory")]
     InvalidConfigPath,
+    #[error("Confirmation required, pass --yes")]
+    ConfirmationRequired,
     #[error("Invalid install path: no parent directory")]
     InvalidInstallPath,
     #[error("Failed to remove config directory: {0}")]
@@ -101,12 +336,20 @@ pub enum UninstallError {
     RemoveInstallDirectoryError(String),
     #[error("Failed to remove executable: {0}")]
     RemoveExecutableError(String),
+    #[error("Uninstall incomplete: {0} item(s) could not be removed")]
+    Incomplete(usize),
+    #[error(
+        "The config or install directory holds files kdguard did not create, pass --force to remove them too: {0}"
+    )]
+    ForeignFiles(String),
 }
 
 #[derive(Error, Debug)]
 pub enum UpdateError {
-    #[error("Failed to get latest tag from GitHub: {0}")]
-    GetLatestTag(String),
+    #[error("Could not reach GitHub: {0}")]
+    Network(String),
+    #[error(transparent)]
+    Http(#[from] HttpError),
     #[error("GitHub API returned error: {0}")]
     GitHubApi(String),
     #[error("Failed to parse JSON response: {0}")]
@@ -119,4 +362,88 @@ pub enum UpdateError {
     SpawnProcess(String),
     #[error("Update process failed: {0}")]
     UpdateProcess(String),
+    #[error("Confirmation required, pass --yes")]
+    ConfirmationRequired,
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("No checksum published for {0}, pass --allow-unverified to install it anyway")]
+    ChecksumMissing(String),
+    #[error("Signature verification failed: {0}")]
+    Signature(#[from] SignatureError),
+    #[error("No signature published for {0}, pass --skip-signature to install it anyway")]
+    SignatureMissing(String),
+    #[error("No prebuilt binary for {platform}, available: {assets}")]
+    NoMatchingAsset { platform: String, assets: String },
+    #[error("{archive} contains no kdguard binary, found: {contents}")]
+    ArchiveLayout { archive: String, contents: String },
+}
+
+impl UpdateError {
+    /// Check whether GitHub could not be reached, rather than the update failing
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Network(_) | Self::Http(_) | Self::GitHubApi(_))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SignatureError {
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("Invalid signature file: {0}")]
+    InvalidSignature(String),
+    #[error("Unsupported signature algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("This build has no release key to verify signatures with")]
+    NoPublicKey,
+    #[error("The signature was made with another key")]
+    KeyMismatch,
+    #[error("The signature does not match the data")]
+    BadSignature,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_follow_the_kind_of_error() {
+        let config: KdguardError =
+            ConfigError::UnknownKeyError("general.colour".to_string()).into();
+        assert_eq!(config.exit_code(), EXIT_CONFIG);
+        assert_eq!(
+            KdguardError::from(GeneratorError::MissingSeedEnv).exit_code(),
+            EXIT_GENERATION
+        );
+        assert_eq!(
+            KdguardError::from(UpdateError::Network("timed out".to_string())).exit_code(),
+            EXIT_NETWORK
+        );
+        // Asking for --yes is not a failed update
+        assert_eq!(
+            KdguardError::from(UpdateError::ConfirmationRequired).exit_code(),
+            EXIT_OTHER
+        );
+        assert_eq!(KdguardError::from("boom").exit_code(), EXIT_OTHER);
+    }
+
+    #[test]
+    fn test_render_lists_causes_only_when_verbose() {
+        let error: KdguardError = UpdateError::Signature(SignatureError::BadSignature).into();
+
+        let rendered = error.render(0);
+        assert_eq!(rendered.lines().count(), 3, "{}", rendered);
+        assert!(!rendered.contains("\n  "), "{}", rendered);
+
+        // The causes are indented below the message and the hint
+        let rendered = error.render(2);
+        assert!(
+            rendered
+                .lines()
+                .last()
+                .unwrap()
+                .ends_with(&SignatureError::BadSignature.to_string()),
+            "{}",
+            rendered
+        );
+    }
 }
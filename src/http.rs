@@ -0,0 +1,291 @@
+use std::{env, fs, path::Path};
+
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+
+use crate::config::NetworkConfig;
+use crate::errors::HttpError;
+use crate::logging::LoggingManager;
+
+/// Environment variables naming a proxy, in the order they are looked up
+const PROXY_ENV: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"];
+
+/// Environment variables listing hosts reached without the proxy
+const NO_PROXY_ENV: [&str; 2] = ["NO_PROXY", "no_proxy"];
+
+/// Words in an error that point at a failed TLS handshake
+const TLS_ERROR_WORDS: [&str; 4] = ["certificate", "tls", "ssl", "handshake"];
+
+/// The proxy the HTTP clients connect through
+#[derive(Debug, PartialEq)]
+pub struct ProxySettings {
+    pub url: String,
+    /// Comma separated hosts reached directly, from `NO_PROXY`
+    pub no_proxy: Option<String>,
+}
+
+pub struct HttpManager;
+
+impl HttpManager {
+    /// Get a client builder set up with the proxy and CA bundle of the `[network]` config
+    ///
+    /// Every HTTP feature builds its client here, so they all work behind the same proxy.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent`: The user agent sent with each request
+    /// * `config`: The `[network]` config section
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder, otherwise an error if the proxy or the CA bundle is invalid
+    pub fn builder(user_agent: &str, config: &NetworkConfig) -> Result<ClientBuilder, HttpError> {
+        Self::builder_with(user_agent, config, |name| env::var(name).ok())
+    }
+
+    /// Get a client builder, reading the environment through `env_var`
+    fn builder_with(
+        user_agent: &str,
+        config: &NetworkConfig,
+        env_var: impl Fn(&str) -> Option<String>,
+    ) -> Result<ClientBuilder, HttpError> {
+        let mut builder = reqwest::Client::builder().user_agent(user_agent);
+
+        if let Some(proxy) = Self::resolve_proxy(config, env_var) {
+            LoggingManager::info("Connecting through a proxy");
+            let mut settings = Proxy::all(&proxy.url).map_err(|e| {
+                let error = HttpError::InvalidProxy(e.to_string());
+                LoggingManager::error(&error.to_string());
+                error
+            })?;
+            if let Some(no_proxy) = &proxy.no_proxy {
+                settings = settings.no_proxy(NoProxy::from_string(no_proxy));
+            }
+            builder = builder.proxy(settings);
+        }
+
+        if let Some(path) = &config.extra_ca_bundle {
+            for certificate in Self::load_ca_bundle(Path::new(path))? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(builder)
+    }
+
+    /// Pick the proxy, the config wins over `HTTPS_PROXY` and `HTTP_PROXY`
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The `[network]` config section
+    /// * `env_var`: Reads an environment variable
+    ///
+    /// # Returns
+    ///
+    /// Returns the proxy, None to connect directly
+    pub fn resolve_proxy(
+        config: &NetworkConfig,
+        env_var: impl Fn(&str) -> Option<String>,
+    ) -> Option<ProxySettings> {
+        let first_set = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|name| env_var(name))
+                .find(|value| !value.trim().is_empty())
+        };
+        let url = config
+            .proxy
+            .clone()
+            .filter(|proxy| !proxy.trim().is_empty())
+            .or_else(|| first_set(&PROXY_ENV))?;
+
+        Some(ProxySettings {
+            url,
+            no_proxy: first_set(&NO_PROXY_ENV),
+        })
+    }
+
+    /// Read the certificates of a PEM bundle
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The PEM file
+    ///
+    /// # Returns
+    ///
+    /// Returns the certificates, otherwise an error if the file cannot be read or holds none
+    pub fn load_ca_bundle(path: &Path) -> Result<Vec<Certificate>, HttpError> {
+        let bundle_error = |error: String| {
+            let error = HttpError::CaBundle {
+                path: path.display().to_string(),
+                error,
+            };
+            LoggingManager::error(&error.to_string());
+            error
+        };
+
+        let pem = fs::read(path).map_err(|e| bundle_error(e.to_string()))?;
+        let certificates =
+            Certificate::from_pem_bundle(&pem).map_err(|e| bundle_error(e.to_string()))?;
+        if certificates.is_empty() {
+            return Err(bundle_error("no PEM certificates found".to_string()));
+        }
+
+        LoggingManager::info(&format!(
+            "Loaded {} certificate(s) from {}",
+            certificates.len(),
+            path.display()
+        ));
+        Ok(certificates)
+    }
+
+    /// Describe a failed request with all its causes
+    ///
+    /// reqwest only names the outermost error, the cause of a connection failure is
+    /// further down the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `error`: The error of the request
+    ///
+    /// # Returns
+    ///
+    /// Returns the message, with a hint at the `[network]` config for TLS errors
+    pub fn describe_error(error: &reqwest::Error) -> String {
+        let mut message = error.to_string();
+        let mut source = std::error::Error::source(error);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        Self::with_tls_hint(message)
+    }
+
+    /// Add a hint at the `[network]` config if a message is about TLS
+    fn with_tls_hint(message: String) -> String {
+        let lowercase = message.to_lowercase();
+        if TLS_ERROR_WORDS.iter().any(|word| lowercase.contains(word)) {
+            format!(
+                "{}. If a proxy inspects TLS traffic, set network.extra_ca_bundle to its CA certificate (PEM) and network.proxy in the config",
+                message
+            )
+        } else {
+            message
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_CA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/extra_ca.pem");
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| {
+            vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    #[test]
+    fn test_resolve_proxy() {
+        let config = NetworkConfig::default();
+        assert_eq!(HttpManager::resolve_proxy(&config, env(&[])), None);
+
+        let vars = env(&[
+            ("http_proxy", "http://plain:3128"),
+            ("HTTPS_PROXY", "http://secure:3128"),
+            ("NO_PROXY", "localhost,.corp"),
+        ]);
+        assert_eq!(
+            HttpManager::resolve_proxy(&config, vars),
+            Some(ProxySettings {
+                url: "http://secure:3128".to_string(),
+                no_proxy: Some("localhost,.corp".to_string()),
+            })
+        );
+        assert_eq!(
+            HttpManager::resolve_proxy(&config, env(&[("http_proxy", "http://plain:3128")]))
+                .unwrap()
+                .url,
+            "http://plain:3128"
+        );
+
+        // The config wins over the environment, empty values count as unset
+        let config = NetworkConfig {
+            proxy: Some("http://configured:8080".to_string()),
+            extra_ca_bundle: None,
+        };
+        let vars = env(&[("HTTPS_PROXY", "http://secure:3128")]);
+        assert_eq!(
+            HttpManager::resolve_proxy(&config, vars).unwrap().url,
+            "http://configured:8080"
+        );
+        let config = NetworkConfig {
+            proxy: Some(" ".to_string()),
+            extra_ca_bundle: None,
+        };
+        assert_eq!(
+            HttpManager::resolve_proxy(&config, env(&[("HTTPS_PROXY", "")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_builder_uses_proxy_and_ca_bundle() {
+        let config = NetworkConfig {
+            proxy: None,
+            extra_ca_bundle: Some(FIXTURE_CA.to_string()),
+        };
+        let vars = env(&[("HTTPS_PROXY", "http://proxy.example:3128")]);
+        let builder = HttpManager::builder_with("kdguard-test", &config, vars).unwrap();
+        assert!(builder.build().is_ok());
+
+        let config = NetworkConfig {
+            proxy: Some("http://[::1".to_string()),
+            extra_ca_bundle: None,
+        };
+        assert!(matches!(
+            HttpManager::builder_with("kdguard-test", &config, env(&[])),
+            Err(HttpError::InvalidProxy(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_ca_bundle() {
+        assert_eq!(
+            HttpManager::load_ca_bundle(Path::new(FIXTURE_CA))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let missing = Path::new(FIXTURE_CA).with_file_name("missing.pem");
+        assert!(matches!(
+            HttpManager::load_ca_bundle(&missing),
+            Err(HttpError::CaBundle { .. })
+        ));
+        // A file without certificates is a mistake, not an empty bundle
+        let not_pem = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert!(matches!(
+            HttpManager::load_ca_bundle(&not_pem),
+            Err(HttpError::CaBundle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tls_errors_get_a_hint() {
+        let message = HttpManager::with_tls_hint(
+            "error sending request: invalid peer certificate: UnknownIssuer".to_string(),
+        );
+        assert!(message.contains("network.extra_ca_bundle"), "{}", message);
+
+        let message = HttpManager::with_tls_hint("connection refused".to_string());
+        assert_eq!(message, "connection refused");
+    }
+}
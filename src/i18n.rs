@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use lingua_i18n_rs::prelude::Lingua;
+use serde_json::Value;
+
+use crate::config::EMBEDDED_LANGUAGES;
+use crate::logging::LoggingManager;
+
+lazy_static! {
+    /// The built-in English texts, used for keys the active language file lacks
+    static ref ENGLISH: Value = serde_json::from_str(EMBEDDED_LANGUAGES[0].1).unwrap_or_default();
+    /// The keys already reported as missing, so each one is only logged once
+    static ref MISSING_KEYS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Translate a key into the active language
+///
+/// A key missing from the language file, for example in a partial custom translation,
+/// falls back to the built-in English text and finally to the key itself.
+///
+/// # Arguments
+///
+/// * `key`: The dotted translation key
+/// * `params`: The values of the `{{name}}` placeholders
+///
+/// # Returns
+///
+/// Returns the translated text with the placeholders replaced
+pub fn tr(key: &str, params: &[(&str, &str)]) -> String {
+    Lingua::t(key, params).unwrap_or_else(|_| {
+        report_missing(key);
+        fallback(key, params)
+    })
+}
+
+/// Translate a text made of several lines into the active language
+///
+/// The language files hold such texts as a list of lines, so a translation can have more
+/// or fewer lines than the English one. A single string is split at its line breaks.
+///
+/// # Arguments
+///
+/// * `key`: The dotted translation key
+///
+/// # Returns
+///
+/// Returns the translated lines, the English ones if the key is missing
+pub fn tr_lines(key: &str) -> Vec<String> {
+    // Lingua returns lists as their JSON text
+    let text = Lingua::t(key, &[]).unwrap_or_else(|_| {
+        report_missing(key);
+        match english_text(key) {
+            Some(Value::String(text)) => text.clone(),
+            Some(lines @ Value::Array(_)) => lines.to_string(),
+            _ => key.to_string(),
+        }
+    });
+    serde_json::from_str::<Vec<String>>(&text)
+        .unwrap_or_else(|_| text.lines().map(str::to_string).collect())
+}
+
+/// Load the built-in languages straight from the binary
+///
+/// Used when the languages directory cannot be written, so the CLI can still print
+/// its messages and help without placing files anywhere else.
+pub fn load_embedded_languages() {
+    for (lang, content) in EMBEDDED_LANGUAGES {
+        if let Err(e) = Lingua::load_translations_from_str(lang, content) {
+            LoggingManager::warn(&format!("Failed to load embedded language {}: {}", lang, e));
+        }
+    }
+}
+
+/// Get the built-in English text of a key
+///
+/// # Arguments
+///
+/// * `key`: The dotted translation key
+/// * `params`: The values of the `{{name}}` placeholders
+///
+/// # Returns
+///
+/// Returns the English text, the key itself if there is none
+fn fallback(key: &str, params: &[(&str, &str)]) -> String {
+    let text = english_text(key).and_then(Value::as_str);
+
+    match text {
+        Some(text) => params.iter().fold(text.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{}}}}}", name), value)
+        }),
+        None => key.to_string(),
+    }
+}
+
+/// Look up a key in the built-in English texts
+fn english_text(key: &str) -> Option<&'static Value> {
+    key.split('.')
+        .try_fold(&*ENGLISH, |node, part| node.get(part))
+}
+
+/// Find the texts of a reference language that another language lacks
+///
+/// Lists of lines count as one text, so a translation may have more or fewer lines.
+///
+/// # Arguments
+///
+/// * `reference`: The parsed reference language, usually English
+/// * `language`: The parsed language to compare
+///
+/// # Returns
+///
+/// Returns the dotted keys missing from `language`, sorted
+pub(crate) fn missing_keys(reference: &Value, language: &Value) -> Vec<String> {
+    let mut missing = Vec::new();
+    collect_missing(reference, Some(language), "", &mut missing);
+    missing.sort();
+    missing
+}
+
+/// Collect the keys below a reference node that the other language lacks
+fn collect_missing(
+    reference: &Value,
+    language: Option<&Value>,
+    prefix: &str,
+    missing: &mut Vec<String>,
+) {
+    match reference.as_object() {
+        Some(object) => {
+            for (key, child) in object {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                let translated = language.and_then(|language| language.get(key));
+                collect_missing(child, translated, &path, missing);
+            }
+        }
+        None if language.is_none_or(Value::is_object) => missing.push(prefix.to_string()),
+        None => {}
+    }
+}
+
+/// Get the built-in English texts, the reference of every language
+pub(crate) fn english() -> &'static Value {
+    &ENGLISH
+}
+
+/// Log a missing key the first time it is looked up
+fn report_missing(key: &str) {
+    let first_time = MISSING_KEYS
+        .lock()
+        .map(|mut keys| keys.insert(key.to_string()))
+        .unwrap_or(false);
+    if first_time {
+        LoggingManager::warn(&format!("Missing translation for key: {}", key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_uses_english_then_the_key() {
+        assert_eq!(
+            fallback("cli.about", &[]),
+            "A CLI tool to generate secure and random passwords"
+        );
+        assert_eq!(
+            fallback("config.export.success", &[("path", "/tmp/kdguard.toml")]),
+            "Configuration exported to /tmp/kdguard.toml"
+        );
+        // Sections are not texts
+        assert_eq!(fallback("cli", &[]), "cli");
+        assert_eq!(fallback("cli.no_such_key", &[]), "cli.no_such_key");
+    }
+
+    #[test]
+    fn test_missing_keys() {
+        let reference = serde_json::json!({
+            "a": "A",
+            "b": {"c": "C", "d": ["D1", "D2"]},
+            "e": {"f": "F"}
+        });
+        let language = serde_json::json!({
+            "a": "a",
+            "b": {"d": ["d1"], "extra": "x"},
+            "e": "not a section"
+        });
+        assert_eq!(missing_keys(&reference, &language), ["b.c", "e.f"]);
+        assert!(missing_keys(&reference, &reference).is_empty());
+    }
+
+    #[test]
+    fn test_tr_lines_reads_lists_and_falls_back() {
+        assert_eq!(tr_lines("tui.does_not_exist"), ["tui.does_not_exist"]);
+        // Every line of the list, not its JSON text
+        for lines in [
+            tr_lines("tui.help.history"),
+            serde_json::from_str(&english_text("tui.help.history").unwrap().to_string()).unwrap(),
+        ] {
+            assert!(lines.len() > 1, "{:?}", lines);
+            assert!(
+                lines.iter().all(|line| !line.starts_with('[')),
+                "{:?}",
+                lines
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_embedded_languages() {
+        load_embedded_languages();
+        let languages = Lingua::get_languages().unwrap();
+        for (lang, _) in EMBEDDED_LANGUAGES {
+            assert!(languages.iter().any(|loaded| loaded == lang), "{}", lang);
+        }
+    }
+
+    #[test]
+    fn test_missing_keys_are_reported_once() {
+        report_missing("tests.reported_key");
+        assert!(
+            !MISSING_KEYS
+                .lock()
+                .unwrap()
+                .insert("tests.reported_key".to_string())
+        );
+        assert_eq!(tr("tests.unknown_key", &[]), "tests.unknown_key");
+    }
+}
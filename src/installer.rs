@@ -0,0 +1,639 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+use crate::errors::UpdateError;
+use crate::logging::{LoggingManager, Redactable};
+
+/// Suffix of the binary kept for `kdguard update --rollback`
+const PREVIOUS_BINARY_SUFFIX: &str = ".prev";
+
+/// Suffix of the file holding the version of the previous binary
+const PREVIOUS_VERSION_SUFFIX: &str = ".prev.version";
+
+/// Suffix of a binary written but not yet moved into place
+const NEW_BINARY_SUFFIX: &str = ".new";
+
+/// Suffix of a file moved aside because Windows still runs it, deleted on the next start
+const STALE_SUFFIX: &str = ".old";
+
+/// Places the binary and the `kdg` alias in the install directory
+///
+/// Windows cannot delete or overwrite a running executable, but it can rename one.
+/// So a binary in use is always renamed aside first and removed on the next start.
+pub struct InstallManager;
+
+impl InstallManager {
+    /// Get the file name of the binary
+    pub fn binary_name() -> &'static str {
+        if cfg!(windows) {
+            "kdguard.exe"
+        } else {
+            "kdguard"
+        }
+    }
+
+    /// Get the file name of the `kdg` alias
+    pub fn alias_name() -> &'static str {
+        if cfg!(windows) { "kdg.exe" } else { "kdg" }
+    }
+
+    /// List the files kdguard itself places next to the binary
+    ///
+    /// # Arguments
+    ///
+    /// * `binary_path` - The installed binary
+    ///
+    /// # Returns
+    ///
+    /// Returns the binary, the alias, and the files an update keeps or leaves behind,
+    /// whether they exist or not
+    pub fn installed_files(binary_path: &Path) -> Vec<PathBuf> {
+        let alias_path = binary_path.with_file_name(Self::alias_name());
+        let mut files = vec![binary_path.to_path_buf()];
+        for suffix in [
+            PREVIOUS_BINARY_SUFFIX,
+            PREVIOUS_VERSION_SUFFIX,
+            NEW_BINARY_SUFFIX,
+            STALE_SUFFIX,
+        ] {
+            files.push(Self::sibling(binary_path, suffix));
+        }
+        files.push(Self::sibling(&alias_path, STALE_SUFFIX));
+        files.push(alias_path);
+        files
+    }
+
+    /// Get a file next to the binary, named after it
+    fn sibling(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        path.with_file_name(name)
+    }
+
+    /// Read the version recorded next to the previous binary
+    ///
+    /// # Arguments
+    ///
+    /// * `binary_path` - The installed binary
+    ///
+    /// # Returns
+    ///
+    /// Returns the version, `unknown` if it was not recorded, None if no previous
+    /// binary is kept
+    pub fn read_previous_version(binary_path: &Path) -> Option<String> {
+        if !Self::sibling(binary_path, PREVIOUS_BINARY_SUFFIX).exists() {
+            return None;
+        }
+        let version = fs::read_to_string(Self::sibling(binary_path, PREVIOUS_VERSION_SUFFIX))
+            .map(|version| version.trim().to_string())
+            .unwrap_or_default();
+        Some(if version.is_empty() {
+            "unknown".to_string()
+        } else {
+            version
+        })
+    }
+
+    /// Replace the installed binary and keep the replaced one as `<binary>.prev`
+    ///
+    /// The running binary is renamed, never overwritten, which Windows allows.
+    ///
+    /// # Arguments
+    ///
+    /// * `binary_path` - The installed binary
+    /// * `data` - The new binary
+    /// * `current_version` - The version of the binary being replaced
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the new binary is in place, otherwise an error leaving the
+    /// installed binary untouched
+    pub fn install_binary(
+        binary_path: &Path,
+        data: &[u8],
+        current_version: &str,
+    ) -> Result<(), UpdateError> {
+        let temp_path = Self::sibling(binary_path, NEW_BINARY_SUFFIX);
+        let previous_path = Self::sibling(binary_path, PREVIOUS_BINARY_SUFFIX);
+
+        let mut file = File::create(&temp_path).map_err(|e| {
+            let error = format!("Failed to create temporary binary file: {}", e);
+            LoggingManager::error(&error);
+            UpdateError::Update(error)
+        })?;
+        file.write_all(data).map_err(|e| {
+            let error = format!("Failed to write binary data: {}", e);
+            LoggingManager::error(&error);
+            UpdateError::Update(error)
+        })?;
+        drop(file);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o755)).map_err(|e| {
+                let error = format!("Failed to set file permissions: {}", e);
+                LoggingManager::error(&error);
+                UpdateError::Update(error)
+            })?;
+        }
+
+        if binary_path.exists() {
+            // The kept binary may be running as well
+            Self::move_aside(&previous_path).map_err(|e| {
+                let error = format!("Failed to remove the previous binary: {}", e);
+                LoggingManager::error(&error);
+                UpdateError::Update(error)
+            })?;
+            rename(binary_path, &previous_path).map_err(|e| {
+                let error = format!("Failed to keep the current binary: {}", e);
+                LoggingManager::error(&error);
+                UpdateError::Update(error)
+            })?;
+            Self::record_previous_version(binary_path, current_version);
+        }
+
+        rename(&temp_path, binary_path).map_err(|e| {
+            let error = format!("Failed to rename new binary: {}", e);
+            LoggingManager::error(&error);
+            if previous_path.exists() {
+                rename(&previous_path, binary_path).ok();
+            }
+            UpdateError::Update(error)
+        })
+    }
+
+    /// Write the version of the previous binary, failures only hide it from `doctor`
+    fn record_previous_version(binary_path: &Path, version: &str) {
+        let path = Self::sibling(binary_path, PREVIOUS_VERSION_SUFFIX);
+        if let Err(e) = fs::write(&path, version) {
+            LoggingManager::warn(&format!("Failed to record the previous version: {}", e));
+        }
+    }
+
+    /// Swap the installed binary with the previous one
+    ///
+    /// The replaced binary is kept in turn, so a rollback can be undone with another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `binary_path` - The installed binary
+    /// * `current_version` - The version of the binary being replaced
+    ///
+    /// # Returns
+    ///
+    /// Returns the version rolled back to, None if no previous binary is kept,
+    /// otherwise an error leaving the installed binary untouched
+    pub fn rollback_binary(
+        binary_path: &Path,
+        current_version: &str,
+    ) -> Result<Option<String>, UpdateError> {
+        let Some(restored) = Self::read_previous_version(binary_path) else {
+            return Ok(None);
+        };
+        let temp_path = Self::sibling(binary_path, NEW_BINARY_SUFFIX);
+        let previous_path = Self::sibling(binary_path, PREVIOUS_BINARY_SUFFIX);
+        let swap_error = |e: io::Error| {
+            let error = format!("Failed to roll back: {}", e);
+            LoggingManager::error(&error);
+            UpdateError::Update(error)
+        };
+
+        let had_binary = binary_path.exists();
+        if had_binary {
+            rename(binary_path, &temp_path).map_err(swap_error)?;
+        }
+        if let Err(e) = rename(&previous_path, binary_path) {
+            if had_binary {
+                rename(&temp_path, binary_path).ok();
+            }
+            return Err(swap_error(e));
+        }
+        if had_binary {
+            rename(&temp_path, &previous_path).map_err(swap_error)?;
+            Self::record_previous_version(binary_path, current_version);
+        } else {
+            fs::remove_file(Self::sibling(binary_path, PREVIOUS_VERSION_SUFFIX)).ok();
+        }
+
+        LoggingManager::info(&format!(
+            "Rolled back from {} to {}",
+            current_version, restored
+        ));
+        Ok(Some(restored))
+    }
+
+    /// Remove a file, or rename it to `<file>.old` if it cannot be removed
+    ///
+    /// Windows refuses to delete an executable while it runs, e.g. the `kdg` copy
+    /// during `kdg update`. The renamed file is deleted by `finish_pending`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file to free the path of
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the path is free, otherwise an error
+    fn move_aside(path: &Path) -> io::Result<()> {
+        Self::move_aside_with(path, |path| fs::remove_file(path))
+    }
+
+    /// Free a path, removing the file with `remove`
+    fn move_aside_with(path: &Path, remove: impl Fn(&Path) -> io::Result<()>) -> io::Result<()> {
+        // A dangling symlink does not exist but still takes the path
+        if fs::symlink_metadata(path).is_err() {
+            return Ok(());
+        }
+        let Err(e) = remove(path) else {
+            return Ok(());
+        };
+
+        LoggingManager::info(&format!(
+            "Cannot remove {} ({}), moving it aside",
+            Redactable(path.display()),
+            e
+        ));
+        let stale = Self::sibling(path, STALE_SUFFIX);
+        if fs::symlink_metadata(&stale).is_ok() {
+            remove(&stale)?;
+        }
+        rename(path, &stale)
+    }
+
+    /// Point the `kdg` alias at the binary
+    ///
+    /// The alias is a symlink if the system allows one, otherwise a copy of the binary.
+    /// Windows only allows symlinks in developer mode or for administrators.
+    ///
+    /// # Arguments
+    ///
+    /// * `binary_path`: The installed binary
+    /// * `alias_path`: The alias
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the alias runs the binary, otherwise an error
+    pub fn refresh_alias(binary_path: &Path, alias_path: &Path) -> Result<(), UpdateError> {
+        Self::refresh_alias_with(binary_path, alias_path, link)
+    }
+
+    /// Point the alias at the binary, creating a symlink with `link`
+    fn refresh_alias_with(
+        binary_path: &Path,
+        alias_path: &Path,
+        link: impl Fn(&Path, &Path) -> io::Result<()>,
+    ) -> Result<(), UpdateError> {
+        Self::move_aside(alias_path).map_err(|e| {
+            let error = format!("Failed to remove existing alias: {}", e);
+            LoggingManager::error(&error);
+            UpdateError::Update(error)
+        })?;
+
+        if let Err(e) = link(binary_path, alias_path) {
+            LoggingManager::info(&format!("Cannot create a symlink ({}), copying instead", e));
+            fs::copy(binary_path, alias_path).map_err(|e| {
+                let error = format!("Failed to create alias: {}", e);
+                LoggingManager::error(&error);
+                UpdateError::Update(error)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Check that the alias runs the expected version
+    ///
+    /// # Arguments
+    ///
+    /// * `alias_path`: The alias
+    /// * `expected_version`: The version just installed
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if `kdg --version` prints the version, otherwise an error
+    pub fn verify_alias(alias_path: &Path, expected_version: &str) -> Result<(), UpdateError> {
+        let output = Command::new(alias_path)
+            .arg("--version")
+            .output()
+            .map_err(|e| {
+                let error = format!("Failed to run {}: {}", Redactable(alias_path.display()), e);
+                LoggingManager::warn(&error);
+                UpdateError::Update(error)
+            })?;
+        let printed = String::from_utf8_lossy(&output.stdout);
+        // `kdguard 1.2.3 (abc1234)`
+        let version = printed.split_whitespace().nth(1).unwrap_or_default();
+        if output.status.success() && version == expected_version {
+            return Ok(());
+        }
+
+        let error = format!(
+            "{} reports version {:?}, expected {}",
+            Redactable(alias_path.display()),
+            printed.trim(),
+            expected_version
+        );
+        LoggingManager::warn(&error);
+        Err(UpdateError::Update(error))
+    }
+
+    /// Finish an update on the next start
+    ///
+    /// Deletes the files a previous update had to move aside and refreshes an alias
+    /// copy older than the binary. Failures are only logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `binary_path`: The installed binary
+    /// * `alias_path`: The alias
+    pub fn finish_pending(binary_path: &Path, alias_path: &Path) {
+        for leftover in [
+            Self::sibling(binary_path, STALE_SUFFIX),
+            Self::sibling(binary_path, NEW_BINARY_SUFFIX),
+            Self::sibling(alias_path, STALE_SUFFIX),
+        ] {
+            if leftover.exists() {
+                match fs::remove_file(&leftover) {
+                    Ok(()) => {
+                        LoggingManager::info(&format!("Removed {}", Redactable(leftover.display())))
+                    }
+                    Err(e) => LoggingManager::debug(&format!(
+                        "Cannot remove {} yet: {}",
+                        Redactable(leftover.display()),
+                        e
+                    )),
+                }
+            }
+        }
+
+        if Self::alias_is_stale(binary_path, alias_path) {
+            LoggingManager::info("The alias copy is older than the binary, refreshing it");
+            // An error is already logged
+            let _ = Self::refresh_alias(binary_path, alias_path);
+        }
+    }
+
+    /// Check whether the alias is a copy that differs from the binary
+    fn alias_is_stale(binary_path: &Path, alias_path: &Path) -> bool {
+        let (Ok(binary), Ok(alias)) = (fs::metadata(binary_path), fs::symlink_metadata(alias_path))
+        else {
+            return false;
+        };
+        if alias.file_type().is_symlink() {
+            return false;
+        }
+        let modified =
+            |metadata: &fs::Metadata| metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        binary.len() != alias.len() || modified(&alias) < modified(&binary)
+    }
+}
+
+/// Create a symlink to a file
+#[cfg(unix)]
+fn link(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Create a symlink to a file, fails without developer mode or administrator rights
+#[cfg(windows)]
+fn link(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+/// Rename a file
+#[cfg(not(windows))]
+fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to)
+}
+
+/// Rename a file, retrying while a virus scanner or the indexer briefly holds it open
+#[cfg(windows)]
+fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    const ATTEMPTS: u32 = 5;
+    let mut attempt = 1;
+    loop {
+        match fs::rename(from, to) {
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied && attempt < ATTEMPTS => {
+                attempt += 1;
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// Install directory for one test, removed when dropped
+    struct TempInstall(TempDir);
+
+    impl TempInstall {
+        fn new(name: &str) -> Self {
+            Self(TempDir::new(name))
+        }
+
+        fn binary(&self) -> PathBuf {
+            self.0.join("kdguard")
+        }
+
+        fn alias(&self) -> PathBuf {
+            self.0.join("kdg")
+        }
+
+        fn read(&self, name: &str) -> String {
+            fs::read_to_string(self.0.join(name)).unwrap()
+        }
+    }
+
+    /// Fails like Windows does for a running executable
+    fn locked(_: &Path) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::PermissionDenied))
+    }
+
+    /// Fails like Windows does without developer mode
+    fn no_symlinks(_: &Path, _: &Path) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::PermissionDenied))
+    }
+
+    #[test]
+    fn test_install_keeps_the_previous_binary() {
+        let install = TempInstall::new("swap");
+        let binary = install.binary();
+
+        // A first install has nothing to keep
+        InstallManager::install_binary(&binary, b"v1", "0.9.0").unwrap();
+        assert_eq!(install.read("kdguard"), "v1");
+        assert_eq!(InstallManager::read_previous_version(&binary), None);
+
+        InstallManager::install_binary(&binary, b"v2", "1.0.0").unwrap();
+        assert_eq!(install.read("kdguard"), "v2");
+        assert_eq!(install.read("kdguard.prev"), "v1");
+        assert_eq!(
+            InstallManager::read_previous_version(&binary).as_deref(),
+            Some("1.0.0")
+        );
+
+        // Only one generation is kept
+        InstallManager::install_binary(&binary, b"v3", "2.0.0").unwrap();
+        assert_eq!(install.read("kdguard.prev"), "v2");
+        assert_eq!(
+            InstallManager::read_previous_version(&binary).as_deref(),
+            Some("2.0.0")
+        );
+        assert!(!install.0.join("kdguard.new").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&binary).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+
+    #[test]
+    fn test_rollback_swaps_the_binaries() {
+        let install = TempInstall::new("rollback");
+        let binary = install.binary();
+        InstallManager::install_binary(&binary, b"v1", "0.9.0").unwrap();
+        InstallManager::install_binary(&binary, b"v2", "1.0.0").unwrap();
+
+        assert_eq!(
+            InstallManager::rollback_binary(&binary, "2.0.0").unwrap(),
+            Some("1.0.0".to_string())
+        );
+        assert_eq!(install.read("kdguard"), "v1");
+        assert_eq!(install.read("kdguard.prev"), "v2");
+        assert_eq!(
+            InstallManager::read_previous_version(&binary).as_deref(),
+            Some("2.0.0")
+        );
+
+        // Rolling back again undoes the rollback
+        assert_eq!(
+            InstallManager::rollback_binary(&binary, "1.0.0").unwrap(),
+            Some("2.0.0".to_string())
+        );
+        assert_eq!(install.read("kdguard"), "v2");
+        assert_eq!(install.read("kdguard.prev"), "v1");
+    }
+
+    #[test]
+    fn test_rollback_without_previous_binary() {
+        let install = TempInstall::new("no-prev");
+        let binary = install.binary();
+
+        assert_eq!(
+            InstallManager::rollback_binary(&binary, "1.0.0").unwrap(),
+            None
+        );
+        InstallManager::install_binary(&binary, b"v1", "0.9.0").unwrap();
+        assert_eq!(
+            InstallManager::rollback_binary(&binary, "1.0.0").unwrap(),
+            None
+        );
+        assert_eq!(install.read("kdguard"), "v1");
+
+        // A previous binary without a recorded version can still be restored
+        fs::write(install.0.join("kdguard.prev"), "v0").unwrap();
+        assert_eq!(
+            InstallManager::rollback_binary(&binary, "1.0.0").unwrap(),
+            Some("unknown".to_string())
+        );
+        assert_eq!(install.read("kdguard"), "v0");
+    }
+
+    #[test]
+    fn test_locked_files_are_moved_aside_and_removed_later() {
+        let install = TempInstall::new("locked");
+        let alias = install.alias();
+        fs::write(&alias, "running copy").unwrap();
+        fs::write(install.0.join("kdg.old"), "older copy").unwrap();
+
+        InstallManager::move_aside_with(&alias, |path| {
+            if path == alias {
+                locked(path)
+            } else {
+                fs::remove_file(path)
+            }
+        })
+        .unwrap();
+        assert!(!alias.exists());
+        assert_eq!(install.read("kdg.old"), "running copy");
+
+        // Nothing to free
+        InstallManager::move_aside_with(&alias, locked).unwrap();
+
+        // The next start cleans up, including a binary left over from a broken update
+        fs::write(install.binary(), "v2").unwrap();
+        fs::write(install.0.join("kdguard.old"), "v1").unwrap();
+        fs::write(install.0.join("kdguard.new"), "half").unwrap();
+        InstallManager::finish_pending(&install.binary(), &alias);
+        for leftover in ["kdg.old", "kdguard.old", "kdguard.new"] {
+            assert!(!install.0.join(leftover).exists(), "{}", leftover);
+        }
+    }
+
+    #[test]
+    fn test_alias_falls_back_to_a_copy() {
+        let install = TempInstall::new("alias-copy");
+        let (binary, alias) = (install.binary(), install.alias());
+        fs::write(&binary, "v1").unwrap();
+
+        InstallManager::refresh_alias_with(&binary, &alias, no_symlinks).unwrap();
+        assert!(
+            !fs::symlink_metadata(&alias)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(install.read("kdg"), "v1");
+        assert!(!InstallManager::alias_is_stale(&binary, &alias));
+
+        // An update replaces the binary, the copy is refreshed on the next start
+        fs::write(&binary, "v2 is bigger").unwrap();
+        assert!(InstallManager::alias_is_stale(&binary, &alias));
+        InstallManager::finish_pending(&binary, &alias);
+        assert_eq!(install.read("kdg"), "v2 is bigger");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_alias_symlink_is_never_stale() {
+        let install = TempInstall::new("alias-link");
+        let (binary, alias) = (install.binary(), install.alias());
+        fs::write(&binary, "v1").unwrap();
+        // A dangling symlink is replaced as well
+        std::os::unix::fs::symlink(install.0.join("gone"), &alias).unwrap();
+
+        InstallManager::refresh_alias(&binary, &alias).unwrap();
+        assert_eq!(
+            fs::canonicalize(&alias).unwrap(),
+            fs::canonicalize(&binary).unwrap()
+        );
+        fs::write(&binary, "v2 is bigger").unwrap();
+        assert!(!InstallManager::alias_is_stale(&binary, &alias));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_alias() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let install = TempInstall::new("alias-verify");
+        let alias = install.alias();
+        fs::write(&alias, "#!/bin/sh\necho 'kdguard 1.2.3 (abc1234)'\n").unwrap();
+        fs::set_permissions(&alias, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(InstallManager::verify_alias(&alias, "1.2.3").is_ok());
+        assert!(InstallManager::verify_alias(&alias, "1.2.4").is_err());
+        assert!(InstallManager::verify_alias(&install.0.join("missing"), "1.2.3").is_err());
+    }
+}
@@ -1,20 +1,39 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use chrono::TimeDelta;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, Command, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use lazy_static::lazy_static;
-use lingua_i18n_rs::prelude::Lingua;
 
-use crate::config::Config;
+use crate::config::{Config, ConfigFormat};
+use crate::i18n::tr;
+use crate::logging::{LOG_LEVELS, LogFormat};
+use crate::password::expiry::parse_duration;
+use crate::password::policy::STRICT_POLICY_NAME;
+use crate::password::{InsertStyle, Kdf, SheetFormat};
+use crate::update::LONG_VERSION;
 
 mod config;
+mod doctor;
+mod http;
+mod installer;
 mod password;
+pub mod signature;
 pub mod tui;
 mod uninstall;
 mod update;
 
 mod errors;
+mod i18n;
 mod logging;
+mod progress;
+#[cfg(test)]
+mod test_support;
 
 lazy_static! {
-    pub static ref CONFIG: Config = Config::load_config().unwrap();
+    // A config that cannot be loaded falls back to the defaults, main warns about it
+    pub static ref CONFIG: Config = Config::load_active().unwrap_or_default();
     static ref DEFAULT_MODE_STR: String = CONFIG.general.default_mode.clone();
 }
 
@@ -42,84 +61,417 @@ fn get_default_mode() -> &'static str {
 
 #[derive(Parser)]
 #[command(
-    version,
-    about = Lingua::t("cli.about", &[]).unwrap(),
+    version = LONG_VERSION,
+    disable_version_flag = true,
+    about = tr("cli.about", &[]),
+    after_help = tr("cli.exit_codes", &[]),
     author = "KarnesTH <p_haehnel@hotmail.de>"
 )]
 pub struct Cli {
     #[clap(subcommand)]
     pub commands: Option<Commands>,
-    #[clap(short, long, help = Lingua::t("cli.args.mode_help", &[]).unwrap(), value_parser = parse_password_mode, default_value = get_default_mode())]
+    #[clap(short = 'V', long, help = tr("cli.args.version_help", &[]))]
+    pub version: bool,
+    #[clap(long, requires = "version", help = tr("cli.args.json_help", &[]))]
+    pub json: bool,
+    #[clap(long, global = true, value_name = "DIR", help = tr("cli.args.config_help", &[]))]
+    pub config: Option<PathBuf>,
+    #[clap(long, global = true, value_name = "NAME", help = tr("cli.args.profile_help", &[]))]
+    pub profile: Option<String>,
+    #[clap(long, global = true, help = tr("cli.args.no_update_check_help", &[]))]
+    pub no_update_check: bool,
+    #[clap(short, long, global = true, action = clap::ArgAction::Count, help = tr("cli.args.verbose_help", &[]))]
+    pub verbose: u8,
+    #[clap(long, global = true, value_name = "LEVEL", value_parser = LOG_LEVELS, ignore_case = true, help = tr("cli.args.log_level_help", &[]))]
+    pub log_level: Option<String>,
+    #[clap(long, global = true, value_name = "FORMAT", value_enum, ignore_case = true, help = tr("cli.args.log_format_help", &[]))]
+    pub log_format: Option<LogFormat>,
+    #[clap(long, global = true, help = tr("cli.args.no_log_file_help", &[]))]
+    pub no_log_file: bool,
+    #[clap(long, global = true, help = tr("cli.args.no_system_info_help", &[]))]
+    pub no_system_info: bool,
+    /// Generation options without a command, deprecated in favor of `generate`
+    #[command(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Options of the `generate` command
+#[derive(Args, Clone, PartialEq)]
+pub struct GenerateArgs {
+    #[clap(short, long, help = tr("cli.args.mode_help", &[]), value_enum, ignore_case = true, default_value = get_default_mode())]
     pub mode: PasswordMode,
-    #[clap(short, long, help = Lingua::t("cli.args.length_help", &[]).unwrap(), default_value_t = CONFIG.general.default_length)]
+    #[clap(short, long, help = tr("cli.args.length_help", &[]), default_value_t = CONFIG.general.default_length)]
     pub length: usize,
-    #[clap(short, long, help = Lingua::t("cli.args.count_help", &[]).unwrap(), default_value_t = CONFIG.general.default_count)]
+    #[clap(short, long, help = tr("cli.args.count_help", &[]), default_value_t = CONFIG.general.default_count)]
     pub count: usize,
     #[clap(
         short,
         long,
-        help = Lingua::t("cli.args.save_help", &[]).unwrap(),
+        help = tr("cli.args.save_help", &[]),
         default_value_t = CONFIG.general.auto_save
     )]
     pub save: bool,
-    #[clap(short, long, help = Lingua::t("cli.args.output_help", &[]).unwrap())]
+    #[clap(short, long, help = tr("cli.args.output_help", &[]))]
     pub output: Option<String>,
-    #[clap(short, long, help = Lingua::t("cli.args.pattern_help", &[]).unwrap())]
+    #[clap(short, long, help = tr("cli.args.pattern_help", &[]))]
     pub pattern: Option<String>,
-    #[clap(short, long, help = Lingua::t("cli.args.phrase_help", &[]).unwrap())]
+    #[clap(long, value_name = "CANDIDATES", help = tr("cli.args.prefer_typeable_help", &[]), num_args = 0..=1, default_missing_value = "8")]
+    pub prefer_typeable: Option<usize>,
+    #[clap(long, help = tr("cli.args.shuffle_help", &[]), default_value_t = false)]
+    pub shuffle: bool,
+    #[clap(short, long, help = tr("cli.args.phrase_help", &[]))]
     pub words: Option<usize>,
-    #[clap(long, help = Lingua::t("cli.args.seed_env_help", &[]).unwrap())]
+    #[clap(long, value_name = "N", help = tr("cli.args.phrase_digits_help", &[]), default_value_t = 0)]
+    pub phrase_digits: usize,
+    #[clap(long, value_name = "N", help = tr("cli.args.phrase_symbols_help", &[]), default_value_t = 0)]
+    pub phrase_symbols: usize,
+    #[clap(long, value_enum, help = tr("cli.args.phrase_insert_style_help", &[]), default_value_t = InsertStyle::Between)]
+    pub phrase_insert_style: InsertStyle,
+    #[clap(long, help = tr("cli.args.seed_env_help", &[]))]
     pub seed_env: Option<String>,
-    #[clap(long, help = Lingua::t("cli.args.service_help", &[]).unwrap())]
+    #[clap(long, help = tr("cli.args.service_help", &[]))]
     pub service: Option<String>,
-    #[clap(long, help = Lingua::t("cli.args.salt_help", &[]).unwrap())]
+    #[clap(long, value_name = "PATH", help = tr("cli.args.services_file_help", &[]), conflicts_with_all = ["service", "verify_policy"])]
+    pub services_file: Option<String>,
+    #[clap(long, help = tr("cli.args.salt_help", &[]))]
     pub salt: Option<String>,
+    #[clap(long, value_enum, help = tr("cli.args.kdf_help", &[]))]
+    pub kdf: Option<Kdf>,
+    #[clap(long, help = tr("cli.args.verify_policy_help", &[]), num_args = 0..=1, default_missing_value = STRICT_POLICY_NAME)]
+    pub verify_policy: Option<String>,
+    #[clap(short = '0', long, help = tr("cli.args.print0_help", &[]), conflicts_with = "verify_policy")]
+    pub print0: bool,
+    #[clap(long, help = tr("cli.args.show_help", &[]), default_value_t = false)]
+    pub show: bool,
+    #[clap(long, value_name = "DURATION", value_parser = parse_duration, help = tr("cli.args.expires_help", &[]), conflicts_with = "verify_policy")]
+    pub expires: Option<TimeDelta>,
+}
+
+/// Options of the root command that are not generation options
+const GLOBAL_ARGS: [&str; 10] = [
+    "version",
+    "json",
+    "config",
+    "profile",
+    "no_update_check",
+    "verbose",
+    "log_level",
+    "log_format",
+    "no_log_file",
+    "no_system_info",
+];
+
+impl Cli {
+    /// Build the command line interface
+    ///
+    /// The generation options of the root command still work but only the `generate`
+    /// command lists them in its help.
+    ///
+    /// # Returns
+    ///
+    /// Returns the command to parse the arguments with
+    pub fn build_command() -> Command {
+        Self::command().mut_args(|arg| {
+            if GLOBAL_ARGS.contains(&arg.get_id().as_str()) {
+                arg
+            } else {
+                arg.hide(true)
+            }
+        })
+    }
+
+    /// Check whether any password generation argument was passed on the command line
+    ///
+    /// # Arguments
+    ///
+    /// * `matches`: The parsed command line arguments
+    ///
+    /// # Returns
+    ///
+    /// Returns true if at least one argument was not taken from its default, otherwise false
+    pub fn has_generation_args(matches: &ArgMatches) -> bool {
+        matches
+            .ids()
+            .filter(|id| !GLOBAL_ARGS.contains(&id.as_str()))
+            .any(|id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine))
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    #[command(about = Lingua::t("cli.cli_commands.check_password.about", &[]).unwrap())]
+    #[command(about = tr("cli.cli_commands.generate.about", &[]))]
+    Generate(GenerateArgs),
+    #[command(about = tr("cli.cli_commands.check_password.about", &[]))]
     Check {
         #[clap(
-            help = Lingua::t("cli.cli_commands.check_password.password_help", &[]).unwrap()
+            help = tr("cli.cli_commands.check_password.password_help", &[]),
+            value_name = "PASSWORDS",
+            required_unless_present = "file"
         )]
-        password: String,
-        #[clap(short, long, help = Lingua::t("cli.cli_commands.check_password.detailed_help", &[]).unwrap(), default_value_t = false)]
+        passwords: Vec<String>,
+        #[clap(short, long, help = tr("cli.cli_commands.check_password.file_help", &[]), conflicts_with_all = ["passwords", "compare", "policy"])]
+        file: Option<String>,
+        #[clap(short, long, help = tr("cli.cli_commands.check_password.output_help", &[]), requires = "file")]
+        output: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.check_password.find_reuse_help", &[]), requires = "file")]
+        find_reuse: bool,
+        #[clap(long, help = tr("cli.cli_commands.check_password.json_help", &[]), requires = "find_reuse")]
+        json: bool,
+        #[clap(short, long, help = tr("cli.cli_commands.check_password.detailed_help", &[]), default_value_t = false)]
         detailed: bool,
+        #[clap(long, help = tr("cli.cli_commands.check_password.banned_words_help", &[]))]
+        banned_words: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.check_password.policy_help", &[]), num_args = 0..=1, default_missing_value = STRICT_POLICY_NAME)]
+        policy: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.check_password.username_help", &[]))]
+        username: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.check_password.compare_help", &[]), default_value_t = false)]
+        compare: bool,
+        #[clap(long, value_name = "INDEX", help = tr("cli.cli_commands.check_password.breachdb_help", &[]), num_args = 0..=1, conflicts_with = "find_reuse")]
+        breachdb: Option<Option<String>>,
+    },
+    #[command(about = tr("cli.cli_commands.backup_sheet.about", &[]))]
+    BackupSheet {
+        #[clap(short, long, help = tr("cli.cli_commands.backup_sheet.output_help", &[]))]
+        output: Option<String>,
+        #[clap(long, value_enum, ignore_case = true, default_value = "text", help = tr("cli.cli_commands.backup_sheet.format_help", &[]))]
+        format: SheetFormat,
+        #[clap(long, help = tr("cli.args.salt_help", &[]))]
+        salt: Option<String>,
+        #[clap(long = "service", value_name = "SERVICE", help = tr("cli.cli_commands.backup_sheet.service_help", &[]))]
+        services: Vec<String>,
+        #[clap(long, value_enum, help = tr("cli.args.kdf_help", &[]))]
+        kdf: Option<Kdf>,
+        #[clap(long, help = tr("cli.cli_commands.backup_sheet.seed_env_help", &[]))]
+        seed_env: Option<String>,
+    },
+    #[command(about = tr("cli.cli_commands.expire_check.about", &[]))]
+    ExpireCheck {
+        #[clap(help = tr("cli.cli_commands.expire_check.file_help", &[]))]
+        file: String,
     },
-    #[command(about = Lingua::t("cli.cli_commands.manage_config.about", &[]).unwrap())]
+    #[command(about = tr("cli.cli_commands.patterns.about", &[]))]
+    Patterns {
+        #[clap(subcommand)]
+        commands: PatternsCommands,
+    },
+    #[command(about = tr("cli.cli_commands.breachdb.about", &[]))]
+    Breachdb {
+        #[clap(subcommand)]
+        commands: BreachdbCommands,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.about", &[]))]
     Config {
         #[clap(subcommand)]
         commands: ConfigCommands,
     },
-    #[command(about = Lingua::t("cli.cli_commands.uninstall.about", &[]).unwrap())]
-    Uninstall,
+    #[command(about = tr("cli.cli_commands.update.about", &[]))]
+    Update {
+        #[clap(short, long, help = tr("cli.cli_commands.update.yes_help", &[]))]
+        yes: bool,
+        #[clap(long, help = tr("cli.cli_commands.update.allow_unverified_help", &[]))]
+        allow_unverified: bool,
+        #[clap(long, help = tr("cli.cli_commands.update.skip_signature_help", &[]))]
+        skip_signature: bool,
+        #[clap(long, help = tr("cli.cli_commands.update.rollback_help", &[]), conflicts_with_all = ["allow_unverified", "skip_signature"])]
+        rollback: bool,
+    },
+    #[command(about = tr("cli.cli_commands.uninstall.about", &[]))]
+    Uninstall {
+        #[clap(short, long, help = tr("cli.cli_commands.uninstall.yes_help", &[]))]
+        yes: bool,
+        #[clap(long, help = tr("cli.cli_commands.uninstall.no_backup_help", &[]))]
+        no_backup: bool,
+        #[clap(long, help = tr("cli.cli_commands.uninstall.dry_run_help", &[]))]
+        dry_run: bool,
+        #[clap(long, help = tr("cli.cli_commands.uninstall.force_help", &[]))]
+        force: bool,
+    },
+    #[command(about = tr("cli.cli_commands.doctor.about", &[]))]
+    Doctor,
+    #[command(about = tr("cli.cli_commands.logs.about", &[]))]
+    Logs {
+        #[clap(long, help = tr("cli.cli_commands.logs.path_help", &[]), conflicts_with_all = ["tail", "clear"])]
+        path: bool,
+        #[clap(long, value_name = "N", help = tr("cli.cli_commands.logs.tail_help", &[]), conflicts_with = "clear")]
+        tail: Option<usize>,
+        #[clap(long, help = tr("cli.cli_commands.logs.clear_help", &[]))]
+        clear: bool,
+        #[clap(short, long, help = tr("cli.cli_commands.logs.yes_help", &[]), requires = "clear")]
+        yes: bool,
+    },
+    #[command(
+        about = tr("cli.cli_commands.completions.about", &[]),
+        long_about = tr("cli.cli_commands.completions.long_about", &[])
+    )]
+    Completions {
+        #[clap(help = tr("cli.cli_commands.completions.shell_help", &[]))]
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PatternsCommands {
+    #[command(about = tr("cli.cli_commands.patterns.list_about", &[]))]
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum BreachdbCommands {
+    #[command(about = tr("cli.cli_commands.breachdb.import_about", &[]))]
+    Import {
+        #[clap(help = tr("cli.cli_commands.breachdb.path_help", &[]))]
+        path: String,
+        #[clap(short, long, help = tr("cli.cli_commands.breachdb.output_help", &[]))]
+        output: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum ConfigCommands {
-    #[command(about = Lingua::t("cli.cli_commands.manage_config.show_about", &[]).unwrap())]
-    Show,
-    #[command(about = Lingua::t("cli.cli_commands.manage_config.edit_about", &[]).unwrap())]
+    #[command(about = tr("cli.cli_commands.manage_config.show_about", &[]))]
+    Show {
+        #[clap(long, value_enum, ignore_case = true, default_value = "pretty", help = tr("cli.cli_commands.manage_config.format_help", &[]))]
+        format: ConfigFormat,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.edit_about", &[]))]
     Edit {
-        #[clap(short, long, help = Lingua::t("cli.cli_commands.manage_config.edit_language_help", &[]).unwrap())]
+        #[clap(short, long, help = tr("cli.cli_commands.manage_config.edit_language_help", &[]))]
         lang: Option<String>,
-        #[clap(short, long, help = Lingua::t("cli.cli_commands.manage_config.edit_default_length_help", &[]).unwrap())]
+        #[clap(short, long, help = tr("cli.cli_commands.manage_config.edit_default_length_help", &[]))]
         password_length: Option<usize>,
-        #[clap(short, long, help = Lingua::t("cli.cli_commands.manage_config.edit_default_count_help", &[]).unwrap())]
+        #[clap(short, long, help = tr("cli.cli_commands.manage_config.edit_default_count_help", &[]))]
         count: Option<usize>,
-        #[clap(short, long, help = Lingua::t("cli.cli_commands.manage_config.edit_auto_save_help", &[]).unwrap())]
+        #[clap(short, long, help = tr("cli.cli_commands.manage_config.edit_auto_save_help", &[]))]
         auto_save: Option<bool>,
+        #[clap(long, help = tr("cli.cli_commands.manage_config.edit_default_mode_help", &[]))]
+        default_mode: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.manage_config.edit_default_pattern_help", &[]))]
+        default_pattern: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.manage_config.edit_default_words_help", &[]))]
+        default_words: Option<usize>,
+        #[clap(long, help = tr("cli.cli_commands.manage_config.edit_output_dir_help", &[]))]
+        output_dir: Option<String>,
+        #[clap(long, help = tr("cli.cli_commands.manage_config.edit_output_template_help", &[]))]
+        output_template: Option<String>,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.reset_about", &[]))]
+    Reset {
+        #[clap(short, long, help = tr("cli.cli_commands.manage_config.reset_yes_help", &[]))]
+        yes: bool,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.path_about", &[]))]
+    Path,
+    #[command(about = tr("cli.cli_commands.manage_config.export_about", &[]))]
+    Export {
+        #[clap(help = tr("cli.cli_commands.manage_config.export_path_help", &[]))]
+        path: Option<String>,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.import_about", &[]))]
+    Import {
+        #[clap(help = tr("cli.cli_commands.manage_config.import_path_help", &[]))]
+        path: String,
+        #[clap(short, long, help = tr("cli.cli_commands.manage_config.import_yes_help", &[]))]
+        yes: bool,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.get_about", &[]))]
+    Get {
+        #[clap(help = tr("cli.cli_commands.manage_config.key_help", &[]))]
+        key: String,
+    },
+    #[command(about = tr("cli.cli_commands.manage_config.set_about", &[]))]
+    Set {
+        #[clap(help = tr("cli.cli_commands.manage_config.key_help", &[]))]
+        key: String,
+        #[clap(help = tr("cli.cli_commands.manage_config.value_help", &[]), allow_hyphen_values = true)]
+        value: String,
     },
 }
 
 pub mod prelude {
     pub use super::CONFIG;
-    pub use crate::config::Config;
-    pub use crate::logging::LoggingManager;
-    pub use crate::password::{Generator, HealthCheck};
-    pub use crate::tui::{App, run, ui};
-    pub use crate::uninstall::UninstallManager;
-    pub use crate::update::UpdateManager;
-    pub use crate::{Cli, Commands, ConfigCommands, PasswordMode, parse_password_mode};
+    pub use crate::config::{Config, ConfigFormat, ConfigUpdate, DeterministicConfig};
+    pub use crate::doctor::{CheckOutcome, DiagnosticCheck, DoctorManager};
+    pub use crate::errors::{ConfigError, GeneratorError, KdguardError};
+    pub use crate::i18n::{load_embedded_languages, tr};
+    pub use crate::logging::{LogFormat, LoggingManager, LoggingSettings};
+    pub use crate::password::{
+        BackupSheet, BreachDatabase, CommonPasswords, DictionaryProvider, ExpiryReport,
+        GeneratedPassword, GenerationMetadata, Generator, HealthCheck, InsertStyle, Kdf,
+        PasswordAnalysis, PasswordRequest, PhraseOptions, Policy, ReuseReport, ScoringWeights,
+        Suggestion, Warning,
+    };
+    pub use crate::progress::{
+        BulkOutcome, Cancellation, NoProgress, Progress, ProgressReporter, StderrProgress,
+    };
+    pub use crate::tui::{App, UpdateStatus, run, ui};
+    pub use crate::uninstall::{
+        UninstallItem, UninstallManager, UninstallOptions, UninstallPaths, UninstallStep,
+    };
+    pub use crate::update::{LONG_VERSION, UpdateManager, UpdateOptions, VersionInfo};
+    pub use crate::{
+        BreachdbCommands, Cli, Commands, ConfigCommands, GenerateArgs, PasswordMode,
+        PatternsCommands, parse_password_mode,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password::health_check::tests::init_lingua_for_tests;
+
+    fn parse(args: &[&str]) -> Cli {
+        let matches = Cli::build_command()
+            .try_get_matches_from(args)
+            .unwrap_or_else(|e| panic!("{}", e));
+        <Cli as clap::FromArgMatches>::from_arg_matches(&matches).unwrap()
+    }
+
+    #[test]
+    fn test_generate_command_matches_root_options() {
+        init_lingua_for_tests();
+        let options = [
+            "-m",
+            "deterministic",
+            "-l",
+            "24",
+            "-c",
+            "3",
+            "--seed-env",
+            "SEED",
+            "--service",
+            "github",
+            "--salt",
+            "pepper",
+            "-s",
+            "-o",
+            "out.txt",
+        ];
+
+        let root = parse(&[&["kdguard"], &options[..]].concat());
+        let generate = parse(&[&["kdguard", "generate"], &options[..]].concat());
+        assert!(root.commands.is_none());
+        match generate.commands {
+            Some(Commands::Generate(args)) => assert!(args == root.generate),
+            _ => panic!("expected the generate command"),
+        }
+        assert_eq!(root.generate.length, 24);
+        assert_eq!(root.generate.output.as_deref(), Some("out.txt"));
+    }
+
+    #[test]
+    fn test_root_help_hides_generation_options() {
+        init_lingua_for_tests();
+        let mut command = Cli::build_command();
+        let help = command.render_help().to_string();
+        assert!(!help.contains("--length"), "{}", help);
+        assert!(help.contains("--profile"), "{}", help);
+
+        let help = command
+            .find_subcommand_mut("generate")
+            .unwrap()
+            .render_help()
+            .to_string();
+        assert!(help.contains("--length"), "{}", help);
+    }
 }
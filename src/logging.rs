@@ -1,78 +1,484 @@
 use std::{
     env::consts::{ARCH, OS},
+    fmt,
     fs::{self, File},
-    path::PathBuf,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
-use chrono::Local;
-use log::LevelFilter;
-use simplelog::{CombinedLogger, Config, WriteLogger};
+use chrono::{Local, SecondsFormat};
+use clap::ValueEnum;
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use simplelog::{CombinedLogger, Config, SharedLogger, WriteLogger};
 use sysinfo::System;
 
 use crate::errors::LoggingError;
 
 const MAX_LOG_FILES: usize = 10;
 
+/// File in the log directory holding the hostname, CPU, and memory of the machine
+const SYSTEM_INFO_FILE: &str = "system_info.log";
+
+/// Base directories of the `kdguard/logs` directory, in the order they are tried
+const LOG_BASE_DIRS: [fn() -> Option<PathBuf>; 4] = [
+    dirs::state_dir,
+    dirs::data_local_dir,
+    dirs::cache_dir,
+    || Some(std::env::temp_dir()),
+];
+
+/// Size a log file grows to before a new one is started, `logging.max_log_size_kb`
+pub const DEFAULT_MAX_LOG_SIZE_KB: u64 = 512;
+
+/// Environment variable that sets the log level
+pub const LOG_LEVEL_ENV: &str = "KDGUARD_LOG";
+
+/// The log levels accepted by `--log-level` and `KDGUARD_LOG`
+pub const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// How the log file writes its messages, `--log-format` or `logging.log_format`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// The text lines of simplelog
+    #[default]
+    Text,
+    /// One JSON object per line, for log collectors
+    Json,
+}
+
+/// Whether `Redactable` values are logged in full, only set by `--log-level trace`
+static REVEAL: AtomicBool = AtomicBool::new(false);
+
+/// A user-controlled value in a log message
+///
+/// Patterns, paths, service names and variable names are logged as their length. They
+/// are only written in full while trace messages are logged and `--log-level trace`
+/// was given, `-vvv` and `KDGUARD_LOG` keep them redacted.
+pub struct Redactable<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Redactable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if REVEAL.load(Ordering::Relaxed) && log::log_enabled!(log::Level::Trace) {
+            write!(f, "{}", self.0)
+        } else {
+            write!(
+                f,
+                "<redacted, {} chars>",
+                self.0.to_string().chars().count()
+            )
+        }
+    }
+}
+
+/// Where log messages go and from which level on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoggingSettings {
+    /// Level of the messages written to the log file and the console
+    pub level: LevelFilter,
+    /// Also print the messages to stderr
+    pub console: bool,
+    /// Write a log file
+    pub log_file: bool,
+    /// Log `Redactable` values in full
+    pub reveal: bool,
+    /// Size in bytes after which a new log file is started
+    pub max_file_size: u64,
+    /// Format of the log file, the console always gets text
+    pub format: LogFormat,
+    /// Write the hostname, CPU, and memory to `system_info.log`
+    pub system_info: bool,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Info,
+            console: false,
+            log_file: true,
+            reveal: false,
+            max_file_size: DEFAULT_MAX_LOG_SIZE_KB * 1024,
+            format: LogFormat::Text,
+            system_info: true,
+        }
+    }
+}
+
+impl LoggingSettings {
+    /// Build the settings from the command line and `KDGUARD_LOG`
+    ///
+    /// `--log-level` wins over `KDGUARD_LOG`, which wins over the level of `-v`. Only
+    /// `--log-level trace` logs user-controlled values in full.
+    ///
+    /// # Arguments
+    ///
+    /// * `verbose`: How often `-v` was given, once prints the log to stderr, twice
+    ///   adds debug messages, three times trace messages
+    /// * `log_level`: The level given with `--log-level`
+    /// * `no_log_file`: Whether `--no-log-file` was given
+    ///
+    /// # Returns
+    ///
+    /// Returns the settings
+    pub fn from_args(verbose: u8, log_level: Option<&str>, no_log_file: bool) -> Self {
+        let env_level = std::env::var(LOG_LEVEL_ENV).ok();
+        let level = log_level
+            .or(env_level.as_deref())
+            .and_then(|level| LevelFilter::from_str(level).ok())
+            .unwrap_or(match verbose {
+                0 | 1 => LevelFilter::Info,
+                2 => LevelFilter::Debug,
+                _ => LevelFilter::Trace,
+            });
+
+        Self {
+            level,
+            console: verbose > 0,
+            log_file: !no_log_file,
+            reveal: log_level.is_some_and(|level| level.eq_ignore_ascii_case("trace")),
+            ..Self::default()
+        }
+    }
+}
+
+/// Log file that continues in a new file once it grew past a size
+///
+/// A new file is only started between two lines, so a message never spans two files.
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    max_size: u64,
+    written: u64,
+    at_line_start: bool,
+}
+
+impl RotatingFile {
+    /// Create the first log file in a directory
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: The log directory
+    /// * `max_size`: Size in bytes after which a new file is started
+    ///
+    /// # Returns
+    ///
+    /// Returns the writer, otherwise an error if the file cannot be created
+    fn create(dir: PathBuf, max_size: u64) -> io::Result<Self> {
+        let file = File::create(LoggingManager::new_log_path(&dir))?;
+        Ok(Self {
+            dir,
+            file,
+            max_size,
+            written: 0,
+            at_line_start: true,
+        })
+    }
+
+    /// Continue in a new file and remove the oldest ones
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file = File::create(LoggingManager::new_log_path(&self.dir))?;
+        self.written = 0;
+        LoggingManager::cleanup_old_logs(&self.dir, MAX_LOG_FILES).map_err(io::Error::other)
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.at_line_start && self.written >= self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        if written > 0 {
+            self.at_line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// A log message as written by `JsonLogger`
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    ts: String,
+    level: &'a str,
+    target: &'a str,
+    msg: String,
+}
+
+/// Logger writing one JSON object per message
+struct JsonLogger {
+    level: LevelFilter,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLogger {
+    fn new(level: LevelFilter, writer: Box<dyn Write + Send>) -> Box<Self> {
+        Box::new(Self {
+            level,
+            writer: Mutex::new(writer),
+        })
+    }
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let record = JsonRecord {
+            ts: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            level: record.level().as_str(),
+            target: record.target(),
+            msg: record.args().to_string(),
+        };
+        let Ok(mut line) = serde_json::to_string(&record) else {
+            return;
+        };
+        line.push('\n');
+        // A failed write cannot be logged either
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl SharedLogger for JsonLogger {
+    fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    fn config(&self) -> Option<&Config> {
+        None
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        Box::new(*self)
+    }
+}
+
 pub struct LoggingManager;
 
 impl LoggingManager {
     /// Initialize Logging
     ///
+    /// # Arguments
+    ///
+    /// * `settings`: The level and the destinations of the log messages
+    ///
     /// # Returns
     ///
-    /// Returns Ok(()) if successful, otherwise an error
-    pub fn init() -> Result<(), LoggingError> {
+    /// Returns Ok(()) if successful, otherwise an error. Without a log file the console
+    /// output is still set up, without any logger the messages are dropped
+    pub fn init(settings: &LoggingSettings) -> Result<(), LoggingError> {
+        REVEAL.store(settings.reveal, Ordering::Relaxed);
+        let (log_file, file_error) = if settings.log_file {
+            match Self::create_log_file(settings) {
+                Ok(file) => (Some(file), None),
+                Err(e) => (None, Some(e)),
+            }
+        } else {
+            (None, None)
+        };
+        let loggers = Self::loggers(
+            settings,
+            log_file.map(|file| Box::new(file) as Box<dyn Write + Send>),
+            Box::new(std::io::stderr()),
+        );
+        if !loggers.is_empty() {
+            CombinedLogger::init(loggers).map_err(|e| LoggingError::Initialize(e.to_string()))?;
+        }
+
+        file_error.map_or(Ok(()), Err)
+    }
+
+    /// Create the log file of this run and remove the oldest ones
+    ///
+    /// # Arguments
+    ///
+    /// * `settings`: The size limit of a file and whether to write the system information
+    ///
+    /// # Returns
+    ///
+    /// Returns the log file if successful, otherwise an error
+    fn create_log_file(settings: &LoggingSettings) -> Result<RotatingFile, LoggingError> {
         let logging_path = Self::get_logging_path()?;
 
-        let system_info_path = logging_path.join("system_info.log");
+        Self::update_system_info(&logging_path, settings.system_info)?;
+
+        let log_file = RotatingFile::create(logging_path.clone(), settings.max_file_size)
+            .map_err(|e| LoggingError::CreateFile(e.to_string()))?;
+
+        Self::cleanup_old_logs(&logging_path, MAX_LOG_FILES)?;
+
+        Ok(log_file)
+    }
+
+    /// Write the system information once, or delete it if it must not be collected
+    ///
+    /// # Arguments
+    ///
+    /// * `logging_path`: The path to the logging directory
+    /// * `collect`: Whether the system information may be collected
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise an error
+    fn update_system_info(logging_path: &Path, collect: bool) -> Result<(), LoggingError> {
+        let system_info_path = logging_path.join(SYSTEM_INFO_FILE);
+        if !collect {
+            return match fs::remove_file(&system_info_path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    Err(LoggingError::WriteSystemInfo(e.to_string()))
+                }
+                _ => Ok(()),
+            };
+        }
         if !system_info_path.exists() {
             let system_info = Self::collect_system_info()?;
             fs::write(&system_info_path, system_info)
                 .map_err(|e| LoggingError::WriteSystemInfo(e.to_string()))?;
         }
+        Ok(())
+    }
 
+    /// Get a path for a new log file, named after the current time
+    ///
+    /// A counter is added if a file of the same second exists, so no log is overwritten.
+    fn new_log_path(dir: &Path) -> PathBuf {
         let datetime = Local::now().format("%Y-%m-%dT%H-%M-%S");
-        let log_file_name = format!("kdguard_{}.log", datetime);
-        let log_file_path = logging_path.join(&log_file_name);
-
-        let log_file =
-            File::create(&log_file_path).map_err(|e| LoggingError::CreateFile(e.to_string()))?;
-
-        CombinedLogger::init(vec![WriteLogger::new(
-            LevelFilter::Info,
-            Config::default(),
-            log_file,
-        )])
-        .map_err(|e| LoggingError::Initialize(e.to_string()))?;
-
-        Self::cleanup_old_logs(&logging_path, MAX_LOG_FILES)?;
+        let mut path = dir.join(format!("kdguard_{}.log", datetime));
+        let mut counter = 1;
+        while path.exists() {
+            path = dir.join(format!("kdguard_{}_{}.log", datetime, counter));
+            counter += 1;
+        }
+        path
+    }
 
-        Ok(())
+    /// Build the loggers for the settings
+    ///
+    /// # Arguments
+    ///
+    /// * `settings`: The level and the destinations of the log messages
+    /// * `file`: The log file, None to write no file
+    /// * `console`: The console output, used if the settings enable it
+    ///
+    /// # Returns
+    ///
+    /// Returns one logger per destination
+    fn loggers(
+        settings: &LoggingSettings,
+        file: Option<Box<dyn Write + Send>>,
+        console: Box<dyn Write + Send>,
+    ) -> Vec<Box<dyn SharedLogger>> {
+        let mut loggers: Vec<Box<dyn SharedLogger>> = Vec::new();
+        if let Some(file) = file {
+            loggers.push(match settings.format {
+                LogFormat::Text => WriteLogger::new(settings.level, Config::default(), file),
+                LogFormat::Json => JsonLogger::new(settings.level, file),
+            });
+        }
+        if settings.console {
+            loggers.push(WriteLogger::new(settings.level, Config::default(), console));
+        }
+        loggers
     }
 
     /// Get the logging path
     ///
+    /// Logs go to the `logs` directory of an overridden config directory. Otherwise the
+    /// first writable of the state, local data, cache, and temp directories is used,
+    /// macOS and Windows have no state directory.
+    ///
     /// # Returns
     ///
     /// Returns the logging path if successful, otherwise an error
-    fn get_logging_path() -> Result<PathBuf, LoggingError> {
-        if OS == "Windows" || OS == "Darwin" {
-            let local_app_data = dirs::data_local_dir().ok_or(LoggingError::GetDirectory(
-                "Failed to get logging directory".to_string(),
-            ))?;
-            let logging_dir = local_app_data.join("kdguard").join("logs");
+    pub fn get_logging_path() -> Result<PathBuf, LoggingError> {
+        if let Some(config_dir) = crate::config::Config::config_dir_override() {
+            let logging_dir = config_dir.join("logs");
             fs::create_dir_all(&logging_dir)
                 .map_err(|e| LoggingError::CreateDirectory(e.to_string()))?;
             Ok(logging_dir)
         } else {
-            let log_dir = dirs::state_dir().ok_or(LoggingError::GetDirectory(
-                "Failed to get logging directory".to_string(),
-            ))?;
-            let logging_dir = log_dir.join("kdguard").join("logs");
-            fs::create_dir_all(&logging_dir)
-                .map_err(|e| LoggingError::CreateDirectory(e.to_string()))?;
-            Ok(logging_dir)
+            Self::first_writable_dir(&LOG_BASE_DIRS)
+        }
+    }
+
+    /// Find the log directory without creating it
+    ///
+    /// # Returns
+    ///
+    /// Returns the directory `get_logging_path` would use if it exists already, None if
+    /// no log directory was created yet
+    pub fn existing_logging_path() -> Option<PathBuf> {
+        match crate::config::Config::config_dir_override() {
+            Some(config_dir) => Some(config_dir.join("logs")),
+            None => Self::first_existing_dir(&LOG_BASE_DIRS),
         }
+        .filter(|logging_dir| logging_dir.is_dir())
+    }
+
+    /// Get the first `kdguard/logs` directory that exists in one of the base directories
+    ///
+    /// # Arguments
+    ///
+    /// * `providers`: The base directories in the order they are tried
+    ///
+    /// # Returns
+    ///
+    /// Returns the logging path, None if no base directory holds one
+    fn first_existing_dir(providers: &[fn() -> Option<PathBuf>]) -> Option<PathBuf> {
+        providers
+            .iter()
+            .filter_map(|provider| provider())
+            .map(|base| base.join("kdguard").join("logs"))
+            .find(|logging_dir| logging_dir.is_dir())
+    }
+
+    /// Get the `kdguard/logs` directory in the first base directory that can hold it
+    ///
+    /// # Arguments
+    ///
+    /// * `providers`: The base directories in the order they are tried, None if a
+    ///   platform has no such directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the created logging path, otherwise an error naming the last failure
+    fn first_writable_dir(providers: &[fn() -> Option<PathBuf>]) -> Result<PathBuf, LoggingError> {
+        let mut error = LoggingError::GetDirectory("Failed to get logging directory".to_string());
+        for base in providers.iter().filter_map(|provider| provider()) {
+            let logging_dir = base.join("kdguard").join("logs");
+            match fs::create_dir_all(&logging_dir) {
+                Ok(()) => return Ok(logging_dir),
+                Err(e) => {
+                    error =
+                        LoggingError::CreateDirectory(format!("{}: {}", logging_dir.display(), e))
+                }
+            }
+        }
+        Err(error)
     }
 
     /// Collect system information
@@ -132,9 +538,28 @@ impl LoggingManager {
     /// # Returns
     ///
     /// Returns Ok(()) if successful, otherwise an error
-    fn cleanup_old_logs(logging_path: &PathBuf, max_count: usize) -> Result<(), LoggingError> {
-        let mut log_files: Vec<_> = fs::read_dir(logging_path)
-            .map_err(|e| LoggingError::Cleanup(e.to_string()))?
+    fn cleanup_old_logs(logging_path: &Path, max_count: usize) -> Result<(), LoggingError> {
+        let log_files =
+            Self::log_files(logging_path).map_err(|e| LoggingError::Cleanup(e.to_string()))?;
+        for path in log_files.iter().skip(max_count) {
+            fs::remove_file(path).map_err(|e| LoggingError::Cleanup(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// List the log files of a directory
+    ///
+    /// # Arguments
+    ///
+    /// * `logging_path`: The path to the logging directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the log files, the newest first, otherwise an error if the directory
+    /// cannot be read
+    fn log_files(logging_path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut log_files: Vec<_> = fs::read_dir(logging_path)?
             .filter_map(|entry| {
                 let entry = entry.ok()?;
                 let path = entry.path();
@@ -146,22 +571,74 @@ impl LoggingManager {
                         .unwrap_or(false)
                 {
                     let metadata = fs::metadata(&path).ok()?;
-                    Some((path, metadata.modified().ok()?))
+                    Some((metadata.modified().ok()?, path))
                 } else {
                     None
                 }
             })
             .collect();
 
-        log_files.sort_by(|a, b| b.1.cmp(&a.1));
+        // Files rotated within the same second only differ by their counter
+        log_files.sort_by(|a, b| b.cmp(a));
+        Ok(log_files.into_iter().map(|(_, path)| path).collect())
+    }
 
-        if log_files.len() > max_count {
-            for (path, _) in log_files.iter().skip(max_count) {
-                fs::remove_file(path).map_err(|e| LoggingError::Cleanup(e.to_string()))?;
-            }
+    /// Get the newest log file
+    ///
+    /// # Arguments
+    ///
+    /// * `logging_path`: The path to the logging directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the log file, None if there is none, otherwise an error
+    pub fn newest_log(logging_path: &Path) -> Result<Option<PathBuf>, LoggingError> {
+        let log_files =
+            Self::log_files(logging_path).map_err(|e| LoggingError::Read(e.to_string()))?;
+        Ok(log_files.into_iter().next())
+    }
+
+    /// Read the last lines of a log file
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The log file
+    /// * `count`: The number of lines
+    ///
+    /// # Returns
+    ///
+    /// Returns up to `count` lines in file order, otherwise an error
+    pub fn tail(path: &Path, count: usize) -> Result<Vec<String>, LoggingError> {
+        let content = fs::read(path).map_err(|e| LoggingError::Read(e.to_string()))?;
+        let content = String::from_utf8_lossy(&content);
+        let lines: Vec<&str> = content.lines().collect();
+        Ok(lines[lines.len().saturating_sub(count)..]
+            .iter()
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    /// Delete every log file and the system information
+    ///
+    /// # Arguments
+    ///
+    /// * `logging_path`: The path to the logging directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of deleted files, otherwise an error
+    pub fn clear_logs(logging_path: &Path) -> Result<usize, LoggingError> {
+        let mut files =
+            Self::log_files(logging_path).map_err(|e| LoggingError::Cleanup(e.to_string()))?;
+        let system_info_path = logging_path.join(SYSTEM_INFO_FILE);
+        if system_info_path.exists() {
+            files.push(system_info_path);
+        }
+        for path in &files {
+            fs::remove_file(path).map_err(|e| LoggingError::Cleanup(e.to_string()))?;
         }
 
-        Ok(())
+        Ok(files.len())
     }
 
     /// Log an info message
@@ -182,6 +659,15 @@ impl LoggingManager {
         log::debug!("{}", msg);
     }
 
+    /// Log a trace message
+    ///
+    /// # Arguments
+    ///
+    /// * `msg`: The message to log
+    pub fn trace(msg: &str) {
+        log::trace!("{}", msg);
+    }
+
     /// Log a warning message
     ///
     /// # Arguments
@@ -200,3 +686,299 @@ impl LoggingManager {
         log::error!("{}", msg);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use log::{Level, Log, Record};
+
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// Writer keeping everything written to it
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn log_every_level(loggers: &[Box<dyn SharedLogger>]) {
+        for level in [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ] {
+            for logger in loggers {
+                logger.log(
+                    &Record::builder()
+                        .level(level)
+                        .args(format_args!("{} message", level))
+                        .build(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_loggers_filter_by_level() {
+        let (file, console) = (Captured::default(), Captured::default());
+        let settings = LoggingSettings {
+            level: LevelFilter::Warn,
+            console: true,
+            log_file: true,
+            ..LoggingSettings::default()
+        };
+        let loggers = LoggingManager::loggers(
+            &settings,
+            Some(Box::new(file.clone())),
+            Box::new(console.clone()),
+        );
+        log_every_level(&loggers);
+
+        for output in [file.text(), console.text()] {
+            assert!(output.contains("ERROR message"), "{}", output);
+            assert!(output.contains("WARN message"), "{}", output);
+            assert!(!output.contains("INFO message"), "{}", output);
+        }
+    }
+
+    #[test]
+    fn test_json_format_writes_one_object_per_line() {
+        let (file, console) = (Captured::default(), Captured::default());
+        let settings = LoggingSettings {
+            level: LevelFilter::Debug,
+            console: true,
+            format: LogFormat::Json,
+            ..LoggingSettings::default()
+        };
+        let loggers = LoggingManager::loggers(
+            &settings,
+            Some(Box::new(file.clone())),
+            Box::new(console.clone()),
+        );
+        log_every_level(&loggers);
+
+        let lines: Vec<serde_json::Value> = file
+            .text()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["level"], "ERROR");
+        assert_eq!(lines[3]["msg"], "DEBUG message");
+        for line in &lines {
+            for key in ["ts", "level", "target", "msg"] {
+                assert!(line.get(key).is_some(), "{}", line);
+            }
+        }
+        assert!(file.text().starts_with("{\"ts\":"), "{}", file.text());
+        // The console stays readable
+        assert!(!console.text().contains('{'), "{}", console.text());
+    }
+
+    #[test]
+    fn test_console_only_when_verbose() {
+        let console = Captured::default();
+        let loggers = LoggingManager::loggers(
+            &LoggingSettings::from_args(0, Some("trace"), true),
+            None,
+            Box::new(console.clone()),
+        );
+        assert!(loggers.is_empty());
+
+        let loggers = LoggingManager::loggers(
+            &LoggingSettings::from_args(2, None, true),
+            None,
+            Box::new(console.clone()),
+        );
+        log_every_level(&loggers);
+        assert!(console.text().contains("DEBUG message"));
+        assert!(!console.text().contains("TRACE message"));
+    }
+
+    #[test]
+    fn test_settings_from_args() {
+        let settings = LoggingSettings::from_args(0, None, false);
+        assert_eq!(settings, LoggingSettings::default());
+
+        let settings = LoggingSettings::from_args(1, None, true);
+        assert_eq!(settings.level, LevelFilter::Info);
+        assert!(settings.console && !settings.log_file);
+        assert_eq!(
+            LoggingSettings::from_args(3, None, false).level,
+            LevelFilter::Trace
+        );
+
+        // The explicit level wins over -v
+        assert_eq!(
+            LoggingSettings::from_args(2, Some("error"), false).level,
+            LevelFilter::Error
+        );
+        assert_eq!(
+            LoggingSettings::from_args(0, Some("OFF"), false).level,
+            LevelFilter::Off
+        );
+
+        // User-controlled values are only revealed on request
+        assert!(LoggingSettings::from_args(0, Some("TRACE"), false).reveal);
+        assert!(!LoggingSettings::from_args(3, None, false).reveal);
+        assert!(!LoggingSettings::from_args(3, Some("debug"), false).reveal);
+    }
+
+    #[test]
+    fn test_rotating_file_starts_new_files() {
+        let logs = TempDir::new("rotate");
+        let mut file = RotatingFile::create(logs.0.clone(), 100).unwrap();
+        for line in 0..20 {
+            // Loggers write a message in several parts
+            file.write_all(format!("line {:02} ", line).as_bytes())
+                .unwrap();
+            file.write_all(b"of the test log\n").unwrap();
+        }
+        file.flush().unwrap();
+
+        let files = LoggingManager::log_files(&logs.0).unwrap();
+        assert!(files.len() > 1, "{:?}", files);
+        let mut lines = Vec::new();
+        for path in files.iter().rev() {
+            let content = fs::read_to_string(path).unwrap();
+            // A file only grows past the limit by its last line
+            assert!(content.len() < 100 + 24, "{}", content);
+            assert!(content.ends_with('\n'), "{}", content);
+            lines.extend(content.lines().map(str::to_string));
+        }
+        let expected: Vec<String> = (0..20)
+            .map(|line| format!("line {:02} of the test log", line))
+            .collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn test_rotation_keeps_the_file_cap() {
+        let logs = TempDir::new("rotate-cap");
+        let mut file = RotatingFile::create(logs.0.clone(), 1).unwrap();
+        for _ in 0..MAX_LOG_FILES + 5 {
+            file.write_all(b"full\n").unwrap();
+        }
+        assert_eq!(
+            LoggingManager::log_files(&logs.0).unwrap().len(),
+            MAX_LOG_FILES
+        );
+    }
+
+    #[test]
+    fn test_tail_and_clear_logs() {
+        let logs = TempDir::new("tail");
+        assert_eq!(LoggingManager::newest_log(&logs.0).unwrap(), None);
+
+        let older = logs.0.join("kdguard_2024-01-01T00-00-00.log");
+        fs::write(&older, "old\n").unwrap();
+        let newest = logs.0.join("kdguard_2024-01-01T00-00-00_1.log");
+        fs::write(&newest, "one\ntwo\nthree\n").unwrap();
+        fs::write(logs.0.join("system_info.log"), "info").unwrap();
+        fs::write(logs.0.join("notes.txt"), "kept").unwrap();
+        // Same second, the counter decides
+        let time = fs::metadata(&older).unwrap().modified().unwrap();
+        File::options()
+            .write(true)
+            .open(&newest)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+
+        assert_eq!(
+            LoggingManager::newest_log(&logs.0).unwrap(),
+            Some(newest.clone())
+        );
+        assert_eq!(LoggingManager::tail(&newest, 2).unwrap(), ["two", "three"]);
+        assert_eq!(LoggingManager::tail(&newest, 10).unwrap().len(), 3);
+        assert!(LoggingManager::tail(&newest, 0).unwrap().is_empty());
+
+        assert_eq!(LoggingManager::clear_logs(&logs.0).unwrap(), 3);
+        assert_eq!(LoggingManager::newest_log(&logs.0).unwrap(), None);
+        assert!(logs.0.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_system_info_is_not_collected_when_disabled() {
+        let logs = TempDir::new("system-info");
+        let path = logs.0.join(SYSTEM_INFO_FILE);
+
+        LoggingManager::update_system_info(&logs.0, false).unwrap();
+        assert!(!path.exists());
+
+        // A file from an earlier run is kept while enabled and deleted once disabled
+        fs::write(&path, "Hostname: earlier").unwrap();
+        LoggingManager::update_system_info(&logs.0, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Hostname: earlier");
+        LoggingManager::update_system_info(&logs.0, false).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_logging_path_falls_back_in_order() {
+        // Providers are plain functions, so the directories live in fixed places
+        fn missing() -> Option<PathBuf> {
+            None
+        }
+        fn unwritable() -> Option<PathBuf> {
+            // A file where a directory is needed
+            Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        }
+        fn first() -> Option<PathBuf> {
+            Some(std::env::temp_dir().join(format!("kdguard-logs-first-{}", std::process::id())))
+        }
+        fn second() -> Option<PathBuf> {
+            Some(std::env::temp_dir().join(format!("kdguard-logs-second-{}", std::process::id())))
+        }
+
+        let path =
+            LoggingManager::first_writable_dir(&[missing, unwritable, first, second]).unwrap();
+        assert_eq!(path, first().unwrap().join("kdguard").join("logs"));
+        assert!(!second().unwrap().exists());
+        assert_eq!(
+            LoggingManager::first_existing_dir(&[missing, unwritable, second, first]),
+            Some(path)
+        );
+        assert_eq!(LoggingManager::first_existing_dir(&[second]), None);
+        let _ = fs::remove_dir_all(first().unwrap());
+
+        assert!(matches!(
+            LoggingManager::first_writable_dir(&[missing, unwritable]),
+            Err(LoggingError::CreateDirectory(_))
+        ));
+        assert!(matches!(
+            LoggingManager::first_writable_dir(&[missing]),
+            Err(LoggingError::GetDirectory(_))
+        ));
+    }
+
+    #[test]
+    fn test_redactable_hides_the_value() {
+        // No logger is installed in unit tests, so values are never revealed
+        let message = format!("Pattern: {}", Redactable("ULLDäS"));
+        assert_eq!(message, "Pattern: <redacted, 6 chars>");
+        let path = std::path::Path::new("/home/someone/out.txt");
+        assert_eq!(
+            Redactable(path.display()).to_string(),
+            "<redacted, 21 chars>"
+        );
+    }
+}
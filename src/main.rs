@@ -1,57 +1,942 @@
-use clap::Parser;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use clap::{FromArgMatches, ValueEnum};
+use inquire::Confirm;
 use kdguard::prelude::*;
 use lingua_i18n_rs::prelude::Lingua;
 
+/// Exit code used when a checked password violates the selected policy
+const POLICY_VIOLATION_EXIT_CODE: i32 = 2;
+/// Exit code used when `expire-check` found expired passwords
+const EXPIRED_EXIT_CODE: i32 = 2;
+/// Exit code used when a bulk run was interrupted with Ctrl-C
+const CANCELLED_EXIT_CODE: i32 = 130;
+
+/// How often `-v` was given, decides how much of an error is shown
+static VERBOSE: AtomicU8 = AtomicU8::new(0);
+
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging first
-    LoggingManager::init()?;
+async fn main() {
+    if let Err(e) = run().await {
+        LoggingManager::error(&e.to_string());
+        eprintln!("{}", e.render(VERBOSE.load(Ordering::Relaxed)));
+        std::process::exit(e.exit_code());
+    }
+}
+
+async fn run() -> Result<(), KdguardError> {
+    // The config directory is needed by the logging, the languages, and the CONFIG static,
+    // so `--config` and `--profile` are read before clap parses the arguments
+    if let Some(dir) = global_arg(std::env::args_os(), "--config") {
+        Config::set_config_dir(PathBuf::from(dir));
+    }
+    if let Some(name) = global_arg(std::env::args_os(), "--profile") {
+        Config::set_profile(name.to_string_lossy().to_string());
+    }
+    // A dry run lists the config, languages, and logs it would remove, so it must not
+    // create them on the way
+    if is_uninstall_dry_run(std::env::args_os()) {
+        Config::set_read_only();
+    }
 
-    // Ensure config directory and file exist (CONFIG lazy_static needs this)
+    // Ensure config directory and file exist. A config that cannot be loaded is reported
+    // once the CLI is parsed and CONFIG falls back to the built-in defaults
+    let config_error = Config::load_active().err();
     let _ = &CONFIG;
 
-    // Initialize languages first (before CLI parsing)
-    let languages_path = Config::get_languages_path()?;
-    Lingua::new(languages_path.to_str().ok_or("Invalid languages path")?).init()?;
+    // Initialize languages first (before CLI parsing). If the config directory cannot be
+    // written, the embedded languages are loaded from the binary instead. Without
+    // readable language files the built-in English texts are shown
+    match Config::get_languages_path() {
+        Ok(path) => {
+            if let Some(path) = path.to_str() {
+                let _ = Lingua::new(path).init();
+            }
+        }
+        Err(_) => load_embedded_languages(),
+    }
 
     // Load language from config
     let config_path = Config::get_config_path()?;
     let lang =
         Lingua::load_lang_from_config(&config_path, "lang").unwrap_or_else(|_| "en".to_string());
-    Lingua::set_language(&lang)?;
-
-    // Check for update
-    UpdateManager::check_update().await?;
+    if Lingua::set_language(&lang).is_err() {
+        let _ = Lingua::set_language("en");
+    }
 
     // Parse CLI
-    let cli = Cli::parse();
+    let matches = Cli::build_command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    VERBOSE.store(cli.verbose, Ordering::Relaxed);
+
+    if cli.version {
+        if cli.json {
+            println!("{}", VersionInfo::current().to_json());
+        } else {
+            println!("kdguard {}", LONG_VERSION);
+        }
+        return Ok(());
+    }
+
+    // Completions only need the command definition, not the config, logs, or updates
+    if let Some(Commands::Completions { shell }) = cli.commands {
+        clap_complete::generate(
+            shell,
+            &mut Cli::build_command(),
+            "kdguard",
+            &mut std::io::stdout(),
+        );
+        return Ok(());
+    }
+
+    // Logging is set up by the parsed flags, so loading the config and the languages is
+    // not logged. Without a writable log directory kdguard still runs
+    let tui = cli.commands.is_none() && !Cli::has_generation_args(&matches);
+    let mut logging =
+        LoggingSettings::from_args(cli.verbose, cli.log_level.as_deref(), cli.no_log_file);
+    // Console output would draw over the TUI
+    logging.console &= !tui;
+    // A log of its own would become the newest log `kdguard logs --tail` shows
+    logging.log_file &= !matches!(cli.commands, Some(Commands::Logs { .. }));
+    logging.log_file &= !Config::is_read_only();
+    logging.max_file_size = CONFIG.logging.max_log_size_kb.saturating_mul(1024);
+    logging.format = cli.log_format.unwrap_or(CONFIG.logging.log_format);
+    logging.system_info = !cli.no_system_info && CONFIG.logging.collect_system_info;
+    if let Err(e) = LoggingManager::init(&logging) {
+        eprintln!(
+            "\x1b[1;33m{}\x1b[0m",
+            tr("config.load.logging", &[("error", &e.to_string())])
+        );
+    }
+    if !Config::is_read_only() {
+        UpdateManager::finish_pending();
+    }
+
+    let repairs_config = matches!(
+        cli.commands,
+        Some(Commands::Config {
+            commands: ConfigCommands::Reset { .. } | ConfigCommands::Path
+        })
+    );
+    let degraded = config_error.is_some();
+    match config_error {
+        // An unknown profile is a mistake in this invocation, the defaults would hide it
+        Some(e @ ConfigError::UnknownProfileError(_)) if !repairs_config => {
+            eprintln!("\x1b[1;31mError in {}:\x1b[0m {}", config_path.display(), e);
+            std::process::exit(KdguardError::from(e).exit_code());
+        }
+        Some(e) if !repairs_config => {
+            LoggingManager::warn(&format!("Using the default config: {}", e));
+            eprintln!(
+                "\x1b[1;33m{}\x1b[0m",
+                tr(
+                    "config.load.warning",
+                    &[("path", &config_path.display().to_string())]
+                )
+            );
+            eprintln!("{}", e);
+            eprintln!("{}", tr("config.reset.hint", &[]));
+        }
+        _ => {}
+    }
+
+    HealthCheck::set_scoring_weights(CONFIG.scoring);
+
+    let updates_enabled = !cli.no_update_check && CONFIG.update.check_updates;
+
+    // Start TUI if no commands provided, it checks for updates in the background
+    if tui {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        if updates_enabled {
+            tokio::spawn(async move {
+                let status = match UpdateManager::available_update(false).await {
+                    Ok(Some(tag)) => UpdateStatus::Available(tag),
+                    Ok(None) => UpdateStatus::UpToDate,
+                    Err(e) => UpdateStatus::Failed(e.to_string()),
+                };
+                let _ = sender.send(status);
+            });
+        } else {
+            let _ = sender.send(UpdateStatus::Disabled);
+        }
+        // Keeps the runtime free to drive the update check while the TUI blocks
+        tokio::task::block_in_place(|| kdguard::tui::run(receiver))
+            .map_err(|e| KdguardError::Other(e.to_string()))?;
+        return Ok(());
+    }
+
+    if cli.commands.is_none() {
+        LoggingManager::warn("Generation options used without the generate command");
+        eprintln!(
+            "\x1b[33m{}\x1b[0m",
+            tr("cli.cli_commands.generate.deprecated", &[])
+        );
+    }
+
+    // Check for updates once a day when generating in a terminal. Other commands, scripts,
+    // and broken setups never wait for GitHub, and a failed check does not stop generation
+    let generating = matches!(cli.commands, None | Some(Commands::Generate(_)));
+    let interactive = std::io::stdout().is_terminal() && std::io::stdin().is_terminal();
+    if updates_enabled
+        && generating
+        && interactive
+        && !degraded
+        && let Err(e) = UpdateManager::check_update_if_due().await
+    {
+        LoggingManager::warn(&format!("Update check failed: {}", e));
+        // The next automatic check is a day away, so the failure must not go unnoticed
+        eprintln!(
+            "\x1b[1;33m{}\x1b[0m",
+            tr(
+                "cli.cli_commands.update.auto_failed",
+                &[("error", &e.to_string())]
+            )
+        );
+    }
 
     if let Some(commands) = cli.commands {
         match commands {
-            Commands::Check { password, detailed } => {
-                HealthCheck::check_password(&password, detailed)?;
+            Commands::Generate(args) => return generate(&args),
+            Commands::Check {
+                passwords,
+                file,
+                output,
+                find_reuse,
+                json,
+                detailed,
+                banned_words,
+                policy,
+                username,
+                compare,
+                breachdb,
+            } => {
+                let mut breaches = match breachdb {
+                    Some(path) => Some(open_breach_database(path)?),
+                    None => None,
+                };
+                if let Some(path) = banned_words.or_else(|| CONFIG.check.banned_words_path.clone())
+                {
+                    HealthCheck::load_banned_words(Path::new(&path))?;
+                }
+                if let Some(file) = file {
+                    if find_reuse {
+                        return find_reuse_in_file(Path::new(&file), output.as_deref(), json);
+                    }
+                    return check_file(Path::new(&file), output.as_deref(), breaches.as_mut());
+                }
+                let policy = match policy {
+                    Some(name) => {
+                        let mut policy = CONFIG.get_policy(&name)?;
+                        // --username replaces the username of the profile
+                        if username.is_some() {
+                            policy = policy.with_username(username);
+                        }
+                        Some((policy, name))
+                    }
+                    None => None,
+                };
+                let previous = if compare {
+                    Some(HealthCheck::prompt_previous_password()?)
+                } else {
+                    None
+                };
+                let compliant = HealthCheck::check_passwords(
+                    &passwords,
+                    detailed,
+                    policy
+                        .as_ref()
+                        .map(|(policy, name)| (name.as_str(), policy)),
+                    previous.as_deref(),
+                )?;
+                if let Some(database) = breaches.as_mut() {
+                    for (index, password) in passwords.iter().enumerate() {
+                        let count = database.lookup(password)?;
+                        let line = if count > 0 {
+                            tr(
+                                "commands.check.breachdb.found",
+                                &[("count", &count.to_string())],
+                            )
+                        } else {
+                            tr("commands.check.breachdb.not_found", &[])
+                        };
+                        if passwords.len() > 1 {
+                            println!("#{}: {}", index + 1, line);
+                        } else {
+                            println!("{}", line);
+                        }
+                    }
+                }
+                if !compliant {
+                    std::process::exit(POLICY_VIOLATION_EXIT_CODE);
+                }
+            }
+            Commands::BackupSheet {
+                output,
+                format,
+                salt,
+                services,
+                kdf,
+                seed_env,
+            } => {
+                if seed_env.is_some() {
+                    eprintln!("{}", tr("commands.backup_sheet.seed_excluded", &[]));
+                }
+                let sheet = BackupSheet::collect(
+                    &CONFIG.deterministic,
+                    &CONFIG.services,
+                    &services,
+                    salt.as_deref(),
+                    kdf,
+                    seed_env.as_deref(),
+                )?
+                .render(format);
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, sheet)?;
+                        println!("{}", tr("commands.backup_sheet.saved", &[("path", &path)]));
+                    }
+                    None => print!("{}", sheet),
+                }
+            }
+            Commands::Breachdb {
+                commands: BreachdbCommands::Import { path, output },
+            } => {
+                let output = match output {
+                    Some(output) => PathBuf::from(output),
+                    None => BreachDatabase::default_path()?,
+                };
+                let summary = BreachDatabase::import(Path::new(&path), &output)?;
+                let key = if summary.wordlist {
+                    "commands.breachdb.imported_wordlist"
+                } else {
+                    "commands.breachdb.imported"
+                };
+                println!(
+                    "{}",
+                    tr(
+                        key,
+                        &[
+                            ("count", &summary.records.to_string()),
+                            ("path", &output.display().to_string())
+                        ]
+                    )
+                );
+            }
+            Commands::ExpireCheck { file } => {
+                let report = ExpiryReport::from_file(Path::new(&file))?;
+                print!("{}", report.render());
+                if !report.expired.is_empty() {
+                    std::process::exit(EXPIRED_EXIT_CODE);
+                }
+            }
+            Commands::Patterns {
+                commands: PatternsCommands::List,
+            } => {
+                let patterns = Generator::named_patterns(&CONFIG.patterns);
+                let width = patterns.iter().map(|p| p.name.len()).max().unwrap_or(0) + 1;
+                for named in patterns {
+                    let entropy = match Generator::pattern_entropy(&named.pattern, &BTreeMap::new())
+                    {
+                        Some(bits) => tr(
+                            "commands.patterns.bits",
+                            &[("bits", &format!("{:.0}", bits))],
+                        ),
+                        None => tr("commands.patterns.invalid", &[]),
+                    };
+                    let mut line = format!(
+                        "{:<width$}  {:>9}  {}",
+                        format!("@{}", named.name),
+                        entropy,
+                        named.pattern,
+                        width = width
+                    );
+                    if named.custom {
+                        line.push_str(&format!("  {}", tr("commands.patterns.custom", &[])));
+                    }
+                    println!("{}", line);
+                }
             }
             Commands::Config { commands } => match commands {
-                ConfigCommands::Show => {
+                ConfigCommands::Show {
+                    format: format @ (ConfigFormat::Toml | ConfigFormat::Json),
+                } => {
+                    println!("{}", Config::render(&CONFIG, format)?.trim_end());
+                }
+                ConfigCommands::Show { .. } => {
                     Config::print_config(&CONFIG);
+                    if degraded
+                        && std::io::stdin().is_terminal()
+                        && Confirm::new(&tr("config.load.regenerate", &[]))
+                            .with_default(false)
+                            .prompt()?
+                    {
+                        print_reset(Config::reset_config()?);
+                    }
                 }
                 ConfigCommands::Edit {
                     lang,
                     password_length,
                     count,
                     auto_save,
+                    default_mode,
+                    default_pattern,
+                    default_words,
+                    output_dir,
+                    output_template,
                 } => {
-                    Config::update_config(lang, password_length, count, auto_save)?;
+                    Config::update_config(ConfigUpdate {
+                        lang: lang.clone(),
+                        password_length,
+                        count,
+                        auto_save,
+                        default_mode,
+                        default_pattern,
+                        default_words,
+                        output_dir,
+                        output_filename_template: output_template,
+                        theme: None,
+                    })?;
+                    if let Some(lang) = lang {
+                        Lingua::set_language(&lang)?;
+                    }
+                    println!("\n\x1b[1;32m{}\x1b[0m", tr("config.edit.success", &[]));
+                    println!("{}", "=".repeat(50));
+                }
+                ConfigCommands::Reset { yes } => {
+                    let confirmed = yes
+                        || Confirm::new(&tr("config.reset.confirm", &[]))
+                            .with_default(false)
+                            .prompt()?;
+                    if !confirmed {
+                        LoggingManager::info("Config reset cancelled by user");
+                        println!("{}", tr("config.reset.cancelled", &[]));
+                        return Ok(());
+                    }
+                    print_reset(Config::reset_config()?);
+                }
+                ConfigCommands::Export { path } => {
+                    let exported = Config::load_config()?.export()?;
+                    match path {
+                        Some(path) => {
+                            std::fs::write(&path, exported)?;
+                            LoggingManager::info(&format!("Config exported to: {}", path));
+                            eprintln!(
+                                "{}",
+                                tr("config.export.success", &[("path", path.as_str())])
+                            );
+                        }
+                        None => print!("{}", exported),
+                    }
+                }
+                ConfigCommands::Import { path, yes } => {
+                    let current = Config::load_config()?;
+                    let imported = match current.import(&std::fs::read_to_string(&path)?) {
+                        Ok(imported) => imported,
+                        Err(e) => {
+                            eprintln!("\x1b[1;31mError in {}:\x1b[0m {}", path, e);
+                            std::process::exit(KdguardError::from(e).exit_code());
+                        }
+                    };
+                    let changes = current.diff(&imported)?;
+                    if changes.is_empty() {
+                        println!("{}", tr("config.import.no_changes", &[]));
+                        return Ok(());
+                    }
+
+                    println!("\n\x1b[1;33m{}\x1b[0m", tr("config.import.changes", &[]));
+                    for change in &changes {
+                        let color = if change.starts_with('+') { "32" } else { "31" };
+                        println!("  \x1b[{}m{}\x1b[0m", color, change);
+                    }
+                    let confirmed = yes
+                        || Confirm::new(&tr("config.import.confirm", &[]))
+                            .with_default(false)
+                            .prompt()?;
+                    if !confirmed {
+                        LoggingManager::info("Config import cancelled by user");
+                        println!("{}", tr("config.import.cancelled", &[]));
+                        return Ok(());
+                    }
+                    Config::save_config(&imported)?;
+                    LoggingManager::info(&format!("Config imported from: {}", path));
+                    println!("\n\x1b[1;32m{}\x1b[0m", tr("config.import.success", &[]));
+                }
+                ConfigCommands::Get { key } => {
+                    println!("{}", Config::load_config()?.get_key(&key)?);
+                }
+                ConfigCommands::Set { key, value } => Config::set_config_value(&key, &value)?,
+                ConfigCommands::Path => {
+                    println!("{}", config_path.display());
+                    println!("{}", Config::get_languages_path()?.display());
+                    println!("{}", LoggingManager::get_logging_path()?.display());
                 }
             },
-            Commands::Uninstall => {
-                UninstallManager::uninstall()?;
+            Commands::Update { rollback: true, .. } => match UpdateManager::rollback() {
+                Ok(Some(version)) => println!(
+                    "{}",
+                    tr(
+                        "cli.cli_commands.update.rolled_back",
+                        &[
+                            ("version", &version),
+                            ("current", env!("CARGO_PKG_VERSION"))
+                        ]
+                    )
+                ),
+                Ok(None) => {
+                    eprintln!("{}", tr("cli.cli_commands.update.no_previous", &[]));
+                    std::process::exit(1);
+                }
+                Err(e) => return Err(e.into()),
+            },
+            Commands::Update {
+                yes,
+                allow_unverified,
+                skip_signature,
+                rollback: false,
+            } => match UpdateManager::check_update(UpdateOptions {
+                assume_yes: yes,
+                allow_unverified,
+                skip_signature,
+                include_skipped: true,
+            })
+            .await
+            {
+                Ok(true) => {}
+                Ok(false) => println!("{}", tr("cli.cli_commands.update.up_to_date", &[])),
+                Err(e) => return Err(e.into()),
+            },
+            Commands::Uninstall {
+                yes,
+                no_backup,
+                dry_run,
+                force,
+            } => UninstallManager::uninstall(UninstallOptions {
+                assume_yes: yes,
+                backup: !no_backup,
+                dry_run,
+                force,
+            })?,
+            Commands::Doctor => {
+                let checks = DoctorManager::default_checks();
+                if !DoctorManager::run_checks(&checks, &mut std::io::stdout())? {
+                    std::process::exit(1);
+                }
             }
+            Commands::Logs {
+                tail, clear, yes, ..
+            } => {
+                let logging_path = LoggingManager::get_logging_path()?;
+                if let Some(count) = tail {
+                    match LoggingManager::newest_log(&logging_path)? {
+                        Some(path) => {
+                            for line in LoggingManager::tail(&path, count)? {
+                                println!("{}", line);
+                            }
+                        }
+                        None => eprintln!("{}", tr("cli.cli_commands.logs.no_logs", &[])),
+                    }
+                } else if clear {
+                    if !yes && !std::io::stdin().is_terminal() {
+                        eprintln!(
+                            "\x1b[1;31m{}\x1b[0m",
+                            tr("cli.cli_commands.logs.needs_yes", &[])
+                        );
+                        std::process::exit(1);
+                    }
+                    let confirmed = yes
+                        || Confirm::new(&tr("cli.cli_commands.logs.confirm", &[]))
+                            .with_default(false)
+                            .prompt()?;
+                    if !confirmed {
+                        println!("{}", tr("cli.cli_commands.logs.cancelled", &[]));
+                        return Ok(());
+                    }
+                    let removed = LoggingManager::clear_logs(&logging_path)?;
+                    println!(
+                        "{}",
+                        tr(
+                            "cli.cli_commands.logs.cleared",
+                            &[("count", &removed.to_string())]
+                        )
+                    );
+                } else {
+                    println!("{}", logging_path.display());
+                }
+            }
+            // Printed before the config is checked
+            Commands::Completions { .. } => {}
         }
         return Ok(());
     }
 
-    // Start TUI if no commands provided
-    kdguard::tui::run()?;
+    // Generation arguments were provided without the generate command
+    generate(&cli.generate)
+}
+
+/// Find the value of a global option in the raw command line arguments
+///
+/// # Arguments
+///
+/// * `args`: The command line arguments, starting with the program name
+/// * `name`: The long option, e.g. `--config`
+///
+/// # Returns
+///
+/// Returns the value, or None if the option is missing or has no value
+fn global_arg(args: impl IntoIterator<Item = OsString>, name: &str) -> Option<OsString> {
+    let prefix = format!("{}=", name);
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg.to_str().and_then(|arg| arg.strip_prefix(&prefix)) {
+            return Some(OsString::from(value));
+        }
+    }
+    None
+}
+
+/// Check for `uninstall --dry-run` before clap parses the arguments
+///
+/// # Arguments
+///
+/// * `args`: The command line arguments, including the program name
+///
+/// # Returns
+///
+/// Returns true if `--dry-run` follows the `uninstall` command
+fn is_uninstall_dry_run(args: impl IntoIterator<Item = OsString>) -> bool {
+    args.into_iter()
+        .skip(1)
+        .take_while(|arg| arg != "--")
+        .skip_while(|arg| arg != "uninstall")
+        .any(|arg| arg == "--dry-run")
+}
+
+/// Print the result of a config reset
+///
+/// # Arguments
+///
+/// * `backup_path`: The backup of the previous config file, if there was one
+fn print_reset(backup_path: Option<PathBuf>) {
+    println!("\n\x1b[1;32m{}\x1b[0m", tr("config.reset.success", &[]));
+    if let Some(backup_path) = backup_path {
+        println!(
+            "{}",
+            tr(
+                "config.reset.backup",
+                &[("path", &backup_path.display().to_string())]
+            )
+        );
+    }
+}
+
+/// Generate passwords or verify a deterministic password from the CLI arguments
+fn generate(cli: &GenerateArgs) -> Result<(), KdguardError> {
+    // Shown before any password, so a mistyped seed is noticed first
+    if let Some((fingerprint, mismatch)) = Generator::fingerprint_from_cli(cli) {
+        if mismatch {
+            let saved = CONFIG
+                .deterministic
+                .fingerprint
+                .as_deref()
+                .unwrap_or_default();
+            eprintln!(
+                "\x1b[1;31m{}\x1b[0m",
+                tr(
+                    "commands.generate.fingerprint.mismatch",
+                    &[("fingerprint", &fingerprint), ("saved", saved)]
+                )
+            );
+        } else {
+            eprintln!(
+                "{}",
+                tr(
+                    "commands.generate.fingerprint.shown",
+                    &[("fingerprint", &fingerprint)]
+                )
+            );
+        }
+    }
+    if let Some(name) = &cli.verify_policy {
+        let policy = CONFIG.get_policy(name)?;
+        let report = Generator::verify_from_cli(cli, &policy)?;
+        HealthCheck::print_verification_report(name, &report);
+        if cli.show {
+            let password = PasswordRequest::from_cli(cli)?.build()?.password;
+            println!(
+                "{}",
+                tr("commands.generate.verify.shown", &[("password", &password)])
+            );
+        }
+        if !report.policy.is_compliant() {
+            std::process::exit(POLICY_VIOLATION_EXIT_CODE);
+        }
+        return Ok(());
+    }
+
+    let services = match (&cli.services_file, cli.service.as_deref()) {
+        (Some(path), _) => Some(std::fs::read_to_string(path)?),
+        (None, Some("-")) => Some(std::io::read_to_string(std::io::stdin())?),
+        _ => None,
+    };
+    if let Some(services) = services {
+        return generate_for_services(cli, &services);
+    }
+
+    let cancellation = cancel_on_ctrl_c();
+    let mut reporter = StderrProgress::new();
+    let request = PasswordRequest::from_cli(cli)?;
+    let mut entropy = 0.0;
+    let (passwords, outcome) =
+        Generator::generate_bulk(cli.count, &mut reporter, &cancellation, || {
+            request.build().map(|generated| {
+                entropy = generated.entropy;
+                generated.password
+            })
+        })?;
+    reporter.finish();
+
+    // The selection gives away up to log2 of the candidates, stated so it is not hidden
+    if let Some(candidates) = cli.prefer_typeable {
+        eprintln!(
+            "{}",
+            tr(
+                "commands.generate.typeable",
+                &[
+                    ("candidates", candidates.to_string().as_str()),
+                    ("entropy", &format!("{:.1}", entropy)),
+                    ("reduction", &format!("{:.1}", (candidates as f64).log2())),
+                ]
+            )
+        );
+    }
+
+    // Passwords written to a file stay off the screen unless asked for
+    let saving = cli.save || cli.output.is_some();
+    let metadata = GenerationMetadata::from_cli(cli, passwords.len());
+    let expires = metadata.expires_display().map(|date| {
+        format!(
+            "\x1b[1;33m{}\x1b[0m",
+            tr("commands.generate.expires", &[("date", &date)])
+        )
+    });
+    if cli.print0 {
+        print_nul_separated(&passwords)?;
+    } else if !saving || cli.show {
+        println!("\n\x1b[1;36m{}\x1b[0m", tr("commands.generate.title", &[]));
+        println!("{}", "=".repeat(50));
+        for password in &passwords {
+            println!("{}", password);
+        }
+        println!("{}", "=".repeat(50));
+    }
+    // Stated with the passwords, so the reminder is handed off with them
+    if let Some(expires) = &expires {
+        if cli.print0 {
+            eprintln!("{}", expires);
+        } else {
+            println!("{}", expires);
+        }
+    }
+
+    if saving {
+        let count = passwords.len();
+        let output = match &cli.output {
+            Some(output) => PathBuf::from(output),
+            None => {
+                let mode = cli
+                    .mode
+                    .to_possible_value()
+                    .ok_or("Invalid password mode")?;
+                CONFIG.general.output_path(mode.get_name(), count)?
+            }
+        };
+        Generator::save_to_file(passwords, &output, &metadata)?;
+        let saved = tr(
+            "commands.generate.saved",
+            &[
+                ("count", count.to_string().as_str()),
+                ("path", &output.display().to_string()),
+            ],
+        );
+        // Keeps the NUL separated output free of other text
+        if cli.print0 {
+            eprintln!("{}", saved);
+        } else {
+            println!("{}", saved);
+        }
+    }
+
+    if outcome.cancelled {
+        eprintln!(
+            "{}",
+            tr(
+                "commands.generate.cancelled",
+                &[
+                    ("completed", outcome.completed.to_string().as_str()),
+                    ("total", outcome.total.to_string().as_str())
+                ]
+            )
+        );
+        std::process::exit(CANCELLED_EXIT_CODE);
+    }
+
+    Ok(())
+}
+
+/// Print each value followed by a NUL byte, like `find -print0`
+fn print_nul_separated(values: &[String]) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for value in values {
+        stdout.write_all(value.as_bytes())?;
+        stdout.write_all(b"\0")?;
+    }
+    stdout.flush()
+}
+
+/// Print one `service<TAB>password` line per service of a list
+fn generate_for_services(cli: &GenerateArgs, list: &str) -> Result<(), KdguardError> {
+    let (services, skipped) = Generator::parse_services(list);
+    let lines: Vec<String> = Generator::generate_for_services(cli, &services)?
+        .into_iter()
+        .map(|(service, password)| format!("{}\t{}", service, password))
+        .collect();
+    let saving = cli.save || cli.output.is_some();
+    let metadata = GenerationMetadata::from_cli(cli, lines.len());
+    if cli.print0 {
+        print_nul_separated(&lines)?;
+    } else if !saving || cli.show {
+        for line in &lines {
+            println!("{}", line);
+        }
+    }
+
+    if saving {
+        let count = lines.len();
+        let output = match &cli.output {
+            Some(output) => PathBuf::from(output),
+            None => CONFIG.general.output_path("deterministic", count)?,
+        };
+        Generator::save_to_file(lines, &output, &metadata)?;
+        let saved = tr(
+            "commands.generate.saved",
+            &[
+                ("count", count.to_string().as_str()),
+                ("path", &output.display().to_string()),
+            ],
+        );
+        // Printed lines stay free of other text for scripts
+        if cli.print0 || cli.show {
+            eprintln!("{}", saved);
+        } else {
+            println!("{}", saved);
+        }
+    }
+    // Kept off stdout, which only holds the service lines
+    if let Some(date) = metadata.expires_display() {
+        eprintln!(
+            "\x1b[1;33m{}\x1b[0m",
+            tr("commands.generate.expires", &[("date", &date)])
+        );
+    }
+    if skipped > 0 {
+        eprintln!(
+            "\x1b[33m{}\x1b[0m",
+            tr(
+                "commands.generate.services_skipped",
+                &[("count", skipped.to_string().as_str())]
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Open the breach database given to `--breachdb`, or the one in the config directory
+fn open_breach_database(path: Option<String>) -> Result<BreachDatabase, KdguardError> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => BreachDatabase::default_path()?,
+    };
+    Ok(BreachDatabase::open(&path)?)
+}
+
+/// Check every password of a file and write the results to a file or stdout
+fn check_file(
+    input: &Path,
+    output: Option<&str>,
+    mut breaches: Option<&mut BreachDatabase>,
+) -> Result<(), KdguardError> {
+    let cancellation = cancel_on_ctrl_c();
+    let mut reporter = StderrProgress::new();
+    let outcome = match output {
+        Some(output) => {
+            let mut writer = BufWriter::new(File::create(output)?);
+            HealthCheck::check_file(
+                input,
+                &mut writer,
+                breaches.as_deref_mut(),
+                &mut reporter,
+                &cancellation,
+            )?
+        }
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            HealthCheck::check_file(input, &mut stdout, breaches, &mut reporter, &cancellation)?
+        }
+    };
+    reporter.finish();
+
+    if outcome.cancelled {
+        eprintln!(
+            "{}",
+            tr(
+                "commands.check.bulk_cancelled",
+                &[
+                    ("completed", outcome.completed.to_string().as_str()),
+                    ("total", outcome.total.to_string().as_str())
+                ]
+            )
+        );
+        std::process::exit(CANCELLED_EXIT_CODE);
+    }
+
+    Ok(())
+}
+
+/// Report the identical and similar passwords of a file, by line number only
+fn find_reuse_in_file(input: &Path, output: Option<&str>, json: bool) -> Result<(), KdguardError> {
+    let report = ReuseReport::from_file(input)?;
+    let rendered = if json {
+        report.to_json() + "\n"
+    } else {
+        report.render_table()
+    };
+    match output {
+        Some(output) => std::fs::write(output, rendered)?,
+        None => print!("{}", rendered),
+    }
     Ok(())
 }
+
+/// Create a cancellation that is triggered by Ctrl-C
+fn cancel_on_ctrl_c() -> Cancellation {
+    let cancellation = Cancellation::new();
+    let handle = cancellation.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            handle.cancel();
+        }
+    });
+    cancellation
+}
@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+
+use chrono::Local;
+use clap::ValueEnum;
+
+use crate::config::DeterministicConfig;
+use crate::errors::GeneratorError;
+use crate::i18n::tr;
+use crate::logging::LoggingManager;
+use crate::password::generator::{DETERMINISTIC_LENGTH, Generator, Kdf};
+
+/// Environment variable used in the regeneration steps when none was given
+const DEFAULT_SEED_ENV: &str = "KDGUARD_SEED";
+
+/// Salt used by deterministic mode when none is given
+const DEFAULT_SALT: &str = "kdguard";
+
+/// The document format of a backup sheet
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum SheetFormat {
+    /// Plain text for printing
+    #[default]
+    Text,
+    /// Markdown with headings and code blocks
+    Markdown,
+}
+
+/// A printable record of the non-secret parameters of a deterministic setup
+///
+/// Holds everything needed to get the same passwords again except the seed, which is
+/// never read into the sheet. At most its fingerprint is.
+#[derive(Debug, Clone)]
+pub struct BackupSheet {
+    salt: Option<String>,
+    services: BTreeMap<String, String>,
+    kdf: DeterministicConfig,
+    fingerprint: Option<String>,
+    seed_env: String,
+}
+
+impl BackupSheet {
+    /// Collect the parameters from the config and the command line
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The `[deterministic]` config section
+    /// * `services`: The `[services]` config table, service names with a note
+    /// * `extra_services`: Services given on the command line
+    /// * `salt`: The salt passed to `generate`
+    /// * `kdf`: The KDF given on the command line, overrides the config
+    /// * `seed_env`: The variable holding the seed, only read for the fingerprint
+    ///
+    /// # Returns
+    ///
+    /// Returns the backup sheet, else returns an error if the seed variable is unset
+    pub fn collect(
+        config: &DeterministicConfig,
+        services: &BTreeMap<String, String>,
+        extra_services: &[String],
+        salt: Option<&str>,
+        kdf: Option<Kdf>,
+        seed_env: Option<&str>,
+    ) -> Result<Self, GeneratorError> {
+        LoggingManager::info("Collecting the backup sheet parameters");
+
+        let mut kdf_config = config.clone();
+        if let Some(kdf) = kdf {
+            kdf_config.kdf = kdf;
+        }
+
+        let fingerprint = match seed_env {
+            Some(seed_env) => Some(Generator::seed_fingerprint(
+                &Generator::read_seed(seed_env)?,
+                salt,
+                &kdf_config,
+            )?),
+            None => config.fingerprint.clone(),
+        };
+
+        let mut services = services.clone();
+        for service in extra_services {
+            services.entry(service.trim().to_string()).or_default();
+        }
+        services.retain(|service, _| !service.is_empty());
+
+        Ok(Self {
+            salt: salt.map(str::to_string),
+            services,
+            kdf: kdf_config,
+            fingerprint,
+            seed_env: seed_env.unwrap_or(DEFAULT_SEED_ENV).to_string(),
+        })
+    }
+
+    /// Render the sheet as a document
+    ///
+    /// # Arguments
+    ///
+    /// * `format`: The document format
+    ///
+    /// # Returns
+    ///
+    /// Returns the document
+    pub fn render(&self, format: SheetFormat) -> String {
+        let mut doc = Document::new(format);
+        let none = tr("commands.backup_sheet.none", &[]);
+
+        doc.title(&tr("commands.backup_sheet.title", &[]));
+        doc.line(&tr(
+            "commands.backup_sheet.created",
+            &[
+                ("date", &Local::now().format("%Y-%m-%d").to_string()),
+                ("version", env!("CARGO_PKG_VERSION")),
+            ],
+        ));
+        doc.line(&tr("commands.backup_sheet.no_secrets", &[]));
+
+        doc.heading(&tr("commands.backup_sheet.parameters", &[]));
+        let length = DETERMINISTIC_LENGTH.to_string();
+        let salt = match &self.salt {
+            Some(salt) => salt.clone(),
+            None => tr(
+                "commands.backup_sheet.default_salt",
+                &[("salt", DEFAULT_SALT)],
+            ),
+        };
+        let kdf = match self.kdf.kdf {
+            Kdf::Hkdf => "hkdf".to_string(),
+            Kdf::Argon2id => tr(
+                "commands.backup_sheet.argon2id",
+                &[
+                    ("memory", &self.kdf.memory_kib.to_string()),
+                    ("iterations", &self.kdf.iterations.to_string()),
+                ],
+            ),
+        };
+        doc.item(&tr("commands.backup_sheet.length", &[("length", &length)]));
+        doc.item(&tr("commands.backup_sheet.salt", &[("salt", &salt)]));
+        doc.item(&tr("commands.backup_sheet.kdf", &[("kdf", &kdf)]));
+        doc.item(&tr(
+            "commands.backup_sheet.fingerprint",
+            &[("fingerprint", self.fingerprint.as_deref().unwrap_or(&none))],
+        ));
+
+        doc.heading(&tr("commands.backup_sheet.services", &[]));
+        if self.services.is_empty() {
+            doc.item(&none);
+        }
+        for (service, note) in &self.services {
+            if note.is_empty() {
+                doc.item(service);
+            } else {
+                doc.item(&format!("{}: {}", service, note));
+            }
+        }
+
+        doc.heading(&tr("commands.backup_sheet.regenerate", &[]));
+        doc.step(&tr(
+            "commands.backup_sheet.steps.install",
+            &[("version", env!("CARGO_PKG_VERSION"))],
+        ));
+        if self.kdf.kdf == Kdf::Argon2id {
+            doc.step(&tr("commands.backup_sheet.steps.config", &[]));
+            doc.code(&format!(
+                "[deterministic]\nkdf = \"argon2id\"\nmemory_kib = {}\niterations = {}",
+                self.kdf.memory_kib, self.kdf.iterations
+            ));
+        }
+        doc.step(&tr("commands.backup_sheet.steps.seed", &[]));
+        doc.code(&format!("read -rs {0} && export {0}", self.seed_env));
+        doc.step(&tr("commands.backup_sheet.steps.generate", &[]));
+        doc.code(&self.commands().join("\n"));
+        if self.fingerprint.is_some() {
+            doc.step(&tr("commands.backup_sheet.steps.fingerprint", &[]));
+        }
+
+        doc.finish()
+    }
+
+    /// Get the `generate` commands giving the passwords back, one per service
+    fn commands(&self) -> Vec<String> {
+        let mut base = format!(
+            "kdguard generate -m deterministic --seed-env {}",
+            self.seed_env
+        );
+        if let Some(salt) = &self.salt {
+            base.push_str(&format!(" --salt {}", shell_quote(salt)));
+        }
+        if self.kdf.kdf == Kdf::Argon2id {
+            base.push_str(" --kdf argon2id");
+        }
+
+        if self.services.is_empty() {
+            return vec![base];
+        }
+        self.services
+            .keys()
+            .map(|service| format!("{} --service {}", base, shell_quote(service)))
+            .collect()
+    }
+}
+
+/// Quote a value for a POSIX shell if it needs it
+///
+/// # Arguments
+///
+/// * `value`: The value to quote
+///
+/// # Returns
+///
+/// Returns the value as it is if it is safe, otherwise in single quotes
+fn shell_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.@:/+".contains(c));
+    if safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// A document built from blocks, rendered in one of the sheet formats
+struct Document {
+    format: SheetFormat,
+    out: String,
+    steps: usize,
+}
+
+impl Document {
+    fn new(format: SheetFormat) -> Self {
+        Self {
+            format,
+            out: String::new(),
+            steps: 0,
+        }
+    }
+
+    fn title(&mut self, text: &str) {
+        match self.format {
+            SheetFormat::Text => {
+                let rule = "=".repeat(text.chars().count());
+                self.out.push_str(&format!("{}\n{}\n\n", text, rule));
+            }
+            SheetFormat::Markdown => self.out.push_str(&format!("# {}\n\n", text)),
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        match self.format {
+            SheetFormat::Text => {
+                let rule = "-".repeat(text.chars().count());
+                self.out.push_str(&format!("\n{}\n{}\n\n", text, rule));
+            }
+            SheetFormat::Markdown => self.out.push_str(&format!("\n## {}\n\n", text)),
+        }
+    }
+
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&format!("{}\n\n", text));
+    }
+
+    fn item(&mut self, text: &str) {
+        match self.format {
+            SheetFormat::Text => self.out.push_str(&format!("  * {}\n", text)),
+            SheetFormat::Markdown => self.out.push_str(&format!("- {}\n", text)),
+        }
+    }
+
+    fn step(&mut self, text: &str) {
+        self.steps += 1;
+        self.out.push_str(&format!("{}. {}\n", self.steps, text));
+    }
+
+    fn code(&mut self, code: &str) {
+        match self.format {
+            SheetFormat::Text => {
+                for line in code.lines() {
+                    self.out.push_str(&format!("       {}\n", line));
+                }
+                self.out.push('\n');
+            }
+            SheetFormat::Markdown => {
+                self.out.push_str("\n   ```\n");
+                for line in code.lines() {
+                    self.out.push_str(&format!("   {}\n", line));
+                }
+                self.out.push_str("   ```\n\n");
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        self.out.trim_end().to_string() + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet() -> BackupSheet {
+        let mut services = BTreeMap::new();
+        services.insert("github".to_string(), "work account".to_string());
+        BackupSheet::collect(
+            &DeterministicConfig {
+                kdf: Kdf::Argon2id,
+                memory_kib: 256,
+                iterations: 2,
+                fingerprint: Some("amber-cactus".to_string()),
+            },
+            &services,
+            &["my bank".to_string(), " ".to_string()],
+            Some("2024"),
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_backup_sheet_lists_the_parameters() {
+        let sheet = sheet();
+        let text = sheet.render(SheetFormat::Text);
+
+        assert!(text.contains("amber-cactus"));
+        assert!(text.contains("2024"));
+        assert!(text.contains("github: work account"));
+        assert!(text.contains("memory_kib = 256"));
+        assert!(text.contains(
+            "kdguard generate -m deterministic --seed-env KDGUARD_SEED --salt 2024 --kdf argon2id --service 'my bank'"
+        ));
+        assert!(!text.contains("```"));
+
+        let markdown = sheet.render(SheetFormat::Markdown);
+        assert!(markdown.starts_with("# "));
+        assert!(markdown.contains("\n## "));
+        assert!(markdown.contains("- github: work account"));
+        assert!(markdown.contains("```"));
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("github.com"), "github.com");
+        assert_eq!(shell_quote("my bank"), "'my bank'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
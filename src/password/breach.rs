@@ -0,0 +1,147 @@
+use std::fmt::Write;
+
+use ring::digest::{SHA1_FOR_LEGACY_USE_ONLY, digest};
+
+use crate::CONFIG;
+use crate::errors::BreachError;
+use crate::http::HttpManager;
+use crate::logging::LoggingManager;
+
+/// The Have I Been Pwned range API, queried with the first five characters of the hash
+const RANGE_API: &str = "https://api.pwnedpasswords.com/range/";
+/// Number of hash characters sent to the API
+const PREFIX_LENGTH: usize = 5;
+
+pub struct BreachCheck;
+
+impl BreachCheck {
+    /// Hash a password the way the range API expects it
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to hash
+    ///
+    /// # Returns
+    ///
+    /// Returns the SHA-1 hash as uppercase hex
+    pub fn hash(password: &str) -> String {
+        let hash = digest(&SHA1_FOR_LEGACY_USE_ONLY, password.as_bytes());
+        hash.as_ref()
+            .iter()
+            .fold(String::with_capacity(40), |mut hex, byte| {
+                let _ = write!(hex, "{:02X}", byte);
+                hex
+            })
+    }
+
+    /// Look up how often a password hash appears in known breaches
+    ///
+    /// Only the first five characters of the hash leave the machine, the API answers
+    /// with all suffixes sharing that prefix and the match is made locally.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash`: The uppercase SHA-1 hex of the password, see `hash`
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of times the password was seen in breaches, 0 if it was not
+    /// found, otherwise an error
+    pub async fn lookup(hash: &str) -> Result<u64, BreachError> {
+        let (prefix, suffix) = hash.split_at(PREFIX_LENGTH.min(hash.len()));
+        LoggingManager::info(&format!("Checking breaches for hash prefix {}", prefix));
+
+        let client = HttpManager::builder("kdguard-breach-check", &CONFIG.network)?
+            .build()
+            .map_err(|e| {
+                let error = format!("Failed to create HTTP client: {}", e);
+                LoggingManager::error(&error);
+                BreachError::Request(error)
+            })?;
+
+        // Padding hides the real number of suffixes for the prefix from observers
+        let res = client
+            .get(format!("{}{}", RANGE_API, prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .map_err(|e| {
+                let error = format!(
+                    "Failed to reach the breach API: {}",
+                    HttpManager::describe_error(&e)
+                );
+                LoggingManager::error(&error);
+                BreachError::Request(error)
+            })?;
+
+        let status = res.status();
+        if !status.is_success() {
+            let error = format!("Breach API returned status {}", status);
+            LoggingManager::error(&error);
+            return Err(BreachError::Api(error));
+        }
+
+        let body = res.text().await.map_err(|e| {
+            let error = format!("Failed to read response body: {}", e);
+            LoggingManager::error(&error);
+            BreachError::Request(error)
+        })?;
+
+        Self::parse_range(&body, suffix)
+    }
+
+    /// Find the count of a hash suffix in a range API response
+    ///
+    /// # Arguments
+    ///
+    /// * `body`: The response, one `SUFFIX:COUNT` per line
+    /// * `suffix`: The hash without its five character prefix
+    ///
+    /// # Returns
+    ///
+    /// Returns the count of the suffix, 0 if it is not listed, otherwise an error
+    fn parse_range(body: &str, suffix: &str) -> Result<u64, BreachError> {
+        for line in body.lines() {
+            let Some((candidate, count)) = line.trim().split_once(':') else {
+                continue;
+            };
+            if candidate.eq_ignore_ascii_case(suffix) {
+                return count.trim().parse().map_err(|_| {
+                    let error = format!("Invalid count in breach API response: {}", count);
+                    LoggingManager::error(&error);
+                    BreachError::ParseResponse(error)
+                });
+            }
+        }
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_uppercase_sha1() {
+        assert_eq!(
+            BreachCheck::hash("password"),
+            "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8"
+        );
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let body = "0018A45C4D1DEF81644B54AB7F969B88D65:10\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FD9:0\r\n";
+
+        let found = BreachCheck::parse_range(body, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+        assert_eq!(found.unwrap(), 9545824);
+        // Padding entries have a count of 0
+        let padded = BreachCheck::parse_range(body, "1E4C9B93F3F0682250B6CF8331B7EE68FD9");
+        assert_eq!(padded.unwrap(), 0);
+        let missing = BreachCheck::parse_range(body, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF");
+        assert_eq!(missing.unwrap(), 0);
+        assert!(BreachCheck::parse_range("ABC:many", "abc").is_err());
+    }
+}
@@ -0,0 +1,582 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use ring::digest::{SHA1_FOR_LEGACY_USE_ONLY, digest};
+
+use crate::config::Config;
+use crate::errors::{BreachError, ConfigError};
+use crate::logging::{LoggingManager, Redactable};
+
+/// Name of the index file in the config directory
+pub(crate) const INDEX_FILE: &str = "breach.idx";
+/// First bytes of an index file, the last one is the format version
+const MAGIC: &[u8; 8] = b"KDGBRDB1";
+/// Magic, record count, prefix table offset, bloom filter offset and bits, hash count
+const HEADER_LENGTH: u64 = 48;
+/// Hash bytes covered by the prefix table, the rest is stored in the record
+const PREFIX_BYTES: usize = 2;
+/// Entries of the prefix table, one per prefix plus the end of the last one
+const PREFIX_ENTRIES: usize = (1 << (8 * PREFIX_BYTES)) + 1;
+/// A record is the SHA-1 without its prefix and a little-endian u32 count
+const RECORD_LENGTH: u64 = (20 - PREFIX_BYTES + 4) as u64;
+/// Bloom filter bits per record, about 1% false positives with `BLOOM_HASHES`
+const BLOOM_BITS_PER_RECORD: u64 = 10;
+/// Upper bound of the bloom filter, which is built in memory
+const MAX_BLOOM_BYTES: u64 = 256 * 1024 * 1024;
+/// Bit positions set per record
+const BLOOM_HASHES: u32 = 7;
+
+/// An offline copy of breached password hashes, queried without network access
+///
+/// The index holds sorted SHA-1 records behind a table of 2-byte prefixes and a bloom
+/// filter. Lookups read a few bytes at known offsets, nothing is loaded into memory.
+pub struct BreachDatabase {
+    file: File,
+    records: u64,
+    prefix_offset: u64,
+    bloom_offset: u64,
+    bloom_bits: u64,
+    bloom_hashes: u32,
+}
+
+/// The outcome of an import
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImportSummary {
+    /// Distinct hashes in the index
+    pub records: u64,
+    /// Whether the source was a plain wordlist rather than a hash dump
+    pub wordlist: bool,
+}
+
+impl BreachDatabase {
+    /// Get the path of the index in the config directory
+    ///
+    /// # Returns
+    ///
+    /// Returns the path, else returns an error if the config directory is unknown
+    pub fn default_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::get_config_dir()?.join(INDEX_FILE))
+    }
+
+    /// Build an index from a hash dump or a wordlist
+    ///
+    /// A dump has one uppercase or lowercase SHA-1 per line, optionally followed by
+    /// `:COUNT`, sorted by hash as in the downloads of Have I Been Pwned. It is streamed,
+    /// so its size does not matter. Any other file is read as a wordlist with one
+    /// password per line; wordlists are hashed and sorted in memory. The index is
+    /// written next to `output` first and only replaces it when complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: The dump or wordlist
+    /// * `output`: The path of the index
+    ///
+    /// # Returns
+    ///
+    /// Returns what was imported, else returns an error
+    pub fn import(source: &Path, output: &Path) -> Result<ImportSummary, BreachError> {
+        LoggingManager::info(&format!(
+            "Importing breach database from {} into {}",
+            Redactable(source.display()),
+            Redactable(output.display())
+        ));
+
+        let mut reader =
+            BufReader::new(File::open(source).map_err(|e| {
+                database_error(format!("Failed to open {}: {}", source.display(), e))
+            })?);
+        let wordlist = !Self::is_hash_dump(&mut reader)?;
+
+        if let Some(dir) = output.parent() {
+            fs::create_dir_all(dir).map_err(|e| {
+                database_error(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        let temporary = output.with_extension("idx.tmp");
+        let result = Self::write_index(reader, wordlist, &temporary).and_then(|records| {
+            fs::rename(&temporary, output).map_err(|e| {
+                database_error(format!("Failed to replace {}: {}", output.display(), e))
+            })?;
+            Ok(records)
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        let records = result?;
+
+        LoggingManager::info(&format!(
+            "Imported {} breached hashes (wordlist: {})",
+            records, wordlist
+        ));
+        Ok(ImportSummary { records, wordlist })
+    }
+
+    /// Open an index for lookups
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path of the index
+    ///
+    /// # Returns
+    ///
+    /// Returns the database, else returns an error if the file is missing or no index
+    pub fn open(path: &Path) -> Result<Self, BreachError> {
+        let mut file = File::open(path).map_err(|e| {
+            database_error(format!(
+                "Failed to open {}: {}, create it with `kdguard breachdb import`",
+                path.display(),
+                e
+            ))
+        })?;
+        let invalid = || database_error(format!("{} is not a breach index", path.display()));
+
+        let mut header = [0u8; HEADER_LENGTH as usize];
+        file.read_exact(&mut header).map_err(|_| invalid())?;
+        if &header[..8] != MAGIC {
+            return Err(invalid());
+        }
+        let field = |index: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&header[8 + index * 8..16 + index * 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let mut hashes = [0u8; 4];
+        hashes.copy_from_slice(&header[40..44]);
+
+        let database = Self {
+            records: field(0),
+            prefix_offset: field(1),
+            bloom_offset: field(2),
+            bloom_bits: field(3),
+            bloom_hashes: u32::from_le_bytes(hashes),
+            file,
+        };
+        // The header comes from the file, so a corrupt one must not overflow the offsets
+        let prefix_offset = database
+            .records
+            .checked_mul(RECORD_LENGTH)
+            .and_then(|records| records.checked_add(HEADER_LENGTH))
+            .ok_or_else(invalid)?;
+        let bloom_offset = prefix_offset
+            .checked_add(PREFIX_ENTRIES as u64 * 8)
+            .ok_or_else(invalid)?;
+        let expected = database
+            .bloom_offset
+            .checked_add(database.bloom_bits.div_ceil(8))
+            .ok_or_else(invalid)?;
+        let length = database.file.metadata().map_err(|_| invalid())?.len();
+        if database.prefix_offset != prefix_offset
+            || database.bloom_offset != bloom_offset
+            || database.bloom_bits == 0
+            || length != expected
+        {
+            return Err(invalid());
+        }
+
+        LoggingManager::info(&format!(
+            "Opened breach database with {} hashes",
+            database.records
+        ));
+        Ok(database)
+    }
+
+    /// Get the number of hashes in the index
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Look up how often a password appears in the index
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to look up
+    ///
+    /// # Returns
+    ///
+    /// Returns the count, 0 if the password is not in the index, else returns an error
+    pub fn lookup(&mut self, password: &str) -> Result<u64, BreachError> {
+        let hash = sha1(password);
+
+        for position in bloom_positions(&hash, self.bloom_bits, self.bloom_hashes) {
+            let byte = self.read_at::<1>(self.bloom_offset + position / 8)?;
+            if byte[0] & (1 << (position % 8)) == 0 {
+                return Ok(0);
+            }
+        }
+
+        let prefix = u16::from_be_bytes([hash[0], hash[1]]) as u64;
+        let mut low = u64::from_le_bytes(self.read_at(self.prefix_offset + prefix * 8)?);
+        let mut high = u64::from_le_bytes(self.read_at(self.prefix_offset + prefix * 8 + 8)?);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let record =
+                self.read_at::<{ RECORD_LENGTH as usize }>(HEADER_LENGTH + middle * RECORD_LENGTH)?;
+            match record[..20 - PREFIX_BYTES].cmp(&hash[PREFIX_BYTES..]) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => {
+                    let mut count = [0u8; 4];
+                    count.copy_from_slice(&record[20 - PREFIX_BYTES..]);
+                    return Ok(u64::from(u32::from_le_bytes(count)));
+                }
+            }
+        }
+        Ok(0)
+    }
+
+    /// Read a fixed number of bytes at an offset of the index
+    fn read_at<const N: usize>(&mut self, offset: u64) -> Result<[u8; N], BreachError> {
+        let mut bytes = [0u8; N];
+        self.file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| self.file.read_exact(&mut bytes))
+            .map_err(|e| database_error(format!("Failed to read the breach index: {}", e)))?;
+        Ok(bytes)
+    }
+
+    /// Check whether a source is a hash dump, judged by its first non-empty line
+    fn is_hash_dump(reader: &mut BufReader<File>) -> Result<bool, BreachError> {
+        loop {
+            let buffer = reader
+                .fill_buf()
+                .map_err(|e| database_error(format!("Failed to read the source: {}", e)))?;
+            if buffer.is_empty() {
+                return Ok(false);
+            }
+            let blank = buffer
+                .iter()
+                .take_while(|byte| byte.is_ascii_whitespace())
+                .count();
+            if blank < buffer.len() {
+                let line = buffer[blank..].split(|&byte| byte == b'\n').next();
+                let line = String::from_utf8_lossy(line.unwrap_or_default());
+                return Ok(parse_dump_line(line.trim_end()).is_some());
+            }
+            let consumed = buffer.len();
+            reader.consume(consumed);
+        }
+    }
+
+    /// Write the records, the prefix table, the bloom filter, and finally the header
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of records, else returns an error
+    fn write_index(
+        reader: BufReader<File>,
+        wordlist: bool,
+        path: &Path,
+    ) -> Result<u64, BreachError> {
+        let write_error = |e: std::io::Error| {
+            database_error(format!("Failed to write {}: {}", path.display(), e))
+        };
+        let mut writer = BufWriter::new(File::create(path).map_err(write_error)?);
+        writer
+            .write_all(&[0u8; HEADER_LENGTH as usize])
+            .map_err(write_error)?;
+
+        let mut prefix_counts = vec![0u64; PREFIX_ENTRIES - 1];
+        let mut records = 0u64;
+        let mut write_record = |hash: &[u8; 20], count: u64| -> Result<(), BreachError> {
+            prefix_counts[u16::from_be_bytes([hash[0], hash[1]]) as usize] += 1;
+            records += 1;
+            writer
+                .write_all(&hash[PREFIX_BYTES..])
+                .and_then(|_| {
+                    writer.write_all(&(count.min(u64::from(u32::MAX)) as u32).to_le_bytes())
+                })
+                .map_err(write_error)
+        };
+
+        if wordlist {
+            let mut hashes = Vec::new();
+            for line in reader.lines() {
+                let line =
+                    line.map_err(|e| database_error(format!("Failed to read the source: {}", e)))?;
+                let password = line.trim_end_matches('\r');
+                if !password.is_empty() {
+                    hashes.push(sha1(password));
+                }
+            }
+            hashes.sort_unstable();
+            for group in hashes.chunk_by(|a, b| a == b) {
+                write_record(&group[0], group.len() as u64)?;
+            }
+        } else {
+            let mut previous: Option<([u8; 20], u64)> = None;
+            for (index, line) in reader.lines().enumerate() {
+                let line =
+                    line.map_err(|e| database_error(format!("Failed to read the source: {}", e)))?;
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let (hash, count) = parse_dump_line(line).ok_or_else(|| {
+                    invalid_dump(format!(
+                        "line {} is not a SHA-1 hash with a count",
+                        index + 1
+                    ))
+                })?;
+                previous = match previous {
+                    Some((last, total)) if last == hash => Some((last, total + count)),
+                    Some((last, _)) if last > hash => {
+                        return Err(invalid_dump(format!(
+                            "line {} is out of order, the dump must be sorted by hash",
+                            index + 1
+                        )));
+                    }
+                    Some((last, total)) => {
+                        write_record(&last, total)?;
+                        Some((hash, count))
+                    }
+                    None => Some((hash, count)),
+                };
+            }
+            if let Some((last, total)) = previous {
+                write_record(&last, total)?;
+            }
+        }
+
+        let prefix_offset = HEADER_LENGTH + records * RECORD_LENGTH;
+        let mut start = 0u64;
+        writer
+            .write_all(&start.to_le_bytes())
+            .map_err(write_error)?;
+        for count in &prefix_counts {
+            start += count;
+            writer
+                .write_all(&start.to_le_bytes())
+                .map_err(write_error)?;
+        }
+        writer.flush().map_err(write_error)?;
+
+        // The records are read back in prefix order to fill the filter
+        let bloom_bits = (records * BLOOM_BITS_PER_RECORD).clamp(64, MAX_BLOOM_BYTES * 8);
+        let mut bloom = vec![0u8; bloom_bits.div_ceil(8) as usize];
+        let mut records_reader = BufReader::new(File::open(path).map_err(write_error)?);
+        records_reader
+            .seek(SeekFrom::Start(HEADER_LENGTH))
+            .map_err(write_error)?;
+        let mut record = [0u8; RECORD_LENGTH as usize];
+        for (prefix, count) in prefix_counts.iter().enumerate() {
+            for _ in 0..*count {
+                records_reader
+                    .read_exact(&mut record)
+                    .map_err(write_error)?;
+                let mut hash = [0u8; 20];
+                hash[..PREFIX_BYTES].copy_from_slice(&(prefix as u16).to_be_bytes());
+                hash[PREFIX_BYTES..].copy_from_slice(&record[..20 - PREFIX_BYTES]);
+                for position in bloom_positions(&hash, bloom_bits, BLOOM_HASHES) {
+                    bloom[(position / 8) as usize] |= 1 << (position % 8);
+                }
+            }
+        }
+        writer.write_all(&bloom).map_err(write_error)?;
+
+        let bloom_offset = prefix_offset + PREFIX_ENTRIES as u64 * 8;
+        let mut header = Vec::with_capacity(HEADER_LENGTH as usize);
+        header.extend_from_slice(MAGIC);
+        for field in [records, prefix_offset, bloom_offset, bloom_bits] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header.extend_from_slice(&BLOOM_HASHES.to_le_bytes());
+        header.resize(HEADER_LENGTH as usize, 0);
+
+        let mut file = writer
+            .into_inner()
+            .map_err(|e| write_error(e.into_error()))?;
+        file.seek(SeekFrom::Start(0))
+            .and_then(|_| file.write_all(&header))
+            .and_then(|_| file.sync_all())
+            .map_err(write_error)?;
+
+        Ok(records)
+    }
+}
+
+/// Hash a password with SHA-1 as the breach dumps do
+fn sha1(password: &str) -> [u8; 20] {
+    let mut hash = [0u8; 20];
+    hash.copy_from_slice(digest(&SHA1_FOR_LEGACY_USE_ONLY, password.as_bytes()).as_ref());
+    hash
+}
+
+/// Parse a `HASH` or `HASH:COUNT` line of a dump
+///
+/// # Arguments
+///
+/// * `line`: The line without its line break
+///
+/// # Returns
+///
+/// Returns the hash and its count, 1 if none is given, or None if the line is no hash
+fn parse_dump_line(line: &str) -> Option<([u8; 20], u64)> {
+    let (hex, count) = match line.split_once(':') {
+        Some((hex, count)) => (hex, count.trim().parse().ok()?),
+        None => (line, 1),
+    };
+    if hex.len() != 40 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut hash = [0u8; 20];
+    for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some((hash, count))
+}
+
+/// Get the bloom filter bits of a hash by double hashing
+///
+/// SHA-1 output is uniform already, so two of its words serve as the base hashes.
+fn bloom_positions(hash: &[u8; 20], bits: u64, hashes: u32) -> impl Iterator<Item = u64> {
+    let mut first = [0u8; 8];
+    let mut second = [0u8; 8];
+    first.copy_from_slice(&hash[4..12]);
+    second.copy_from_slice(&hash[12..20]);
+    let first = u64::from_le_bytes(first);
+    let second = u64::from_le_bytes(second) | 1;
+
+    (0..u64::from(hashes)).map(move |i| first.wrapping_add(i.wrapping_mul(second)) % bits)
+}
+
+fn database_error(error: String) -> BreachError {
+    LoggingManager::error(&error);
+    BreachError::Database(error)
+}
+
+fn invalid_dump(error: String) -> BreachError {
+    LoggingManager::error(&error);
+    BreachError::InvalidDump(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::password::BreachCheck;
+    use crate::test_support::TempDir;
+
+    /// A sorted dump in the format of the Have I Been Pwned downloads
+    fn dump(passwords: &[(&str, u64)]) -> String {
+        let mut lines: Vec<String> = passwords
+            .iter()
+            .map(|(password, count)| format!("{}:{}", BreachCheck::hash(password), count))
+            .collect();
+        lines.sort();
+        lines.join("\r\n") + "\r\n"
+    }
+
+    #[test]
+    fn test_import_dump_finds_hits_and_misses() {
+        let dir = TempDir::new("dump");
+        let source = dir.0.join("pwned.txt");
+        let index = dir.0.join("breach.idx");
+        fs::write(
+            &source,
+            dump(&[("password", 9545824), ("letmein", 12), ("hunter2", 3)]),
+        )
+        .unwrap();
+
+        let summary = BreachDatabase::import(&source, &index).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                records: 3,
+                wordlist: false,
+            }
+        );
+        assert!(!index.with_extension("idx.tmp").exists());
+
+        let mut database = BreachDatabase::open(&index).unwrap();
+        assert_eq!(database.records(), 3);
+        assert_eq!(database.lookup("password").unwrap(), 9545824);
+        assert_eq!(database.lookup("letmein").unwrap(), 12);
+        assert_eq!(database.lookup("hunter2").unwrap(), 3);
+        assert_eq!(database.lookup("Password").unwrap(), 0);
+        assert_eq!(database.lookup("correct horse battery staple").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_import_wordlist_counts_duplicates() {
+        let dir = TempDir::new("wordlist");
+        let source = dir.0.join("words.txt");
+        let index = dir.0.join("breach.idx");
+        fs::write(&source, "\nqwerty\nsecret\n\nqwerty\n").unwrap();
+
+        let summary = BreachDatabase::import(&source, &index).unwrap();
+        assert!(summary.wordlist);
+        assert_eq!(summary.records, 2);
+
+        let mut database = BreachDatabase::open(&index).unwrap();
+        assert_eq!(database.lookup("qwerty").unwrap(), 2);
+        assert_eq!(database.lookup("secret").unwrap(), 1);
+        assert_eq!(database.lookup("qwertz").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_import_rejects_unsorted_dumps() {
+        let dir = TempDir::new("unsorted");
+        let source = dir.0.join("pwned.txt");
+        let index = dir.0.join("breach.idx");
+        let mut lines: Vec<String> = ["a", "b", "c"]
+            .iter()
+            .map(|password| format!("{}:1", BreachCheck::hash(password)))
+            .collect();
+        lines.sort();
+        lines.reverse();
+        fs::write(&source, lines.join("\n")).unwrap();
+
+        assert!(matches!(
+            BreachDatabase::import(&source, &index),
+            Err(BreachError::InvalidDump(_))
+        ));
+        assert!(!index.exists());
+        assert!(!index.with_extension("idx.tmp").exists());
+    }
+
+    #[test]
+    fn test_open_rejects_other_files() {
+        let dir = TempDir::new("invalid");
+        let path = dir.0.join("breach.idx");
+        fs::write(&path, "not an index").unwrap();
+        assert!(matches!(
+            BreachDatabase::open(&path),
+            Err(BreachError::Database(_))
+        ));
+        assert!(BreachDatabase::open(&dir.0.join("missing.idx")).is_err());
+    }
+
+    #[test]
+    fn test_open_rejects_overflowing_headers() {
+        let dir = TempDir::new("overflow");
+        let path = dir.0.join("breach.idx");
+        for fields in [[u64::MAX, 0, 0, 8], [0, 0, u64::MAX, u64::MAX]] {
+            let mut header = MAGIC.to_vec();
+            for field in fields {
+                header.extend_from_slice(&field.to_le_bytes());
+            }
+            header.extend_from_slice(&3u32.to_le_bytes());
+            header.resize(HEADER_LENGTH as usize, 0);
+            fs::write(&path, &header).unwrap();
+
+            assert!(matches!(
+                BreachDatabase::open(&path),
+                Err(BreachError::Database(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_dump_line() {
+        let hash = "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8";
+        assert_eq!(parse_dump_line(&format!("{}:7", hash)).unwrap().1, 7);
+        assert_eq!(
+            parse_dump_line(&hash.to_lowercase()).unwrap().0,
+            parse_dump_line(hash).unwrap().0
+        );
+        assert!(parse_dump_line("password").is_none());
+        assert!(parse_dump_line(&format!("{}:many", hash)).is_none());
+        assert!(parse_dump_line("8846F7EAEE8FB117AD06BDD830B7586C:3").is_none());
+    }
+}
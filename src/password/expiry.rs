@@ -0,0 +1,272 @@
+use std::path::Path;
+
+use chrono::{DateTime, Local, SecondsFormat, TimeDelta};
+use serde::Serialize;
+
+use crate::errors::GeneratorError;
+use crate::i18n::tr;
+use crate::logging::{LoggingManager, Redactable};
+use crate::password::{Generator, SavedEntry};
+
+/// Longest duration `--expires` accepts, about ten years
+pub const MAX_EXPIRY_DAYS: i64 = 3650;
+
+/// Parse the duration of `--expires`, a whole number followed by `h`, `d`, or `w`
+///
+/// # Arguments
+///
+/// * `value`: The duration, e.g. `24h`, `7d`, or `2w`
+///
+/// # Returns
+///
+/// Returns the duration, else returns an error message for clap to show
+pub fn parse_duration(value: &str) -> Result<TimeDelta, String> {
+    let invalid = || {
+        format!(
+            "Invalid duration '{}', expected a number followed by h, d, or w (e.g. 24h, 7d, 2w)",
+            value
+        )
+    };
+    let value = value.trim();
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let amount = &value[..value.len() - unit.len_utf8()];
+    if amount.is_empty() || !amount.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let hours = match unit.to_ascii_lowercase() {
+        'h' => Some(amount),
+        'd' => amount.checked_mul(24),
+        'w' => amount.checked_mul(24 * 7),
+        _ => return Err(invalid()),
+    };
+    match hours {
+        Some(0) => Err("The duration must be longer than 0".to_string()),
+        Some(hours) if hours <= MAX_EXPIRY_DAYS * 24 => Ok(TimeDelta::hours(hours)),
+        _ => Err(format!(
+            "The duration must be at most {} days",
+            MAX_EXPIRY_DAYS
+        )),
+    }
+}
+
+/// Get the time a duration from now, as stored in the metadata of saved passwords
+///
+/// # Arguments
+///
+/// * `duration`: How long the passwords are meant to be used
+///
+/// # Returns
+///
+/// Returns the RFC 3339 time of the expiry
+pub fn expires_in(duration: TimeDelta) -> String {
+    (Local::now() + duration).to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// Format a stored expiry for the printed summary, e.g. `2025-01-12 14:00`
+///
+/// # Arguments
+///
+/// * `expires`: The RFC 3339 time of the expiry
+///
+/// # Returns
+///
+/// Returns the expiry in local time, or None if it is not a valid time
+pub fn format_expiry(expires: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(expires).ok().map(|time| {
+        time.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    })
+}
+
+/// A saved password past its expiry, identified by its position only
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpiredEntry {
+    /// Position of the password in the file, starting at 1
+    pub number: usize,
+    pub service: Option<String>,
+    /// RFC 3339 time of the expiry
+    pub expires: String,
+}
+
+/// The expired passwords of a file saved by kdguard, `kdguard expire-check`
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ExpiryReport {
+    pub expired: Vec<ExpiredEntry>,
+    /// Number of passwords in the file
+    pub checked: usize,
+    /// Passwords saved without an expiry
+    pub without_expiry: usize,
+}
+
+impl ExpiryReport {
+    /// Find the expired passwords of a file saved with `--save` or `--output`
+    ///
+    /// # Arguments
+    ///
+    /// * `input`: The path to the saved file, in any of the save formats
+    ///
+    /// # Returns
+    ///
+    /// Returns the report, else returns an error if the file cannot be read
+    pub fn from_file(input: &Path) -> Result<Self, GeneratorError> {
+        LoggingManager::info(&format!(
+            "Looking for expired passwords in: {}",
+            Redactable(input.display())
+        ));
+        let report = Self::from_entries(&Generator::load_from_file(input)?, Local::now());
+        LoggingManager::info(&format!(
+            "Expiry check finished: {} of {} passwords expired",
+            report.expired.len(),
+            report.checked
+        ));
+        Ok(report)
+    }
+
+    /// Find the expired entries of a saved file at a point in time
+    ///
+    /// # Arguments
+    ///
+    /// * `entries`: The saved passwords with their metadata
+    /// * `now`: The time to compare the expiries with
+    ///
+    /// # Returns
+    ///
+    /// Returns the report, entries with an invalid expiry count as without one
+    pub fn from_entries(entries: &[SavedEntry], now: DateTime<Local>) -> Self {
+        let mut report = Self {
+            checked: entries.len(),
+            ..Self::default()
+        };
+        for (index, entry) in entries.iter().enumerate() {
+            let expires = entry.metadata.expires.as_deref().and_then(|expires| {
+                DateTime::parse_from_rfc3339(expires)
+                    .ok()
+                    .map(|time| (expires, time))
+            });
+            match expires {
+                Some((expires, time)) if time <= now => report.expired.push(ExpiredEntry {
+                    number: index + 1,
+                    service: entry.metadata.service.clone(),
+                    expires: expires.to_string(),
+                }),
+                Some(_) => {}
+                None => report.without_expiry += 1,
+            }
+        }
+        report
+    }
+
+    /// Render one line per expired password and a summary
+    pub fn render(&self) -> String {
+        if self.without_expiry == self.checked {
+            return tr(
+                "commands.expire_check.no_expiry",
+                &[("count", &self.checked.to_string())],
+            ) + "\n";
+        }
+
+        let mut rendered = String::new();
+        for entry in &self.expired {
+            let number = entry.number.to_string();
+            let date = format_expiry(&entry.expires).unwrap_or_else(|| entry.expires.clone());
+            let line = match &entry.service {
+                Some(service) => tr(
+                    "commands.expire_check.expired_service",
+                    &[("number", &number), ("service", service), ("date", &date)],
+                ),
+                None => tr(
+                    "commands.expire_check.expired",
+                    &[("number", &number), ("date", &date)],
+                ),
+            };
+            rendered.push_str(&format!("\x1b[31m{}\x1b[0m\n", line));
+        }
+        rendered.push_str(&tr(
+            "commands.expire_check.summary",
+            &[
+                ("expired", &self.expired.len().to_string()),
+                ("count", &self.checked.to_string()),
+            ],
+        ));
+        rendered.push('\n');
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordMode;
+    use crate::password::GenerationMetadata;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("24h"), Ok(TimeDelta::hours(24)));
+        assert_eq!(parse_duration("7d"), Ok(TimeDelta::days(7)));
+        assert_eq!(parse_duration("2w"), Ok(TimeDelta::weeks(2)));
+        assert_eq!(parse_duration(" 1D "), Ok(TimeDelta::days(1)));
+        assert_eq!(
+            parse_duration(&format!("{}d", MAX_EXPIRY_DAYS)),
+            Ok(TimeDelta::days(MAX_EXPIRY_DAYS))
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_garbage() {
+        for value in [
+            "", "h", "24", "24m", "1.5d", "-1d", "+1d", "d7", "7 d", "1w2d", "7dd", "tomorrow",
+            "٣d",
+        ] {
+            assert!(parse_duration(value).is_err(), "{:?} was accepted", value);
+        }
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration(&format!("{}d", MAX_EXPIRY_DAYS + 1)).is_err());
+        assert!(parse_duration("99999999999999999999w").is_err());
+        assert!(parse_duration(&format!("{}w", i64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_format_expiry() {
+        let expires = expires_in(TimeDelta::hours(1));
+        let expected = (Local::now() + TimeDelta::hours(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        assert!(format_expiry(&expires).unwrap().starts_with(&expected));
+        assert_eq!(format_expiry("next week"), None);
+    }
+
+    fn entry(expires: Option<TimeDelta>, service: Option<&str>) -> SavedEntry {
+        let mut metadata = GenerationMetadata::new(&PasswordMode::Random, 1);
+        metadata.expires = expires
+            .map(|offset| (Local::now() + offset).to_rfc3339_opts(SecondsFormat::Secs, false));
+        metadata.service = service.map(str::to_string);
+        SavedEntry {
+            password: "secret".to_string(),
+            metadata,
+        }
+    }
+
+    #[test]
+    fn test_report_lists_expired_entries() {
+        let entries = [
+            entry(Some(TimeDelta::hours(-1)), Some("github")),
+            entry(Some(TimeDelta::days(1)), None),
+            entry(None, None),
+            entry(Some(TimeDelta::weeks(-2)), None),
+        ];
+        let report = ExpiryReport::from_entries(&entries, Local::now());
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.without_expiry, 1);
+        assert_eq!(
+            report
+                .expired
+                .iter()
+                .map(|entry| (entry.number, entry.service.as_deref()))
+                .collect::<Vec<_>>(),
+            [(1, Some("github")), (4, None)]
+        );
+        assert!(!report.render().contains("secret"));
+    }
+}
@@ -0,0 +1,164 @@
+use serde::Serialize;
+
+use crate::i18n::tr;
+
+/// A range of characters in a password, end exclusive
+///
+/// Counted in chars, not bytes, so it lines up with multibyte input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Get the number of characters in the span
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Check whether the span covers no characters
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Render a masked password and a line of `^` under the span
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The number of characters in the password
+    ///
+    /// # Returns
+    ///
+    /// Returns the masked password and the marker line, both one column per character
+    pub fn markers(&self, length: usize) -> (String, String) {
+        (
+            "*".repeat(length.max(self.end)),
+            format!("{}{}", " ".repeat(self.start), "^".repeat(self.len())),
+        )
+    }
+}
+
+/// A weakness found while analyzing a password
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Warning {
+    TooShort,
+    NoLowercase,
+    NoUppercase,
+    NoDigits,
+    NoSpecial,
+    /// Part of the password appears in the common passwords list
+    CommonPattern {
+        matched_len: usize,
+        span: Span,
+    },
+    /// The password contains a term of a banned words list
+    BannedWord {
+        list: String,
+    },
+    /// The same character three or more times in a row
+    Repetition {
+        span: Span,
+    },
+    /// Three or more letters or digits counting up or down, like `abc` or `321`
+    Sequence {
+        span: Span,
+    },
+    /// A year from 1900 to 2099
+    Year {
+        span: Span,
+    },
+    LowObservedEntropy,
+    SimilarToPrevious,
+}
+
+/// An improvement suggested after analyzing a password
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Suggestion {
+    IncreaseLength,
+    AddLowercase,
+    AddUppercase,
+    AddDigits,
+    AddSpecial,
+    AvoidSimpleSequences,
+    AvoidBannedWords,
+    AvoidRepetitions,
+    AvoidYears,
+    VaryCharacters,
+    AvoidSimilarPasswords,
+}
+
+impl Warning {
+    /// Get the translated message of the warning
+    ///
+    /// # Returns
+    ///
+    /// Returns the message in the current language
+    pub fn message(&self) -> String {
+        let key = match self {
+            Warning::TooShort => "password_too_short",
+            Warning::NoLowercase => "no_lowercase",
+            Warning::NoUppercase => "no_uppercase",
+            Warning::NoDigits => "no_digits",
+            Warning::NoSpecial => "no_special",
+            Warning::CommonPattern { .. } => "common_patterns",
+            Warning::BannedWord { list } => {
+                return tr(
+                    "commands.check.warnings.banned_words",
+                    &[("list", list.as_str())],
+                );
+            }
+            Warning::Repetition { .. } => "repetitions",
+            Warning::Sequence { .. } => "sequences",
+            Warning::Year { .. } => "years",
+            Warning::LowObservedEntropy => "low_observed_entropy",
+            Warning::SimilarToPrevious => "similar_to_previous",
+        };
+
+        tr(&format!("commands.check.warnings.{}", key), &[])
+    }
+
+    /// Get the characters of the password the warning is about
+    ///
+    /// # Returns
+    ///
+    /// Returns the span, or None if the warning is about the password as a whole
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Warning::CommonPattern { span, .. }
+            | Warning::Repetition { span }
+            | Warning::Sequence { span }
+            | Warning::Year { span } => Some(*span),
+            _ => None,
+        }
+    }
+}
+
+impl Suggestion {
+    /// Get the translated message of the suggestion
+    ///
+    /// # Returns
+    ///
+    /// Returns the message in the current language
+    pub fn message(&self) -> String {
+        let key = match self {
+            Suggestion::IncreaseLength => "password_to_short",
+            Suggestion::AddLowercase => "add_lowercase",
+            Suggestion::AddUppercase => "add_uppercase",
+            Suggestion::AddDigits => "add_digits",
+            Suggestion::AddSpecial => "add_special",
+            Suggestion::AvoidSimpleSequences => "avoid_simple_sequences",
+            Suggestion::AvoidBannedWords => "avoid_banned_words",
+            Suggestion::AvoidRepetitions => "avoid_repetitions",
+            Suggestion::AvoidYears => "avoid_years",
+            Suggestion::VaryCharacters => "vary_characters",
+            Suggestion::AvoidSimilarPasswords => "avoid_similar_passwords",
+        };
+
+        tr(&format!("commands.check.suggestions.{}", key), &[])
+    }
+}
@@ -1,19 +1,334 @@
-use std::{fs::OpenOptions, io::Write, path::Path, sync::OnceLock};
+use std::{collections::BTreeMap, fs::OpenOptions, io::Write, path::Path, sync::OnceLock};
 
-use chrono::Local;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chrono::{Local, SecondsFormat};
+use clap::ValueEnum;
+use ring::digest::{SHA256, digest};
 use ring::hkdf;
 use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
 
-use crate::CONFIG;
+use crate::config::DeterministicConfig;
 use crate::errors::GeneratorError;
-use crate::logging::LoggingManager;
-
-const CHARSET: &[u8] =
+use crate::logging::{LoggingManager, Redactable};
+use crate::password::expiry;
+use crate::password::health_check::{HealthCheck, VerificationReport};
+use crate::password::policy::Policy;
+use crate::password::request::PasswordRequest;
+use crate::progress::{BulkOutcome, Cancellation, ProgressReporter, run_bulk};
+use crate::{CONFIG, GenerateArgs, PasswordMode};
+
+pub(crate) const CHARSET: &[u8] =
     b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-_=+";
 
+pub(crate) const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub(crate) const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+pub(crate) const DIGITS: &[u8] = b"0123456789";
+pub(crate) const SPECIAL: &[u8] = b"!@#$%^&*()-_=+";
+const ALNUM: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Patterns shipped with kdguard, used as `--pattern @name`
+///
+/// Entries of the `[patterns]` config table with the same name take precedence.
+pub const BUILTIN_PATTERNS: [(&str, &str); 3] = [
+    ("pin6", "DDDDDD"),
+    (
+        "strong16",
+        "ULDS{any}{any}{any}{any}{any}{any}{any}{any}{any}{any}{any}{any}",
+    ),
+    ("wifi", "LLLLDDDDLLLLDDDD"),
+];
+
+/// Length of deterministic passwords
+pub(crate) const DETERMINISTIC_LENGTH: usize = 20;
+
+/// Memory of the Argon2id stretching when the config sets none, 64 MiB
+pub const DEFAULT_ARGON2_MEMORY_KIB: u32 = 64 * 1024;
+
+/// Passes of the Argon2id stretching when the config sets none
+pub const DEFAULT_ARGON2_ITERATIONS: u32 = 3;
+
+/// How the seed is turned into a key before the passwords are expanded from it,
+/// `--kdf` or `deterministic.kdf`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Kdf {
+    /// HKDF-SHA256 only, fast, the derivation of earlier versions
+    #[default]
+    Hkdf,
+    /// Argon2id first, slow and memory-hard against guessing the seed
+    #[serde(alias = "argon2")]
+    #[value(alias = "argon2")]
+    Argon2id,
+}
+
+/// Symbols inserted into phrases, the special characters without the word separator
+const PHRASE_SYMBOLS: &[u8] = b"!@#$%^&*()_=+";
+
+/// Where the digits and symbols of `--phrase-digits` and `--phrase-symbols` go
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum InsertStyle {
+    /// Between two words
+    #[default]
+    Between,
+    /// After the last word
+    Append,
+    /// Before, between, or after the words
+    RandomPosition,
+}
+
+/// Digits and symbols added to a phrase to meet site rules
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PhraseOptions {
+    pub digits: usize,
+    pub symbols: usize,
+    pub style: InsertStyle,
+}
+
+impl PhraseOptions {
+    /// Check whether any characters are inserted
+    pub fn inserts(&self) -> bool {
+        self.digits > 0 || self.symbols > 0
+    }
+}
+
+/// Words of the English wordlist a seed fingerprint is made of, 10 bits each
+const FINGERPRINT_WORDS: usize = 1024;
+
+/// Guesses per second assumed for crack time estimates, a fast offline attack on a GPU rig
+const GUESSES_PER_SECOND: f64 = 1e10;
+
+/// Get the characters a pattern placeholder is replaced with
+///
+/// # Arguments
+///
+/// * `c`: The pattern character, one of U, L, D, S
+///
+/// # Returns
+///
+/// Returns the charset, or None if the character is not a placeholder
+fn pattern_charset(c: char) -> Option<&'static [u8]> {
+    match c {
+        'U' => Some(UPPERCASE),
+        'L' => Some(LOWERCASE),
+        'D' => Some(DIGITS),
+        'S' => Some(SPECIAL),
+        _ => None,
+    }
+}
+
+/// Check whether a character is typed with the left hand on a QWERTY keyboard
+///
+/// # Returns
+///
+/// Returns the hand, or None if the character is not on the keyboard
+fn is_left_hand(c: char) -> Option<bool> {
+    const LEFT: &str = "qwertasdfgzxcvb12345!@#$%`~";
+    const RIGHT: &str = "yuiophjklnm67890^&*()-_=+[]{};:'\",.<>/?\\|";
+    let c = c.to_ascii_lowercase();
+    if LEFT.contains(c) {
+        Some(true)
+    } else if RIGHT.contains(c) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Check whether a character needs Shift on a QWERTY keyboard
+fn is_shifted(c: char) -> bool {
+    c.is_ascii_uppercase() || "~!@#$%^&*()_+{}|:\"<>?".contains(c)
+}
+
+/// Uppercase the first letter of a word
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Get the charset of a long-form pattern alias
+///
+/// # Arguments
+///
+/// * `name`: The alias without braces, e.g. `upper`
+///
+/// # Returns
+///
+/// Returns the charset, or None if there is no such alias
+fn alias_charset(name: &str) -> Option<&'static [u8]> {
+    match name {
+        "upper" => Some(UPPERCASE),
+        "lower" => Some(LOWERCASE),
+        "digit" => Some(DIGITS),
+        "special" => Some(SPECIAL),
+        "alnum" => Some(ALNUM),
+        "any" => Some(CHARSET),
+        _ => None,
+    }
+}
+
+/// A pattern that can be used by name, built in or from the `[patterns]` config table
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedPattern {
+    pub name: String,
+    pub pattern: String,
+    pub custom: bool,
+}
+
 pub struct Generator;
 
 impl Generator {
+    /// Range of lengths accepted in random mode
+    pub const LENGTH_RANGE: std::ops::RangeInclusive<usize> = 8..=64;
+
+    /// Range of word counts accepted in phrase mode
+    pub const WORDS_RANGE: std::ops::RangeInclusive<usize> = 3..=20;
+
+    /// Range of digits and of symbols that can be inserted into a phrase
+    pub const PHRASE_INSERT_RANGE: std::ops::RangeInclusive<usize> = 0..=10;
+
+    /// Range of candidates a typeable random password is picked from
+    pub const TYPEABLE_CANDIDATES_RANGE: std::ops::RangeInclusive<usize> = 2..=64;
+
+    /// Check that a pattern is not empty and only uses U, L, D, S, and aliases
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern to check, or `@name` of a named pattern
+    /// * `custom`: The `[patterns]` config table
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the pattern can be generated from, else returns an error
+    pub fn validate_pattern(
+        pattern: &str,
+        custom: &BTreeMap<String, String>,
+    ) -> Result<(), GeneratorError> {
+        Self::pattern_charsets(Self::resolve_pattern(pattern, custom)?).map(|_| ())
+    }
+
+    /// Look up a named pattern
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern, `@name` is looked up and anything else returned as it is
+    /// * `custom`: The `[patterns]` config table, taking precedence over the built-in ones
+    ///
+    /// # Returns
+    ///
+    /// Returns the pattern, else returns an error if there is no pattern of that name
+    pub fn resolve_pattern<'a>(
+        pattern: &'a str,
+        custom: &'a BTreeMap<String, String>,
+    ) -> Result<&'a str, GeneratorError> {
+        let Some(name) = pattern.strip_prefix('@') else {
+            return Ok(pattern);
+        };
+
+        custom
+            .get(name)
+            .map(String::as_str)
+            .or_else(|| {
+                BUILTIN_PATTERNS
+                    .iter()
+                    .find(|(builtin, _)| *builtin == name)
+                    .map(|(_, pattern)| *pattern)
+            })
+            .ok_or_else(|| {
+                LoggingManager::error(&format!("Unknown named pattern: {}", name));
+                GeneratorError::UnknownNamedPattern(name.to_string())
+            })
+    }
+
+    /// List the built-in and configured named patterns
+    ///
+    /// # Arguments
+    ///
+    /// * `custom`: The `[patterns]` config table
+    ///
+    /// # Returns
+    ///
+    /// Returns the patterns sorted by name, configured ones replacing built-in ones
+    pub fn named_patterns(custom: &BTreeMap<String, String>) -> Vec<NamedPattern> {
+        let mut patterns: BTreeMap<&str, NamedPattern> = BTreeMap::new();
+        for (name, pattern) in BUILTIN_PATTERNS {
+            patterns.insert(
+                name,
+                NamedPattern {
+                    name: name.to_string(),
+                    pattern: pattern.to_string(),
+                    custom: false,
+                },
+            );
+        }
+        for (name, pattern) in custom {
+            patterns.insert(
+                name,
+                NamedPattern {
+                    name: name.clone(),
+                    pattern: pattern.clone(),
+                    custom: true,
+                },
+            );
+        }
+        patterns.into_values().collect()
+    }
+
+    /// Split a pattern into the charsets of its positions
+    ///
+    /// A position is one of U, L, D, S, or a long-form alias in braces such as `{alnum}`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern, named patterns must be resolved first
+    ///
+    /// # Returns
+    ///
+    /// Returns one charset per generated character, else returns an error
+    pub(crate) fn pattern_charsets(pattern: &str) -> Result<Vec<&'static [u8]>, GeneratorError> {
+        if pattern.is_empty() {
+            return Err(GeneratorError::EmptyPattern);
+        }
+
+        let mut charsets = Vec::with_capacity(pattern.len());
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '{' {
+                charsets.push(pattern_charset(c).ok_or_else(|| {
+                    LoggingManager::error(&format!(
+                        "Invalid pattern character: {}. Only U, L, D, S, and aliases are allowed",
+                        Redactable(c)
+                    ));
+                    GeneratorError::InvalidPatternCharacter(c)
+                })?);
+                continue;
+            }
+
+            let mut name = String::new();
+            let closed = chars.by_ref().any(|c| {
+                if c == '}' {
+                    return true;
+                }
+                name.push(c);
+                false
+            });
+            if !closed {
+                LoggingManager::error("Pattern alias is missing its closing brace");
+                return Err(GeneratorError::UnclosedPatternAlias(name));
+            }
+            charsets.push(alias_charset(&name).ok_or_else(|| {
+                LoggingManager::error(&format!("Unknown pattern alias: {{{}}}", Redactable(&name)));
+                GeneratorError::UnknownPatternAlias(name)
+            })?);
+        }
+
+        Ok(charsets)
+    }
+
     /// Generate random password
     ///
     /// # Arguments
@@ -24,12 +339,32 @@ impl Generator {
     ///
     /// Returns the generated password as String, else returns an error
     pub fn generate_random_password(length: usize) -> Result<String, GeneratorError> {
+        PasswordRequest::random()
+            .length(length)
+            .build()
+            .map(|generated| generated.password)
+    }
+
+    /// Generate a random password from the characters of a charset
+    ///
+    /// Draws again until every character class is in the password, so the charset must
+    /// keep at least one character of each.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: length of a password
+    /// * `charset`: The characters to pick from
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password as String, else returns an error
+    pub(crate) fn random_password(length: usize, charset: &[u8]) -> Result<String, GeneratorError> {
         LoggingManager::info(&format!(
             "Generating random password with length: {}",
             length
         ));
 
-        if !(8..=64).contains(&length) {
+        if !Self::LENGTH_RANGE.contains(&length) {
             let error = format!("Password length must be between 8 and 64, got: {}", length);
             LoggingManager::error(&error);
             return Err(GeneratorError::InvalidLength(error));
@@ -49,8 +384,8 @@ impl Generator {
                     GeneratorError::RandomBytesError(error)
                 })?;
                 let random_u32 = u32::from_be_bytes(bytes);
-                let idx = (random_u32 as usize) % CHARSET.len();
-                password.push(CHARSET[idx] as char);
+                let idx = (random_u32 as usize) % charset.len();
+                password.push(charset[idx] as char);
             }
 
             if Self::is_valid_password(&password) {
@@ -66,50 +401,163 @@ impl Generator {
         Err(GeneratorError::MaxRetriesExceeded)
     }
 
-    /// Generate pattern based password
+    /// Generate random passwords and keep the one that is easiest to type
+    ///
+    /// Every candidate comes from the CSPRNG like any random password. Picking one of
+    /// `candidates` costs at most log2(`candidates`) bits of entropy, which the caller
+    /// has to account for.
     ///
     /// # Arguments
     ///
-    /// * `pattern`: The pattern to generate the password from
+    /// * `length`: length of a password
+    /// * `charset`: The characters to pick from
+    /// * `candidates`: The number of passwords to choose from
     ///
     /// # Returns
     ///
-    /// Returns the generated password as String, else returns an error
-    pub fn generate_pattern_password(pattern: &str) -> Result<String, GeneratorError> {
-        LoggingManager::info(&format!(
-            "Generating pattern password with pattern: {}",
-            pattern
-        ));
+    /// Returns the candidate with the lowest `typeability_cost`, else returns an error
+    pub(crate) fn typeable_password(
+        length: usize,
+        charset: &[u8],
+        candidates: usize,
+    ) -> Result<String, GeneratorError> {
+        let mut best: Option<(u32, String)> = None;
+        for _ in 0..candidates.max(1) {
+            let password = Self::random_password(length, charset)?;
+            let cost = Self::typeability_cost(&password);
+            if best.as_ref().is_none_or(|(lowest, _)| cost < *lowest) {
+                best = Some((cost, password));
+            }
+        }
+        best.map(|(_, password)| password)
+            .ok_or(GeneratorError::MaxRetriesExceeded)
+    }
 
-        if pattern.is_empty() {
-            LoggingManager::error("Pattern cannot be empty");
-            return Err(GeneratorError::EmptyPattern);
+    /// Rate how awkward a password is to type on a QWERTY keyboard
+    ///
+    /// Each character typed with the same hand as the one before costs 1, each character
+    /// that needs Shift costs 1, and each character repeating the one before costs 3.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to rate
+    ///
+    /// # Returns
+    ///
+    /// Returns the cost, lower is easier to type
+    pub fn typeability_cost(password: &str) -> u32 {
+        let mut cost = 0;
+        let mut previous: Option<char> = None;
+        for c in password.chars() {
+            if is_shifted(c) {
+                cost += 1;
+            }
+            if let Some(previous) = previous {
+                if previous == c {
+                    cost += 3;
+                } else if let (Some(left), Some(previous_left)) =
+                    (is_left_hand(c), is_left_hand(previous))
+                    && left == previous_left
+                {
+                    cost += 1;
+                }
+            }
+            previous = Some(c);
         }
+        cost
+    }
 
-        const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
-        const DIGITS: &[u8] = b"0123456789";
-        const SPECIAL: &[u8] = b"!@#$%^&*()-_=+";
+    /// Pick a uniformly distributed random index below a bound
+    ///
+    /// Values from the biased top of the `u32` range are drawn again.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random number generator
+    /// * `bound`: The exclusive upper bound, at least 1
+    ///
+    /// # Returns
+    ///
+    /// Returns the index, else returns an error
+    fn random_index(rng: &SystemRandom, bound: usize) -> Result<usize, GeneratorError> {
+        let bound = bound as u64;
+        let zone = (u64::from(u32::MAX) + 1) / bound * bound;
+
+        loop {
+            let mut bytes = [0u8; 4];
+            rng.fill(&mut bytes).map_err(|e| {
+                let error = format!("Failed to fill random bytes: {}", e);
+                LoggingManager::error(&error);
+                GeneratorError::RandomBytesError(error)
+            })?;
+            let value = u64::from(u32::from_be_bytes(bytes));
+            if value < zone {
+                return Ok((value % bound) as usize);
+            }
+        }
+    }
 
-        let mut password = String::with_capacity(pattern.len());
+    /// Shuffle items in place with a Fisher–Yates shuffle
+    ///
+    /// Uses the system CSPRNG, so the order can neither be reproduced nor predicted.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The items to shuffle
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, else returns an error
+    pub fn shuffle<T>(items: &mut [T]) -> Result<(), GeneratorError> {
         let rng = SystemRandom::new();
 
-        for c in pattern.chars() {
-            let charset: &[u8] = match c {
-                'U' => UPPERCASE,
-                'L' => LOWERCASE,
-                'D' => DIGITS,
-                'S' => SPECIAL,
-                _ => {
-                    let error = format!(
-                        "Invalid pattern character: '{}'. Only U, L, D, S are allowed",
-                        c
-                    );
-                    LoggingManager::error(&error);
-                    return Err(GeneratorError::InvalidPatternCharacter(c));
-                }
-            };
+        for i in (1..items.len()).rev() {
+            let j = Self::random_index(&rng, i + 1)?;
+            items.swap(i, j);
+        }
+
+        Ok(())
+    }
+
+    /// Generate pattern based password
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern to generate the password from
+    /// * `shuffle`: Whether to permute the generated characters, so the class of a
+    ///   position does not follow from the pattern
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password as String, else returns an error
+    pub fn generate_pattern_password(
+        pattern: &str,
+        shuffle: bool,
+    ) -> Result<String, GeneratorError> {
+        PasswordRequest::pattern(pattern)
+            .shuffle(shuffle)
+            .build()
+            .map(|generated| generated.password)
+    }
+
+    /// Generate a password with one character of each charset
+    ///
+    /// # Arguments
+    ///
+    /// * `charsets`: The charsets of the positions, see `pattern_charsets`
+    /// * `shuffle`: Whether to permute the generated characters
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password as String, else returns an error
+    pub(crate) fn pattern_password(
+        charsets: &[Vec<u8>],
+        shuffle: bool,
+    ) -> Result<String, GeneratorError> {
+        let mut password = String::with_capacity(charsets.len());
+        let rng = SystemRandom::new();
 
+        for charset in charsets {
             let mut bytes = [0u8; 4];
             rng.fill(&mut bytes).map_err(|e| {
                 let error = format!("Failed to fill random bytes: {}", e);
@@ -121,12 +569,18 @@ impl Generator {
             password.push(charset[idx] as char);
         }
 
+        if shuffle {
+            let mut chars: Vec<char> = password.chars().collect();
+            Self::shuffle(&mut chars)?;
+            password = chars.into_iter().collect();
+        }
+
         LoggingManager::info("Successfully generated pattern password");
         Ok(password)
     }
 
     /// Get cached wordlist for a given language
-    fn get_wordlist(lang: &str) -> &'static Vec<&'static str> {
+    pub(crate) fn get_wordlist(lang: &str) -> &'static Vec<&'static str> {
         static WORDLIST_EN: OnceLock<Vec<&'static str>> = OnceLock::new();
         static WORDLIST_DE: OnceLock<Vec<&'static str>> = OnceLock::new();
 
@@ -148,20 +602,49 @@ impl Generator {
 
     /// Generate phrase based password
     ///
+    /// When `options` inserts digits or symbols, the words are capitalized as well, so the
+    /// phrase has every character class sites usually ask for.
+    ///
+    /// # Arguments
+    ///
+    /// * `words_count`: Number of words to use in the phrase
+    /// * `options`: The digits and symbols to insert and where
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password phrase as String, else returns an error
+    pub fn generate_phrase_password(
+        words_count: usize,
+        options: &PhraseOptions,
+    ) -> Result<String, GeneratorError> {
+        PasswordRequest::phrase(words_count)
+            .phrase_options(*options)
+            .build()
+            .map(|generated| generated.password)
+    }
+
+    /// Generate a phrase from the wordlist of a language
+    ///
     /// # Arguments
     ///
     /// * `words_count`: Number of words to use in the phrase
+    /// * `options`: The digits and symbols to insert and where
+    /// * `lang`: The language of the wordlist
     ///
     /// # Returns
     ///
     /// Returns the generated password phrase as String, else returns an error
-    pub fn generate_phrase_password(words_count: usize) -> Result<String, GeneratorError> {
+    pub(crate) fn phrase_password(
+        words_count: usize,
+        options: &PhraseOptions,
+        lang: &str,
+    ) -> Result<String, GeneratorError> {
         LoggingManager::info(&format!(
             "Generating phrase password with {} words",
             words_count
         ));
 
-        if !(3..=20).contains(&words_count) {
+        if !Self::WORDS_RANGE.contains(&words_count) {
             LoggingManager::error(&format!(
                 "Word count must be between 3 and 20, got: {}",
                 words_count
@@ -169,7 +652,16 @@ impl Generator {
             return Err(GeneratorError::InvalidWordCount);
         }
 
-        let lang = CONFIG.language.lang.as_str();
+        if !Self::PHRASE_INSERT_RANGE.contains(&options.digits)
+            || !Self::PHRASE_INSERT_RANGE.contains(&options.symbols)
+        {
+            LoggingManager::error(&format!(
+                "Phrase digits and symbols must be between 0 and 10, got: {} and {}",
+                options.digits, options.symbols
+            ));
+            return Err(GeneratorError::InvalidPhraseInsertions);
+        }
+
         let words = Self::get_wordlist(lang);
 
         if words.is_empty() {
@@ -178,9 +670,9 @@ impl Generator {
         }
 
         let rng = SystemRandom::new();
-        let mut phrase = String::new();
+        let mut chosen = Vec::with_capacity(words_count);
 
-        for i in 0..words_count {
+        for _ in 0..words_count {
             let mut bytes = [0u8; 4];
             rng.fill(&mut bytes).map_err(|e| {
                 let error = format!("Failed to fill random bytes: {}", e);
@@ -189,17 +681,73 @@ impl Generator {
             })?;
             let random_u32 = u32::from_be_bytes(bytes);
             let idx = (random_u32 as usize) % words.len();
-
-            if i > 0 {
-                phrase.push('-');
-            }
-            phrase.push_str(words[idx]);
+            chosen.push(words[idx]);
         }
 
+        let phrase = Self::insert_into_phrase(&chosen, options)?;
+
         LoggingManager::info("Successfully generated phrase password");
         Ok(phrase)
     }
 
+    /// Join the words of a phrase and insert the requested digits and symbols
+    ///
+    /// The inserted characters form their own groups between the separators, so every
+    /// word stays whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `words`: The words of the phrase
+    /// * `options`: The digits and symbols to insert and where
+    ///
+    /// # Returns
+    ///
+    /// Returns the phrase, else returns an error
+    fn insert_into_phrase(
+        words: &[&str],
+        options: &PhraseOptions,
+    ) -> Result<String, GeneratorError> {
+        if !options.inserts() {
+            return Ok(words.join("-"));
+        }
+
+        let rng = SystemRandom::new();
+        let mut inserted = Vec::with_capacity(options.digits + options.symbols);
+        for _ in 0..options.digits {
+            inserted.push(DIGITS[Self::random_index(&rng, DIGITS.len())?] as char);
+        }
+        for _ in 0..options.symbols {
+            inserted.push(PHRASE_SYMBOLS[Self::random_index(&rng, PHRASE_SYMBOLS.len())?] as char);
+        }
+        Self::shuffle(&mut inserted)?;
+
+        // Slot i comes before word i, the last slot after the last word
+        let mut slots = vec![String::new(); words.len() + 1];
+        for c in inserted {
+            let slot = match options.style {
+                InsertStyle::Between if words.len() > 1 => {
+                    1 + Self::random_index(&rng, words.len() - 1)?
+                }
+                InsertStyle::Between | InsertStyle::Append => words.len(),
+                InsertStyle::RandomPosition => Self::random_index(&rng, words.len() + 1)?,
+            };
+            slots[slot].push(c);
+        }
+
+        let mut groups = Vec::with_capacity(words.len() * 2 + 1);
+        for (slot, word) in slots.iter().zip(words) {
+            if !slot.is_empty() {
+                groups.push(slot.clone());
+            }
+            groups.push(capitalize(word));
+        }
+        if let Some(last) = slots.last().filter(|slot| !slot.is_empty()) {
+            groups.push(last.clone());
+        }
+
+        Ok(groups.join("-"))
+    }
+
     /// Generate deterministic password from seed word
     ///
     /// # Arguments
@@ -207,6 +755,7 @@ impl Generator {
     /// * `seed`: The seed word to generate the password from
     /// * `salt`: Optional salt for additional entropy (default: "kdguard")
     /// * `service`: Optional service name to derive service-specific passwords
+    /// * `kdf`: How the seed is stretched, see `derive_key`
     ///
     /// # Returns
     ///
@@ -215,43 +764,144 @@ impl Generator {
         seed: &str,
         salt: Option<&str>,
         service: Option<&str>,
+        kdf: &DeterministicConfig,
+    ) -> Result<String, GeneratorError> {
+        let mut request = PasswordRequest::deterministic(seed).kdf(kdf.clone());
+        if let Some(salt) = salt {
+            request = request.salt(salt);
+        }
+        if let Some(service) = service {
+            request = request.service(service);
+        }
+        request.build().map(|generated| generated.password)
+    }
+
+    /// Derive the deterministic password of a service and counter
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: The seed word to generate the password from
+    /// * `salt`: Optional salt for additional entropy (default: "kdguard")
+    /// * `service`: Optional service name to derive service-specific passwords
+    /// * `counter`: The version of the service password, 1 for the first one
+    /// * `kdf`: How the seed is stretched, see `derive_key`
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password as String (always 20 characters), else returns an error
+    pub(crate) fn deterministic_password(
+        seed: &str,
+        salt: Option<&str>,
+        service: Option<&str>,
+        counter: u32,
+        kdf: &DeterministicConfig,
     ) -> Result<String, GeneratorError> {
-        const DEFAULT_LENGTH: usize = 20;
+        let key = Self::derive_key(seed, salt, kdf)?;
+        Self::expand_password(&key, service, counter)
+    }
 
+    /// Turn a seed into the key the deterministic passwords are expanded from
+    ///
+    /// With Argon2id the seed is stretched first, the SHA-256 of the salt is its salt as
+    /// Argon2 needs at least 8 bytes. The result goes through the same HKDF extraction
+    /// as a plain seed. The key only depends on the seed, the salt, and the KDF
+    /// settings, so it is derived once for many services.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: The seed word
+    /// * `salt`: Optional salt for additional entropy (default: "kdguard")
+    /// * `kdf`: The KDF and its Argon2id parameters
+    ///
+    /// # Returns
+    ///
+    /// Returns the key, else returns an error if the seed is empty or the parameters are invalid
+    pub fn derive_key(
+        seed: &str,
+        salt: Option<&str>,
+        kdf: &DeterministicConfig,
+    ) -> Result<hkdf::Prk, GeneratorError> {
         LoggingManager::info(&format!(
-            "Generating deterministic password (seed length: {}, salt: {}, service: {})",
+            "Deriving deterministic key (seed length: {}, salt: {}, kdf: {:?})",
             seed.len(),
             salt.is_some(),
-            service.is_some()
+            kdf.kdf
         ));
-
         if seed.is_empty() {
             LoggingManager::error("Seed cannot be empty");
             return Err(GeneratorError::EmptySeed);
         }
 
         let salt_bytes = salt.unwrap_or("kdguard").as_bytes();
-        let seed_bytes = seed.as_bytes();
-
         let salt_key = hkdf::Salt::new(hkdf::HKDF_SHA256, salt_bytes);
-        let prk = salt_key.extract(seed_bytes);
-
-        const MAX_RETRIES: u32 = 1000;
-        const OUTPUT_SIZE: usize = 32;
-
-        for retry in 0..MAX_RETRIES {
-            let mut output = [0u8; OUTPUT_SIZE];
-            let mut info = b"kdguard-password".to_vec();
-            if let Some(service_name) = service {
-                info.extend_from_slice(b"-");
-                info.extend_from_slice(service_name.as_bytes());
+        match kdf.kdf {
+            Kdf::Hkdf => Ok(salt_key.extract(seed.as_bytes())),
+            Kdf::Argon2id => {
+                let params =
+                    Params::new(kdf.memory_kib, kdf.iterations, 1, Some(32)).map_err(|e| {
+                        LoggingManager::error(&format!("Invalid Argon2id parameters: {}", e));
+                        GeneratorError::Argon2Error(e.to_string())
+                    })?;
+                let mut stretched = Zeroizing::new([0u8; 32]);
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(
+                        seed.as_bytes(),
+                        digest(&SHA256, salt_bytes).as_ref(),
+                        &mut *stretched,
+                    )
+                    .map_err(|e| {
+                        LoggingManager::error(&format!("Failed to stretch the seed: {}", e));
+                        GeneratorError::Argon2Error(e.to_string())
+                    })?;
+                Ok(salt_key.extract(&*stretched))
             }
-            info.extend_from_slice(b"-");
-            info.extend_from_slice(&retry.to_be_bytes());
-            let info_slice: &[u8] = &info;
-            let info_array = [info_slice];
+        }
+    }
+
+    /// Expand the password of a service from a derived key
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The key from `derive_key`
+    /// * `service`: Optional service name to derive service-specific passwords
+    /// * `counter`: The version of the service password, the first one has none in its info
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password as String (always 20 characters), else returns an error
+    fn expand_password(
+        key: &hkdf::Prk,
+        service: Option<&str>,
+        counter: u32,
+    ) -> Result<String, GeneratorError> {
+        LoggingManager::info(&format!(
+            "Generating deterministic password (service: {})",
+            service.is_some()
+        ));
+        if let Some(service) = service {
+            LoggingManager::trace(&format!("Service: {}", Redactable(service)));
+        }
+
+        const MAX_RETRIES: u32 = 1000;
+        const OUTPUT_SIZE: usize = 32;
 
-            let okm = prk.expand(&info_array, hkdf::HKDF_SHA256).map_err(|_| {
+        for retry in 0..MAX_RETRIES {
+            let mut output = [0u8; OUTPUT_SIZE];
+            let mut info = b"kdguard-password".to_vec();
+            if let Some(service_name) = service {
+                info.extend_from_slice(b"-");
+                info.extend_from_slice(service_name.as_bytes());
+            }
+            if counter > 1 {
+                info.extend_from_slice(b"-#");
+                info.extend_from_slice(&counter.to_be_bytes());
+            }
+            info.extend_from_slice(b"-");
+            info.extend_from_slice(&retry.to_be_bytes());
+            let info_slice: &[u8] = &info;
+            let info_array = [info_slice];
+
+            let okm = key.expand(&info_array, hkdf::HKDF_SHA256).map_err(|_| {
                 LoggingManager::error("Failed to expand HKDF");
                 GeneratorError::HkdfExpandError
             })?;
@@ -262,10 +912,10 @@ impl Generator {
                 GeneratorError::HkdfFillError
             })?;
 
-            let mut password = String::with_capacity(DEFAULT_LENGTH);
+            let mut password = String::with_capacity(DETERMINISTIC_LENGTH);
             let offset = (retry as usize * 13) % OUTPUT_SIZE;
 
-            for i in 0..DEFAULT_LENGTH {
+            for i in 0..DETERMINISTIC_LENGTH {
                 let byte_idx = (offset + i) % OUTPUT_SIZE;
                 let idx = (output[byte_idx] as usize) % CHARSET.len();
                 password.push(CHARSET[idx] as char);
@@ -286,6 +936,358 @@ impl Generator {
         Err(GeneratorError::MaxRetriesExceeded)
     }
 
+    /// Get a short fingerprint of a seed to spot typos in it
+    ///
+    /// Two words of the English wordlist picked by expanding the key of `derive_key` with
+    /// an info string of its own, e.g. `amber-cactus`. Checking a guessed seed against
+    /// the fingerprint costs as much as deriving its passwords, and with 10 bits per
+    /// word it tells at most 20 bits about the seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: The seed word
+    /// * `salt`: Optional salt for additional entropy (default: "kdguard")
+    /// * `kdf`: The KDF and its Argon2id parameters
+    ///
+    /// # Returns
+    ///
+    /// Returns the fingerprint, else returns an error if the seed is empty or the
+    /// parameters are invalid
+    pub fn seed_fingerprint(
+        seed: &str,
+        salt: Option<&str>,
+        kdf: &DeterministicConfig,
+    ) -> Result<String, GeneratorError> {
+        let key = Self::derive_key(seed, salt, kdf)?;
+        let mut output = [0u8; 32];
+        key.expand(&[b"kdguard-seed-fingerprint"], hkdf::HKDF_SHA256)
+            .map_err(|_| GeneratorError::HkdfExpandError)?
+            .fill(&mut output)
+            .map_err(|_| GeneratorError::HkdfFillError)?;
+
+        // The top 20 bits of the output
+        let bits = u32::from_be_bytes([0, output[0], output[1], output[2]]) >> 4;
+        let words = &Self::get_wordlist("en")[..FINGERPRINT_WORDS];
+        Ok(format!(
+            "{}-{}",
+            words[(bits >> 10) as usize],
+            words[(bits & 0x3ff) as usize]
+        ))
+    }
+
+    /// Get the fingerprint of the seed of the CLI arguments and compare it to the saved one
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The parsed generation arguments
+    ///
+    /// # Returns
+    ///
+    /// Returns the fingerprint and whether it differs from `deterministic.fingerprint`,
+    /// None outside of deterministic mode or without a seed
+    pub fn fingerprint_from_cli(cli: &GenerateArgs) -> Option<(String, bool)> {
+        if cli.mode != PasswordMode::Deterministic {
+            return None;
+        }
+        let seed = Zeroizing::new(std::env::var(cli.seed_env.as_deref()?).ok()?);
+        let fingerprint =
+            Self::seed_fingerprint(&seed, cli.salt.as_deref(), &Self::kdf_from_cli(cli)).ok()?;
+        LoggingManager::info(&format!("Seed fingerprint: {}", Redactable(&fingerprint)));
+
+        let mismatch = CONFIG
+            .deterministic
+            .fingerprint
+            .as_deref()
+            .is_some_and(|saved| !saved.trim().eq_ignore_ascii_case(&fingerprint));
+        if mismatch {
+            LoggingManager::warn("Seed fingerprint does not match the saved one");
+        }
+        Some((fingerprint, mismatch))
+    }
+
+    /// Estimate the entropy of the passwords a mode generates without generating one
+    ///
+    /// Uses the keyspace of the generator: the full charset per character for random and
+    /// deterministic passwords, the placeholder charsets for patterns, and the wordlist of
+    /// the configured language for phrases.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode`: The password mode
+    /// * `length`: The length of random passwords
+    /// * `pattern`: The pattern of pattern passwords
+    /// * `words`: The number of words of phrase passwords
+    ///
+    /// # Returns
+    ///
+    /// Returns the entropy in bits, or None if the pattern is empty or invalid
+    pub fn estimate_entropy(
+        mode: &PasswordMode,
+        length: usize,
+        pattern: &str,
+        words: usize,
+    ) -> Option<f64> {
+        let charset_bits = (CHARSET.len() as f64).log2();
+        match mode {
+            PasswordMode::Random => Some(length as f64 * charset_bits),
+            PasswordMode::Pattern => Self::pattern_entropy(pattern, &CONFIG.patterns),
+            PasswordMode::Phrase => {
+                let wordlist = Self::get_wordlist(CONFIG.language.lang.as_str());
+                Some(words as f64 * (wordlist.len().max(1) as f64).log2())
+            }
+            PasswordMode::Deterministic => Some(DETERMINISTIC_LENGTH as f64 * charset_bits),
+        }
+    }
+
+    /// Estimate the entropy of a pattern
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern`: The pattern, or `@name` of a named pattern
+    /// * `custom`: The `[patterns]` config table
+    ///
+    /// # Returns
+    ///
+    /// Returns the entropy in bits, or None if the pattern is empty or invalid
+    pub fn pattern_entropy(pattern: &str, custom: &BTreeMap<String, String>) -> Option<f64> {
+        let pattern = Self::resolve_pattern(pattern, custom).ok()?;
+        let charsets = Self::pattern_charsets(pattern).ok()?;
+        Some(
+            charsets
+                .iter()
+                .map(|charset| (charset.len() as f64).log2())
+                .sum(),
+        )
+    }
+
+    /// Estimate the average time to guess a password by brute force
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy`: The entropy of the password in bits
+    ///
+    /// # Returns
+    ///
+    /// Returns the time in seconds to search half of the keyspace at `GUESSES_PER_SECOND`
+    pub fn crack_time_seconds(entropy: f64) -> f64 {
+        2f64.powf(entropy - 1.0) / GUESSES_PER_SECOND
+    }
+
+    /// Generate a password for the parsed generation arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The parsed generation arguments
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated password as String, else returns an error
+    pub fn generate_from_cli(cli: &GenerateArgs) -> Result<String, GeneratorError> {
+        PasswordRequest::from_cli(cli)?
+            .build()
+            .map(|generated| generated.password)
+    }
+
+    /// Split a list of services into unique service names
+    ///
+    /// # Arguments
+    ///
+    /// * `list`: One service per line
+    ///
+    /// # Returns
+    ///
+    /// Returns the services in their order and the number of empty or duplicate lines
+    pub fn parse_services(list: &str) -> (Vec<String>, usize) {
+        let mut services: Vec<String> = Vec::new();
+        let mut skipped = 0;
+        for line in list.lines().map(str::trim) {
+            if line.is_empty() || services.iter().any(|service| service == line) {
+                skipped += 1;
+            } else {
+                services.push(line.to_string());
+            }
+        }
+        (services, skipped)
+    }
+
+    /// Derive one deterministic password per service from the seed and salt of the CLI arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The parsed generation arguments
+    /// * `services`: The service names
+    ///
+    /// # Returns
+    ///
+    /// Returns the services with their passwords, else returns an error if not in deterministic mode
+    pub fn generate_for_services(
+        cli: &GenerateArgs,
+        services: &[String],
+    ) -> Result<Vec<(String, String)>, GeneratorError> {
+        if cli.mode != PasswordMode::Deterministic {
+            LoggingManager::error("A list of services was used outside of deterministic mode");
+            return Err(GeneratorError::ServicesMode);
+        }
+
+        let seed = Self::read_seed(
+            cli.seed_env
+                .as_deref()
+                .ok_or(GeneratorError::MissingSeedEnv)?,
+        )?;
+        LoggingManager::info(&format!(
+            "Generating deterministic passwords for {} services",
+            services.len()
+        ));
+        // A slow KDF only runs once for the whole list
+        let key = Self::derive_key(&seed, cli.salt.as_deref(), &Self::kdf_from_cli(cli))?;
+        services
+            .iter()
+            .map(|service| {
+                Self::expand_password(&key, Some(service), 1)
+                    .map(|password| (service.clone(), password))
+            })
+            .collect()
+    }
+
+    /// Get the KDF settings of the config with `--kdf` applied
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The parsed generation arguments
+    ///
+    /// # Returns
+    ///
+    /// Returns the KDF settings to derive with
+    pub(crate) fn kdf_from_cli(cli: &GenerateArgs) -> DeterministicConfig {
+        DeterministicConfig {
+            kdf: cli.kdf.unwrap_or(CONFIG.deterministic.kdf),
+            ..CONFIG.deterministic.clone()
+        }
+    }
+
+    /// Generate many passwords with progress reporting and cancellation
+    ///
+    /// On cancellation the passwords generated so far are returned, so callers can
+    /// still save the partial result.
+    ///
+    /// # Arguments
+    ///
+    /// * `count`: The number of passwords to generate
+    /// * `reporter`: The reporter receiving progress updates
+    /// * `cancellation`: The cancellation checked before every password
+    /// * `generate`: Generates a single password
+    ///
+    /// # Returns
+    ///
+    /// Returns the generated passwords and the outcome, else returns an error
+    pub fn generate_bulk(
+        count: usize,
+        reporter: &mut dyn ProgressReporter,
+        cancellation: &Cancellation,
+        mut generate: impl FnMut() -> Result<String, GeneratorError>,
+    ) -> Result<(Vec<String>, BulkOutcome), GeneratorError> {
+        let mut passwords = Vec::with_capacity(count);
+        let outcome = run_bulk(count, reporter, cancellation, |_| {
+            passwords.push(generate()?);
+            Ok(())
+        })?;
+
+        if outcome.cancelled {
+            LoggingManager::info(&format!(
+                "Password generation cancelled after {} of {} passwords",
+                outcome.completed, outcome.total
+            ));
+        }
+
+        Ok((passwords, outcome))
+    }
+
+    /// Verify the deterministic password for the parsed generation arguments against a policy
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The parsed generation arguments
+    /// * `policy`: The policy to verify against
+    ///
+    /// # Returns
+    ///
+    /// Returns the verification report, else returns an error if not in deterministic mode
+    pub fn verify_from_cli(
+        cli: &GenerateArgs,
+        policy: &Policy,
+    ) -> Result<VerificationReport, GeneratorError> {
+        if cli.mode != PasswordMode::Deterministic {
+            LoggingManager::error("--verify-policy used outside of deterministic mode");
+            return Err(GeneratorError::VerifyPolicyMode);
+        }
+
+        let seed = Self::read_seed(
+            cli.seed_env
+                .as_deref()
+                .ok_or(GeneratorError::MissingSeedEnv)?,
+        )?;
+        Self::verify_deterministic_password(
+            &seed,
+            cli.salt.as_deref(),
+            cli.service.as_deref(),
+            &Self::kdf_from_cli(cli),
+            policy,
+        )
+    }
+
+    /// Read the seed for deterministic generation from an environment variable
+    ///
+    /// # Arguments
+    ///
+    /// * `seed_env`: The name of the environment variable containing the seed
+    ///
+    /// # Returns
+    ///
+    /// Returns the seed, else returns an error if the variable is missing or empty
+    pub fn read_seed(seed_env: &str) -> Result<String, GeneratorError> {
+        match std::env::var(seed_env) {
+            Ok(seed) if !seed.is_empty() => Ok(seed),
+            _ => {
+                LoggingManager::error(&format!(
+                    "Environment variable {} not found",
+                    Redactable(seed_env)
+                ));
+                Err(GeneratorError::SeedEnvNotFound(seed_env.to_string()))
+            }
+        }
+    }
+
+    /// Derive a deterministic password and verify it against a policy
+    ///
+    /// The derived password is dropped after the verification, only the report is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: The seed word to derive the password from
+    /// * `salt`: Optional salt for additional entropy (default: "kdguard")
+    /// * `service`: Optional service name to derive service-specific passwords
+    /// * `kdf`: How the seed is stretched
+    /// * `policy`: The policy to verify the derived password against
+    ///
+    /// # Returns
+    ///
+    /// Returns the verification report, else returns an error
+    pub fn verify_deterministic_password(
+        seed: &str,
+        salt: Option<&str>,
+        service: Option<&str>,
+        kdf: &DeterministicConfig,
+        policy: &Policy,
+    ) -> Result<VerificationReport, GeneratorError> {
+        let password = Self::generate_deterministic_password(seed, salt, service, kdf)?;
+        let report = HealthCheck::verify_password(&password, policy);
+        LoggingManager::info(&format!(
+            "Verified deterministic password against policy (compliant: {})",
+            report.policy.is_compliant()
+        ));
+        Ok(report)
+    }
+
     /// Check valid password
     ///
     /// # Arguments
@@ -322,21 +1324,45 @@ impl Generator {
 
     /// Save passwords to a file
     ///
+    /// The format follows the file extension: `.csv` writes one row per password with the
+    /// metadata as columns, `.json` an object with `metadata` and `passwords`, anything else
+    /// plain text with the metadata in the header.
+    ///
     /// # Arguments
     ///
-    /// * `password`: The password string to save
-    /// * `output_path`: The path to save the password to
+    /// * `passwords`: The passwords to save
+    /// * `output_path`: The path to save the passwords to
+    /// * `metadata`: How the passwords were generated
     ///
     /// # Returns
     ///
     /// Returns Ok(()) if successful, otherwise an error
-    pub fn save_to_file(passwords: Vec<String>, output_path: &Path) -> Result<(), GeneratorError> {
+    pub fn save_to_file(
+        passwords: Vec<String>,
+        output_path: &Path,
+        metadata: &GenerationMetadata,
+    ) -> Result<(), GeneratorError> {
         LoggingManager::info(&format!(
             "Saving {} passwords to file: {}",
             passwords.len(),
-            output_path.display()
+            Redactable(output_path.display())
         ));
 
+        let content = match SaveFormat::from_path(output_path) {
+            SaveFormat::Plain => metadata.plain(&passwords),
+            SaveFormat::Csv => metadata.csv(&passwords),
+            SaveFormat::Json => serde_json::to_string_pretty(&SavedPasswords {
+                metadata: metadata.clone(),
+                passwords,
+            })
+            .map(|json| json + "\n")
+            .map_err(|e| {
+                let error = format!("Failed to serialize passwords: {}", e);
+                LoggingManager::error(&error);
+                GeneratorError::SaveFileError(error)
+            })?,
+        };
+
         let mut file = OpenOptions::new()
             .create(true)
             .write(true)
@@ -347,35 +1373,406 @@ impl Generator {
                 LoggingManager::error(&error);
                 GeneratorError::SaveFileError(error)
             })?;
-        let header = format!(
-            "Generated with kdguard\nDate: {}\nGenerated passwords:\n",
-            Local::now().format("%d.%m.%Y %H:%M:%S")
-        );
-        file.write_all(header.as_bytes()).map_err(|e| {
-            let error = format!("Failed to write header to file: {}", e);
+        file.write_all(content.as_bytes()).map_err(|e| {
+            let error = format!("Failed to write passwords to file: {}", e);
             LoggingManager::error(&error);
             GeneratorError::SaveFileError(error)
         })?;
-        for password in passwords {
-            file.write_all(password.as_bytes()).map_err(|e| {
-                let error = format!("Failed to write password to file: {}", e);
-                LoggingManager::error(&error);
-                GeneratorError::SaveFileError(error)
-            })?;
-            file.write_all(b"\n").map_err(|e| {
-                let error = format!("Failed to write newline to file: {}", e);
-                LoggingManager::error(&error);
-                GeneratorError::SaveFileError(error)
-            })?;
-        }
 
         LoggingManager::info("Successfully saved passwords to file");
         Ok(())
     }
+
+    /// Read the passwords of a file written by `save_to_file` with their metadata
+    ///
+    /// The format follows the file extension, like when saving. CSV files are read by their
+    /// column names, so files of earlier versions with fewer columns are read as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_path`: The path to the saved file
+    ///
+    /// # Returns
+    ///
+    /// Returns the passwords in the order of the file, else returns an error if the file
+    /// cannot be read or was not saved by kdguard
+    pub fn load_from_file(input_path: &Path) -> Result<Vec<SavedEntry>, GeneratorError> {
+        let read_error = |error: String| {
+            let error = format!("{}: {}", input_path.display(), error);
+            LoggingManager::error(&format!(
+                "Failed to read saved passwords from {}",
+                Redactable(input_path.display())
+            ));
+            GeneratorError::ReadFileError(error)
+        };
+        let content = std::fs::read_to_string(input_path).map_err(|e| read_error(e.to_string()))?;
+
+        match SaveFormat::from_path(input_path) {
+            SaveFormat::Plain => GenerationMetadata::from_plain(&content),
+            SaveFormat::Csv => GenerationMetadata::from_csv(&content),
+            SaveFormat::Json => serde_json::from_str::<SavedPasswords>(&content)
+                .map(|saved| {
+                    saved
+                        .passwords
+                        .into_iter()
+                        .map(|password| SavedEntry {
+                            password,
+                            metadata: saved.metadata.clone(),
+                        })
+                        .collect()
+                })
+                .map_err(|e| e.to_string()),
+        }
+        .map_err(read_error)
+    }
+}
+
+/// A password read back from a saved file with the metadata it was saved with
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedEntry {
+    pub password: String,
+    pub metadata: GenerationMetadata,
+}
+
+/// The format of a saved password file, picked by its extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SaveFormat {
+    Plain,
+    Csv,
+    Json,
+}
+
+impl SaveFormat {
+    fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("csv") => Self::Csv,
+            Some("json") => Self::Json,
+            _ => Self::Plain,
+        }
+    }
+}
+
+/// The content of a saved JSON password file
+#[derive(Serialize, Deserialize)]
+struct SavedPasswords {
+    metadata: GenerationMetadata,
+    passwords: Vec<String>,
+}
+
+/// How saved passwords were generated, written into the file with them
+///
+/// Only the parameters of the mode are set. Seeds are never part of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationMetadata {
+    pub mode: String,
+    pub length: Option<usize>,
+    pub words: Option<usize>,
+    pub pattern: Option<String>,
+    pub service: Option<String>,
+    pub count: usize,
+    /// RFC 3339 time of the generation
+    pub timestamp: String,
+    /// The kdguard version the passwords were generated with
+    pub version: String,
+    /// RFC 3339 time after which the passwords should no longer be used, `--expires`
+    pub expires: Option<String>,
+}
+
+/// Columns of a saved CSV password file
+const CSV_COLUMNS: [&str; 10] = [
+    "password",
+    "mode",
+    "length",
+    "words",
+    "pattern",
+    "service",
+    "count",
+    "timestamp",
+    "version",
+    "expires",
+];
+
+impl GenerationMetadata {
+    /// Create the metadata of a run with only the mode set
+    ///
+    /// # Arguments
+    ///
+    /// * `mode`: The password mode
+    /// * `count`: The number of generated passwords
+    ///
+    /// # Returns
+    ///
+    /// Returns the metadata stamped with the current time and version
+    pub fn new(mode: &PasswordMode, count: usize) -> Self {
+        Self {
+            mode: mode
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default(),
+            length: None,
+            words: None,
+            pattern: None,
+            service: None,
+            count,
+            timestamp: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            expires: None,
+        }
+    }
+
+    /// Create the metadata of a run from the command line
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The generation arguments
+    /// * `count`: The number of generated passwords
+    ///
+    /// # Returns
+    ///
+    /// Returns the metadata with the parameters of the mode
+    pub fn from_cli(cli: &GenerateArgs, count: usize) -> Self {
+        let mut metadata = Self::new(&cli.mode, count);
+        match cli.mode {
+            PasswordMode::Random => metadata.length = Some(cli.length),
+            PasswordMode::Pattern => {
+                metadata.pattern = cli
+                    .pattern
+                    .clone()
+                    .or_else(|| CONFIG.general.default_pattern.clone())
+            }
+            PasswordMode::Phrase => {
+                metadata.words = Some(cli.words.unwrap_or(CONFIG.general.default_words))
+            }
+            PasswordMode::Deterministic => {
+                metadata.length = Some(DETERMINISTIC_LENGTH);
+                metadata.service = cli.service.clone().filter(|service| service != "-");
+            }
+        }
+        metadata.expires = cli.expires.map(expiry::expires_in);
+        metadata
+    }
+
+    /// Get the expiry for the printed summary, e.g. `2025-01-12 14:00`
+    ///
+    /// # Returns
+    ///
+    /// Returns the expiry in local time, or None if the passwords do not expire
+    pub fn expires_display(&self) -> Option<String> {
+        self.expires.as_deref().and_then(expiry::format_expiry)
+    }
+
+    /// Get the metadata as labelled values, unset parameters left out
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![("Mode", self.mode.clone())];
+        if let Some(length) = self.length {
+            fields.push(("Length", length.to_string()));
+        }
+        if let Some(words) = self.words {
+            fields.push(("Words", words.to_string()));
+        }
+        if let Some(pattern) = &self.pattern {
+            fields.push(("Pattern", pattern.clone()));
+        }
+        if let Some(service) = &self.service {
+            fields.push(("Service", service.clone()));
+        }
+        fields.push(("Count", self.count.to_string()));
+        if let Some(expires) = &self.expires {
+            fields.push(("Expires", expires.clone()));
+        }
+        fields
+    }
+
+    /// Render a plain text file with the metadata in the header
+    fn plain(&self, passwords: &[String]) -> String {
+        let mut content = format!(
+            "Generated with kdguard {}\nDate: {}\n",
+            self.version, self.timestamp
+        );
+        for (label, value) in self.fields() {
+            content.push_str(&format!("{}: {}\n", label, value));
+        }
+        content.push_str("Generated passwords:\n");
+        for password in passwords {
+            content.push_str(password);
+            content.push('\n');
+        }
+        content
+    }
+
+    /// Render a CSV file with the metadata repeated in every row
+    fn csv(&self, passwords: &[String]) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        let metadata = [
+            self.mode.clone(),
+            optional(self.length.map(|length| length.to_string())),
+            optional(self.words.map(|words| words.to_string())),
+            optional(self.pattern.clone()),
+            optional(self.service.clone()),
+            self.count.to_string(),
+            self.timestamp.clone(),
+            self.version.clone(),
+            optional(self.expires.clone()),
+        ]
+        .map(|value| csv_field(&value))
+        .join(",");
+
+        let mut content = CSV_COLUMNS.join(",") + "\n";
+        for password in passwords {
+            content.push_str(&format!("{},{}\n", csv_field(password), metadata));
+        }
+        content
+    }
+
+    /// Read a plain text file written by `plain`
+    fn from_plain(content: &str) -> Result<Vec<SavedEntry>, String> {
+        let not_saved = || "not a password file saved by kdguard".to_string();
+        let (header, passwords) = content
+            .split_once("Generated passwords:\n")
+            .ok_or_else(not_saved)?;
+        let mut lines = header.lines();
+        let version = lines
+            .next()
+            .and_then(|line| line.strip_prefix("Generated with kdguard "))
+            .ok_or_else(not_saved)?;
+
+        let mut metadata = Self {
+            mode: String::new(),
+            length: None,
+            words: None,
+            pattern: None,
+            service: None,
+            count: 0,
+            timestamp: String::new(),
+            version: version.to_string(),
+            expires: None,
+        };
+        for (label, value) in lines.filter_map(|line| line.split_once(": ")) {
+            let value = value.to_string();
+            match label {
+                "Date" => metadata.timestamp = value,
+                "Mode" => metadata.mode = value,
+                "Length" => metadata.length = value.parse().ok(),
+                "Words" => metadata.words = value.parse().ok(),
+                "Pattern" => metadata.pattern = Some(value),
+                "Service" => metadata.service = Some(value),
+                "Count" => metadata.count = value.parse().unwrap_or_default(),
+                "Expires" => metadata.expires = Some(value),
+                _ => {}
+            }
+        }
+
+        Ok(passwords
+            .lines()
+            .map(|password| SavedEntry {
+                password: password.trim_end_matches('\r').to_string(),
+                metadata: metadata.clone(),
+            })
+            .collect())
+    }
+
+    /// Read a CSV file written by `csv`, each row with its own metadata
+    fn from_csv(content: &str) -> Result<Vec<SavedEntry>, String> {
+        let mut records = csv_records(content).into_iter();
+        let columns = records.next().unwrap_or_default();
+        let column = |name: &str| columns.iter().position(|column| column == name);
+        let password_column = column("password").ok_or("the password column is missing")?;
+        let [
+            _,
+            mode,
+            length,
+            words,
+            pattern,
+            service,
+            count,
+            timestamp,
+            version,
+            expires,
+        ] = CSV_COLUMNS.map(column);
+
+        Ok(records
+            .map(|record| {
+                let field = |index: Option<usize>| {
+                    index
+                        .and_then(|index| record.get(index))
+                        .filter(|value| !value.is_empty())
+                        .cloned()
+                };
+                let metadata = Self {
+                    mode: field(mode).unwrap_or_default(),
+                    length: field(length).and_then(|length| length.parse().ok()),
+                    words: field(words).and_then(|words| words.parse().ok()),
+                    pattern: field(pattern),
+                    service: field(service),
+                    count: field(count)
+                        .and_then(|count| count.parse().ok())
+                        .unwrap_or_default(),
+                    timestamp: field(timestamp).unwrap_or_default(),
+                    version: field(version).unwrap_or_default(),
+                    expires: field(expires),
+                };
+                SavedEntry {
+                    password: record.get(password_column).cloned().unwrap_or_default(),
+                    metadata,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Split CSV content into records of unquoted fields, the reverse of `csv_field`
+fn csv_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !quoted => {}
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Quote a CSV field if it contains a separator, quote, or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use chrono::TimeDelta;
+
     use super::*;
 
     #[test]
@@ -386,13 +1783,192 @@ mod tests {
         assert!(Generator::is_valid_password(&password));
     }
 
+    #[test]
+    fn test_typeability_cost() {
+        // Alternating hands without Shift or repeats
+        assert_eq!(Generator::typeability_cost("sldkfj"), 0);
+        // Every key on the left hand
+        assert_eq!(Generator::typeability_cost("qwerty"), 4);
+        // Shift on every character, but alternating hands
+        assert_eq!(Generator::typeability_cost("S)D(G"), 5);
+        // Repeats cost more than a same-hand pair
+        assert_eq!(Generator::typeability_cost("aa"), 3);
+        assert_eq!(Generator::typeability_cost("as"), 1);
+        assert_eq!(Generator::typeability_cost("a1p-"), 2);
+        assert_eq!(Generator::typeability_cost(""), 0);
+        assert!(Generator::typeability_cost("fjdksla;") < Generator::typeability_cost("F#D$S%A!"));
+    }
+
+    #[test]
+    fn test_typeable_password_picks_the_lowest_cost() {
+        for _ in 0..10 {
+            let password = Generator::typeable_password(16, CHARSET, 8).unwrap();
+            assert_eq!(password.len(), 16);
+            assert!(Generator::is_valid_password(&password));
+        }
+
+        // The best of many candidates is on average easier than a single one
+        let total = |candidates| -> u32 {
+            (0..40)
+                .map(|_| {
+                    Generator::typeability_cost(
+                        &Generator::typeable_password(16, CHARSET, candidates).unwrap(),
+                    )
+                })
+                .sum()
+        };
+        assert!(total(32) < total(1));
+    }
+
     #[test]
     fn test_generate_pattern_password() {
-        let password =
-            Generator::generate_pattern_password("UDDL").expect("Failed to generate password");
+        let password = Generator::generate_pattern_password("UDDL", false)
+            .expect("Failed to generate password");
         assert_eq!(password.len(), 4)
     }
 
+    /// Get the pattern character of the class a password character belongs to
+    fn class_of(c: char) -> char {
+        ['U', 'L', 'D', 'S']
+            .into_iter()
+            .find(|&class| pattern_charset(class).is_some_and(|set| set.contains(&(c as u8))))
+            .expect("Character outside of the pattern charsets")
+    }
+
+    #[test]
+    fn test_shuffled_pattern_password() {
+        let pattern = "ULLLLDDS";
+        let mut expected: Vec<char> = pattern.chars().collect();
+        expected.sort_unstable();
+
+        let mut first_classes = std::collections::HashSet::new();
+        for _ in 0..64 {
+            let password = Generator::generate_pattern_password(pattern, true).unwrap();
+            let mut classes: Vec<char> = password.chars().map(class_of).collect();
+            first_classes.insert(classes[0]);
+            classes.sort_unstable();
+            assert_eq!(classes, expected);
+        }
+        // Uppercase comes first only one time in eight, never every time in 64 runs
+        assert!(first_classes.len() > 1);
+
+        let unshuffled = Generator::generate_pattern_password(pattern, false).unwrap();
+        let classes: String = unshuffled.chars().map(class_of).collect();
+        assert_eq!(classes, pattern);
+    }
+
+    #[test]
+    fn test_shuffle_keeps_the_items() {
+        let mut items: Vec<u32> = (0..32).collect();
+        Generator::shuffle(&mut items).unwrap();
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..32).collect::<Vec<_>>());
+
+        let mut empty: [u32; 0] = [];
+        assert!(Generator::shuffle(&mut empty).is_ok());
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        let custom = BTreeMap::new();
+        assert!(Generator::validate_pattern("ULDS", &custom).is_ok());
+        assert!(matches!(
+            Generator::validate_pattern("", &custom),
+            Err(GeneratorError::EmptyPattern)
+        ));
+        assert!(matches!(
+            Generator::validate_pattern("ULx", &custom),
+            Err(GeneratorError::InvalidPatternCharacter('x'))
+        ));
+    }
+
+    #[test]
+    fn test_pattern_aliases() {
+        let charsets = Generator::pattern_charsets("U{lower}D{special}{alnum}S{any}").unwrap();
+        assert_eq!(
+            charsets,
+            [
+                UPPERCASE, LOWERCASE, DIGITS, SPECIAL, ALNUM, SPECIAL, CHARSET
+            ]
+        );
+        assert_eq!(
+            Generator::pattern_charsets("{upper}{digit}").unwrap(),
+            Generator::pattern_charsets("UD").unwrap()
+        );
+
+        assert!(matches!(
+            Generator::pattern_charsets("U{letter}D"),
+            Err(GeneratorError::UnknownPatternAlias(name)) if name == "letter"
+        ));
+        assert!(matches!(
+            Generator::pattern_charsets("UL{UPPER}"),
+            Err(GeneratorError::UnknownPatternAlias(name)) if name == "UPPER"
+        ));
+        assert!(matches!(
+            Generator::pattern_charsets("UL{alnum"),
+            Err(GeneratorError::UnclosedPatternAlias(name)) if name == "alnum"
+        ));
+        assert!(matches!(
+            Generator::pattern_charsets("U}"),
+            Err(GeneratorError::InvalidPatternCharacter('}'))
+        ));
+
+        let password =
+            Generator::generate_pattern_password("{digit}{digit}{upper}", false).unwrap();
+        assert_eq!(password.chars().map(class_of).collect::<String>(), "DDU");
+    }
+
+    #[test]
+    fn test_named_patterns() {
+        let mut custom = BTreeMap::new();
+        custom.insert("door".to_string(), "DDDD".to_string());
+        custom.insert("wifi".to_string(), "{alnum}{alnum}".to_string());
+
+        assert_eq!(
+            Generator::resolve_pattern("@pin6", &custom).unwrap(),
+            "DDDDDD"
+        );
+        assert_eq!(
+            Generator::resolve_pattern("@door", &custom).unwrap(),
+            "DDDD"
+        );
+        assert_eq!(
+            Generator::resolve_pattern("@wifi", &custom).unwrap(),
+            "{alnum}{alnum}"
+        );
+        assert_eq!(Generator::resolve_pattern("ULDS", &custom).unwrap(), "ULDS");
+        assert!(matches!(
+            Generator::resolve_pattern("@nope", &custom),
+            Err(GeneratorError::UnknownNamedPattern(name)) if name == "nope"
+        ));
+
+        for (name, pattern) in BUILTIN_PATTERNS {
+            assert!(
+                Generator::validate_pattern(pattern, &custom).is_ok(),
+                "{}",
+                name
+            );
+        }
+        let names: Vec<(String, bool)> = Generator::named_patterns(&custom)
+            .into_iter()
+            .map(|named| (named.name, named.custom))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("door".to_string(), true),
+                ("pin6".to_string(), false),
+                ("strong16".to_string(), false),
+                ("wifi".to_string(), true),
+            ]
+        );
+
+        let pin = Generator::pattern_entropy("@pin6", &custom).unwrap();
+        assert!((pin - 6.0 * 10f64.log2()).abs() < 1e-9);
+        assert_eq!(Generator::pattern_entropy("@nope", &custom), None);
+    }
+
     #[test]
     fn test_is_valid_password() {
         assert!(Generator::is_valid_password("(123P@ssw0rd"));
@@ -409,7 +1985,8 @@ mod tests {
 
     #[test]
     fn test_generate_phrase_password() {
-        let phrase = Generator::generate_phrase_password(5).expect("Failed to generate phrase");
+        let phrase = Generator::generate_phrase_password(5, &PhraseOptions::default())
+            .expect("Failed to generate phrase");
         let words: Vec<&str> = phrase.split('-').collect();
         assert_eq!(words.len(), 5);
         assert!(!phrase.is_empty());
@@ -417,18 +1994,86 @@ mod tests {
 
     #[test]
     fn test_error_generate_phrase_password() {
-        assert!(Generator::generate_phrase_password(2).is_err());
-        assert!(Generator::generate_phrase_password(21).is_err());
-        assert!(Generator::generate_phrase_password(3).is_ok());
-        assert!(Generator::generate_phrase_password(20).is_ok());
+        let plain = PhraseOptions::default();
+        assert!(Generator::generate_phrase_password(2, &plain).is_err());
+        assert!(Generator::generate_phrase_password(21, &plain).is_err());
+        assert!(Generator::generate_phrase_password(3, &plain).is_ok());
+        assert!(Generator::generate_phrase_password(20, &plain).is_ok());
+
+        let too_many = PhraseOptions {
+            digits: 11,
+            ..PhraseOptions::default()
+        };
+        assert!(matches!(
+            Generator::generate_phrase_password(4, &too_many),
+            Err(GeneratorError::InvalidPhraseInsertions)
+        ));
+    }
+
+    #[test]
+    fn test_phrase_insertions() {
+        let words = ["alpha", "bravo", "charlie", "delta"];
+        let is_inserted = |c: char| c.is_ascii_digit() || PHRASE_SYMBOLS.contains(&(c as u8));
+
+        for style in [
+            InsertStyle::Between,
+            InsertStyle::Append,
+            InsertStyle::RandomPosition,
+        ] {
+            let options = PhraseOptions {
+                digits: 3,
+                symbols: 2,
+                style,
+            };
+            let phrase = Generator::insert_into_phrase(&words, &options).unwrap();
+
+            assert_eq!(phrase.chars().filter(char::is_ascii_digit).count(), 3);
+            assert_eq!(
+                phrase
+                    .bytes()
+                    .filter(|b| PHRASE_SYMBOLS.contains(b))
+                    .count(),
+                2
+            );
+            assert!(Generator::is_valid_password(&phrase), "{}", phrase);
+
+            let kept: Vec<&str> = phrase
+                .split('-')
+                .filter(|group| !group.chars().all(is_inserted))
+                .collect();
+            assert_eq!(kept, ["Alpha", "Bravo", "Charlie", "Delta"], "{}", phrase);
+
+            match style {
+                InsertStyle::Between => {
+                    assert!(phrase.starts_with("Alpha-") && phrase.ends_with("-Delta"));
+                }
+                InsertStyle::Append => {
+                    assert!(phrase.starts_with("Alpha-Bravo-Charlie-Delta-"));
+                }
+                InsertStyle::RandomPosition => {}
+            }
+        }
+
+        let plain = Generator::insert_into_phrase(&words, &PhraseOptions::default()).unwrap();
+        assert_eq!(plain, "alpha-bravo-charlie-delta");
     }
 
     #[test]
     fn test_generate_deterministic_password() {
-        let password1 = Generator::generate_deterministic_password("test-seed", None, None)
-            .expect("Failed to generate deterministic password");
-        let password2 = Generator::generate_deterministic_password("test-seed", None, None)
-            .expect("Failed to generate deterministic password");
+        let password1 = Generator::generate_deterministic_password(
+            "test-seed",
+            None,
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
+        let password2 = Generator::generate_deterministic_password(
+            "test-seed",
+            None,
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
 
         assert_eq!(password1.len(), 20);
         assert_eq!(password1, password2);
@@ -437,25 +2082,47 @@ mod tests {
 
     #[test]
     fn test_deterministic_password_different_seeds() {
-        let password1 = Generator::generate_deterministic_password("seed1", None, None)
-            .expect("Failed to generate deterministic password");
-        let password2 = Generator::generate_deterministic_password("seed2", None, None)
-            .expect("Failed to generate deterministic password");
+        let password1 = Generator::generate_deterministic_password(
+            "seed1",
+            None,
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
+        let password2 = Generator::generate_deterministic_password(
+            "seed2",
+            None,
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
 
         assert_ne!(password1, password2);
     }
 
     #[test]
     fn test_deterministic_password_with_salt() {
-        let password1 =
-            Generator::generate_deterministic_password("test-seed", Some("salt1"), None)
-                .expect("Failed to generate deterministic password");
-        let password2 =
-            Generator::generate_deterministic_password("test-seed", Some("salt1"), None)
-                .expect("Failed to generate deterministic password");
-        let password3 =
-            Generator::generate_deterministic_password("test-seed", Some("salt2"), None)
-                .expect("Failed to generate deterministic password");
+        let password1 = Generator::generate_deterministic_password(
+            "test-seed",
+            Some("salt1"),
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
+        let password2 = Generator::generate_deterministic_password(
+            "test-seed",
+            Some("salt1"),
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
+        let password3 = Generator::generate_deterministic_password(
+            "test-seed",
+            Some("salt2"),
+            None,
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
 
         assert_eq!(password1, password2);
         assert_ne!(password1, password3);
@@ -463,23 +2130,391 @@ mod tests {
 
     #[test]
     fn test_deterministic_password_with_service() {
-        let password1 =
-            Generator::generate_deterministic_password("test-seed", None, Some("github"))
-                .expect("Failed to generate deterministic password");
-        let password2 =
-            Generator::generate_deterministic_password("test-seed", None, Some("github"))
-                .expect("Failed to generate deterministic password");
-        let password3 =
-            Generator::generate_deterministic_password("test-seed", None, Some("gitlab"))
-                .expect("Failed to generate deterministic password");
+        let password1 = Generator::generate_deterministic_password(
+            "test-seed",
+            None,
+            Some("github"),
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
+        let password2 = Generator::generate_deterministic_password(
+            "test-seed",
+            None,
+            Some("github"),
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
+        let password3 = Generator::generate_deterministic_password(
+            "test-seed",
+            None,
+            Some("gitlab"),
+            &DeterministicConfig::default(),
+        )
+        .expect("Failed to generate deterministic password");
 
         assert_eq!(password1, password2);
         assert_ne!(password1, password3);
     }
 
+    #[test]
+    fn test_verify_deterministic_password() {
+        crate::password::health_check::tests::init_lingua_for_tests();
+
+        let report = Generator::verify_deterministic_password(
+            "seed",
+            None,
+            Some("github"),
+            &DeterministicConfig::default(),
+            &Policy::nist(),
+        )
+        .unwrap();
+        let password = Generator::generate_deterministic_password(
+            "seed",
+            None,
+            Some("github"),
+            &DeterministicConfig::default(),
+        )
+        .unwrap();
+        let expected = HealthCheck::verify_password(&password, &Policy::nist());
+        assert_eq!(report.policy, expected.policy);
+        assert_eq!(report.score, expected.score);
+        assert_eq!(report.entropy, expected.entropy);
+        assert_eq!(report.length, 20);
+    }
+
+    #[test]
+    fn test_generate_bulk_cancellation_saves_partial_output() {
+        let cancellation = Cancellation::new();
+        let handle = cancellation.clone();
+        let (passwords, outcome) = Generator::generate_bulk(
+            1000,
+            &mut |progress: crate::progress::Progress| {
+                if progress.completed >= 30 {
+                    handle.cancel();
+                }
+            },
+            &cancellation,
+            || Generator::generate_random_password(16),
+        )
+        .unwrap();
+
+        assert!(outcome.cancelled);
+        assert_eq!(outcome.completed, 30);
+        assert_eq!(passwords.len(), 30);
+
+        let path = std::env::temp_dir().join(format!(
+            "kdguard_partial_generation_{}.txt",
+            std::process::id()
+        ));
+        Generator::save_to_file(
+            passwords.clone(),
+            &path,
+            &GenerationMetadata::new(&PasswordMode::Random, passwords.len()),
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for password in &passwords {
+            assert!(content.contains(password.as_str()));
+        }
+    }
+
+    fn metadata() -> GenerationMetadata {
+        let mut metadata = GenerationMetadata::new(&PasswordMode::Deterministic, 2);
+        metadata.length = Some(DETERMINISTIC_LENGTH);
+        metadata.service = Some("my, \"bank\"".to_string());
+        metadata
+    }
+
+    /// Save passwords to a temporary file with an extension and read it back
+    fn saved(extension: &str, passwords: &[&str]) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "kdguard_saved_metadata_{}.{}",
+            std::process::id(),
+            extension
+        ));
+        let passwords = passwords.iter().map(|p| p.to_string()).collect();
+        Generator::save_to_file(passwords, &path, &metadata()).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_saved_plain_file_has_a_metadata_header() {
+        let content = saved("txt", &["first", "second"]);
+        let (header, passwords) = content.split_once("Generated passwords:\n").unwrap();
+        assert_eq!(passwords, "first\nsecond\n");
+
+        let fields: HashMap<&str, &str> = header
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(": "))
+            .collect();
+        let metadata = metadata();
+        assert_eq!(
+            header.lines().next(),
+            Some(format!("Generated with kdguard {}", metadata.version).as_str())
+        );
+        assert_eq!(fields["Date"], metadata.timestamp);
+        assert_eq!(fields["Mode"], "deterministic");
+        assert_eq!(fields["Length"], "20");
+        assert_eq!(fields["Service"], "my, \"bank\"");
+        assert_eq!(fields["Count"], "2");
+        assert!(!fields.contains_key("Words"));
+    }
+
+    #[test]
+    fn test_saved_csv_file_has_metadata_columns() {
+        let content = saved("csv", &["first", "second"]);
+        let mut lines = content.lines();
+        assert_eq!(lines.next(), Some(CSV_COLUMNS.join(",").as_str()));
+
+        let metadata = metadata();
+        for (line, password) in lines.zip(["first", "second"]) {
+            let fields: Vec<&str> = line.rsplitn(5, ',').collect();
+            assert_eq!(fields[0], "");
+            assert_eq!(fields[1], metadata.version);
+            assert_eq!(fields[2], metadata.timestamp);
+            assert_eq!(fields[3], "2");
+            assert_eq!(
+                fields[4],
+                format!("{},deterministic,20,,,\"my, \"\"bank\"\"\"", password)
+            );
+        }
+    }
+
+    #[test]
+    fn test_saved_json_file_has_metadata_fields() {
+        let content = saved("JSON", &["first", "second"]);
+        let parsed: SavedPasswords = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed.passwords, ["first", "second"]);
+        assert_eq!(parsed.metadata.mode, metadata().mode);
+        assert_eq!(parsed.metadata.service, metadata().service);
+        assert_eq!(parsed.metadata.length, Some(DETERMINISTIC_LENGTH));
+        assert_eq!(parsed.metadata.words, None);
+        assert_eq!(parsed.metadata.count, 2);
+    }
+
+    #[test]
+    fn test_saved_files_round_trip() {
+        let mut expiring = metadata();
+        expiring.expires = Some(expiry::expires_in(TimeDelta::days(7)));
+        expiring.pattern = Some("LLDD".to_string());
+        let passwords = ["first", "a,\"b\"", "third"];
+
+        for extension in ["txt", "csv", "json"] {
+            for metadata in [metadata(), expiring.clone()] {
+                let path = std::env::temp_dir().join(format!(
+                    "kdguard_round_trip_{}.{}",
+                    std::process::id(),
+                    extension
+                ));
+                let saved = passwords.iter().map(|p| p.to_string()).collect();
+                Generator::save_to_file(saved, &path, &metadata).unwrap();
+                let entries = Generator::load_from_file(&path).unwrap();
+                std::fs::remove_file(&path).unwrap();
+
+                assert_eq!(
+                    entries
+                        .iter()
+                        .map(|entry| entry.password.as_str())
+                        .collect::<Vec<_>>(),
+                    passwords,
+                    "{}",
+                    extension
+                );
+                for entry in entries {
+                    assert_eq!(entry.metadata, metadata, "{}", extension);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_from_file_reads_csv_without_expires_column() {
+        let content = "password,mode,count\n\"x,y\",random,1\n";
+        let entries = GenerationMetadata::from_csv(content).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].password, "x,y");
+        assert_eq!(entries[0].metadata.mode, "random");
+        assert_eq!(entries[0].metadata.expires, None);
+        assert!(GenerationMetadata::from_plain("just\npasswords\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_services() {
+        let (services, skipped) =
+            Generator::parse_services("github\n\n  gitlab \ngithub\r\nmail\n   \n");
+        assert_eq!(services, ["github", "gitlab", "mail"]);
+        assert_eq!(skipped, 3);
+        assert_eq!(Generator::parse_services(""), (vec![], 0));
+    }
+
+    #[test]
+    fn test_read_seed_missing_env() {
+        let result = Generator::read_seed("KDGUARD_TEST_SEED_THAT_DOES_NOT_EXIST");
+        assert!(matches!(result, Err(GeneratorError::SeedEnvNotFound(_))));
+    }
+
     #[test]
     fn test_error_generate_deterministic_password() {
-        assert!(Generator::generate_deterministic_password("", None, None).is_err());
-        assert!(Generator::generate_deterministic_password("seed", None, None).is_ok());
+        assert!(
+            Generator::generate_deterministic_password(
+                "",
+                None,
+                None,
+                &DeterministicConfig::default()
+            )
+            .is_err()
+        );
+        assert!(
+            Generator::generate_deterministic_password(
+                "seed",
+                None,
+                None,
+                &DeterministicConfig::default()
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_estimate_entropy() {
+        let close = |actual: Option<f64>, expected: f64| {
+            let actual = actual.unwrap();
+            assert!(
+                (actual - expected).abs() < 0.01,
+                "{} != {}",
+                actual,
+                expected
+            );
+        };
+
+        // 76 characters per position
+        close(
+            Generator::estimate_entropy(&PasswordMode::Random, 16, "", 4),
+            16.0 * 76f64.log2(),
+        );
+        close(
+            Generator::estimate_entropy(&PasswordMode::Deterministic, 8, "", 4),
+            20.0 * 76f64.log2(),
+        );
+        // 26 * 26 * 10 * 14 combinations
+        close(
+            Generator::estimate_entropy(&PasswordMode::Pattern, 0, "ULDS", 4),
+            (26.0f64 * 26.0 * 10.0 * 14.0).log2(),
+        );
+        let wordlist = Generator::get_wordlist(CONFIG.language.lang.as_str()).len() as f64;
+        close(
+            Generator::estimate_entropy(&PasswordMode::Phrase, 0, "", 5),
+            5.0 * wordlist.log2(),
+        );
+
+        assert_eq!(
+            Generator::estimate_entropy(&PasswordMode::Pattern, 0, "", 4),
+            None
+        );
+        assert_eq!(
+            Generator::estimate_entropy(&PasswordMode::Pattern, 0, "ULX", 4),
+            None
+        );
+    }
+
+    #[test]
+    fn test_crack_time_seconds() {
+        // Half of a 2^41 keyspace at 10^10 guesses per second
+        let seconds = Generator::crack_time_seconds(41.0);
+        assert!((seconds - 2f64.powi(40) / 1e10).abs() < 1e-6);
+        assert!(Generator::crack_time_seconds(128.0) > 1e20);
+    }
+
+    #[test]
+    fn test_deterministic_known_answers() {
+        let seed = "correct horse battery staple";
+        let hkdf = DeterministicConfig::default();
+        // Small parameters keep the test fast, the derivation is the same
+        let argon2id = DeterministicConfig {
+            kdf: Kdf::Argon2id,
+            memory_kib: 256,
+            iterations: 2,
+            ..DeterministicConfig::default()
+        };
+        let derive = |salt, service, kdf| {
+            Generator::generate_deterministic_password(seed, salt, service, kdf).unwrap()
+        };
+
+        // Passwords of earlier versions must not change
+        assert_eq!(derive(None, None, &hkdf), "puD1zR$c3(zBCApWfLie");
+        assert_eq!(
+            derive(Some("2024"), Some("github"), &hkdf),
+            "Zp8FMb!XyBmEzkeAWdxR"
+        );
+        assert_eq!(derive(None, None, &argon2id), "%H-euB44sPDpfg1lWv9@");
+        assert_eq!(
+            derive(Some("2024"), Some("github"), &argon2id),
+            "Iz5*5O!9S#pM3)%aT5m4"
+        );
+
+        // The parameters are part of the derivation
+        let more_passes = DeterministicConfig {
+            iterations: 3,
+            ..argon2id.clone()
+        };
+        assert_ne!(
+            derive(None, None, &more_passes),
+            derive(None, None, &argon2id)
+        );
+    }
+
+    #[test]
+    fn test_seed_fingerprint() {
+        let hkdf = DeterministicConfig::default();
+        let argon2id = DeterministicConfig {
+            kdf: Kdf::Argon2id,
+            memory_kib: 256,
+            iterations: 2,
+            ..DeterministicConfig::default()
+        };
+        let fingerprint_of =
+            |seed, salt, kdf| Generator::seed_fingerprint(seed, salt, kdf).unwrap();
+        let seed = "correct horse battery staple";
+        let fingerprint = fingerprint_of(seed, None, &hkdf);
+        assert_eq!(fingerprint, fingerprint_of(seed, None, &hkdf));
+        assert_ne!(
+            fingerprint,
+            fingerprint_of("correct horse battery stapel", None, &hkdf)
+        );
+        assert!(Generator::seed_fingerprint("", None, &hkdf).is_err());
+
+        // Derived from the stretched key, so a guess costs as much as the passwords
+        let stretched = fingerprint_of(seed, None, &argon2id);
+        assert_ne!(stretched, fingerprint);
+        assert_eq!(stretched, fingerprint_of(seed, None, &argon2id));
+        assert_ne!(fingerprint_of(seed, Some("2024"), &argon2id), stretched);
+
+        // Both words come from the part of the wordlist that keeps it at 20 bits
+        let words = &Generator::get_wordlist("en")[..FINGERPRINT_WORDS];
+        let (first, second) = fingerprint.split_once('-').unwrap();
+        assert!(
+            words.contains(&first) && words.contains(&second),
+            "{}",
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn test_argon2id_rejects_invalid_parameters() {
+        let kdf = DeterministicConfig {
+            kdf: Kdf::Argon2id,
+            memory_kib: 4,
+            iterations: 1,
+            ..DeterministicConfig::default()
+        };
+        assert!(matches!(
+            Generator::generate_deterministic_password("seed", None, None, &kdf),
+            Err(GeneratorError::Argon2Error(_))
+        ));
     }
 }
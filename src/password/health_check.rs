@@ -1,482 +1,1978 @@
-use lingua_i18n_rs::prelude::Lingua;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs,
+    path::Path,
+    sync::{OnceLock, RwLock},
+};
+
+use inquire::Password;
+use serde::{Deserialize, Serialize};
 
 use crate::errors::HealthCheckError;
-use crate::logging::LoggingManager;
+use crate::i18n::tr;
+use crate::logging::{LoggingManager, Redactable};
+use crate::password::feedback::{Span, Suggestion, Warning};
+use crate::password::policy::{Policy, PolicyResult, PolicyRule, RuleResult};
+use crate::password::{BreachDatabase, Generator};
+use crate::progress::{BulkOutcome, Cancellation, ProgressReporter, run_bulk};
 
 pub struct HealthCheck;
 
 const COMMON_PASSWORDS: &str = include_str!("../../data/10k-most-common-passwords.txt");
 
-#[derive(Debug, Clone)]
+/// Minimum number of characters counting up or down to be reported as a sequence
+const MIN_SEQUENCE_LENGTH: usize = 3;
+
+/// Years reported in passwords
+const YEAR_RANGE: std::ops::RangeInclusive<u32> = 1900..=2099;
+
+/// Below this ratio of observed to maximum entropy a password is considered too repetitive
+const ENTROPY_RATIO_THRESHOLD: f64 = 0.5;
+/// Maximum number of points deducted for a low observed entropy ratio
+const MAX_ENTROPY_PENALTY: u32 = 20;
+/// Minimum length of a banned word to be matched as a substring
+const MIN_BANNED_SUBSTRING_LENGTH: usize = 4;
+/// Minimum length of a common password to be matched as a substring, shorter ones only match exactly
+const MIN_COMMON_SUBSTRING_LENGTH: usize = 5;
+/// Similarity ratio above which two passwords are considered too similar
+const SIMILARITY_THRESHOLD: f64 = 0.7;
+/// Characters accepted as word separators in passphrases
+const PASSPHRASE_SEPARATORS: &[char] = &['-', '_', ' ', '.', '+'];
+/// Minimum number of wordlist words for a password to be treated as a passphrase
+const MIN_PASSPHRASE_WORDS: usize = 3;
+/// Languages whose embedded wordlists are used for passphrase detection
+const PASSPHRASE_LANGUAGES: &[&str] = &["en", "de"];
+
+#[derive(Debug, Clone, Serialize)]
 pub struct PasswordScore {
     pub total: u32,
     pub length_score: u32,
     pub diversity_score: u32,
     pub complexity_score: u32,
     pub entropy_score: u32,
+    pub entropy_penalty: u32,
+    /// The weights the total was calculated with
+    pub weights: ScoringWeights,
 }
 
-#[derive(Debug, Clone)]
-pub struct PasswordAnalysis {
-    pub score: PasswordScore,
-    pub rating: String,
-    pub has_lowercase: bool,
-    pub has_uppercase: bool,
-    pub has_digit: bool,
-    pub has_special: bool,
-    pub length: usize,
+impl PasswordScore {
+    /// Highest length score, reached from 17 characters on
+    pub const MAX_LENGTH_SCORE: u32 = 25;
+    /// Highest diversity score, 5 per character class and 10 for all four
+    pub const MAX_DIVERSITY_SCORE: u32 = 30;
+    /// Highest complexity score, without common patterns and repetitions
+    pub const MAX_COMPLEXITY_SCORE: u32 = 25;
+    /// Highest entropy score, reached from 50 bits on
+    pub const MAX_ENTROPY_SCORE: u32 = 20;
+    /// Highest total, the sum of the component maxima
+    pub const MAX_TOTAL: u32 = Self::MAX_LENGTH_SCORE
+        + Self::MAX_DIVERSITY_SCORE
+        + Self::MAX_COMPLEXITY_SCORE
+        + Self::MAX_ENTROPY_SCORE;
+
+    /// Get the total on a scale from 0 to 100, whatever the weights of the components
+    ///
+    /// # Returns
+    ///
+    /// Returns the rounded percentage of `MAX_TOTAL`
+    pub fn normalized(&self) -> u32 {
+        (self.total.min(Self::MAX_TOTAL) * 100 + Self::MAX_TOTAL / 2) / Self::MAX_TOTAL
+    }
+}
+
+/// Multipliers of the score components, set in the `[scoring]` config section
+///
+/// The weighted sum is scaled back to `MAX_TOTAL`, so the rating bands keep their
+/// meaning whatever the weights. Equal weights give the plain sum of the components.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub length: f64,
+    pub diversity: f64,
+    pub complexity: f64,
     pub entropy: f64,
-    pub warnings: Vec<String>,
-    pub suggestions: Vec<String>,
 }
 
-impl HealthCheck {
-    /// Check a given password
+impl ScoringWeights {
+    pub const DEFAULT: ScoringWeights = ScoringWeights {
+        length: 1.0,
+        diversity: 1.0,
+        complexity: 1.0,
+        entropy: 1.0,
+    };
+
+    /// Get the weights by their config key
+    pub fn fields(&self) -> [(&'static str, f64); 4] {
+        [
+            ("length", self.length),
+            ("diversity", self.diversity),
+            ("complexity", self.complexity),
+            ("entropy", self.entropy),
+        ]
+    }
+
+    /// Calculate the total of the components before the entropy penalty
     ///
     /// # Arguments
     ///
-    /// * `password`: The password to check
-    /// * `detailed`: Whether to show detailed analysis
+    /// * `length`: The length score
+    /// * `diversity`: The diversity score
+    /// * `complexity`: The complexity score
+    /// * `entropy`: The entropy score
     ///
     /// # Returns
     ///
-    /// Returns the analysis of the password
-    pub fn check_password(password: &str, detailed: bool) -> Result<(), HealthCheckError> {
-        LoggingManager::info(&format!(
-            "Checking password health (detailed: {})",
-            detailed
-        ));
-
-        let analysis = Self::analyze_password(password);
-
-        LoggingManager::info(&format!(
-            "Password analysis completed: rating={}, score={}, length={}, entropy={:.2}",
-            analysis.rating, analysis.score.total, analysis.length, analysis.entropy
-        ));
-
-        Self::print_result(&analysis, detailed);
+    /// Returns the weighted sum on the scale of `PasswordScore::MAX_TOTAL`
+    fn total(&self, length: u32, diversity: u32, complexity: u32, entropy: u32) -> u32 {
+        let points = self.length * length as f64
+            + self.diversity * diversity as f64
+            + self.complexity * complexity as f64
+            + self.entropy * entropy as f64;
+        let max = self.length * PasswordScore::MAX_LENGTH_SCORE as f64
+            + self.diversity * PasswordScore::MAX_DIVERSITY_SCORE as f64
+            + self.complexity * PasswordScore::MAX_COMPLEXITY_SCORE as f64
+            + self.entropy * PasswordScore::MAX_ENTROPY_SCORE as f64;
+        (points / max * PasswordScore::MAX_TOTAL as f64).round() as u32
+    }
+}
 
-        Ok(())
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self::DEFAULT
     }
+}
 
-    /// Analyze a given password
+static BANNED_WORDS: OnceLock<BannedWords> = OnceLock::new();
+static COMMON_PASSWORDS_INDEX: OnceLock<Vec<&'static str>> = OnceLock::new();
+static DICTIONARIES: RwLock<Vec<Box<dyn DictionaryProvider>>> = RwLock::new(Vec::new());
+static SCORING_WEIGHTS: RwLock<ScoringWeights> = RwLock::new(ScoringWeights::DEFAULT);
+
+/// A source of words a password must neither be nor contain
+///
+/// The embedded common passwords list and the banned words list are built in,
+/// further sources like an offline breach database or a corporate list can be
+/// added with `HealthCheck::add_dictionary`.
+pub trait DictionaryProvider: Send + Sync {
+    /// Get the name of the source shown in warnings
+    fn name(&self) -> &str;
+
+    /// Find the longest entry a given password is or contains
     ///
     /// # Arguments
     ///
-    /// * `password`: The password to analyze
+    /// * `password`: The lowercase password to check
     ///
     /// # Returns
     ///
-    /// Returns the analysis of the password
-    pub fn analyze_password(password: &str) -> PasswordAnalysis {
-        let length = password.len();
-        let length_score = Self::calculate_length_score(length);
-        let (diversity_score, has_lowercase, has_uppercase, has_digit, has_special) =
-            Self::calculate_diversity_score(password);
-        let complexity_score = Self::calculate_complexity_score(password);
-        let (entropy_score, entropy) = Self::calculate_entropy_score(password);
-
-        let total = length_score + diversity_score + complexity_score + entropy_score;
-        let rating = Self::score_to_rating(total);
+    /// Returns the length of the matched entry in characters, otherwise None
+    fn find(&self, password: &str) -> Option<usize>;
+}
 
-        let mut warnings = Vec::new();
-        let mut suggestions = Vec::new();
+/// The embedded list of the 10k most common passwords
+pub struct CommonPasswords;
 
-        if length < 8 {
-            warnings.push(Lingua::t("commands.check.warnings.password_too_short", &[]).unwrap());
-            suggestions
-                .push(Lingua::t("commands.check.suggestions.password_to_short", &[]).unwrap());
-        }
+impl CommonPasswords {
+    /// Get the sorted index of the embedded list, built on first use
+    ///
+    /// The data file is already lowercase, so entries are borrowed as they are.
+    fn index() -> &'static [&'static str] {
+        COMMON_PASSWORDS_INDEX.get_or_init(|| {
+            let mut entries: Vec<&str> = COMMON_PASSWORDS
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            entries.sort_unstable();
+            entries.dedup();
+            entries
+        })
+    }
 
-        if !has_lowercase {
-            warnings.push(Lingua::t("commands.check.warnings.no_lowercase", &[]).unwrap());
-            suggestions.push(Lingua::t("commands.check.suggestions.add_lowercase", &[]).unwrap());
+    /// Locate the longest entry of the embedded list in a given password
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The lowercase password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns the characters of the password the entry covers, otherwise None
+    fn locate(password: &str) -> Option<Span> {
+        let index = Self::index();
+        if index.binary_search(&password).is_ok() {
+            return Some(Span::new(0, password.chars().count()));
         }
 
-        if !has_uppercase {
-            warnings.push(Lingua::t("commands.check.warnings.no_uppercase", &[]).unwrap());
-            suggestions.push(Lingua::t("commands.check.suggestions.add_uppercase", &[]).unwrap());
-        }
+        longest_entry_in(index, password, MIN_COMMON_SUBSTRING_LENGTH)
+    }
+}
 
-        if !has_digit {
-            warnings.push(Lingua::t("commands.check.warnings.no_digits", &[]).unwrap());
-            suggestions.push(Lingua::t("commands.check.suggestions.add_digits", &[]).unwrap());
-        }
+impl DictionaryProvider for CommonPasswords {
+    fn name(&self) -> &str {
+        "10k-most-common-passwords.txt"
+    }
 
-        if !has_special {
-            warnings.push(Lingua::t("commands.check.warnings.no_special", &[]).unwrap());
-            suggestions.push(Lingua::t("commands.check.suggestions.add_special", &[]).unwrap());
-        }
+    fn find(&self, password: &str) -> Option<usize> {
+        Self::locate(password).map(|span| span.len())
+    }
+}
 
-        if Self::has_common_patterns(password) {
-            warnings.push(Lingua::t("commands.check.warnings.common_patterns", &[]).unwrap());
-            suggestions
-                .push(Lingua::t("commands.check.suggestions.avoid_simple_sequences", &[]).unwrap());
-        }
+/// Find the longest entry of a sorted list contained in a given password
+///
+/// Every substring of the password with at least `min_len` characters is looked
+/// up by binary search, longest first, so the cost depends on the password length,
+/// not the list size, and the search stops at the first match.
+///
+/// # Arguments
+///
+/// * `entries`: The sorted entries
+/// * `password`: The lowercase password to check
+/// * `min_len`: The minimum length of an entry to be matched as a substring
+///
+/// # Returns
+///
+/// Returns the characters of the password the longest contained entry covers, otherwise None
+fn longest_entry_in<S: AsRef<str>>(entries: &[S], password: &str, min_len: usize) -> Option<Span> {
+    let boundaries: Vec<usize> = password
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(password.len()))
+        .collect();
+    let chars = boundaries.len() - 1;
+
+    (min_len.max(1)..=chars).rev().find_map(|len| {
+        (0..=chars - len)
+            .find(|&start| {
+                let window = &password[boundaries[start]..boundaries[start + len]];
+                entries
+                    .binary_search_by(|entry| entry.as_ref().cmp(window))
+                    .is_ok()
+            })
+            .map(|start| Span::new(start, start + len))
+    })
+}
 
-        if Self::has_repetitions(password) {
-            warnings.push(Lingua::t("commands.check.warnings.repetitions", &[]).unwrap());
-            suggestions
-                .push(Lingua::t("commands.check.suggestions.avoid_repetitions", &[]).unwrap());
-        }
+/// The list a detected common pattern was found in
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternSource {
+    /// The embedded list of the 10k most common passwords
+    CommonPasswords { matched_len: usize, span: Span },
+    /// A user supplied banned words list, identified by its file name
+    BannedWords(String),
+}
 
-        PasswordAnalysis {
-            score: PasswordScore {
-                total,
-                length_score,
-                diversity_score,
-                complexity_score,
-                entropy_score,
-            },
-            rating,
-            has_lowercase,
-            has_uppercase,
-            has_digit,
-            has_special,
-            length,
-            entropy,
-            warnings,
-            suggestions,
-        }
-    }
+/// An indexed list of banned words loaded from a file
+#[derive(Debug, Clone)]
+pub struct BannedWords {
+    name: String,
+    exact: Vec<String>,
+    substrings: Vec<String>,
+}
 
-    /// Calculate the length score of a given password
+impl BannedWords {
+    /// Load a banned words list from a file
+    ///
+    /// Entries are matched case-insensitively. Empty lines and lines starting with `#` are ignored.
     ///
     /// # Arguments
     ///
-    /// * `length`: The length of the password
+    /// * `path`: The path to the file containing one banned word per line
     ///
     /// # Returns
     ///
-    /// Returns the length score
-    fn calculate_length_score(length: usize) -> u32 {
-        match length {
-            0..=7 => 0,
-            8..=12 => 10,
-            13..=16 => 20,
-            _ => 25,
-        }
+    /// Returns the indexed banned words list, else returns an error
+    pub fn from_file(path: &Path) -> Result<Self, HealthCheckError> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            let error = format!("Failed to read banned words file: {}", e);
+            LoggingManager::error(&error);
+            HealthCheckError::LoadBannedWordsError(error)
+        })?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(Self::from_content(name, &content))
     }
 
-    /// Calculate the diversity score of a given password
+    /// Build an indexed banned words list from its content
     ///
     /// # Arguments
     ///
-    /// * `password`: The password to analyze
+    /// * `name`: The name of the list shown in warnings
+    /// * `content`: The list content with one banned word per line
     ///
     /// # Returns
     ///
-    /// Returns the diversity score
-    fn calculate_diversity_score(password: &str) -> (u32, bool, bool, bool, bool) {
-        let mut has_lowercase = false;
-        let mut has_uppercase = false;
-        let mut has_digit = false;
-        let mut has_special = false;
-
-        let special_chars: &[char] = &[
-            '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '-', '_', '=', '+',
-        ];
+    /// Returns the indexed banned words list
+    fn from_content(name: String, content: &str) -> Self {
+        let mut exact = Vec::new();
+        let mut substrings = Vec::new();
+
+        for line in content.lines() {
+            let word = line.trim().to_lowercase();
+            if word.is_empty() || word.starts_with('#') {
+                continue;
+            }
 
-        for c in password.chars() {
-            if c.is_lowercase() {
-                has_lowercase = true;
-            } else if c.is_uppercase() {
-                has_uppercase = true;
-            } else if c.is_ascii_digit() {
-                has_digit = true;
-            } else if special_chars.contains(&c) {
-                has_special = true;
+            if word.chars().count() >= MIN_BANNED_SUBSTRING_LENGTH {
+                substrings.push(word);
+            } else {
+                exact.push(word);
             }
         }
 
-        let mut score = 0;
-        if has_lowercase {
-            score += 5;
-        }
-        if has_uppercase {
-            score += 5;
-        }
-        if has_digit {
-            score += 5;
-        }
-        if has_special {
-            score += 5;
-        }
+        exact.sort();
+        exact.dedup();
+        substrings.sort();
+        substrings.dedup();
 
-        if has_lowercase && has_uppercase && has_digit && has_special {
-            score += 10;
+        Self {
+            name,
+            exact,
+            substrings,
         }
-
-        (score, has_lowercase, has_uppercase, has_digit, has_special)
     }
 
-    /// Calculate the complexity score of a given password
+    /// Check if a given password contains a banned word
     ///
     /// # Arguments
     ///
-    /// * `password`: The password to analyze
+    /// * `password`: The password to check
     ///
     /// # Returns
     ///
-    /// Returns the complexity score
-    fn calculate_complexity_score(password: &str) -> u32 {
-        let mut score = 0;
+    /// Returns true if the password contains a banned word, otherwise false
+    pub fn matches(&self, password: &str) -> bool {
+        self.find(&password.to_lowercase()).is_some()
+    }
 
-        if !Self::has_common_patterns(password) {
-            score += 15;
-        }
+    /// Get the name of the list
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
 
-        if !Self::has_repetitions(password) {
-            score += 10;
+impl DictionaryProvider for BannedWords {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn find(&self, password: &str) -> Option<usize> {
+        if self
+            .exact
+            .binary_search_by(|word| word.as_str().cmp(password))
+            .is_ok()
+        {
+            return Some(password.chars().count());
         }
 
-        score
+        longest_entry_in(&self.substrings, password, MIN_BANNED_SUBSTRING_LENGTH)
+            .map(|span| span.len())
     }
+}
 
-    /// Check if a given password has common patterns
-    ///
-    /// Checks if the password (or parts of it) appear in the common passwords list
+/// The model used to estimate the entropy of a password
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum EntropyModel {
+    /// Every character drawn uniformly from the detected charset
+    Character,
+    /// Words drawn uniformly from an embedded wordlist
+    Passphrase { words: usize },
+}
+
+/// Model-dependent score components of a password
+struct ModelScores {
+    entropy_model: EntropyModel,
+    entropy: f64,
+    diversity_score: u32,
+    complexity_score: u32,
+    entropy_score: u32,
+    common_pattern: Option<PatternSource>,
+}
+
+impl ModelScores {
+    /// Sum of the model-dependent score components
+    fn points(&self) -> u32 {
+        self.diversity_score + self.complexity_score + self.entropy_score
+    }
+}
+
+/// Similarity between a password and a previous one
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimilarityReport {
+    pub levenshtein_distance: usize,
+    pub longest_common_substring: usize,
+    /// Ratio between 0.0 (completely different) and 1.0 (identical)
+    pub similarity: f64,
+    pub case_only_change: bool,
+    pub counter_increment: bool,
+    pub is_similar: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PasswordAnalysis {
+    pub score: PasswordScore,
+    pub rating: String,
+    pub has_lowercase: bool,
+    pub has_uppercase: bool,
+    pub has_digit: bool,
+    pub has_special: bool,
+    pub length: usize,
+    pub entropy: f64,
+    pub observed_entropy: f64,
+    pub entropy_model: EntropyModel,
+    pub warnings: Vec<Warning>,
+    pub suggestions: Vec<Suggestion>,
+    pub similarity: Option<SimilarityReport>,
+}
+
+/// Policy verification of a password that does not contain the password itself
+///
+/// Only lengths, character class counts, and entropy are kept, so the report can be
+/// shown without revealing any character of the verified password.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationReport {
+    pub length: usize,
+    pub lowercase: usize,
+    pub uppercase: usize,
+    pub digits: usize,
+    pub special: usize,
+    pub entropy: f64,
+    pub observed_entropy: f64,
+    pub rating: String,
+    pub score: u32,
+    pub policy: PolicyResult,
+}
+
+impl HealthCheck {
+    /// Check a given password
     ///
     /// # Arguments
     ///
     /// * `password`: The password to check
+    /// * `detailed`: Whether to show detailed analysis
+    /// * `policy`: Optional name and profile of a policy to validate against
+    /// * `previous`: Optional previous password to compare against
     ///
     /// # Returns
     ///
-    /// Returns true if the password has common patterns, otherwise false
-    fn has_common_patterns(password: &str) -> bool {
-        let password_lower = password.to_lowercase();
-
-        for line in COMMON_PASSWORDS.lines() {
-            let common_pw = line.trim().to_lowercase();
-            if common_pw.is_empty() {
-                continue;
-            }
+    /// Returns whether the password complies with the policy (always true without a policy)
+    pub fn check_password(
+        password: &str,
+        detailed: bool,
+        policy: Option<(&str, &Policy)>,
+        previous: Option<&str>,
+    ) -> Result<bool, HealthCheckError> {
+        LoggingManager::info(&format!(
+            "Checking password health (detailed: {}, policy: {})",
+            detailed,
+            policy.map(|(name, _)| name).unwrap_or("none")
+        ));
 
-            if password_lower == common_pw
-                || password_lower.contains(&common_pw)
-                || common_pw.contains(&password_lower)
-            {
-                return true;
-            }
+        let analysis = Self::analyze_with_previous(password, previous);
+        Self::print_result(&analysis, detailed, password.chars().count());
+        if let Some(report) = &analysis.similarity {
+            Self::print_similarity(report);
         }
 
-        false
+        let Some((name, policy)) = policy else {
+            println!("{}", "=".repeat(50));
+            return Ok(true);
+        };
+
+        let result = Self::validate_against_policy(password, policy);
+        LoggingManager::info(&format!(
+            "Policy validation completed: policy={}, compliant={}",
+            name,
+            result.is_compliant()
+        ));
+        Self::print_policy_result(name, &result);
+        println!("{}", "=".repeat(50));
+
+        Ok(result.is_compliant())
     }
 
-    /// Check if a given password has repetitions
+    /// Check several passwords given on the command line
+    ///
+    /// A single password prints the full result like `check_password`. More passwords
+    /// print one line each, or the full result each when `detailed` is set, followed by
+    /// a summary. The passwords themselves are never printed.
     ///
     /// # Arguments
     ///
-    /// * `password`: The password to check
+    /// * `passwords`: The passwords to check
+    /// * `detailed`: Whether to show the full analysis of every password
+    /// * `policy`: Optional name and profile of a policy to validate against
+    /// * `previous`: Optional previous password every password is compared against
     ///
     /// # Returns
     ///
-    /// Returns true if the password has repetitions, otherwise false
-    fn has_repetitions(password: &str) -> bool {
-        let chars: Vec<char> = password.chars().collect();
-        if chars.len() < 3 {
-            return false;
+    /// Returns whether all passwords comply with the policy (always true without a policy)
+    pub fn check_passwords(
+        passwords: &[String],
+        detailed: bool,
+        policy: Option<(&str, &Policy)>,
+        previous: Option<&str>,
+    ) -> Result<bool, HealthCheckError> {
+        if let [password] = passwords {
+            return Self::check_password(password, detailed, policy, previous);
         }
+        LoggingManager::info(&format!(
+            "Checking {} passwords (detailed: {}, policy: {})",
+            passwords.len(),
+            detailed,
+            policy.map(|(name, _)| name).unwrap_or("none")
+        ));
 
-        for i in 0..chars.len() - 2 {
-            if chars[i] == chars[i + 1] && chars[i + 1] == chars[i + 2] {
-                return true;
+        let mut failed = 0;
+        for (index, password) in passwords.iter().enumerate() {
+            let compliant = if detailed {
+                Self::check_password(password, true, policy, previous)?
+            } else {
+                let analysis = Self::analyze_with_previous(password, previous);
+                let result =
+                    policy.map(|(_, policy)| Self::validate_against_policy(password, policy));
+                println!(
+                    "{}",
+                    Self::format_compact_result(index + 1, &analysis, result.as_ref())
+                );
+                result.is_none_or(|result| result.is_compliant())
+            };
+            if !compliant {
+                failed += 1;
             }
         }
 
-        false
+        let count = passwords.len().to_string();
+        let summary = match policy {
+            Some((name, _)) => tr(
+                "commands.check.summary_policy",
+                &[
+                    ("count", count.as_str()),
+                    ("policy", name),
+                    ("compliant", (passwords.len() - failed).to_string().as_str()),
+                    ("failed", failed.to_string().as_str()),
+                ],
+            ),
+            None => tr("commands.check.summary", &[("count", count.as_str())]),
+        };
+        println!("\n{}", summary);
+        LoggingManager::info(&format!(
+            "Checked {} passwords, {} not compliant",
+            passwords.len(),
+            failed
+        ));
+
+        Ok(failed == 0)
     }
 
-    /// Calculate the entropy score of a given password
+    /// Analyze a password and compare it with a previous one
     ///
     /// # Arguments
     ///
     /// * `password`: The password to analyze
+    /// * `previous`: Optional previous password to compare against
     ///
     /// # Returns
     ///
-    /// Returns the entropy score
-    fn calculate_entropy_score(password: &str) -> (u32, f64) {
-        let mut charset_size = 0;
-
-        let mut has_lowercase = false;
-        let mut has_uppercase = false;
-        let mut has_digit = false;
-        let mut has_special = false;
-
-        let special_chars: &[char] = &[
-            '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '-', '_', '=', '+',
-        ];
-
-        for c in password.chars() {
-            if c.is_lowercase() && !has_lowercase {
-                charset_size += 26;
-                has_lowercase = true;
-            } else if c.is_uppercase() && !has_uppercase {
-                charset_size += 26;
-                has_uppercase = true;
-            } else if c.is_ascii_digit() && !has_digit {
-                charset_size += 10;
-                has_digit = true;
-            } else if special_chars.contains(&c) && !has_special {
-                charset_size += special_chars.len();
-                has_special = true;
+    /// Returns the analysis including the similarity report if a previous password was given
+    fn analyze_with_previous(password: &str, previous: Option<&str>) -> PasswordAnalysis {
+        let mut analysis = Self::analyze_password(password);
+        if let Some(previous) = previous {
+            let report = Self::compare_passwords(previous, password);
+            LoggingManager::info(&format!(
+                "Password comparison completed: similarity={:.2}, similar={}",
+                report.similarity, report.is_similar
+            ));
+            if report.is_similar {
+                analysis.warnings.push(Warning::SimilarToPrevious);
+                analysis.suggestions.push(Suggestion::AvoidSimilarPasswords);
             }
+            analysis.similarity = Some(report);
         }
 
-        if charset_size == 0 {
-            return (0, 0.0);
-        }
-
-        let length = password.len() as f64;
-        let entropy = length * (charset_size as f64).log2();
-
-        let score = match entropy {
-            e if e < 30.0 => 5,
-            e if e < 40.0 => 10,
-            e if e < 50.0 => 15,
-            _ => 20,
-        };
+        LoggingManager::info(&format!(
+            "Password analysis completed: rating={}, score={}, length={}, entropy={:.2}, observed_entropy={:.2}",
+            analysis.rating,
+            analysis.score.total,
+            analysis.length,
+            analysis.entropy,
+            analysis.observed_entropy
+        ));
 
-        (score, entropy)
+        analysis
     }
 
-    /// Convert a score to a rating
+    /// Check every password of a file, one password per line
+    ///
+    /// Results are written as tab-separated `line`, `score`, and `rating` columns, the
+    /// passwords themselves are never written. Empty lines are skipped. On cancellation
+    /// the results of all lines checked so far are flushed to the output. With a breach
+    /// database, a `breaches` column holds how often each password appears in it.
     ///
     /// # Arguments
     ///
-    /// * `score`: The score to convert
+    /// * `input`: The path to the file containing the passwords
+    /// * `output`: The writer receiving the results
+    /// * `breaches`: The offline breach database to look every password up in
+    /// * `reporter`: The reporter receiving progress updates
+    /// * `cancellation`: The cancellation checked before every line
     ///
     /// # Returns
     ///
-    /// Returns the rating
-    fn score_to_rating(score: u32) -> String {
-        match score {
-            0..=40 => Lingua::t("commands.check.score_rating.weak", &[]).unwrap(),
-            41..=60 => Lingua::t("commands.check.score_rating.medium", &[]).unwrap(),
-            61..=80 => Lingua::t("commands.check.score_rating.strong", &[]).unwrap(),
-            _ => Lingua::t("commands.check.score_rating.very_strong", &[]).unwrap(),
+    /// Returns the outcome of the bulk check, else returns an error
+    pub fn check_file(
+        input: &Path,
+        output: &mut dyn std::io::Write,
+        mut breaches: Option<&mut BreachDatabase>,
+        reporter: &mut dyn ProgressReporter,
+        cancellation: &Cancellation,
+    ) -> Result<BulkOutcome, HealthCheckError> {
+        LoggingManager::info(&format!(
+            "Checking passwords from file: {}",
+            Redactable(input.display())
+        ));
+
+        let content = fs::read_to_string(input).map_err(|e| {
+            LoggingManager::error(&format!(
+                "Failed to read {}: {}",
+                Redactable(input.display()),
+                e
+            ));
+            HealthCheckError::ReadPasswordFile(format!("Failed to read {}: {}", input.display(), e))
+        })?;
+        let lines: Vec<&str> = content.lines().collect();
+        let write_error = |e: std::io::Error| {
+            let error = format!("Failed to write check results: {}", e);
+            LoggingManager::error(&error);
+            HealthCheckError::WriteResults(error)
+        };
+
+        let header = if breaches.is_some() {
+            "line\tscore\trating\tbreaches"
+        } else {
+            "line\tscore\trating"
+        };
+        writeln!(output, "{}", header).map_err(write_error)?;
+        let outcome = run_bulk(lines.len(), reporter, cancellation, |index| {
+            let password = lines[index].trim_end_matches('\r');
+            if password.is_empty() {
+                return Ok(());
+            }
+            let analysis = Self::analyze_password(password);
+            write!(
+                output,
+                "{}\t{}\t{}",
+                index + 1,
+                analysis.score.normalized(),
+                analysis.rating
+            )
+            .map_err(write_error)?;
+            if let Some(database) = breaches.as_deref_mut() {
+                write!(output, "\t{}", database.lookup(password)?).map_err(write_error)?;
+            }
+            writeln!(output).map_err(write_error)
+        });
+        output.flush().map_err(write_error)?;
+        let outcome = outcome?;
+
+        LoggingManager::info(&format!(
+            "Bulk check finished: {} of {} lines checked (cancelled: {})",
+            outcome.completed, outcome.total, outcome.cancelled
+        ));
+
+        Ok(outcome)
+    }
+
+    /// Prompt for a previous password without echoing it
+    ///
+    /// # Returns
+    ///
+    /// Returns the entered password, else returns an error
+    pub fn prompt_previous_password() -> Result<String, HealthCheckError> {
+        Password::new(&tr("commands.check.similarity.prompt", &[]))
+            .without_confirmation()
+            .prompt()
+            .map_err(|e| {
+                let error = format!("Failed to read previous password: {}", e);
+                LoggingManager::error(&error);
+                HealthCheckError::Prompt(error)
+            })
+    }
+
+    /// Compare a password with a previous one
+    ///
+    /// # Arguments
+    ///
+    /// * `previous`: The previous password
+    /// * `password`: The new password
+    ///
+    /// # Returns
+    ///
+    /// Returns the similarity report of both passwords
+    pub fn compare_passwords(previous: &str, password: &str) -> SimilarityReport {
+        let previous_chars: Vec<char> = previous.chars().collect();
+        let chars: Vec<char> = password.chars().collect();
+
+        let levenshtein_distance = Self::levenshtein_distance(&previous_chars, &chars);
+        let longest_common_substring = Self::longest_common_substring(&previous_chars, &chars);
+        let max_length = previous_chars.len().max(chars.len());
+        let similarity = if max_length == 0 {
+            1.0
+        } else {
+            1.0 - levenshtein_distance as f64 / max_length as f64
+        };
+
+        let case_only_change =
+            previous != password && previous.to_lowercase() == password.to_lowercase();
+        let counter_increment = Self::is_counter_increment(previous, password);
+
+        SimilarityReport {
+            levenshtein_distance,
+            longest_common_substring,
+            similarity,
+            case_only_change,
+            counter_increment,
+            is_similar: similarity > SIMILARITY_THRESHOLD || case_only_change || counter_increment,
+        }
+    }
+
+    /// Calculate the Levenshtein distance of two character sequences
+    ///
+    /// # Arguments
+    ///
+    /// * `a`: The first sequence
+    /// * `b`: The second sequence
+    ///
+    /// # Returns
+    ///
+    /// Returns the minimum number of insertions, deletions, and substitutions
+    pub(crate) fn levenshtein_distance(a: &[char], b: &[char]) -> usize {
+        let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+        let mut current_row = vec![0; b.len() + 1];
+
+        for (i, ca) in a.iter().enumerate() {
+            current_row[0] = i + 1;
+            for (j, cb) in b.iter().enumerate() {
+                let substitution = previous_row[j] + usize::from(ca != cb);
+                current_row[j + 1] = substitution
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1);
+            }
+            std::mem::swap(&mut previous_row, &mut current_row);
+        }
+
+        previous_row[b.len()]
+    }
+
+    /// Calculate the length of the longest common substring of two character sequences
+    ///
+    /// # Arguments
+    ///
+    /// * `a`: The first sequence
+    /// * `b`: The second sequence
+    ///
+    /// # Returns
+    ///
+    /// Returns the length of the longest common substring
+    fn longest_common_substring(a: &[char], b: &[char]) -> usize {
+        let mut longest = 0;
+        let mut previous_row = vec![0; b.len() + 1];
+        let mut current_row = vec![0; b.len() + 1];
+
+        for ca in a {
+            for (j, cb) in b.iter().enumerate() {
+                current_row[j + 1] = if ca == cb { previous_row[j] + 1 } else { 0 };
+                longest = longest.max(current_row[j + 1]);
+            }
+            std::mem::swap(&mut previous_row, &mut current_row);
+        }
+
+        longest
+    }
+
+    /// Check if one password is the other plus an appended or changed counter
+    ///
+    /// E.g. "hunter" → "hunter2" or "hunter2" → "hunter3".
+    ///
+    /// # Arguments
+    ///
+    /// * `previous`: The previous password
+    /// * `password`: The new password
+    ///
+    /// # Returns
+    ///
+    /// Returns true if both passwords only differ in their trailing digits, otherwise false
+    fn is_counter_increment(previous: &str, password: &str) -> bool {
+        let previous_stem = previous.trim_end_matches(|c: char| c.is_ascii_digit());
+        let stem = password.trim_end_matches(|c: char| c.is_ascii_digit());
+
+        !stem.is_empty()
+            && previous_stem.to_lowercase() == stem.to_lowercase()
+            && previous[previous_stem.len()..] != password[stem.len()..]
+    }
+
+    /// Validate a given password against a policy
+    ///
+    /// Special characters are all non-alphanumeric, non-whitespace characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to validate
+    /// * `policy`: The policy to validate against
+    ///
+    /// # Returns
+    ///
+    /// Returns the pass/fail result of every rule of the policy
+    pub fn validate_against_policy(password: &str, policy: &Policy) -> PolicyResult {
+        let chars: Vec<char> = password.chars().collect();
+        let count = |predicate: fn(&char) -> bool| chars.iter().filter(|c| predicate(c)).count();
+
+        let rules = policy
+            .rules()
+            .into_iter()
+            .map(|rule| {
+                let passed = match rule {
+                    PolicyRule::MinLength(min) => chars.len() >= min,
+                    PolicyRule::MaxLength(max) => chars.len() <= max,
+                    PolicyRule::MinLowercase(min) => count(|c| c.is_lowercase()) >= min,
+                    PolicyRule::MinUppercase(min) => count(|c| c.is_uppercase()) >= min,
+                    PolicyRule::MinDigits(min) => count(|c| c.is_ascii_digit()) >= min,
+                    PolicyRule::MinSpecial(min) => {
+                        count(|c| !c.is_alphanumeric() && !c.is_whitespace()) >= min
+                    }
+                    PolicyRule::NotCommon => {
+                        Self::find_common_pattern(password, Self::banned_words()).is_none()
+                    }
+                    PolicyRule::NoUsername => policy.username().is_none_or(|username| {
+                        !password.to_lowercase().contains(&username.to_lowercase())
+                    }),
+                    PolicyRule::NoRepetitions => !Self::has_repetitions(password),
+                };
+                RuleResult { rule, passed }
+            })
+            .collect();
+
+        PolicyResult { rules }
+    }
+
+    /// Verify a password against a policy without keeping the password
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to verify
+    /// * `policy`: The policy to verify against
+    ///
+    /// # Returns
+    ///
+    /// Returns the verification report
+    pub fn verify_password(password: &str, policy: &Policy) -> VerificationReport {
+        let chars: Vec<char> = password.chars().collect();
+        let count = |predicate: fn(&char) -> bool| chars.iter().filter(|c| predicate(c)).count();
+        let analysis = Self::analyze_password(password);
+
+        VerificationReport {
+            length: chars.len(),
+            lowercase: count(|c| c.is_lowercase()),
+            uppercase: count(|c| c.is_uppercase()),
+            digits: count(|c| c.is_ascii_digit()),
+            special: count(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+            entropy: analysis.entropy,
+            observed_entropy: analysis.observed_entropy,
+            rating: analysis.rating,
+            score: analysis.score.normalized(),
+            policy: Self::validate_against_policy(password, policy),
+        }
+    }
+
+    /// Analyze a given password
+    ///
+    /// This is the stable entry point for library consumers. Warnings and suggestions
+    /// are returned as typed values, use their `message` method to get translated text.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    ///
+    /// # Returns
+    ///
+    /// Returns the analysis of the password
+    pub fn analyze_password(password: &str) -> PasswordAnalysis {
+        Self::analyze_password_with(password, &Self::scoring_weights())
+    }
+
+    /// Analyze a given password with the given weights of the score components
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    /// * `weights`: The weights of the score components
+    ///
+    /// # Returns
+    ///
+    /// Returns the analysis of the password
+    pub fn analyze_password_with(password: &str, weights: &ScoringWeights) -> PasswordAnalysis {
+        let length = password.len();
+        let length_score = Self::calculate_length_score(length);
+        let (diversity_score, has_lowercase, has_uppercase, has_digit, has_special) =
+            Self::calculate_diversity_score(password);
+        let has_repetitions = Self::has_repetitions(password);
+
+        let mut model = Self::score_character_model(password, diversity_score, has_repetitions);
+        if let Some((words, wordlist_size)) = Self::detect_passphrase(password) {
+            let passphrase =
+                Self::score_passphrase_model(password, words, wordlist_size, has_repetitions);
+            if passphrase.points() > model.points() {
+                model = passphrase;
+            }
+        }
+        let is_passphrase = matches!(model.entropy_model, EntropyModel::Passphrase { .. });
+        let ModelScores {
+            entropy_model,
+            entropy,
+            diversity_score,
+            complexity_score,
+            entropy_score,
+            common_pattern,
+        } = model;
+
+        let observed_entropy = Self::calculate_observed_entropy(password);
+        let entropy_ratio = Self::calculate_entropy_ratio(password, observed_entropy);
+        let entropy_penalty = Self::calculate_entropy_penalty(entropy_ratio);
+
+        let score = PasswordScore {
+            total: weights
+                .total(
+                    length_score,
+                    diversity_score,
+                    complexity_score,
+                    entropy_score,
+                )
+                .saturating_sub(entropy_penalty),
+            length_score,
+            diversity_score,
+            complexity_score,
+            entropy_score,
+            entropy_penalty,
+            weights: *weights,
+        };
+        let rating = Self::score_to_rating(score.normalized());
+
+        let mut warnings = Vec::new();
+        let mut suggestions = Vec::new();
+
+        if length < 8 {
+            warnings.push(Warning::TooShort);
+            suggestions.push(Suggestion::IncreaseLength);
+        }
+
+        if !has_lowercase {
+            warnings.push(Warning::NoLowercase);
+            suggestions.push(Suggestion::AddLowercase);
+        }
+
+        if !has_uppercase {
+            warnings.push(Warning::NoUppercase);
+            suggestions.push(Suggestion::AddUppercase);
+        }
+
+        if !has_digit && !is_passphrase {
+            warnings.push(Warning::NoDigits);
+            suggestions.push(Suggestion::AddDigits);
+        }
+
+        if !has_special && !is_passphrase {
+            warnings.push(Warning::NoSpecial);
+            suggestions.push(Suggestion::AddSpecial);
+        }
+
+        match common_pattern {
+            Some(PatternSource::CommonPasswords { matched_len, span }) => {
+                warnings.push(Warning::CommonPattern { matched_len, span });
+                suggestions.push(Suggestion::AvoidSimpleSequences);
+            }
+            Some(PatternSource::BannedWords(list)) => {
+                warnings.push(Warning::BannedWord { list });
+                suggestions.push(Suggestion::AvoidBannedWords);
+            }
+            None => {}
+        }
+
+        if let Some(span) = Self::find_repetition(password) {
+            warnings.push(Warning::Repetition { span });
+            suggestions.push(Suggestion::AvoidRepetitions);
+        }
+
+        // Reported to point at them, the score is left as it is
+        if let Some(span) = Self::find_sequence(password) {
+            warnings.push(Warning::Sequence { span });
+            if !suggestions.contains(&Suggestion::AvoidSimpleSequences) {
+                suggestions.push(Suggestion::AvoidSimpleSequences);
+            }
+        }
+
+        if let Some(span) = Self::find_year(password) {
+            warnings.push(Warning::Year { span });
+            suggestions.push(Suggestion::AvoidYears);
+        }
+
+        if entropy_ratio < ENTROPY_RATIO_THRESHOLD {
+            warnings.push(Warning::LowObservedEntropy);
+            suggestions.push(Suggestion::VaryCharacters);
+        }
+
+        PasswordAnalysis {
+            score,
+            rating,
+            has_lowercase,
+            has_uppercase,
+            has_digit,
+            has_special,
+            length,
+            entropy,
+            observed_entropy,
+            entropy_model,
+            warnings,
+            suggestions,
+            similarity: None,
+        }
+    }
+
+    /// Score a given password under the character model
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    /// * `diversity_score`: The diversity score of the password
+    /// * `has_repetitions`: Whether the password contains repetitions
+    ///
+    /// # Returns
+    ///
+    /// Returns the model-dependent score components
+    fn score_character_model(
+        password: &str,
+        diversity_score: u32,
+        has_repetitions: bool,
+    ) -> ModelScores {
+        let common_pattern = Self::find_common_pattern(password, Self::banned_words());
+        let (entropy_score, entropy) = Self::calculate_entropy_score(password);
+
+        ModelScores {
+            entropy_model: EntropyModel::Character,
+            entropy,
+            diversity_score,
+            complexity_score: Self::calculate_complexity_score(
+                common_pattern.is_some(),
+                has_repetitions,
+            ),
+            entropy_score,
+            common_pattern,
+        }
+    }
+
+    /// Score a given password under the passphrase model
+    ///
+    /// Passphrase words are dictionary words by construction, so only the phrase
+    /// as a whole is checked against the common passwords list. The keyspace is
+    /// defined by the wordlist, so character diversity is not required either.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    /// * `words`: The number of wordlist words in the password
+    /// * `wordlist_size`: The size of the matching wordlist
+    /// * `has_repetitions`: Whether the password contains repetitions
+    ///
+    /// # Returns
+    ///
+    /// Returns the model-dependent score components
+    fn score_passphrase_model(
+        password: &str,
+        words: usize,
+        wordlist_size: usize,
+        has_repetitions: bool,
+    ) -> ModelScores {
+        let common_pattern = if Self::is_common_password(password) {
+            let matched_len = password.chars().count();
+            Some(PatternSource::CommonPasswords {
+                matched_len,
+                span: Span::new(0, matched_len),
+            })
+        } else {
+            Self::find_banned_word(password, Self::banned_words())
+        };
+        let entropy = words as f64 * (wordlist_size as f64).log2();
+
+        ModelScores {
+            entropy_model: EntropyModel::Passphrase { words },
+            entropy,
+            diversity_score: PasswordScore::MAX_DIVERSITY_SCORE,
+            complexity_score: Self::calculate_complexity_score(
+                common_pattern.is_some(),
+                has_repetitions,
+            ),
+            entropy_score: Self::entropy_to_score(entropy),
+            common_pattern,
+        }
+    }
+
+    /// Calculate the length score of a given password
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The length of the password
+    ///
+    /// # Returns
+    ///
+    /// Returns the length score
+    fn calculate_length_score(length: usize) -> u32 {
+        match length {
+            0..=7 => 0,
+            8..=12 => 10,
+            13..=16 => 20,
+            _ => PasswordScore::MAX_LENGTH_SCORE,
+        }
+    }
+
+    /// Calculate the diversity score of a given password
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    ///
+    /// # Returns
+    ///
+    /// Returns the diversity score
+    fn calculate_diversity_score(password: &str) -> (u32, bool, bool, bool, bool) {
+        let mut has_lowercase = false;
+        let mut has_uppercase = false;
+        let mut has_digit = false;
+        let mut has_special = false;
+
+        let special_chars: &[char] = &[
+            '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '-', '_', '=', '+',
+        ];
+
+        for c in password.chars() {
+            if c.is_lowercase() {
+                has_lowercase = true;
+            } else if c.is_uppercase() {
+                has_uppercase = true;
+            } else if c.is_ascii_digit() {
+                has_digit = true;
+            } else if special_chars.contains(&c) {
+                has_special = true;
+            }
+        }
+
+        let mut score = 0;
+        if has_lowercase {
+            score += 5;
+        }
+        if has_uppercase {
+            score += 5;
+        }
+        if has_digit {
+            score += 5;
+        }
+        if has_special {
+            score += 5;
+        }
+
+        if has_lowercase && has_uppercase && has_digit && has_special {
+            score += 10;
+        }
+
+        (score, has_lowercase, has_uppercase, has_digit, has_special)
+    }
+
+    /// Calculate the complexity score of a given password
+    ///
+    /// # Arguments
+    ///
+    /// * `has_common_patterns`: Whether the password contains common patterns
+    /// * `has_repetitions`: Whether the password contains repetitions
+    ///
+    /// # Returns
+    ///
+    /// Returns the complexity score
+    fn calculate_complexity_score(has_common_patterns: bool, has_repetitions: bool) -> u32 {
+        let mut score = 0;
+
+        if !has_common_patterns {
+            score += 15;
+        }
+
+        if !has_repetitions {
+            score += 10;
+        }
+
+        score
+    }
+
+    /// Load the banned words list used by all subsequent checks
+    ///
+    /// The list is loaded and indexed once per process; later calls are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path to the banned words file
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise an error
+    pub fn load_banned_words(path: &Path) -> Result<(), HealthCheckError> {
+        if BANNED_WORDS.get().is_some() {
+            return Ok(());
+        }
+
+        let banned_words = BannedWords::from_file(path)?;
+        LoggingManager::info(&format!(
+            "Loaded banned words list {} ({} entries)",
+            Redactable(banned_words.name()),
+            banned_words.exact.len() + banned_words.substrings.len()
+        ));
+        let _ = BANNED_WORDS.set(banned_words);
+
+        Ok(())
+    }
+
+    /// Get the banned words list loaded for this process
+    fn banned_words() -> Option<&'static BannedWords> {
+        BANNED_WORDS.get()
+    }
+
+    /// Add a dictionary checked by every analysis after the built-in lists
+    ///
+    /// # Arguments
+    ///
+    /// * `provider`: The dictionary to add
+    pub fn add_dictionary(provider: Box<dyn DictionaryProvider>) {
+        LoggingManager::info(&format!("Added dictionary {}", Redactable(provider.name())));
+        if let Ok(mut dictionaries) = DICTIONARIES.write() {
+            dictionaries.push(provider);
+        }
+    }
+
+    /// Set the weights of the score components used by every following analysis
+    ///
+    /// # Arguments
+    ///
+    /// * `weights`: The weights, from the `[scoring]` config section
+    pub fn set_scoring_weights(weights: ScoringWeights) {
+        if weights != ScoringWeights::DEFAULT {
+            LoggingManager::info(&format!("Using scoring weights {:?}", weights));
+        }
+        if let Ok(mut current) = SCORING_WEIGHTS.write() {
+            *current = weights;
+        }
+    }
+
+    /// Get the weights of the score components set for this process
+    pub fn scoring_weights() -> ScoringWeights {
+        SCORING_WEIGHTS
+            .read()
+            .map(|weights| *weights)
+            .unwrap_or_default()
+    }
+
+    /// Find the list a common pattern of a given password appears in
+    ///
+    /// Checks the embedded common passwords list first, then the banned words list
+    /// and the added dictionaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    /// * `banned_words`: The banned words list to check in addition to the embedded list
+    ///
+    /// # Returns
+    ///
+    /// Returns the source of the match, otherwise None
+    fn find_common_pattern(
+        password: &str,
+        banned_words: Option<&BannedWords>,
+    ) -> Option<PatternSource> {
+        let password_lower = password.to_lowercase();
+        if let Some(span) = CommonPasswords::locate(&password_lower) {
+            return Some(PatternSource::CommonPasswords {
+                matched_len: span.len(),
+                span,
+            });
+        }
+
+        Self::find_banned_word(password, banned_words)
+    }
+
+    /// Find a banned word in a given password
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    /// * `banned_words`: The banned words list to check before the added dictionaries
+    ///
+    /// # Returns
+    ///
+    /// Returns the matching list as source, otherwise None
+    fn find_banned_word(
+        password: &str,
+        banned_words: Option<&BannedWords>,
+    ) -> Option<PatternSource> {
+        if let Some(list) = banned_words.filter(|list| list.matches(password)) {
+            return Some(PatternSource::BannedWords(list.name().to_string()));
+        }
+
+        let password_lower = password.to_lowercase();
+        let dictionaries = DICTIONARIES.read().ok()?;
+        dictionaries
+            .iter()
+            .find(|dictionary| dictionary.find(&password_lower).is_some())
+            .map(|dictionary| PatternSource::BannedWords(dictionary.name().to_string()))
+    }
+
+    /// Check if a given password exactly matches an entry of the common passwords list
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the password is a common password, otherwise false
+    fn is_common_password(password: &str) -> bool {
+        CommonPasswords::index()
+            .binary_search(&password.to_lowercase().as_str())
+            .is_ok()
+    }
+
+    /// Detect whether a given password is a separator-delimited wordlist passphrase
+    ///
+    /// All words must come from the same embedded wordlist. Wordlist entries that
+    /// themselves contain a hyphen (e.g. "t-shirt") are counted as a single word, and
+    /// repeated words are counted once.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of distinct words and the size of the matching wordlist, otherwise None
+    fn detect_passphrase(password: &str) -> Option<(usize, usize)> {
+        let separator = password
+            .chars()
+            .find(|c| PASSPHRASE_SEPARATORS.contains(c))?;
+        let password_lower = password.to_lowercase();
+        let tokens: Vec<&str> = password_lower.split(separator).collect();
+
+        if tokens.len() < MIN_PASSPHRASE_WORDS || tokens.iter().any(|t| t.is_empty()) {
+            return None;
+        }
+
+        PASSPHRASE_LANGUAGES.iter().find_map(|lang| {
+            let wordlist = Generator::get_wordlist(lang);
+            let words = Self::count_wordlist_words(&tokens, separator, wordlist)?;
+            (words >= MIN_PASSPHRASE_WORDS).then_some((words, wordlist.len()))
+        })
+    }
+
+    /// Count the distinct wordlist words in a sequence of tokens
+    ///
+    /// A repeated word adds no entropy, so it is only counted once.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens`: The separator-delimited tokens of the password
+    /// * `separator`: The separator used between the tokens
+    /// * `wordlist`: The wordlist to look the tokens up in
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of distinct words if every token belongs to the wordlist, otherwise None
+    fn count_wordlist_words(tokens: &[&str], separator: char, wordlist: &[&str]) -> Option<usize> {
+        let mut words = HashSet::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            if separator == '-' && i + 1 < tokens.len() {
+                let joined = format!("{}-{}", tokens[i], tokens[i + 1]);
+                if wordlist.contains(&joined.as_str()) {
+                    words.insert(joined);
+                    i += 2;
+                    continue;
+                }
+            }
+
+            if !wordlist.contains(&tokens[i]) {
+                return None;
+            }
+            words.insert(tokens[i].to_string());
+            i += 1;
+        }
+
+        Some(words.len())
+    }
+
+    /// Check if a given password has repetitions
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the password has repetitions, otherwise false
+    fn has_repetitions(password: &str) -> bool {
+        Self::find_repetition(password).is_some()
+    }
+
+    /// Find the first character repeated three or more times in a row
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns the whole run of the character, otherwise None
+    fn find_repetition(password: &str) -> Option<Span> {
+        Self::find_run(password, 3, |a, b| a == b)
+    }
+
+    /// Find the first run of letters or digits counting up or down, like `abc` or `987`
+    ///
+    /// Letters are compared without case, a run does not mix letters and digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns the longest run at that position, otherwise None
+    fn find_sequence(password: &str) -> Option<Span> {
+        let step = |a: char, b: char| {
+            let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+            let same_class = (a.is_ascii_lowercase() && b.is_ascii_lowercase())
+                || (a.is_ascii_digit() && b.is_ascii_digit());
+            same_class.then(|| b as i32 - a as i32)
+        };
+        let chars: Vec<char> = password.chars().collect();
+
+        let mut start = 0;
+        while start + 1 < chars.len() {
+            let direction = step(chars[start], chars[start + 1]).filter(|d| d.abs() == 1);
+            let Some(direction) = direction else {
+                start += 1;
+                continue;
+            };
+            let mut end = start + 2;
+            while end < chars.len() && step(chars[end - 1], chars[end]) == Some(direction) {
+                end += 1;
+            }
+            if end - start >= MIN_SEQUENCE_LENGTH {
+                return Some(Span::new(start, end));
+            }
+            start = end - 1;
+        }
+
+        None
+    }
+
+    /// Find the first year from 1900 to 2099 that stands on its own
+    ///
+    /// Only four digits in a row count, digits of a longer number do not.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to check
+    ///
+    /// # Returns
+    ///
+    /// Returns the characters of the year, otherwise None
+    fn find_year(password: &str) -> Option<Span> {
+        let chars: Vec<char> = password.chars().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let end = start
+                + chars[start..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count();
+            if end - start == 4 {
+                let digits: String = chars[start..end].iter().collect();
+                if digits.parse().is_ok_and(|year| YEAR_RANGE.contains(&year)) {
+                    return Some(Span::new(start, end));
+                }
+            }
+            start = end + 1;
         }
+
+        None
     }
 
-    /// Print the result of a password analysis
+    /// Find the first run of characters where each follows the one before
     ///
     /// # Arguments
     ///
-    /// * `analysis`: The analysis to print
-    /// * `detailed`: Whether to show detailed analysis
+    /// * `password`: The password to check
+    /// * `min_len`: The minimum length of a run
+    /// * `follows`: Whether a character continues the run of the one before it
     ///
     /// # Returns
     ///
-    /// Returns nothing
-    fn print_result(analysis: &PasswordAnalysis, detailed: bool) {
-        let weak = Lingua::t("commands.check.score_rating.weak", &[]).unwrap();
-        let medium = Lingua::t("commands.check.score_rating.medium", &[]).unwrap();
-        let strong = Lingua::t("commands.check.score_rating.strong", &[]).unwrap();
-        let very_strong = Lingua::t("commands.check.score_rating.very_strong", &[]).unwrap();
+    /// Returns the whole first run of at least `min_len` characters, otherwise None
+    fn find_run(
+        password: &str,
+        min_len: usize,
+        follows: impl Fn(char, char) -> bool,
+    ) -> Option<Span> {
+        let chars: Vec<char> = password.chars().collect();
+        let mut start = 0;
+        for end in 1..=chars.len() {
+            if end == chars.len() || !follows(chars[end - 1], chars[end]) {
+                if end - start >= min_len {
+                    return Some(Span::new(start, end));
+                }
+                start = end;
+            }
+        }
+
+        None
+    }
+
+    /// Calculate the entropy score of a given password
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    ///
+    /// # Returns
+    ///
+    /// Returns the entropy score
+    fn calculate_entropy_score(password: &str) -> (u32, f64) {
+        let charset_size = Self::charset_size(password);
+        if charset_size == 0 {
+            return (0, 0.0);
+        }
+
+        let length = password.len() as f64;
+        let entropy = length * (charset_size as f64).log2();
+
+        (Self::entropy_to_score(entropy), entropy)
+    }
+
+    /// Calculate the size of the character set a given password draws from
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    ///
+    /// # Returns
+    ///
+    /// Returns the summed size of the character classes used, 0 if none is used
+    fn charset_size(password: &str) -> usize {
+        let mut charset_size = 0;
+
+        let mut has_lowercase = false;
+        let mut has_uppercase = false;
+        let mut has_digit = false;
+        let mut has_special = false;
+
+        let special_chars: &[char] = &[
+            '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '-', '_', '=', '+',
+        ];
+
+        for c in password.chars() {
+            if c.is_lowercase() && !has_lowercase {
+                charset_size += 26;
+                has_lowercase = true;
+            } else if c.is_uppercase() && !has_uppercase {
+                charset_size += 26;
+                has_uppercase = true;
+            } else if c.is_ascii_digit() && !has_digit {
+                charset_size += 10;
+                has_digit = true;
+            } else if special_chars.contains(&c) && !has_special {
+                charset_size += special_chars.len();
+                has_special = true;
+            }
+        }
+
+        charset_size
+    }
+
+    /// Convert an entropy value to an entropy score
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy`: The entropy in bits
+    ///
+    /// # Returns
+    ///
+    /// Returns the entropy score
+    fn entropy_to_score(entropy: f64) -> u32 {
+        if entropy <= 0.0 {
+            return 0;
+        }
+
+        match entropy {
+            e if e < 30.0 => 5,
+            e if e < 40.0 => 10,
+            e if e < 50.0 => 15,
+            _ => PasswordScore::MAX_ENTROPY_SCORE,
+        }
+    }
+
+    /// Calculate the observed (Shannon) entropy of a given password
+    ///
+    /// Unlike the charset entropy, this measures the actual character distribution,
+    /// so repeated characters lower the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    ///
+    /// # Returns
+    ///
+    /// Returns the observed entropy in bits for the whole password
+    fn calculate_observed_entropy(password: &str) -> f64 {
+        let chars: Vec<char> = password.chars().collect();
+        if chars.is_empty() {
+            return 0.0;
+        }
+
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in &chars {
+            *counts.entry(*c).or_insert(0) += 1;
+        }
+
+        let length = chars.len() as f64;
+        let per_char: f64 = counts
+            .values()
+            .map(|&count| {
+                let p = count as f64 / length;
+                -p * p.log2()
+            })
+            .sum();
+
+        per_char * length
+    }
+
+    /// Calculate the ratio of observed entropy to the maximum observable entropy
+    ///
+    /// The Shannon entropy of n characters drawn from a character set of size c can
+    /// never exceed n * log2(min(n, c)), so a long password from a small character set
+    /// is measured against what that set allows.
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The password to analyze
+    /// * `observed_entropy`: The observed entropy of the password
+    ///
+    /// # Returns
+    ///
+    /// Returns a ratio between 0.0 and 1.0
+    fn calculate_entropy_ratio(password: &str, observed_entropy: f64) -> f64 {
+        let length = password.chars().count();
+        if length < 2 {
+            return 1.0;
+        }
+
+        // Characters outside the known classes leave the set size unknown
+        let charset_size = match Self::charset_size(password) {
+            0 => length,
+            size => size.min(length),
+        };
+        if charset_size < 2 {
+            return 1.0;
+        }
+
+        let max_entropy = length as f64 * (charset_size as f64).log2();
+        (observed_entropy / max_entropy).clamp(0.0, 1.0)
+    }
+
+    /// Calculate the penalty for a low observed entropy ratio
+    ///
+    /// # Arguments
+    ///
+    /// * `ratio`: The ratio of observed to maximum entropy
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of points to deduct from the total score
+    fn calculate_entropy_penalty(ratio: f64) -> u32 {
+        if ratio >= ENTROPY_RATIO_THRESHOLD {
+            return 0;
+        }
+
+        let shortfall = (ENTROPY_RATIO_THRESHOLD - ratio) / ENTROPY_RATIO_THRESHOLD;
+        (shortfall * MAX_ENTROPY_PENALTY as f64).round() as u32
+    }
+
+    /// Convert a score to a rating
+    ///
+    /// # Arguments
+    ///
+    /// * `score`: The score to convert
+    ///
+    /// # Returns
+    ///
+    /// Returns the rating
+    fn score_to_rating(score: u32) -> String {
+        match score {
+            0..=40 => tr("commands.check.score_rating.weak", &[]),
+            41..=60 => tr("commands.check.score_rating.medium", &[]),
+            61..=80 => tr("commands.check.score_rating.strong", &[]),
+            _ => tr("commands.check.score_rating.very_strong", &[]),
+        }
+    }
 
-        let color = match analysis.rating.as_str() {
+    /// Get the terminal color of a rating
+    ///
+    /// # Arguments
+    ///
+    /// * `rating`: The translated rating
+    ///
+    /// # Returns
+    ///
+    /// Returns the ANSI escape sequence for the rating
+    fn rating_color(rating: &str) -> &'static str {
+        let weak = tr("commands.check.score_rating.weak", &[]);
+        let medium = tr("commands.check.score_rating.medium", &[]);
+        let strong = tr("commands.check.score_rating.strong", &[]);
+        let very_strong = tr("commands.check.score_rating.very_strong", &[]);
+
+        match rating {
             s if s == weak => "\x1b[1;31m",
             s if s == medium => "\x1b[1;33m",
             s if s == strong => "\x1b[1;32m",
             s if s == very_strong => "\x1b[1;32m",
             _ => "\x1b[0m",
-        };
+        }
+    }
 
-        println!(
-            "\n\x1b[1;36m{}\x1b[0m",
-            Lingua::t("commands.check.title", &[]).unwrap()
+    /// Format the result of one of several checked passwords as a single line
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The position of the password, starting at 1
+    /// * `analysis`: The analysis of the password
+    /// * `policy`: The policy validation result, if a policy was given
+    ///
+    /// # Returns
+    ///
+    /// Returns the line with rating, points, and policy compliance
+    fn format_compact_result(
+        index: usize,
+        analysis: &PasswordAnalysis,
+        policy: Option<&PolicyResult>,
+    ) -> String {
+        let mut line = format!(
+            "#{:<3} {}",
+            index,
+            tr(
+                "commands.check.rating",
+                &[
+                    (
+                        "rating",
+                        format!(
+                            "{}{}\x1b[0m",
+                            Self::rating_color(&analysis.rating),
+                            analysis.rating
+                        )
+                        .as_str()
+                    ),
+                    ("points", analysis.score.normalized().to_string().as_str())
+                ]
+            )
         );
+        if let Some(result) = policy {
+            let failed = result.rules.iter().filter(|rule| !rule.passed).count();
+            let _ = write!(
+                line,
+                "  {}",
+                if failed == 0 {
+                    format!(
+                        "\x1b[1;32m✓ {}\x1b[0m",
+                        tr("commands.check.policy.compliant", &[])
+                    )
+                } else {
+                    format!(
+                        "\x1b[1;31m✗ {}\x1b[0m",
+                        tr(
+                            "commands.check.policy.rules_failed",
+                            &[("count", failed.to_string().as_str())]
+                        )
+                    )
+                }
+            );
+        }
+        line
+    }
+
+    /// Print the result of a password analysis
+    ///
+    /// # Arguments
+    ///
+    /// * `analysis`: The analysis to print
+    /// * `detailed`: Whether to show detailed analysis
+    /// * `chars`: The number of characters in the password, for the masked markers
+    ///
+    /// # Returns
+    ///
+    /// Returns nothing
+    fn print_result(analysis: &PasswordAnalysis, detailed: bool, chars: usize) {
+        let color = Self::rating_color(&analysis.rating);
+
+        println!("\n\x1b[1;36m{}\x1b[0m", tr("commands.check.title", &[]));
         println!("{}", "=".repeat(50));
         println!(
             "{}",
-            Lingua::t(
+            tr(
                 "commands.check.rating",
                 &[
                     (
                         "rating",
                         format!("{}{}\x1b[0m", color, analysis.rating).as_str()
                     ),
-                    ("points", analysis.score.total.to_string().as_str())
+                    ("points", analysis.score.normalized().to_string().as_str())
                 ]
             )
-            .unwrap()
         );
         println!(
             "{}",
-            Lingua::t(
+            tr(
                 "commands.check.length",
                 &[("length", analysis.length.to_string().as_str())]
             )
-            .unwrap()
+        );
+        let entropy_model = match analysis.entropy_model {
+            EntropyModel::Character => tr("commands.check.entropy_models.character", &[]),
+            EntropyModel::Passphrase { words } => tr(
+                "commands.check.entropy_models.passphrase",
+                &[("words", words.to_string().as_str())],
+            ),
+        };
+        println!(
+            "{}",
+            tr(
+                "commands.check.entropy_model",
+                &[("model", entropy_model.as_str())]
+            )
         );
 
         if detailed {
             println!(
                 "\n\x1b[1;33m{}\x1b[0m",
-                Lingua::t("commands.check.subtitle_detailed", &[]).unwrap()
+                tr("commands.check.subtitle_detailed", &[])
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.length_score",
-                    &[(
-                        "length_score",
-                        analysis.score.length_score.to_string().as_str()
-                    )]
+                    &[
+                        (
+                            "length_score",
+                            analysis.score.length_score.to_string().as_str()
+                        ),
+                        ("max", PasswordScore::MAX_LENGTH_SCORE.to_string().as_str())
+                    ]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.diversity_score",
-                    &[(
-                        "diversity_score",
-                        analysis.score.diversity_score.to_string().as_str()
-                    )]
+                    &[
+                        (
+                            "diversity_score",
+                            analysis.score.diversity_score.to_string().as_str()
+                        ),
+                        (
+                            "max",
+                            PasswordScore::MAX_DIVERSITY_SCORE.to_string().as_str()
+                        )
+                    ]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.complexity_score",
-                    &[(
-                        "complexity_score",
-                        analysis.score.complexity_score.to_string().as_str()
-                    )]
+                    &[
+                        (
+                            "complexity_score",
+                            analysis.score.complexity_score.to_string().as_str()
+                        ),
+                        (
+                            "max",
+                            PasswordScore::MAX_COMPLEXITY_SCORE.to_string().as_str()
+                        )
+                    ]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.entropy_score",
-                    &[(
-                        "entropy_score",
-                        analysis.score.entropy_score.to_string().as_str()
-                    )]
+                    &[
+                        (
+                            "entropy_score",
+                            analysis.score.entropy_score.to_string().as_str()
+                        ),
+                        ("max", PasswordScore::MAX_ENTROPY_SCORE.to_string().as_str())
+                    ]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.entropy",
-                    &[("entropy", format!("{:.2}", analysis.entropy).as_str())]
+                    &[
+                        ("entropy", format!("{:.2}", analysis.entropy).as_str()),
+                        (
+                            "observed_entropy",
+                            format!("{:.2}", analysis.observed_entropy).as_str()
+                        )
+                    ]
+                )
+            );
+            if analysis.score.entropy_penalty > 0 {
+                println!(
+                    "  {}",
+                    tr(
+                        "commands.check.entropy_penalty",
+                        &[(
+                            "entropy_penalty",
+                            analysis.score.entropy_penalty.to_string().as_str()
+                        )]
+                    )
+                );
+            }
+            let weights = analysis.score.weights;
+            println!(
+                "  {}",
+                tr(
+                    "commands.check.weights",
+                    &[
+                        ("length", weights.length.to_string().as_str()),
+                        ("diversity", weights.diversity.to_string().as_str()),
+                        ("complexity", weights.complexity.to_string().as_str()),
+                        ("entropy", weights.entropy.to_string().as_str())
+                    ]
                 )
-                .unwrap()
             );
 
             println!(
                 "\n\x1b[1;33m{}\x1b[0m",
-                Lingua::t("commands.check.subtitle_categories", &[]).unwrap()
+                tr("commands.check.subtitle_categories", &[])
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.lowercase",
                     &[(
                         "lowercase",
@@ -491,11 +1987,10 @@ impl HealthCheck {
                         .as_str()
                     )]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.uppercase",
                     &[(
                         "uppercase",
@@ -510,11 +2005,10 @@ impl HealthCheck {
                         .as_str()
                     )]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.digits",
                     &[(
                         "digits",
@@ -529,11 +2023,10 @@ impl HealthCheck {
                         .as_str()
                     )]
                 )
-                .unwrap()
             );
             println!(
                 "  {}",
-                Lingua::t(
+                tr(
                     "commands.check.special",
                     &[(
                         "special",
@@ -548,37 +2041,260 @@ impl HealthCheck {
                         .as_str()
                     )]
                 )
-                .unwrap()
             );
 
-            if !analysis.warnings.is_empty() {
-                println!(
-                    "\n\x1b[1;31m{}\x1b[0m",
-                    Lingua::t("commands.check.warnings.title", &[]).unwrap()
-                );
-                for warning in &analysis.warnings {
-                    println!("  ⚠️\t{}", warning);
-                }
-            }
+            if !analysis.warnings.is_empty() {
+                println!(
+                    "\n\x1b[1;31m{}\x1b[0m",
+                    tr("commands.check.warnings.title", &[])
+                );
+                for warning in &analysis.warnings {
+                    println!("  ⚠️\t{}", warning.message());
+                    // Points at the characters without showing them
+                    if let Some(span) = warning.span() {
+                        let (masked, markers) = span.markers(chars);
+                        println!("\t{}\n\t\x1b[1;31m{}\x1b[0m", masked, markers);
+                    }
+                }
+            }
+
+            if !analysis.suggestions.is_empty() && analysis.score.normalized() < 80 {
+                println!(
+                    "\n\x1b[1;33m{}\x1b[0m",
+                    tr("commands.check.suggestions.title", &[])
+                );
+                for suggestion in &analysis.suggestions {
+                    println!("  💡\t{}", suggestion.message());
+                }
+            }
+        }
+    }
+
+    /// Print the result of a password comparison
+    ///
+    /// # Arguments
+    ///
+    /// * `report`: The similarity report to print
+    fn print_similarity(report: &SimilarityReport) {
+        let yes = tr("commands.check.similarity.yes", &[]);
+        let no = tr("commands.check.similarity.no", &[]);
+
+        println!(
+            "\n\x1b[1;33m{}\x1b[0m",
+            tr("commands.check.similarity.title", &[])
+        );
+        println!(
+            "  {}",
+            tr(
+                "commands.check.similarity.levenshtein_distance",
+                &[("distance", report.levenshtein_distance.to_string().as_str())]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
+                "commands.check.similarity.longest_common_substring",
+                &[(
+                    "length",
+                    report.longest_common_substring.to_string().as_str()
+                )]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
+                "commands.check.similarity.similarity",
+                &[(
+                    "similarity",
+                    format!("{:.0}", report.similarity * 100.0).as_str()
+                )]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
+                "commands.check.similarity.case_only_change",
+                &[("value", if report.case_only_change { &yes } else { &no })]
+            )
+        );
+        println!(
+            "  {}",
+            tr(
+                "commands.check.similarity.counter_increment",
+                &[("value", if report.counter_increment { &yes } else { &no })]
+            )
+        );
+
+        if report.is_similar {
+            println!(
+                "\n\x1b[1;31m{}\x1b[0m",
+                Warning::SimilarToPrevious.message()
+            );
+        }
+    }
+
+    /// Print the result of a policy validation
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the policy
+    /// * `result`: The validation result to print
+    fn print_policy_result(name: &str, result: &PolicyResult) {
+        print!("{}", Self::format_policy_result(name, result));
+    }
+
+    /// Format the result of a policy validation
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the policy
+    /// * `result`: The validation result to format
+    ///
+    /// # Returns
+    ///
+    /// Returns the formatted result, one line per rule
+    fn format_policy_result(name: &str, result: &PolicyResult) -> String {
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "\n\x1b[1;33m{}\x1b[0m",
+            tr("commands.check.policy.title", &[("policy", name)])
+        );
+
+        for rule_result in &result.rules {
+            let (key, count) = match rule_result.rule {
+                PolicyRule::MinLength(count) => ("min_length", Some(count)),
+                PolicyRule::MaxLength(count) => ("max_length", Some(count)),
+                PolicyRule::MinLowercase(count) => ("min_lowercase", Some(count)),
+                PolicyRule::MinUppercase(count) => ("min_uppercase", Some(count)),
+                PolicyRule::MinDigits(count) => ("min_digits", Some(count)),
+                PolicyRule::MinSpecial(count) => ("min_special", Some(count)),
+                PolicyRule::NotCommon => ("not_common", None),
+                PolicyRule::NoUsername => ("no_username", None),
+                PolicyRule::NoRepetitions => ("no_repetitions", None),
+            };
+            let count = count.map(|c| c.to_string()).unwrap_or_default();
+            let description = tr(
+                &format!("commands.check.policy.rules.{}", key),
+                &[("count", count.as_str())],
+            );
+
+            let _ = writeln!(
+                output,
+                "  {}\x1b[0m {}",
+                if rule_result.passed {
+                    "\x1b[1;32m✓"
+                } else {
+                    "\x1b[1;31m✗"
+                },
+                description
+            );
+        }
 
-            if !analysis.suggestions.is_empty() && analysis.score.total < 80 {
-                println!(
-                    "\n\x1b[1;33m{}\x1b[0m",
-                    Lingua::t("commands.check.suggestions.title", &[]).unwrap()
-                );
-                for suggestion in &analysis.suggestions {
-                    println!("  💡\t{}", suggestion);
-                }
-            }
+        if result.is_compliant() {
+            let _ = writeln!(
+                output,
+                "\n\x1b[1;32m{}\x1b[0m",
+                tr("commands.check.policy.compliant", &[])
+            );
+        } else {
+            let _ = writeln!(
+                output,
+                "\n\x1b[1;31m{}\x1b[0m",
+                tr("commands.check.policy.non_compliant", &[])
+            );
         }
 
-        println!("{}", "=".repeat(50));
+        output
+    }
+
+    /// Print a verification report
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the verified policy
+    /// * `report`: The report to print
+    pub fn print_verification_report(name: &str, report: &VerificationReport) {
+        print!("{}", Self::format_verification_report(name, report));
+    }
+
+    /// Format a verification report
+    ///
+    /// The output only contains counts and metrics, never characters of the password.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the verified policy
+    /// * `report`: The report to format
+    ///
+    /// # Returns
+    ///
+    /// Returns the formatted report
+    fn format_verification_report(name: &str, report: &VerificationReport) -> String {
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "\n\x1b[1;36m{}\x1b[0m",
+            tr("commands.generate.verify.title", &[])
+        );
+        let _ = writeln!(output, "{}", "=".repeat(50));
+        let _ = writeln!(
+            output,
+            "{}",
+            tr(
+                "commands.check.rating",
+                &[
+                    ("rating", report.rating.as_str()),
+                    ("points", report.score.to_string().as_str())
+                ]
+            )
+        );
+        let _ = writeln!(
+            output,
+            "{}",
+            tr(
+                "commands.check.length",
+                &[("length", report.length.to_string().as_str())]
+            )
+        );
+        let _ = writeln!(
+            output,
+            "{}",
+            tr(
+                "commands.generate.verify.classes",
+                &[
+                    ("lowercase", report.lowercase.to_string().as_str()),
+                    ("uppercase", report.uppercase.to_string().as_str()),
+                    ("digits", report.digits.to_string().as_str()),
+                    ("special", report.special.to_string().as_str())
+                ]
+            )
+        );
+        let _ = writeln!(
+            output,
+            "{}",
+            tr(
+                "commands.check.entropy",
+                &[
+                    ("entropy", format!("{:.2}", report.entropy).as_str()),
+                    (
+                        "observed_entropy",
+                        format!("{:.2}", report.observed_entropy).as_str()
+                    )
+                ]
+            )
+        );
+        output.push_str(&Self::format_policy_result(name, &report.policy));
+        let _ = writeln!(output, "{}", "=".repeat(50));
+
+        output
     }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::password::policy::STRICT_POLICY_NAME;
 
     #[test]
     fn test_calculate_length_score() {
@@ -590,6 +2306,126 @@ mod tests {
         assert_eq!(HealthCheck::calculate_length_score(20), 25);
     }
 
+    #[test]
+    fn test_score_components_stay_within_their_maxima() {
+        init_lingua_for_tests();
+        assert_eq!(PasswordScore::MAX_TOTAL, 100);
+
+        let corpus = [
+            "",
+            "a",
+            "password",
+            "123456789012",
+            "aaaaaaaaaaaaaaaaaaaaaaaa",
+            "Xy9$mK2@nP7#qW",
+            "Tr0ub4dor&3",
+            "correct-horse-battery-staple",
+            "wincing--edginess-vitally-Sprocket",
+            "Äpfel-Öl-Übermut-42!",
+            "!@#$%^&*()-_=+!@#$%^&*()-_=+",
+            "aB3$aB3$aB3$aB3$aB3$aB3$",
+            "ZxcvbnmasdfghjklQwertyuiop1234567890!@#$",
+        ];
+        for password in corpus {
+            let score = HealthCheck::analyze_password(password).score;
+            assert!(
+                score.length_score <= PasswordScore::MAX_LENGTH_SCORE,
+                "{}",
+                password
+            );
+            assert!(
+                score.diversity_score <= PasswordScore::MAX_DIVERSITY_SCORE,
+                "{}",
+                password
+            );
+            assert!(
+                score.complexity_score <= PasswordScore::MAX_COMPLEXITY_SCORE,
+                "{}",
+                password
+            );
+            assert!(
+                score.entropy_score <= PasswordScore::MAX_ENTROPY_SCORE,
+                "{}",
+                password
+            );
+            assert!(score.total <= PasswordScore::MAX_TOTAL, "{}", password);
+            assert!(score.normalized() <= 100, "{}", password);
+        }
+    }
+
+    #[test]
+    fn test_normalized_score() {
+        let score = |total| PasswordScore {
+            total,
+            length_score: 0,
+            diversity_score: 0,
+            complexity_score: 0,
+            entropy_score: 0,
+            entropy_penalty: 0,
+            weights: ScoringWeights::DEFAULT,
+        };
+        assert_eq!(score(0).normalized(), 0);
+        assert_eq!(score(67).normalized(), 67);
+        assert_eq!(score(PasswordScore::MAX_TOTAL).normalized(), 100);
+        // A total above the maximum is still shown as a full gauge
+        assert_eq!(score(PasswordScore::MAX_TOTAL + 10).normalized(), 100);
+    }
+
+    #[test]
+    fn test_default_weights_sum_the_components() {
+        init_lingua_for_tests();
+        for password in ["password", "Tr0ub4dor&3", "aaaaaaaA1!", "Xy9$mK2@nP7#qW"] {
+            let score =
+                HealthCheck::analyze_password_with(password, &ScoringWeights::DEFAULT).score;
+            let sum = score.length_score
+                + score.diversity_score
+                + score.complexity_score
+                + score.entropy_score;
+            assert_eq!(
+                score.total,
+                sum.saturating_sub(score.entropy_penalty),
+                "{}",
+                password
+            );
+            assert_eq!(
+                score.total,
+                HealthCheck::analyze_password(password).score.total,
+                "{}",
+                password
+            );
+        }
+    }
+
+    #[test]
+    fn test_weights_move_rating_bands() {
+        init_lingua_for_tests();
+        let length_first = ScoringWeights {
+            length: 3.0,
+            diversity: 0.5,
+            ..ScoringWeights::DEFAULT
+        };
+        let rating = |password: &str, weights: &ScoringWeights| {
+            let analysis = HealthCheck::analyze_password_with(password, weights);
+            (analysis.score.normalized(), analysis.rating)
+        };
+
+        // Long but lowercase only: strong by default, very strong when length counts more
+        let long = "qzvkwjxmptrbnhgfdslcyu";
+        assert_eq!(rating(long, &ScoringWeights::DEFAULT).0, 75);
+        let (points, rating_text) = rating(long, &length_first);
+        assert_eq!(points, 91);
+        assert_eq!(
+            rating_text,
+            tr("commands.check.score_rating.very_strong", &[])
+        );
+
+        // Short but diverse: strong by default, weak when length counts more
+        assert_eq!(rating("Ab1!xY", &ScoringWeights::DEFAULT).0, 65);
+        let (points, rating_text) = rating("Ab1!xY", &length_first);
+        assert_eq!(points, 37);
+        assert_eq!(rating_text, tr("commands.check.score_rating.weak", &[]));
+    }
+
     #[test]
     fn test_calculate_diversity_score() {
         let (score, has_lower, has_upper, has_digit, has_special) =
@@ -614,9 +2450,357 @@ mod tests {
 
     #[test]
     fn test_has_common_patterns() {
-        assert!(HealthCheck::has_common_patterns("password"));
-        assert!(HealthCheck::has_common_patterns("123456"));
-        assert!(!HealthCheck::has_common_patterns("Xy9$mK2@nP7#qW"));
+        assert!(HealthCheck::find_common_pattern("password", None).is_some());
+        assert!(HealthCheck::find_common_pattern("123456", None).is_some());
+        assert!(HealthCheck::find_common_pattern("Xy9$mK2@nP7#qW", None).is_none());
+    }
+
+    #[test]
+    fn test_common_passwords_index() {
+        let index = CommonPasswords::index();
+        assert!(index.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(HealthCheck::is_common_password("Dragon"));
+        assert!(!HealthCheck::is_common_password("MyDragon2024!"));
+
+        assert_eq!(CommonPasswords.find("mydragon2024!"), Some(6));
+        assert_eq!(CommonPasswords.find("1234"), Some(4));
+        // Four character entries only match exactly, fragments of entries not at all
+        assert_eq!(CommonPasswords.find("xy9$mk1234@np7#qw"), None);
+        assert_eq!(CommonPasswords.find("ass"), None);
+        assert_eq!(CommonPasswords.find("ä-password-ö"), Some(8));
+        assert_eq!(
+            CommonPasswords::locate("mydragon2024!"),
+            Some(Span::new(2, 8))
+        );
+        assert_eq!(CommonPasswords::locate("dragon"), Some(Span::new(0, 6)));
+    }
+
+    struct TestDictionary;
+
+    impl DictionaryProvider for TestDictionary {
+        fn name(&self) -> &str {
+            "corporate.txt"
+        }
+
+        fn find(&self, password: &str) -> Option<usize> {
+            password.contains("vexquorin").then_some(9)
+        }
+    }
+
+    #[test]
+    fn test_added_dictionary() {
+        HealthCheck::add_dictionary(Box::new(TestDictionary));
+
+        assert_eq!(
+            HealthCheck::find_common_pattern("Xy9$VexQuorin!2", None),
+            Some(PatternSource::BannedWords("corporate.txt".to_string()))
+        );
+        assert!(HealthCheck::find_common_pattern("Xy9$mK2@nP7#qW", None).is_none());
+    }
+
+    #[test]
+    fn test_banned_words() {
+        let path =
+            std::env::temp_dir().join(format!("kdguard_banned_words_{}.txt", std::process::id()));
+        fs::write(&path, "# corporate list\nQworvex\nZyntrax\n\nxyz\n").unwrap();
+        let banned_words = BannedWords::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            banned_words.name(),
+            path.file_name().unwrap().to_str().unwrap()
+        );
+        assert!(banned_words.matches("QWORVEX"));
+        assert!(banned_words.matches("Xy9$qworvex!2"));
+        assert!(banned_words.matches("xyz"));
+        assert!(!banned_words.matches("Xy9$xyz!2"));
+        assert!(!banned_words.matches("Xy9$mK2@nP7#qW"));
+
+        assert_eq!(
+            HealthCheck::find_common_pattern("Xy9$Zyntrax!2", Some(&banned_words)),
+            Some(PatternSource::BannedWords(banned_words.name().to_string()))
+        );
+        assert_eq!(
+            HealthCheck::find_common_pattern("password", Some(&banned_words)),
+            Some(PatternSource::CommonPasswords {
+                matched_len: 8,
+                span: Span::new(0, 8)
+            })
+        );
+        assert_eq!(
+            HealthCheck::find_common_pattern("Xy9$mK2@nP7#qW", Some(&banned_words)),
+            None
+        );
+        assert_eq!(
+            HealthCheck::find_common_pattern("Xy9$Zyntrax!2", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_banned_words_missing_file() {
+        let path = std::env::temp_dir().join("kdguard_banned_words_missing.txt");
+        assert!(BannedWords::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_check_file_cancellation_writes_partial_output() {
+        init_lingua_for_tests();
+
+        let input = std::env::temp_dir().join(format!(
+            "kdguard_bulk_check_input_{}.txt",
+            std::process::id()
+        ));
+        let output = std::env::temp_dir().join(format!(
+            "kdguard_bulk_check_output_{}.tsv",
+            std::process::id()
+        ));
+        let passwords: Vec<String> = (0..200).map(|i| format!("Xy9$mK2@nP{}", i)).collect();
+        fs::write(&input, passwords.join("\n")).unwrap();
+
+        let cancellation = Cancellation::new();
+        let handle = cancellation.clone();
+        let mut file = std::io::BufWriter::new(fs::File::create(&output).unwrap());
+        let outcome = HealthCheck::check_file(
+            &input,
+            &mut file,
+            None,
+            &mut |progress: crate::progress::Progress| {
+                if progress.completed >= 20 {
+                    handle.cancel();
+                }
+            },
+            &cancellation,
+        )
+        .unwrap();
+        drop(file);
+
+        let content = fs::read_to_string(&output).unwrap();
+        fs::remove_file(&input).unwrap();
+        fs::remove_file(&output).unwrap();
+
+        assert!(outcome.cancelled);
+        assert_eq!(outcome.completed, 20);
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 21);
+        assert_eq!(lines[0], "line\tscore\trating");
+        assert!(lines[20].starts_with("20\t"));
+        assert!(!content.contains("Xy9$mK2@nP"));
+    }
+
+    #[test]
+    fn test_levenshtein_and_longest_common_substring() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        assert_eq!(
+            HealthCheck::levenshtein_distance(&chars("kitten"), &chars("sitting")),
+            3
+        );
+        assert_eq!(
+            HealthCheck::levenshtein_distance(&chars(""), &chars("abc")),
+            3
+        );
+        assert_eq!(
+            HealthCheck::longest_common_substring(&chars("hunter2"), &chars("xhunt3")),
+            4
+        );
+        assert_eq!(
+            HealthCheck::longest_common_substring(&chars("abc"), &chars("xyz")),
+            0
+        );
+    }
+
+    #[test]
+    fn test_compare_passwords_case_only_change() {
+        let report = HealthCheck::compare_passwords("Summer#Breeze42", "sUMMER#bREEZE42");
+        assert!(report.case_only_change);
+        assert!(!report.counter_increment);
+        assert!(report.is_similar);
+    }
+
+    #[test]
+    fn test_compare_passwords_counter_increment() {
+        let report = HealthCheck::compare_passwords("hunter2", "hunter3");
+        assert!(report.counter_increment);
+        assert_eq!(report.levenshtein_distance, 1);
+        assert_eq!(report.longest_common_substring, 6);
+        assert!(report.is_similar);
+
+        let report = HealthCheck::compare_passwords("Tr0ub4dor&3x", "Tr0ub4dor&3x10");
+        assert!(report.counter_increment);
+        assert!(report.is_similar);
+    }
+
+    #[test]
+    fn test_compare_passwords_different() {
+        let report = HealthCheck::compare_passwords("hunter2", "Xy9$mK2@nP7#qW");
+        assert!(!report.case_only_change);
+        assert!(!report.counter_increment);
+        assert!(report.similarity < SIMILARITY_THRESHOLD);
+        assert!(!report.is_similar);
+    }
+
+    #[test]
+    fn test_verification_report_does_not_leak_password() {
+        init_lingua_for_tests();
+
+        let password = Generator::generate_deterministic_password(
+            "verify-seed",
+            None,
+            Some("github"),
+            &crate::config::DeterministicConfig::default(),
+        )
+        .unwrap();
+        let report = HealthCheck::verify_password(&password, &Policy::strict());
+        let output = HealthCheck::format_verification_report(STRICT_POLICY_NAME, &report);
+
+        assert_eq!(report.length, 20);
+        assert_eq!(
+            report.lowercase + report.uppercase + report.digits + report.special,
+            20
+        );
+        assert!(!output.contains(&password));
+        let chars: Vec<char> = password.chars().collect();
+        for window in chars.windows(4) {
+            let fragment: String = window.iter().collect();
+            assert!(!output.contains(&fragment), "leaked fragment {}", fragment);
+        }
+    }
+
+    fn rule_passed(result: &PolicyResult, rule: PolicyRule) -> bool {
+        result
+            .rules
+            .iter()
+            .find(|r| r.rule == rule)
+            .map(|r| r.passed)
+            .expect("rule not part of the policy")
+    }
+
+    #[test]
+    fn test_format_compact_result() {
+        init_lingua_for_tests();
+
+        let analysis = HealthCheck::analyze_password("Xy9$mK2@nP7");
+        let line = HealthCheck::format_compact_result(3, &analysis, None);
+        assert!(line.starts_with("#3 "), "{}", line);
+        assert!(line.contains(&analysis.score.normalized().to_string()));
+        assert!(!line.contains("Xy9$mK2@nP7"));
+        assert!(!line.contains('\n'));
+
+        let policy = Policy {
+            min_length: 12,
+            ..Policy::default()
+        };
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP7", &policy);
+        let line = HealthCheck::format_compact_result(3, &analysis, Some(&result));
+        assert!(line.contains("✗"), "{}", line);
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP7#qW", &policy);
+        let line = HealthCheck::format_compact_result(3, &analysis, Some(&result));
+        assert!(line.contains("✓"), "{}", line);
+    }
+
+    #[test]
+    fn test_policy_length_rules() {
+        let policy = Policy {
+            min_length: 12,
+            max_length: Some(16),
+            ..Policy::default()
+        };
+
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP7", &policy);
+        assert!(!rule_passed(&result, PolicyRule::MinLength(12)));
+        assert!(rule_passed(&result, PolicyRule::MaxLength(16)));
+
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP7#qWz4!", &policy);
+        assert!(rule_passed(&result, PolicyRule::MinLength(12)));
+        assert!(!rule_passed(&result, PolicyRule::MaxLength(16)));
+
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP7#qW", &policy);
+        assert!(result.is_compliant());
+    }
+
+    #[test]
+    fn test_policy_skips_zero_minimums() {
+        let policy = Policy {
+            min_length: 0,
+            ..Policy::default()
+        };
+        assert!(policy.rules().is_empty());
+
+        let result = HealthCheck::validate_against_policy("a", &policy);
+        assert!(result.rules.is_empty());
+        assert!(result.is_compliant());
+    }
+
+    #[test]
+    fn test_policy_character_class_rules() {
+        let policy = Policy {
+            min_length: 0,
+            min_lowercase: 2,
+            min_uppercase: 2,
+            min_digits: 2,
+            min_special: 2,
+            ..Policy::default()
+        };
+
+        let result = HealthCheck::validate_against_policy("abCD12!.", &policy);
+        assert!(result.is_compliant());
+
+        let result = HealthCheck::validate_against_policy("aBC1!", &policy);
+        assert!(!rule_passed(&result, PolicyRule::MinLowercase(2)));
+        assert!(rule_passed(&result, PolicyRule::MinUppercase(2)));
+        assert!(!rule_passed(&result, PolicyRule::MinDigits(2)));
+        assert!(!rule_passed(&result, PolicyRule::MinSpecial(2)));
+        assert!(!result.is_compliant());
+    }
+
+    #[test]
+    fn test_policy_common_username_and_repetition_rules() {
+        let policy = Policy {
+            forbid_common: true,
+            forbid_username: true,
+            forbid_repetitions: true,
+            ..Policy::default()
+        }
+        .with_username(Some("jdoe".to_string()));
+
+        let result = HealthCheck::validate_against_policy("password", &policy);
+        assert!(!rule_passed(&result, PolicyRule::NotCommon));
+
+        let result = HealthCheck::validate_against_policy("Xy9$JDoe@nP7#qW", &policy);
+        assert!(!rule_passed(&result, PolicyRule::NoUsername));
+
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP777qW", &policy);
+        assert!(!rule_passed(&result, PolicyRule::NoRepetitions));
+
+        let result = HealthCheck::validate_against_policy("Xy9$mK2@nP7#qW", &policy);
+        assert!(result.is_compliant());
+    }
+
+    #[test]
+    fn test_builtin_policies() {
+        let nist = Policy::builtin("NIST-800-63B").unwrap();
+        assert_eq!(nist, Policy::nist());
+        assert_eq!(
+            nist.rules(),
+            vec![PolicyRule::MinLength(8), PolicyRule::NotCommon]
+        );
+        assert_eq!(
+            nist.clone().with_username(Some("jdoe".to_string())).rules(),
+            vec![
+                PolicyRule::MinLength(8),
+                PolicyRule::NotCommon,
+                PolicyRule::NoUsername
+            ]
+        );
+        assert!(Policy::builtin("strict").is_some());
+        assert!(Policy::builtin("unknown").is_none());
+
+        let strict = Policy::strict().with_username(Some("jdoe".to_string()));
+        let nist = nist.with_username(Some("jdoe".to_string()));
+        let lowercase_only = "vbqzhxwpjtkf";
+        assert!(HealthCheck::validate_against_policy(lowercase_only, &nist).is_compliant());
+        assert!(!HealthCheck::validate_against_policy(lowercase_only, &strict).is_compliant());
+        assert!(HealthCheck::validate_against_policy("Xy9$mK2@nP7#qW", &strict).is_compliant());
     }
 
     #[test]
@@ -630,17 +2814,112 @@ mod tests {
         assert_eq!(entropy2, 0.0);
     }
 
-    fn init_lingua_for_tests() {
+    #[test]
+    fn test_calculate_observed_entropy() {
+        assert_eq!(HealthCheck::calculate_observed_entropy(""), 0.0);
+        assert_eq!(HealthCheck::calculate_observed_entropy("aaaa"), 0.0);
+
+        let repetitive = HealthCheck::calculate_observed_entropy("aaaaaaaA1!");
+        let mixed = HealthCheck::calculate_observed_entropy("Xy9$mK2@nP");
+        assert!(repetitive < mixed);
+        assert!((mixed - 10.0 * 10f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_entropy_ratio_and_penalty() {
+        let repetitive = "aaaaaaaA1!";
+        let ratio = HealthCheck::calculate_entropy_ratio(
+            repetitive,
+            HealthCheck::calculate_observed_entropy(repetitive),
+        );
+        assert!(ratio < ENTROPY_RATIO_THRESHOLD);
+        assert!(HealthCheck::calculate_entropy_penalty(ratio) > 0);
+
+        let mixed = "Xy9$mK2@nP7#qW";
+        let ratio = HealthCheck::calculate_entropy_ratio(
+            mixed,
+            HealthCheck::calculate_observed_entropy(mixed),
+        );
+        assert_eq!(ratio, 1.0);
+        assert_eq!(HealthCheck::calculate_entropy_penalty(ratio), 0);
+
+        assert_eq!(
+            HealthCheck::calculate_entropy_penalty(0.0),
+            MAX_ENTROPY_PENALTY
+        );
+
+        // Long passwords are measured against their character set, not their length
+        let digits: String = (0..200)
+            .map(|i| char::from_digit((i * 7) % 10, 10).unwrap())
+            .collect();
+        let ratio = HealthCheck::calculate_entropy_ratio(
+            &digits,
+            HealthCheck::calculate_observed_entropy(&digits),
+        );
+        assert!((ratio - 1.0).abs() < 1e-9, "{}", ratio);
+        assert_eq!(HealthCheck::calculate_entropy_penalty(ratio), 0);
+    }
+
+    #[test]
+    fn test_detect_passphrase() {
+        assert_eq!(
+            HealthCheck::detect_passphrase("wincing-edginess-vitally-footprint-absence-wolverine"),
+            Some((6, 7776))
+        );
+        assert_eq!(
+            HealthCheck::detect_passphrase("Nager Anketten Gediegen Geduld"),
+            Some((4, 7776))
+        );
+        assert_eq!(
+            HealthCheck::detect_passphrase("t-shirt-drop-down-felt-tip"),
+            Some((3, 7776))
+        );
+        assert_eq!(HealthCheck::detect_passphrase("wincing-edginess"), None);
+        assert_eq!(
+            HealthCheck::detect_passphrase("wincing-xqzv-footprint"),
+            None
+        );
+        assert_eq!(
+            HealthCheck::detect_passphrase("wincing--edginess-vitally"),
+            None
+        );
+        assert_eq!(HealthCheck::detect_passphrase("Xy9$mK2@nP7#qW"), None);
+    }
+
+    #[test]
+    fn test_repeated_passphrase_words_count_once() {
+        assert_eq!(
+            HealthCheck::detect_passphrase("wincing-wincing-edginess-wincing-vitally"),
+            Some((3, 7776))
+        );
+        assert_eq!(
+            HealthCheck::detect_passphrase("apple-apple-apple-apple-apple-apple"),
+            None
+        );
+
+        let repeated = HealthCheck::analyze_password("apple-apple-apple-apple-apple-apple");
+        assert!(!matches!(
+            repeated.entropy_model,
+            EntropyModel::Passphrase { .. }
+        ));
+        let distinct =
+            HealthCheck::analyze_password("wincing-edginess-vitally-footprint-absence-wolverine");
+        assert!(repeated.score.total < distinct.score.total);
+        assert!(repeated.score.total < 65, "{}", repeated.score.total);
+    }
+
+    pub(crate) fn init_lingua_for_tests() {
+        use lingua_i18n_rs::prelude::Lingua;
         use std::sync::Once;
         static INIT: Once = Once::new();
 
         INIT.call_once(|| {
-            if let Ok(languages_path) = crate::config::Config::get_languages_path() {
-                if let Some(path_str) = languages_path.to_str() {
-                    let lingua = Lingua::new(path_str);
-                    let _ = lingua.init();
-                    let _ = Lingua::set_language("en");
-                }
+            if let Ok(languages_path) = crate::config::Config::get_languages_path()
+                && let Some(path_str) = languages_path.to_str()
+            {
+                let lingua = Lingua::new(path_str);
+                let _ = lingua.init();
+                let _ = Lingua::set_language("en");
             }
         });
     }
@@ -674,4 +2953,124 @@ mod tests {
         assert!(analysis.has_special);
         assert!(analysis.score.total > 0);
     }
+
+    #[test]
+    fn test_analyze_password_warnings() {
+        init_lingua_for_tests();
+
+        let analysis = HealthCheck::analyze_password("abc");
+        assert!(analysis.warnings.contains(&Warning::TooShort));
+        assert!(analysis.warnings.contains(&Warning::NoUppercase));
+        assert!(analysis.warnings.contains(&Warning::NoDigits));
+        assert!(analysis.warnings.contains(&Warning::NoSpecial));
+        assert!(!analysis.warnings.contains(&Warning::NoLowercase));
+        assert!(analysis.suggestions.contains(&Suggestion::IncreaseLength));
+
+        let analysis = HealthCheck::analyze_password("Xy9$password!2");
+        assert!(analysis.warnings.contains(&Warning::CommonPattern {
+            matched_len: 8,
+            span: Span::new(4, 12)
+        }));
+        assert!(
+            analysis
+                .suggestions
+                .contains(&Suggestion::AvoidSimpleSequences)
+        );
+
+        let analysis = HealthCheck::analyze_password("Xy9$mK2@nP7#qW");
+        assert!(analysis.warnings.is_empty());
+        assert!(analysis.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_warning_spans() {
+        init_lingua_for_tests();
+
+        assert_eq!(
+            HealthCheck::find_repetition("Xy9$aaaa!2"),
+            Some(Span::new(4, 8))
+        );
+        assert_eq!(HealthCheck::find_repetition("aab"), None);
+        // Counted in chars, the umlauts take two bytes each
+        assert_eq!(
+            HealthCheck::find_repetition("äöüßßß1"),
+            Some(Span::new(3, 6))
+        );
+
+        assert_eq!(
+            HealthCheck::find_sequence("Xq!abcd7"),
+            Some(Span::new(3, 7))
+        );
+        assert_eq!(HealthCheck::find_sequence("ü$9876"), Some(Span::new(2, 6)));
+        assert_eq!(HealthCheck::find_sequence("xAbC"), Some(Span::new(1, 4)));
+        // Two in a row, letters into digits, and skipped letters are no sequence
+        assert_eq!(HealthCheck::find_sequence("ab!z9:ace"), None);
+
+        assert_eq!(HealthCheck::find_year("Qü!1987x"), Some(Span::new(3, 7)));
+        assert_eq!(HealthCheck::find_year("pw12024"), None);
+        assert_eq!(HealthCheck::find_year("pw1850"), None);
+        assert_eq!(HealthCheck::find_year("x99-2031"), Some(Span::new(4, 8)));
+
+        let analysis = HealthCheck::analyze_password("Ä€!qwerty2024");
+        assert!(analysis.warnings.contains(&Warning::Year {
+            span: Span::new(9, 13)
+        }));
+        assert!(analysis.warnings.contains(&Warning::CommonPattern {
+            matched_len: 6,
+            span: Span::new(3, 9)
+        }));
+        assert!(analysis.suggestions.contains(&Suggestion::AvoidYears));
+
+        let (masked, markers) = Span::new(3, 9).markers(13);
+        assert_eq!(masked, "*************");
+        assert_eq!(markers, "   ^^^^^^");
+    }
+
+    #[test]
+    fn test_analyze_password_observed_entropy() {
+        init_lingua_for_tests();
+
+        let repetitive = HealthCheck::analyze_password("aaaaaaaA1!");
+        let mixed = HealthCheck::analyze_password("Xy9$mK2@nP");
+        assert_eq!(repetitive.entropy, mixed.entropy);
+        assert!(repetitive.observed_entropy < mixed.observed_entropy);
+        assert!(repetitive.score.entropy_penalty > 0);
+        assert_eq!(mixed.score.entropy_penalty, 0);
+        assert!(repetitive.score.total < mixed.score.total);
+        assert!(repetitive.warnings.contains(&Warning::LowObservedEntropy));
+        assert!(repetitive.suggestions.contains(&Suggestion::VaryCharacters));
+        assert!(!mixed.warnings.contains(&Warning::LowObservedEntropy));
+    }
+
+    #[test]
+    fn test_analyze_passphrase() {
+        init_lingua_for_tests();
+
+        let phrase = "wincing-edginess-vitally-footprint-absence-wolverine";
+        let (diversity_score, _, _, _, _) = HealthCheck::calculate_diversity_score(phrase);
+        let (character_entropy_score, _) = HealthCheck::calculate_entropy_score(phrase);
+        let character_total = HealthCheck::calculate_length_score(phrase.len())
+            + diversity_score
+            + HealthCheck::calculate_complexity_score(
+                HealthCheck::find_common_pattern(phrase, None).is_some(),
+                HealthCheck::has_repetitions(phrase),
+            )
+            + character_entropy_score;
+        let very_strong = tr("commands.check.score_rating.very_strong", &[]);
+        assert_ne!(HealthCheck::score_to_rating(character_total), very_strong);
+
+        let analysis = HealthCheck::analyze_password(phrase);
+        assert_eq!(
+            analysis.entropy_model,
+            EntropyModel::Passphrase { words: 6 }
+        );
+        assert!((analysis.entropy - 6.0 * 7776f64.log2()).abs() < 1e-9);
+        assert_eq!(analysis.rating, very_strong);
+
+        assert!(!analysis.warnings.contains(&Warning::NoDigits));
+        assert!(!analysis.warnings.contains(&Warning::NoSpecial));
+
+        let random = HealthCheck::analyze_password("Xy9$mK2@nP7#qW");
+        assert_eq!(random.entropy_model, EntropyModel::Character);
+    }
 }
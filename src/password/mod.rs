@@ -1,5 +1,27 @@
+pub mod backup_sheet;
+pub mod breach;
+pub mod breach_db;
+pub mod expiry;
+pub mod feedback;
 mod generator;
 pub mod health_check;
+pub mod policy;
+pub mod request;
+pub mod reuse;
 
-pub use generator::Generator;
-pub use health_check::{HealthCheck, PasswordAnalysis};
+pub use backup_sheet::{BackupSheet, SheetFormat};
+pub use breach::BreachCheck;
+pub use breach_db::BreachDatabase;
+pub use expiry::ExpiryReport;
+pub use feedback::{Span, Suggestion, Warning};
+pub(crate) use generator::DETERMINISTIC_LENGTH;
+pub use generator::{
+    DEFAULT_ARGON2_ITERATIONS, DEFAULT_ARGON2_MEMORY_KIB, GenerationMetadata, Generator,
+    InsertStyle, Kdf, PhraseOptions, SavedEntry,
+};
+pub use health_check::{
+    CommonPasswords, DictionaryProvider, HealthCheck, PasswordAnalysis, ScoringWeights,
+};
+pub use policy::Policy;
+pub use request::{GeneratedPassword, PasswordRequest};
+pub use reuse::ReuseReport;
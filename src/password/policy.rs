@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of the built-in NIST SP 800-63B profile
+pub const NIST_POLICY_NAME: &str = "nist-800-63b";
+/// Name of the built-in strict profile, used when `--policy` is given without a name
+pub const STRICT_POLICY_NAME: &str = "strict";
+
+/// A password policy profile
+///
+/// Minimums of 0 and disabled flags are not checked, neither is `forbid_username`
+/// without a username.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Policy {
+    pub min_length: usize,
+    pub max_length: Option<usize>,
+    pub min_lowercase: usize,
+    pub min_uppercase: usize,
+    pub min_digits: usize,
+    pub min_special: usize,
+    pub forbid_common: bool,
+    pub forbid_username: bool,
+    pub forbid_repetitions: bool,
+    /// Username checked by the `forbid_username` rule, from the profile or `--username`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// A single rule of a password policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyRule {
+    MinLength(usize),
+    MaxLength(usize),
+    MinLowercase(usize),
+    MinUppercase(usize),
+    MinDigits(usize),
+    MinSpecial(usize),
+    NotCommon,
+    NoUsername,
+    NoRepetitions,
+}
+
+/// The outcome of a single policy rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleResult {
+    pub rule: PolicyRule,
+    pub passed: bool,
+}
+
+/// The outcome of validating a password against a policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyResult {
+    pub rules: Vec<RuleResult>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: None,
+            min_lowercase: 0,
+            min_uppercase: 0,
+            min_digits: 0,
+            min_special: 0,
+            forbid_common: false,
+            forbid_username: false,
+            forbid_repetitions: false,
+            username: None,
+        }
+    }
+}
+
+impl Policy {
+    /// NIST SP 800-63B profile
+    ///
+    /// At least 8 characters, no composition rules, no common or context-specific passwords.
+    pub fn nist() -> Self {
+        Self {
+            min_length: 8,
+            forbid_common: true,
+            forbid_username: true,
+            ..Self::default()
+        }
+    }
+
+    /// Strict profile requiring length, every character class, and no weak patterns
+    pub fn strict() -> Self {
+        Self {
+            min_length: 12,
+            max_length: None,
+            min_lowercase: 1,
+            min_uppercase: 1,
+            min_digits: 1,
+            min_special: 2,
+            forbid_common: true,
+            forbid_username: true,
+            forbid_repetitions: true,
+            username: None,
+        }
+    }
+
+    /// Get a built-in policy profile by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the profile
+    ///
+    /// # Returns
+    ///
+    /// Returns the profile if it exists, otherwise None
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            NIST_POLICY_NAME => Some(Self::nist()),
+            STRICT_POLICY_NAME => Some(Self::strict()),
+            _ => None,
+        }
+    }
+
+    /// Set the username checked by the `forbid_username` rule
+    ///
+    /// # Arguments
+    ///
+    /// * `username`: The username
+    ///
+    /// # Returns
+    ///
+    /// Returns the policy with the username set
+    pub fn with_username(mut self, username: Option<String>) -> Self {
+        self.username = username;
+        self
+    }
+
+    /// Get the username checked by the `forbid_username` rule
+    ///
+    /// The username is never taken from the environment, so a password is validated the
+    /// same way no matter who runs the check.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured username, None if none or only whitespace is set
+    pub fn username(&self) -> Option<&str> {
+        self.username
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Get the active rules of the policy
+    ///
+    /// # Returns
+    ///
+    /// Returns the rules in the order they are reported
+    pub fn rules(&self) -> Vec<PolicyRule> {
+        let mut rules = Vec::new();
+
+        if self.min_length > 0 {
+            rules.push(PolicyRule::MinLength(self.min_length));
+        }
+        if let Some(max_length) = self.max_length {
+            rules.push(PolicyRule::MaxLength(max_length));
+        }
+        if self.min_lowercase > 0 {
+            rules.push(PolicyRule::MinLowercase(self.min_lowercase));
+        }
+        if self.min_uppercase > 0 {
+            rules.push(PolicyRule::MinUppercase(self.min_uppercase));
+        }
+        if self.min_digits > 0 {
+            rules.push(PolicyRule::MinDigits(self.min_digits));
+        }
+        if self.min_special > 0 {
+            rules.push(PolicyRule::MinSpecial(self.min_special));
+        }
+        if self.forbid_common {
+            rules.push(PolicyRule::NotCommon);
+        }
+        if self.forbid_username && self.username().is_some() {
+            rules.push(PolicyRule::NoUsername);
+        }
+        if self.forbid_repetitions {
+            rules.push(PolicyRule::NoRepetitions);
+        }
+
+        rules
+    }
+}
+
+impl PolicyResult {
+    /// Check whether every rule passed
+    pub fn is_compliant(&self) -> bool {
+        self.rules.iter().all(|result| result.passed)
+    }
+}
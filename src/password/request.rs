@@ -0,0 +1,496 @@
+use zeroize::Zeroizing;
+
+use crate::config::DeterministicConfig;
+use crate::errors::GeneratorError;
+use crate::logging::{LoggingManager, Redactable};
+use crate::password::generator::{
+    CHARSET, DETERMINISTIC_LENGTH, DIGITS, Generator, LOWERCASE, PhraseOptions, SPECIAL, UPPERCASE,
+};
+use crate::{CONFIG, GenerateArgs, PasswordMode};
+
+/// A password with the mode it was generated in
+#[derive(Clone)]
+pub struct GeneratedPassword {
+    pub password: String,
+    pub mode: PasswordMode,
+    /// Entropy of the generator's keyspace in bits, like `Generator::estimate_entropy`
+    pub entropy: f64,
+}
+
+/// The parameters of a password, checked together when it is built
+///
+/// Options that do not belong to the mode of the request, e.g. a length for a phrase,
+/// are rejected by `build` instead of being ignored.
+#[derive(Clone)]
+pub struct PasswordRequest {
+    mode: PasswordMode,
+    length: Option<usize>,
+    exclude: Option<String>,
+    typeable: Option<usize>,
+    pattern: String,
+    shuffle: bool,
+    words: usize,
+    phrase: Option<PhraseOptions>,
+    seed: Zeroizing<String>,
+    salt: Option<String>,
+    service: Option<String>,
+    counter: Option<u32>,
+    kdf: Option<DeterministicConfig>,
+}
+
+impl PasswordRequest {
+    /// Length of random passwords when none is set
+    pub const DEFAULT_LENGTH: usize = 16;
+
+    fn new(mode: PasswordMode) -> Self {
+        Self {
+            mode,
+            length: None,
+            exclude: None,
+            typeable: None,
+            pattern: String::new(),
+            shuffle: false,
+            words: 0,
+            phrase: None,
+            seed: Zeroizing::new(String::new()),
+            salt: None,
+            service: None,
+            counter: None,
+            kdf: None,
+        }
+    }
+
+    /// Request a random password with a character of every class
+    ///
+    /// ```
+    /// use kdguard::prelude::PasswordRequest;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let generated = PasswordRequest::random().length(24).exclude("0O1l").build()?;
+    /// assert_eq!(generated.password.len(), 24);
+    /// assert!(!generated.password.contains(['0', 'O', '1', 'l']));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn random() -> Self {
+        Self::new(PasswordMode::Random)
+    }
+
+    /// Request a password following a pattern of U, L, D, S, and aliases, or `@name`
+    ///
+    /// ```
+    /// use kdguard::prelude::PasswordRequest;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let generated = PasswordRequest::pattern("ULLLDD{special}").build()?;
+    /// assert_eq!(generated.password.chars().count(), 7);
+    /// assert!(generated.password.starts_with(|c: char| c.is_ascii_uppercase()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pattern(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            ..Self::new(PasswordMode::Pattern)
+        }
+    }
+
+    /// Request a passphrase from the wordlist of the configured language
+    ///
+    /// ```
+    /// use kdguard::prelude::{InsertStyle, PasswordRequest, PhraseOptions};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let generated = PasswordRequest::phrase(4).build()?;
+    /// assert_eq!(generated.password.split('-').count(), 4);
+    ///
+    /// let options = PhraseOptions { digits: 2, symbols: 0, style: InsertStyle::Append };
+    /// let generated = PasswordRequest::phrase(4).phrase_options(options).build()?;
+    /// assert!(generated.password.ends_with(|c: char| c.is_ascii_digit()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn phrase(words: usize) -> Self {
+        Self {
+            words,
+            ..Self::new(PasswordMode::Phrase)
+        }
+    }
+
+    /// Request the password a seed derives, the same on every machine
+    ///
+    /// ```
+    /// use kdguard::prelude::PasswordRequest;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let first = PasswordRequest::deterministic("correct horse").service("github").build()?;
+    /// let again = PasswordRequest::deterministic("correct horse").service("github").build()?;
+    /// let rotated = PasswordRequest::deterministic("correct horse")
+    ///     .service("github")
+    ///     .counter(2)
+    ///     .build()?;
+    /// assert_eq!(first.password, again.password);
+    /// assert_ne!(first.password, rotated.password);
+    /// assert_eq!(rotated.password.len(), 20);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deterministic(seed: &str) -> Self {
+        Self {
+            seed: Zeroizing::new(seed.to_string()),
+            ..Self::new(PasswordMode::Deterministic)
+        }
+    }
+
+    /// Set the length of a random password
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Leave out characters that are easily confused, random and pattern passwords only
+    pub fn exclude(mut self, chars: &str) -> Self {
+        self.exclude = Some(chars.to_string());
+        self
+    }
+
+    /// Pick the random password that is easiest to type out of several candidates
+    ///
+    /// The entropy of the result is lowered by log2(`candidates`), the most the choice
+    /// can give away.
+    ///
+    /// ```
+    /// use kdguard::prelude::PasswordRequest;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let plain = PasswordRequest::random().length(16).build()?;
+    /// let typeable = PasswordRequest::random().length(16).prefer_typeable(8).build()?;
+    /// assert_eq!(typeable.password.len(), 16);
+    /// assert_eq!(plain.entropy - typeable.entropy, 3.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prefer_typeable(mut self, candidates: usize) -> Self {
+        self.typeable = Some(candidates);
+        self
+    }
+
+    /// Permute the characters of a pattern password, so a position does not tell its class
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Set the digits and symbols inserted into a phrase
+    pub fn phrase_options(mut self, options: PhraseOptions) -> Self {
+        self.phrase = Some(options);
+        self
+    }
+
+    /// Set the salt of a deterministic password
+    pub fn salt(mut self, salt: &str) -> Self {
+        self.salt = Some(salt.to_string());
+        self
+    }
+
+    /// Set the service a deterministic password is derived for
+    pub fn service(mut self, service: &str) -> Self {
+        self.service = Some(service.to_string());
+        self
+    }
+
+    /// Set the version of a deterministic password, increased to rotate it
+    ///
+    /// The first version is 1 and gives the password of a request without a counter.
+    pub fn counter(mut self, counter: u32) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Set how the seed of a deterministic password is stretched, HKDF only by default
+    pub fn kdf(mut self, kdf: DeterministicConfig) -> Self {
+        self.kdf = Some(kdf);
+        self
+    }
+
+    /// Build the request of the parsed generation arguments
+    ///
+    /// The seed of deterministic mode is read from its environment variable once, so
+    /// the request can be built many times.
+    ///
+    /// # Arguments
+    ///
+    /// * `cli`: The parsed generation arguments
+    ///
+    /// # Returns
+    ///
+    /// Returns the request, else returns an error if the seed cannot be read
+    pub fn from_cli(cli: &GenerateArgs) -> Result<Self, GeneratorError> {
+        let request = match cli.mode {
+            PasswordMode::Random => Self::random().length(cli.length),
+            PasswordMode::Pattern => {
+                let pattern = cli
+                    .pattern
+                    .as_deref()
+                    .or(CONFIG.general.default_pattern.as_deref());
+                Self::pattern(pattern.unwrap_or_default()).shuffle(cli.shuffle)
+            }
+            PasswordMode::Phrase => Self::phrase(cli.words.unwrap_or(CONFIG.general.default_words))
+                .phrase_options(PhraseOptions {
+                    digits: cli.phrase_digits,
+                    symbols: cli.phrase_symbols,
+                    style: cli.phrase_insert_style,
+                }),
+            PasswordMode::Deterministic => {
+                let seed = Zeroizing::new(Generator::read_seed(
+                    cli.seed_env
+                        .as_deref()
+                        .ok_or(GeneratorError::MissingSeedEnv)?,
+                )?);
+                let mut request = Self::deterministic(&seed).kdf(Generator::kdf_from_cli(cli));
+                if let Some(salt) = &cli.salt {
+                    request = request.salt(salt);
+                }
+                if let Some(service) = &cli.service {
+                    request = request.service(service);
+                }
+                request
+            }
+        };
+        // Left to `validate` to reject outside of random mode
+        Ok(match cli.prefer_typeable {
+            Some(candidates) => request.prefer_typeable(candidates),
+            None => request,
+        })
+    }
+
+    /// Generate the requested password
+    ///
+    /// # Returns
+    ///
+    /// Returns the password with its mode and entropy, else returns an error if the
+    /// options do not fit together or the generation fails
+    pub fn build(&self) -> Result<GeneratedPassword, GeneratorError> {
+        self.validate()?;
+
+        let (password, entropy) = match self.mode {
+            PasswordMode::Random => {
+                let charset = self.without_excluded(CHARSET);
+                let length = self.length.unwrap_or(Self::DEFAULT_LENGTH);
+                let entropy = length as f64 * (charset.len() as f64).log2();
+                match self.typeable {
+                    Some(candidates) => (
+                        Generator::typeable_password(length, &charset, candidates)?,
+                        entropy - (candidates as f64).log2(),
+                    ),
+                    None => (Generator::random_password(length, &charset)?, entropy),
+                }
+            }
+            PasswordMode::Pattern => {
+                LoggingManager::info(&format!(
+                    "Generating pattern password with pattern: {}",
+                    Redactable(&self.pattern)
+                ));
+                if self.pattern.is_empty() {
+                    LoggingManager::error("Pattern cannot be empty");
+                    return Err(GeneratorError::EmptyPattern);
+                }
+                let pattern = Generator::resolve_pattern(&self.pattern, &CONFIG.patterns)?;
+                let charsets: Vec<Vec<u8>> = Generator::pattern_charsets(pattern)?
+                    .into_iter()
+                    .map(|charset| self.without_excluded(charset))
+                    .collect();
+                if charsets.iter().any(Vec::is_empty) {
+                    return Err(self.invalid("the excluded characters empty a pattern position"));
+                }
+                let entropy = charsets
+                    .iter()
+                    .map(|charset| (charset.len() as f64).log2())
+                    .sum();
+                (
+                    Generator::pattern_password(&charsets, self.shuffle)?,
+                    entropy,
+                )
+            }
+            PasswordMode::Phrase => {
+                let lang = CONFIG.language.lang.as_str();
+                let wordlist = Generator::get_wordlist(lang);
+                (
+                    Generator::phrase_password(self.words, &self.phrase.unwrap_or_default(), lang)?,
+                    self.words as f64 * (wordlist.len().max(1) as f64).log2(),
+                )
+            }
+            PasswordMode::Deterministic => (
+                Generator::deterministic_password(
+                    &self.seed,
+                    self.salt.as_deref(),
+                    self.service.as_deref(),
+                    self.counter.unwrap_or(1),
+                    &self.kdf.clone().unwrap_or_default(),
+                )?,
+                DETERMINISTIC_LENGTH as f64 * (CHARSET.len() as f64).log2(),
+            ),
+        };
+
+        Ok(GeneratedPassword {
+            password,
+            mode: self.mode.clone(),
+            entropy,
+        })
+    }
+
+    /// Check that every option set belongs to the mode of the request
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the options fit together, else returns an error naming the first
+    /// option that does not
+    pub fn validate(&self) -> Result<(), GeneratorError> {
+        let mode = &self.mode;
+        let random = *mode == PasswordMode::Random;
+        let deterministic = *mode == PasswordMode::Deterministic;
+        let misplaced = [
+            (
+                self.length.is_some() && !random,
+                "a length only applies to random passwords",
+            ),
+            (
+                self.exclude.is_some()
+                    && !matches!(mode, PasswordMode::Random | PasswordMode::Pattern),
+                "excluded characters only apply to random and pattern passwords",
+            ),
+            (
+                self.typeable.is_some() && !random,
+                "typeable selection only applies to random passwords",
+            ),
+            (
+                self.typeable.is_some_and(|candidates| {
+                    !Generator::TYPEABLE_CANDIDATES_RANGE.contains(&candidates)
+                }),
+                "the number of typeable candidates must be between 2 and 64",
+            ),
+            (
+                self.shuffle && *mode != PasswordMode::Pattern,
+                "shuffling only applies to pattern passwords",
+            ),
+            (
+                self.phrase.is_some() && *mode != PasswordMode::Phrase,
+                "digits and symbols only apply to phrase passwords",
+            ),
+            (
+                (self.salt.is_some()
+                    || self.service.is_some()
+                    || self.counter.is_some()
+                    || self.kdf.is_some())
+                    && !deterministic,
+                "a salt, service, counter, or KDF only applies to deterministic passwords",
+            ),
+            (self.counter == Some(0), "the counter starts at 1"),
+        ];
+        if let Some((_, reason)) = misplaced.iter().find(|(misplaced, _)| *misplaced) {
+            return Err(self.invalid(reason));
+        }
+
+        // A random password needs a character of every class, see `Generator::random_password`
+        if random && self.exclude.is_some() {
+            let classes = [
+                (UPPERCASE, "uppercase letters"),
+                (LOWERCASE, "lowercase letters"),
+                (DIGITS, "digits"),
+                (SPECIAL, "special characters"),
+            ];
+            if let Some((_, class)) = classes
+                .iter()
+                .find(|(charset, _)| self.without_excluded(charset).is_empty())
+            {
+                return Err(self.invalid(&format!("the excluded characters leave no {}", class)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the excluded characters from a charset
+    fn without_excluded(&self, charset: &[u8]) -> Vec<u8> {
+        let exclude = self.exclude.as_deref().unwrap_or_default();
+        charset
+            .iter()
+            .copied()
+            .filter(|&c| !exclude.contains(c as char))
+            .collect()
+    }
+
+    /// Log and build the error of a request that cannot be generated
+    fn invalid(&self, reason: &str) -> GeneratorError {
+        LoggingManager::error(&format!("Invalid password request: {}", reason));
+        GeneratorError::InvalidRequest(reason.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_request_excludes_characters() {
+        let request = PasswordRequest::random().length(40).exclude("0O1lI");
+        for _ in 0..20 {
+            let generated = request.build().unwrap();
+            assert_eq!(generated.password.len(), 40);
+            assert!(!generated.password.contains(['0', 'O', '1', 'l', 'I']));
+        }
+
+        let generated = PasswordRequest::random().build().unwrap();
+        assert_eq!(generated.password.len(), PasswordRequest::DEFAULT_LENGTH);
+        assert!(generated.mode == PasswordMode::Random);
+        assert!((generated.entropy - 16.0 * (CHARSET.len() as f64).log2()).abs() < 1e-9);
+
+        let excluded = PasswordRequest::random().exclude("0O1lI").build().unwrap();
+        assert!(excluded.entropy < generated.entropy);
+    }
+
+    #[test]
+    fn test_request_rejects_mismatched_options() {
+        let cases = [
+            PasswordRequest::phrase(4).length(20),
+            PasswordRequest::deterministic("seed").exclude("0"),
+            PasswordRequest::random().shuffle(true),
+            PasswordRequest::pattern("ULDS").phrase_options(PhraseOptions::default()),
+            PasswordRequest::random().service("github"),
+            PasswordRequest::deterministic("seed").counter(0),
+            PasswordRequest::random().exclude("0123456789"),
+            PasswordRequest::pattern("ULD").exclude("0123456789"),
+            PasswordRequest::phrase(4).prefer_typeable(8),
+            PasswordRequest::random().prefer_typeable(1),
+        ];
+        for request in cases {
+            assert!(matches!(
+                request.build(),
+                Err(GeneratorError::InvalidRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_deterministic_counter() {
+        let kdf = DeterministicConfig::default();
+        let first = PasswordRequest::deterministic("seed")
+            .service("github")
+            .build()
+            .unwrap();
+        let counted = PasswordRequest::deterministic("seed")
+            .service("github")
+            .counter(1)
+            .kdf(kdf)
+            .build()
+            .unwrap();
+        let rotated = PasswordRequest::deterministic("seed")
+            .service("github")
+            .counter(2)
+            .build()
+            .unwrap();
+
+        assert_eq!(first.password, counted.password);
+        assert_ne!(first.password, rotated.password);
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use ring::digest::{SHA256, digest};
+use serde::Serialize;
+
+use crate::errors::HealthCheckError;
+use crate::i18n::tr;
+use crate::logging::{LoggingManager, Redactable};
+use crate::password::health_check::HealthCheck;
+
+/// Largest edit distance at which two passwords count as near duplicates
+pub const MAX_REUSE_DISTANCE: usize = 2;
+
+/// Two different passwords of a file that differ in only a few characters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimilarPair {
+    /// The first line of each password
+    pub lines: [usize; 2],
+    pub distance: usize,
+}
+
+/// Identical and nearly identical passwords of a file, identified by line number only
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ReuseReport {
+    /// Lines holding the same password, one group per password used more than once
+    pub identical: Vec<Vec<usize>>,
+    /// Passwords at most `MAX_REUSE_DISTANCE` edits apart
+    pub similar: Vec<SimilarPair>,
+    /// Number of non-empty lines checked
+    pub checked: usize,
+}
+
+/// A distinct password of the file with the lines it is on
+struct Entry {
+    digest: [u8; 32],
+    chars: Vec<char>,
+    lines: Vec<usize>,
+}
+
+impl ReuseReport {
+    /// Find reused passwords in a file, one password per line
+    ///
+    /// Passwords are grouped by their SHA-256 digest. Digests sharing a bucket are compared
+    /// in constant time, so the lookup does not depend on where two passwords differ.
+    /// Distinct passwords are then compared pairwise, skipping pairs whose lengths alone
+    /// are too far apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `input`: The path to the file containing the passwords
+    ///
+    /// # Returns
+    ///
+    /// Returns the report, else returns an error if the file cannot be read
+    pub fn from_file(input: &Path) -> Result<Self, HealthCheckError> {
+        LoggingManager::info(&format!(
+            "Looking for reused passwords in: {}",
+            Redactable(input.display())
+        ));
+
+        let content = fs::read_to_string(input).map_err(|e| {
+            LoggingManager::error(&format!(
+                "Failed to read {}: {}",
+                Redactable(input.display()),
+                e
+            ));
+            HealthCheckError::ReadPasswordFile(format!("Failed to read {}: {}", input.display(), e))
+        })?;
+
+        let report = Self::from_lines(content.lines());
+        LoggingManager::info(&format!(
+            "Reuse check finished: {} lines, {} groups of identical passwords, {} similar pairs",
+            report.checked,
+            report.identical.len(),
+            report.similar.len()
+        ));
+        Ok(report)
+    }
+
+    /// Find reused passwords among lines
+    ///
+    /// # Arguments
+    ///
+    /// * `lines`: The passwords, line numbers start at 1 and empty lines are skipped
+    ///
+    /// # Returns
+    ///
+    /// Returns the report
+    pub fn from_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Self {
+        let mut entries: Vec<Entry> = Vec::new();
+        // Buckets by the first digest bytes, holding indexes into `entries`
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut checked = 0;
+
+        for (index, line) in lines.enumerate() {
+            let password = line.trim_end_matches('\r');
+            if password.is_empty() {
+                continue;
+            }
+            checked += 1;
+
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(digest(&SHA256, password.as_bytes()).as_ref());
+            let mut prefix = [0u8; 8];
+            prefix.copy_from_slice(&hash[..8]);
+
+            let bucket = buckets.entry(u64::from_le_bytes(prefix)).or_default();
+            match bucket
+                .iter()
+                .find(|&&entry| constant_time_eq(&entries[entry].digest, &hash))
+            {
+                Some(&entry) => entries[entry].lines.push(index + 1),
+                None => {
+                    bucket.push(entries.len());
+                    entries.push(Entry {
+                        digest: hash,
+                        chars: password.chars().collect(),
+                        lines: vec![index + 1],
+                    });
+                }
+            }
+        }
+
+        let identical = entries
+            .iter()
+            .filter(|entry| entry.lines.len() > 1)
+            .map(|entry| entry.lines.clone())
+            .collect();
+
+        // Sorted by length, so the inner loop stops once lengths differ too much
+        let mut by_length: Vec<&Entry> = entries.iter().collect();
+        by_length.sort_by_key(|entry| entry.chars.len());
+        let mut similar = Vec::new();
+        for (i, a) in by_length.iter().enumerate() {
+            for b in &by_length[i + 1..] {
+                if b.chars.len() - a.chars.len() > MAX_REUSE_DISTANCE {
+                    break;
+                }
+                let distance = HealthCheck::levenshtein_distance(&a.chars, &b.chars);
+                if distance <= MAX_REUSE_DISTANCE {
+                    let (first, second) = (a.lines[0].min(b.lines[0]), a.lines[0].max(b.lines[0]));
+                    similar.push(SimilarPair {
+                        lines: [first, second],
+                        distance,
+                    });
+                }
+            }
+        }
+        similar.sort_by_key(|pair| pair.lines);
+
+        Self {
+            identical,
+            similar,
+            checked,
+        }
+    }
+
+    /// Get the number of lines holding a password that is also on another line
+    pub fn reused_lines(&self) -> usize {
+        self.identical.iter().map(Vec::len).sum()
+    }
+
+    /// Format the report as a JSON object
+    ///
+    /// # Returns
+    ///
+    /// Returns the pretty printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reuse report is always serializable")
+    }
+
+    /// Render the report as tables of line numbers with a summary
+    ///
+    /// # Returns
+    ///
+    /// Returns the text, never containing a password
+    pub fn render_table(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(&format!("{}\n", tr("commands.check.reuse.identical", &[])));
+        if self.identical.is_empty() {
+            out.push_str(&format!("  {}\n", tr("commands.check.reuse.none", &[])));
+        } else {
+            out.push_str(&format!(
+                "  {:<6}{}\n",
+                tr("commands.check.reuse.group", &[]),
+                tr("commands.check.reuse.lines", &[])
+            ));
+            for (group, lines) in self.identical.iter().enumerate() {
+                out.push_str(&format!("  {:<6}{}\n", group + 1, join_lines(lines)));
+            }
+        }
+
+        out.push_str(&format!(
+            "\n{}\n",
+            tr(
+                "commands.check.reuse.similar",
+                &[("distance", &MAX_REUSE_DISTANCE.to_string())]
+            )
+        ));
+        if self.similar.is_empty() {
+            out.push_str(&format!("  {}\n", tr("commands.check.reuse.none", &[])));
+        } else {
+            out.push_str(&format!(
+                "  {:<14}{}\n",
+                tr("commands.check.reuse.lines", &[]),
+                tr("commands.check.reuse.distance", &[])
+            ));
+            for pair in &self.similar {
+                out.push_str(&format!(
+                    "  {:<14}{}\n",
+                    join_lines(&pair.lines),
+                    pair.distance
+                ));
+            }
+        }
+
+        out.push_str(&format!(
+            "\n{}\n",
+            tr(
+                "commands.check.reuse.summary",
+                &[
+                    ("checked", &self.checked.to_string()),
+                    ("groups", &self.identical.len().to_string()),
+                    ("reused", &self.reused_lines().to_string()),
+                    ("similar", &self.similar.len().to_string()),
+                ]
+            )
+        ));
+        out
+    }
+}
+
+/// Compare two digests without exiting early on the first difference
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Join line numbers with commas
+fn join_lines(lines: &[usize]) -> String {
+    lines
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "Summer2024!\n\
+        correct-horse\n\
+        Summer2024!\n\
+        \n\
+        Summer2025!\n\
+        Tr0ub4dor&3\n\
+        correct-horse\n\
+        Summer2024!\n\
+        unrelated-password\n\
+        Tr0ub4dor&3xy\n";
+
+    #[test]
+    fn test_reuse_groups_and_similar_pairs() {
+        let report = ReuseReport::from_lines(FIXTURE.lines());
+
+        assert_eq!(report.checked, 9);
+        assert_eq!(report.identical, [vec![1, 3, 8], vec![2, 7]]);
+        assert_eq!(report.reused_lines(), 5);
+        assert_eq!(
+            report.similar,
+            [
+                SimilarPair {
+                    lines: [1, 5],
+                    distance: 1,
+                },
+                SimilarPair {
+                    lines: [6, 10],
+                    distance: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reuse_report_never_contains_passwords() {
+        let report = ReuseReport::from_lines(FIXTURE.lines());
+        let table = report.render_table();
+        let json = report.to_json().split_whitespace().collect::<String>();
+
+        for password in FIXTURE.lines().filter(|line| !line.is_empty()) {
+            assert!(!table.contains(password), "{}", table);
+            assert!(!json.contains(password), "{}", json);
+        }
+        assert!(table.contains("1, 3, 8"), "{}", table);
+        assert!(json.contains("\"identical\":[[1,3,8],[2,7]]"), "{}", json);
+        assert!(
+            json.contains("{\"lines\":[6,10],\"distance\":2}"),
+            "{}",
+            json
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        let a = [7u8; 32];
+        let mut b = a;
+        assert!(constant_time_eq(&a, &b));
+        b[31] = 8;
+        assert!(!constant_time_eq(&a, &b));
+    }
+}
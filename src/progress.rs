@@ -0,0 +1,275 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Delay before the stderr progress line is shown, so short runs stay quiet
+const STDERR_PROGRESS_DELAY: Duration = Duration::from_millis(500);
+/// Minimum time between two stderr progress updates
+const STDERR_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The state of a running bulk operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub completed: usize,
+    pub total: usize,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Get the completed share of the operation
+    ///
+    /// # Returns
+    ///
+    /// Returns the percentage between 0 and 100
+    pub fn percentage(&self) -> u16 {
+        if self.total == 0 {
+            return 100;
+        }
+        (self.completed.min(self.total) * 100 / self.total) as u16
+    }
+
+    /// Get the number of completed items per second
+    ///
+    /// # Returns
+    ///
+    /// Returns the throughput, 0.0 if no time has elapsed yet
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            0.0
+        } else {
+            self.completed as f64 / seconds
+        }
+    }
+}
+
+/// Receives progress updates of bulk operations
+///
+/// Implemented for every `FnMut(Progress)`, so library users can pass a closure
+/// to hook their own progress UI.
+pub trait ProgressReporter {
+    /// Report the current progress
+    ///
+    /// # Arguments
+    ///
+    /// * `progress`: The current progress
+    fn report(&mut self, progress: Progress);
+}
+
+impl<F: FnMut(Progress)> ProgressReporter for F {
+    fn report(&mut self, progress: Progress) {
+        self(progress)
+    }
+}
+
+/// Progress reporter that ignores all updates
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&mut self, _progress: Progress) {}
+}
+
+/// Progress reporter rendering a percentage and throughput line on stderr
+pub struct StderrProgress {
+    last_update: Option<Instant>,
+}
+
+impl Default for StderrProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StderrProgress {
+    pub fn new() -> Self {
+        Self { last_update: None }
+    }
+
+    /// Terminate the progress line if one was shown
+    pub fn finish(&mut self) {
+        if self.last_update.take().is_some() {
+            eprintln!();
+        }
+    }
+}
+
+impl ProgressReporter for StderrProgress {
+    fn report(&mut self, progress: Progress) {
+        let done = progress.completed >= progress.total;
+        if progress.elapsed < STDERR_PROGRESS_DELAY {
+            return;
+        }
+        if let Some(last_update) = self.last_update
+            && last_update.elapsed() < STDERR_PROGRESS_INTERVAL
+            && !done
+        {
+            return;
+        }
+
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r{:>3}% ({}/{}) {:.0}/s",
+            progress.percentage(),
+            progress.completed,
+            progress.total,
+            progress.throughput()
+        );
+        let _ = stderr.flush();
+        self.last_update = Some(Instant::now());
+    }
+}
+
+/// Shared flag to stop a running bulk operation, e.g. from a Ctrl-C handler
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the operation to stop after the current item
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether the operation was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// The result of a bulk operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BulkOutcome {
+    pub completed: usize,
+    pub total: usize,
+    pub cancelled: bool,
+}
+
+/// Run a bulk operation item by item with progress reporting and cancellation
+///
+/// Progress is reported whenever the completed percentage changes, so reporters
+/// receive at most about 100 updates regardless of the number of items.
+///
+/// # Arguments
+///
+/// * `total`: The number of items to process
+/// * `reporter`: The reporter receiving progress updates
+/// * `cancellation`: The cancellation checked before every item
+/// * `step`: The operation for a single item, called with the item index
+///
+/// # Returns
+///
+/// Returns the outcome of the operation, else returns the first error of `step`
+pub fn run_bulk<E>(
+    total: usize,
+    reporter: &mut dyn ProgressReporter,
+    cancellation: &Cancellation,
+    mut step: impl FnMut(usize) -> Result<(), E>,
+) -> Result<BulkOutcome, E> {
+    let start = Instant::now();
+    let mut last_percentage = None;
+
+    for index in 0..total {
+        if cancellation.is_cancelled() {
+            return Ok(BulkOutcome {
+                completed: index,
+                total,
+                cancelled: true,
+            });
+        }
+
+        step(index)?;
+
+        let progress = Progress {
+            completed: index + 1,
+            total,
+            elapsed: start.elapsed(),
+        };
+        if last_percentage != Some(progress.percentage()) || progress.completed == total {
+            last_percentage = Some(progress.percentage());
+            reporter.report(progress);
+        }
+    }
+
+    Ok(BulkOutcome {
+        completed: total,
+        total,
+        cancelled: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_percentage_and_throughput() {
+        let progress = Progress {
+            completed: 25,
+            total: 200,
+            elapsed: Duration::from_secs(5),
+        };
+        assert_eq!(progress.percentage(), 12);
+        assert_eq!(progress.throughput(), 5.0);
+
+        let empty = Progress {
+            completed: 0,
+            total: 0,
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(empty.percentage(), 100);
+        assert_eq!(empty.throughput(), 0.0);
+    }
+
+    #[test]
+    fn test_run_bulk_reports_progress() {
+        let mut reports = Vec::new();
+        let mut processed = 0;
+        let outcome = run_bulk::<()>(
+            1000,
+            &mut |progress: Progress| reports.push(progress.completed),
+            &Cancellation::new(),
+            |_| {
+                processed += 1;
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(processed, 1000);
+        assert!(!outcome.cancelled);
+        assert_eq!(outcome.completed, 1000);
+        assert_eq!(reports.len(), 101);
+        assert_eq!(reports.last(), Some(&1000));
+    }
+
+    #[test]
+    fn test_run_bulk_cancellation() {
+        let cancellation = Cancellation::new();
+        let handle = cancellation.clone();
+        let outcome = run_bulk::<()>(100, &mut NoProgress, &cancellation, |index| {
+            if index == 9 {
+                handle.cancel();
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(outcome.cancelled);
+        assert_eq!(outcome.completed, 10);
+    }
+
+    #[test]
+    fn test_run_bulk_error() {
+        let result = run_bulk(10, &mut NoProgress, &Cancellation::new(), |index| {
+            if index == 3 { Err("failed") } else { Ok(()) }
+        });
+        assert_eq!(result, Err("failed"));
+    }
+}
@@ -0,0 +1,307 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::signature::{ED25519, UnparsedPublicKey};
+
+use crate::errors::SignatureError;
+use crate::logging::LoggingManager;
+
+/// Public key of the release signing key in minisign format, set when building a release
+pub const RELEASE_PUBLIC_KEY: &str = env!("KDGUARD_RELEASE_PUBLIC_KEY");
+
+/// Algorithm id of minisign signatures made over the data itself
+const ALGORITHM_ED25519: &[u8; 2] = b"Ed";
+
+/// Algorithm id of minisign signatures made over a BLAKE2b hash of the data
+const ALGORITHM_PREHASHED: &[u8; 2] = b"ED";
+
+/// A minisign ed25519 public key
+#[derive(Debug, PartialEq)]
+pub struct PublicKey {
+    key_id: [u8; 8],
+    key: [u8; 32],
+}
+
+impl PublicKey {
+    /// Parse a public key as written by `minisign -G`
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded`: The contents of the `.pub` file, or only its base64 line
+    ///
+    /// # Returns
+    ///
+    /// Returns the public key, otherwise an error if it is malformed
+    pub fn parse(encoded: &str) -> Result<Self, SignatureError> {
+        let line = payload_lines(encoded)
+            .next()
+            .ok_or_else(|| SignatureError::InvalidPublicKey("empty key".to_string()))?;
+        let bytes = decode(line).map_err(SignatureError::InvalidPublicKey)?;
+        if bytes.len() != 42 {
+            return Err(SignatureError::InvalidPublicKey(format!(
+                "expected 42 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        if &bytes[..2] != ALGORITHM_ED25519 {
+            return Err(SignatureError::InvalidPublicKey(
+                "not an ed25519 key".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            key_id: bytes[2..10].try_into().unwrap(),
+            key: bytes[10..].try_into().unwrap(),
+        })
+    }
+
+    /// Get the key embedded in this build
+    ///
+    /// # Returns
+    ///
+    /// Returns the release key, otherwise an error if the build has none
+    pub fn release() -> Result<Self, SignatureError> {
+        if RELEASE_PUBLIC_KEY.trim().is_empty() {
+            return Err(SignatureError::NoPublicKey);
+        }
+        Self::parse(RELEASE_PUBLIC_KEY)
+    }
+}
+
+/// A detached minisign signature
+#[derive(Debug, PartialEq)]
+pub struct Signature {
+    key_id: [u8; 8],
+    signature: [u8; 64],
+    trusted_comment: String,
+    global_signature: [u8; 64],
+}
+
+impl Signature {
+    /// Parse a signature as written by `minisign -S -l`
+    ///
+    /// Only signatures over the data itself are supported, not the prehashed ones
+    /// minisign writes without `-l`.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded`: The contents of the `.sig` file
+    ///
+    /// # Returns
+    ///
+    /// Returns the signature, otherwise an error if it is malformed or prehashed
+    pub fn parse(encoded: &str) -> Result<Self, SignatureError> {
+        let invalid = |message: &str| SignatureError::InvalidSignature(message.to_string());
+        let mut lines = payload_lines(encoded);
+
+        let bytes = decode(lines.next().ok_or_else(|| invalid("empty signature"))?)
+            .map_err(SignatureError::InvalidSignature)?;
+        if bytes.len() != 74 {
+            return Err(SignatureError::InvalidSignature(format!(
+                "expected 74 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        match &bytes[..2] {
+            algorithm if algorithm == ALGORITHM_ED25519 => {}
+            algorithm if algorithm == ALGORITHM_PREHASHED => {
+                return Err(SignatureError::UnsupportedAlgorithm(
+                    "prehashed signature, sign with minisign -l".to_string(),
+                ));
+            }
+            algorithm => {
+                return Err(SignatureError::UnsupportedAlgorithm(
+                    String::from_utf8_lossy(algorithm).to_string(),
+                ));
+            }
+        }
+
+        let trusted_comment = encoded
+            .lines()
+            .find_map(|line| line.strip_prefix("trusted comment: "))
+            .ok_or_else(|| invalid("missing trusted comment"))?
+            .to_string();
+        let global_signature = decode(
+            lines
+                .next()
+                .ok_or_else(|| invalid("missing global signature"))?,
+        )
+        .map_err(SignatureError::InvalidSignature)?;
+
+        Ok(Self {
+            key_id: bytes[2..10].try_into().unwrap(),
+            signature: bytes[10..].try_into().unwrap(),
+            trusted_comment,
+            global_signature: global_signature
+                .try_into()
+                .map_err(|_| invalid("global signature must be 64 bytes"))?,
+        })
+    }
+
+    /// Verify the signature over data
+    ///
+    /// The trusted comment is verified as well, so it cannot be swapped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The public key the data must be signed with
+    /// * `data`: The signed data
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the signature is valid, otherwise an error
+    pub fn verify(&self, key: &PublicKey, data: &[u8]) -> Result<(), SignatureError> {
+        if self.key_id != key.key_id {
+            LoggingManager::error("Signature was made with another key");
+            return Err(SignatureError::KeyMismatch);
+        }
+
+        let public_key = UnparsedPublicKey::new(&ED25519, key.key);
+        public_key.verify(data, &self.signature).map_err(|_| {
+            LoggingManager::error("Signature does not match the data");
+            SignatureError::BadSignature
+        })?;
+
+        let mut global = self.signature.to_vec();
+        global.extend_from_slice(self.trusted_comment.as_bytes());
+        public_key
+            .verify(&global, &self.global_signature)
+            .map_err(|_| {
+                LoggingManager::error("Signature of the trusted comment is invalid");
+                SignatureError::BadSignature
+            })?;
+
+        LoggingManager::info(&format!(
+            "Signature verified, trusted comment: {}",
+            self.trusted_comment
+        ));
+        Ok(())
+    }
+}
+
+/// Get the lines of a minisign file without comments and blank lines
+fn payload_lines(encoded: &str) -> impl Iterator<Item = &str> {
+    encoded
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains("comment:"))
+}
+
+/// Decode a base64 line
+fn decode(line: &str) -> Result<Vec<u8>, String> {
+    STANDARD.decode(line).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    use super::*;
+
+    pub(crate) const KEY_ID: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    /// Key pair from a fixed seed, so the fixtures stay the same between runs
+    pub(crate) fn key_pair(seed: u8) -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap()
+    }
+
+    pub(crate) fn public_key_file(pair: &Ed25519KeyPair, key_id: [u8; 8]) -> String {
+        let mut bytes = ALGORITHM_ED25519.to_vec();
+        bytes.extend_from_slice(&key_id);
+        bytes.extend_from_slice(pair.public_key().as_ref());
+        format!(
+            "untrusted comment: minisign public key\n{}\n",
+            STANDARD.encode(bytes)
+        )
+    }
+
+    pub(crate) fn signature_file(pair: &Ed25519KeyPair, data: &[u8], comment: &str) -> String {
+        let signature = pair.sign(data);
+        let mut bytes = ALGORITHM_ED25519.to_vec();
+        bytes.extend_from_slice(&KEY_ID);
+        bytes.extend_from_slice(signature.as_ref());
+        let mut global = signature.as_ref().to_vec();
+        global.extend_from_slice(comment.as_bytes());
+        format!(
+            "untrusted comment: signature\n{}\ntrusted comment: {}\n{}\n",
+            STANDARD.encode(bytes),
+            comment,
+            STANDARD.encode(pair.sign(&global))
+        )
+    }
+
+    #[test]
+    fn test_verify_signed_fixture() {
+        let pair = key_pair(7);
+        let key = PublicKey::parse(&public_key_file(&pair, KEY_ID)).unwrap();
+        let data = b"kdguard release binary";
+        let signature =
+            Signature::parse(&signature_file(&pair, data, "timestamp:1800000000")).unwrap();
+
+        assert!(signature.verify(&key, data).is_ok());
+        assert!(matches!(
+            signature.verify(&key, b"kdguard release binarY"),
+            Err(SignatureError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_other_keys_and_comments() {
+        let pair = key_pair(7);
+        let data = b"kdguard release binary";
+        let file = signature_file(&pair, data, "timestamp:1800000000");
+        let signature = Signature::parse(&file).unwrap();
+
+        let other = PublicKey::parse(&public_key_file(&key_pair(8), [9; 8])).unwrap();
+        assert!(matches!(
+            signature.verify(&other, data),
+            Err(SignatureError::KeyMismatch)
+        ));
+        // Same key id, different key
+        let forged = PublicKey::parse(&public_key_file(&key_pair(8), KEY_ID)).unwrap();
+        assert!(matches!(
+            signature.verify(&forged, data),
+            Err(SignatureError::BadSignature)
+        ));
+
+        let key = PublicKey::parse(&public_key_file(&pair, KEY_ID)).unwrap();
+        let swapped = Signature::parse(&file.replace("1800000000", "1900000000")).unwrap();
+        assert!(matches!(
+            swapped.verify(&key, data),
+            Err(SignatureError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_files() {
+        assert!(matches!(
+            PublicKey::parse(""),
+            Err(SignatureError::InvalidPublicKey(_))
+        ));
+        assert!(matches!(
+            PublicKey::parse("not base64!"),
+            Err(SignatureError::InvalidPublicKey(_))
+        ));
+
+        let pair = key_pair(7);
+        let file = signature_file(&pair, b"data", "comment");
+        let prehashed = {
+            let line = file.lines().nth(1).unwrap();
+            let mut bytes = STANDARD.decode(line).unwrap();
+            bytes[..2].copy_from_slice(ALGORITHM_PREHASHED);
+            file.replace(line, &STANDARD.encode(bytes))
+        };
+        assert!(matches!(
+            Signature::parse(&prehashed),
+            Err(SignatureError::UnsupportedAlgorithm(_))
+        ));
+
+        let without_comment: String = file
+            .lines()
+            .filter(|line| !line.starts_with("trusted comment"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert!(matches!(
+            Signature::parse(&without_comment),
+            Err(SignatureError::InvalidSignature(_))
+        ));
+    }
+}
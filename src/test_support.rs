@@ -0,0 +1,48 @@
+//! Helpers shared by the unit tests
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of temporary directories created by this test process
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary directory for one test, removed when dropped
+pub struct TempDir(pub PathBuf);
+
+impl TempDir {
+    /// Create an empty temporary directory
+    ///
+    /// The path contains the process id and a counter, so tests of different modules
+    /// may use the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: Names the directory after the test
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "kdguard-test-{}-{}-{}",
+            name,
+            std::process::id(),
+            CREATED.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,17 +1,42 @@
+use std::env::consts::{ARCH, OS};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
 use crate::{
     PasswordMode,
-    config::Config,
-    password::{Generator, HealthCheck, PasswordAnalysis},
+    config::{Config, ConfigUpdate, GeneralConfig, Preset, THEME_PRESETS},
+    errors::GeneratorError,
+    logging::LoggingManager,
+    parse_password_mode,
+    password::{
+        BreachCheck, DETERMINISTIC_LENGTH, GenerationMetadata, Generator, HealthCheck, InsertStyle,
+        PasswordAnalysis, PasswordRequest, PhraseOptions,
+    },
+    progress::{BulkOutcome, Cancellation, Progress},
 };
-use ratatui::crossterm::event::KeyCode;
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use lingua_i18n_rs::prelude::Lingua;
+use ratatui::crossterm::event::{KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
+
+use super::keymap::KeyMap;
+use super::screens::{app_layout, generator_panes, screen_layout, too_small, tr};
+use super::text_input::TextInput;
+use super::theme::Theme;
+use zeroize::Zeroizing;
 
+#[derive(Clone, Copy)]
 pub enum CurrentScreen {
     Main,
     GeneratorModeSelection,
     Generator,
     Settings,
     Help,
+    About,
     Check,
+    History,
     Exit,
 }
 
@@ -20,41 +45,275 @@ pub struct App {
     pub generator: GeneratorState,
     pub settings: Settings,
     pub help: Help,
+    pub history: History,
     pub exit: Exit,
-    pub generated_passwords: Vec<String>,
-    pub password_input: String,
+    pub theme: Theme,
+    pub generated_passwords: Vec<GeneratedPassword>,
+    /// The batch replaced by the last generation, brought back with `u`
+    pub previous_passwords: Vec<GeneratedPassword>,
+    /// Whether the shown results were written to a file since they were generated
+    pub results_saved: bool,
+    pub password_input: TextInput,
     pub selected_index: usize,
     pub show_detailed_check: bool,
     pub check_result: Option<PasswordAnalysis>,
+    /// Time of the last keystroke in the check field that has not been analyzed yet
+    pub check_pending_since: Option<Instant>,
+    /// The breach lookup of the check input running on a background thread
+    pub breach_check: Option<BreachJob>,
+    /// Number of breaches the check input was found in, or the error of the lookup
+    pub breach_result: Option<Result<u64, String>>,
     pub input_mode: InputMode,
     pub input_field: InputField,
     pub error_message: Option<String>,
+    pub generation: Option<GenerationJob>,
+    pub status_message: Option<StatusMessage>,
+    pub save_filename: TextInput,
+    pub pending_overwrite: bool,
+    /// The highlighted entry while the preset list is open
+    pub preset_picker: Option<usize>,
+    pub preset_name: TextInput,
+    /// Receives the result of the background update check
+    pub updates: Option<Receiver<UpdateStatus>>,
+    pub update_status: UpdateStatus,
+    /// Version and path details, collected when the about screen opens
+    pub about: Vec<AboutEntry>,
+    pub show_update_dialog: bool,
+    /// The one-shot generation shown over the screen, see `open_quick_generate`
+    pub quick_generate: Option<QuickGenerate>,
+    pub keymap: KeyMap,
+    /// Whether the key cheat sheet is shown over the screen
+    pub show_keymap: bool,
+    /// Problem with the config shown in the status bar, e.g. invalid key bindings
+    pub config_warning: Option<String>,
+    /// Whether the uninstaller should run once the TUI has restored the terminal
+    pub uninstall_requested: bool,
+    clipboard: Option<arboard::Clipboard>,
+}
+
+/// Allowed number of passwords per generation
+const COUNT_RANGE: std::ops::RangeInclusive<usize> = 1..=100;
+
+/// Number of entries in the main menu
+const MAIN_MENU_ITEMS: usize = 7;
+/// Number of entries in the generator mode selection
+const GENERATOR_MODES: usize = 4;
+
+/// Delay after the last keystroke before the check screen analyzes the input
+const CHECK_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How long a transient status message stays visible
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+/// The pane of the generator screen receiving navigation keys
+#[derive(Clone, Copy, PartialEq)]
+pub enum GeneratorFocus {
+    Parameters,
+    Results,
+}
+
+/// The result of the background update check
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateStatus {
+    /// The check has not finished yet
+    Checking,
+    UpToDate,
+    /// A newer release with this tag exists
+    Available(String),
+    Failed(String),
+    /// Turned off in the config or with `--no-update-check`
+    Disabled,
+}
+
+/// A line of the about screen
+pub struct AboutEntry {
+    /// The translation key below `tui.about`
+    pub key: &'static str,
+    /// The English label, used when copying the details for bug reports
+    pub label: &'static str,
+    pub value: String,
+}
+
+/// A transient message shown in the footer
+pub struct StatusMessage {
+    pub text: String,
+    pub created: Instant,
+}
+
+/// A password generation running on a background thread
+pub struct GenerationJob {
+    receiver: Receiver<GenerationMessage>,
+    cancellation: Cancellation,
+    mode: PasswordMode,
+    parameters: String,
+    pub progress: Progress,
+}
+
+/// The generator state and results set aside while the quick generate dialog is open
+pub struct QuickGenerate {
+    generator: GeneratorState,
+    passwords: Vec<GeneratedPassword>,
+    error: Option<String>,
+}
+
+/// A breach lookup running on a background thread
+pub struct BreachJob {
+    receiver: Receiver<Result<u64, String>>,
+    pub started: Instant,
+}
+
+enum GenerationMessage {
+    Progress(Progress),
+    Finished(Result<(Vec<GeneratedPassword>, BulkOutcome), String>),
+}
+
+/// A generated password with its strength, shown in the results pane
+///
+/// The strength is analyzed once when the password is generated, so moving through the
+/// results never runs the dictionary checks again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedPassword {
+    pub password: String,
+    pub score: u32,
+    pub rating: String,
+    pub entropy: f64,
+}
+
+impl GeneratedPassword {
+    /// Rate a generated password
+    ///
+    /// # Arguments
+    ///
+    /// * `password`: The generated password
+    ///
+    /// # Returns
+    ///
+    /// Returns the password with its score, rating and entropy
+    pub fn rate(password: String) -> Self {
+        let analysis = HealthCheck::analyze_password(&password);
+        Self {
+            password,
+            score: analysis.score.normalized(),
+            rating: analysis.rating,
+            entropy: analysis.entropy,
+        }
+    }
 }
 
+type PasswordGenerator = Box<dyn FnMut() -> Result<String, GeneratorError> + Send>;
+
 pub struct GeneratorState {
     pub length: usize,
     pub count: usize,
     pub mode: PasswordMode,
-    pub pattern: String,
+    pub pattern: TextInput,
     pub words: Option<usize>,
-    pub seed_env: String,
-    pub service: String,
-    pub salt: String,
+    /// Digits and symbols inserted into phrases
+    pub phrase: PhraseOptions,
+    pub seed_env: TextInput,
+    /// Seed typed into the TUI, used instead of `seed_env` when set
+    pub seed: TextInput,
+    pub service: TextInput,
+    pub salt: TextInput,
     pub selected_mode_index: usize,
     pub editing_field: Option<GeneratorField>,
+    /// Pending digits while typing a numeric field
+    pub numeric_input: TextInput,
+    /// The pane receiving navigation keys
+    pub focus: GeneratorFocus,
+    /// The highlighted entry of the results pane
+    pub selected_result: usize,
+    /// The first visible entry of the results pane, kept between frames
+    pub results_offset: usize,
+    /// The number of visible entries of the results pane
+    pub results_page: usize,
+    /// Whether the seed env var and salt are shown in plain text
+    pub show_seed: bool,
+    /// Whether the results are masked, set when they were saved automatically
+    pub hide_results: bool,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+impl GeneratorState {
+    /// Create the generator state from the general defaults of the config
+    ///
+    /// # Arguments
+    ///
+    /// * `general`: The general config holding the default mode, length, count, pattern, and words
+    ///
+    /// # Returns
+    ///
+    /// Returns the generator state with the parameters pane focused
+    pub fn from_config(general: &GeneralConfig) -> Self {
+        let mut pattern = TextInput::new();
+        if let Some(default_pattern) = &general.default_pattern {
+            pattern.set(default_pattern);
+        }
+
+        Self {
+            length: general.default_length,
+            count: general.default_count,
+            mode: parse_password_mode(&general.default_mode).unwrap_or(PasswordMode::Random),
+            pattern,
+            words: Some(general.default_words),
+            phrase: PhraseOptions::default(),
+            seed_env: TextInput::new().with_mask(),
+            seed: TextInput::new().with_mask(),
+            service: TextInput::new(),
+            salt: TextInput::new().with_mask(),
+            selected_mode_index: 0,
+            editing_field: None,
+            numeric_input: TextInput::new().with_max_length(3),
+            focus: GeneratorFocus::Parameters,
+            selected_result: 0,
+            results_offset: 0,
+            results_page: 1,
+            show_seed: false,
+            hide_results: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GeneratorField {
     Length,
     Count,
     Pattern,
     Words,
+    PhraseDigits,
+    PhraseSymbols,
+    InsertStyle,
     SeedEnv,
+    Seed,
     Service,
     Salt,
 }
 
+impl GeneratorField {
+    /// Get the parameter rows of a generator mode in display order
+    ///
+    /// Shared by input handling and rendering so the cursor always points at a rendered row.
+    pub fn rows(mode: &PasswordMode) -> &'static [GeneratorField] {
+        match mode {
+            PasswordMode::Random => &[GeneratorField::Length, GeneratorField::Count],
+            PasswordMode::Pattern => &[GeneratorField::Pattern, GeneratorField::Count],
+            PasswordMode::Phrase => &[
+                GeneratorField::Words,
+                GeneratorField::PhraseDigits,
+                GeneratorField::PhraseSymbols,
+                GeneratorField::InsertStyle,
+                GeneratorField::Count,
+            ],
+            PasswordMode::Deterministic => &[
+                GeneratorField::SeedEnv,
+                GeneratorField::Seed,
+                GeneratorField::Service,
+                GeneratorField::Salt,
+                GeneratorField::Count,
+            ],
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum InputMode {
     Normal,
@@ -66,33 +325,96 @@ pub enum InputField {
     None,
     PasswordCheck,
     Generator(GeneratorField),
-    Settings(SettingsField),
+    SaveFilename,
+    PresetName,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SettingsField {
     Language,
+    DefaultMode,
     DefaultLength,
     DefaultCount,
     AutoSave,
+    Theme,
+    ResetConfig,
+    Uninstall,
+}
+
+impl SettingsField {
+    /// The rows of the settings screen in display order
+    pub const ROWS: [SettingsField; 8] = [
+        SettingsField::Language,
+        SettingsField::DefaultMode,
+        SettingsField::DefaultLength,
+        SettingsField::DefaultCount,
+        SettingsField::AutoSave,
+        SettingsField::Theme,
+        SettingsField::ResetConfig,
+        SettingsField::Uninstall,
+    ];
+}
+
+/// A maintenance action of the settings screen that needs a confirmation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaintenanceAction {
+    ResetConfig,
+    Uninstall,
 }
 
 pub struct Settings {
     pub language: String,
+    /// Languages with a language file, cycled by the language picker
+    pub languages: Vec<String>,
+    pub default_mode: PasswordMode,
     pub auto_save: bool,
     pub default_length: usize,
     pub default_count: usize,
+    /// The theme preset, cycled through `THEME_PRESETS`
+    pub theme: String,
     pub selected_index: usize,
+    /// The config as last loaded or saved, used to detect unsaved changes
+    pub saved: Config,
+    /// The profile selected with `--profile` or `KDGUARD_PROFILE`, its values replace
+    /// the general defaults in the generator
+    pub profile: Option<String>,
+    /// Whether leaving with unsaved changes waits for save, discard, or cancel
+    pub pending_exit: bool,
+    /// The maintenance action waiting for a y/n confirmation
+    pub pending_action: Option<MaintenanceAction>,
+    /// The error of a failed maintenance action, shown in a dialog
+    pub maintenance_error: Option<String>,
 }
 
 pub struct Help {
     pub scroll: usize,
+    /// Largest scroll offset that still fills the viewport, updated on every render
+    pub max_scroll: usize,
+    /// Number of visible help lines, used for paging
+    pub page_height: usize,
 }
 
 pub struct Exit {
     pub exit: bool,
 }
 
+/// Passwords generated during the session, kept in memory only
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+    pub selected: usize,
+    pub pending_clear: bool,
+}
+
+/// A generated password with the settings it was generated with
+///
+/// The password is zeroized when the entry is dropped.
+pub struct HistoryEntry {
+    pub password: Zeroizing<String>,
+    pub mode: PasswordMode,
+    pub parameters: String,
+    pub created: DateTime<Local>,
+}
+
 impl Default for App {
     fn default() -> Self {
         Self::new()
@@ -101,54 +423,152 @@ impl Default for App {
 
 impl App {
     pub fn new() -> Self {
-        let config = Config::load_config().unwrap();
-        let default_mode = match config.general.default_mode.to_lowercase().as_str() {
-            "random" => PasswordMode::Random,
-            "pattern" => PasswordMode::Pattern,
-            "phrase" => PasswordMode::Phrase,
-            "deterministic" => PasswordMode::Deterministic,
-            _ => PasswordMode::Random,
+        // A config that cannot be loaded was already reported, the TUI runs on the defaults
+        let config = Config::load_config().unwrap_or_default();
+        let profile = Config::selected_profile();
+        let general = config
+            .general_for(profile.as_deref())
+            .unwrap_or_else(|_| config.general.clone());
+        let default_mode =
+            parse_password_mode(&config.general.default_mode).unwrap_or(PasswordMode::Random);
+
+        let theme = Theme::from_config(&config.theme);
+        let (keymap, config_warning) = match KeyMap::from_config(&config.keybindings) {
+            Ok(keymap) => (keymap, None),
+            Err(e) => {
+                let warning = tr("tui.keymap.invalid", &[("error", &e)]);
+                LoggingManager::warn(&warning);
+                (KeyMap::default(), Some(warning))
+            }
         };
+        let mut languages = Config::available_languages().unwrap_or_default();
+        if !languages.contains(&config.language.lang) {
+            languages.push(config.language.lang.clone());
+        }
+
+        let error_message = config.check.banned_words_path.as_ref().and_then(|path| {
+            HealthCheck::load_banned_words(std::path::Path::new(path))
+                .err()
+                .map(|e| e.to_string())
+        });
 
         Self {
             current_screen: CurrentScreen::Main,
-            generator: GeneratorState {
-                length: config.general.default_length,
-                count: config.general.default_count,
-                mode: default_mode,
-                pattern: String::new(),
-                words: None,
-                seed_env: String::new(),
-                service: String::new(),
-                salt: String::new(),
-                selected_mode_index: 0,
-                editing_field: None,
-            },
+            generator: GeneratorState::from_config(&general),
             settings: Settings {
-                language: config.language.lang,
+                languages,
+                default_mode,
+                language: config.language.lang.clone(),
                 auto_save: config.general.auto_save,
                 default_length: config.general.default_length,
                 default_count: config.general.default_count,
+                theme: config.theme.preset().to_string(),
                 selected_index: 0,
+                saved: config,
+                profile,
+                pending_exit: false,
+                pending_action: None,
+                maintenance_error: None,
+            },
+            help: Help {
+                scroll: 0,
+                max_scroll: 0,
+                page_height: 1,
+            },
+            history: History {
+                entries: Vec::new(),
+                selected: 0,
+                pending_clear: false,
             },
-            help: Help { scroll: 0 },
             exit: Exit { exit: false },
+            theme,
             generated_passwords: Vec::new(),
-            password_input: String::new(),
+            previous_passwords: Vec::new(),
+            results_saved: false,
+            password_input: TextInput::new().with_mask(),
             selected_index: 0,
             show_detailed_check: false,
             check_result: None,
+            check_pending_since: None,
+            breach_check: None,
+            breach_result: None,
             input_mode: InputMode::Normal,
             input_field: InputField::None,
-            error_message: None,
+            error_message,
+            generation: None,
+            status_message: None,
+            save_filename: TextInput::new(),
+            pending_overwrite: false,
+            preset_picker: None,
+            preset_name: TextInput::new(),
+            updates: None,
+            update_status: UpdateStatus::Checking,
+            about: Vec::new(),
+            show_update_dialog: false,
+            quick_generate: None,
+            keymap,
+            show_keymap: false,
+            config_warning,
+            uninstall_requested: false,
+            clipboard: None,
         }
     }
 
     pub fn handle_input(&mut self, key: KeyCode) -> bool {
+        if self.show_update_dialog {
+            if matches!(
+                key,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('u') | KeyCode::Char('U')
+            ) {
+                self.show_update_dialog = false;
+            }
+            return false;
+        }
+
         if self.input_mode == InputMode::Editing {
             return self.handle_editing_input(key);
         }
 
+        // Confirmation prompts answer with fixed letters regardless of the key bindings
+        let key = if self.confirmation_pending() {
+            key
+        } else {
+            match self.keymap.resolve(key) {
+                Some(key) => key,
+                None => return false,
+            }
+        };
+
+        if self.show_keymap {
+            self.show_keymap = false;
+            return false;
+        }
+
+        if self.quick_generate.is_some() {
+            return self.handle_quick_generate_input(key);
+        }
+
+        if key == KeyCode::Char('?') {
+            self.show_keymap = true;
+            return false;
+        }
+
+        if key == KeyCode::Char('g')
+            && !self.confirmation_pending()
+            && self.settings.maintenance_error.is_none()
+        {
+            self.open_quick_generate();
+            return false;
+        }
+
+        if matches!(key, KeyCode::Char('u') | KeyCode::Char('U'))
+            && self.available_update().is_some()
+            && !(key == KeyCode::Char('u') && self.can_restore_previous())
+        {
+            self.show_update_dialog = true;
+            return false;
+        }
+
         match self.current_screen {
             CurrentScreen::Main => self.handle_main_input(key),
             CurrentScreen::GeneratorModeSelection => {
@@ -157,20 +577,191 @@ impl App {
             CurrentScreen::Generator => self.handle_generator_input(key),
             CurrentScreen::Settings => self.handle_settings_input(key),
             CurrentScreen::Help => self.handle_help_input(key),
+            CurrentScreen::About => self.handle_about_input(key),
             CurrentScreen::Check => self.handle_check_input(key),
+            CurrentScreen::History => self.handle_history_input(key),
             CurrentScreen::Exit => self.handle_exit_input(key),
         }
     }
 
+    /// Check whether the current screen waits for a yes/no style answer
+    fn confirmation_pending(&self) -> bool {
+        matches!(self.current_screen, CurrentScreen::Exit)
+            || self.pending_overwrite
+            || self.history.pending_clear
+            || self.settings.pending_exit
+            || self.settings.pending_action.is_some()
+    }
+
+    /// Insert pasted text into the field currently being edited
+    ///
+    /// Control characters, including the trailing newline most terminals send,
+    /// are dropped. Outside of editing mode the paste is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: The pasted text
+    pub fn handle_paste(&mut self, text: &str) {
+        if self.input_mode != InputMode::Editing {
+            return;
+        }
+
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.handle_editing_input(KeyCode::Char(c));
+        }
+    }
+
+    /// Handle a mouse click or scroll event
+    ///
+    /// The layout is recomputed from the terminal size to find the clicked list row.
+    /// Mouse events are ignored while a field is edited, a prompt is pending, or the
+    /// terminal is below the minimum size.
+    ///
+    /// # Arguments
+    ///
+    /// * `event`: The mouse event
+    /// * `area`: The area of the whole terminal
+    pub fn handle_mouse(&mut self, event: MouseEvent, area: Rect) {
+        if self.input_mode == InputMode::Editing
+            || self.generation.is_some()
+            || self.pending_overwrite
+            || self.show_update_dialog
+            || self.quick_generate.is_some()
+            || self.settings.pending_action.is_some()
+            || self.settings.maintenance_error.is_some()
+            || too_small(area)
+        {
+            return;
+        }
+
+        let body = screen_layout(app_layout(area).0)[1];
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left)
+                if matches!(self.current_screen, CurrentScreen::Generator) =>
+            {
+                self.click_generator(body, event.column, event.row);
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(row) = Self::list_row(body, event.column, event.row) else {
+                    return;
+                };
+                match self.current_screen {
+                    CurrentScreen::Main if row < MAIN_MENU_ITEMS => {
+                        self.selected_index = row;
+                        self.handle_main_input(KeyCode::Enter);
+                    }
+                    CurrentScreen::GeneratorModeSelection if row < GENERATOR_MODES => {
+                        self.generator.selected_mode_index = row;
+                        self.handle_generator_mode_selection_input(KeyCode::Enter);
+                    }
+                    CurrentScreen::History => {
+                        let index = row + Self::list_offset(self.history.selected, body);
+                        if index < self.history.entries.len() {
+                            self.history.selected = index;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let key = if event.kind == MouseEventKind::ScrollUp {
+                    KeyCode::Up
+                } else {
+                    KeyCode::Down
+                };
+                match self.current_screen {
+                    CurrentScreen::Help => {
+                        self.handle_help_input(key);
+                    }
+                    CurrentScreen::Generator if !self.generated_passwords.is_empty() => {
+                        self.generator.focus = GeneratorFocus::Results;
+                        self.handle_results_input(key);
+                    }
+                    CurrentScreen::History if !self.history.pending_clear => {
+                        self.handle_history_input(key);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Get the row of a bordered list at a terminal position
+    ///
+    /// # Arguments
+    ///
+    /// * `area`: The area of the list including its border
+    /// * `column`: The clicked column
+    /// * `row`: The clicked row
+    ///
+    /// # Returns
+    ///
+    /// Returns the visible row index, None if the position is outside the list
+    fn list_row(area: Rect, column: u16, row: u16) -> Option<usize> {
+        let inner = area.inner(Margin::new(1, 1));
+        inner
+            .contains(Position::new(column, row))
+            .then(|| (row - inner.y) as usize)
+    }
+
+    /// Get the first visible row of a bordered list
+    ///
+    /// Lists are rendered with a fresh state each frame, so they scroll just far enough
+    /// to keep the selected row visible.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected`: The index of the selected row
+    /// * `area`: The area of the list including its border
+    ///
+    /// # Returns
+    ///
+    /// Returns the index of the first visible row
+    fn list_offset(selected: usize, area: Rect) -> usize {
+        (selected + 1).saturating_sub(area.height.saturating_sub(2) as usize)
+    }
+
+    /// Select the parameter or generated password at a position of the generator screen
+    fn click_generator(&mut self, body: Rect, column: u16, row: u16) {
+        let panes = generator_panes(body);
+
+        if let Some(index) = Self::list_row(panes[0], column, row) {
+            if index < GeneratorField::rows(&self.generator.mode).len() {
+                self.generator.focus = GeneratorFocus::Parameters;
+                self.selected_index = index;
+            }
+        } else if let Some(index) = Self::list_row(panes[1], column, row) {
+            let index = index + self.generator.results_offset;
+            if index < self.generated_passwords.len() {
+                self.generator.focus = GeneratorFocus::Results;
+                self.generator.selected_result = index;
+            }
+        }
+    }
+
     fn handle_editing_input(&mut self, key: KeyCode) -> bool {
         match key {
             KeyCode::Enter => {
+                if let InputField::Generator(
+                    field @ (GeneratorField::Length
+                    | GeneratorField::Count
+                    | GeneratorField::Words
+                    | GeneratorField::PhraseDigits
+                    | GeneratorField::PhraseSymbols),
+                ) = self.input_field
+                    && !self.apply_numeric_input(field)
+                {
+                    return false;
+                }
+
                 let current_field = self.input_field;
                 self.input_mode = InputMode::Normal;
                 self.input_field = InputField::None;
 
-                if let InputField::Generator(GeneratorField::Pattern) = current_field {
+                if let InputField::Generator(_) = current_field {
                     self.generator.editing_field = None;
+                    self.generator.numeric_input.clear();
                 }
 
                 if let InputField::PasswordCheck = current_field
@@ -178,68 +769,80 @@ impl App {
                 {
                     self.check_password();
                 }
+
+                if let InputField::SaveFilename = current_field {
+                    self.request_save();
+                    if matches!(self.current_screen, CurrentScreen::Exit) {
+                        return self.exit_if_saved();
+                    }
+                }
+
+                if let InputField::PresetName = current_field {
+                    self.save_preset();
+                }
                 false
             }
             KeyCode::Esc => {
+                if !self.generator.numeric_input.is_empty() {
+                    self.error_message = None;
+                }
                 self.input_mode = InputMode::Normal;
                 self.input_field = InputField::None;
                 self.generator.editing_field = None;
+                self.generator.numeric_input.clear();
                 false
             }
-            KeyCode::Char(c) => {
-                match self.input_field {
-                    InputField::PasswordCheck => {
-                        self.password_input.push(c);
-                        self.check_result = None;
-                    }
-                    InputField::Generator(GeneratorField::Pattern) => {
-                        self.generator.pattern.push(c);
-                    }
-                    InputField::Generator(GeneratorField::SeedEnv) => {
-                        self.generator.seed_env.push(c);
-                    }
-                    InputField::Generator(GeneratorField::Service) => {
-                        self.generator.service.push(c);
-                    }
-                    InputField::Generator(GeneratorField::Salt) => {
-                        self.generator.salt.push(c);
-                    }
-                    InputField::Settings(SettingsField::Language) => {
-                        self.settings.language.push(c);
-                    }
-                    _ => {}
-                }
+            KeyCode::Char(c)
+                if matches!(
+                    self.input_field,
+                    InputField::Generator(
+                        GeneratorField::Length
+                            | GeneratorField::Count
+                            | GeneratorField::Words
+                            | GeneratorField::PhraseDigits
+                            | GeneratorField::PhraseSymbols
+                    )
+                ) && !c.is_ascii_digit() =>
+            {
                 false
             }
-            KeyCode::Backspace => {
-                match self.input_field {
-                    InputField::PasswordCheck => {
-                        self.password_input.pop();
-                        self.check_result = None;
-                    }
-                    InputField::Generator(GeneratorField::Pattern) => {
-                        self.generator.pattern.pop();
-                    }
-                    InputField::Generator(GeneratorField::SeedEnv) => {
-                        self.generator.seed_env.pop();
-                    }
-                    InputField::Generator(GeneratorField::Service) => {
-                        self.generator.service.pop();
-                    }
-                    InputField::Generator(GeneratorField::Salt) => {
-                        self.generator.salt.pop();
-                    }
-                    InputField::Settings(SettingsField::Language) => {
-                        self.settings.language.pop();
-                    }
-                    _ => {}
+            _ => {
+                let field = self.input_field;
+                if let Some(input) = self.editing_input()
+                    && input.handle_key(key)
+                    && field == InputField::PasswordCheck
+                {
+                    self.schedule_check();
                 }
                 false
             }
-            _ => false,
         }
     }
 
+    /// Get the text input of the field being edited
+    fn editing_input(&mut self) -> Option<&mut TextInput> {
+        let input = match self.input_field {
+            InputField::None => return None,
+            InputField::PasswordCheck => &mut self.password_input,
+            InputField::Generator(
+                GeneratorField::Length
+                | GeneratorField::Count
+                | GeneratorField::Words
+                | GeneratorField::PhraseDigits
+                | GeneratorField::PhraseSymbols,
+            ) => &mut self.generator.numeric_input,
+            InputField::Generator(GeneratorField::InsertStyle) => return None,
+            InputField::Generator(GeneratorField::Pattern) => &mut self.generator.pattern,
+            InputField::Generator(GeneratorField::SeedEnv) => &mut self.generator.seed_env,
+            InputField::Generator(GeneratorField::Seed) => &mut self.generator.seed,
+            InputField::Generator(GeneratorField::Service) => &mut self.generator.service,
+            InputField::Generator(GeneratorField::Salt) => &mut self.generator.salt,
+            InputField::SaveFilename => &mut self.save_filename,
+            InputField::PresetName => &mut self.preset_name,
+        };
+        Some(input)
+    }
+
     fn handle_main_input(&mut self, key: KeyCode) -> bool {
         match key {
             KeyCode::Char('q') | KeyCode::Esc => {
@@ -253,7 +856,7 @@ impl App {
                 false
             }
             KeyCode::Down => {
-                if self.selected_index < 4 {
+                if self.selected_index + 1 < MAIN_MENU_ITEMS {
                     self.selected_index += 1;
                 }
                 false
@@ -264,22 +867,28 @@ impl App {
                         self.current_screen = CurrentScreen::GeneratorModeSelection;
                         self.selected_index = 0;
                     }
-                    1 => {
+                    1 => self.open_quick_generate(),
+                    2 => {
                         self.current_screen = CurrentScreen::Check;
                         self.password_input.clear();
                         self.check_result = None;
+                        self.reset_breach_check();
                         self.input_field = InputField::None;
                         self.input_mode = InputMode::Normal;
                     }
-                    2 => {
+                    3 => {
+                        self.current_screen = CurrentScreen::History;
+                        self.history.pending_clear = false;
+                    }
+                    4 => {
                         self.current_screen = CurrentScreen::Settings;
                         self.selected_index = 0;
                     }
-                    3 => {
+                    5 => {
                         self.current_screen = CurrentScreen::Help;
                         self.help.scroll = 0;
                     }
-                    4 => {
+                    6 => {
                         self.current_screen = CurrentScreen::Exit;
                     }
                     _ => {}
@@ -304,21 +913,21 @@ impl App {
                 false
             }
             KeyCode::Down => {
-                if self.generator.selected_mode_index < 3 {
+                if self.generator.selected_mode_index + 1 < GENERATOR_MODES {
                     self.generator.selected_mode_index += 1;
                 }
                 false
             }
             KeyCode::Enter => {
-                self.generator.mode = match self.generator.selected_mode_index {
+                self.selected_index = 0;
+                self.set_generator_mode(match self.generator.selected_mode_index {
                     0 => PasswordMode::Random,
                     1 => PasswordMode::Pattern,
                     2 => PasswordMode::Phrase,
                     3 => PasswordMode::Deterministic,
                     _ => PasswordMode::Random,
-                };
+                });
                 self.current_screen = CurrentScreen::Generator;
-                self.selected_index = 0;
                 false
             }
             _ => false,
@@ -326,6 +935,72 @@ impl App {
     }
 
     fn handle_generator_input(&mut self, key: KeyCode) -> bool {
+        if let Some(job) = &self.generation {
+            if key == KeyCode::Esc {
+                job.cancellation.cancel();
+            }
+            return false;
+        }
+
+        if self.pending_overwrite {
+            match key {
+                KeyCode::Char('y') => {
+                    self.pending_overwrite = false;
+                    self.save_passwords();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.pending_overwrite = false;
+                    self.set_status(&tr("tui.generator.save_cancelled", &[]));
+                }
+                _ => {}
+            }
+            return false;
+        }
+
+        if self.preset_picker.is_some() {
+            self.handle_preset_picker_input(key);
+            return false;
+        }
+
+        if key == KeyCode::Char('p') {
+            self.preset_picker = Some(0);
+            return false;
+        }
+
+        if key == KeyCode::Char('S') {
+            self.preset_name.clear();
+            self.input_mode = InputMode::Editing;
+            self.input_field = InputField::PresetName;
+            return false;
+        }
+
+        if key == KeyCode::Char('s')
+            && self.input_mode == InputMode::Normal
+            && !self.generated_passwords.is_empty()
+        {
+            self.save_filename.set(&Self::default_save_filename());
+            self.input_mode = InputMode::Editing;
+            self.input_field = InputField::SaveFilename;
+            return false;
+        }
+
+        if key == KeyCode::Tab && !self.generated_passwords.is_empty() {
+            self.generator.focus = match self.generator.focus {
+                GeneratorFocus::Parameters => GeneratorFocus::Results,
+                GeneratorFocus::Results => GeneratorFocus::Parameters,
+            };
+            return false;
+        }
+
+        if key == KeyCode::Char('u') && self.can_restore_previous() {
+            self.restore_previous_passwords();
+            return false;
+        }
+
+        if self.generator.focus == GeneratorFocus::Results {
+            return self.handle_results_input(key);
+        }
+
         match key {
             KeyCode::Esc => {
                 if self.input_mode == InputMode::Editing {
@@ -333,9 +1008,7 @@ impl App {
                     self.input_field = InputField::None;
                     self.generator.editing_field = None;
                 } else {
-                    self.current_screen = CurrentScreen::GeneratorModeSelection;
-                    self.selected_index = 0;
-                    self.error_message = None;
+                    self.leave_generator();
                 }
                 false
             }
@@ -346,224 +1019,541 @@ impl App {
                 false
             }
             KeyCode::Down => {
-                if self.input_mode == InputMode::Normal {
-                    let max_index = match self.generator.mode {
-                        PasswordMode::Random => 1,
-                        PasswordMode::Pattern => 1,
-                        PasswordMode::Phrase => 1,
-                        PasswordMode::Deterministic => 3,
-                    };
-                    if self.selected_index < max_index {
-                        self.selected_index += 1;
-                    }
+                let rows = GeneratorField::rows(&self.generator.mode);
+                if self.input_mode == InputMode::Normal && self.selected_index + 1 < rows.len() {
+                    self.selected_index += 1;
                 }
                 false
             }
             KeyCode::Left => {
                 if self.input_mode == InputMode::Normal {
-                    match self.generator.mode {
-                        PasswordMode::Random => {
-                            if self.selected_index == 0 && self.generator.length > 8 {
-                                self.generator.length -= 1;
-                            }
-                        }
-                        PasswordMode::Phrase => {
-                            if self.selected_index == 0 {
-                                if let Some(ref mut words) = self.generator.words {
-                                    if *words > 3 {
-                                        *words -= 1;
-                                    }
-                                } else {
-                                    self.generator.words = Some(3);
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
+                    self.adjust_selected_row(false);
                 }
                 false
             }
             KeyCode::Right => {
                 if self.input_mode == InputMode::Normal {
-                    match self.generator.mode {
-                        PasswordMode::Random => {
-                            if self.selected_index == 0 && self.generator.length < 64 {
-                                self.generator.length += 1;
-                            }
-                        }
-                        PasswordMode::Phrase => {
-                            if self.selected_index == 0 {
-                                if let Some(ref mut words) = self.generator.words {
-                                    if *words < 20 {
-                                        *words += 1;
-                                    }
-                                } else {
-                                    self.generator.words = Some(4);
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
+                    self.adjust_selected_row(true);
                 }
                 false
             }
             KeyCode::Enter => {
-                if self.input_mode == InputMode::Editing {
-                    self.input_mode = InputMode::Normal;
-                    self.input_field = InputField::None;
-                    self.generator.editing_field = None;
-                    return false;
-                }
-
-                match self.generator.mode {
-                    PasswordMode::Random => {
-                        self.generate_passwords();
-                    }
-                    PasswordMode::Pattern => {
-                        if self.selected_index == 0 {
-                            self.input_mode = InputMode::Editing;
-                            self.input_field = InputField::Generator(GeneratorField::Pattern);
-                            self.generator.editing_field = Some(GeneratorField::Pattern);
-                            self.generator.pattern.clear();
-                        } else {
-                            self.generate_passwords();
-                        }
-                    }
-                    PasswordMode::Phrase => {
-                        self.generate_passwords();
-                    }
-                    PasswordMode::Deterministic => match self.selected_index {
-                        0 => {
-                            self.input_mode = InputMode::Editing;
-                            self.input_field = InputField::Generator(GeneratorField::SeedEnv);
-                            self.generator.editing_field = Some(GeneratorField::SeedEnv);
-                            self.generator.seed_env.clear();
-                        }
-                        1 => {
-                            self.input_mode = InputMode::Editing;
-                            self.input_field = InputField::Generator(GeneratorField::Service);
-                            self.generator.editing_field = Some(GeneratorField::Service);
-                            self.generator.service.clear();
-                        }
-                        2 => {
-                            self.input_mode = InputMode::Editing;
-                            self.input_field = InputField::Generator(GeneratorField::Salt);
-                            self.generator.editing_field = Some(GeneratorField::Salt);
-                            self.generator.salt.clear();
-                        }
-                        _ => {
-                            self.generate_passwords();
-                        }
-                    },
+                match self.selected_row() {
+                    Some(
+                        field @ (GeneratorField::Pattern
+                        | GeneratorField::SeedEnv
+                        | GeneratorField::Seed
+                        | GeneratorField::Service
+                        | GeneratorField::Salt),
+                    ) => self.start_editing(field, true),
+                    _ => self.generate_passwords(),
                 }
                 false
             }
             KeyCode::Char('e') => {
-                if self.input_mode == InputMode::Normal {
-                    match self.generator.mode {
-                        PasswordMode::Pattern => {
-                            if self.selected_index == 0 {
-                                self.input_field = InputField::Generator(GeneratorField::Pattern);
-                                self.generator.editing_field = Some(GeneratorField::Pattern);
-                                self.input_mode = InputMode::Editing;
-                            }
-                        }
-                        PasswordMode::Deterministic => match self.selected_index {
-                            0 => {
-                                self.input_field = InputField::Generator(GeneratorField::SeedEnv);
-                                self.generator.editing_field = Some(GeneratorField::SeedEnv);
-                                self.input_mode = InputMode::Editing;
-                            }
-                            1 => {
-                                self.input_field = InputField::Generator(GeneratorField::Service);
-                                self.generator.editing_field = Some(GeneratorField::Service);
-                                self.input_mode = InputMode::Editing;
-                            }
-                            2 => {
-                                self.input_field = InputField::Generator(GeneratorField::Salt);
-                                self.generator.editing_field = Some(GeneratorField::Salt);
-                                self.input_mode = InputMode::Editing;
-                            }
-                            _ => {}
-                        },
-                        _ => {}
-                    }
+                if self.input_mode == InputMode::Normal
+                    && let Some(field) = self.selected_row()
+                {
+                    self.start_editing(field, false);
                 }
                 false
             }
+            KeyCode::Char('v') if self.generator.mode == PasswordMode::Deterministic => {
+                self.generator.show_seed = !self.generator.show_seed;
+                self.generator.seed_env.masked = !self.generator.show_seed;
+                self.generator.salt.masked = !self.generator.show_seed;
+                false
+            }
             _ => false,
         }
     }
 
-    fn handle_settings_input(&mut self, key: KeyCode) -> bool {
+    fn handle_preset_picker_input(&mut self, key: KeyCode) {
+        let Some(selected) = self.preset_picker else {
+            return;
+        };
+        let count = self.settings.saved.presets.len();
+
         match key {
-            KeyCode::Esc => {
-                self.current_screen = CurrentScreen::Main;
-                self.selected_index = 2; // Zurück zu Settings im Hauptmenü
-                false
-            }
-            KeyCode::Up => {
-                if self.settings.selected_index > 0 {
-                    self.settings.selected_index -= 1;
+            KeyCode::Up => self.preset_picker = Some(selected.saturating_sub(1)),
+            KeyCode::Down if selected + 1 < count => self.preset_picker = Some(selected + 1),
+            KeyCode::Enter => {
+                if let Some((name, preset)) = self.settings.saved.presets.iter().nth(selected) {
+                    let (name, preset) = (name.clone(), preset.clone());
+                    self.load_preset(&preset);
+                    self.set_status(&tr("tui.generator.preset_loaded", &[("name", &name)]));
                 }
-                false
+                self.preset_picker = None;
             }
-            KeyCode::Down => {
-                if self.settings.selected_index < 3 {
-                    self.settings.selected_index += 1;
-                }
-                false
+            KeyCode::Esc | KeyCode::Char('p') => self.preset_picker = None,
+            _ => {}
+        }
+    }
+
+    /// Apply a preset to the generator, keeping the values it does not set
+    pub fn load_preset(&mut self, preset: &Preset) {
+        if let Ok(mode) = parse_password_mode(&preset.mode) {
+            self.set_generator_mode(mode);
+        }
+        if let Some(length) = preset.length {
+            self.generator.length = length.clamp(
+                *Generator::LENGTH_RANGE.start(),
+                *Generator::LENGTH_RANGE.end(),
+            );
+        }
+        if let Some(count) = preset.count {
+            self.generator.count = count.clamp(*COUNT_RANGE.start(), *COUNT_RANGE.end());
+        }
+        if let Some(words) = preset.words {
+            self.generator.words = Some(words.clamp(
+                *Generator::WORDS_RANGE.start(),
+                *Generator::WORDS_RANGE.end(),
+            ));
+        }
+        let text_fields = [
+            (&preset.pattern, &mut self.generator.pattern),
+            (&preset.seed_env, &mut self.generator.seed_env),
+            (&preset.service, &mut self.generator.service),
+            (&preset.salt, &mut self.generator.salt),
+        ];
+        for (value, field) in text_fields {
+            if let Some(value) = value {
+                field.set(value);
             }
-            KeyCode::Enter => {
-                self.save_settings();
-                false
+        }
+
+        self.selected_index = 0;
+        self.generator.focus = GeneratorFocus::Parameters;
+        self.error_message = None;
+    }
+
+    /// Build a preset from the generator, setting only the fields of the current mode
+    pub fn current_preset(&self) -> Preset {
+        let text = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        let mode = &self.generator.mode;
+        let mut preset = Preset {
+            mode: mode
+                .to_possible_value()
+                .map(|value| value.get_name().to_string())
+                .unwrap_or_default(),
+            count: Some(self.generator.count),
+            ..Preset::default()
+        };
+
+        match mode {
+            PasswordMode::Random => preset.length = Some(self.generator.length),
+            PasswordMode::Pattern => preset.pattern = text(self.generator.pattern.value()),
+            PasswordMode::Phrase => preset.words = self.generator.words,
+            PasswordMode::Deterministic => {
+                preset.seed_env = text(self.generator.seed_env.value());
+                preset.service = text(self.generator.service.value());
+                preset.salt = text(self.generator.salt.value());
             }
-            KeyCode::Left => {
-                match self.settings.selected_index {
-                    1 => {
-                        if self.settings.default_length > 4 {
-                            self.settings.default_length -= 1;
-                        }
-                    }
-                    2 => {
-                        if self.settings.default_count > 1 {
-                            self.settings.default_count -= 1;
-                        }
-                    }
-                    3 => {
-                        self.settings.auto_save = !self.settings.auto_save;
-                    }
-                    _ => {}
+        }
+
+        preset
+    }
+
+    /// Save the generator state under the typed preset name
+    fn save_preset(&mut self) {
+        let name = self.preset_name.value().trim().to_string();
+        let preset = self.current_preset();
+
+        match Config::save_preset(&name, preset.clone()) {
+            Ok(()) => {
+                self.settings.saved.presets.insert(name.clone(), preset);
+                self.error_message = None;
+                self.set_status(&tr("tui.generator.preset_saved", &[("name", &name)]));
+            }
+            Err(e) => {
+                self.error_message = Some(tr(
+                    "tui.generator.preset_failed",
+                    &[("error", &e.to_string())],
+                ))
+            }
+        }
+    }
+
+    /// Get the parameter row the cursor is on
+    pub fn selected_row(&self) -> Option<GeneratorField> {
+        GeneratorField::rows(&self.generator.mode)
+            .get(self.selected_index)
+            .copied()
+    }
+
+    /// Switch the generator mode and keep the cursor on an existing row
+    /// Return to the mode selection and wipe the typed seed
+    fn leave_generator(&mut self) {
+        self.generator.seed.clear();
+        self.current_screen = CurrentScreen::GeneratorModeSelection;
+        self.selected_index = 0;
+        self.error_message = None;
+    }
+
+    pub fn set_generator_mode(&mut self, mode: PasswordMode) {
+        self.generator.mode = mode;
+        let rows = GeneratorField::rows(&self.generator.mode);
+        self.selected_index = self.selected_index.min(rows.len() - 1);
+    }
+
+    /// Decrease or increase the value of the selected row within its allowed range
+    fn adjust_selected_row(&mut self, increase: bool) {
+        let step = |value: usize, range: std::ops::RangeInclusive<usize>| {
+            if increase {
+                (value + 1).min(*range.end())
+            } else {
+                value.saturating_sub(1).max(*range.start())
+            }
+        };
+
+        match self.selected_row() {
+            Some(GeneratorField::Length) => {
+                self.generator.length = step(self.generator.length, Generator::LENGTH_RANGE);
+            }
+            Some(GeneratorField::Count) => {
+                self.generator.count = step(self.generator.count, COUNT_RANGE);
+            }
+            Some(GeneratorField::Words) => {
+                self.generator.words = Some(match self.generator.words {
+                    Some(words) => step(words, Generator::WORDS_RANGE),
+                    None if increase => 4,
+                    None => 3,
+                });
+            }
+            Some(GeneratorField::PhraseDigits) => {
+                self.generator.phrase.digits =
+                    step(self.generator.phrase.digits, Generator::PHRASE_INSERT_RANGE);
+            }
+            Some(GeneratorField::PhraseSymbols) => {
+                self.generator.phrase.symbols = step(
+                    self.generator.phrase.symbols,
+                    Generator::PHRASE_INSERT_RANGE,
+                );
+            }
+            Some(GeneratorField::InsertStyle) => {
+                let styles = InsertStyle::value_variants();
+                let index = styles
+                    .iter()
+                    .position(|style| *style == self.generator.phrase.style)
+                    .unwrap_or(0);
+                let index = if increase {
+                    (index + 1) % styles.len()
+                } else {
+                    (index + styles.len() - 1) % styles.len()
+                };
+                self.generator.phrase.style = styles[index];
+            }
+            _ => {}
+        }
+    }
+
+    /// Start editing a parameter field
+    ///
+    /// Numeric fields are typed into a pending buffer, text fields are edited in place
+    /// and optionally cleared first.
+    fn start_editing(&mut self, field: GeneratorField, clear: bool) {
+        match field {
+            GeneratorField::Length => {
+                self.generator
+                    .numeric_input
+                    .set(&self.generator.length.to_string());
+            }
+            GeneratorField::Count => {
+                self.generator
+                    .numeric_input
+                    .set(&self.generator.count.to_string());
+            }
+            GeneratorField::Words => {
+                self.generator
+                    .numeric_input
+                    .set(&self.generator.words.unwrap_or(4).to_string());
+            }
+            GeneratorField::PhraseDigits => {
+                self.generator
+                    .numeric_input
+                    .set(&self.generator.phrase.digits.to_string());
+            }
+            GeneratorField::PhraseSymbols => {
+                self.generator
+                    .numeric_input
+                    .set(&self.generator.phrase.symbols.to_string());
+            }
+            // The style is only cycled with the arrow keys
+            GeneratorField::InsertStyle => return,
+            GeneratorField::Pattern if clear => self.generator.pattern.clear(),
+            GeneratorField::SeedEnv if clear => self.generator.seed_env.clear(),
+            GeneratorField::Seed if clear => self.generator.seed.clear(),
+            GeneratorField::Service if clear => self.generator.service.clear(),
+            GeneratorField::Salt if clear => self.generator.salt.clear(),
+            _ => {}
+        }
+
+        self.input_field = InputField::Generator(field);
+        self.generator.editing_field = Some(field);
+        self.input_mode = InputMode::Editing;
+        if let Some(input) = self.editing_input() {
+            input.handle_key(KeyCode::End);
+        }
+    }
+
+    fn handle_settings_input(&mut self, key: KeyCode) -> bool {
+        if self.settings.maintenance_error.is_some() {
+            self.settings.maintenance_error = None;
+            return false;
+        }
+
+        if let Some(action) = self.settings.pending_action {
+            match key {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.settings.pending_action = None;
+                    return self.run_maintenance_action(action);
                 }
-                false
+                KeyCode::Char('n') | KeyCode::Esc => self.settings.pending_action = None,
+                _ => {}
             }
-            KeyCode::Right => {
-                match self.settings.selected_index {
-                    0 => {
-                        self.input_mode = InputMode::Editing;
-                        self.input_field = InputField::Settings(SettingsField::Language);
+            return false;
+        }
+
+        if self.settings.pending_exit {
+            match key {
+                KeyCode::Char('s') | KeyCode::Enter => {
+                    self.settings.pending_exit = false;
+                    if self.save_settings() {
+                        self.leave_settings();
                     }
-                    1 => {
-                        if self.settings.default_length < 128 {
-                            self.settings.default_length += 1;
-                        }
+                }
+                KeyCode::Char('d') => {
+                    self.settings.pending_exit = false;
+                    self.discard_settings();
+                    self.leave_settings();
+                }
+                KeyCode::Char('c') | KeyCode::Esc => {
+                    self.settings.pending_exit = false;
+                }
+                _ => {}
+            }
+            return false;
+        }
+
+        match key {
+            KeyCode::Esc => {
+                if self.settings_dirty() {
+                    self.settings.pending_exit = true;
+                } else {
+                    self.leave_settings();
+                }
+                false
+            }
+            KeyCode::Up => {
+                if self.settings.selected_index > 0 {
+                    self.settings.selected_index -= 1;
+                }
+                false
+            }
+            KeyCode::Down => {
+                if self.settings.selected_index + 1 < SettingsField::ROWS.len() {
+                    self.settings.selected_index += 1;
+                }
+                false
+            }
+            KeyCode::Enter => {
+                match self.selected_setting() {
+                    Some(SettingsField::ResetConfig) => {
+                        self.settings.pending_action = Some(MaintenanceAction::ResetConfig);
                     }
-                    2 => {
-                        if self.settings.default_count < 100 {
-                            self.settings.default_count += 1;
-                        }
+                    Some(SettingsField::Uninstall) => {
+                        self.settings.pending_action = Some(MaintenanceAction::Uninstall);
                     }
-                    3 => {
-                        self.settings.auto_save = !self.settings.auto_save;
+                    _ => {
+                        self.save_settings();
                     }
-                    _ => {}
                 }
                 false
             }
+            KeyCode::Left => {
+                self.adjust_selected_setting(false);
+                false
+            }
+            KeyCode::Right => {
+                self.adjust_selected_setting(true);
+                false
+            }
             _ => false,
         }
     }
 
+    /// Return to the settings entry of the main menu
+    fn leave_settings(&mut self) {
+        self.current_screen = CurrentScreen::Main;
+        self.selected_index = 4; // Zurück zu Settings im Hauptmenü
+        self.error_message = None;
+    }
+
+    /// Check whether the settings differ from the saved config
+    pub fn settings_dirty(&self) -> bool {
+        let saved = &self.settings.saved;
+        self.settings.language != saved.language.lang
+            || self.settings.default_mode
+                != parse_password_mode(&saved.general.default_mode).unwrap_or(PasswordMode::Random)
+            || self.settings.default_length != saved.general.default_length
+            || self.settings.default_count != saved.general.default_count
+            || self.settings.auto_save != saved.general.auto_save
+            || self.settings.theme != saved.theme.preset()
+    }
+
+    /// Run a confirmed maintenance action
+    ///
+    /// Uninstalling only leaves the TUI, the uninstaller runs once the terminal is restored.
+    ///
+    /// # Arguments
+    ///
+    /// * `action`: The confirmed action
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the TUI should exit
+    fn run_maintenance_action(&mut self, action: MaintenanceAction) -> bool {
+        match action {
+            MaintenanceAction::ResetConfig => {
+                if let Err(e) = self.reset_config() {
+                    self.settings.maintenance_error = Some(e);
+                }
+                false
+            }
+            MaintenanceAction::Uninstall => {
+                self.uninstall_requested = true;
+                self.exit.exit = true;
+                true
+            }
+        }
+    }
+
+    /// Write the built-in defaults to the config file and reload the settings from them
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise the error to show
+    fn reset_config(&mut self) -> Result<(), String> {
+        let defaults = Config::default();
+        Config::save_config(&defaults)
+            .map_err(|e| tr("tui.settings.reset_failed", &[("error", &e.to_string())]))?;
+        LoggingManager::info("Configuration reset to defaults from the TUI");
+
+        self.theme = Theme::from_config(&defaults.theme);
+        self.keymap = KeyMap::default();
+        self.config_warning = None;
+        self.settings.saved = defaults;
+        self.discard_settings();
+        self.error_message = None;
+
+        Lingua::set_language(&self.settings.language)
+            .map_err(|e| tr("tui.settings.language_failed", &[("error", &e.to_string())]))?;
+        self.set_status(&tr("tui.settings.reset_done", &[]));
+        Ok(())
+    }
+
+    /// Reset the settings to the saved config
+    fn discard_settings(&mut self) {
+        let saved = &self.settings.saved;
+        self.settings.language = saved.language.lang.clone();
+        self.settings.default_mode =
+            parse_password_mode(&saved.general.default_mode).unwrap_or(PasswordMode::Random);
+        self.settings.default_length = saved.general.default_length;
+        self.settings.default_count = saved.general.default_count;
+        self.settings.auto_save = saved.general.auto_save;
+        self.settings.theme = saved.theme.preset().to_string();
+    }
+
+    /// Get the settings row the cursor is on
+    pub fn selected_setting(&self) -> Option<SettingsField> {
+        SettingsField::ROWS
+            .get(self.settings.selected_index)
+            .copied()
+    }
+
+    /// Decrease or increase the value of the selected settings row within its allowed range
+    fn adjust_selected_setting(&mut self, increase: bool) {
+        let step = |value: usize, range: std::ops::RangeInclusive<usize>| {
+            let value = if increase {
+                value.saturating_add(1)
+            } else {
+                value.saturating_sub(1)
+            };
+            value.clamp(*range.start(), *range.end())
+        };
+
+        match self.selected_setting() {
+            Some(SettingsField::Language) => self.cycle_language(increase),
+            Some(SettingsField::DefaultMode) => self.cycle_default_mode(increase),
+            Some(SettingsField::DefaultLength) => {
+                self.settings.default_length =
+                    step(self.settings.default_length, Generator::LENGTH_RANGE);
+            }
+            Some(SettingsField::DefaultCount) => {
+                self.settings.default_count = step(self.settings.default_count, COUNT_RANGE);
+            }
+            Some(SettingsField::AutoSave) => self.settings.auto_save = !self.settings.auto_save,
+            Some(SettingsField::Theme) => self.cycle_theme(increase),
+            Some(SettingsField::ResetConfig | SettingsField::Uninstall) | None => {}
+        }
+    }
+
+    /// Get the allowed range of the selected settings row, if it is numeric
+    pub fn selected_setting_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        match self.selected_setting() {
+            Some(SettingsField::DefaultLength) => Some(Generator::LENGTH_RANGE),
+            Some(SettingsField::DefaultCount) => Some(COUNT_RANGE),
+            _ => None,
+        }
+    }
+
+    /// Select the previous or next available language
+    fn cycle_language(&mut self, forward: bool) {
+        let languages = &self.settings.languages;
+        if languages.is_empty() {
+            return;
+        }
+
+        let next = match languages
+            .iter()
+            .position(|lang| *lang == self.settings.language)
+        {
+            Some(index) if forward => (index + 1) % languages.len(),
+            Some(index) => (index + languages.len() - 1) % languages.len(),
+            None => 0,
+        };
+        self.settings.language = languages[next].clone();
+    }
+
+    /// Select the previous or next default generator mode
+    fn cycle_default_mode(&mut self, forward: bool) {
+        let modes = PasswordMode::value_variants();
+        let index = modes
+            .iter()
+            .position(|mode| *mode == self.settings.default_mode)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % modes.len()
+        } else {
+            (index + modes.len() - 1) % modes.len()
+        };
+        self.settings.default_mode = modes[next].clone();
+    }
+
+    /// Select the previous or next theme preset
+    fn cycle_theme(&mut self, forward: bool) {
+        let index = THEME_PRESETS
+            .iter()
+            .position(|preset| *preset == self.settings.theme)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % THEME_PRESETS.len()
+        } else {
+            (index + THEME_PRESETS.len() - 1) % THEME_PRESETS.len()
+        };
+        self.settings.theme = THEME_PRESETS[next].to_string();
+    }
+
     fn handle_help_input(&mut self, key: KeyCode) -> bool {
         match key {
             KeyCode::Esc | KeyCode::Char('q') => {
@@ -572,32 +1562,204 @@ impl App {
                 false
             }
             KeyCode::Up => {
-                if self.help.scroll > 0 {
-                    self.help.scroll -= 1;
-                }
+                self.help.scroll = self.help.scroll.saturating_sub(1);
                 false
             }
             KeyCode::Down => {
-                self.help.scroll += 1;
+                self.help.scroll = (self.help.scroll + 1).min(self.help.max_scroll);
+                false
+            }
+            KeyCode::PageUp => {
+                self.help.scroll = self.help.scroll.saturating_sub(self.help.page_height);
+                false
+            }
+            KeyCode::PageDown => {
+                self.help.scroll =
+                    (self.help.scroll + self.help.page_height).min(self.help.max_scroll);
+                false
+            }
+            KeyCode::Home => {
+                self.help.scroll = 0;
+                false
+            }
+            KeyCode::End => {
+                self.help.scroll = self.help.max_scroll;
+                false
+            }
+            KeyCode::Char('a') => {
+                self.open_about();
                 false
             }
             _ => false,
         }
     }
 
+    /// Collect the version and path details and show the about screen
+    pub fn open_about(&mut self) {
+        let path = |result: Result<std::path::PathBuf, String>| {
+            result.map_or_else(
+                |e| tr("tui.about.unavailable", &[("error", &e)]),
+                |path| path.display().to_string(),
+            )
+        };
+
+        self.about = vec![
+            AboutEntry {
+                key: "version",
+                label: "Version",
+                value: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            AboutEntry {
+                key: "config_path",
+                label: "Config file",
+                value: path(Config::get_config_path().map_err(|e| e.to_string())),
+            },
+            AboutEntry {
+                key: "languages_path",
+                label: "Languages directory",
+                value: path(Config::get_languages_path().map_err(|e| e.to_string())),
+            },
+            AboutEntry {
+                key: "log_path",
+                label: "Log directory",
+                value: path(LoggingManager::get_logging_path().map_err(|e| e.to_string())),
+            },
+            AboutEntry {
+                key: "language",
+                label: "Language",
+                value: self.settings.saved.language.lang.clone(),
+            },
+        ];
+        self.current_screen = CurrentScreen::About;
+    }
+
+    /// Get the about details as plain English text for bug reports
+    pub fn about_text(&self) -> String {
+        let update = match &self.update_status {
+            UpdateStatus::Checking => "still checking".to_string(),
+            UpdateStatus::UpToDate => "up to date".to_string(),
+            UpdateStatus::Available(tag) => format!("{} available", tag),
+            UpdateStatus::Failed(error) => format!("failed ({})", error),
+            UpdateStatus::Disabled => "disabled".to_string(),
+        };
+
+        let mut text = format!("kdguard {} ({} {})\n", env!("CARGO_PKG_VERSION"), OS, ARCH);
+        for entry in &self.about {
+            text.push_str(&format!("{}: {}\n", entry.label, entry.value));
+        }
+        text.push_str(&format!("Update check: {}\n", update));
+        text
+    }
+
+    /// Get the saved general defaults with the active profile merged in
+    ///
+    /// # Returns
+    ///
+    /// Returns the defaults the generator starts with
+    pub fn general_defaults(&self) -> GeneralConfig {
+        let saved = &self.settings.saved;
+        saved
+            .general_for(self.settings.profile.as_deref())
+            .unwrap_or_else(|_| saved.general.clone())
+    }
+
+    /// Generate one password with the saved defaults and show it in a dialog
+    ///
+    /// The generator state and results are set aside and restored when the dialog
+    /// closes, so the generator screen keeps its parameters and passwords.
+    pub fn open_quick_generate(&mut self) {
+        if self.generation.is_some() || self.quick_generate.is_some() {
+            return;
+        }
+
+        let mut generator = GeneratorState::from_config(&self.general_defaults());
+        generator.count = 1;
+        self.quick_generate = Some(QuickGenerate {
+            generator: std::mem::replace(&mut self.generator, generator),
+            passwords: std::mem::take(&mut self.generated_passwords),
+            error: self.error_message.take(),
+        });
+        self.generate_passwords();
+    }
+
+    /// Close the quick generate dialog and bring back the generator state
+    fn close_quick_generate(&mut self) {
+        if let Some(job) = self.generation.take() {
+            job.cancellation.cancel();
+        }
+        if let Some(quick) = self.quick_generate.take() {
+            self.generator = quick.generator;
+            self.generated_passwords = quick.passwords;
+            self.error_message = quick.error;
+        }
+    }
+
+    fn handle_quick_generate_input(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('c') => {
+                if let Some(password) = self.generated_passwords.first() {
+                    self.copy_to_clipboard(password.password.clone());
+                }
+            }
+            KeyCode::Char('r') => self.generate_passwords(),
+            KeyCode::Esc | KeyCode::Char('q') => self.close_quick_generate(),
+            _ => {}
+        }
+        false
+    }
+
+    fn handle_about_input(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('c') => self.copy_to_clipboard(self.about_text()),
+            KeyCode::Esc | KeyCode::Char('q') => self.current_screen = CurrentScreen::Help,
+            _ => {}
+        }
+        false
+    }
+
+    /// Remember the scroll position of the results pane after it was rendered
+    ///
+    /// # Arguments
+    ///
+    /// * `offset`: The index of the first visible result
+    /// * `page`: The number of visible results
+    pub fn set_results_viewport(&mut self, offset: usize, page: usize) {
+        self.generator.results_offset = offset;
+        self.generator.results_page = page.max(1);
+    }
+
+    /// Update the scroll limits of the help screen after the text was laid out
+    ///
+    /// # Arguments
+    ///
+    /// * `content_height`: The number of wrapped help lines
+    /// * `viewport_height`: The number of visible lines
+    pub fn set_help_viewport(&mut self, content_height: usize, viewport_height: usize) {
+        self.help.max_scroll = content_height.saturating_sub(viewport_height);
+        self.help.page_height = viewport_height.max(1);
+        self.help.scroll = self.help.scroll.min(self.help.max_scroll);
+    }
+
     fn handle_check_input(&mut self, key: KeyCode) -> bool {
         match self.input_mode {
             InputMode::Editing => false, // handled in handle_editing_input
             InputMode::Normal => match key {
                 KeyCode::Esc => {
                     self.current_screen = CurrentScreen::Main;
-                    self.selected_index = 1; // Zurück zum Menüpunkt "Check"
+                    self.selected_index = 2; // Zurück zum Menüpunkt "Check"
                     self.password_input.clear();
                     self.check_result = None;
+                    self.check_pending_since = None;
+                    self.reset_breach_check();
+                    self.password_input.masked = true;
                     self.input_field = InputField::None;
                     false
                 }
-                KeyCode::Enter => {
+                KeyCode::Char('v') => {
+                    self.password_input.masked = !self.password_input.masked;
+                    false
+                }
+                KeyCode::Enter | KeyCode::Char('i') => {
                     self.input_mode = InputMode::Editing;
                     self.input_field = InputField::PasswordCheck;
                     false
@@ -614,6 +1776,12 @@ impl App {
                 KeyCode::Char('c') => {
                     self.password_input.clear();
                     self.check_result = None;
+                    self.check_pending_since = None;
+                    self.reset_breach_check();
+                    false
+                }
+                KeyCode::Char('b') => {
+                    self.start_breach_check();
                     false
                 }
                 _ => false,
@@ -621,92 +1789,2019 @@ impl App {
         }
     }
 
+    fn handle_history_input(&mut self, key: KeyCode) -> bool {
+        if self.history.pending_clear {
+            match key {
+                KeyCode::Char('y') => {
+                    self.history.pending_clear = false;
+                    self.history.entries.clear();
+                    self.history.selected = 0;
+                    self.set_status(&tr("tui.history.cleared", &[]));
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.history.pending_clear = false,
+                _ => {}
+            }
+            return false;
+        }
+
+        match key {
+            KeyCode::Esc => {
+                self.current_screen = CurrentScreen::Main;
+                self.selected_index = 3; // Zurück zum Menüpunkt "History"
+            }
+            KeyCode::Up => {
+                self.history.selected = self.history.selected.saturating_sub(1);
+            }
+            KeyCode::Down if self.history.selected + 1 < self.history.entries.len() => {
+                self.history.selected += 1;
+            }
+            KeyCode::Char('c') => {
+                if let Some(entry) = self.history.entries.get(self.history.selected) {
+                    let password = entry.password.to_string();
+                    self.copy_to_clipboard(password);
+                }
+            }
+            KeyCode::Char('x') if !self.history.entries.is_empty() => {
+                self.history.pending_clear = true;
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Add generated passwords to the session history
+    ///
+    /// # Arguments
+    ///
+    /// * `passwords`: The generated passwords
+    /// * `mode`: The mode they were generated with
+    /// * `parameters`: A description of the generation parameters
+    fn record_history(&mut self, passwords: &[String], mode: &PasswordMode, parameters: &str) {
+        let created = Local::now();
+        self.history
+            .entries
+            .extend(passwords.iter().map(|password| HistoryEntry {
+                password: Zeroizing::new(password.clone()),
+                mode: mode.clone(),
+                parameters: parameters.to_string(),
+                created,
+            }));
+    }
+
+    /// Describe the current generator parameters for the history
+    ///
+    /// Seeds are referenced by their environment variable only, typed seeds are never recorded.
+    fn generation_parameters(&self) -> String {
+        let parameter = |key: &str, params: &[(&str, &str)]| {
+            tr(&format!("tui.history.parameters.{}", key), params)
+        };
+        let mut parameters = Vec::new();
+        match self.generator.mode {
+            PasswordMode::Random => parameters.push(parameter(
+                "length",
+                &[("value", &self.generator.length.to_string())],
+            )),
+            PasswordMode::Pattern => parameters.push(parameter(
+                "pattern",
+                &[("value", self.generator.pattern.value())],
+            )),
+            PasswordMode::Phrase => {
+                parameters.push(parameter(
+                    "words",
+                    &[("count", &self.generator.words.unwrap_or(4).to_string())],
+                ));
+                if self.generator.phrase.inserts() {
+                    parameters.push(parameter(
+                        "inserts",
+                        &[
+                            ("digits", &self.generator.phrase.digits.to_string()),
+                            ("symbols", &self.generator.phrase.symbols.to_string()),
+                        ],
+                    ));
+                }
+            }
+            PasswordMode::Deterministic => {
+                parameters.push(if self.generator.seed.is_empty() {
+                    parameter("seed_env", &[("name", self.generator.seed_env.value())])
+                } else {
+                    parameter("typed_seed", &[])
+                });
+                if !self.generator.service.is_empty() {
+                    parameters.push(parameter(
+                        "service",
+                        &[("name", self.generator.service.value())],
+                    ));
+                }
+                let seed = if self.generator.seed.is_empty() {
+                    Zeroizing::new(
+                        std::env::var(self.generator.seed_env.value()).unwrap_or_default(),
+                    )
+                } else {
+                    Zeroizing::new(self.generator.seed.value().to_string())
+                };
+                let salt = Some(self.generator.salt.value()).filter(|salt| !salt.is_empty());
+                if let Ok(fingerprint) =
+                    Generator::seed_fingerprint(&seed, salt, &self.settings.saved.deterministic)
+                {
+                    let mut text = parameter("fingerprint", &[("fingerprint", &fingerprint)]);
+                    if let Some(saved) = &self.settings.saved.deterministic.fingerprint
+                        && !saved.trim().eq_ignore_ascii_case(&fingerprint)
+                    {
+                        text.push(' ');
+                        text.push_str(&parameter("saved_fingerprint", &[("fingerprint", saved)]));
+                    }
+                    parameters.push(text);
+                }
+            }
+        }
+        parameters.join(", ")
+    }
+
     fn handle_exit_input(&mut self, key: KeyCode) -> bool {
+        if self.pending_overwrite {
+            match key {
+                KeyCode::Char('y') => {
+                    self.pending_overwrite = false;
+                    self.save_passwords();
+                    return self.exit_if_saved();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.pending_overwrite = false,
+                _ => {}
+            }
+            return false;
+        }
+
+        // Enter only confirms when nothing would be lost
+        let unsaved = self.unsaved_results() > 0;
         match key {
-            KeyCode::Char('y') | KeyCode::Enter => {
+            KeyCode::Char('y') => {
+                self.exit.exit = true;
+                true
+            }
+            KeyCode::Enter if !unsaved => {
                 self.exit.exit = true;
                 true
             }
+            KeyCode::Char('s') if unsaved => {
+                self.save_filename.set(&Self::default_save_filename());
+                self.input_mode = InputMode::Editing;
+                self.input_field = InputField::SaveFilename;
+                false
+            }
             KeyCode::Char('n') | KeyCode::Esc => {
                 self.current_screen = CurrentScreen::Main;
                 self.selected_index = 0;
+                self.error_message = None;
                 false
             }
             _ => false,
         }
     }
 
-    pub fn generate_passwords(&mut self) {
-        self.error_message = None;
-        self.generated_passwords.clear();
-
-        for _ in 0..self.generator.count {
-            let result = match self.generator.mode {
-                PasswordMode::Random => Generator::generate_random_password(self.generator.length),
-                PasswordMode::Pattern => {
-                    if self.generator.pattern.is_empty() {
-                        self.error_message = Some("Pattern is required".to_string());
-                        return;
-                    }
-                    Generator::generate_pattern_password(&self.generator.pattern)
-                }
-                PasswordMode::Phrase => {
-                    Generator::generate_phrase_password(self.generator.words.unwrap_or(4))
+    /// Get the number of generated passwords that were not saved to a file
+    pub fn unsaved_results(&self) -> usize {
+        if self.results_saved {
+            0
+        } else {
+            self.generated_passwords.len()
+        }
+    }
+
+    /// Leave the TUI once save-and-exit wrote the passwords, a failed save stays on the prompt
+    fn exit_if_saved(&mut self) -> bool {
+        self.exit.exit = self.results_saved;
+        self.exit.exit
+    }
+
+    /// Build a generator for the current mode and parameters
+    ///
+    /// # Returns
+    ///
+    /// Returns the generator, None with `error_message` set if a required parameter is missing
+    fn password_generator(&mut self) -> Option<PasswordGenerator> {
+        let request = match self.generator.mode {
+            PasswordMode::Random => PasswordRequest::random().length(self.generator.length),
+            PasswordMode::Pattern => {
+                if self.generator.pattern.is_empty() {
+                    self.error_message = Some(tr("tui.generator.pattern_required", &[]));
+                    return None;
                 }
-                PasswordMode::Deterministic => {
-                    if self.generator.seed_env.is_empty() {
-                        self.error_message =
-                            Some("Seed environment variable is required".to_string());
-                        return;
-                    }
-                    let seed = std::env::var(&self.generator.seed_env).unwrap_or_default();
-                    if seed.is_empty() {
-                        self.error_message = Some(format!(
-                            "Environment variable '{}' not found",
-                            self.generator.seed_env
-                        ));
-                        return;
-                    }
-                    Generator::generate_deterministic_password(
-                        &seed,
-                        if self.generator.salt.is_empty() {
-                            None
-                        } else {
-                            Some(&self.generator.salt)
-                        },
-                        if self.generator.service.is_empty() {
-                            None
-                        } else {
-                            Some(&self.generator.service)
-                        },
+                PasswordRequest::pattern(self.generator.pattern.value())
+            }
+            PasswordMode::Phrase => PasswordRequest::phrase(self.generator.words.unwrap_or(4))
+                .phrase_options(self.generator.phrase),
+            PasswordMode::Deterministic => {
+                let seed = if !self.generator.seed.is_empty() {
+                    Zeroizing::new(self.generator.seed.value().to_string())
+                } else if self.generator.seed_env.is_empty() {
+                    self.error_message = Some(tr("tui.generator.seed_required", &[]));
+                    return None;
+                } else {
+                    Zeroizing::new(
+                        std::env::var(self.generator.seed_env.value()).unwrap_or_default(),
                     )
+                };
+                if seed.is_empty() {
+                    self.error_message = Some(tr(
+                        "tui.generator.seed_env_missing",
+                        &[("name", self.generator.seed_env.value())],
+                    ));
+                    return None;
                 }
-            };
-
-            match result {
-                Ok(password) => self.generated_passwords.push(password),
-                Err(e) => {
-                    self.error_message = Some(format!("Error: {}", e));
-                    return;
+                let mut request = PasswordRequest::deterministic(&seed)
+                    .kdf(self.settings.saved.deterministic.clone());
+                if !self.generator.salt.is_empty() {
+                    request = request.salt(self.generator.salt.value());
                 }
+                if !self.generator.service.is_empty() {
+                    request = request.service(self.generator.service.value());
+                }
+                request
             }
-        }
-    }
+        };
 
-    pub fn check_password(&mut self) {
-        self.check_result = Some(HealthCheck::analyze_password(&self.password_input));
+        Some(Box::new(move || {
+            request.build().map(|generated| generated.password)
+        }))
     }
 
-    pub fn save_settings(&mut self) {
-        if let Err(e) = Config::update_config(
-            Some(self.settings.language.clone()),
-            Some(self.settings.default_length),
-            Some(self.settings.default_count),
-            Some(self.settings.auto_save),
-        ) {
-            self.error_message = Some(format!("Failed to save settings: {}", e));
-        } else {
-            self.error_message = None;
+    pub fn generate_passwords(&mut self) {
+        if self.generation.is_some() {
+            return;
         }
+
+        self.error_message = None;
+
+        let Some(generate) = self.password_generator() else {
+            return;
+        };
+
+        let count = self.generator.count;
+        let cancellation = Cancellation::new();
+        let thread_cancellation = cancellation.clone();
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let progress_sender = sender.clone();
+            let result = Generator::generate_bulk(
+                count,
+                &mut |progress: Progress| {
+                    let _ = progress_sender.send(GenerationMessage::Progress(progress));
+                },
+                &thread_cancellation,
+                generate,
+            )
+            .map(|(passwords, outcome)| {
+                let rated = passwords.into_iter().map(GeneratedPassword::rate).collect();
+                (rated, outcome)
+            })
+            .map_err(|e| e.to_string());
+            let _ = sender.send(GenerationMessage::Finished(result));
+        });
+
+        self.generation = Some(GenerationJob {
+            receiver,
+            cancellation,
+            mode: self.generator.mode.clone(),
+            parameters: self.generation_parameters(),
+            progress: Progress {
+                completed: 0,
+                total: count,
+                elapsed: Duration::ZERO,
+            },
+        });
+    }
+
+    /// Validate and apply the typed value of a numeric field
+    ///
+    /// Returns false and sets an error message if the value is out of range.
+    fn apply_numeric_input(&mut self, field: GeneratorField) -> bool {
+        let (key, range) = match field {
+            GeneratorField::Length => ("length", Generator::LENGTH_RANGE),
+            GeneratorField::Count => ("count", COUNT_RANGE),
+            GeneratorField::PhraseDigits => ("digits", Generator::PHRASE_INSERT_RANGE),
+            GeneratorField::PhraseSymbols => ("symbols", Generator::PHRASE_INSERT_RANGE),
+            _ => ("words", Generator::WORDS_RANGE),
+        };
+
+        let value = match self.generator.numeric_input.value().parse::<usize>() {
+            Ok(value) if range.contains(&value) => value,
+            _ => {
+                self.error_message = Some(tr(
+                    "tui.generator.out_of_range",
+                    &[
+                        (
+                            "name",
+                            &tr(&format!("tui.generator.field_names.{}", key), &[]),
+                        ),
+                        ("min", &range.start().to_string()),
+                        ("max", &range.end().to_string()),
+                    ],
+                ));
+                return false;
+            }
+        };
+
+        match field {
+            GeneratorField::Length => self.generator.length = value,
+            GeneratorField::Count => self.generator.count = value,
+            GeneratorField::PhraseDigits => self.generator.phrase.digits = value,
+            GeneratorField::PhraseSymbols => self.generator.phrase.symbols = value,
+            _ => self.generator.words = Some(value),
+        }
+        self.generator.numeric_input.clear();
+        self.error_message = None;
+        true
+    }
+
+    fn handle_results_input(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Up => {
+                self.generator.selected_result = self.generator.selected_result.saturating_sub(1);
+            }
+            KeyCode::Down
+                if self.generator.selected_result + 1 < self.generated_passwords.len() =>
+            {
+                self.generator.selected_result += 1;
+            }
+            KeyCode::PageUp => {
+                self.generator.selected_result = self
+                    .generator
+                    .selected_result
+                    .saturating_sub(self.generator.results_page);
+            }
+            KeyCode::PageDown => {
+                self.generator.selected_result = (self.generator.selected_result
+                    + self.generator.results_page)
+                    .min(self.generated_passwords.len().saturating_sub(1));
+            }
+            KeyCode::Home => self.generator.selected_result = 0,
+            KeyCode::End => {
+                self.generator.selected_result = self.generated_passwords.len().saturating_sub(1);
+            }
+            KeyCode::Char('c') => self.copy_selected_password(),
+            KeyCode::Char('v') => self.generator.hide_results = !self.generator.hide_results,
+            KeyCode::Char('r') => self.regenerate_selected_password(),
+            KeyCode::Char('x') => self.remove_selected_password(),
+            KeyCode::Esc => self.generator.focus = GeneratorFocus::Parameters,
+            _ => {}
+        }
+        false
+    }
+
+    /// Replace the highlighted generated password with a new one
+    ///
+    /// Deterministic passwords would come out identical, so they are left unchanged.
+    pub fn regenerate_selected_password(&mut self) {
+        let index = self.generator.selected_result;
+        if index >= self.generated_passwords.len() {
+            return;
+        }
+        if self.generator.mode == PasswordMode::Deterministic {
+            self.set_status(&tr("tui.generator.deterministic_same", &[]));
+            return;
+        }
+
+        let Some(mut generate) = self.password_generator() else {
+            return;
+        };
+        match generate() {
+            Ok(password) => {
+                self.record_history(
+                    std::slice::from_ref(&password),
+                    &self.generator.mode.clone(),
+                    &self.generation_parameters(),
+                );
+                self.generated_passwords[index] = GeneratedPassword::rate(password);
+                self.results_saved = false;
+                self.error_message = None;
+            }
+            Err(e) => self.error_message = Some(e.to_string()),
+        }
+    }
+
+    /// Remove the highlighted generated password from the results
+    pub fn remove_selected_password(&mut self) {
+        if self.generator.selected_result >= self.generated_passwords.len() {
+            return;
+        }
+
+        self.generated_passwords
+            .remove(self.generator.selected_result);
+        if self.generated_passwords.is_empty() {
+            self.generator.selected_result = 0;
+            self.generator.focus = GeneratorFocus::Parameters;
+        } else {
+            self.generator.selected_result = self
+                .generator
+                .selected_result
+                .min(self.generated_passwords.len() - 1);
+        }
+    }
+
+    /// Copy the highlighted generated password to the system clipboard
+    pub fn copy_selected_password(&mut self) {
+        if let Some(generated) = self.generated_passwords.get(self.generator.selected_result) {
+            let password = generated.password.clone();
+            self.copy_to_clipboard(password);
+        }
+    }
+
+    /// Copy text to the system clipboard and report the outcome in the footer
+    fn copy_to_clipboard(&mut self, password: String) {
+        let result = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(password),
+            None => arboard::Clipboard::new().and_then(|mut clipboard| {
+                let result = clipboard.set_text(password);
+                // The clipboard must outlive the copy on X11, so keep it for the session
+                self.clipboard = Some(clipboard);
+                result
+            }),
+        };
+
+        match result {
+            Ok(()) => {
+                self.error_message = None;
+                self.set_status(&tr("tui.common.copied", &[]));
+            }
+            Err(e) => {
+                self.error_message = Some(tr(
+                    "tui.common.clipboard_error",
+                    &[("error", &e.to_string())],
+                ))
+            }
+        }
+    }
+
+    /// Get the default file name for saving generated passwords
+    fn default_save_filename() -> String {
+        format!("kdguard_{}.txt", Local::now().format("%Y%m%d_%H%M%S"))
+    }
+
+    /// Save the generated passwords, asking for confirmation if the file already exists
+    fn request_save(&mut self) {
+        if self.save_filename.value().trim().is_empty() {
+            self.error_message = Some(tr("tui.generator.filename_required", &[]));
+            return;
+        }
+
+        if Path::new(self.save_filename.value().trim()).exists() {
+            self.pending_overwrite = true;
+        } else {
+            self.save_passwords();
+        }
+    }
+
+    /// Save the generated passwords to `save_filename`
+    fn save_passwords(&mut self) {
+        let path = self.save_filename.value().trim().to_string();
+        let passwords = self
+            .generated_passwords
+            .iter()
+            .map(|generated| generated.password.clone())
+            .collect();
+        let metadata = self.generation_metadata();
+        match Generator::save_to_file(passwords, Path::new(&path), &metadata) {
+            Ok(()) => {
+                self.error_message = None;
+                self.results_saved = true;
+                self.set_status(&tr(
+                    "tui.generator.saved",
+                    &[
+                        ("count", &self.generated_passwords.len().to_string()),
+                        ("path", &path),
+                    ],
+                ));
+            }
+            Err(e) => self.error_message = Some(e.to_string()),
+        }
+    }
+
+    /// Describe the generated passwords for a saved file
+    ///
+    /// Seeds are left out, typed or not.
+    fn generation_metadata(&self) -> GenerationMetadata {
+        let mut metadata =
+            GenerationMetadata::new(&self.generator.mode, self.generated_passwords.len());
+        match self.generator.mode {
+            PasswordMode::Random => metadata.length = Some(self.generator.length),
+            PasswordMode::Pattern => {
+                metadata.pattern = Some(self.generator.pattern.value().to_string())
+            }
+            PasswordMode::Phrase => metadata.words = Some(self.generator.words.unwrap_or(4)),
+            PasswordMode::Deterministic => {
+                metadata.length = Some(DETERMINISTIC_LENGTH);
+                metadata.service = (!self.generator.service.is_empty())
+                    .then(|| self.generator.service.value().to_string());
+            }
+        }
+        metadata
+    }
+
+    /// Show a transient message in the footer
+    pub fn set_status(&mut self, text: &str) {
+        self.status_message = Some(StatusMessage {
+            text: text.to_string(),
+            created: Instant::now(),
+        });
+    }
+
+    /// Get the status message if it has not expired yet
+    pub fn status(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|status| status.created.elapsed() < STATUS_MESSAGE_DURATION)
+            .map(|status| status.text.as_str())
+    }
+
+    /// Advance time-based state, called once per frame
+    pub fn tick(&mut self) {
+        self.poll_generation();
+        self.poll_updates();
+        self.poll_breach_check();
+
+        if let Some(since) = self.check_pending_since
+            && since.elapsed() >= CHECK_DEBOUNCE
+        {
+            self.check_pending_since = None;
+            if self.password_input.is_empty() {
+                self.check_result = None;
+            } else {
+                self.check_password();
+            }
+        }
+    }
+
+    /// Analyze the check input once typing pauses
+    ///
+    /// The breach result belongs to the previous input, so it is dropped right away.
+    fn schedule_check(&mut self) {
+        self.check_pending_since = Some(Instant::now());
+        self.reset_breach_check();
+    }
+
+    /// Look up the check input in known breaches without blocking the UI
+    ///
+    /// Only the hash is handed to the worker thread, which runs the request on its own
+    /// runtime and sends the result back to be picked up by `poll_breach_check`.
+    pub fn start_breach_check(&mut self) {
+        if self.password_input.is_empty() || self.breach_check.is_some() {
+            return;
+        }
+
+        let hash = BreachCheck::hash(self.password_input.value());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| {
+                    tr(
+                        "tui.check.breach_start_failed",
+                        &[("error", &e.to_string())],
+                    )
+                })
+                .and_then(|runtime| {
+                    runtime
+                        .block_on(BreachCheck::lookup(&hash))
+                        .map_err(|e| e.to_string())
+                });
+            let _ = sender.send(result);
+        });
+
+        self.breach_result = None;
+        self.breach_check = Some(BreachJob {
+            receiver,
+            started: Instant::now(),
+        });
+    }
+
+    /// Forget the breach lookup of the check input, a running one is left to finish unseen
+    fn reset_breach_check(&mut self) {
+        self.breach_check = None;
+        self.breach_result = None;
+    }
+
+    /// Pick up the result of the running breach lookup, if it arrived
+    pub fn poll_breach_check(&mut self) {
+        let Some(job) = &self.breach_check else {
+            return;
+        };
+
+        match job.receiver.try_recv() {
+            Ok(result) => {
+                self.breach_result = Some(result);
+                self.breach_check = None;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.breach_result = Some(Err(tr("tui.check.breach_no_result", &[])));
+                self.breach_check = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    /// Pick up the result of the background update check, if it arrived
+    pub fn poll_updates(&mut self) {
+        let Some(receiver) = &self.updates else {
+            return;
+        };
+
+        match receiver.try_recv() {
+            Ok(status) => {
+                self.update_status = status;
+                self.updates = None;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.update_status = UpdateStatus::Failed(tr("tui.about.update_no_result", &[]));
+                self.updates = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    /// Get the release tag of a newer version found by the update check
+    pub fn available_update(&self) -> Option<&str> {
+        match &self.update_status {
+            UpdateStatus::Available(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// Apply updates of the running background generation, if any
+    pub fn poll_generation(&mut self) {
+        let Some(job) = &mut self.generation else {
+            return;
+        };
+
+        let mut finished = None;
+        while let Ok(message) = job.receiver.try_recv() {
+            match message {
+                GenerationMessage::Progress(progress) => job.progress = progress,
+                GenerationMessage::Finished(result) => finished = Some(result),
+            }
+        }
+
+        let Some(result) = finished else {
+            return;
+        };
+        let Some(job) = self.generation.take() else {
+            return;
+        };
+
+        match result {
+            Ok((passwords, outcome)) => {
+                let plain: Vec<String> = passwords.iter().map(|p| p.password.clone()).collect();
+                self.record_history(&plain, &job.mode, &job.parameters);
+                let replaced = std::mem::replace(&mut self.generated_passwords, passwords);
+                // The quick dialog works on a stash of the generator results, see `open_quick_generate`
+                if self.quick_generate.is_none() {
+                    self.results_saved = false;
+                    if !replaced.is_empty() {
+                        self.previous_passwords = replaced;
+                    }
+                }
+                self.generator.selected_result = 0;
+                self.generator.results_offset = 0;
+                self.generator.focus = GeneratorFocus::Parameters;
+                // Like the CLI, saved passwords stay off the screen until shown
+                self.generator.hide_results = self.settings.auto_save
+                    && self.quick_generate.is_none()
+                    && !self.generated_passwords.is_empty();
+                if self.generator.hide_results {
+                    self.save_filename.set(&Self::default_save_filename());
+                    self.save_passwords();
+                }
+                if outcome.cancelled {
+                    self.error_message = Some(tr(
+                        "tui.generator.cancelled",
+                        &[
+                            ("completed", &outcome.completed.to_string()),
+                            ("total", &outcome.total.to_string()),
+                        ],
+                    ));
+                }
+            }
+            Err(e) => self.error_message = Some(e.to_string()),
+        }
+    }
+
+    /// Check whether `u` on the generator screen has a previous batch to bring back
+    pub fn can_restore_previous(&self) -> bool {
+        matches!(self.current_screen, CurrentScreen::Generator)
+            && self.quick_generate.is_none()
+            && !self.previous_passwords.is_empty()
+    }
+
+    /// Swap the shown results with the batch of the generation before
+    ///
+    /// Only one level is kept, so pressing `u` again returns to the newer batch.
+    pub fn restore_previous_passwords(&mut self) {
+        if self.previous_passwords.is_empty() {
+            return;
+        }
+        std::mem::swap(&mut self.generated_passwords, &mut self.previous_passwords);
+        self.results_saved = false;
+        self.generator.selected_result = 0;
+        self.generator.results_offset = 0;
+        self.generator.hide_results = false;
+        self.error_message = None;
+        self.set_status(&tr("tui.generator.restored", &[]));
+    }
+
+    pub fn check_password(&mut self) {
+        self.check_result = Some(HealthCheck::analyze_password(self.password_input.value()));
+    }
+
+    /// Save the settings to the config file and switch to the chosen language
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the settings were saved, otherwise the error is shown in the footer
+    pub fn save_settings(&mut self) -> bool {
+        if !self.settings.languages.contains(&self.settings.language) {
+            self.error_message = Some(tr(
+                "tui.settings.unknown_language",
+                &[("language", &self.settings.language)],
+            ));
+            return false;
+        }
+
+        let default_mode = self
+            .settings
+            .default_mode
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+        if let Err(e) = Config::update_config(ConfigUpdate {
+            lang: Some(self.settings.language.clone()),
+            password_length: Some(self.settings.default_length),
+            count: Some(self.settings.default_count),
+            auto_save: Some(self.settings.auto_save),
+            default_mode: Some(default_mode.clone()),
+            theme: Some(self.settings.theme.clone()),
+            ..Default::default()
+        }) {
+            self.error_message = Some(tr("tui.settings.save_failed", &[("error", &e.to_string())]));
+            return false;
+        }
+
+        let saved = &mut self.settings.saved;
+        saved.language.lang = self.settings.language.clone();
+        saved.general.default_mode = default_mode;
+        saved.general.default_length = self.settings.default_length;
+        saved.general.default_count = self.settings.default_count;
+        saved.general.auto_save = self.settings.auto_save;
+        saved.theme.set_preset(&self.settings.theme);
+        self.theme = Theme::from_config(&saved.theme);
+
+        if let Err(e) = Lingua::set_language(&self.settings.language) {
+            self.error_message = Some(tr(
+                "tui.settings.language_failed",
+                &[("error", &e.to_string())],
+            ));
+            return false;
+        }
+
+        self.error_message = None;
+        self.set_status(&tr("tui.settings.saved", &[]));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{KeyBindingsConfig, KeyList, ThemeConfig};
+    use std::collections::BTreeMap;
+
+    /// Build unrated results, so tests don't depend on the language
+    fn results<S: AsRef<str>>(passwords: &[S]) -> Vec<GeneratedPassword> {
+        passwords
+            .iter()
+            .map(|password| GeneratedPassword {
+                password: password.as_ref().to_string(),
+                score: 0,
+                rating: String::new(),
+                entropy: 0.0,
+            })
+            .collect()
+    }
+
+    fn generator_app(mode: PasswordMode) -> App {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Generator;
+        app.generator.mode = mode;
+        app.generator.length = 16;
+        app.generator.count = 5;
+        app
+    }
+
+    #[test]
+    fn test_live_check_is_debounced() {
+        crate::password::health_check::tests::init_lingua_for_tests();
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Check;
+        app.input_mode = InputMode::Editing;
+        app.input_field = InputField::PasswordCheck;
+
+        for c in "Xy9$mK2@".chars() {
+            app.handle_input(KeyCode::Char(c));
+        }
+        app.tick();
+        assert!(app.check_result.is_none());
+
+        app.check_pending_since = Some(Instant::now() - CHECK_DEBOUNCE);
+        app.tick();
+        assert_eq!(app.check_result.as_ref().map(|a| a.length), Some(8));
+        assert!(app.check_pending_since.is_none());
+
+        app.handle_input(KeyCode::Backspace);
+        app.check_pending_since = Some(Instant::now() - CHECK_DEBOUNCE);
+        app.tick();
+        assert_eq!(app.check_result.as_ref().map(|a| a.length), Some(7));
+    }
+
+    #[test]
+    fn test_check_input_edits_at_cursor() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Check;
+        app.handle_input(KeyCode::Char('i'));
+        app.handle_paste("pasword");
+
+        for _ in 0..4 {
+            app.handle_input(KeyCode::Left);
+        }
+        app.handle_input(KeyCode::Char('s'));
+        assert_eq!(app.password_input.value(), "password");
+
+        app.handle_input(KeyCode::Home);
+        app.handle_input(KeyCode::Delete);
+        app.handle_input(KeyCode::End);
+        app.handle_input(KeyCode::Char('!'));
+        assert_eq!(app.password_input.value(), "assword!");
+        assert!(app.check_pending_since.is_some());
+
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.check_result.as_ref().map(|a| a.length), Some(8));
+    }
+
+    #[test]
+    fn test_check_screen_opens_in_normal_mode() {
+        let mut app = App::new();
+        app.selected_index = 2;
+        app.handle_input(KeyCode::Enter);
+        assert!(matches!(app.current_screen, CurrentScreen::Check));
+        assert!(app.input_mode == InputMode::Normal);
+
+        // A single Esc leaves the screen
+        app.handle_input(KeyCode::Esc);
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert_eq!(app.selected_index, 2);
+
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Enter);
+        assert!(app.input_mode == InputMode::Editing);
+        // While typing, shortcut letters are part of the password
+        app.handle_input(KeyCode::Char('q'));
+        app.handle_input(KeyCode::Char('v'));
+        assert_eq!(app.password_input.value(), "qv");
+        assert!(app.password_input.masked);
+
+        // Esc only stops typing, the second one leaves the screen
+        app.handle_input(KeyCode::Esc);
+        assert!(matches!(app.current_screen, CurrentScreen::Check));
+        assert!(app.input_mode == InputMode::Normal);
+        assert_eq!(app.password_input.value(), "qv");
+        app.handle_input(KeyCode::Esc);
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert!(app.password_input.is_empty());
+
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Char('i'));
+        assert!(app.input_mode == InputMode::Editing);
+        assert!(app.input_field == InputField::PasswordCheck);
+    }
+
+    #[test]
+    fn test_paste_into_editing_field() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Check;
+        app.handle_paste("ignored");
+        assert!(app.password_input.is_empty());
+
+        app.input_mode = InputMode::Editing;
+        app.input_field = InputField::PasswordCheck;
+        app.handle_paste("Tr0ub4dor&3\tcorrect-horse-battery\r\n");
+        assert_eq!(
+            app.password_input.value(),
+            "Tr0ub4dor&3correct-horse-battery"
+        );
+        assert!(app.check_pending_since.is_some());
+
+        let mut app = generator_app(PasswordMode::Deterministic);
+        app.input_mode = InputMode::Editing;
+        app.input_field = InputField::Generator(GeneratorField::Service);
+        app.handle_paste("github.com\n");
+        assert_eq!(app.generator.service.value(), "github.com");
+    }
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: ratatui::crossterm::event::KeyModifiers::NONE,
+        }
+    }
+
+    fn scroll(kind: MouseEventKind) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column: 10,
+            row: 10,
+            modifiers: ratatui::crossterm::event::KeyModifiers::NONE,
+        }
+    }
+
+    const TERMINAL: Rect = Rect::new(0, 0, 80, 24);
+
+    #[test]
+    fn test_results_pane_actions() {
+        crate::password::health_check::tests::init_lingua_for_tests();
+        let mut app = generator_app(PasswordMode::Random);
+        app.handle_input(KeyCode::Tab);
+        assert!(app.generator.focus == GeneratorFocus::Parameters);
+
+        app.generated_passwords = results(&["first", "second", "third"]);
+        app.handle_input(KeyCode::Tab);
+        assert!(app.generator.focus == GeneratorFocus::Results);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.generator.selected_result, 1);
+
+        app.handle_input(KeyCode::Char('r'));
+        let regenerated = &app.generated_passwords[1];
+        assert_ne!(regenerated.password, "second");
+        assert_eq!(regenerated.password.len(), 16);
+        assert!(!regenerated.rating.is_empty());
+        assert_eq!(app.generated_passwords[0].password, "first");
+        assert_eq!(app.generated_passwords[2].password, "third");
+        assert_eq!(app.history.entries.len(), 1);
+
+        app.handle_input(KeyCode::Down);
+        app.handle_input(KeyCode::Char('x'));
+        assert_eq!(app.generated_passwords.len(), 2);
+        assert_eq!(app.generator.selected_result, 1);
+        app.handle_input(KeyCode::Char('x'));
+        app.handle_input(KeyCode::Char('x'));
+        assert!(app.generated_passwords.is_empty());
+        assert!(app.generator.focus == GeneratorFocus::Parameters);
+
+        app.generated_passwords = results(&["first"]);
+        app.generator.focus = GeneratorFocus::Results;
+        app.handle_input(KeyCode::Esc);
+        assert!(app.generator.focus == GeneratorFocus::Parameters);
+    }
+
+    #[test]
+    fn test_confirming_an_edit_clears_the_editing_field() {
+        let mut app = generator_app(PasswordMode::Deterministic);
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.editing_field, Some(GeneratorField::SeedEnv));
+        app.handle_input(KeyCode::Char('S'));
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.seed_env.value(), "S");
+        assert_eq!(app.generator.editing_field, None);
+        assert!(app.input_mode == InputMode::Normal);
+
+        app.selected_index = 4;
+        app.handle_input(KeyCode::Char('e'));
+        assert_eq!(app.generator.editing_field, Some(GeneratorField::Count));
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.editing_field, None);
+        assert!(app.generator.numeric_input.is_empty());
+    }
+
+    #[test]
+    fn test_typed_seed_is_used_and_wiped() {
+        let mut app = generator_app(PasswordMode::Deterministic);
+        app.generator.seed_env.set("KDGUARD_TEST_UNSET_SEED");
+        app.selected_index = 1;
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.editing_field, Some(GeneratorField::Seed));
+        app.handle_paste("correct horse battery staple");
+        app.handle_input(KeyCode::Backspace);
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.seed.value(), "correct horse battery stapl");
+
+        // The typed seed wins over the unset environment variable
+        let mut generate = app.password_generator().unwrap();
+        assert_eq!(
+            generate().unwrap(),
+            Generator::generate_deterministic_password(
+                "correct horse battery stapl",
+                None,
+                None,
+                &crate::config::DeterministicConfig::default()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            app.generation_parameters(),
+            format!(
+                "typed seed, fingerprint {}",
+                Generator::seed_fingerprint(
+                    "correct horse battery stapl",
+                    None,
+                    &app.settings.saved.deterministic
+                )
+                .unwrap()
+            )
+        );
+
+        app.handle_input(KeyCode::Esc);
+        assert!(matches!(
+            app.current_screen,
+            CurrentScreen::GeneratorModeSelection
+        ));
+        assert!(app.generator.seed.is_empty());
+        assert!(app.password_generator().is_none());
+    }
+
+    #[test]
+    fn test_toggle_seed_visibility() {
+        let mut app = generator_app(PasswordMode::Deterministic);
+        assert!(!app.generator.show_seed);
+        app.handle_input(KeyCode::Char('v'));
+        assert!(app.generator.show_seed);
+        app.handle_input(KeyCode::Char('v'));
+        assert!(!app.generator.show_seed);
+
+        // While editing, v is typed into the field
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Char('v'));
+        assert_eq!(app.generator.seed_env.value(), "v");
+        assert!(!app.generator.show_seed);
+
+        let mut app = generator_app(PasswordMode::Random);
+        app.handle_input(KeyCode::Char('v'));
+        assert!(!app.generator.show_seed);
+    }
+
+    #[test]
+    fn test_load_preset_from_picker() {
+        let mut app = generator_app(PasswordMode::Random);
+        app.settings.saved.presets = BTreeMap::from([
+            (
+                "github".to_string(),
+                Preset {
+                    mode: "deterministic".to_string(),
+                    seed_env: Some("KDGUARD_SEED".to_string()),
+                    service: Some("github".to_string()),
+                    count: Some(1),
+                    ..Preset::default()
+                },
+            ),
+            (
+                "phrase".to_string(),
+                Preset {
+                    mode: "phrase".to_string(),
+                    words: Some(6),
+                    ..Preset::default()
+                },
+            ),
+        ]);
+
+        app.handle_input(KeyCode::Char('p'));
+        assert_eq!(app.preset_picker, Some(0));
+        app.handle_input(KeyCode::Down);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.preset_picker, Some(1));
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.preset_picker, None);
+        assert!(app.generator.mode == PasswordMode::Phrase);
+        assert_eq!(app.generator.words, Some(6));
+        assert_eq!(app.generator.count, 5);
+
+        app.handle_input(KeyCode::Char('p'));
+        app.handle_input(KeyCode::Enter);
+        assert!(app.generator.mode == PasswordMode::Deterministic);
+        assert_eq!(app.generator.seed_env.value(), "KDGUARD_SEED");
+        assert_eq!(app.generator.service.value(), "github");
+        assert_eq!(app.generator.count, 1);
+        assert_eq!(app.current_preset(), app.settings.saved.presets["github"]);
+
+        app.handle_input(KeyCode::Char('p'));
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(app.preset_picker, None);
+        assert!(matches!(app.current_screen, CurrentScreen::Generator));
+    }
+
+    #[test]
+    fn test_current_preset_only_sets_mode_fields() {
+        let mut app = generator_app(PasswordMode::Random);
+        app.generator.pattern.set("ULDD");
+        app.generator.service.set("github");
+        let preset = app.current_preset();
+        assert_eq!(preset.mode, "random");
+        assert_eq!(preset.length, Some(16));
+        assert!(preset.pattern.is_none());
+        assert!(preset.service.is_none());
+        assert!(preset.validate().is_ok());
+
+        app.set_generator_mode(PasswordMode::Pattern);
+        let preset = app.current_preset();
+        assert_eq!(preset.pattern.as_deref(), Some("ULDD"));
+        assert!(preset.length.is_none());
+        assert!(preset.validate().is_ok());
+
+        // An empty pattern is rejected before anything is written
+        app.generator.pattern.clear();
+        app.handle_input(KeyCode::Char('S'));
+        assert!(app.input_field == InputField::PresetName);
+        app.handle_paste("pattern");
+        app.handle_input(KeyCode::Enter);
+        assert!(app.error_message.is_some());
+        assert!(!app.settings.saved.presets.contains_key("pattern"));
+    }
+
+    #[test]
+    fn test_regenerate_keeps_deterministic_passwords() {
+        let mut app = generator_app(PasswordMode::Deterministic);
+        app.generated_passwords = results(&["derived"]);
+        app.generator.focus = GeneratorFocus::Results;
+        app.handle_input(KeyCode::Char('r'));
+        assert_eq!(app.generated_passwords[0].password, "derived");
+        assert!(app.status().is_some());
+    }
+
+    #[test]
+    fn test_click_main_menu_item() {
+        let mut app = App::new();
+        // Title takes three rows, the menu border one more
+        app.handle_mouse(click(10, 6), TERMINAL);
+        assert!(matches!(app.current_screen, CurrentScreen::Check));
+
+        let mut app = App::new();
+        app.handle_mouse(click(0, 5), TERMINAL);
+        app.handle_mouse(click(10, 15), TERMINAL);
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+
+        app.handle_mouse(click(10, 4), TERMINAL);
+        assert!(matches!(
+            app.current_screen,
+            CurrentScreen::GeneratorModeSelection
+        ));
+        app.handle_mouse(click(10, 6), TERMINAL);
+        assert!(matches!(app.current_screen, CurrentScreen::Generator));
+        assert!(app.generator.mode == PasswordMode::Phrase);
+    }
+
+    #[test]
+    fn test_click_generator_rows() {
+        let mut app = generator_app(PasswordMode::Random);
+        let passwords: Vec<String> = (0..30).map(|i| format!("password{}", i)).collect();
+        app.generated_passwords = results(&passwords);
+
+        app.handle_mouse(click(10, 5), TERMINAL);
+        assert_eq!(app.selected_row(), Some(GeneratorField::Count));
+
+        // The results pane takes the right 60% of the body
+        app.handle_mouse(click(40, 4), TERMINAL);
+        assert!(app.generator.focus == GeneratorFocus::Results);
+        assert_eq!(app.generator.selected_result, 0);
+        app.handle_mouse(click(10, 4), TERMINAL);
+        assert!(app.generator.focus == GeneratorFocus::Parameters);
+        assert_eq!(app.selected_row(), Some(GeneratorField::Length));
+
+        // Clicks map through the offset of the last rendered frame, scrolled to the end here.
+        // The status bar takes the last line, leaving 15 visible results
+        app.generator.selected_result = 29;
+        app.set_results_viewport(15, 15);
+        app.handle_mouse(click(40, 18), TERMINAL);
+        assert_eq!(app.generator.selected_result, 29);
+        app.handle_mouse(click(40, 17), TERMINAL);
+        assert_eq!(app.generator.selected_result, 28);
+    }
+
+    #[test]
+    fn test_update_notice_and_dialog() {
+        let mut app = App::new();
+        let (sender, receiver) = mpsc::channel();
+        app.updates = Some(receiver);
+
+        app.tick();
+        assert!(app.available_update().is_none());
+        assert!(app.updates.is_some());
+
+        // Without an update U is handled by the screen as usual
+        app.handle_input(KeyCode::Char('U'));
+        assert!(!app.show_update_dialog);
+
+        sender
+            .send(UpdateStatus::Available("v9.9.9".to_string()))
+            .unwrap();
+        app.tick();
+        assert_eq!(app.available_update(), Some("v9.9.9"));
+        assert!(app.updates.is_none());
+
+        app.handle_input(KeyCode::Char('U'));
+        assert!(app.show_update_dialog);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_index, 0);
+        app.handle_input(KeyCode::Esc);
+        assert!(!app.show_update_dialog);
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+
+        // A finished check without an update closes the channel
+        let mut app = App::new();
+        let (sender, receiver) = mpsc::channel();
+        app.updates = Some(receiver);
+        sender.send(UpdateStatus::UpToDate).unwrap();
+        app.tick();
+        assert!(app.updates.is_none());
+        assert_eq!(app.update_status, UpdateStatus::UpToDate);
+        assert!(app.available_update().is_none());
+
+        // A check that ends without sending a result counts as failed
+        let mut app = App::new();
+        let (sender, receiver) = mpsc::channel::<UpdateStatus>();
+        app.updates = Some(receiver);
+        drop(sender);
+        app.tick();
+        assert!(app.updates.is_none());
+        assert!(matches!(app.update_status, UpdateStatus::Failed(_)));
+    }
+
+    #[test]
+    fn test_key_bindings_and_cheat_sheet() {
+        let mut app = App::new();
+        app.keymap = KeyMap::from_config(&KeyBindingsConfig {
+            preset: Some("vim".to_string()),
+            quit: Some(KeyList::One("x".to_string())),
+            ..KeyBindingsConfig::default()
+        })
+        .unwrap();
+
+        app.handle_input(KeyCode::Char('j'));
+        assert_eq!(app.selected_index, 1);
+        app.handle_input(KeyCode::Char('k'));
+        assert_eq!(app.selected_index, 0);
+
+        app.handle_input(KeyCode::Char('?'));
+        assert!(app.show_keymap);
+        // Any key closes the cheat sheet without reaching the screen
+        app.handle_input(KeyCode::Char('j'));
+        assert!(!app.show_keymap);
+        assert_eq!(app.selected_index, 0);
+
+        // The replaced quit key is ignored, the new one opens the exit prompt
+        app.handle_input(KeyCode::Char('q'));
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        app.handle_input(KeyCode::Char('x'));
+        assert!(matches!(app.current_screen, CurrentScreen::Exit));
+
+        // The prompt answers with its own letters, so the vim 'h' does not cancel it
+        app.handle_input(KeyCode::Char('h'));
+        assert!(matches!(app.current_screen, CurrentScreen::Exit));
+        app.handle_input(KeyCode::Char('n'));
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+    }
+
+    #[test]
+    fn test_scroll_help_and_results() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Help;
+        app.set_help_viewport(40, 16);
+        app.handle_mouse(scroll(MouseEventKind::ScrollDown), TERMINAL);
+        app.handle_mouse(scroll(MouseEventKind::ScrollDown), TERMINAL);
+        app.handle_mouse(scroll(MouseEventKind::ScrollUp), TERMINAL);
+        assert_eq!(app.help.scroll, 1);
+
+        let mut app = generator_app(PasswordMode::Random);
+        app.handle_mouse(scroll(MouseEventKind::ScrollDown), TERMINAL);
+        assert!(app.generator.focus == GeneratorFocus::Parameters);
+
+        app.generated_passwords = results(&["a", "b"]);
+        app.handle_mouse(scroll(MouseEventKind::ScrollDown), TERMINAL);
+        app.handle_mouse(scroll(MouseEventKind::ScrollDown), TERMINAL);
+        assert!(app.generator.focus == GeneratorFocus::Results);
+        assert_eq!(app.generator.selected_result, 1);
+    }
+
+    fn wait_for_generation(app: &mut App) {
+        while app.generation.is_some() {
+            std::thread::sleep(Duration::from_millis(5));
+            app.poll_generation();
+        }
+    }
+
+    #[test]
+    fn test_history_accumulates_generations() {
+        crate::password::health_check::tests::init_lingua_for_tests();
+        let mut app = generator_app(PasswordMode::Random);
+        app.settings.auto_save = false;
+        app.generator.count = 3;
+        app.generate_passwords();
+        wait_for_generation(&mut app);
+        app.generator.length = 20;
+        app.generate_passwords();
+        wait_for_generation(&mut app);
+
+        assert_eq!(app.generated_passwords.len(), 3);
+        assert_eq!(app.history.entries.len(), 6);
+        assert_eq!(app.history.entries[0].parameters, "length 16");
+        assert_eq!(app.history.entries[5].parameters, "length 20");
+        assert_eq!(
+            app.history.entries[5].password.as_str(),
+            app.generated_passwords[2].password
+        );
+    }
+
+    #[test]
+    fn test_failed_generation_keeps_results() {
+        crate::password::health_check::tests::init_lingua_for_tests();
+        let mut app = generator_app(PasswordMode::Random);
+        app.settings.auto_save = false;
+        app.generator.count = 2;
+        app.generate_passwords();
+        wait_for_generation(&mut app);
+        let first: Vec<String> = app
+            .generated_passwords
+            .iter()
+            .map(|p| p.password.clone())
+            .collect();
+
+        app.set_generator_mode(PasswordMode::Pattern);
+        app.generate_passwords();
+        assert!(app.generation.is_none());
+        assert_eq!(app.error_message.as_deref(), Some("Pattern is required"));
+        assert_eq!(app.generated_passwords.len(), 2);
+        assert_eq!(app.generated_passwords[0].password, first[0]);
+        assert!(app.previous_passwords.is_empty());
+
+        app.set_generator_mode(PasswordMode::Random);
+        app.generate_passwords();
+        wait_for_generation(&mut app);
+        assert_ne!(app.generated_passwords[0].password, first[0]);
+
+        app.handle_input(KeyCode::Char('u'));
+        assert_eq!(app.generated_passwords[0].password, first[0]);
+        assert_eq!(app.generated_passwords[1].password, first[1]);
+        app.handle_input(KeyCode::Char('u'));
+        assert_ne!(app.generated_passwords[0].password, first[0]);
+    }
+
+    #[test]
+    fn test_history_navigation_and_clear() {
+        let mut app = App::new();
+        app.record_history(
+            &["first".to_string(), "second".to_string()],
+            &PasswordMode::Random,
+            "length 16",
+        );
+
+        app.selected_index = 3;
+        app.handle_input(KeyCode::Enter);
+        assert!(matches!(app.current_screen, CurrentScreen::History));
+
+        app.handle_input(KeyCode::Down);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.history.selected, 1);
+
+        app.handle_input(KeyCode::Char('x'));
+        assert!(app.history.pending_clear);
+        app.handle_input(KeyCode::Char('n'));
+        assert_eq!(app.history.entries.len(), 2);
+
+        app.handle_input(KeyCode::Char('x'));
+        app.handle_input(KeyCode::Char('y'));
+        assert!(app.history.entries.is_empty());
+        assert_eq!(app.history.selected, 0);
+
+        app.handle_input(KeyCode::Esc);
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert_eq!(app.selected_index, 3);
+    }
+
+    fn settings_app() -> App {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Settings;
+        app.settings.languages = vec!["de".to_string(), "en".to_string()];
+        app.settings.language = "en".to_string();
+        app.settings.default_mode = PasswordMode::Random;
+        app.settings.saved.language.lang = "en".to_string();
+        app.settings.saved.general.default_mode = "random".to_string();
+        app.settings.saved.theme = ThemeConfig::default();
+        app.discard_settings();
+        app
+    }
+
+    #[test]
+    fn test_maintenance_actions_need_confirmation() {
+        let mut app = settings_app();
+        app.settings.selected_index = SettingsField::ROWS
+            .iter()
+            .position(|row| *row == SettingsField::ResetConfig)
+            .unwrap();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(
+            app.settings.pending_action,
+            Some(MaintenanceAction::ResetConfig)
+        );
+        // Other keys wait for the answer
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_setting(), Some(SettingsField::ResetConfig));
+        app.handle_input(KeyCode::Char('n'));
+        assert_eq!(app.settings.pending_action, None);
+
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_setting(), Some(SettingsField::Uninstall));
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(app.settings.pending_action, None);
+        assert!(!app.uninstall_requested);
+
+        app.handle_input(KeyCode::Enter);
+        assert!(app.handle_input(KeyCode::Char('y')));
+        assert!(app.uninstall_requested);
+        assert!(app.exit.exit);
+    }
+
+    #[test]
+    fn test_maintenance_error_dialog_closes_on_any_key() {
+        let mut app = settings_app();
+        app.settings.maintenance_error = Some("Failed to reset configuration".to_string());
+        app.handle_input(KeyCode::Char('x'));
+        assert_eq!(app.settings.maintenance_error, None);
+        assert!(matches!(app.current_screen, CurrentScreen::Settings));
+    }
+
+    #[test]
+    fn test_settings_language_picker() {
+        let mut app = settings_app();
+        assert_eq!(app.selected_setting(), Some(SettingsField::Language));
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.language, "de");
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.language, "en");
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.language, "de");
+
+        app.handle_input(KeyCode::Char('x'));
+        assert!(app.input_mode == InputMode::Normal);
+        assert_eq!(app.settings.language, "de");
+    }
+
+    #[test]
+    fn test_settings_default_mode_picker() {
+        let mut app = settings_app();
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_setting(), Some(SettingsField::DefaultMode));
+        app.handle_input(KeyCode::Right);
+        assert!(app.settings.default_mode == PasswordMode::Pattern);
+        app.handle_input(KeyCode::Left);
+        app.handle_input(KeyCode::Left);
+        assert!(app.settings.default_mode == PasswordMode::Deterministic);
+    }
+
+    #[test]
+    fn test_settings_arrows_adjust_numeric_rows() {
+        let mut app = settings_app();
+        app.settings.default_length = 16;
+        app.settings.default_count = 5;
+        app.settings.selected_index = 2;
+        assert_eq!(app.selected_setting_range(), Some(8..=64));
+
+        app.handle_input(KeyCode::Right);
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.default_length, 18);
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.default_length, 17);
+
+        app.settings.default_length = 64;
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.default_length, 64);
+        app.settings.default_length = 8;
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.default_length, 8);
+        // Values from an older config outside the range snap back into it
+        app.settings.default_length = 128;
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.default_length, 64);
+
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_setting_range(), Some(1..=100));
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.default_count, 6);
+        app.handle_input(KeyCode::Left);
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.default_count, 4);
+
+        app.settings.default_count = 100;
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.default_count, 100);
+        app.settings.default_count = 1;
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.default_count, 1);
+
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_setting_range(), None);
+    }
+
+    #[test]
+    fn test_settings_theme_picker() {
+        let mut app = settings_app();
+        app.settings.selected_index = 5;
+        assert_eq!(app.selected_setting(), Some(SettingsField::Theme));
+        assert_eq!(app.settings.theme, "dark");
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.settings.theme, "light");
+        assert!(app.settings_dirty());
+        app.handle_input(KeyCode::Left);
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.settings.theme, "high-contrast");
+    }
+
+    #[test]
+    fn test_settings_dirty_tracking() {
+        let mut app = settings_app();
+        assert!(!app.settings_dirty());
+        app.settings.selected_index = 4;
+        app.handle_input(KeyCode::Right);
+        assert!(app.settings_dirty());
+        app.handle_input(KeyCode::Right);
+        assert!(!app.settings_dirty());
+
+        app.handle_input(KeyCode::Esc);
+        assert!(!app.settings.pending_exit);
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert_eq!(app.selected_index, 4);
+    }
+
+    #[test]
+    fn test_unsaved_settings_cancel() {
+        let mut app = settings_app();
+        app.settings.default_count += 1;
+        app.handle_input(KeyCode::Esc);
+        assert!(app.settings.pending_exit);
+        assert!(matches!(app.current_screen, CurrentScreen::Settings));
+
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.settings.selected_index, 0);
+        app.handle_input(KeyCode::Char('c'));
+        assert!(!app.settings.pending_exit);
+        assert!(matches!(app.current_screen, CurrentScreen::Settings));
+        assert!(app.settings_dirty());
+    }
+
+    #[test]
+    fn test_unsaved_settings_discard() {
+        let mut app = settings_app();
+        let count = app.settings.default_count;
+        app.settings.default_count += 1;
+        app.settings.language = "de".to_string();
+        app.handle_input(KeyCode::Esc);
+        app.handle_input(KeyCode::Char('d'));
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert_eq!(app.settings.default_count, count);
+        assert_eq!(app.settings.language, "en");
+        assert!(!app.settings_dirty());
+    }
+
+    #[test]
+    fn test_unsaved_settings_save() {
+        // An unknown language fails the save before the config file is touched
+        let mut app = settings_app();
+        app.settings.default_count += 1;
+        app.settings.language = "enn".to_string();
+        app.handle_input(KeyCode::Esc);
+        assert!(app.settings.pending_exit);
+        app.handle_input(KeyCode::Char('s'));
+
+        // A failed save keeps the screen open with the error and the changes
+        assert!(matches!(app.current_screen, CurrentScreen::Settings));
+        assert!(!app.settings.pending_exit);
+        assert!(app.settings_dirty());
+        assert_eq!(app.error_message.as_deref(), Some("Unknown language: enn"));
+        assert_eq!(app.status(), None);
+    }
+
+    #[test]
+    fn test_save_settings_rejects_unknown_language() {
+        let mut app = settings_app();
+        app.settings.language = "enn".to_string();
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.error_message.as_deref(), Some("Unknown language: enn"));
+    }
+
+    #[test]
+    fn test_breach_check_result_is_polled_and_reset() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Check;
+
+        // Nothing to look up without a password
+        app.handle_input(KeyCode::Char('b'));
+        assert!(app.breach_check.is_none());
+
+        app.password_input.set("password");
+        let (sender, receiver) = mpsc::channel();
+        app.breach_check = Some(BreachJob {
+            receiver,
+            started: Instant::now(),
+        });
+        app.tick();
+        assert!(app.breach_check.is_some());
+        assert!(app.breach_result.is_none());
+
+        sender.send(Ok(3)).unwrap();
+        app.tick();
+        assert!(app.breach_check.is_none());
+        assert_eq!(app.breach_result, Some(Ok(3)));
+
+        // Typing makes the result stale
+        app.handle_input(KeyCode::Char('i'));
+        app.handle_input(KeyCode::Char('1'));
+        assert!(app.breach_result.is_none());
+
+        // A lookup that dies without a result is reported inline
+        let (sender, receiver) = mpsc::channel::<Result<u64, String>>();
+        app.breach_check = Some(BreachJob {
+            receiver,
+            started: Instant::now(),
+        });
+        drop(sender);
+        app.tick();
+        assert!(matches!(app.breach_result, Some(Err(_))));
+    }
+
+    #[test]
+    fn test_quick_generate_uses_active_profile() {
+        use crate::config::ProfileConfig;
+
+        let mut app = App::new();
+        app.settings.saved.general.default_length = 16;
+        app.settings.saved.profiles = BTreeMap::from([(
+            "work".to_string(),
+            ProfileConfig {
+                default_length: Some(32),
+                ..Default::default()
+            },
+        )]);
+        app.settings.profile = Some("work".to_string());
+        assert_eq!(app.general_defaults().default_length, 32);
+
+        app.handle_input(KeyCode::Char('g'));
+        assert_eq!(app.generator.length, 32);
+        app.handle_input(KeyCode::Esc);
+
+        // The settings keep editing the general values
+        assert_eq!(app.settings.saved.general.default_length, 16);
+        app.settings.profile = Some("gone".to_string());
+        assert_eq!(app.general_defaults().default_length, 16);
+    }
+
+    #[test]
+    fn test_quick_generate_keeps_generator_state() {
+        let mut app = App::new();
+        app.generator.length = 12;
+        app.generated_passwords = results(&["kept"]);
+
+        app.handle_input(KeyCode::Char('g'));
+        assert!(app.quick_generate.is_some());
+        assert_eq!(app.generator.count, 1);
+        assert_eq!(
+            app.generator.length,
+            app.settings.saved.general.default_length
+        );
+        assert!(app.generated_passwords.is_empty());
+
+        // Other keys are swallowed by the dialog
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.selected_index, 0);
+
+        app.handle_input(KeyCode::Esc);
+        assert!(app.quick_generate.is_none());
+        assert!(app.generation.is_none());
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert_eq!(app.generator.length, 12);
+        assert_eq!(app.generated_passwords[0].password, "kept");
+
+        // The menu entry opens it too, but not from a confirmation prompt
+        app.selected_index = 1;
+        app.handle_input(KeyCode::Enter);
+        assert!(app.quick_generate.is_some());
+        app.handle_input(KeyCode::Char('q'));
+        app.current_screen = CurrentScreen::Exit;
+        app.handle_input(KeyCode::Char('g'));
+        assert!(app.quick_generate.is_none());
+    }
+
+    #[test]
+    fn test_about_screen_from_help() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Help;
+
+        app.handle_input(KeyCode::Char('a'));
+        assert!(matches!(app.current_screen, CurrentScreen::About));
+        assert_eq!(app.about[0].key, "version");
+        assert_eq!(app.about[0].value, env!("CARGO_PKG_VERSION"));
+
+        let text = app.about_text();
+        assert!(text.starts_with(&format!("kdguard {}", env!("CARGO_PKG_VERSION"))));
+        assert!(text.contains("Config file: "));
+        assert!(text.contains("Update check: still checking"));
+
+        app.update_status = UpdateStatus::Failed("offline".to_string());
+        assert!(app.about_text().contains("Update check: failed (offline)"));
+
+        app.handle_input(KeyCode::Esc);
+        assert!(matches!(app.current_screen, CurrentScreen::Help));
+    }
+
+    #[test]
+    fn test_help_scroll_is_clamped() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Help;
+        app.set_help_viewport(50, 20);
+        assert_eq!(app.help.max_scroll, 30);
+
+        app.handle_input(KeyCode::Up);
+        assert_eq!(app.help.scroll, 0);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.help.scroll, 1);
+        app.handle_input(KeyCode::PageDown);
+        assert_eq!(app.help.scroll, 21);
+        app.handle_input(KeyCode::PageDown);
+        assert_eq!(app.help.scroll, 30);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.help.scroll, 30);
+        app.handle_input(KeyCode::PageUp);
+        assert_eq!(app.help.scroll, 10);
+        app.handle_input(KeyCode::Home);
+        assert_eq!(app.help.scroll, 0);
+        app.handle_input(KeyCode::End);
+        assert_eq!(app.help.scroll, 30);
+
+        // A taller terminal shows everything and resets the offset
+        app.set_help_viewport(50, 60);
+        assert_eq!(app.help.scroll, 0);
+        app.handle_input(KeyCode::Down);
+        assert_eq!(app.help.scroll, 0);
+    }
+
+    #[test]
+    fn test_password_visibility_resets_on_leave() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Check;
+        app.handle_input(KeyCode::Char('v'));
+        assert!(!app.password_input.masked);
+        app.handle_input(KeyCode::Char('v'));
+        assert!(app.password_input.masked);
+
+        app.handle_input(KeyCode::Char('v'));
+        app.handle_input(KeyCode::Esc);
+        assert!(app.password_input.masked);
+    }
+
+    #[test]
+    fn test_selected_row_stays_valid() {
+        let modes = [
+            PasswordMode::Random,
+            PasswordMode::Pattern,
+            PasswordMode::Phrase,
+            PasswordMode::Deterministic,
+        ];
+        let keys = [
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Right,
+            KeyCode::Up,
+            KeyCode::Left,
+            KeyCode::Down,
+        ];
+
+        for from in &modes {
+            for to in &modes {
+                let mut app = generator_app(from.clone());
+                for key in keys {
+                    app.handle_input(key);
+                    assert!(app.selected_row().is_some());
+                }
+                app.set_generator_mode(to.clone());
+                assert!(app.selected_row().is_some());
+                for key in keys {
+                    app.handle_input(key);
+                    assert!(app.selected_row().is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_row_is_reachable() {
+        let mut app = generator_app(PasswordMode::Deterministic);
+        let mut visited = vec![app.selected_row().unwrap()];
+        for _ in 0..10 {
+            app.handle_input(KeyCode::Down);
+            visited.push(app.selected_row().unwrap());
+        }
+        for row in GeneratorField::rows(&PasswordMode::Deterministic) {
+            assert!(visited.contains(row));
+        }
+
+        app.selected_index = 3;
+        app.set_generator_mode(PasswordMode::Random);
+        assert_eq!(app.selected_row(), Some(GeneratorField::Count));
+    }
+
+    #[test]
+    fn test_adjust_count_with_arrows() {
+        let mut app = generator_app(PasswordMode::Random);
+        app.selected_index = 1;
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.generator.count, 6);
+        app.handle_input(KeyCode::Left);
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.generator.count, 4);
+
+        app.generator.count = 1;
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.generator.count, 1);
+        app.generator.count = 100;
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.generator.count, 100);
+
+        let mut app = generator_app(PasswordMode::Deterministic);
+        app.selected_index = 4;
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.generator.count, 6);
+    }
+
+    #[test]
+    fn test_type_numeric_fields() {
+        let mut app = generator_app(PasswordMode::Random);
+        app.handle_input(KeyCode::Char('e'));
+        assert!(app.input_field == InputField::Generator(GeneratorField::Length));
+        assert_eq!(app.generator.numeric_input.value(), "16");
+        app.handle_input(KeyCode::Backspace);
+        app.handle_input(KeyCode::Backspace);
+        for c in ['4', 'x', '8'] {
+            app.handle_input(KeyCode::Char(c));
+        }
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.length, 48);
+        assert!(app.input_mode == InputMode::Normal);
+
+        let mut app = generator_app(PasswordMode::Phrase);
+        app.handle_input(KeyCode::Char('e'));
+        app.generator.numeric_input.set("7");
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.words, Some(7));
+    }
+
+    #[test]
+    fn test_phrase_insertion_rows() {
+        let mut app = generator_app(PasswordMode::Phrase);
+        app.selected_index = 1;
+        assert_eq!(app.selected_row(), Some(GeneratorField::PhraseDigits));
+        app.handle_input(KeyCode::Right);
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.generator.phrase.digits, 2);
+
+        app.selected_index = 2;
+        app.handle_input(KeyCode::Char('e'));
+        app.generator.numeric_input.set("11");
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(
+            app.error_message.as_deref(),
+            Some("Symbols must be between 0 and 10")
+        );
+        app.generator.numeric_input.set("1");
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.phrase.symbols, 1);
+
+        app.selected_index = 3;
+        app.handle_input(KeyCode::Char('e'));
+        assert!(app.input_mode == InputMode::Normal);
+        app.handle_input(KeyCode::Left);
+        assert_eq!(app.generator.phrase.style, InsertStyle::RandomPosition);
+        app.handle_input(KeyCode::Right);
+        assert_eq!(app.generator.phrase.style, InsertStyle::Between);
+    }
+
+    #[test]
+    fn test_type_numeric_field_out_of_range() {
+        let mut app = generator_app(PasswordMode::Random);
+        app.selected_index = 1;
+        app.handle_input(KeyCode::Char('e'));
+        assert!(app.input_field == InputField::Generator(GeneratorField::Count));
+        app.generator.numeric_input.set("250");
+        app.handle_input(KeyCode::Enter);
+        assert_eq!(app.generator.count, 5);
+        assert!(app.input_mode == InputMode::Editing);
+        assert_eq!(
+            app.error_message.as_deref(),
+            Some("Count must be between 1 and 100")
+        );
+
+        app.handle_input(KeyCode::Esc);
+        assert_eq!(app.generator.count, 5);
+        assert!(app.input_mode == InputMode::Normal);
+    }
+
+    #[test]
+    fn test_save_passwords_with_overwrite_confirmation() {
+        let path =
+            std::env::temp_dir().join(format!("kdguard_tui_save_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Generator;
+        app.generated_passwords = results(&["first-password"]);
+
+        app.handle_input(KeyCode::Char('s'));
+        assert!(app.input_field == InputField::SaveFilename);
+        assert!(app.save_filename.value().starts_with("kdguard_"));
+        app.save_filename.set(&path.to_string_lossy());
+        app.handle_input(KeyCode::Enter);
+        assert!(!app.pending_overwrite);
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("first-password")
+        );
+
+        app.generated_passwords = results(&["second-password"]);
+        app.handle_input(KeyCode::Char('s'));
+        app.save_filename.set(&path.to_string_lossy());
+        app.handle_input(KeyCode::Enter);
+        assert!(app.pending_overwrite);
+        app.handle_input(KeyCode::Char('n'));
+        assert!(!app.pending_overwrite);
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("first-password")
+        );
+
+        app.handle_input(KeyCode::Char('s'));
+        app.save_filename.set(&path.to_string_lossy());
+        app.handle_input(KeyCode::Enter);
+        app.handle_input(KeyCode::Char('y'));
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(content.contains("second-password"));
+        assert!(!content.contains("first-password"));
+    }
+
+    #[test]
+    fn test_exit_with_unsaved_passwords() {
+        // Cancel keeps the TUI open, Enter alone does not drop the passwords
+        let mut app = App::new();
+        app.generated_passwords = results(&["unsaved-password"]);
+        app.handle_input(KeyCode::Char('q'));
+        assert!(matches!(app.current_screen, CurrentScreen::Exit));
+        assert_eq!(app.unsaved_results(), 1);
+        assert!(!app.handle_input(KeyCode::Enter));
+        assert!(!app.handle_input(KeyCode::Char('n')));
+        assert!(matches!(app.current_screen, CurrentScreen::Main));
+        assert!(!app.exit.exit);
+
+        // Exit anyway
+        app.handle_input(KeyCode::Char('q'));
+        assert!(app.handle_input(KeyCode::Char('y')));
+        assert!(app.exit.exit);
+
+        // Save and exit
+        let path =
+            std::env::temp_dir().join(format!("kdguard_tui_exit_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut app = App::new();
+        app.generated_passwords = results(&["unsaved-password"]);
+        app.handle_input(KeyCode::Char('q'));
+        assert!(!app.handle_input(KeyCode::Char('s')));
+        assert!(app.input_field == InputField::SaveFilename);
+        app.save_filename.set(&path.to_string_lossy());
+        assert!(app.handle_input(KeyCode::Enter));
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(content.contains("unsaved-password"));
+        assert_eq!(app.unsaved_results(), 0);
+    }
+
+    #[test]
+    fn test_exit_after_save_needs_no_warning() {
+        let mut app = App::new();
+        app.generated_passwords = results(&["saved-password"]);
+        app.results_saved = true;
+        app.handle_input(KeyCode::Char('q'));
+        assert_eq!(app.unsaved_results(), 0);
+        // Without unsaved passwords there is nothing to save
+        assert!(!app.handle_input(KeyCode::Char('s')));
+        assert!(app.input_field == InputField::None);
+        assert!(app.handle_input(KeyCode::Enter));
     }
 }
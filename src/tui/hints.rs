@@ -0,0 +1,407 @@
+use ratatui::crossterm::event::KeyCode;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::PasswordMode;
+use crate::tui::app::{
+    App, CurrentScreen, GeneratorField, GeneratorFocus, InputField, InputMode, SettingsField,
+};
+use crate::tui::keymap::{Action, KeyMap, key_name};
+use crate::tui::screens::tr;
+use crate::tui::theme::Theme;
+
+/// The key of a footer hint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintKey {
+    /// The keys bound to an action, as configured in the key map
+    Action(Action),
+    /// The keys bound to several actions shown as one hint, e.g. ↑↓
+    Actions(&'static [Action]),
+    /// A key the key map does not translate, like the keys of text input
+    Fixed(KeyCode),
+    /// ← and →, which change values
+    LeftRight,
+}
+
+/// What a key does in the current state of a screen, shown in the footer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hint {
+    pub key: HintKey,
+    /// The name of the label below `tui.hints`
+    pub label: &'static str,
+}
+
+impl Hint {
+    const fn new(key: HintKey, label: &'static str) -> Self {
+        Self { key, label }
+    }
+
+    /// Get the keys of the hint as shown in the footer
+    ///
+    /// # Arguments
+    ///
+    /// * `keymap`: The key map input handling uses
+    ///
+    /// # Returns
+    ///
+    /// Returns the key names, alternatives separated by `/`
+    pub fn keys(&self, keymap: &KeyMap) -> String {
+        let bound = |action: Action| {
+            keymap
+                .keys(action)
+                .iter()
+                .map(|key| key_name(*key))
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+        match self.key {
+            HintKey::Action(action) => bound(action),
+            HintKey::Actions(actions) => {
+                let single = actions.iter().all(|action| keymap.keys(*action).len() == 1);
+                let names: Vec<String> = actions.iter().map(|action| bound(*action)).collect();
+                names.join(if single { "" } else { " " })
+            }
+            HintKey::Fixed(key) => key_name(key),
+            HintKey::LeftRight => "←→".to_string(),
+        }
+    }
+}
+
+/// Translate a hint label
+fn label_text(label: &str) -> String {
+    tr(&format!("tui.hints.{}", label), &[])
+}
+
+const UP_DOWN: &[Action] = &[Action::Up, Action::Down];
+
+impl App {
+    /// Get the key hints of the current screen and state
+    ///
+    /// Bound actions are shown with the keys of the key map, so the footer always
+    /// names the keys input handling reacts to.
+    ///
+    /// # Returns
+    ///
+    /// Returns the hints in display order, empty if the state shows a prompt instead
+    pub fn footer_hints(&self) -> Vec<Hint> {
+        use HintKey::{Action as Bound, Actions, Fixed, LeftRight};
+
+        if self.quick_generate.is_some() {
+            return vec![
+                Hint::new(Bound(Action::Copy), "copy"),
+                Hint::new(Fixed(KeyCode::Char('r')), "regenerate"),
+                Hint::new(Bound(Action::Back), "close"),
+            ];
+        }
+
+        // Text input receives the keys as they are, without the key map
+        if self.input_mode == InputMode::Editing {
+            return match self.input_field {
+                InputField::PasswordCheck => vec![
+                    Hint::new(Fixed(KeyCode::Enter), "stop_typing"),
+                    Hint::new(Fixed(KeyCode::Esc), "stop_typing"),
+                ],
+                _ => vec![
+                    Hint::new(Fixed(KeyCode::Enter), "confirm"),
+                    Hint::new(Fixed(KeyCode::Esc), "cancel"),
+                ],
+            };
+        }
+
+        match self.current_screen {
+            CurrentScreen::Main => vec![
+                Hint::new(Actions(UP_DOWN), "navigate"),
+                Hint::new(Bound(Action::Select), "select"),
+                Hint::new(Fixed(KeyCode::Char('g')), "quick"),
+                Hint::new(Fixed(KeyCode::Char('?')), "keys"),
+                Hint::new(Bound(Action::Quit), "exit"),
+                Hint::new(Bound(Action::Back), "exit"),
+            ],
+            CurrentScreen::GeneratorModeSelection => vec![
+                Hint::new(Actions(UP_DOWN), "navigate"),
+                Hint::new(Bound(Action::Select), "select"),
+                Hint::new(Bound(Action::Back), "back"),
+            ],
+            CurrentScreen::Generator => self.generator_hints(),
+            CurrentScreen::Settings => {
+                let mut hints = vec![Hint::new(Actions(UP_DOWN), "navigate")];
+                if matches!(
+                    self.selected_setting(),
+                    Some(SettingsField::ResetConfig | SettingsField::Uninstall)
+                ) {
+                    hints.push(Hint::new(Bound(Action::Select), "run"));
+                } else {
+                    hints.push(Hint::new(LeftRight, "change"));
+                    hints.push(Hint::new(Bound(Action::Select), "save"));
+                }
+                hints.push(Hint::new(Bound(Action::Back), "back"));
+                hints
+            }
+            CurrentScreen::Help => vec![
+                Hint::new(Actions(UP_DOWN), "scroll"),
+                Hint::new(Fixed(KeyCode::PageUp), "page"),
+                Hint::new(Fixed(KeyCode::PageDown), "page"),
+                Hint::new(Fixed(KeyCode::Char('a')), "about"),
+                Hint::new(Bound(Action::Back), "back"),
+                Hint::new(Bound(Action::Quit), "back"),
+            ],
+            CurrentScreen::About => vec![
+                Hint::new(Bound(Action::Copy), "copy_details"),
+                Hint::new(Bound(Action::Back), "back"),
+            ],
+            CurrentScreen::Check => vec![
+                Hint::new(Bound(Action::Select), "type"),
+                Hint::new(Fixed(KeyCode::Char('i')), "type"),
+                Hint::new(Fixed(KeyCode::Char('v')), "show_hide"),
+                Hint::new(Fixed(KeyCode::Char('d')), "details"),
+                Hint::new(Fixed(KeyCode::Char('b')), "breaches"),
+                Hint::new(Bound(Action::Copy), "clear"),
+                Hint::new(Bound(Action::Back), "back"),
+            ],
+            CurrentScreen::History => vec![
+                Hint::new(Actions(UP_DOWN), "navigate"),
+                Hint::new(Bound(Action::Copy), "copy"),
+                Hint::new(Fixed(KeyCode::Char('x')), "clear_history"),
+                Hint::new(Bound(Action::Back), "back"),
+            ],
+            CurrentScreen::Exit => Vec::new(),
+        }
+    }
+
+    /// Get the key hints of the generator screen
+    fn generator_hints(&self) -> Vec<Hint> {
+        use HintKey::{Action as Bound, Actions, Fixed, LeftRight};
+
+        if self.preset_picker.is_some() {
+            return vec![
+                Hint::new(Actions(UP_DOWN), "navigate"),
+                Hint::new(Bound(Action::Select), "load"),
+                Hint::new(Bound(Action::Back), "close"),
+            ];
+        }
+
+        let generated = !self.generated_passwords.is_empty();
+        if self.generator.focus == GeneratorFocus::Results {
+            let mut hints = vec![
+                Hint::new(Actions(UP_DOWN), "navigate"),
+                Hint::new(Bound(Action::Copy), "copy"),
+                Hint::new(Fixed(KeyCode::Char('v')), "show_hide"),
+            ];
+            if self.generator.mode != PasswordMode::Deterministic {
+                hints.push(Hint::new(Fixed(KeyCode::Char('r')), "regenerate"));
+            }
+            hints.extend([
+                Hint::new(Fixed(KeyCode::Char('x')), "remove"),
+                Hint::new(Bound(Action::Save), "save"),
+                Hint::new(Fixed(KeyCode::Tab), "parameters"),
+                Hint::new(Bound(Action::Back), "parameters"),
+            ]);
+            return hints;
+        }
+
+        let mut hints = vec![Hint::new(Actions(UP_DOWN), "navigate")];
+        match self.selected_row() {
+            Some(
+                GeneratorField::Length
+                | GeneratorField::Count
+                | GeneratorField::Words
+                | GeneratorField::PhraseDigits
+                | GeneratorField::PhraseSymbols,
+            ) => {
+                hints.push(Hint::new(LeftRight, "change"));
+                hints.push(Hint::new(Bound(Action::Edit), "type_value"));
+                hints.push(Hint::new(Bound(Action::Select), "generate"));
+            }
+            Some(GeneratorField::InsertStyle) => {
+                hints.push(Hint::new(LeftRight, "change"));
+                hints.push(Hint::new(Bound(Action::Select), "generate"));
+            }
+            Some(_) => {
+                hints.push(Hint::new(Bound(Action::Select), "edit"));
+                hints.push(Hint::new(Bound(Action::Edit), "edit"));
+            }
+            None => {}
+        }
+        if self.generator.mode == PasswordMode::Deterministic {
+            hints.push(Hint::new(Fixed(KeyCode::Char('v')), "show_seed"));
+        }
+        if generated {
+            hints.push(Hint::new(Fixed(KeyCode::Tab), "results"));
+            hints.push(Hint::new(Bound(Action::Save), "save"));
+        }
+        if self.can_restore_previous() {
+            hints.push(Hint::new(Fixed(KeyCode::Char('u')), "previous"));
+        }
+        hints.extend([
+            Hint::new(Fixed(KeyCode::Char('p')), "presets"),
+            Hint::new(Fixed(KeyCode::Char('S')), "save_preset"),
+            Hint::new(Bound(Action::Back), "change_mode"),
+        ]);
+        hints
+    }
+
+    /// Get a label shown before the hints, for states that change what keys do
+    pub fn footer_state(&self) -> Option<String> {
+        if self.input_mode != InputMode::Editing || self.quick_generate.is_some() {
+            return None;
+        }
+        let label = match self.input_field {
+            InputField::PasswordCheck => "typing",
+            InputField::Generator(_) => "editing",
+            _ => return None,
+        };
+        Some(label_text(label))
+    }
+}
+
+/// Join hints into footer entries, merging neighbours with the same label
+///
+/// # Arguments
+///
+/// * `hints`: The hints in display order
+/// * `keymap`: The key map input handling uses
+///
+/// # Returns
+///
+/// Returns the keys and the label of each entry
+pub fn hint_entries(hints: &[Hint], keymap: &KeyMap) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, &'static str)> = Vec::new();
+    for hint in hints {
+        let keys = hint.keys(keymap);
+        match entries.last_mut() {
+            Some((merged, label)) if *label == hint.label => {
+                if !merged.split('/').any(|key| key == keys) {
+                    merged.push('/');
+                    merged.push_str(&keys);
+                }
+            }
+            _ => entries.push((keys, hint.label)),
+        }
+    }
+    entries
+        .into_iter()
+        .map(|(keys, label)| (keys, label_text(label)))
+        .collect()
+}
+
+/// Render the hints of the current state as a footer line
+///
+/// # Arguments
+///
+/// * `app`: The application state
+/// * `theme`: The colors to use
+///
+/// # Returns
+///
+/// Returns the line with the keys highlighted
+pub fn hint_line(app: &App, theme: &Theme) -> Line<'static> {
+    let mut spans = Vec::new();
+    if let Some(state) = app.footer_state() {
+        spans.push(Span::styled(
+            format!("{}  ", state),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    for (index, (keys, label)) in hint_entries(&app.footer_hints(), &app.keymap)
+        .into_iter()
+        .enumerate()
+    {
+        if index > 0 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(
+            keys,
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            format!(" {}", label),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyBindingsConfig;
+
+    /// Render the hints of an app as plain text
+    fn footer(app: &App) -> String {
+        hint_entries(&app.footer_hints(), &app.keymap)
+            .into_iter()
+            .map(|(keys, label)| format!("{} {}", keys, label))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn test_main_hints() {
+        let app = App::new();
+        assert_eq!(
+            footer(&app),
+            "↑↓ navigate, Enter select, g quick password, ? all keys, q/Esc exit"
+        );
+    }
+
+    #[test]
+    fn test_generator_hints_follow_the_focused_row() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Generator;
+        app.generator.mode = PasswordMode::Pattern;
+        app.selected_index = 0;
+        let text = footer(&app);
+        assert!(text.contains("Enter/e edit"), "{}", text);
+        assert!(!text.contains("generate"), "{}", text);
+
+        app.selected_index = 1;
+        let text = footer(&app);
+        assert!(text.contains("←→ change value"), "{}", text);
+        assert!(text.contains("e type a value"), "{}", text);
+        assert!(text.contains("Enter generate"), "{}", text);
+        assert!(!text.contains("show or hide seed"), "{}", text);
+
+        app.generator.mode = PasswordMode::Deterministic;
+        app.selected_index = 1;
+        let text = footer(&app);
+        assert!(text.contains("v show or hide seed"), "{}", text);
+        assert!(!text.contains("results"), "{}", text);
+    }
+
+    #[test]
+    fn test_editing_hints_do_not_go_back() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Check;
+        assert!(footer(&app).ends_with("Esc back"));
+
+        app.input_mode = InputMode::Editing;
+        app.input_field = InputField::PasswordCheck;
+        assert_eq!(footer(&app), "Enter/Esc stop typing");
+        assert_eq!(app.footer_state().as_deref(), Some("TYPING"));
+
+        app.current_screen = CurrentScreen::Generator;
+        app.input_field = InputField::Generator(GeneratorField::Pattern);
+        assert_eq!(footer(&app), "Enter confirm, Esc cancel");
+        assert_eq!(app.footer_state().as_deref(), Some("EDITING"));
+    }
+
+    #[test]
+    fn test_hints_use_the_configured_keys() {
+        let mut app = App::new();
+        app.keymap = KeyMap::from_config(&KeyBindingsConfig {
+            preset: Some("vim".to_string()),
+            copy: Some(crate::config::KeyList::One("y".to_string())),
+            ..KeyBindingsConfig::default()
+        })
+        .unwrap();
+        app.current_screen = CurrentScreen::History;
+        assert_eq!(
+            footer(&app),
+            "↑/k ↓/j navigate, y copy, x clear history, Esc/h back"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use ratatui::crossterm::event::KeyCode;
+
+use crate::config::{KEYBINDING_PRESETS, KeyBindingsConfig, KeyList};
+use crate::tui::app::CurrentScreen;
+
+/// An action that can be bound to keys in the `[keybindings]` config section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Select,
+    Back,
+    Edit,
+    Copy,
+    Save,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::Up,
+        Action::Down,
+        Action::Select,
+        Action::Back,
+        Action::Edit,
+        Action::Copy,
+        Action::Save,
+        Action::Quit,
+    ];
+
+    /// Get the key the screens handle for this action
+    pub fn key(&self) -> KeyCode {
+        match self {
+            Action::Up => KeyCode::Up,
+            Action::Down => KeyCode::Down,
+            Action::Select => KeyCode::Enter,
+            Action::Back => KeyCode::Esc,
+            Action::Edit => KeyCode::Char('e'),
+            Action::Copy => KeyCode::Char('c'),
+            Action::Save => KeyCode::Char('s'),
+            Action::Quit => KeyCode::Char('q'),
+        }
+    }
+
+    /// Get the name used in the config and in translation keys
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Edit => "edit",
+            Action::Copy => "copy",
+            Action::Save => "save",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Get the actions a screen handles, in the order of the cheat sheet
+    pub fn for_screen(screen: &CurrentScreen) -> &'static [Action] {
+        match screen {
+            CurrentScreen::Main => &[Action::Up, Action::Down, Action::Select, Action::Quit],
+            CurrentScreen::GeneratorModeSelection => {
+                &[Action::Up, Action::Down, Action::Select, Action::Back]
+            }
+            CurrentScreen::Generator => &[
+                Action::Up,
+                Action::Down,
+                Action::Select,
+                Action::Back,
+                Action::Edit,
+                Action::Copy,
+                Action::Save,
+            ],
+            CurrentScreen::Settings => &[Action::Up, Action::Down, Action::Select, Action::Back],
+            CurrentScreen::Help => &[Action::Up, Action::Down, Action::Back, Action::Quit],
+            CurrentScreen::About => &[Action::Copy, Action::Back],
+            CurrentScreen::Check => &[Action::Select, Action::Back],
+            CurrentScreen::History => &[Action::Up, Action::Down, Action::Copy, Action::Back],
+            CurrentScreen::Exit => &[],
+        }
+    }
+}
+
+/// The keys bound to each action
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMap {
+    bindings: Vec<(Action, Vec<KeyCode>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, vec![action.key()]))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// The default keys plus h/j/k/l for back, down, up, and select
+    pub fn vim() -> Self {
+        let mut keymap = Self::default();
+        for (action, key) in [
+            (Action::Up, 'k'),
+            (Action::Down, 'j'),
+            (Action::Select, 'l'),
+            (Action::Back, 'h'),
+        ] {
+            keymap.bind(action, vec![action.key(), KeyCode::Char(key)]);
+        }
+        keymap
+    }
+
+    /// Get a built-in key map by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the preset, one of `KEYBINDING_PRESETS`
+    ///
+    /// # Returns
+    ///
+    /// Returns the key map if the preset exists, otherwise None
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "vim" => Some(Self::vim()),
+            _ => None,
+        }
+    }
+
+    /// Build the key map from the config
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The key bindings config
+    ///
+    /// # Returns
+    ///
+    /// Returns the key map, or an error if the preset is unknown, a key cannot be parsed,
+    /// an action has no key, or a key is bound to several actions
+    pub fn from_config(config: &KeyBindingsConfig) -> Result<Self, String> {
+        let preset = config.preset.as_deref().unwrap_or(KEYBINDING_PRESETS[0]);
+        let mut keymap =
+            Self::preset(preset).ok_or(format!("Unknown key binding preset: {}", preset))?;
+
+        let overrides = [
+            (Action::Up, &config.up),
+            (Action::Down, &config.down),
+            (Action::Select, &config.select),
+            (Action::Back, &config.back),
+            (Action::Edit, &config.edit),
+            (Action::Copy, &config.copy),
+            (Action::Save, &config.save),
+            (Action::Quit, &config.quit),
+        ];
+        for (action, keys) in overrides {
+            if let Some(keys) = keys {
+                keymap.bind(action, parse_keys(action, keys)?);
+            }
+        }
+
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    fn bind(&mut self, action: Action, keys: Vec<KeyCode>) {
+        if let Some((_, bound)) = self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            *bound = keys;
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, (action, keys)) in self.bindings.iter().enumerate() {
+            if keys.is_empty() {
+                return Err(format!("No key bound to {}", action.name()));
+            }
+            for (other, other_keys) in &self.bindings[index + 1..] {
+                if let Some(key) = keys.iter().find(|key| other_keys.contains(key)) {
+                    return Err(format!(
+                        "Key {} is bound to both {} and {}",
+                        key_name(*key),
+                        action.name(),
+                        other.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the keys bound to an action
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the action a key is bound to
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    /// Translate a pressed key into the key the screens handle
+    ///
+    /// Bound keys become the key of their action. The key of an action that was
+    /// rebound to other keys is dropped, all other keys pass through unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The pressed key
+    ///
+    /// # Returns
+    ///
+    /// Returns the key to handle, or None if it should be ignored
+    pub fn resolve(&self, key: KeyCode) -> Option<KeyCode> {
+        if let Some(action) = self.action(key) {
+            return Some(action.key());
+        }
+        if Action::ALL.iter().any(|action| action.key() == key) {
+            return None;
+        }
+        Some(key)
+    }
+}
+
+fn parse_keys(action: Action, keys: &KeyList) -> Result<Vec<KeyCode>, String> {
+    keys.keys()
+        .iter()
+        .map(|key| parse_key(key).map_err(|e| format!("{} for {}", e, action.name())))
+        .collect()
+}
+
+/// Parse a key name from the config
+///
+/// Accepts single characters (case-sensitive), the named keys `Up`, `Down`, `Left`,
+/// `Right`, `Enter`, `Esc`, `Tab`, `Backspace`, `Delete`, `Home`, `End`, `PageUp`,
+/// `PageDown`, and `Space` (case-insensitive), and `F1` to `F12`.
+///
+/// # Arguments
+///
+/// * `key`: The key name
+///
+/// # Returns
+///
+/// Returns the key if successful, otherwise an error
+pub fn parse_key(key: &str) -> Result<KeyCode, String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+
+    let lower = key.to_lowercase();
+    let code = match lower.as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "space" => KeyCode::Char(' '),
+        _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => return Err(format!("Unknown key '{}'", key)),
+        },
+    };
+    Ok(code)
+}
+
+/// Get the display name of a key for the cheat sheet
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("k"), Ok(KeyCode::Char('k')));
+        assert_eq!(parse_key("K"), Ok(KeyCode::Char('K')));
+        assert_eq!(parse_key("pageDown"), Ok(KeyCode::PageDown));
+        assert_eq!(parse_key("Space"), Ok(KeyCode::Char(' ')));
+        assert_eq!(parse_key("F5"), Ok(KeyCode::F(5)));
+        assert!(parse_key("F13").is_err());
+        assert!(parse_key("Hyper").is_err());
+        assert!(parse_key("").is_err());
+    }
+
+    #[test]
+    fn test_vim_preset() {
+        let keymap = KeyMap::from_config(&KeyBindingsConfig {
+            preset: Some("vim".to_string()),
+            ..KeyBindingsConfig::default()
+        })
+        .unwrap();
+        assert_eq!(keymap.resolve(KeyCode::Char('j')), Some(KeyCode::Down));
+        assert_eq!(keymap.resolve(KeyCode::Char('k')), Some(KeyCode::Up));
+        assert_eq!(keymap.resolve(KeyCode::Char('h')), Some(KeyCode::Esc));
+        assert_eq!(keymap.resolve(KeyCode::Char('l')), Some(KeyCode::Enter));
+        assert_eq!(keymap.resolve(KeyCode::Up), Some(KeyCode::Up));
+        assert_eq!(keymap.resolve(KeyCode::Char('x')), Some(KeyCode::Char('x')));
+    }
+
+    #[test]
+    fn test_overrides_replace_the_preset_keys() {
+        let keymap = KeyMap::from_config(&KeyBindingsConfig {
+            copy: Some(KeyList::One("y".to_string())),
+            quit: Some(KeyList::Many(vec!["q".to_string(), "F10".to_string()])),
+            ..KeyBindingsConfig::default()
+        })
+        .unwrap();
+        assert_eq!(keymap.keys(Action::Copy), [KeyCode::Char('y')]);
+        assert_eq!(keymap.resolve(KeyCode::Char('y')), Some(KeyCode::Char('c')));
+        // The replaced key no longer copies
+        assert_eq!(keymap.resolve(KeyCode::Char('c')), None);
+        assert_eq!(keymap.resolve(KeyCode::F(10)), Some(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_invalid_bindings_are_rejected() {
+        let conflict = KeyMap::from_config(&KeyBindingsConfig {
+            preset: Some("vim".to_string()),
+            copy: Some(KeyList::One("j".to_string())),
+            ..KeyBindingsConfig::default()
+        });
+        assert!(conflict.unwrap_err().contains("bound to both"));
+
+        let unparsable = KeyMap::from_config(&KeyBindingsConfig {
+            save: Some(KeyList::One("Hyper".to_string())),
+            ..KeyBindingsConfig::default()
+        });
+        assert!(unparsable.unwrap_err().contains("save"));
+
+        let empty = KeyMap::from_config(&KeyBindingsConfig {
+            back: Some(KeyList::Many(Vec::new())),
+            ..KeyBindingsConfig::default()
+        });
+        assert!(empty.is_err());
+
+        let preset = KeyMap::from_config(&KeyBindingsConfig {
+            preset: Some("emacs".to_string()),
+            ..KeyBindingsConfig::default()
+        });
+        assert!(preset.is_err());
+    }
+}
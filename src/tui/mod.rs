@@ -1,59 +1,176 @@
 mod app;
+mod hints;
+mod keymap;
 mod screens;
+mod text_input;
+mod theme;
 mod ui;
 
-use ratatui::{Terminal, backend::CrosstermBackend};
+use ratatui::{Terminal, backend::CrosstermBackend, layout::Rect};
 use std::io::{self, stdout};
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
 
-pub use app::App;
+use crate::uninstall::{UninstallManager, UninstallOptions};
+
+pub use app::{App, UpdateStatus};
 pub use ui::ui;
 
-pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// Panic hook called by the standard library
+type PanicHook = Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Send + Sync + 'static>;
+
+/// Run the TUI until the user exits
+///
+/// # Arguments
+///
+/// * `updates`: Receives the result of the background update check
+///
+/// # Returns
+///
+/// Returns Ok(()) if successful, otherwise an error
+pub fn run(updates: Receiver<UpdateStatus>) -> Result<(), Box<dyn std::error::Error>> {
+    // Installed before entering raw mode so a failing setup is cleaned up as well
+    let previous_hook = install_panic_hook(|| {
+        let _ = restore_terminal();
+    });
     ratatui::crossterm::terminal::enable_raw_mode()?;
 
     let mut stdout = stdout();
     ratatui::crossterm::execute!(
         stdout,
         ratatui::crossterm::terminal::EnterAlternateScreen,
+        ratatui::crossterm::event::EnableBracketedPaste,
+        ratatui::crossterm::event::EnableMouseCapture,
         ratatui::crossterm::cursor::Hide
     )?;
 
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
     let mut app = App::new();
-    let result = run_app(&mut terminal, &mut app);
+    app.updates = Some(updates);
+    let result = Terminal::new(CrosstermBackend::new(stdout))
+        .map_err(Into::into)
+        .and_then(|mut terminal| run_app(&mut terminal, &mut app));
 
-    ratatui::crossterm::terminal::disable_raw_mode()?;
-    ratatui::crossterm::execute!(
-        terminal.backend_mut(),
-        ratatui::crossterm::terminal::LeaveAlternateScreen,
-        ratatui::crossterm::cursor::Show
-    )?;
-    terminal.show_cursor()?;
+    restore_panic_hook(previous_hook);
+    restore_terminal()?;
+    result?;
 
-    if let Err(err) = result {
-        eprintln!("Error: {}", err);
+    // The uninstaller asks on the plain terminal, so it runs after leaving raw mode
+    if app.uninstall_requested {
+        UninstallManager::uninstall(UninstallOptions {
+            backup: true,
+            ..UninstallOptions::default()
+        })?;
     }
 
     Ok(())
 }
 
+/// Install a panic hook that runs `restore` before the previous hook prints the panic
+///
+/// # Arguments
+///
+/// * `restore`: Restores the terminal to a usable state
+///
+/// # Returns
+///
+/// Returns the previous hook, to be reinstalled with `restore_panic_hook`
+fn install_panic_hook(restore: impl Fn() + Send + Sync + 'static) -> Arc<PanicHook> {
+    let previous: Arc<PanicHook> = Arc::new(std::panic::take_hook());
+    let chained = previous.clone();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        chained(info);
+    }));
+    previous
+}
+
+/// Reinstall the panic hook that was active before `install_panic_hook`
+///
+/// # Arguments
+///
+/// * `previous`: The hook returned by `install_panic_hook`
+fn restore_panic_hook(previous: Arc<PanicHook>) {
+    std::panic::set_hook(Box::new(move |info| previous(info)));
+}
+
+/// Leave raw mode and the alternate screen and release mouse and paste capture
+fn restore_terminal() -> io::Result<()> {
+    ratatui::crossterm::terminal::disable_raw_mode()?;
+    ratatui::crossterm::execute!(
+        stdout(),
+        ratatui::crossterm::event::DisableMouseCapture,
+        ratatui::crossterm::event::DisableBracketedPaste,
+        ratatui::crossterm::terminal::LeaveAlternateScreen,
+        ratatui::crossterm::cursor::Show
+    )
+}
+
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
 ) -> Result<(), Box<dyn std::error::Error>> {
     loop {
+        app.tick();
         terminal.draw(|f| ui(f, app))?;
 
-        if ratatui::crossterm::event::poll(std::time::Duration::from_millis(16))?
-            && let ratatui::crossterm::event::Event::Key(key) = ratatui::crossterm::event::read()?
-            && key.kind == ratatui::crossterm::event::KeyEventKind::Press
-            && app.handle_input(key.code)
-        {
-            break;
+        if !ratatui::crossterm::event::poll(std::time::Duration::from_millis(16))? {
+            continue;
+        }
+
+        match ratatui::crossterm::event::read()? {
+            ratatui::crossterm::event::Event::Key(key)
+                if key.kind == ratatui::crossterm::event::KeyEventKind::Press
+                    && app.handle_input(key.code) =>
+            {
+                break;
+            }
+            ratatui::crossterm::event::Event::Paste(text) => app.handle_paste(&text),
+            // Redraw right away at the new size instead of waiting for the next poll
+            ratatui::crossterm::event::Event::Resize(_, _) => terminal.autoresize()?,
+            ratatui::crossterm::event::Event::Mouse(event) => {
+                let size = terminal.size()?;
+                app.handle_mouse(event, Rect::new(0, 0, size.width, size.height));
+            }
+            _ => {}
         }
     }
 
     Ok(())
 }
+
+// Panic hooks are process-wide and would interfere with other tests running in
+// parallel, so this test only runs with `cargo test --features panic-test`
+#[cfg(all(test, feature = "panic-test"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_panic_hook_restores_terminal() {
+        let called = Arc::new(AtomicUsize::new(0));
+        let count = called.clone();
+        std::panic::set_hook(Box::new(move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let restored = Arc::new(AtomicUsize::new(0));
+        let flag = restored.clone();
+        let previous = install_panic_hook(move || {
+            flag.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let result = std::panic::catch_unwind(|| panic!("controlled panic"));
+        assert!(result.is_err());
+        assert_eq!(restored.load(Ordering::SeqCst), 1);
+        assert_eq!(called.load(Ordering::SeqCst), 1);
+
+        // After leaving the TUI a panic reaches the previous hook only
+        restore_panic_hook(previous);
+        let result = std::panic::catch_unwind(|| panic!("controlled panic"));
+        let _ = std::panic::take_hook();
+
+        assert!(result.is_err());
+        assert_eq!(restored.load(Ordering::SeqCst), 1);
+        assert_eq!(called.load(Ordering::SeqCst), 2);
+    }
+}
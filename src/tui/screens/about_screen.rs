@@ -0,0 +1,80 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+};
+
+use crate::tui::app::{App, UpdateStatus};
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{screen_layout, tr};
+
+pub fn render_about_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = screen_layout(area);
+
+    let title = Paragraph::new(tr("tui.about.title", &[]))
+        .block(app.theme.block().title(tr("tui.about.title", &[])))
+        .style(
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let label_style = Style::default()
+        .fg(app.theme.highlight)
+        .add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Line> = app
+        .about
+        .iter()
+        .map(|entry| {
+            let label = tr(&format!("tui.about.{}", entry.key), &[]);
+            Line::from(vec![
+                Span::styled(format!("{}: ", label), label_style),
+                Span::raw(entry.value.clone()),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}: ", tr("tui.about.update", &[])), label_style),
+        update_status(app),
+    ]));
+
+    let details = Paragraph::new(lines)
+        .block(app.theme.block())
+        .wrap(Wrap { trim: false });
+    frame.render_widget(details, chunks[1]);
+
+    let footer = Paragraph::new(hint_line(app, &app.theme))
+        .block(app.theme.block())
+        .alignment(Alignment::Center);
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Describe the result of the background update check
+fn update_status(app: &App) -> Span<'static> {
+    match &app.update_status {
+        UpdateStatus::Checking => Span::styled(
+            tr("tui.about.update_checking", &[]),
+            Style::default().fg(app.theme.muted),
+        ),
+        UpdateStatus::UpToDate => Span::styled(
+            tr("tui.about.update_current", &[]),
+            Style::default().fg(app.theme.success),
+        ),
+        UpdateStatus::Available(tag) => Span::styled(
+            tr("tui.about.update_available", &[("tag", tag)]),
+            Style::default().fg(app.theme.highlight),
+        ),
+        UpdateStatus::Failed(error) => Span::styled(
+            tr("tui.about.update_failed", &[("error", error)]),
+            Style::default().fg(app.theme.error),
+        ),
+        UpdateStatus::Disabled => Span::styled(
+            tr("tui.about.update_disabled", &[]),
+            Style::default().fg(app.theme.muted),
+        ),
+    }
+}
@@ -1,48 +1,47 @@
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
+    text::{Line, Span as TextSpan},
     widgets::{Block, Borders, Gauge, Paragraph, Wrap},
 };
 
-use crate::password::health_check::PasswordAnalysis;
+use crate::password::Span;
+use crate::password::health_check::{EntropyModel, PasswordAnalysis, PasswordScore};
 use crate::tui::app::{App, InputMode};
+use crate::tui::hints::hint_line;
+use crate::tui::screens::tr;
 
-const CHECK_TITLE: &str = "Password Health Check";
-const CHECK_BLOCK_TITLE: &str = "Check Password";
-const PASSWORD_FIELD_TITLE: &str = "Password";
-const ANALYSIS_FIELD_TITLE: &str = "Analysis";
-const PLACEHOLDER_TEXT: &str = "Enter a password and press Enter to check its strength";
-const FOOTER_TEXT: &str =
-    "Type password, Enter to check, 'd' for details, 'c' to clear, Esc to go back";
+/// Frames of the spinner shown while the breach lookup runs
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
-pub fn render_check_screen(frame: &mut Frame, app: &App) {
+pub fn render_check_screen(frame: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Length(5),
             Constraint::Min(0),
+            Constraint::Length(1),
             Constraint::Length(3),
         ])
-        .split(frame.area());
+        .split(area);
 
-    render_header(frame, chunks[0]);
+    render_header(frame, app, chunks[0]);
     render_password_input(frame, app, chunks[1]);
     render_analysis_area(frame, app, chunks[2]);
-    render_footer(frame, chunks[3]);
+    if let Some(ref analysis) = app.check_result {
+        render_rating_gauge(frame, app, chunks[3], analysis);
+    }
+    render_footer(frame, app, chunks[4]);
 }
 
-fn render_header(frame: &mut Frame, area: Rect) {
-    let title = Paragraph::new(CHECK_TITLE)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(CHECK_BLOCK_TITLE),
-        )
+fn render_header(frame: &mut Frame, app: &App, area: Rect) {
+    let title = Paragraph::new(tr("tui.check.title", &[]))
+        .block(app.theme.block().title(tr("tui.check.block_title", &[])))
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center);
@@ -50,26 +49,23 @@ fn render_header(frame: &mut Frame, area: Rect) {
 }
 
 fn render_password_input(frame: &mut Frame, app: &App, area: Rect) {
-    let input_display = if app.password_input.is_empty() {
-        format!("<{}>", PLACEHOLDER_TEXT)
+    let editing = app.input_mode == InputMode::Editing;
+    let input_display = if app.password_input.is_empty() && !editing {
+        format!("<{}>", placeholder_text(app))
     } else {
-        "*".repeat(app.password_input.len())
+        app.password_input.display(editing)
     };
 
     let input_style = if app.input_mode == InputMode::Editing {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(app.theme.highlight)
             .add_modifier(Modifier::BOLD)
     } else {
         Style::default()
     };
 
     let input = Paragraph::new(input_display)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(PASSWORD_FIELD_TITLE),
-        )
+        .block(app.theme.block().title(tr("tui.check.password", &[])))
         .style(input_style);
     frame.render_widget(input, area);
 }
@@ -78,115 +74,207 @@ fn render_analysis_area(frame: &mut Frame, app: &App, area: Rect) {
     if let Some(ref analysis) = app.check_result {
         render_analysis_details(frame, app, area, analysis);
     } else {
-        let placeholder = Paragraph::new(PLACEHOLDER_TEXT)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(ANALYSIS_FIELD_TITLE),
-            )
+        let placeholder = Paragraph::new(placeholder_text(app))
+            .block(app.theme.block().title(analysis_title()))
             .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::Gray));
+            .style(Style::default().fg(app.theme.muted));
         frame.render_widget(placeholder, area);
     }
 }
 
 fn render_analysis_details(frame: &mut Frame, app: &App, area: Rect, analysis: &PasswordAnalysis) {
-    let rating_color = match analysis.rating.as_str() {
-        s if s.contains("Weak") => Color::Red,
-        s if s.contains("Medium") => Color::Yellow,
-        s if s.contains("Strong") => Color::Green,
-        _ => Color::Green,
-    };
-
+    let check_mark = |passed: bool| if passed { "✓" } else { "✗" };
     let mut lines = vec![
-        format!("Score: {}/100", analysis.score.total),
-        format!("Length: {}", analysis.length),
-        format!("Entropy: {:.2}", analysis.entropy),
+        tr(
+            "tui.check.score",
+            &[("score", &analysis.score.normalized().to_string())],
+        ),
+        tr(
+            "tui.check.length",
+            &[("length", &analysis.length.to_string())],
+        ),
+        tr(
+            "tui.check.entropy",
+            &[
+                ("entropy", &format!("{:.2}", analysis.entropy)),
+                ("observed", &format!("{:.2}", analysis.observed_entropy)),
+            ],
+        ),
+        match analysis.entropy_model {
+            EntropyModel::Character => tr("tui.check.entropy_model_character", &[]),
+            EntropyModel::Passphrase { words } => tr(
+                "tui.check.entropy_model_passphrase",
+                &[("words", &words.to_string())],
+            ),
+        },
         "".to_string(),
-        "Requirements:".to_string(),
-        format!(
-            "  Lowercase: {}",
-            if analysis.has_lowercase { "✓" } else { "✗" }
+        tr("tui.check.requirements", &[]),
+        tr(
+            "tui.check.lowercase",
+            &[("status", check_mark(analysis.has_lowercase))],
         ),
-        format!(
-            "  Uppercase: {}",
-            if analysis.has_uppercase { "✓" } else { "✗" }
+        tr(
+            "tui.check.uppercase",
+            &[("status", check_mark(analysis.has_uppercase))],
         ),
-        format!(
-            "  Digits:    {}",
-            if analysis.has_digit { "✓" } else { "✗" }
+        tr(
+            "tui.check.digits",
+            &[("status", check_mark(analysis.has_digit))],
         ),
-        format!(
-            "  Special:   {}",
-            if analysis.has_special { "✓" } else { "✗" }
+        tr(
+            "tui.check.special",
+            &[("status", check_mark(analysis.has_special))],
         ),
     ];
 
     if app.show_detailed_check {
+        let score = |key: &str, value: u32, max: u32| {
+            tr(
+                key,
+                &[("score", &value.to_string()), ("max", &max.to_string())],
+            )
+        };
         lines.push("".to_string());
-        lines.push("Detailed Scores:".to_string());
-        lines.push(format!(
-            "  Length Score:     {}",
-            analysis.score.length_score
+        lines.push(tr("tui.check.detailed_scores", &[]));
+        lines.push(score(
+            "tui.check.length_score",
+            analysis.score.length_score,
+            PasswordScore::MAX_LENGTH_SCORE,
         ));
-        lines.push(format!(
-            "  Diversity Score:  {}",
-            analysis.score.diversity_score
+        lines.push(score(
+            "tui.check.diversity_score",
+            analysis.score.diversity_score,
+            PasswordScore::MAX_DIVERSITY_SCORE,
         ));
-        lines.push(format!(
-            "  Complexity Score: {}",
-            analysis.score.complexity_score
+        lines.push(score(
+            "tui.check.complexity_score",
+            analysis.score.complexity_score,
+            PasswordScore::MAX_COMPLEXITY_SCORE,
         ));
-        lines.push(format!(
-            "  Entropy Score:    {}",
-            analysis.score.entropy_score
+        lines.push(score(
+            "tui.check.entropy_score",
+            analysis.score.entropy_score,
+            PasswordScore::MAX_ENTROPY_SCORE,
         ));
+        if analysis.score.entropy_penalty > 0 {
+            lines.push(tr(
+                "tui.check.entropy_penalty",
+                &[("penalty", &analysis.score.entropy_penalty.to_string())],
+            ));
+        }
+    }
 
+    let mut text: Vec<Line> = lines.into_iter().map(Line::from).collect();
+    if app.show_detailed_check {
         if !analysis.warnings.is_empty() {
-            lines.push("".to_string());
-            lines.push("Warnings:".to_string());
+            text.push(Line::default());
+            text.push(Line::from(tr("tui.check.warnings", &[])));
+            let chars = app.password_input.value().chars().count();
             for warning in &analysis.warnings {
-                lines.push(format!("  ⚠ {}", warning));
+                text.push(Line::from(format!("  ⚠ {}", warning.message())));
+                if let Some(span) = warning.span() {
+                    text.push(span_line(app, span, chars));
+                }
             }
         }
 
         if !analysis.suggestions.is_empty() {
-            lines.push("".to_string());
-            lines.push("Suggestions:".to_string());
+            text.push(Line::default());
+            text.push(Line::from(tr("tui.check.suggestions", &[])));
             for suggestion in &analysis.suggestions {
-                lines.push(format!("  💡 {}", suggestion));
+                text.push(Line::from(format!("  💡 {}", suggestion.message())));
             }
         }
     }
+    if let Some(breach) = breach_line(app) {
+        text.insert(0, breach);
+    }
 
-    let content = Paragraph::new(lines.join("\n"))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(ANALYSIS_FIELD_TITLE),
-        )
+    let content = Paragraph::new(text)
+        .block(app.theme.block().title(analysis_title()))
         .wrap(Wrap { trim: true });
 
     frame.render_widget(content, area);
+}
 
-    // Render Gauge at the bottom of the analysis area
-    let gauge_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
+/// Show the password masked with the characters of a warning highlighted
+fn span_line(app: &App, span: Span, chars: usize) -> Line<'static> {
+    let (masked, _) = span.markers(chars);
+    let (before, rest) = masked.split_at(span.start.min(masked.len()));
+    let (marked, after) = rest.split_at(span.len().min(rest.len()));
+    Line::from(vec![
+        TextSpan::raw(format!("    {}", before)),
+        TextSpan::styled(
+            marked.to_string(),
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD),
+        ),
+        TextSpan::raw(after.to_string()),
+    ])
+}
+
+/// Describe the breach lookup of the input, None if it was not started
+fn breach_line(app: &App) -> Option<Line<'static>> {
+    if let Some(job) = &app.breach_check {
+        let frame = SPINNER[(job.started.elapsed().as_millis() / 100) as usize % SPINNER.len()];
+        let text = tr(
+            "tui.check.breach_checking",
+            &[("spinner", &frame.to_string())],
+        );
+        return Some(Line::styled(text, Style::default().fg(app.theme.muted)));
+    }
+
+    let line = match app.breach_result.as_ref()? {
+        Ok(0) => Line::styled(
+            tr("tui.check.breach_not_found", &[]),
+            Style::default().fg(app.theme.success),
+        ),
+        Ok(count) => Line::styled(
+            tr("tui.check.breach_found", &[("count", &count.to_string())]),
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Err(error) => Line::styled(
+            tr("tui.check.breach_failed", &[("error", error)]),
+            Style::default().fg(app.theme.error),
+        ),
+    };
+    Some(line)
+}
+
+fn render_rating_gauge(frame: &mut Frame, app: &App, area: Rect, analysis: &PasswordAnalysis) {
+    // Colored by score, the rating text itself is translated
+    let score = analysis.score.normalized();
+    let rating_color = app.theme.rating(score);
 
     let gauge = Gauge::default()
         .block(Block::default().borders(Borders::NONE))
         .gauge_style(Style::default().fg(rating_color))
-        .percent(analysis.score.total.min(100) as u16)
-        .label(format!(
-            "Rating: {} ({}%)",
-            analysis.rating, analysis.score.total
+        .percent(score as u16)
+        .label(tr(
+            "tui.check.rating",
+            &[("rating", &analysis.rating), ("score", &score.to_string())],
         ));
-    frame.render_widget(gauge, gauge_area);
+    frame.render_widget(gauge, area);
 }
 
-fn render_footer(frame: &mut Frame, area: Rect) {
-    let footer = Paragraph::new(FOOTER_TEXT)
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Gray))
+fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let footer = Paragraph::new(hint_line(app, &app.theme))
+        .block(app.theme.block())
         .alignment(Alignment::Center);
     frame.render_widget(footer, area);
 }
+
+fn placeholder_text(app: &App) -> String {
+    if app.input_mode == InputMode::Editing {
+        tr("tui.check.placeholder", &[])
+    } else {
+        tr("tui.check.placeholder_normal", &[])
+    }
+}
+
+fn analysis_title() -> String {
+    tr("tui.check.analysis", &[])
+}
@@ -0,0 +1,44 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+/// Get an area of the given height centered over another area
+///
+/// # Arguments
+///
+/// * `area`: The area to center the dialog over
+/// * `height`: The height of the dialog including its border
+///
+/// # Returns
+///
+/// Returns the dialog area, 70% as wide as `area`
+fn dialog_area(area: Rect, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(70)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Render a dialog centered over an area, hiding what is below it
+///
+/// # Arguments
+///
+/// * `frame`: The frame to render into
+/// * `area`: The area to center the dialog over
+/// * `height`: The height of the dialog including its border
+/// * `dialog`: The content with its block and style, it is wrapped and centered
+pub fn render_dialog(frame: &mut Frame, area: Rect, height: u16, dialog: Paragraph) {
+    let area = dialog_area(area, height);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        dialog
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center),
+        area,
+    );
+}
@@ -1,233 +1,573 @@
+use std::rc::Rc;
+
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Clear, Gauge, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
+    },
 };
 
-use crate::PasswordMode;
-use crate::tui::app::App;
-
-pub fn render_generator_mode_selection(frame: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(10),
-            Constraint::Length(3),
-        ])
-        .split(frame.area());
-
-    let title = Paragraph::new("Select Generator Mode")
-        .block(Block::default().borders(Borders::ALL).title("Generator"))
+use crate::password::{Generator, InsertStyle};
+use crate::tui::app::{App, GeneratorField, GeneratorFocus, InputField, InputMode};
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{screen_layout, tr};
+use crate::tui::text_input::TextInput;
+use crate::{PasswordMode, parse_password_mode};
+
+pub fn render_generator_mode_selection(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = screen_layout(area);
+
+    let title = Paragraph::new(tr("tui.generator.select_mode", &[]))
+        .block(app.theme.block().title(tr("tui.generator.title", &[])))
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center);
     frame.render_widget(title, chunks[0]);
 
     let modes = [
-        ("Random", "Generates passwords with random characters"),
-        ("Pattern", "Generates passwords based on a custom pattern"),
-        ("Phrase", "Generates memorable passphrases from word lists"),
-        (
-            "Deterministic",
-            "Generates consistent passwords from a seed",
-        ),
+        PasswordMode::Random,
+        PasswordMode::Pattern,
+        PasswordMode::Phrase,
+        PasswordMode::Deterministic,
     ];
 
     let items: Vec<ListItem> = modes
         .iter()
         .enumerate()
-        .map(|(idx, (name, desc))| {
+        .map(|(idx, mode)| {
             let style = if app.generator.selected_mode_index == idx {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(app.theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             };
-            let content = format!("{:<15} - {}", name, desc);
+            let content = format!("{:<15} - {}", mode_name(mode), mode_description(mode));
             ListItem::new(content).style(style)
         })
         .collect();
 
     let list = List::new(items)
         .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Available Modes"),
+            app.theme
+                .block()
+                .title(tr("tui.generator.available_modes", &[])),
         )
         .highlight_symbol(">> ");
 
     frame.render_widget(list, chunks[1]);
 
-    let footer = Paragraph::new("↑↓ to navigate, Enter to select, Esc to go back")
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Gray))
+    let footer = Paragraph::new(hint_line(app, &app.theme))
+        .block(app.theme.block())
         .alignment(Alignment::Center);
     frame.render_widget(footer, chunks[2]);
 }
 
-pub fn render_generator_screen(frame: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(10),
-            Constraint::Length(3),
-        ])
-        .split(frame.area());
-
-    let mode_name = match app.generator.mode {
-        PasswordMode::Random => "Random",
-        PasswordMode::Pattern => "Pattern",
-        PasswordMode::Phrase => "Phrase",
-        PasswordMode::Deterministic => "Deterministic",
-    };
+pub fn render_generator_screen(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = screen_layout(area);
 
-    let title = Paragraph::new(format!("Password Generator: {}", mode_name))
-        .block(Block::default().borders(Borders::ALL).title("Generator"))
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center);
+    let title = Paragraph::new(tr(
+        "tui.generator.heading",
+        &[("mode", &mode_name(&app.generator.mode))],
+    ))
+    .block(app.theme.block().title(tr("tui.generator.title", &[])))
+    .style(
+        Style::default()
+            .fg(app.theme.accent)
+            .add_modifier(Modifier::BOLD),
+    )
+    .alignment(Alignment::Center);
     frame.render_widget(title, chunks[0]);
 
-    render_parameters(frame, app, chunks[1]);
+    let panes = generator_panes(chunks[1]);
+    render_parameters(frame, app, panes[0]);
+    render_results(frame, app, panes[1]);
 
-    let footer_text = if let Some(error) = &app.error_message {
-        format!("Error: {}", error)
-    } else if !app.generated_passwords.is_empty() {
-        format!("Generated {} password(s)", app.generated_passwords.len())
+    if let Some(job) = &app.generation {
+        let gauge = Gauge::default()
+            .block(app.theme.block())
+            .gauge_style(Style::default().fg(app.theme.accent))
+            .percent(job.progress.percentage())
+            .label(tr(
+                "tui.generator.progress",
+                &[
+                    ("completed", &job.progress.completed.to_string()),
+                    ("total", &job.progress.total.to_string()),
+                    ("rate", &format!("{:.0}", job.progress.throughput())),
+                ],
+            ));
+        frame.render_widget(gauge, chunks[2]);
+        return;
+    }
+
+    let message = if app.input_field == InputField::PresetName {
+        Some(tr(
+            "tui.generator.preset_prompt",
+            &[("name", &app.preset_name.display(true))],
+        ))
+    } else if app.input_field == InputField::SaveFilename {
+        Some(tr(
+            "tui.generator.save_prompt",
+            &[("filename", &app.save_filename.display(true))],
+        ))
+    } else if app.pending_overwrite {
+        Some(tr(
+            "tui.generator.overwrite_prompt",
+            &[("filename", app.save_filename.value().trim())],
+        ))
     } else {
-        "↑↓ to navigate, ←→ change values, Enter to generate, Esc to change mode".to_string()
+        app.error_message
+            .as_ref()
+            .map(|error| tr("tui.common.error", &[("error", error)]))
     };
 
-    let footer = Paragraph::new(footer_text)
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(if app.error_message.is_some() {
-            Color::Red
-        } else {
-            Color::Gray
-        }))
-        .alignment(Alignment::Center);
+    let footer = match message {
+        Some(message) => {
+            Paragraph::new(message).style(Style::default().fg(if app.error_message.is_some() {
+                app.theme.error
+            } else {
+                app.theme.muted
+            }))
+        }
+        None => Paragraph::new(hint_line(app, &app.theme)),
+    }
+    .block(app.theme.block())
+    .alignment(Alignment::Center);
     frame.render_widget(footer, chunks[2]);
+
+    if let Some(selected) = app.preset_picker {
+        render_preset_picker(frame, app, chunks[1], selected);
+    }
 }
 
-fn render_parameters(frame: &mut Frame, app: &App, area: Rect) {
-    let mut items = Vec::new();
+/// Render the saved presets as a list over the generator body
+fn render_preset_picker(frame: &mut Frame, app: &App, area: Rect, selected: usize) {
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+    let block = app
+        .theme
+        .block()
+        .border_style(Style::default().fg(app.theme.highlight))
+        .title(tr("tui.generator.presets", &[]));
+    frame.render_widget(Clear, area);
 
-    match app.generator.mode {
-        PasswordMode::Random => {
-            items.push((format!("Length: {}", app.generator.length), 0));
-            items.push((format!("Count: {}", app.generator.count), 1));
-        }
-        PasswordMode::Pattern => {
-            items.push((
-                format!(
-                    "Pattern: {}",
-                    if app.generator.pattern.is_empty() {
-                        "<Enter pattern (U=Upper, L=Lower, D=Digit, S=Special)>".to_string()
-                    } else {
-                        app.generator.pattern.clone()
-                    }
-                ),
-                0,
-            ));
-            items.push((format!("Count: {}", app.generator.count), 1));
-        }
-        PasswordMode::Phrase => {
-            items.push((format!("Words: {}", app.generator.words.unwrap_or(4)), 0));
-            items.push((format!("Count: {}", app.generator.count), 1));
-        }
-        PasswordMode::Deterministic => {
-            items.push((
-                format!(
-                    "Seed Env Var: {}",
-                    if app.generator.seed_env.is_empty() {
-                        "<Enter env var name>".to_string()
-                    } else {
-                        app.generator.seed_env.clone()
-                    }
-                ),
-                0,
-            ));
-            items.push((
-                format!(
-                    "Service: {}",
-                    if app.generator.service.is_empty() {
-                        "<Optional>".to_string()
-                    } else {
-                        app.generator.service.clone()
-                    }
-                ),
-                1,
-            ));
-            items.push((
-                format!(
-                    "Salt: {}",
-                    if app.generator.salt.is_empty() {
-                        "<Optional>".to_string()
-                    } else {
-                        app.generator.salt.clone()
-                    }
-                ),
-                2,
-            ));
-            items.push((format!("Count: {}", app.generator.count), 3));
-        }
+    if app.settings.saved.presets.is_empty() {
+        let empty = Paragraph::new(tr("tui.generator.no_presets", &[]))
+            .block(block)
+            .style(Style::default().fg(app.theme.muted))
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, area);
+        return;
     }
 
-    let list_items: Vec<ListItem> = items
+    let items: Vec<ListItem> = app
+        .settings
+        .saved
+        .presets
         .iter()
-        .map(|(text, idx)| {
-            let prefix = if app.selected_index == *idx {
-                ">> "
-            } else {
-                "   "
-            };
-            let style = if app.selected_index == *idx {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            };
-            ListItem::new(format!("{}{}", prefix, text)).style(style)
+        .map(|(name, preset)| {
+            let mode = parse_password_mode(&preset.mode)
+                .map(|mode| mode_name(&mode))
+                .unwrap_or_else(|_| preset.mode.clone());
+            ListItem::new(format!("{:<24} {}", name, mode))
         })
         .collect();
 
-    let mut all_lines = Vec::new();
-    all_lines.extend(list_items);
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
 
-    if !app.generated_passwords.is_empty() {
-        all_lines.push(ListItem::new(""));
-        all_lines.push(ListItem::new("Generated Passwords:"));
-        all_lines.push(ListItem::new("─".repeat(40)));
-        for (idx, password) in app.generated_passwords.iter().enumerate() {
-            all_lines.push(ListItem::new(format!("{}. {}", idx + 1, password)));
-        }
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Split the generator body into the parameters and results panes
+///
+/// Also used by mouse handling to hit-test clicks.
+///
+/// # Arguments
+///
+/// * `area`: The body area of the generator screen
+///
+/// # Returns
+///
+/// Returns the parameters and results areas
+pub fn generator_panes(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area)
+}
+
+/// Get the border style of a pane, highlighting the focused one
+fn pane_border(app: &App, pane: GeneratorFocus) -> Style {
+    if app.generator.focus == pane {
+        Style::default().fg(app.theme.highlight)
+    } else {
+        Style::default().fg(app.theme.border)
     }
+}
 
-    let list = List::new(all_lines)
-        .block(Block::default().borders(Borders::ALL).title("Parameters"))
-        .highlight_style(
+fn render_parameters(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = GeneratorField::rows(&app.generator.mode)
+        .iter()
+        .map(|row| ListItem::new(row_label(app, *row)))
+        .collect();
+
+    // The edited row and its pane stand out so captured keystrokes are obvious
+    let (border, highlight) = if app.input_mode == InputMode::Editing {
+        (
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        )
+    } else {
+        (
+            pane_border(app, GeneratorFocus::Parameters),
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )
+    };
+
+    let block = app
+        .theme
+        .block()
+        .border_style(border)
+        .title(tr("tui.generator.parameters", &[]));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // The estimate sits below the rows so clicks still map to the rows from the top
+    let [rows, estimate] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(inner);
+    if let Some(text) = entropy_estimate(app) {
+        let paragraph = Paragraph::new(text)
+            .style(Style::default().fg(app.theme.muted))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, estimate);
+    }
+
+    let list = List::new(items)
+        .highlight_style(highlight)
         .highlight_symbol(">> ");
 
-    let mut state = ratatui::widgets::ListState::default();
-    let list_index = items.iter().position(|(_, idx)| *idx == app.selected_index);
-    if let Some(idx) = list_index {
-        state.select(Some(idx));
+    let mut state = ListState::default().with_selected(Some(app.selected_index));
+    frame.render_stateful_widget(list, rows, &mut state);
+}
+
+fn render_results(frame: &mut Frame, app: &mut App, area: Rect) {
+    let total = app.generated_passwords.len();
+    let area = if total > 0 {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(area);
+        render_selected_strength(frame, app, rows[1]);
+        rows[0]
+    } else {
+        area
+    };
+    let mut block = app
+        .theme
+        .block()
+        .border_style(pane_border(app, GeneratorFocus::Results))
+        .title(tr(
+            "tui.generator.results",
+            &[("count", &total.to_string())],
+        ));
+
+    if app.generated_passwords.is_empty() {
+        let empty = Paragraph::new(tr("tui.generator.no_results", &[]))
+            .block(block)
+            .style(Style::default().fg(app.theme.muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, area);
+        return;
     }
 
+    let hidden = tr("tui.generator.hidden_result", &[]);
+    let items: Vec<ListItem> = app
+        .generated_passwords
+        .iter()
+        .enumerate()
+        .map(|(idx, generated)| {
+            let password = if app.generator.hide_results {
+                hidden.as_str()
+            } else {
+                generated.password.as_str()
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:>3}. {}  ", idx + 1, password)),
+                Span::styled(
+                    generated.rating.clone(),
+                    Style::default().fg(app.theme.rating(generated.score)),
+                ),
+            ]))
+        })
+        .collect();
+
+    let highlight = if app.generator.focus == GeneratorFocus::Results {
+        Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED)
+    } else {
+        Style::default()
+    };
+    // The offset is kept between frames so the list only scrolls when the selection leaves it
+    let mut state = ListState::default()
+        .with_offset(app.generator.results_offset)
+        .with_selected(Some(app.generator.selected_result));
+    let page = block.inner(area).height as usize;
+    let scrolling = total > page;
+    if scrolling {
+        // Compute the offset the list will use so the indicator matches the visible rows
+        let offset = visible_offset(state.offset(), app.generator.selected_result, page);
+        block = block.title_bottom(
+            Line::from(tr(
+                "tui.generator.results_range",
+                &[
+                    ("first", &(offset + 1).to_string()),
+                    ("last", &(offset + page).min(total).to_string()),
+                    ("total", &total.to_string()),
+                ],
+            ))
+            .right_aligned(),
+        );
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(highlight)
+        .highlight_symbol(">> ");
     frame.render_stateful_widget(list, area, &mut state);
+    app.set_results_viewport(state.offset(), page);
+
+    if scrolling {
+        let mut scrollbar_state =
+            ScrollbarState::new(total.saturating_sub(page)).position(state.offset());
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area.inner(Margin::new(0, 1)),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// Render the strength of the selected result under the list
+///
+/// Uses the analysis kept with each result, nothing is recomputed per frame.
+fn render_selected_strength(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(selected) = app.generated_passwords.get(app.generator.selected_result) else {
+        return;
+    };
+
+    let gauge = Gauge::default()
+        .block(app.theme.block().title(tr("tui.generator.selected", &[])))
+        .gauge_style(Style::default().fg(app.theme.rating(selected.score)))
+        .percent(selected.score.min(100) as u16)
+        .label(tr(
+            "tui.generator.strength",
+            &[
+                ("index", &(app.generator.selected_result + 1).to_string()),
+                ("rating", &selected.rating),
+                ("score", &selected.score.to_string()),
+                ("entropy", &format!("{:.1}", selected.entropy)),
+            ],
+        ));
+    frame.render_widget(gauge, area);
+}
+
+/// Get the first visible row of a list after scrolling the selection into view
+///
+/// Mirrors the scrolling of ratatui's `List` for single-line items.
+fn visible_offset(offset: usize, selected: usize, page: usize) -> usize {
+    if selected < offset {
+        selected
+    } else if page > 0 && selected >= offset + page {
+        selected + 1 - page
+    } else {
+        offset
+    }
+}
+
+/// Get the translated display name of a generator mode
+pub fn mode_name(mode: &PasswordMode) -> String {
+    match mode {
+        PasswordMode::Random => tr("tui.generator.modes.random", &[]),
+        PasswordMode::Pattern => tr("tui.generator.modes.pattern", &[]),
+        PasswordMode::Phrase => tr("tui.generator.modes.phrase", &[]),
+        PasswordMode::Deterministic => tr("tui.generator.modes.deterministic", &[]),
+    }
+}
+
+/// Get the translated description of a generator mode
+fn mode_description(mode: &PasswordMode) -> String {
+    match mode {
+        PasswordMode::Random => tr("tui.generator.descriptions.random", &[]),
+        PasswordMode::Pattern => tr("tui.generator.descriptions.pattern", &[]),
+        PasswordMode::Phrase => tr("tui.generator.descriptions.phrase", &[]),
+        PasswordMode::Deterministic => tr("tui.generator.descriptions.deterministic", &[]),
+    }
+}
+
+/// Render the typed seed as dots and its length, never the seed itself
+fn seed_value(app: &App) -> String {
+    let seed = &app.generator.seed;
+
+    if app.generator.editing_field == Some(GeneratorField::Seed) {
+        seed.display(true)
+    } else if seed.is_empty() {
+        tr("tui.generator.placeholders.seed", &[])
+    } else {
+        tr(
+            "tui.generator.seed_length",
+            &[
+                ("dots", &seed.display(false)),
+                ("count", &seed.len().to_string()),
+            ],
+        )
+    }
+}
+
+/// Render the label and value of a parameter row
+fn row_label(app: &App, row: GeneratorField) -> String {
+    let text_or = |input: &TextInput, placeholder: String| {
+        let editing = app.generator.editing_field == Some(row);
+        if input.is_empty() && !editing {
+            placeholder
+        } else {
+            input.display(editing)
+        }
+    };
+    let optional = || tr("tui.generator.placeholders.optional", &[]);
+
+    let (key, value) = match row {
+        GeneratorField::Length => (
+            "tui.generator.fields.length",
+            numeric_value(app, row, app.generator.length),
+        ),
+        GeneratorField::Count => (
+            "tui.generator.fields.count",
+            numeric_value(app, row, app.generator.count),
+        ),
+        GeneratorField::Words => (
+            "tui.generator.fields.words",
+            numeric_value(app, row, app.generator.words.unwrap_or(4)),
+        ),
+        GeneratorField::PhraseDigits => (
+            "tui.generator.fields.phrase_digits",
+            numeric_value(app, row, app.generator.phrase.digits),
+        ),
+        GeneratorField::PhraseSymbols => (
+            "tui.generator.fields.phrase_symbols",
+            numeric_value(app, row, app.generator.phrase.symbols),
+        ),
+        GeneratorField::InsertStyle => (
+            "tui.generator.fields.insert_style",
+            insert_style_name(app.generator.phrase.style),
+        ),
+        GeneratorField::Pattern => (
+            "tui.generator.fields.pattern",
+            text_or(
+                &app.generator.pattern,
+                tr("tui.generator.placeholders.pattern", &[]),
+            ),
+        ),
+        GeneratorField::SeedEnv => (
+            "tui.generator.fields.seed_env",
+            text_or(
+                &app.generator.seed_env,
+                tr("tui.generator.placeholders.seed_env", &[]),
+            ),
+        ),
+        GeneratorField::Seed => ("tui.generator.fields.seed", seed_value(app)),
+        GeneratorField::Service => (
+            "tui.generator.fields.service",
+            text_or(&app.generator.service, optional()),
+        ),
+        GeneratorField::Salt => (
+            "tui.generator.fields.salt",
+            text_or(&app.generator.salt, optional()),
+        ),
+    };
+
+    tr(key, &[("value", &value)])
+}
+
+/// Get the translated name of a phrase insert style
+fn insert_style_name(style: InsertStyle) -> String {
+    match style {
+        InsertStyle::Between => tr("tui.generator.insert_styles.between", &[]),
+        InsertStyle::Append => tr("tui.generator.insert_styles.append", &[]),
+        InsertStyle::RandomPosition => tr("tui.generator.insert_styles.random_position", &[]),
+    }
+}
+
+/// Describe the entropy of the current parameters, using the value being typed if any
+fn entropy_estimate(app: &App) -> Option<String> {
+    let typed = |field: GeneratorField, value: usize| {
+        if app.input_field == InputField::Generator(field) {
+            app.generator.numeric_input.value().parse().unwrap_or(value)
+        } else {
+            value
+        }
+    };
+    let entropy = Generator::estimate_entropy(
+        &app.generator.mode,
+        typed(GeneratorField::Length, app.generator.length),
+        app.generator.pattern.value(),
+        typed(GeneratorField::Words, app.generator.words.unwrap_or(4)),
+    )?;
+
+    Some(tr(
+        "tui.generator.entropy",
+        &[
+            ("bits", &format!("{:.0}", entropy)),
+            ("time", &crack_time(Generator::crack_time_seconds(entropy))),
+        ],
+    ))
+}
+
+/// Format a crack time in the largest fitting unit
+fn crack_time(seconds: f64) -> String {
+    const MINUTE: f64 = 60.0;
+    const HOUR: f64 = 60.0 * MINUTE;
+    const DAY: f64 = 24.0 * HOUR;
+    const YEAR: f64 = 365.25 * DAY;
+
+    let (key, value) = match seconds {
+        s if s < 1.0 => return tr("tui.generator.crack_time.instant", &[]),
+        s if s < MINUTE => ("tui.generator.crack_time.seconds", s),
+        s if s < HOUR => ("tui.generator.crack_time.minutes", s / MINUTE),
+        s if s < DAY => ("tui.generator.crack_time.hours", s / HOUR),
+        s if s < YEAR => ("tui.generator.crack_time.days", s / DAY),
+        s if s < 100.0 * YEAR => ("tui.generator.crack_time.years", s / YEAR),
+        _ => return tr("tui.generator.crack_time.centuries", &[]),
+    };
+    tr(key, &[("count", &format!("{:.0}", value))])
+}
+
+/// Render a numeric parameter, showing the typed digits with a cursor while it is edited
+fn numeric_value(app: &App, field: GeneratorField, value: usize) -> String {
+    if app.input_field == InputField::Generator(field) {
+        app.generator.numeric_input.display(true)
+    } else {
+        value.to_string()
+    }
 }
@@ -1,69 +1,61 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Layout},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph},
+    layout::{Alignment, Margin, Rect},
+    style::{Modifier, Style},
+    widgets::{Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
 };
 
 use crate::tui::app::App;
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{screen_layout, tr, tr_lines};
 
-pub fn render_help_screen(frame: &mut Frame, _app: &App) {
-    let chunks = Layout::default()
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(3),
-        ])
-        .split(frame.area());
+/// The translation keys of the help sections, each a list of lines
+const SECTIONS: [&str; 5] = [
+    "tui.help.navigation",
+    "tui.help.generator",
+    "tui.help.check",
+    "tui.help.history",
+    "tui.help.settings",
+];
 
-    let title = Paragraph::new("Help")
-        .block(Block::default().borders(Borders::ALL).title("Help"))
+pub fn render_help_screen(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = screen_layout(area);
+
+    let title = Paragraph::new(tr("tui.help.title", &[]))
+        .block(app.theme.block().title(tr("tui.help.title", &[])))
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center);
     frame.render_widget(title, chunks[0]);
 
-    let help_text = vec![
-        "Keyboard Shortcuts:".to_string(),
-        "".to_string(),
-        "Navigation:".to_string(),
-        "  ↑↓     - Navigate up/down".to_string(),
-        "  ←→     - Change values (in generator/settings)".to_string(),
-        "  Enter  - Select/Confirm".to_string(),
-        "  Esc    - Go back/Exit".to_string(),
-        "  q      - Quit".to_string(),
-        "".to_string(),
-        "Generator Mode:".to_string(),
-        "  Random        - Generate random passwords".to_string(),
-        "  Pattern       - Generate from pattern (U=Upper, L=Lower, D=Digit, S=Special)"
-            .to_string(),
-        "  Phrase        - Generate passphrase from wordlist".to_string(),
-        "  Deterministic - Generate deterministic password from seed".to_string(),
-        "".to_string(),
-        "Password Check:".to_string(),
-        "  Type password and press Enter to check".to_string(),
-        "  Press 'd' to toggle detailed view".to_string(),
-        "".to_string(),
-        "Settings:".to_string(),
-        "  Use ←→ to change values".to_string(),
-        "  Press Enter to save changes".to_string(),
-    ];
+    let mut help_text = vec![tr("tui.help.shortcuts", &[])];
+    for key in SECTIONS {
+        help_text.push(String::new());
+        help_text.extend(tr_lines(key));
+    }
+    let help_text = help_text.join("\n");
 
-    let content = Paragraph::new(help_text.join("\n"))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Help & Shortcuts"),
-        )
-        .wrap(ratatui::widgets::Wrap { trim: true });
-    frame.render_widget(content, chunks[1]);
+    let block = app.theme.block().title(tr("tui.help.block_title", &[]));
+    let inner = block.inner(chunks[1]);
+    frame.render_widget(block, chunks[1]);
+
+    let content = Paragraph::new(help_text).wrap(Wrap { trim: true });
+    // The scroll limits depend on the wrapped height, so they are refreshed every frame
+    app.set_help_viewport(content.line_count(inner.width), inner.height as usize);
+    frame.render_widget(content.scroll((app.help.scroll as u16, 0)), inner);
+
+    let mut scrollbar_state = ScrollbarState::new(app.help.max_scroll).position(app.help.scroll);
+    frame.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        chunks[1].inner(Margin::new(0, 1)),
+        &mut scrollbar_state,
+    );
 
-    let footer = Paragraph::new("Press Esc or q to go back")
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Gray))
+    let footer = Paragraph::new(hint_line(app, &app.theme))
+        .block(app.theme.block())
         .alignment(Alignment::Center);
     frame.render_widget(footer, chunks[2]);
 }
@@ -0,0 +1,88 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{List, ListItem, ListState, Paragraph},
+};
+
+use crate::tui::app::App;
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{generator_screen::mode_name, screen_layout, tr};
+
+pub fn render_history_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = screen_layout(area);
+
+    let title = Paragraph::new(tr("tui.history.heading", &[]))
+        .block(app.theme.block().title(tr("tui.history.title", &[])))
+        .style(
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    render_entries(frame, app, chunks[1]);
+    render_footer(frame, app, chunks[2]);
+}
+
+fn render_entries(frame: &mut Frame, app: &App, area: Rect) {
+    let block = app.theme.block().title(tr(
+        "tui.history.passwords",
+        &[("count", &app.history.entries.len().to_string())],
+    ));
+
+    if app.history.entries.is_empty() {
+        let empty = Paragraph::new(tr("tui.history.empty", &[]))
+            .block(block)
+            .style(Style::default().fg(app.theme.muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .history
+        .entries
+        .iter()
+        .map(|entry| {
+            ListItem::new(format!(
+                "{}  {:<13} {:<24} {}",
+                entry.created.format("%H:%M:%S"),
+                mode_name(&entry.mode),
+                entry.parameters,
+                entry.password.as_str()
+            ))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    let mut state = ListState::default().with_selected(Some(app.history.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let footer = if app.history.pending_clear {
+        Paragraph::new(tr(
+            "tui.history.clear_prompt",
+            &[("count", &app.history.entries.len().to_string())],
+        ))
+        .style(Style::default().fg(app.theme.highlight))
+    } else if let Some(error) = &app.error_message {
+        Paragraph::new(tr("tui.common.error", &[("error", error)]))
+            .style(Style::default().fg(app.theme.error))
+    } else {
+        Paragraph::new(hint_line(app, &app.theme))
+    }
+    .block(app.theme.block())
+    .alignment(Alignment::Center);
+    frame.render_widget(footer, area);
+}
@@ -1,54 +1,42 @@
-use lingua_i18n_rs::prelude::Lingua;
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Layout},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{List, ListItem, Paragraph},
 };
 
 use crate::tui::app::App;
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{screen_layout, tr};
 
-pub fn render_main_screen(frame: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(3),
-        ])
-        .split(frame.area());
+pub fn render_main_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = screen_layout(area);
 
-    let title = Paragraph::new(
-        Lingua::t("cli.about", &[]).unwrap_or_else(|_| "kdguard - Password Generator".to_string()),
-    )
-    .block(Block::default().borders(Borders::ALL).title("kdguard"))
-    .style(
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD),
-    )
-    .alignment(Alignment::Center);
+    let title = Paragraph::new(tr("cli.about", &[]))
+        .block(app.theme.block().title("kdguard"))
+        .style(
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center);
     frame.render_widget(title, chunks[0]);
 
     let menu_items = vec![
-        ListItem::new(
-            Lingua::t("tui.main.generate", &[])
-                .unwrap_or_else(|_| "Generate Passwords".to_string()),
-        ),
-        ListItem::new(
-            Lingua::t("tui.main.check", &[]).unwrap_or_else(|_| "Check Password".to_string()),
-        ),
-        ListItem::new(
-            Lingua::t("tui.main.settings", &[]).unwrap_or_else(|_| "Settings".to_string()),
-        ),
-        ListItem::new(Lingua::t("tui.main.help", &[]).unwrap_or_else(|_| "Help".to_string())),
-        ListItem::new(Lingua::t("tui.main.exit", &[]).unwrap_or_else(|_| "Exit".to_string())),
+        ListItem::new(tr("tui.main.generate", &[])),
+        ListItem::new(tr("tui.main.quick", &[])),
+        ListItem::new(tr("tui.main.check", &[])),
+        ListItem::new(tr("tui.main.history", &[])),
+        ListItem::new(tr("tui.main.settings", &[])),
+        ListItem::new(tr("tui.main.help", &[])),
+        ListItem::new(tr("tui.main.exit", &[])),
     ];
 
     let menu = List::new(menu_items)
-        .block(Block::default().borders(Borders::ALL).title("Menu"))
+        .block(app.theme.block().title(tr("tui.main.menu", &[])))
         .highlight_style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol(">> ");
@@ -59,9 +47,8 @@ pub fn render_main_screen(frame: &mut Frame, app: &App) {
         &mut ratatui::widgets::ListState::default().with_selected(Some(app.selected_index)),
     );
 
-    let footer = Paragraph::new("Use ↑↓ to navigate, Enter to select, q/Esc to exit")
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Gray))
+    let footer = Paragraph::new(hint_line(app, &app.theme))
+        .block(app.theme.block())
         .alignment(Alignment::Center);
     frame.render_widget(footer, chunks[2]);
 }
@@ -1,11 +1,125 @@
+mod about_screen;
 mod check_screen;
+mod dialog;
 mod generator_screen;
 mod help_screen;
+mod history_screen;
 mod main_screen;
+mod quick_generate;
 mod settings_screen;
+mod status_bar;
 
+use std::rc::Rc;
+
+use ratatui::layout::{Constraint, Layout, Rect};
+
+pub use crate::i18n::{tr, tr_lines};
+pub use about_screen::render_about_screen;
 pub use check_screen::render_check_screen;
-pub use generator_screen::{render_generator_mode_selection, render_generator_screen};
+pub use dialog::render_dialog;
+pub use generator_screen::{
+    generator_panes, render_generator_mode_selection, render_generator_screen,
+};
 pub use help_screen::render_help_screen;
+pub use history_screen::render_history_screen;
 pub use main_screen::render_main_screen;
+pub use quick_generate::render_quick_generate;
 pub use settings_screen::render_settings_screen;
+pub use status_bar::{render_keymap_overlay, render_status_bar, render_update_dialog};
+
+/// Smallest terminal width the screens are laid out for
+pub const MIN_WIDTH: u16 = 60;
+/// Smallest terminal height the screens are laid out for
+pub const MIN_HEIGHT: u16 = 20;
+/// Body height below which `screen_layout` hides the footer and title
+const MIN_BODY_HEIGHT: u16 = 8;
+
+/// Split the terminal into the screen area and the one-line status bar below it
+///
+/// # Arguments
+///
+/// * `area`: The area of the whole terminal
+///
+/// # Returns
+///
+/// Returns the screen and status bar areas
+pub fn app_layout(area: Rect) -> (Rect, Rect) {
+    let [screen, status_bar] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+    (screen, status_bar)
+}
+
+/// Split the terminal into the title, body, and footer areas shared by the screens
+///
+/// Also used by mouse handling to hit-test clicks against the rendered lists.
+///
+/// # Arguments
+///
+/// * `area`: The screen area above the status bar
+///
+/// # Returns
+///
+/// Returns the title, body, and footer areas
+pub fn screen_layout(area: Rect) -> Rc<[Rect]> {
+    // Short areas drop the footer first and then the title to keep the body usable
+    let footer = if area.height >= MIN_BODY_HEIGHT + 6 {
+        3
+    } else {
+        0
+    };
+    let title = if area.height >= MIN_BODY_HEIGHT + 3 {
+        3
+    } else {
+        0
+    };
+    Layout::default()
+        .constraints([
+            Constraint::Length(title),
+            Constraint::Min(0),
+            Constraint::Length(footer),
+        ])
+        .split(area)
+}
+
+/// Check whether the terminal is smaller than the screens need
+///
+/// # Arguments
+///
+/// * `area`: The area of the whole terminal
+///
+/// # Returns
+///
+/// Returns true if the terminal is narrower than `MIN_WIDTH` or lower than `MIN_HEIGHT`
+pub fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+#[cfg(test)]
+mod tests {
+    /// Collect the dotted paths of all string values below a JSON node
+    fn key_paths(value: &serde_json::Value, prefix: &str, paths: &mut Vec<String>) {
+        if let Some(object) = value.as_object() {
+            for (key, child) in object {
+                key_paths(child, &format!("{}.{}", prefix, key), paths);
+            }
+        } else {
+            paths.push(prefix.to_string());
+        }
+    }
+
+    #[test]
+    fn test_tui_keys_exist_in_every_language() {
+        let en: serde_json::Value =
+            serde_json::from_str(include_str!("../../../languages/en.json")).unwrap();
+        let de: serde_json::Value =
+            serde_json::from_str(include_str!("../../../languages/de.json")).unwrap();
+
+        let mut en_keys = Vec::new();
+        let mut de_keys = Vec::new();
+        key_paths(&en["tui"], "tui", &mut en_keys);
+        key_paths(&de["tui"], "tui", &mut de_keys);
+
+        assert!(!en_keys.is_empty());
+        assert_eq!(en_keys, de_keys);
+    }
+}
@@ -0,0 +1,53 @@
+use ratatui::{
+    Frame,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::Paragraph,
+};
+
+use crate::tui::app::App;
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{render_dialog, tr};
+
+/// Render the password of the quick generate dialog centered over the screen
+pub fn render_quick_generate(frame: &mut Frame, app: &App) {
+    let mut lines = Vec::new();
+    if app.generation.is_some() {
+        lines.push(Line::styled(
+            tr("tui.quick.generating", &[]),
+            Style::default().fg(app.theme.muted),
+        ));
+    } else if let Some(error) = &app.error_message {
+        lines.push(Line::styled(
+            tr("tui.common.error", &[("error", error)]),
+            Style::default().fg(app.theme.error),
+        ));
+    } else if let Some(password) = app.generated_passwords.first() {
+        lines.push(Line::styled(
+            password.password.clone(),
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::styled(
+            tr(
+                "tui.quick.strength",
+                &[
+                    ("rating", &password.rating),
+                    ("score", &password.score.to_string()),
+                ],
+            ),
+            Style::default().fg(app.theme.rating(password.score)),
+        ));
+    }
+    lines.push(Line::default());
+    lines.push(hint_line(app, &app.theme));
+
+    let dialog = Paragraph::new(lines).block(
+        app.theme
+            .block()
+            .border_style(Style::default().fg(app.theme.accent))
+            .title(tr("tui.quick.title", &[])),
+    );
+    render_dialog(frame, frame.area(), 8, dialog);
+}
@@ -1,36 +1,91 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{List, ListItem, ListState, Paragraph},
 };
 
-use crate::tui::app::App;
+use crate::tui::app::{App, MaintenanceAction, SettingsField};
+use crate::tui::hints::hint_line;
+use crate::tui::screens::{generator_screen::mode_name, render_dialog, screen_layout, tr};
 
 const HIGHLIGHT_SYMBOL: &str = ">> ";
-const FOOTER_HELP_TEXT: &str =
-    "Use ↑↓ to navigate, ←→ to change values, Enter to save, Esc to go back";
-
-pub fn render_settings_screen(frame: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(3),
-        ])
-        .split(frame.area());
-
-    render_title(frame, chunks[0]);
+
+pub fn render_settings_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = screen_layout(area);
+
+    render_title(frame, app, chunks[0]);
     render_configuration_list(frame, app, chunks[1]);
     render_footer(frame, app, chunks[2]);
+
+    if let Some(action) = app.settings.pending_action {
+        render_maintenance_confirmation(frame, app, chunks[1], action);
+    } else if let Some(error) = &app.settings.maintenance_error {
+        render_maintenance_error(frame, app, chunks[1], error);
+    }
 }
 
-fn render_title(frame: &mut Frame, area: Rect) {
-    let title = Paragraph::new("Settings")
-        .block(Block::default().borders(Borders::ALL).title("Settings"))
+/// Ask for a y/n confirmation of a maintenance action, styled like the exit prompt
+fn render_maintenance_confirmation(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    action: MaintenanceAction,
+) {
+    let (title, text) = match action {
+        MaintenanceAction::ResetConfig => (
+            tr("tui.settings.reset_config", &[]),
+            tr("tui.settings.reset_confirm", &[]),
+        ),
+        MaintenanceAction::Uninstall => (
+            tr("tui.settings.uninstall", &[]),
+            tr("tui.settings.uninstall_confirm", &[]),
+        ),
+    };
+
+    let dialog = Paragraph::new(text)
+        .block(app.theme.block().title(title))
+        .style(
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        );
+    render_dialog(frame, area, 5, dialog);
+}
+
+/// Show the error of a failed maintenance action until any key is pressed
+fn render_maintenance_error(frame: &mut Frame, app: &App, area: Rect, error: &str) {
+    let text = format!("{}\n\n{}", error, tr("tui.settings.dialog_close", &[]));
+    let dialog = Paragraph::new(text)
+        .block(
+            app.theme
+                .block()
+                .border_style(Style::default().fg(app.theme.error))
+                .title(tr("tui.settings.maintenance_failed", &[])),
+        )
+        .style(Style::default().fg(app.theme.error));
+    render_dialog(frame, area, 6, dialog);
+}
+
+fn render_title(frame: &mut Frame, app: &App, area: Rect) {
+    let heading = if app.settings_dirty() {
+        tr("tui.settings.title_dirty", &[])
+    } else {
+        tr("tui.settings.title", &[])
+    };
+    let heading = match &app.settings.profile {
+        Some(profile) => tr(
+            "tui.settings.profile",
+            &[("title", &heading), ("profile", profile)],
+        ),
+        None => heading,
+    };
+    let title = Paragraph::new(heading)
+        .block(app.theme.block().title(tr("tui.settings.title", &[])))
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center);
@@ -38,25 +93,20 @@ fn render_title(frame: &mut Frame, area: Rect) {
 }
 
 fn render_configuration_list(frame: &mut Frame, app: &App, area: Rect) {
-    let items = vec![
-        ListItem::new(format!("Language: {}", app.settings.language)),
-        ListItem::new(format!("Default Length: {}", app.settings.default_length)),
-        ListItem::new(format!("Default Count: {}", app.settings.default_count)),
-        ListItem::new(format!(
-            "Auto Save: {}",
-            if app.settings.auto_save { "Yes" } else { "No" }
-        )),
-    ];
+    let items: Vec<ListItem> = SettingsField::ROWS
+        .iter()
+        .map(|row| ListItem::new(row_label(app, *row)))
+        .collect();
 
     let list = List::new(items)
         .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Configuration"),
+            app.theme
+                .block()
+                .title(tr("tui.settings.configuration", &[])),
         )
         .highlight_style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol(HIGHLIGHT_SYMBOL);
@@ -66,19 +116,81 @@ fn render_configuration_list(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_stateful_widget(list, area, &mut state);
 }
 
-fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let (footer_text, footer_style) = if let Some(error) = &app.error_message {
-        (format!("Error: {}", error), Style::default().fg(Color::Red))
-    } else {
-        (
-            FOOTER_HELP_TEXT.to_string(),
-            Style::default().fg(Color::Gray),
-        )
+/// Render the label and value of a settings row
+fn row_label(app: &App, row: SettingsField) -> String {
+    let (key, value) = match row {
+        SettingsField::Language => ("tui.settings.language", app.settings.language.clone()),
+        SettingsField::DefaultMode => (
+            "tui.settings.default_mode",
+            mode_name(&app.settings.default_mode),
+        ),
+        SettingsField::DefaultLength => (
+            "tui.settings.default_length",
+            app.settings.default_length.to_string(),
+        ),
+        SettingsField::DefaultCount => (
+            "tui.settings.default_count",
+            app.settings.default_count.to_string(),
+        ),
+        SettingsField::AutoSave => (
+            "tui.settings.auto_save",
+            if app.settings.auto_save {
+                tr("tui.common.yes", &[])
+            } else {
+                tr("tui.common.no", &[])
+            },
+        ),
+        SettingsField::Theme => ("tui.settings.theme", theme_name(&app.settings.theme)),
+        SettingsField::ResetConfig => (
+            "tui.settings.maintenance",
+            tr("tui.settings.reset_config", &[]),
+        ),
+        SettingsField::Uninstall => (
+            "tui.settings.maintenance",
+            tr("tui.settings.uninstall", &[]),
+        ),
     };
 
-    let footer = Paragraph::new(footer_text)
-        .block(Block::default().borders(Borders::ALL))
-        .style(footer_style)
-        .alignment(Alignment::Center);
+    tr(key, &[("value", &value)])
+}
+
+/// Get the translated display name of a theme preset
+fn theme_name(preset: &str) -> String {
+    let key = format!("tui.settings.themes.{}", preset.replace('-', "_"));
+    tr(&key, &[])
+}
+
+fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let footer = if app.settings.pending_exit {
+        Paragraph::new(tr("tui.settings.unsaved_prompt", &[])).style(
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if let Some(error) = &app.error_message {
+        Paragraph::new(tr("tui.common.error", &[("error", error)]))
+            .style(Style::default().fg(app.theme.error))
+    } else {
+        let mut line = hint_line(app, &app.theme);
+        if let Some(range) = app.selected_setting_range() {
+            let allowed = tr(
+                "tui.settings.allowed_range",
+                &[
+                    ("min", &range.start().to_string()),
+                    ("max", &range.end().to_string()),
+                ],
+            );
+            line.spans.insert(
+                0,
+                Span::styled(
+                    format!("{}  ", allowed),
+                    Style::default().fg(app.theme.muted),
+                ),
+            );
+        }
+        Paragraph::new(line)
+    }
+    .block(app.theme.block())
+    .alignment(Alignment::Center);
     frame.render_widget(footer, area);
 }
@@ -0,0 +1,102 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::tui::app::App;
+use crate::tui::keymap::{Action, key_name};
+use crate::tui::screens::{render_dialog, tr};
+
+/// Render the transient notification, config warnings, and the update notice below the screen
+pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(status) = app.status() {
+        let notification =
+            Paragraph::new(format!(" {}", status)).style(Style::default().fg(app.theme.success));
+        frame.render_widget(notification, area);
+    } else if let Some(warning) = &app.config_warning {
+        let warning =
+            Paragraph::new(format!(" {}", warning)).style(Style::default().fg(app.theme.error));
+        frame.render_widget(warning, area);
+    }
+
+    if let Some(tag) = app.available_update() {
+        let notice = Paragraph::new(format!(
+            "{} ",
+            tr("tui.status.update_available", &[("tag", tag)])
+        ))
+        .style(
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Right);
+        frame.render_widget(notice, area);
+    }
+}
+
+/// Render the details of an available update centered over the screen
+pub fn render_update_dialog(frame: &mut Frame, app: &App) {
+    let Some(tag) = app.available_update() else {
+        return;
+    };
+
+    let text = tr(
+        "tui.update.details",
+        &[("tag", tag), ("current", env!("CARGO_PKG_VERSION"))],
+    );
+    let dialog = Paragraph::new(text).block(
+        app.theme
+            .block()
+            .border_style(Style::default().fg(app.theme.highlight))
+            .title(tr("tui.update.title", &[])),
+    );
+    render_dialog(frame, frame.area(), 9, dialog);
+}
+
+/// Render the keys of the current screen's actions centered over the screen
+pub fn render_keymap_overlay(frame: &mut Frame, app: &App) {
+    let actions = Action::for_screen(&app.current_screen);
+    let lines: Vec<Line> = actions
+        .iter()
+        .map(|action| {
+            let keys = app
+                .keymap
+                .keys(*action)
+                .iter()
+                .map(|key| key_name(*key))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let label = tr(&format!("tui.keymap.{}", action.name()), &[]);
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>12}  ", keys),
+                    Style::default()
+                        .fg(app.theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(label),
+            ])
+        })
+        .collect();
+
+    let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 4)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::horizontal([Constraint::Length(40)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let overlay = Paragraph::new(lines).block(
+        app.theme
+            .block()
+            .border_style(Style::default().fg(app.theme.accent))
+            .title(tr("tui.keymap.title", &[]))
+            .title_bottom(tr("tui.keymap.footer", &[])),
+    );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(overlay, area);
+}
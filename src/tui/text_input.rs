@@ -0,0 +1,272 @@
+use ratatui::crossterm::event::KeyCode;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Character shown for each character of a masked value
+const MASK: char = '•';
+/// Character marking the cursor while a field is edited
+const CURSOR: char = '█';
+
+/// A single-line text field with a cursor, shared by the editable fields of the TUI
+///
+/// The value lives in a zeroizing buffer that is grown without leaving copies behind,
+/// so secrets like the seed or the checked password can use it as well.
+#[derive(Clone, Default)]
+pub struct TextInput {
+    value: Zeroizing<String>,
+    /// Cursor position in characters, from 0 to the length of the value
+    cursor: usize,
+    /// Whether the value is shown as dots
+    pub masked: bool,
+    max_length: Option<usize>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of characters the field accepts
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Show the value as dots until `masked` is switched off
+    pub fn with_mask(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Get the number of characters of the value
+    pub fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    /// Get the cursor position in characters
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replace the value and move the cursor to its end
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The new value, cut to the maximum length
+    pub fn set(&mut self, value: &str) {
+        self.clear();
+        for c in value.chars() {
+            self.insert(c);
+        }
+    }
+
+    /// Wipe the value and move the cursor to the start
+    pub fn clear(&mut self) {
+        self.value.zeroize();
+        self.cursor = 0;
+    }
+
+    /// Insert a character at the cursor
+    ///
+    /// # Arguments
+    ///
+    /// * `c`: The character to insert
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the character was inserted, false if the field is full
+    pub fn insert(&mut self, c: char) -> bool {
+        if self.max_length.is_some_and(|max| self.len() >= max) {
+            return false;
+        }
+
+        // `String::insert` reallocates when the buffer is full and frees the old buffer
+        // without wiping it, so the value is moved into a larger zeroizing buffer first
+        if self.value.len() + c.len_utf8() > self.value.capacity() {
+            let mut grown =
+                Zeroizing::new(String::with_capacity((self.value.capacity() * 2).max(32)));
+            grown.push_str(&self.value);
+            self.value = grown;
+        }
+        let index = self.byte_index(self.cursor);
+        self.value.insert(index, c);
+        self.cursor += 1;
+        true
+    }
+
+    /// Apply an editing key
+    ///
+    /// Characters are inserted at the cursor, Backspace and Delete remove the character
+    /// before or at the cursor, and Left, Right, Home, and End move the cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The pressed key
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the value changed
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let index = self.byte_index(self.cursor);
+                self.value.remove(index);
+                true
+            }
+            KeyCode::Delete if self.cursor < self.len() => {
+                let index = self.byte_index(self.cursor);
+                self.value.remove(index);
+                true
+            }
+            KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                false
+            }
+            KeyCode::Right => {
+                self.cursor = (self.cursor + 1).min(self.len());
+                false
+            }
+            KeyCode::Home => {
+                self.cursor = 0;
+                false
+            }
+            KeyCode::End => {
+                self.cursor = self.len();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the text to show for the field
+    ///
+    /// # Arguments
+    ///
+    /// * `editing`: Whether the field is being edited, which shows the cursor
+    ///
+    /// # Returns
+    ///
+    /// Returns the value, or dots if it is masked, with the cursor marked while editing
+    pub fn display(&self, editing: bool) -> String {
+        let mut shown: String = if self.masked {
+            std::iter::repeat_n(MASK, self.len()).collect()
+        } else {
+            self.value.to_string()
+        };
+        if editing {
+            let index = shown
+                .char_indices()
+                .nth(self.cursor)
+                .map_or(shown.len(), |(index, _)| index);
+            shown.insert(index, CURSOR);
+        }
+        shown
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(value: &str) -> TextInput {
+        let mut input = TextInput::new();
+        input.set(value);
+        input
+    }
+
+    #[test]
+    fn test_insert_at_cursor() {
+        let mut input = input("acd");
+        assert_eq!(input.cursor(), 3);
+
+        input.handle_key(KeyCode::Home);
+        input.handle_key(KeyCode::Right);
+        assert!(input.handle_key(KeyCode::Char('b')));
+        assert_eq!(input.value(), "abcd");
+        assert_eq!(input.cursor(), 2);
+
+        input.handle_key(KeyCode::End);
+        input.handle_key(KeyCode::Char('é'));
+        input.handle_key(KeyCode::Left);
+        input.handle_key(KeyCode::Char('ü'));
+        assert_eq!(input.value(), "abcdüé");
+    }
+
+    #[test]
+    fn test_backspace_and_delete() {
+        let mut input = input("abc");
+        input.handle_key(KeyCode::Left);
+
+        assert!(input.handle_key(KeyCode::Backspace));
+        assert_eq!(input.value(), "ac");
+        assert_eq!(input.cursor(), 1);
+        assert!(input.handle_key(KeyCode::Delete));
+        assert_eq!(input.value(), "a");
+        assert!(!input.handle_key(KeyCode::Delete));
+
+        input.handle_key(KeyCode::Home);
+        assert!(!input.handle_key(KeyCode::Backspace));
+        assert_eq!(input.value(), "a");
+    }
+
+    #[test]
+    fn test_cursor_stays_within_value() {
+        let mut input = input("ab");
+        input.handle_key(KeyCode::Right);
+        assert_eq!(input.cursor(), 2);
+        input.handle_key(KeyCode::Home);
+        input.handle_key(KeyCode::Left);
+        assert_eq!(input.cursor(), 0);
+
+        input.clear();
+        assert!(input.is_empty());
+        assert_eq!(input.cursor(), 0);
+    }
+
+    #[test]
+    fn test_max_length() {
+        let mut input = TextInput::new().with_max_length(3);
+        input.set("12345");
+        assert_eq!(input.value(), "123");
+        assert!(!input.handle_key(KeyCode::Char('4')));
+    }
+
+    #[test]
+    fn test_display_masks_and_marks_cursor() {
+        let mut input = input("päss");
+        assert_eq!(input.display(false), "päss");
+        input.handle_key(KeyCode::Left);
+        assert_eq!(input.display(true), "päs█s");
+
+        input.masked = true;
+        assert_eq!(input.display(false), "••••");
+        assert_eq!(input.display(true), "•••█•");
+    }
+
+    #[test]
+    fn test_insert_keeps_contents_when_growing() {
+        let mut input = TextInput::new();
+        let text = "a".repeat(100);
+        for c in text.chars() {
+            input.insert(c);
+        }
+        assert_eq!(input.value(), text);
+        assert!(input.value.capacity() >= 100);
+    }
+}
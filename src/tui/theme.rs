@@ -0,0 +1,187 @@
+use std::str::FromStr;
+
+use ratatui::{
+    style::{Color, Style},
+    widgets::{Block, Borders},
+};
+
+use crate::config::{THEME_PRESETS, ThemeConfig};
+use crate::logging::LoggingManager;
+
+/// Colors used by the TUI screens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Titles, progress, and the field being edited
+    pub accent: Color,
+    /// The selected list entry and pending prompts
+    pub highlight: Color,
+    pub error: Color,
+    pub success: Color,
+    pub border: Color,
+    /// Secondary text such as footers and placeholders
+    pub muted: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The default theme for dark terminal backgrounds
+    pub fn dark() -> Self {
+        Self {
+            accent: Color::Cyan,
+            highlight: Color::Yellow,
+            error: Color::Red,
+            success: Color::Green,
+            border: Color::Reset,
+            muted: Color::Gray,
+        }
+    }
+
+    /// A theme readable on light terminal backgrounds
+    pub fn light() -> Self {
+        Self {
+            accent: Color::Blue,
+            highlight: Color::Magenta,
+            error: Color::Red,
+            success: Color::Rgb(0, 128, 0),
+            border: Color::DarkGray,
+            muted: Color::DarkGray,
+        }
+    }
+
+    /// Bright colors only, for low-vision use and washed-out displays
+    pub fn high_contrast() -> Self {
+        Self {
+            accent: Color::White,
+            highlight: Color::LightYellow,
+            error: Color::LightRed,
+            success: Color::LightGreen,
+            border: Color::White,
+            muted: Color::White,
+        }
+    }
+
+    /// Get a built-in theme by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the preset, one of `THEME_PRESETS`
+    ///
+    /// # Returns
+    ///
+    /// Returns the theme if the preset exists, otherwise None
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Build the theme from the config
+    ///
+    /// An unknown preset or an invalid color falls back to the default theme
+    /// and logs a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The theme config
+    ///
+    /// # Returns
+    ///
+    /// Returns the theme
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        Self::try_from_config(config).unwrap_or_else(|e| {
+            LoggingManager::warn(&format!("{}, using the {} theme", e, THEME_PRESETS[0]));
+            Self::default()
+        })
+    }
+
+    fn try_from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let preset = config.preset();
+        let mut theme = Self::preset(preset).ok_or(format!("Unknown theme: {}", preset))?;
+
+        if let ThemeConfig::Custom(colors) = config {
+            let overrides = [
+                (&colors.accent, &mut theme.accent),
+                (&colors.highlight, &mut theme.highlight),
+                (&colors.error, &mut theme.error),
+                (&colors.success, &mut theme.success),
+                (&colors.border, &mut theme.border),
+                (&colors.muted, &mut theme.muted),
+            ];
+            for (value, color) in overrides {
+                if let Some(value) = value {
+                    *color =
+                        Color::from_str(value).map_err(|_| format!("Invalid color: {}", value))?;
+                }
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Get a bordered block in the border color
+    pub fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.border))
+    }
+
+    /// Get the color of a strength score
+    pub fn rating(&self, score: u32) -> Color {
+        match score {
+            0..=40 => self.error,
+            41..=60 => self.highlight,
+            _ => self.success,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ThemeColors;
+
+    #[test]
+    fn test_every_preset_exists() {
+        for name in THEME_PRESETS {
+            assert!(Theme::preset(name).is_some(), "missing preset {}", name);
+        }
+        assert_eq!(Theme::preset("solarized"), None);
+    }
+
+    #[test]
+    fn test_theme_from_config() {
+        let theme = Theme::from_config(&ThemeConfig::Preset("light".to_string()));
+        assert_eq!(theme, Theme::light());
+
+        let theme = Theme::from_config(&ThemeConfig::Custom(ThemeColors {
+            preset: Some("high-contrast".to_string()),
+            accent: Some("#00afff".to_string()),
+            error: Some("lightred".to_string()),
+            ..ThemeColors::default()
+        }));
+        assert_eq!(theme.accent, Color::Rgb(0, 175, 255));
+        assert_eq!(theme.error, Color::LightRed);
+        assert_eq!(theme.success, Theme::high_contrast().success);
+    }
+
+    #[test]
+    fn test_invalid_theme_falls_back_to_default() {
+        let theme = Theme::from_config(&ThemeConfig::Custom(ThemeColors {
+            preset: Some("light".to_string()),
+            accent: Some("not-a-color".to_string()),
+            ..ThemeColors::default()
+        }));
+        assert_eq!(theme, Theme::default());
+
+        let theme = Theme::from_config(&ThemeConfig::Preset("neon".to_string()));
+        assert_eq!(theme, Theme::default());
+    }
+}
@@ -1,34 +1,314 @@
-use ratatui::Frame;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::Style,
+    widgets::{Paragraph, Wrap},
+};
 
-use super::app::{App, CurrentScreen};
+use super::app::{App, CurrentScreen, InputField};
 use super::screens::{
-    render_check_screen, render_generator_mode_selection, render_generator_screen,
-    render_help_screen, render_main_screen, render_settings_screen,
+    MIN_HEIGHT, MIN_WIDTH, app_layout, render_about_screen, render_check_screen, render_dialog,
+    render_generator_mode_selection, render_generator_screen, render_help_screen,
+    render_history_screen, render_keymap_overlay, render_main_screen, render_quick_generate,
+    render_settings_screen, render_status_bar, render_update_dialog, too_small, tr,
 };
 
 pub fn ui(frame: &mut Frame, app: &mut App) {
+    if too_small(frame.area()) {
+        render_too_small(frame, app);
+        return;
+    }
+
+    let (area, status_bar) = app_layout(frame.area());
+    render_status_bar(frame, app, status_bar);
+    render_screen(frame, app, area);
+
+    if app.quick_generate.is_some() {
+        render_quick_generate(frame, app);
+    }
+
+    if app.show_keymap {
+        render_keymap_overlay(frame, app);
+    }
+
+    if app.show_update_dialog {
+        render_update_dialog(frame, app);
+    }
+}
+
+/// Render the current screen into the area above the status bar
+fn render_screen(frame: &mut Frame, app: &mut App, area: Rect) {
     match app.current_screen {
-        CurrentScreen::Main => render_main_screen(frame, app),
-        CurrentScreen::GeneratorModeSelection => render_generator_mode_selection(frame, app),
-        CurrentScreen::Generator => render_generator_screen(frame, app),
-        CurrentScreen::Settings => render_settings_screen(frame, app),
-        CurrentScreen::Help => render_help_screen(frame, app),
-        CurrentScreen::Check => render_check_screen(frame, app),
-        CurrentScreen::Exit => {
-            use ratatui::layout::{Alignment, Constraint, Layout};
-            use ratatui::style::{Color, Modifier, Style};
-            use ratatui::widgets::{Block, Borders, Paragraph};
-
-            let chunks = Layout::default()
-                .constraints([Constraint::Length(5)])
-                .split(frame.area());
-
-            let exit_text = "Are you sure you want to exit? (y/n)";
-            let exit = Paragraph::new(exit_text)
-                .block(Block::default().borders(Borders::ALL).title("Exit"))
-                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                .alignment(Alignment::Center);
-            frame.render_widget(exit, chunks[0]);
+        CurrentScreen::Main => render_main_screen(frame, app, area),
+        CurrentScreen::GeneratorModeSelection => render_generator_mode_selection(frame, app, area),
+        CurrentScreen::Generator => render_generator_screen(frame, app, area),
+        CurrentScreen::Settings => render_settings_screen(frame, app, area),
+        CurrentScreen::Help => render_help_screen(frame, app, area),
+        CurrentScreen::About => render_about_screen(frame, app, area),
+        CurrentScreen::Check => render_check_screen(frame, app, area),
+        CurrentScreen::History => render_history_screen(frame, app, area),
+        CurrentScreen::Exit => render_exit_screen(frame, app, area),
+    }
+}
+
+/// Render the exit confirmation, which offers to save passwords that would be lost
+fn render_exit_screen(frame: &mut Frame, app: &App, area: Rect) {
+    use ratatui::style::Modifier;
+
+    let unsaved = app.unsaved_results();
+    let mut lines = vec![if app.input_field == InputField::SaveFilename {
+        tr(
+            "tui.generator.save_prompt",
+            &[("filename", &app.save_filename.display(true))],
+        )
+    } else if app.pending_overwrite {
+        tr(
+            "tui.generator.overwrite_prompt",
+            &[("filename", app.save_filename.value().trim())],
+        )
+    } else if unsaved > 0 {
+        tr("tui.exit.unsaved", &[("count", &unsaved.to_string())])
+            + "\n"
+            + &tr("tui.exit.unsaved_choices", &[])
+    } else {
+        tr("tui.exit.confirm", &[])
+    }];
+    if let Some(error) = &app.error_message {
+        lines.push(tr("tui.common.error", &[("error", error)]));
+    }
+
+    let height = if unsaved > 0 { 7 } else { 5 };
+    let exit = Paragraph::new(lines.join("\n"))
+        .block(app.theme.block().title(tr("tui.exit.title", &[])))
+        .style(
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        );
+    render_dialog(frame, area, height, exit);
+}
+
+/// Render a notice instead of the screens while the terminal is below the minimum size
+fn render_too_small(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let text = tr(
+        "tui.too_small",
+        &[
+            ("min_width", &MIN_WIDTH.to_string()),
+            ("min_height", &MIN_HEIGHT.to_string()),
+            ("width", &area.width.to_string()),
+            ("height", &area.height.to_string()),
+        ],
+    );
+    let [area] = Layout::vertical([Constraint::Length(2)])
+        .flex(Flex::Center)
+        .areas(area);
+    let notice = Paragraph::new(text)
+        .style(Style::default().fg(app.theme.error))
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Center);
+    frame.render_widget(notice, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordMode;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    const SCREENS: [CurrentScreen; 9] = [
+        CurrentScreen::Main,
+        CurrentScreen::GeneratorModeSelection,
+        CurrentScreen::Generator,
+        CurrentScreen::Settings,
+        CurrentScreen::Help,
+        CurrentScreen::About,
+        CurrentScreen::Check,
+        CurrentScreen::History,
+        CurrentScreen::Exit,
+    ];
+
+    fn screen_text(terminal: &Terminal<TestBackend>) -> String {
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_small_terminal_shows_notice() {
+        for (width, height) in [(1, 1), (20, 5), (59, 24), (80, 19)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            let mut app = App::new();
+            app.current_screen = CurrentScreen::Generator;
+            terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+            let text = screen_text(&terminal);
+            if width >= 40 {
+                assert!(
+                    text.contains(&format!("have {}x{}", width, height)),
+                    "{}",
+                    text
+                );
+            }
+            assert!(!text.contains("Generator"), "{}", text);
         }
+
+        let mut terminal = Terminal::new(TestBackend::new(MIN_WIDTH, MIN_HEIGHT)).unwrap();
+        let mut app = App::new();
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        assert!(!screen_text(&terminal).contains("too small"));
+    }
+
+    #[test]
+    fn test_screens_render_at_small_sizes() {
+        for screen in SCREENS {
+            for (width, height) in [(1, 1), (10, 4), (30, 8), (45, 12), (60, 20), (80, 24)] {
+                let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+                let mut app = App::new();
+                app.generator.mode = PasswordMode::Deterministic;
+                app.show_keymap = true;
+                app.current_screen = screen;
+                // Bypass the size check so the screen layouts themselves are exercised
+                terminal
+                    .draw(|frame| render_screen(frame, &mut app, frame.area()))
+                    .unwrap();
+                terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_quick_generate_dialog_shows_password() {
+        let mut terminal = Terminal::new(TestBackend::new(MIN_WIDTH, MIN_HEIGHT)).unwrap();
+        let mut app = App::new();
+        app.open_quick_generate();
+        while app.generation.is_some() {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            app.tick();
+        }
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+        let text = screen_text(&terminal);
+        assert!(text.contains("Quick Generate"), "{}", text);
+        assert!(text.contains("Esc close"), "{}", text);
+        let password = &app.generated_passwords[0].password;
+        assert!(text.contains(&password[..8]), "{}", text);
+    }
+
+    #[test]
+    fn test_results_scroll_with_fifty_entries() {
+        use crate::tui::app::{GeneratedPassword, GeneratorFocus};
+        use ratatui::crossterm::event::KeyCode;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Generator;
+        app.generated_passwords = (1..=50)
+            .map(|n| GeneratedPassword {
+                password: format!("password-{:02}", n),
+                score: 0,
+                rating: String::new(),
+                entropy: 0.0,
+            })
+            .collect();
+        app.generator.focus = GeneratorFocus::Results;
+
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        let text = screen_text(&terminal);
+        let page = app.generator.results_page;
+        assert!(page < 50);
+        assert!(text.contains(&format!("1–{} of 50", page)), "{}", text);
+        assert!(!text.contains("password-50"));
+
+        app.handle_input(KeyCode::End);
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        let text = screen_text(&terminal);
+        assert_eq!(app.generator.results_offset, 50 - page);
+        assert!(
+            text.contains(&format!("{}–50 of 50", 51 - page)),
+            "{}",
+            text
+        );
+        assert!(text.contains(">>  50. password-50"), "{}", text);
+        assert!(!text.contains("password-01"));
+
+        // Moving up inside the visible rows keeps the offset, the selection is the true index
+        app.handle_input(KeyCode::Up);
+        app.handle_input(KeyCode::PageUp);
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        assert_eq!(app.generator.selected_result, 48 - page);
+        assert_eq!(app.generator.results_offset, 48 - page);
+        let text = screen_text(&terminal);
+        assert!(
+            text.contains(&format!(">> {:>3}. password-{:02}", 49 - page, 49 - page)),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn test_strength_strip_follows_the_selected_result() {
+        use crate::tui::app::{GeneratedPassword, GeneratorFocus};
+        use ratatui::crossterm::event::KeyCode;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Generator;
+        app.generated_passwords = vec![
+            GeneratedPassword {
+                password: "aaaa".to_string(),
+                score: 12,
+                rating: "Weak".to_string(),
+                entropy: 18.8,
+            },
+            GeneratedPassword {
+                password: "Xk9#mQ2$vL7!".to_string(),
+                score: 91,
+                rating: "Strong".to_string(),
+                entropy: 78.7,
+            },
+        ];
+        app.generator.focus = GeneratorFocus::Results;
+
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        let text = screen_text(&terminal);
+        assert!(text.contains("#1: Weak (12%), 18.8 bits"), "{}", text);
+
+        app.handle_input(KeyCode::Down);
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        let text = screen_text(&terminal);
+        assert!(text.contains("#2: Strong (91%), 78.7 bits"), "{}", text);
+        assert!(!text.contains("18.8 bits"), "{}", text);
+    }
+
+    #[test]
+    fn test_saved_results_stay_hidden_until_shown() {
+        use crate::tui::app::{GeneratedPassword, GeneratorFocus};
+        use ratatui::crossterm::event::KeyCode;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Generator;
+        app.generated_passwords = vec![GeneratedPassword {
+            password: "secret-password".to_string(),
+            score: 0,
+            rating: String::new(),
+            entropy: 0.0,
+        }];
+        app.generator.focus = GeneratorFocus::Results;
+        app.generator.hide_results = true;
+
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        let text = screen_text(&terminal);
+        assert!(!text.contains("secret-password"), "{}", text);
+        assert!(text.contains("v to show"), "{}", text);
+
+        app.handle_input(KeyCode::Char('v'));
+        terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        assert!(screen_text(&terminal).contains("secret-password"));
     }
 }
@@ -1,115 +1,654 @@
-use std::{env::consts::OS, fs};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+};
 
+use chrono::Local;
 use inquire::Confirm;
-use lingua_i18n_rs::prelude::Lingua;
 
 use crate::config::Config;
 use crate::errors::UninstallError;
-use crate::logging::LoggingManager;
+use crate::i18n::tr;
+use crate::installer::InstallManager;
+use crate::logging::{LoggingManager, Redactable};
 
-pub struct UninstallManager;
+/// File in the home directory the config is copied to before it is removed
+const CONFIG_BACKUP_FILE: &str = "kdguard-config-backup.toml";
 
-impl UninstallManager {
-    /// Uninstall kdguard
+/// Something the uninstall writes or removes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UninstallItem {
+    ConfigBackup,
+    ConfigDirectory,
+    LanguagesDirectory,
+    LogDirectory,
+    Alias,
+    RollbackCopy,
+    Executable,
+    InstallDirectory,
+}
+
+impl UninstallItem {
+    /// Get the localized name of the item
+    pub fn label(&self) -> String {
+        let key = match self {
+            Self::ConfigBackup => "config_backup",
+            Self::ConfigDirectory => "config_directory",
+            Self::LanguagesDirectory => "languages_directory",
+            Self::LogDirectory => "log_directory",
+            Self::Alias => "alias",
+            Self::RollbackCopy => "rollback_copy",
+            Self::Executable => "executable",
+            Self::InstallDirectory => "install_directory",
+        };
+        tr(&format!("cli.cli_commands.uninstall.items.{}", key), &[])
+    }
+}
+
+/// The paths of an installation, resolved once so tests can point them elsewhere
+#[derive(Debug, Clone)]
+pub struct UninstallPaths {
+    pub config_dir: PathBuf,
+    /// None if the log directory cannot be found, e.g. logging was never set up
+    pub log_dir: Option<PathBuf>,
+    pub executable: PathBuf,
+    /// Removed as a whole instead of only the executable, the install folder on Windows
+    pub install_dir: Option<PathBuf>,
+    pub backup: PathBuf,
+}
+
+impl UninstallPaths {
+    /// Resolve the paths of the running installation, creating nothing
+    ///
+    /// The log directory comes from `LoggingManager::existing_logging_path`, so it
+    /// follows `--config` and the platform fallbacks like the logger does.
     ///
     /// # Returns
     ///
-    /// Returns Ok(()) if successful, otherwise an error
-    pub fn uninstall() -> Result<(), UninstallError> {
-        LoggingManager::info("Starting uninstall process");
-
-        let config_path = Config::get_config_path().map_err(|e| {
+    /// Returns the paths, otherwise an error if the config or executable cannot be found
+    pub fn resolve() -> Result<Self, UninstallError> {
+        let config_dir = Config::get_config_dir().map_err(|e| {
             let error = format!("Failed to get config path: {}", e);
             LoggingManager::error(&error);
             UninstallError::GetConfigPathError(error)
         })?;
-        let install_path = std::env::current_exe().map_err(|e| {
+        let executable = std::env::current_exe().map_err(|e| {
             let error = format!("Failed to get current executable path: {}", e);
             LoggingManager::error(&error);
             UninstallError::GetExecutablePathError(error)
         })?;
+        let log_dir = LoggingManager::existing_logging_path();
+        let install_dir = if cfg!(windows) {
+            Some(
+                executable
+                    .parent()
+                    .ok_or(UninstallError::InvalidInstallPath)?
+                    .to_path_buf(),
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            config_dir,
+            log_dir,
+            executable,
+            install_dir,
+            backup: dirs::home_dir()
+                .unwrap_or_default()
+                .join(CONFIG_BACKUP_FILE),
+        })
+    }
+
+    /// Get the path the config backup is written to
+    ///
+    /// A backup left by an earlier uninstall is kept, the new one then gets the current
+    /// time in its name.
+    ///
+    /// # Returns
+    ///
+    /// Returns `backup` if it does not exist yet, otherwise the first free timestamped path
+    pub fn backup_target(&self) -> PathBuf {
+        if !self.backup.exists() {
+            return self.backup.clone();
+        }
+
+        let stem = self
+            .backup
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let stamp = Local::now().format("%Y%m%d-%H%M%S");
+        (0..)
+            .map(|n| match n {
+                0 => format!("{}-{}.toml", stem, stamp),
+                n => format!("{}-{}-{}.toml", stem, stamp, n),
+            })
+            .map(|name| self.backup.with_file_name(name))
+            .find(|path| !path.exists())
+            .unwrap_or_else(|| self.backup.clone())
+    }
 
-        LoggingManager::info(&format!("Config path: {}", config_path.display()));
-        LoggingManager::info(&format!("Install path: {}", install_path.display()));
+    /// List what the uninstall removes, in order
+    ///
+    /// # Arguments
+    ///
+    /// * `backup`: Whether the config is copied to `backup` first
+    ///
+    /// # Returns
+    ///
+    /// Returns the items with their paths, whether they exist or not
+    pub fn planned(&self, backup: bool) -> Vec<(UninstallItem, PathBuf)> {
+        let mut items = Vec::new();
+        if backup {
+            items.push((UninstallItem::ConfigBackup, self.backup_target()));
+        }
+        items.push((UninstallItem::ConfigDirectory, self.config_dir.clone()));
+        items.push((
+            UninstallItem::LanguagesDirectory,
+            self.config_dir.join("languages"),
+        ));
+        if let Some(log_dir) = &self.log_dir {
+            items.push((UninstallItem::LogDirectory, log_dir.clone()));
+        }
 
-        let confirm_msg = Lingua::t("cli.cli_commands.uninstall.confirm", &[]).unwrap();
+        let install_dir = self
+            .install_dir
+            .as_deref()
+            .or(self.executable.parent())
+            .unwrap_or(Path::new(""));
+        items.push((
+            UninstallItem::Alias,
+            install_dir.join(InstallManager::alias_name()),
+        ));
+        // Kept by the updater for a rollback
+        for suffix in [".prev", ".prev.version"] {
+            let mut name = self
+                .executable
+                .file_name()
+                .unwrap_or_default()
+                .to_os_string();
+            name.push(suffix);
+            items.push((
+                UninstallItem::RollbackCopy,
+                self.executable.with_file_name(name),
+            ));
+        }
+        match &self.install_dir {
+            Some(install_dir) => items.push((UninstallItem::InstallDirectory, install_dir.clone())),
+            None => items.push((UninstallItem::Executable, self.executable.clone())),
+        }
+        items
+    }
 
-        let confirm = Confirm::new(&confirm_msg)
-            .with_default(false)
-            .prompt()
-            .map_err(|e| {
-                let error = format!("Failed to get user confirmation: {}", e);
-                LoggingManager::error(&error);
-                UninstallError::GetConfigPathError(error)
-            })?;
+    /// List the files in the directories removed as a whole that kdguard did not create
+    ///
+    /// The config directory is always removed as a whole, the install directory only on
+    /// Windows, elsewhere only the known files next to the executable are.
+    ///
+    /// # Returns
+    ///
+    /// Returns the paths of the other files, sorted
+    pub fn foreign_files(&self) -> Vec<PathBuf> {
+        let mut foreign =
+            Self::unknown_entries(&self.config_dir, &Config::owned_files(&self.config_dir));
+        if let Some(install_dir) = &self.install_dir {
+            foreign.extend(Self::unknown_entries(
+                install_dir,
+                &InstallManager::installed_files(&self.executable),
+            ));
+        }
+        foreign.sort();
+        foreign
+    }
+
+    /// List the entries of a directory that are not among the known ones
+    fn unknown_entries(dir: &Path, known: &[PathBuf]) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| !known.contains(path))
+            .collect()
+    }
+}
+
+/// How `kdguard uninstall` runs
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UninstallOptions {
+    /// Skip the confirmations and back up the config, required when stdin is not a terminal
+    pub assume_yes: bool,
+    /// Offer to copy the config to the home directory before removing it
+    pub backup: bool,
+    /// Only list what would be removed
+    pub dry_run: bool,
+    /// Remove the config and install directories even if they hold files kdguard did not
+    /// create
+    pub force: bool,
+}
+
+/// The outcome of one item of an uninstall
+#[derive(Debug)]
+pub struct UninstallStep {
+    pub item: UninstallItem,
+    pub path: PathBuf,
+    /// None if the item was removed, or written for the backup
+    pub error: Option<String>,
+}
+
+pub struct UninstallManager;
+
+impl UninstallManager {
+    /// Uninstall kdguard
+    ///
+    /// # Arguments
+    ///
+    /// * `options`: How to uninstall
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, otherwise an error
+    pub fn uninstall(options: UninstallOptions) -> Result<(), UninstallError> {
+        LoggingManager::info("Starting uninstall process");
+
+        let paths = UninstallPaths::resolve()?;
+        LoggingManager::info(&format!(
+            "Config directory: {}",
+            Redactable(paths.config_dir.display())
+        ));
+        LoggingManager::info(&format!(
+            "Install path: {}",
+            Redactable(paths.executable.display())
+        ));
+
+        if options.dry_run {
+            Self::print_plan(&paths, options.backup);
+            return Ok(());
+        }
+
+        // Checked before asking, the answer would not change it
+        let foreign = paths.foreign_files();
+        if !options.force && !foreign.is_empty() {
+            return Err(Self::refuse(&foreign));
+        }
+
+        if !options.assume_yes && !std::io::stdin().is_terminal() {
+            LoggingManager::error("Uninstall needs a confirmation but stdin is not a terminal");
+            return Err(UninstallError::ConfirmationRequired);
+        }
+        let confirm_msg = tr("cli.cli_commands.uninstall.confirm", &[]);
 
+        let confirm = options.assume_yes || Self::prompt(&confirm_msg, false)?;
         if !confirm {
             LoggingManager::info("Uninstall cancelled by user");
-            println!(
-                "{}",
-                Lingua::t("cli.cli_commands.uninstall.cancelled", &[]).unwrap()
-            );
+            println!("{}", tr("cli.cli_commands.uninstall.cancelled", &[]));
             return Ok(());
         }
 
-        LoggingManager::info("Removing config directory");
-        let config_dir = config_path
-            .parent()
-            .ok_or(UninstallError::InvalidConfigPath)?;
-        fs::remove_dir_all(config_dir).map_err(|e| {
-            let error = format!("Failed to remove config directory: {}", e);
-            LoggingManager::error(&error);
-            UninstallError::RemoveConfigDirectoryError(error)
-        })?;
-        LoggingManager::info("Config directory removed successfully");
-
-        if OS == "Windows" {
-            LoggingManager::info("Removing Windows installation");
-            let install_dir = install_path
-                .parent()
-                .ok_or(UninstallError::InvalidInstallPath)?;
-            let alias_path = install_dir.join("kdg.exe");
-            if alias_path.exists() {
-                LoggingManager::info("Removing alias file");
-                fs::remove_file(&alias_path).map_err(|e| {
-                    let error = format!("Failed to remove alias file: {}", e);
-                    LoggingManager::error(&error);
-                    UninstallError::RemoveAliasError(error)
-                })?;
-            }
-            fs::remove_dir_all(install_dir).map_err(|e| {
-                let error = format!("Failed to remove install directory: {}", e);
-                LoggingManager::error(&error);
-                UninstallError::RemoveInstallDirectoryError(error)
-            })?;
-        } else {
-            LoggingManager::info("Removing Unix installation");
-            let install_dir = install_path
-                .parent()
-                .ok_or(UninstallError::InvalidInstallPath)?;
-            let alias_path = install_dir.join("kdg");
-            if alias_path.exists() {
-                LoggingManager::info("Removing alias file");
-                fs::remove_file(&alias_path).map_err(|e| {
-                    let error = format!("Failed to remove alias file: {}", e);
-                    LoggingManager::error(&error);
-                    UninstallError::RemoveAliasError(error)
-                })?;
+        let backup = options.backup
+            && paths.config_dir.join("config.toml").exists()
+            && (options.assume_yes
+                || Self::prompt(
+                    &tr(
+                        "cli.cli_commands.uninstall.backup_confirm",
+                        &[("path", &paths.backup_target().display().to_string())],
+                    ),
+                    true,
+                )?);
+
+        let steps = Self::remove(&paths, backup, options.force)?;
+        let failed = steps.iter().filter(|step| step.error.is_some()).count();
+        for step in &steps {
+            let path = step.path.display().to_string();
+            match &step.error {
+                None => println!("\x1b[1;32m✓\x1b[0m {}: {}", step.item.label(), path),
+                Some(error) => println!(
+                    "\x1b[1;31m✗\x1b[0m {}: {} ({})",
+                    step.item.label(),
+                    path,
+                    error
+                ),
             }
-            fs::remove_file(install_path).map_err(|e| {
-                let error = format!("Failed to remove executable: {}", e);
-                LoggingManager::error(&error);
-                UninstallError::RemoveExecutableError(error)
-            })?;
+        }
+
+        if failed > 0 {
+            LoggingManager::error(&format!("Uninstall left {} item(s) behind", failed));
+            return Err(UninstallError::Incomplete(failed));
         }
 
         LoggingManager::info("Uninstall completed successfully");
         println!(
             "\n\x1b[1;32m{}\x1b[0m",
-            Lingua::t("cli.cli_commands.uninstall.success", &[]).unwrap()
+            tr("cli.cli_commands.uninstall.success", &[])
         );
         println!("{}", "=".repeat(50));
 
         Ok(())
     }
+
+    /// Print every path an uninstall would write or remove, changing nothing
+    ///
+    /// # Arguments
+    ///
+    /// * `paths`: The paths of the installation
+    /// * `backup`: Whether the config would be backed up
+    pub fn print_plan(paths: &UninstallPaths, backup: bool) {
+        println!("{}", tr("cli.cli_commands.uninstall.dry_run", &[]));
+        for (item, path) in paths.planned(backup) {
+            let state = match item {
+                UninstallItem::ConfigBackup => "written",
+                _ if path.exists() => "exists",
+                _ => "missing",
+            };
+            println!(
+                "  {}: {} ({})",
+                item.label(),
+                path.display(),
+                tr(&format!("cli.cli_commands.uninstall.{}", state), &[])
+            );
+        }
+
+        let foreign = paths.foreign_files();
+        if !foreign.is_empty() {
+            println!(
+                "\x1b[1;33m{}\x1b[0m",
+                tr("cli.cli_commands.uninstall.foreign_files", &[])
+            );
+            for path in foreign {
+                println!("  {}", path.display());
+            }
+        }
+    }
+
+    /// Log and build the error of a directory to remove that holds other files
+    fn refuse(foreign: &[PathBuf]) -> UninstallError {
+        let files: Vec<String> = foreign
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        LoggingManager::error(&format!(
+            "The config or install directory holds {} file(s) kdguard did not create",
+            foreign.len()
+        ));
+        UninstallError::ForeignFiles(files.join(", "))
+    }
+
+    /// Ask a yes/no question
+    fn prompt(message: &str, default: bool) -> Result<bool, UninstallError> {
+        Confirm::new(message)
+            .with_default(default)
+            .prompt()
+            .map_err(|e| {
+                let error = format!("Failed to get user confirmation: {}", e);
+                LoggingManager::error(&error);
+                UninstallError::GetConfigPathError(error)
+            })
+    }
+
+    /// Remove the items of an installation, carrying on past failures
+    ///
+    /// Items that do not exist are left out. The config directory is kept when its
+    /// backup fails, so it is never lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths`: The paths of the installation
+    /// * `backup`: Whether to copy the config file to `paths.backup` first
+    /// * `force`: Remove the config and install directories even if they hold files of
+    ///   others
+    ///
+    /// # Returns
+    ///
+    /// Returns one step per item that was there, with the error if it could not be
+    /// removed, else returns an error without removing anything if the config or
+    /// install directory holds files kdguard did not create
+    pub fn remove(
+        paths: &UninstallPaths,
+        backup: bool,
+        force: bool,
+    ) -> Result<Vec<UninstallStep>, UninstallError> {
+        let foreign = paths.foreign_files();
+        if !force && !foreign.is_empty() {
+            return Err(Self::refuse(&foreign));
+        }
+
+        let mut steps: Vec<UninstallStep> = Vec::new();
+        for (item, path) in paths.planned(backup) {
+            let result = match item {
+                UninstallItem::ConfigBackup => {
+                    Self::write_backup(&paths.config_dir.join("config.toml"), &path)
+                }
+                _ if !path.exists() => continue,
+                // Whatever is inside a directory that was kept stays as well
+                _ if steps.iter().any(|step| {
+                    step.item == UninstallItem::ConfigDirectory
+                        && step.error.is_some()
+                        && path.starts_with(&step.path)
+                }) =>
+                {
+                    continue;
+                }
+                UninstallItem::ConfigDirectory
+                    if steps.iter().any(|step| {
+                        step.item == UninstallItem::ConfigBackup && step.error.is_some()
+                    }) =>
+                {
+                    steps.push(UninstallStep {
+                        item,
+                        path,
+                        error: Some(tr("cli.cli_commands.uninstall.kept_without_backup", &[])),
+                    });
+                    continue;
+                }
+                _ if path.is_dir() => fs::remove_dir_all(&path),
+                _ => fs::remove_file(&path),
+            };
+
+            let error = result.err().map(|e| {
+                LoggingManager::warn(&format!(
+                    "Failed to remove {}: {}",
+                    Redactable(path.display()),
+                    e
+                ));
+                e.to_string()
+            });
+            if error.is_none() {
+                LoggingManager::info(&format!("Removed {}", Redactable(path.display())));
+            }
+            steps.push(UninstallStep { item, path, error });
+        }
+        Ok(steps)
+    }
+
+    /// Copy the config to a backup file that must not exist yet
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The config file
+    /// * `backup`: The path of the backup
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the backup was written, else an error, also if the file exists
+    fn write_backup(config: &Path, backup: &Path) -> io::Result<()> {
+        let mut source = File::open(config)?;
+        let mut target = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(backup)?;
+        io::copy(&mut source, &mut target).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// An installation in a temporary directory, removed when dropped
+    struct TempInstall(TempDir);
+
+    impl TempInstall {
+        fn new(name: &str) -> Self {
+            let root = TempDir::new(name);
+            for dir in ["config/languages", "logs", "bin", "home"] {
+                fs::create_dir_all(root.join(dir)).unwrap();
+            }
+            fs::write(root.join("config").join("config.toml"), "version = 2\n").unwrap();
+            fs::write(root.join("logs").join("system_info.log"), "host").unwrap();
+            fs::write(root.join("bin").join("kdguard"), "binary").unwrap();
+            fs::write(root.join("bin").join("kdg"), "alias").unwrap();
+            Self(root)
+        }
+
+        fn paths(&self) -> UninstallPaths {
+            UninstallPaths {
+                config_dir: self.0.join("config"),
+                log_dir: Some(self.0.join("logs")),
+                executable: self.0.join("bin").join("kdguard"),
+                install_dir: None,
+                backup: self.0.join("home").join(CONFIG_BACKUP_FILE),
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_purges_logs_and_backs_up_config() {
+        let install = TempInstall::new("purge");
+        let paths = install.paths();
+
+        let steps = UninstallManager::remove(&paths, true, false).unwrap();
+        let items: Vec<UninstallItem> = steps.iter().map(|step| step.item).collect();
+        assert_eq!(
+            items,
+            [
+                UninstallItem::ConfigBackup,
+                UninstallItem::ConfigDirectory,
+                UninstallItem::LogDirectory,
+                UninstallItem::Alias,
+                UninstallItem::Executable,
+            ]
+        );
+        assert!(steps.iter().all(|step| step.error.is_none()));
+        assert_eq!(fs::read_to_string(&paths.backup).unwrap(), "version = 2\n");
+        assert!(!paths.config_dir.exists());
+        assert!(!install.0.join("logs").exists());
+        assert!(!install.0.join("bin").join("kdg").exists());
+        assert!(!paths.executable.exists());
+    }
+
+    #[test]
+    fn test_remove_keeps_an_earlier_backup() {
+        let install = TempInstall::new("earlier-backup");
+        let paths = install.paths();
+        fs::write(&paths.backup, "version = 1\n").unwrap();
+
+        let steps = UninstallManager::remove(&paths, true, false).unwrap();
+        assert!(steps.iter().all(|step| step.error.is_none()));
+        assert_eq!(steps[0].item, UninstallItem::ConfigBackup);
+
+        // The step reports the file that was written
+        let written = &steps[0].path;
+        assert_ne!(written, &paths.backup);
+        assert_eq!(written.parent(), paths.backup.parent());
+        let name = written.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("kdguard-config-backup-"), "{}", name);
+        assert!(name.ends_with(".toml"), "{}", name);
+        assert_eq!(fs::read_to_string(written).unwrap(), "version = 2\n");
+        assert_eq!(fs::read_to_string(&paths.backup).unwrap(), "version = 1\n");
+    }
+
+    #[test]
+    fn test_remove_continues_past_failures() {
+        let install = TempInstall::new("failures");
+        let mut paths = install.paths();
+        // The backup cannot be written into a missing directory
+        paths.backup = install.0.join("missing").join(CONFIG_BACKUP_FILE);
+
+        let steps = UninstallManager::remove(&paths, true, false).unwrap();
+        let failed: Vec<UninstallItem> = steps
+            .iter()
+            .filter(|step| step.error.is_some())
+            .map(|step| step.item)
+            .collect();
+        assert_eq!(
+            failed,
+            [UninstallItem::ConfigBackup, UninstallItem::ConfigDirectory]
+        );
+        // The config is kept without a backup, everything else is still removed
+        assert!(paths.config_dir.join("config.toml").exists());
+        assert!(paths.config_dir.join("languages").exists());
+        assert!(!install.0.join("logs").exists());
+        assert!(!paths.executable.exists());
+    }
+
+    #[test]
+    fn test_remove_refuses_install_dir_with_other_files() {
+        let install = TempInstall::new("foreign");
+        let mut paths = install.paths();
+        paths.install_dir = Some(install.0.join("bin"));
+        fs::write(install.0.join("bin").join("kdguard.prev"), "v0").unwrap();
+        let planted = install.0.join("bin").join("notes.txt");
+        fs::write(&planted, "not from kdguard").unwrap();
+
+        assert_eq!(paths.foreign_files(), std::slice::from_ref(&planted));
+        assert!(matches!(
+            UninstallManager::remove(&paths, false, false),
+            Err(UninstallError::ForeignFiles(_))
+        ));
+        // Nothing was touched
+        assert!(paths.config_dir.exists());
+        assert!(install.0.join("logs").exists());
+        assert!(planted.exists());
+
+        let steps = UninstallManager::remove(&paths, false, true).unwrap();
+        assert!(steps.iter().all(|step| step.error.is_none()));
+        assert_eq!(
+            steps.last().map(|step| step.item),
+            Some(UninstallItem::InstallDirectory)
+        );
+        assert!(!install.0.join("bin").exists());
+    }
+
+    #[test]
+    fn test_remove_refuses_config_dir_with_other_files() {
+        let install = TempInstall::new("shared-config");
+        let paths = install.paths();
+        fs::write(paths.config_dir.join("config.toml.bak"), "version = 1\n").unwrap();
+        let planted = paths.config_dir.join("important.doc");
+        fs::write(&planted, "not from kdguard").unwrap();
+
+        assert_eq!(paths.foreign_files(), std::slice::from_ref(&planted));
+        assert!(matches!(
+            UninstallManager::remove(&paths, false, false),
+            Err(UninstallError::ForeignFiles(_))
+        ));
+        assert!(paths.config_dir.join("config.toml").exists());
+        assert!(planted.exists());
+    }
+
+    #[test]
+    fn test_planned_lists_every_path() {
+        let install = TempInstall::new("plan");
+        let paths = install.paths();
+
+        let planned = paths.planned(false);
+        let items: Vec<UninstallItem> = planned.iter().map(|(item, _)| *item).collect();
+        assert_eq!(
+            items,
+            [
+                UninstallItem::ConfigDirectory,
+                UninstallItem::LanguagesDirectory,
+                UninstallItem::LogDirectory,
+                UninstallItem::Alias,
+                UninstallItem::RollbackCopy,
+                UninstallItem::RollbackCopy,
+                UninstallItem::Executable,
+            ]
+        );
+        assert_eq!(planned[1].1, install.0.join("config").join("languages"));
+        assert!(paths.foreign_files().is_empty());
+    }
 }
@@ -1,88 +1,661 @@
 use std::{
     env::consts::{ARCH, OS},
-    fs::{self, File},
-    io::Write,
-    path::PathBuf,
+    fs,
+    io::{IsTerminal, Read},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use inquire::Confirm;
-use lingua_i18n_rs::prelude::Lingua;
+use ring::digest;
+use serde::{Deserialize, Serialize};
 
+use crate::CONFIG;
+use crate::config::Config;
 use crate::errors::UpdateError;
-use crate::logging::LoggingManager;
+use crate::http::HttpManager;
+use crate::i18n::tr;
+use crate::installer::InstallManager;
+use crate::logging::{LoggingManager, Redactable};
+use crate::signature::{PublicKey, Signature};
+
+/// Time between two automatic update checks
+pub const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// File in the config directory holding the time of the last update check
+pub(crate) const LAST_CHECK_FILE: &str = ".last_update_check";
+
+/// File in the config directory holding a version the automatic check does not offer
+pub(crate) const SKIPPED_VERSION_FILE: &str = ".skipped_version";
+
+/// GitHub API endpoint of the latest release, it never returns a prerelease
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/KarnesTH/kdguard/releases/latest";
+
+/// GitHub API endpoint listing the recent releases, prereleases included
+const RELEASES_URL: &str = "https://api.github.com/repos/KarnesTH/kdguard/releases?per_page=20";
+
+/// Lines of the release notes shown before confirming an update
+const RELEASE_NOTES_LINES: usize = 20;
+
+/// Time to wait for a connection to GitHub
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Time the whole check for the latest tag may take
+const TAG_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time to wait for the next chunk of a download
+const DOWNLOAD_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Extra attempts for a download that failed with a transient error
+const DOWNLOAD_RETRIES: u32 = 2;
+
+/// Wait before the first retry, doubled for each further one
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Version printed by `--version`, followed by the short commit hash
+pub const LONG_VERSION: &str =
+    concat!(env!("CARGO_PKG_VERSION"), " (", env!("KDGUARD_COMMIT"), ")");
+
+/// Build metadata printed by `--version --json`
+#[derive(Serialize, Debug, PartialEq)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub commit: &'static str,
+    pub build_date: &'static str,
+    pub target: &'static str,
+    pub config_path: String,
+    pub previous_version: Option<String>,
+}
+
+impl VersionInfo {
+    /// Collect the metadata of the running binary
+    ///
+    /// # Returns
+    ///
+    /// Returns the version info, the config path is empty if it cannot be resolved and
+    /// the previous version is None if no binary is kept for a rollback
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: env!("KDGUARD_COMMIT"),
+            build_date: env!("KDGUARD_BUILD_DATE"),
+            target: env!("KDGUARD_TARGET"),
+            config_path: Config::get_config_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+            previous_version: UpdateManager::previous_version(),
+        }
+    }
+
+    /// Format the version info as a JSON object
+    ///
+    /// # Returns
+    ///
+    /// Returns the pretty printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("version info is always serializable")
+    }
+}
+
+/// Release asset listing the SHA-256 checksums of all binaries
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+/// Suffixes of release assets that accompany a binary instead of being one
+const SIDECAR_SUFFIXES: [&str; 6] = [".sha256", ".sig", ".minisig", ".asc", ".txt", ".json"];
+
+/// A release as returned by the GitHub API, only the fields the updater needs
+#[derive(Deserialize)]
+struct Release {
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Deserialize)]
+struct ReleaseAsset {
+    name: String,
+}
+
+/// How a release asset is packed
+#[derive(Debug, PartialEq)]
+enum ArchiveKind {
+    Binary,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Tell the archive format from the asset name
+    fn of(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Self::TarGz
+        } else if name.ends_with(".zip") {
+            Self::Zip
+        } else {
+            Self::Binary
+        }
+    }
+}
+
+/// A platform kdguard publishes release binaries for
+#[derive(Debug, PartialEq)]
+pub struct Platform {
+    /// Name used in logs and errors, `<os>-<arch>` with `-musl` for static Linux builds
+    pub name: &'static str,
+    /// The value of `std::env::consts::OS`
+    os: &'static str,
+    /// The value of `std::env::consts::ARCH`
+    arch: &'static str,
+    /// Whether the binary is linked against musl instead of glibc
+    musl: bool,
+    /// Words in asset names meaning the operating system
+    os_names: &'static [&'static str],
+    /// Words in asset names meaning the architecture
+    arch_names: &'static [&'static str],
+}
+
+const LINUX_NAMES: &[&str] = &["linux"];
+const MACOS_NAMES: &[&str] = &["macos", "darwin", "apple"];
+// Not `win`, `darwin` contains it
+const WINDOWS_NAMES: &[&str] = &["windows", "win64"];
+const X86_64_NAMES: &[&str] = &["x86_64", "amd64", "x64"];
+const AARCH64_NAMES: &[&str] = &["aarch64", "arm64"];
+
+/// Every platform with release binaries, a new target only needs an entry here
+const PLATFORMS: [Platform; 7] = [
+    Platform {
+        name: "linux-x86_64",
+        os: "linux",
+        arch: "x86_64",
+        musl: false,
+        os_names: LINUX_NAMES,
+        arch_names: X86_64_NAMES,
+    },
+    Platform {
+        name: "linux-x86_64-musl",
+        os: "linux",
+        arch: "x86_64",
+        musl: true,
+        os_names: LINUX_NAMES,
+        arch_names: X86_64_NAMES,
+    },
+    Platform {
+        name: "linux-aarch64",
+        os: "linux",
+        arch: "aarch64",
+        musl: false,
+        os_names: LINUX_NAMES,
+        arch_names: AARCH64_NAMES,
+    },
+    Platform {
+        name: "linux-aarch64-musl",
+        os: "linux",
+        arch: "aarch64",
+        musl: true,
+        os_names: LINUX_NAMES,
+        arch_names: AARCH64_NAMES,
+    },
+    Platform {
+        name: "macos-x86_64",
+        os: "macos",
+        arch: "x86_64",
+        musl: false,
+        os_names: MACOS_NAMES,
+        arch_names: X86_64_NAMES,
+    },
+    Platform {
+        name: "macos-aarch64",
+        os: "macos",
+        arch: "aarch64",
+        musl: false,
+        os_names: MACOS_NAMES,
+        arch_names: AARCH64_NAMES,
+    },
+    Platform {
+        name: "windows-x86_64",
+        os: "windows",
+        arch: "x86_64",
+        musl: false,
+        os_names: WINDOWS_NAMES,
+        arch_names: X86_64_NAMES,
+    },
+];
+
+impl Platform {
+    /// Check whether a release asset is a build for this platform
+    ///
+    /// A glibc build also accepts musl assets, they are linked statically and run
+    /// everywhere; a musl build only accepts musl assets.
+    fn matches(&self, asset: &str) -> bool {
+        let name = asset.to_lowercase();
+        let musl = name.contains("musl");
+        self.os_names.iter().any(|os| name.contains(os))
+            && self.arch_names.iter().any(|arch| name.contains(arch))
+            && (musl || !self.musl)
+    }
+}
+
+/// Fetches release assets, replaced in tests
+pub trait AssetDownloader {
+    /// Download an asset
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the asset
+    ///
+    /// # Returns
+    ///
+    /// Returns the bytes, None if the asset does not exist, otherwise an error
+    async fn download(&self, url: &str) -> Result<Option<Vec<u8>>, UpdateError>;
+}
+
+/// Build the HTTP client used to talk to GitHub, through the proxy of the `[network]` config
+///
+/// # Arguments
+///
+/// * `timeout` - Time the whole request may take, None for downloads of unknown size
+///
+/// # Returns
+///
+/// Returns the client, otherwise an error
+fn http_client(timeout: Option<Duration>) -> Result<reqwest::Client, UpdateError> {
+    let mut builder = HttpManager::builder("kdguard-update-checker", &CONFIG.network)?
+        .connect_timeout(CONNECT_TIMEOUT);
+    builder = match timeout {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder.read_timeout(DOWNLOAD_READ_TIMEOUT),
+    };
+    builder.build().map_err(|e| {
+        let error = format!("Failed to create HTTP client: {}", e);
+        LoggingManager::error(&error);
+        UpdateError::Update(error)
+    })
+}
+
+/// Downloads release assets from GitHub
+struct HttpDownloader {
+    client: reqwest::Client,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl HttpDownloader {
+    fn new() -> Result<Self, UpdateError> {
+        Ok(Self {
+            client: http_client(None)?,
+            retries: DOWNLOAD_RETRIES,
+            backoff: RETRY_BACKOFF,
+        })
+    }
+
+    /// Download once, without retrying
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the asset
+    ///
+    /// # Returns
+    ///
+    /// Returns the bytes, None if the asset does not exist, otherwise the error and
+    /// whether trying again may succeed
+    async fn try_download(&self, url: &str) -> Result<Option<Vec<u8>>, (UpdateError, bool)> {
+        let response = self.client.get(url).send().await.map_err(|e| {
+            let error = format!(
+                "Failed to download {}: {}",
+                url,
+                HttpManager::describe_error(&e)
+            );
+            LoggingManager::error(&error);
+            (UpdateError::Network(error), true)
+        })?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            LoggingManager::info(&format!("Not found: {}", url));
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let error = format!("Failed to download {}: HTTP {}", url, response.status());
+            LoggingManager::error(&error);
+            let transient = response.status().is_server_error();
+            return Err((UpdateError::GitHubApi(error), transient));
+        }
+
+        let data = response.bytes().await.map_err(|e| {
+            let error = format!(
+                "Failed to read {}: {}",
+                url,
+                HttpManager::describe_error(&e)
+            );
+            LoggingManager::error(&error);
+            (UpdateError::Network(error), true)
+        })?;
+        Ok(Some(data.to_vec()))
+    }
+}
+
+impl AssetDownloader for HttpDownloader {
+    async fn download(&self, url: &str) -> Result<Option<Vec<u8>>, UpdateError> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.try_download(url).await {
+                Err((_, true)) if attempt < self.retries => {
+                    attempt += 1;
+                    LoggingManager::warn(&format!(
+                        "Retrying download in {:?} ({}/{})",
+                        backoff, attempt, self.retries
+                    ));
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result.map_err(|(error, _)| error),
+            }
+        }
+    }
+}
+
+/// A release newer than the running version
+#[derive(Debug, PartialEq)]
+pub struct ReleaseInfo {
+    pub tag: String,
+    /// The changelog in markdown
+    pub notes: String,
+    /// Publication date as `YYYY-MM-DD`
+    pub published: Option<String>,
+}
+
+/// How `kdguard update` installs a release
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UpdateOptions {
+    /// Install without asking, required when stdin is not a terminal
+    pub assume_yes: bool,
+    /// Install a release that publishes no checksum
+    pub allow_unverified: bool,
+    /// Install without verifying the release signature
+    pub skip_signature: bool,
+    /// Offer a version that was rolled back from
+    pub include_skipped: bool,
+}
 
 pub struct UpdateManager;
 
 impl UpdateManager {
-    /// Check for update
+    /// Check for update and offer to install it
+    ///
+    /// # Arguments
+    ///
+    /// * `options`: How to install the update
     ///
     /// # Returns
     ///
-    /// Returns Ok(()) if successful, otherwise an error
-    pub async fn check_update() -> Result<(), UpdateError> {
-        LoggingManager::info("Checking for updates");
-        let current_version = env!("CARGO_PKG_VERSION");
-        LoggingManager::info(&format!("Current version: {}", current_version));
+    /// Returns true if a newer release was found, false if already on the latest
+    /// version, otherwise an error
+    pub async fn check_update(options: UpdateOptions) -> Result<bool, UpdateError> {
+        // Fails before the request, an answer could never be given
+        if !options.assume_yes && !std::io::stdin().is_terminal() {
+            LoggingManager::error("Update needs a confirmation but stdin is not a terminal");
+            return Err(UpdateError::ConfirmationRequired);
+        }
+        Self::record_check(SystemTime::now());
+        let Some(release) = Self::available_release(options.include_skipped).await? else {
+            return Ok(false);
+        };
+        // Helps to tell why a release asset for this platform was not found
+        LoggingManager::info(&format!(
+            "Build info:\n{}",
+            VersionInfo::current().to_json()
+        ));
+        Self::confirm_update(&release, options).await?;
 
-        let latest_tag = Self::get_latest_tag().await?;
-        let latest_version = Self::extract_version(&latest_tag);
-        LoggingManager::info(&format!("Latest version: {}", latest_version));
+        Ok(true)
+    }
 
-        if Self::compare_versions(&latest_version, current_version) > 0 {
-            LoggingManager::info("Update available");
-            let confirm = Confirm::new(&Lingua::t("cli.cli_commands.update.confirm", &[]).unwrap())
+    /// Check for update if the last check is older than the check interval
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the check is not due or successful, otherwise an error
+    pub async fn check_update_if_due() -> Result<(), UpdateError> {
+        let last_check = Self::last_check_path().and_then(|path| Self::read_last_check(&path));
+        if !Self::is_check_due(last_check, SystemTime::now()) {
+            LoggingManager::info("Skipping update check, the last one is recent");
+            return Ok(());
+        }
+
+        match Self::check_update(UpdateOptions::default()).await {
+            Ok(_) => Ok(()),
+            // Being offline is no reason to stop the command that was asked for
+            Err(UpdateError::Network(e)) => {
+                LoggingManager::info(&format!(
+                    "Skipping update check, GitHub is unreachable: {}",
+                    e
+                ));
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Ask whether to install a newer release and install it
+    ///
+    /// # Arguments
+    ///
+    /// * `release`: The newer release, its notes are shown before asking
+    /// * `options`: How to install the update
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the update was installed or declined, otherwise an error
+    pub async fn confirm_update(
+        release: &ReleaseInfo,
+        options: UpdateOptions,
+    ) -> Result<(), UpdateError> {
+        let latest_version = Self::extract_version(&release.tag);
+        let delta = match &release.published {
+            Some(date) => tr(
+                "cli.cli_commands.update.delta",
+                &[
+                    ("current", env!("CARGO_PKG_VERSION")),
+                    ("latest", &latest_version),
+                    ("date", date),
+                ],
+            ),
+            None => tr(
+                "cli.cli_commands.update.delta_undated",
+                &[
+                    ("current", env!("CARGO_PKG_VERSION")),
+                    ("latest", &latest_version),
+                ],
+            ),
+        };
+        if !options.assume_yes {
+            let (notes, hidden) = Self::render_notes(&release.notes, RELEASE_NOTES_LINES);
+            if !notes.is_empty() {
+                println!("\n{}", notes.join("\n"));
+            }
+            if hidden > 0 {
+                let url = format!(
+                    "https://github.com/KarnesTH/kdguard/releases/tag/{}",
+                    release.tag
+                );
+                println!(
+                    "{}",
+                    tr(
+                        "cli.cli_commands.update.notes_truncated",
+                        &[("count", &hidden.to_string()), ("url", &url)]
+                    )
+                );
+            }
+            println!();
+        }
+
+        let confirm = options.assume_yes
+            || Confirm::new(&tr("cli.cli_commands.update.confirm", &[]))
+                .with_help_message(&delta)
                 .with_default(false)
                 .prompt()
                 .map_err(|e| {
                     let error = format!("Failed to get user confirmation: {}", e);
                     LoggingManager::error(&error);
-                    UpdateError::GetLatestTag(error)
+                    UpdateError::Update(error)
                 })?;
-            if confirm {
-                LoggingManager::info("User confirmed update, starting update process");
-                Self::update(&latest_tag).await?;
-            } else {
-                LoggingManager::info("Update cancelled by user");
-            }
+        if confirm {
+            LoggingManager::info("User confirmed update, starting update process");
+            Self::update(&release.tag, options).await?;
         } else {
-            LoggingManager::info("Already on latest version");
+            LoggingManager::info("Update cancelled by user");
         }
 
         Ok(())
     }
 
-    /// Get the latest tag from GitHub
+    /// Check whether an automatic update check is due
+    ///
+    /// # Arguments
+    ///
+    /// * `last_check`: The time of the last check, None if there was none
+    /// * `now`: The current time
     ///
     /// # Returns
     ///
-    /// Returns the latest tag if successful, otherwise an error
-    async fn get_latest_tag() -> Result<String, UpdateError> {
-        LoggingManager::info("Fetching latest tag from GitHub API");
+    /// Returns true if there was no check in the check interval, otherwise false
+    pub fn is_check_due(last_check: Option<SystemTime>, now: SystemTime) -> bool {
+        let Some(last_check) = last_check else {
+            return true;
+        };
+        match now.duration_since(last_check) {
+            Ok(elapsed) => elapsed >= UPDATE_CHECK_INTERVAL,
+            // A last check in the future means the clock was changed
+            Err(_) => true,
+        }
+    }
 
-        let client = reqwest::Client::builder()
-            .user_agent("kdguard-update-checker")
-            .build()
-            .map_err(|e| {
-                let error = format!("Failed to create HTTP client: {}", e);
-                LoggingManager::error(&error);
-                UpdateError::GetLatestTag(error)
-            })?;
+    /// Read the time of the last update check
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The file written by `record_check`
+    ///
+    /// # Returns
+    ///
+    /// Returns the time, or None if the file is missing or unreadable
+    fn read_last_check(path: &Path) -> Option<SystemTime> {
+        let seconds = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+        UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+    }
 
-        let res = client
-            .get("https://api.github.com/repos/KarnesTH/kdguard/releases/latest")
-            .send()
-            .await
-            .map_err(|e| {
-                let error = format!("Failed to send request to GitHub API: {}", e);
-                LoggingManager::error(&error);
-                UpdateError::GetLatestTag(error)
-            })?;
+    /// Remember the time of an update check, failures only disable the interval
+    ///
+    /// # Arguments
+    ///
+    /// * `now`: The time of the check
+    fn record_check(now: SystemTime) {
+        let Some(path) = Self::last_check_path() else {
+            return;
+        };
+        let seconds = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        if let Err(e) = fs::write(&path, seconds.to_string()) {
+            LoggingManager::warn(&format!("Failed to record the update check: {}", e));
+        }
+    }
+
+    fn last_check_path() -> Option<PathBuf> {
+        Config::get_config_dir()
+            .ok()
+            .map(|dir| dir.join(LAST_CHECK_FILE))
+    }
+
+    /// Get the latest release if it is newer than the running version
+    ///
+    /// Does not prompt, so it can run in the background while the TUI is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_skipped`: Offer the version last rolled back from as well
+    ///
+    /// # Returns
+    ///
+    /// Returns the release tag if an update is available, None if already on the
+    /// latest version or the latest version is skipped, otherwise an error
+    pub async fn available_update(include_skipped: bool) -> Result<Option<String>, UpdateError> {
+        Ok(Self::available_release(include_skipped)
+            .await?
+            .map(|release| release.tag))
+    }
+
+    /// Get the latest release with its notes if it is newer than the running version
+    ///
+    /// Prereleases are only considered with `allow_prerelease` in the `[update]` config.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_skipped`: Offer the version last rolled back from as well
+    ///
+    /// # Returns
+    ///
+    /// Returns the release if an update is available, None if already on the latest
+    /// version or the latest version is skipped, otherwise an error
+    pub async fn available_release(
+        include_skipped: bool,
+    ) -> Result<Option<ReleaseInfo>, UpdateError> {
+        LoggingManager::info("Checking for updates");
+        let current_version = env!("CARGO_PKG_VERSION");
+        LoggingManager::info(&format!("Current version: {}", current_version));
+
+        let allow_prerelease = CONFIG.update.allow_prerelease;
+        let url = if allow_prerelease {
+            RELEASES_URL
+        } else {
+            LATEST_RELEASE_URL
+        };
+        let client = http_client(Some(TAG_CHECK_TIMEOUT))?;
+        let release = Self::get_latest_release(&client, url, allow_prerelease).await?;
+        let latest_version = Self::extract_version(&release.tag);
+        LoggingManager::info(&format!("Latest version: {}", latest_version));
+
+        if !include_skipped && Self::read_skipped_version().as_deref() == Some(&latest_version) {
+            LoggingManager::info("Latest version was rolled back from, not offering it");
+            Ok(None)
+        } else if Self::compare_versions(&latest_version, current_version) > 0 {
+            LoggingManager::info("Update available");
+            Ok(Some(release))
+        } else {
+            LoggingManager::info("Already on latest version");
+            Ok(None)
+        }
+    }
+
+    /// Get the latest release from GitHub
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client, its timeouts bound the check
+    /// * `url` - The API endpoint of the latest release or of the release list
+    /// * `allow_prerelease` - Whether a prerelease may be the latest release
+    ///
+    /// # Returns
+    ///
+    /// Returns the newest release if successful, otherwise an error
+    async fn get_latest_release(
+        client: &reqwest::Client,
+        url: &str,
+        allow_prerelease: bool,
+    ) -> Result<ReleaseInfo, UpdateError> {
+        LoggingManager::info("Fetching latest release from GitHub API");
+
+        let res = client.get(url).send().await.map_err(|e| {
+            let error = format!(
+                "Failed to send request to GitHub API: {}",
+                HttpManager::describe_error(&e)
+            );
+            LoggingManager::error(&error);
+            UpdateError::Network(error)
+        })?;
 
         let status = res.status();
         let text = res.text().await.map_err(|e| {
             let error = format!("Failed to read response body: {}", e);
             LoggingManager::error(&error);
-            UpdateError::GetLatestTag(error)
+            UpdateError::Network(error)
         })?;
 
         if !status.is_success() {
@@ -97,31 +670,167 @@ impl UpdateManager {
             UpdateError::ParseJson(error)
         })?;
 
-        if let Some(tag_value) = json.get("tag_name") {
-            LoggingManager::info(&format!("Raw tag_name from API: {:?}", tag_value));
+        Self::select_release(json, allow_prerelease)
+    }
+
+    /// Pick the newest release from a GitHub API response
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A single release or a list of releases
+    /// * `allow_prerelease` - Whether a prerelease may be picked
+    ///
+    /// # Returns
+    ///
+    /// Returns the newest release, otherwise an error if none has a valid tag
+    fn select_release(
+        json: serde_json::Value,
+        allow_prerelease: bool,
+    ) -> Result<ReleaseInfo, UpdateError> {
+        let releases = match json {
+            serde_json::Value::Array(releases) => releases,
+            release => vec![release],
+        };
+
+        let mut latest: Option<ReleaseInfo> = None;
+        for release in releases {
+            if let Some(tag_value) = release.get("tag_name") {
+                LoggingManager::info(&format!("Raw tag_name from API: {:?}", tag_value));
+            }
+            let Some(tag) = release
+                .get("tag_name")
+                .and_then(|v| v.as_str())
+                .map(|tag| tag.trim().to_string())
+            else {
+                continue;
+            };
+            if tag.contains("Full Changelog") || tag.contains("http") || tag.contains("compare") {
+                LoggingManager::warn(&format!("Invalid tag format received: {}", tag));
+                continue;
+            }
+            let flag = |name: &str| release.get(name).and_then(|v| v.as_bool()) == Some(true);
+            if flag("draft") {
+                continue;
+            }
+            if !allow_prerelease
+                && (flag("prerelease") || Self::is_prerelease(&Self::extract_version(&tag)))
+            {
+                LoggingManager::info(&format!("Skipping prerelease {}", tag));
+                continue;
+            }
+            let newer = latest.as_ref().is_none_or(|latest| {
+                Self::compare_versions(
+                    &Self::extract_version(&tag),
+                    &Self::extract_version(&latest.tag),
+                ) > 0
+            });
+            if newer {
+                latest = Some(ReleaseInfo {
+                    tag,
+                    notes: release
+                        .get("body")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    published: release
+                        .get("published_at")
+                        .and_then(|v| v.as_str())
+                        .and_then(|date| date.get(..10))
+                        .map(str::to_string),
+                });
+            }
         }
 
-        let latest_tag = json
-            .get("tag_name")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                LoggingManager::error("Failed to parse tag_name from GitHub API response");
-                UpdateError::ParseTagName
-            })?
-            .trim()
-            .to_string();
-
-        if latest_tag.contains("Full Changelog")
-            || latest_tag.contains("http")
-            || latest_tag.contains("compare")
+        let latest = latest.ok_or_else(|| {
+            LoggingManager::error("Failed to parse tag_name from GitHub API response");
+            UpdateError::ParseTagName
+        })?;
+        LoggingManager::info(&format!("Successfully fetched latest tag: {}", latest.tag));
+        Ok(latest)
+    }
+
+    /// Check whether a version has a prerelease suffix like `-rc.1` or `-beta`
+    fn is_prerelease(version: &str) -> bool {
+        version.split('+').next().unwrap_or(version).contains('-')
+    }
+
+    /// Turn the first lines of markdown release notes into plain text
+    ///
+    /// # Arguments
+    ///
+    /// * `notes` - The release notes in markdown
+    /// * `max_lines` - The number of lines to keep
+    ///
+    /// # Returns
+    ///
+    /// Returns the kept lines and the number of lines left out
+    fn render_notes(notes: &str, max_lines: usize) -> (Vec<String>, usize) {
+        let mut lines: Vec<String> = Vec::new();
+        let mut in_comment = false;
+        for line in notes.lines() {
+            let trimmed = line.trim();
+            if in_comment || trimmed.starts_with("<!--") {
+                in_comment = !trimmed.ends_with("-->");
+                continue;
+            }
+            let line = Self::strip_markdown(line.trim_end());
+            // Runs of blank lines collapse into one
+            if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+                continue;
+            }
+            lines.push(line);
+        }
+        while lines.last().is_some_and(|last| last.is_empty()) {
+            lines.pop();
+        }
+
+        let hidden = lines.len().saturating_sub(max_lines);
+        lines.truncate(max_lines);
+        (lines, hidden)
+    }
+
+    /// Strip the markdown of a single line, keeping the text of links and list bullets
+    fn strip_markdown(line: &str) -> String {
+        let indent = line.len() - line.trim_start().len();
+        let mut text = line.trim_start();
+        let mut prefix = String::new();
+        if text.starts_with('#') {
+            text = text.trim_start_matches('#').trim_start();
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| text.strip_prefix(bullet))
         {
-            let error = format!("Invalid tag format received: {}", latest_tag);
-            LoggingManager::error(&error);
-            return Err(UpdateError::ParseTagName);
+            prefix = format!("{}• ", " ".repeat(indent));
+            text = item;
+        } else if text.chars().all(|c| matches!(c, '-' | '*' | '_' | '=')) {
+            // Horizontal rules and heading underlines
+            return String::new();
+        }
+
+        let mut plain = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('[') {
+            let (before, after) = rest.split_at(start);
+            let link = after[1..]
+                .split_once("](")
+                .and_then(|(label, tail)| tail.split_once(')').map(|(_, tail)| (label, tail)));
+            match link {
+                Some((label, tail)) => {
+                    plain.push_str(before.strip_suffix('!').unwrap_or(before));
+                    plain.push_str(label);
+                    rest = tail;
+                }
+                None => {
+                    plain.push_str(before);
+                    plain.push('[');
+                    rest = &after[1..];
+                }
+            }
         }
+        plain.push_str(rest);
+        let plain = plain.replace("**", "").replace("__", "").replace('`', "");
 
-        LoggingManager::info(&format!("Successfully fetched latest tag: {}", latest_tag));
-        Ok(latest_tag)
+        format!("{}{}", prefix, plain.trim())
     }
 
     /// Extract the version from the tag
@@ -135,13 +844,26 @@ impl UpdateManager {
 
     /// Compare two versions
     ///
+    /// Versions follow semver: a prerelease (`1.0.0-rc.1`) is older than its release,
+    /// prerelease identifiers are compared one by one, numbers numerically and below
+    /// names, and build metadata (`+abc`) is ignored.
+    ///
     /// # Returns
     ///
     /// Returns 1 if v1 is greater than v2, -1 if v1 is less than v2, and 0 if they are equal
     fn compare_versions(v1: &str, v2: &str) -> i32 {
-        let v1_parts: Vec<u32> = v1.split('.').map(|s| s.parse().unwrap_or(0)).collect();
-        let v2_parts: Vec<u32> = v2.split('.').map(|s| s.parse().unwrap_or(0)).collect();
+        let split = |version: &str| {
+            let version = version.split('+').next().unwrap_or(version);
+            match version.split_once('-') {
+                Some((core, pre)) => (core.to_string(), Some(pre.to_string())),
+                None => (version.to_string(), None),
+            }
+        };
+        let (v1_core, v1_pre) = split(v1);
+        let (v2_core, v2_pre) = split(v2);
 
+        let v1_parts: Vec<u32> = v1_core.split('.').map(|s| s.parse().unwrap_or(0)).collect();
+        let v2_parts: Vec<u32> = v2_core.split('.').map(|s| s.parse().unwrap_or(0)).collect();
         let max_len = v1_parts.len().max(v2_parts.len());
         for i in 0..max_len {
             let v1_part = v1_parts.get(i).copied().unwrap_or(0);
@@ -154,7 +876,27 @@ impl UpdateManager {
             }
         }
 
-        0
+        let ordering = match (v1_pre, v2_pre) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (Some(v1_pre), Some(v2_pre)) => {
+                let identifier = |id: &str| match id.parse::<u64>() {
+                    // Numeric identifiers sort below alphanumeric ones
+                    Ok(number) => (0, number, String::new()),
+                    Err(_) => (1, 0, id.to_string()),
+                };
+                v1_pre
+                    .split('.')
+                    .map(identifier)
+                    .cmp(v2_pre.split('.').map(identifier))
+            }
+        };
+        match ordering {
+            std::cmp::Ordering::Greater => 1,
+            std::cmp::Ordering::Less => -1,
+            std::cmp::Ordering::Equal => 0,
+        }
     }
 
     /// Detect the platform for the binary download
@@ -162,51 +904,31 @@ impl UpdateManager {
     /// # Returns
     ///
     /// Returns the platform if successful, otherwise an error
-    fn detect_platform() -> Result<String, UpdateError> {
-        let os = match OS {
-            "linux" => "linux",
-            "macos" | "darwin" => "macos",
-            "windows" => "windows",
-            _ => {
-                let error = format!("Unsupported OS: {}", OS);
-                LoggingManager::error(&error);
-                return Err(UpdateError::Update(error));
-            }
-        };
+    fn detect_platform() -> Result<&'static Platform, UpdateError> {
+        Self::platform_for(OS, ARCH, cfg!(target_env = "musl"))
+    }
 
-        let arch = match ARCH {
-            "x86_64" | "amd64" => "x86_64",
-            "aarch64" | "arm64" => {
-                if os == "macos" {
-                    "aarch64"
-                } else {
-                    let error = format!("Unsupported architecture for {}: {}", os, ARCH);
-                    LoggingManager::error(&error);
-                    return Err(UpdateError::Update(error));
-                }
-            }
-            _ => {
-                let error = format!("Unsupported architecture: {}", ARCH);
+    /// Look up the platform of a target in the platform table
+    ///
+    /// # Arguments
+    ///
+    /// * `os` - The operating system, as in `std::env::consts::OS`
+    /// * `arch` - The architecture, as in `std::env::consts::ARCH`
+    /// * `musl` - Whether the binary is linked against musl
+    ///
+    /// # Returns
+    ///
+    /// Returns the platform, otherwise an error if no release binaries exist for it
+    fn platform_for(os: &str, arch: &str, musl: bool) -> Result<&'static Platform, UpdateError> {
+        PLATFORMS
+            .iter()
+            .find(|platform| platform.os == os && platform.arch == arch && platform.musl == musl)
+            .ok_or_else(|| {
+                let libc = if musl { "-musl" } else { "" };
+                let error = format!("No prebuilt binaries for {}-{}{}", os, arch, libc);
                 LoggingManager::error(&error);
-                return Err(UpdateError::Update(error));
-            }
-        };
-
-        let platform = if os == "macos" && arch == "aarch64" {
-            "macos-aarch64"
-        } else if os == "macos" && arch == "x86_64" {
-            "macos-x86_64"
-        } else if os == "linux" && arch == "x86_64" {
-            "linux-x86_64"
-        } else if os == "windows" && arch == "x86_64" {
-            "windows-x86_64"
-        } else {
-            let error = format!("Unsupported platform combination: {}-{}", os, arch);
-            LoggingManager::error(&error);
-            return Err(UpdateError::Update(error));
-        };
-
-        Ok(platform.to_string())
+                UpdateError::Update(error)
+            })
     }
 
     /// Get the install directory path
@@ -215,7 +937,7 @@ impl UpdateManager {
     ///
     /// Returns the install directory path if successful, otherwise an error
     fn get_install_dir() -> Result<PathBuf, UpdateError> {
-        if OS == "Windows" {
+        if cfg!(windows) {
             let local_app_data = dirs::data_local_dir().ok_or_else(|| {
                 let error = "Failed to get LocalAppData directory".to_string();
                 LoggingManager::error(&error);
@@ -232,24 +954,380 @@ impl UpdateManager {
         }
     }
 
+    /// Download a release asset and verify it against the published SHA-256 checksum
+    ///
+    /// The checksum is looked up in the `SHA256SUMS` asset of the release, then in
+    /// `<asset>.sha256`.
+    ///
+    /// # Arguments
+    ///
+    /// * `downloader` - Fetches the release assets
+    /// * `tag` - The tag of the release
+    /// * `asset` - The name of the asset to download
+    /// * `allow_unverified` - Accept the asset if no checksum is published
+    ///
+    /// # Returns
+    ///
+    /// Returns the verified bytes, otherwise an error on a mismatch or a missing checksum
+    async fn download_verified(
+        downloader: &impl AssetDownloader,
+        tag: &str,
+        asset: &str,
+        allow_unverified: bool,
+    ) -> Result<Vec<u8>, UpdateError> {
+        let url = |name: &str| {
+            format!(
+                "https://github.com/KarnesTH/kdguard/releases/download/{}/{}",
+                tag, name
+            )
+        };
+
+        LoggingManager::info(&format!("Downloading from: {}", url(asset)));
+        let data = downloader.download(&url(asset)).await?.ok_or_else(|| {
+            let error = format!("Failed to download binary: {} not found", asset);
+            LoggingManager::error(&error);
+            UpdateError::Update(error)
+        })?;
+
+        let mut expected = match downloader.download(&url(CHECKSUMS_ASSET)).await? {
+            Some(sums) => Self::find_checksum(&String::from_utf8_lossy(&sums), asset),
+            None => None,
+        };
+        if expected.is_none()
+            && let Some(sum) = downloader
+                .download(&url(&format!("{}.sha256", asset)))
+                .await?
+        {
+            expected = Self::find_checksum(&String::from_utf8_lossy(&sum), asset);
+        }
+
+        match expected {
+            Some(expected) => Self::verify_checksum(&data, &expected)?,
+            None if allow_unverified => {
+                LoggingManager::warn(&format!(
+                    "No checksum published for {}, installing it unverified",
+                    asset
+                ));
+                eprintln!(
+                    "\x1b[1;33m{}\x1b[0m",
+                    tr(
+                        "cli.cli_commands.update.checksum_skipped",
+                        &[("asset", asset)]
+                    )
+                );
+            }
+            None => {
+                LoggingManager::error(&format!("No checksum published for {}", asset));
+                return Err(UpdateError::ChecksumMissing(asset.to_string()));
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Verify the detached minisign signature of a release asset with the release key
+    ///
+    /// # Arguments
+    ///
+    /// * `downloader` - Fetches the release assets
+    /// * `tag` - The tag of the release
+    /// * `asset` - The name of the signed asset
+    /// * `data` - The downloaded asset
+    /// * `skip` - Install without verifying, warns on stderr and in the log
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the signature is valid or skipped, otherwise an error
+    async fn verify_signature(
+        downloader: &impl AssetDownloader,
+        tag: &str,
+        asset: &str,
+        data: &[u8],
+        skip: bool,
+    ) -> Result<(), UpdateError> {
+        if skip {
+            LoggingManager::warn(&format!(
+                "Signature verification of {} skipped with --skip-signature",
+                asset
+            ));
+            eprintln!(
+                "\x1b[1;33m{}\x1b[0m",
+                tr(
+                    "cli.cli_commands.update.signature_skipped",
+                    &[("asset", asset)]
+                )
+            );
+            return Ok(());
+        }
+
+        Self::verify_signature_with(downloader, &PublicKey::release()?, tag, asset, data).await
+    }
+
+    /// Verify the detached minisign signature of a release asset with a key
+    ///
+    /// # Arguments
+    ///
+    /// * `downloader` - Fetches the release assets
+    /// * `key` - The key the asset must be signed with
+    /// * `tag` - The tag of the release
+    /// * `asset` - The name of the signed asset
+    /// * `data` - The downloaded asset
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the signature is valid, otherwise an error
+    async fn verify_signature_with(
+        downloader: &impl AssetDownloader,
+        key: &PublicKey,
+        tag: &str,
+        asset: &str,
+        data: &[u8],
+    ) -> Result<(), UpdateError> {
+        let url = format!(
+            "https://github.com/KarnesTH/kdguard/releases/download/{}/{}.sig",
+            tag, asset
+        );
+        let signature = downloader.download(&url).await?.ok_or_else(|| {
+            LoggingManager::error(&format!("No signature published for {}", asset));
+            UpdateError::SignatureMissing(asset.to_string())
+        })?;
+        Signature::parse(&String::from_utf8_lossy(&signature))?.verify(key, data)?;
+
+        Ok(())
+    }
+
+    /// Find the checksum of an asset in a checksum file
+    ///
+    /// Lines are `<hex digest>  <name>` as written by `sha256sum`, a line with only a
+    /// digest is accepted for single-asset `.sha256` files.
+    ///
+    /// # Arguments
+    ///
+    /// * `sums` - The contents of the checksum file
+    /// * `asset` - The name of the asset
+    ///
+    /// # Returns
+    ///
+    /// Returns the lowercase hex digest if one is listed for the asset, otherwise None
+    fn find_checksum(sums: &str, asset: &str) -> Option<String> {
+        sums.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let digest = parts.next()?;
+            let listed = parts.next().map(|name| name.trim_start_matches('*'));
+            let is_digest = digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit());
+            (is_digest && listed.is_none_or(|name| name == asset))
+                .then(|| digest.to_ascii_lowercase())
+        })
+    }
+
+    /// Verify data against a SHA-256 checksum
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The downloaded bytes
+    /// * `expected` - The expected lowercase hex digest
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if the digest matches, otherwise a checksum mismatch error
+    fn verify_checksum(data: &[u8], expected: &str) -> Result<(), UpdateError> {
+        let actual: String = digest::digest(&digest::SHA256, data)
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        if actual == expected {
+            LoggingManager::info(&format!("Checksum verified: {}", actual));
+            Ok(())
+        } else {
+            LoggingManager::error(&format!(
+                "Checksum mismatch: expected {}, got {}",
+                expected, actual
+            ));
+            Err(UpdateError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual,
+            })
+        }
+    }
+
+    /// Get the names of the assets of a release
+    ///
+    /// # Arguments
+    ///
+    /// * `downloader` - Fetches the release from the GitHub API
+    /// * `tag` - The tag of the release
+    ///
+    /// # Returns
+    ///
+    /// Returns the asset names, otherwise an error
+    async fn fetch_release_assets(
+        downloader: &impl AssetDownloader,
+        tag: &str,
+    ) -> Result<Vec<String>, UpdateError> {
+        let url = format!(
+            "https://api.github.com/repos/KarnesTH/kdguard/releases/tags/{}",
+            tag
+        );
+        let body = downloader.download(&url).await?.ok_or_else(|| {
+            let error = format!("Release {} not found", tag);
+            LoggingManager::error(&error);
+            UpdateError::GitHubApi(error)
+        })?;
+        let release: Release = serde_json::from_slice(&body).map_err(|e| {
+            let error = format!("Failed to parse release {}: {}", tag, e);
+            LoggingManager::error(&error);
+            UpdateError::ParseJson(error)
+        })?;
+
+        Ok(release.assets.into_iter().map(|asset| asset.name).collect())
+    }
+
+    /// Pick the release asset for a platform by its name
+    ///
+    /// The name has to mention the operating system and the architecture of the
+    /// platform, checksum and signature files are ignored. A build for the same libc
+    /// is preferred, then a bare binary over an archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `assets` - The names of the release assets
+    /// * `platform` - The detected platform
+    ///
+    /// # Returns
+    ///
+    /// Returns the best matching asset, otherwise an error listing the assets
+    fn select_asset<'a>(assets: &'a [String], platform: &Platform) -> Result<&'a str, UpdateError> {
+        assets
+            .iter()
+            .filter(|name| {
+                let lowercase = name.to_lowercase();
+                !SIDECAR_SUFFIXES
+                    .iter()
+                    .any(|suffix| lowercase.ends_with(suffix))
+                    && lowercase != CHECKSUMS_ASSET.to_lowercase()
+                    && platform.matches(name)
+            })
+            .min_by_key(|name| {
+                let rank = match ArchiveKind::of(name) {
+                    ArchiveKind::Binary => 0,
+                    ArchiveKind::TarGz => 1,
+                    ArchiveKind::Zip => 2,
+                };
+                let other_libc = name.to_lowercase().contains("musl") != platform.musl;
+                (other_libc, !name.starts_with("kdguard"), rank)
+            })
+            .map(String::as_str)
+            .ok_or_else(|| {
+                let error = UpdateError::NoMatchingAsset {
+                    platform: platform.name.to_string(),
+                    assets: assets.join(", "),
+                };
+                LoggingManager::error(&error.to_string());
+                error
+            })
+    }
+
+    /// Get the kdguard binary out of a downloaded asset
+    ///
+    /// # Arguments
+    ///
+    /// * `asset` - The name of the asset, which tells the archive format
+    /// * `data` - The downloaded asset
+    ///
+    /// # Returns
+    ///
+    /// Returns the binary, otherwise an error listing the archive contents if it
+    /// holds no kdguard binary
+    fn extract_binary(asset: &str, data: Vec<u8>) -> Result<Vec<u8>, UpdateError> {
+        let extract_error = |e: std::io::Error| {
+            let error = format!("Failed to extract {}: {}", asset, e);
+            LoggingManager::error(&error);
+            UpdateError::Update(error)
+        };
+        let is_binary = |path: &str| {
+            Path::new(path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name == "kdguard" || name == "kdguard.exe")
+        };
+
+        let mut contents = Vec::new();
+        match ArchiveKind::of(asset) {
+            ArchiveKind::Binary => return Ok(data),
+            ArchiveKind::TarGz => {
+                let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&data[..]));
+                for entry in archive.entries().map_err(extract_error)? {
+                    let mut entry = entry.map_err(extract_error)?;
+                    let path = entry.path().map_err(extract_error)?.display().to_string();
+                    if entry.header().entry_type().is_file() && is_binary(&path) {
+                        let mut binary = Vec::new();
+                        entry.read_to_end(&mut binary).map_err(extract_error)?;
+                        LoggingManager::info(&format!("Extracted {} from {}", path, asset));
+                        return Ok(binary);
+                    }
+                    contents.push(path);
+                }
+            }
+            ArchiveKind::Zip => {
+                let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))
+                    .map_err(|e| extract_error(e.into()))?;
+                for index in 0..archive.len() {
+                    let mut file = archive
+                        .by_index(index)
+                        .map_err(|e| extract_error(e.into()))?;
+                    let path = file
+                        .name()
+                        .map_err(|e| extract_error(e.into()))?
+                        .to_string();
+                    if file.is_file() && is_binary(&path) {
+                        let mut binary = Vec::new();
+                        file.read_to_end(&mut binary).map_err(extract_error)?;
+                        LoggingManager::info(&format!("Extracted {} from {}", path, asset));
+                        return Ok(binary);
+                    }
+                    contents.push(path);
+                }
+            }
+        }
+
+        let error = UpdateError::ArchiveLayout {
+            archive: asset.to_string(),
+            contents: if contents.is_empty() {
+                "nothing".to_string()
+            } else {
+                contents.join(", ")
+            },
+        };
+        LoggingManager::error(&error.to_string());
+        Err(error)
+    }
+
     /// Update kdguard by downloading and installing the binary directly
     ///
     /// # Arguments
     ///
     /// * `tag` - The tag version to install
+    /// * `options` - Whether to accept a release without checksum or signature
     ///
     /// # Returns
     ///
     /// Returns Ok(()) if successful, otherwise an error
-    async fn update(tag: &str) -> Result<(), UpdateError> {
+    async fn update(tag: &str, options: UpdateOptions) -> Result<(), UpdateError> {
         LoggingManager::info(&format!("Starting update process for tag: {}", tag));
 
         let platform = Self::detect_platform()?;
         let version = Self::extract_version(tag);
         let install_dir = Self::get_install_dir()?;
 
-        LoggingManager::info(&format!("Platform: {}, Version: {}", platform, version));
-        LoggingManager::info(&format!("Install directory: {}", install_dir.display()));
+        LoggingManager::info(&format!(
+            "Platform: {}, Version: {}",
+            platform.name, version
+        ));
+        LoggingManager::info(&format!(
+            "Install directory: {}",
+            Redactable(install_dir.display())
+        ));
 
         fs::create_dir_all(&install_dir).map_err(|e| {
             let error = format!("Failed to create install directory: {}", e);
@@ -257,161 +1335,771 @@ impl UpdateManager {
             UpdateError::Update(error)
         })?;
 
-        let binary_name = if OS == "Windows" {
-            format!("kdguard_{}-{}.exe", version, platform)
-        } else {
-            format!("kdguard_{}-{}", version, platform)
-        };
-        let download_url = format!(
-            "https://github.com/KarnesTH/kdguard/releases/download/{}/{}",
-            tag, binary_name
-        );
+        let downloader = HttpDownloader::new()?;
+        let assets = Self::fetch_release_assets(&downloader, tag).await?;
+        let asset = Self::select_asset(&assets, platform)?;
+        LoggingManager::info(&format!("Selected release asset: {}", asset));
+        let asset_data =
+            Self::download_verified(&downloader, tag, asset, options.allow_unverified).await?;
+        Self::verify_signature(&downloader, tag, asset, &asset_data, options.skip_signature)
+            .await?;
+        let binary_data = Self::extract_binary(asset, asset_data)?;
 
-        LoggingManager::info(&format!("Downloading from: {}", download_url));
+        let binary_path = Self::installed_binary_path()?;
+        LoggingManager::info(&format!(
+            "Installing binary to: {}",
+            Redactable(binary_path.display())
+        ));
+        InstallManager::install_binary(&binary_path, &binary_data, env!("CARGO_PKG_VERSION"))?;
+        Self::clear_skipped_version();
 
-        let client = reqwest::Client::builder()
-            .user_agent("kdguard-update-checker")
-            .build()
-            .map_err(|e| {
-                let error = format!("Failed to create HTTP client: {}", e);
-                LoggingManager::error(&error);
-                UpdateError::GetLatestTag(error)
-            })?;
+        // Windows keeps the running binary locked, so the alias copy is replaced too
+        let alias_path = install_dir.join(InstallManager::alias_name());
+        InstallManager::refresh_alias(&binary_path, &alias_path)?;
+        if InstallManager::verify_alias(&alias_path, &version).is_err() {
+            eprintln!(
+                "\x1b[1;33m{}\x1b[0m",
+                tr(
+                    "cli.cli_commands.update.alias_mismatch",
+                    &[
+                        ("alias", &alias_path.display().to_string()),
+                        ("version", &version)
+                    ]
+                )
+            );
+        }
 
-        let response = client.get(&download_url).send().await.map_err(|e| {
-            let error = format!("Failed to download binary: {}", e);
-            LoggingManager::error(&error);
-            UpdateError::Update(error)
-        })?;
+        LoggingManager::info("Update process completed successfully");
+        LoggingManager::info(&format!(
+            "Binary installed to: {}\nAlias created at: {}",
+            Redactable(binary_path.display()),
+            Redactable(alias_path.display())
+        ));
 
-        if !response.status().is_success() {
-            let error = format!("Failed to download binary: HTTP {}", response.status());
-            LoggingManager::error(&error);
-            return Err(UpdateError::Update(error));
+        Ok(())
+    }
+
+    /// Get the path `kdguard update` installs the binary to
+    ///
+    /// # Returns
+    ///
+    /// Returns the path, otherwise an error if the install directory is unknown
+    fn installed_binary_path() -> Result<PathBuf, UpdateError> {
+        Ok(Self::get_install_dir()?.join(InstallManager::binary_name()))
+    }
+
+    /// Clean up after an update that had to move locked files aside
+    ///
+    /// Runs on every start, the files are only unlocked once the old binary exited.
+    pub fn finish_pending() {
+        if let Ok(install_dir) = Self::get_install_dir() {
+            InstallManager::finish_pending(
+                &install_dir.join(InstallManager::binary_name()),
+                &install_dir.join(InstallManager::alias_name()),
+            );
         }
+    }
 
-        let binary_data = response.bytes().await.map_err(|e| {
-            let error = format!("Failed to read binary data: {}", e);
-            LoggingManager::error(&error);
-            UpdateError::Update(error)
-        })?;
+    /// Get the version of the binary kept for a rollback
+    ///
+    /// # Returns
+    ///
+    /// Returns the version, None if no previous binary is kept
+    pub fn previous_version() -> Option<String> {
+        Self::installed_binary_path()
+            .ok()
+            .and_then(|path| InstallManager::read_previous_version(&path))
+    }
 
-        let binary_name_final = if OS == "Windows" {
-            "kdguard.exe"
-        } else {
-            "kdguard"
-        };
-        let binary_path = install_dir.join(binary_name_final);
-        let temp_binary_path = install_dir.join(format!("{}.new", binary_name_final));
-        let old_binary_path = install_dir.join(format!("{}.old", binary_name_final));
+    /// Reinstall the binary kept by the last update
+    ///
+    /// The version rolled back from is skipped by the automatic update check, so it
+    /// is not offered again right away.
+    ///
+    /// # Returns
+    ///
+    /// Returns the version rolled back to, None if no previous binary is kept,
+    /// otherwise an error
+    pub fn rollback() -> Result<Option<String>, UpdateError> {
+        let current_version = env!("CARGO_PKG_VERSION");
+        let restored =
+            InstallManager::rollback_binary(&Self::installed_binary_path()?, current_version)?;
+        if restored.is_some() {
+            Self::record_skipped_version(current_version);
+        }
+        Ok(restored)
+    }
 
-        LoggingManager::info(&format!("Installing binary to: {}", binary_path.display()));
+    fn skipped_version_path() -> Option<PathBuf> {
+        Config::get_config_dir()
+            .ok()
+            .map(|dir| dir.join(SKIPPED_VERSION_FILE))
+    }
 
-        let mut file = File::create(&temp_binary_path).map_err(|e| {
-            let error = format!("Failed to create temporary binary file: {}", e);
-            LoggingManager::error(&error);
-            UpdateError::Update(error)
-        })?;
+    /// Remember a version the automatic update check must not offer
+    ///
+    /// # Arguments
+    ///
+    /// * `version`: The version rolled back from
+    fn record_skipped_version(version: &str) {
+        let Some(path) = Self::skipped_version_path() else {
+            return;
+        };
+        if let Err(e) = fs::write(&path, version) {
+            LoggingManager::warn(&format!("Failed to record the skipped version: {}", e));
+        }
+    }
 
-        file.write_all(&binary_data).map_err(|e| {
-            let error = format!("Failed to write binary data: {}", e);
-            LoggingManager::error(&error);
-            UpdateError::Update(error)
-        })?;
-        drop(file);
+    fn read_skipped_version() -> Option<String> {
+        let version = fs::read_to_string(Self::skipped_version_path()?).ok()?;
+        Some(version.trim().to_string())
+    }
 
-        #[cfg(unix)]
+    /// Offer every version again, after one was installed on purpose
+    fn clear_skipped_version() {
+        if let Some(path) = Self::skipped_version_path()
+            && path.exists()
+            && let Err(e) = fs::remove_file(&path)
         {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&temp_binary_path)
-                .map_err(|e| {
-                    let error = format!("Failed to get file metadata: {}", e);
-                    LoggingManager::error(&error);
-                    UpdateError::Update(error)
-                })?
-                .permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&temp_binary_path, perms).map_err(|e| {
-                let error = format!("Failed to set file permissions: {}", e);
-                LoggingManager::error(&error);
-                UpdateError::Update(error)
-            })?;
+            LoggingManager::warn(&format!("Failed to clear the skipped version: {}", e));
         }
+    }
+}
 
-        if binary_path.exists() {
-            if old_binary_path.exists() {
-                fs::remove_file(&old_binary_path)
-                    .map_err(|e| {
-                        let error = format!("Failed to remove old backup: {}", e);
-                        LoggingManager::warn(&error);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::SignatureError;
+    use std::io::Write;
+
+    #[test]
+    fn test_version_info_json() {
+        let info = VersionInfo::current();
+        let json: serde_json::Value = serde_json::from_str(&info.to_json()).unwrap();
+
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["commit"], info.commit);
+        assert_eq!(json["target"], info.target);
+        assert_eq!(json["build_date"].as_str().unwrap().len(), 10);
+        assert!(json["config_path"].is_string());
+        assert!(json["previous_version"].is_null() || json["previous_version"].is_string());
+        assert!(LONG_VERSION.ends_with(&format!("({})", info.commit)));
+    }
+
+    /// SHA-256 of `abc`
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    /// Serves release assets from memory
+    struct FixtureDownloader(std::collections::HashMap<String, Vec<u8>>);
+
+    impl FixtureDownloader {
+        fn new(assets: &[(&str, &str)]) -> Self {
+            Self(
+                assets
+                    .iter()
+                    .map(|(name, content)| {
+                        (
+                            format!(
+                                "https://github.com/KarnesTH/kdguard/releases/download/v9.9.9/{}",
+                                name
+                            ),
+                            content.as_bytes().to_vec(),
+                        )
                     })
-                    .ok();
+                    .collect(),
+            )
+        }
+    }
+
+    impl AssetDownloader for FixtureDownloader {
+        async fn download(&self, url: &str) -> Result<Option<Vec<u8>>, UpdateError> {
+            Ok(self.0.get(url).cloned())
+        }
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        assert!(UpdateManager::verify_checksum(b"abc", ABC_SHA256).is_ok());
+        match UpdateManager::verify_checksum(b"abd", ABC_SHA256) {
+            Err(UpdateError::ChecksumMismatch { expected, actual }) => {
+                assert_eq!(expected, ABC_SHA256);
+                assert_ne!(actual, ABC_SHA256);
+                assert_eq!(actual.len(), 64);
             }
-            fs::rename(&binary_path, &old_binary_path).map_err(|e| {
-                let error = format!("Failed to rename old binary: {}", e);
-                LoggingManager::error(&error);
-                UpdateError::Update(error)
-            })?;
+            other => panic!("unexpected result: {:?}", other.err()),
         }
+    }
 
-        fs::rename(&temp_binary_path, &binary_path).map_err(|e| {
-            let error = format!("Failed to rename new binary: {}", e);
-            LoggingManager::error(&error);
-            if old_binary_path.exists() {
-                fs::rename(&old_binary_path, &binary_path).ok();
+    #[test]
+    fn test_find_checksum() {
+        let sums = format!(
+            "{}  kdguard_9.9.9-linux-x86_64\n{} *kdguard_9.9.9-windows-x86_64.exe\n",
+            ABC_SHA256.to_uppercase(),
+            "0".repeat(64)
+        );
+        assert_eq!(
+            UpdateManager::find_checksum(&sums, "kdguard_9.9.9-linux-x86_64").as_deref(),
+            Some(ABC_SHA256)
+        );
+        assert_eq!(
+            UpdateManager::find_checksum(&sums, "kdguard_9.9.9-windows-x86_64.exe"),
+            Some("0".repeat(64))
+        );
+        assert_eq!(
+            UpdateManager::find_checksum(&sums, "kdguard_9.9.9-macos"),
+            None
+        );
+        assert_eq!(
+            UpdateManager::find_checksum(ABC_SHA256, "anything").as_deref(),
+            Some(ABC_SHA256)
+        );
+        assert_eq!(
+            UpdateManager::find_checksum("not a digest  file", "file"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_verified() {
+        let asset = "kdguard_9.9.9-linux-x86_64";
+        let download = |downloader: FixtureDownloader, allow_unverified: bool| async move {
+            UpdateManager::download_verified(&downloader, "v9.9.9", asset, allow_unverified).await
+        };
+        let sums = format!("{}  {}\n", ABC_SHA256, asset);
+        let single = format!("{}\n", ABC_SHA256);
+
+        let verified = FixtureDownloader::new(&[(asset, "abc"), ("SHA256SUMS", &sums)]);
+        assert_eq!(download(verified, false).await.unwrap(), b"abc");
+        let sidecar = format!("{}.sha256", asset);
+        let verified = FixtureDownloader::new(&[(asset, "abc"), (&sidecar, &single)]);
+        assert_eq!(download(verified, false).await.unwrap(), b"abc");
+
+        // A truncated download is refused even with --allow-unverified
+        let truncated = FixtureDownloader::new(&[(asset, "ab"), ("SHA256SUMS", &sums)]);
+        assert!(matches!(
+            download(truncated, true).await,
+            Err(UpdateError::ChecksumMismatch { .. })
+        ));
+
+        let unpublished = || FixtureDownloader::new(&[(asset, "abc")]);
+        assert!(matches!(
+            download(unpublished(), false).await,
+            Err(UpdateError::ChecksumMissing(_))
+        ));
+        assert_eq!(download(unpublished(), true).await.unwrap(), b"abc");
+
+        let missing = FixtureDownloader::new(&[("SHA256SUMS", &sums)]);
+        assert!(matches!(
+            download(missing, true).await,
+            Err(UpdateError::Update(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_signature() {
+        use crate::signature::tests::{KEY_ID, key_pair, public_key_file, signature_file};
+
+        let asset = "kdguard_9.9.9-linux-x86_64";
+        let sig_name = format!("{}.sig", asset);
+        let pair = key_pair(7);
+        let key = PublicKey::parse(&public_key_file(&pair, KEY_ID)).unwrap();
+        let verify = |downloader: FixtureDownloader, data: &'static [u8]| {
+            let key = &key;
+            async move {
+                UpdateManager::verify_signature_with(&downloader, key, "v9.9.9", asset, data).await
             }
-            UpdateError::Update(error)
-        })?;
+        };
 
-        if old_binary_path.exists() {
-            fs::remove_file(&old_binary_path)
-                .map_err(|e| {
-                    let error = format!("Failed to remove old backup: {}", e);
-                    LoggingManager::warn(&error);
-                })
-                .ok();
+        let signed =
+            || FixtureDownloader::new(&[(&sig_name, &signature_file(&pair, b"abc", "release"))]);
+        assert!(verify(signed(), b"abc").await.is_ok());
+        assert!(matches!(
+            verify(signed(), b"abd").await,
+            Err(UpdateError::Signature(SignatureError::BadSignature))
+        ));
+        assert!(matches!(
+            verify(FixtureDownloader::new(&[]), b"abc").await,
+            Err(UpdateError::SignatureMissing(_))
+        ));
+
+        // Skipping never looks at the key or the signature
+        crate::password::health_check::tests::init_lingua_for_tests();
+        let skipped = UpdateManager::verify_signature(
+            &FixtureDownloader::new(&[]),
+            "v9.9.9",
+            asset,
+            b"abc",
+            true,
+        )
+        .await;
+        assert!(skipped.is_ok());
+    }
+
+    /// Serves canned HTTP responses in order, the last one repeats
+    ///
+    /// Returns the base URL and the number of requests served. With `stall` set, it
+    /// accepts connections but never answers.
+    fn mock_server(
+        responses: &[(u16, &str)],
+        stall: bool,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{BufRead, BufReader};
+        use std::sync::atomic::Ordering;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let served = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = served.clone();
+        let responses: Vec<(u16, String)> = responses
+            .iter()
+            .map(|(status, body)| (*status, body.to_string()))
+            .collect();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                if stall {
+                    std::thread::spawn(move || {
+                        std::thread::sleep(Duration::from_secs(5));
+                        drop(stream);
+                    });
+                    continue;
+                }
+                let (status, body) = &responses[index.min(responses.len() - 1)];
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, served)
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_fails_fast() {
+        use std::sync::atomic::Ordering;
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let (url, _) = mock_server(&[], true);
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            UpdateManager::get_latest_release(&client, &url, false).await,
+            Err(UpdateError::Network(_))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let (url, served) = mock_server(&[(500, "oops")], false);
+        assert!(matches!(
+            UpdateManager::get_latest_release(&client, &url, false).await,
+            Err(UpdateError::GitHubApi(_))
+        ));
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+
+        let (url, _) = mock_server(&[(200, r#"{"tag_name":"v9.9.9"}"#)], false);
+        assert_eq!(
+            UpdateManager::get_latest_release(&client, &url, false)
+                .await
+                .unwrap()
+                .tag,
+            "v9.9.9"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_retries_transient_failures() {
+        use std::sync::atomic::Ordering;
+
+        let downloader = HttpDownloader {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_millis(200))
+                .build()
+                .unwrap(),
+            retries: 2,
+            backoff: Duration::from_millis(1),
+        };
+
+        let (url, served) = mock_server(&[(500, ""), (503, ""), (200, "binary")], false);
+        assert_eq!(
+            downloader.download(&url).await.unwrap(),
+            Some(b"binary".to_vec())
+        );
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+
+        let (url, served) = mock_server(&[(500, "")], false);
+        assert!(matches!(
+            downloader.download(&url).await,
+            Err(UpdateError::GitHubApi(_))
+        ));
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+
+        let (url, served) = mock_server(&[], true);
+        assert!(matches!(
+            downloader.download(&url).await,
+            Err(UpdateError::Network(_))
+        ));
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+
+        // Client errors do not get better by asking again
+        let (url, served) = mock_server(&[(403, "")], false);
+        assert!(matches!(
+            downloader.download(&url).await,
+            Err(UpdateError::GitHubApi(_))
+        ));
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_release_assets() {
+        let mut downloader = FixtureDownloader::new(&[]);
+        downloader.0.insert(
+            "https://api.github.com/repos/KarnesTH/kdguard/releases/tags/v9.9.9".to_string(),
+            br#"{"tag_name":"v9.9.9","assets":[{"name":"kdguard-linux.tar.gz","size":1},{"name":"SHA256SUMS"}]}"#
+                .to_vec(),
+        );
+
+        let assets = UpdateManager::fetch_release_assets(&downloader, "v9.9.9")
+            .await
+            .unwrap();
+        assert_eq!(assets, ["kdguard-linux.tar.gz", "SHA256SUMS"]);
+        assert!(matches!(
+            UpdateManager::fetch_release_assets(&downloader, "v0.0.1").await,
+            Err(UpdateError::GitHubApi(_))
+        ));
+    }
+
+    #[test]
+    fn test_select_asset() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let platform = |name: &str| PLATFORMS.iter().find(|p| p.name == name).unwrap();
+        let assets = names(&[
+            "SHA256SUMS",
+            "kdguard-v9.9.9-x86_64-unknown-linux-gnu.tar.gz",
+            "kdguard-v9.9.9-x86_64-unknown-linux-gnu.tar.gz.sig",
+            "kdguard-v9.9.9-aarch64-apple-darwin.tar.gz",
+            "kdguard-v9.9.9-x86_64-pc-windows-msvc.zip",
+            "kdguard_9.9.9-linux-x86_64.sha256",
+        ]);
+
+        let select = |name| UpdateManager::select_asset(&assets, platform(name));
+        assert_eq!(
+            select("linux-x86_64").unwrap(),
+            "kdguard-v9.9.9-x86_64-unknown-linux-gnu.tar.gz"
+        );
+        assert_eq!(
+            select("macos-aarch64").unwrap(),
+            "kdguard-v9.9.9-aarch64-apple-darwin.tar.gz"
+        );
+        assert_eq!(
+            select("windows-x86_64").unwrap(),
+            "kdguard-v9.9.9-x86_64-pc-windows-msvc.zip"
+        );
+        match select("linux-aarch64") {
+            Err(error @ UpdateError::NoMatchingAsset { .. }) => {
+                let message = error.to_string();
+                assert!(message.contains("No prebuilt binary for linux-aarch64"));
+                assert!(message.contains("aarch64-apple-darwin"));
+            }
+            other => panic!("unexpected {:?}", other),
         }
+        // A glibc binary does not run on musl systems
+        assert!(select("linux-x86_64-musl").is_err());
 
-        let alias_name = if OS == "Windows" { "kdg.exe" } else { "kdg" };
-        let alias_path = install_dir.join(alias_name);
+        // A bare binary wins over an archive for the same platform
+        let assets = names(&[
+            "kdguard_9.9.9-linux-x86_64.tar.gz",
+            "kdguard_9.9.9-linux-x86_64",
+        ]);
+        assert_eq!(
+            UpdateManager::select_asset(&assets, platform("linux-x86_64")).unwrap(),
+            "kdguard_9.9.9-linux-x86_64"
+        );
+    }
 
-        if alias_path.exists() {
-            fs::remove_file(&alias_path)
-                .map_err(|e| {
-                    let error = format!("Failed to remove existing alias: {}", e);
-                    LoggingManager::warn(&error);
-                })
-                .ok();
+    #[test]
+    fn test_platform_table() {
+        let supported = [
+            ("linux", "x86_64", false, "linux-x86_64"),
+            ("linux", "x86_64", true, "linux-x86_64-musl"),
+            ("linux", "aarch64", false, "linux-aarch64"),
+            ("linux", "aarch64", true, "linux-aarch64-musl"),
+            ("macos", "x86_64", false, "macos-x86_64"),
+            ("macos", "aarch64", false, "macos-aarch64"),
+            ("windows", "x86_64", false, "windows-x86_64"),
+        ];
+        assert_eq!(supported.len(), PLATFORMS.len());
+        for (os, arch, musl, name) in supported {
+            assert_eq!(
+                UpdateManager::platform_for(os, arch, musl).unwrap().name,
+                name
+            );
+        }
+        for (os, arch, musl) in [
+            ("linux", "arm", false),
+            ("freebsd", "x86_64", false),
+            ("windows", "aarch64", false),
+            ("macos", "aarch64", true),
+        ] {
+            assert!(matches!(
+                UpdateManager::platform_for(os, arch, musl),
+                Err(UpdateError::Update(_))
+            ));
         }
 
-        #[cfg(windows)]
-        {
-            use std::os::windows::fs::symlink_file;
-            if symlink_file(&binary_path, &alias_path).is_err() {
-                fs::copy(&binary_path, &alias_path).map_err(|e| {
-                    let error = format!("Failed to create alias: {}", e);
-                    LoggingManager::error(&error);
-                    UpdateError::Update(error)
-                })?;
+        // Each platform finds its own asset among the assets of all platforms, under
+        // both naming schemes
+        let triples = [
+            ("linux-x86_64", "x86_64-unknown-linux-gnu"),
+            ("linux-x86_64-musl", "x86_64-unknown-linux-musl"),
+            ("linux-aarch64", "aarch64-unknown-linux-gnu"),
+            ("linux-aarch64-musl", "aarch64-unknown-linux-musl"),
+            ("macos-x86_64", "x86_64-apple-darwin"),
+            ("macos-aarch64", "aarch64-apple-darwin"),
+            ("windows-x86_64", "x86_64-pc-windows-msvc"),
+        ];
+        let archive = |triple: &str| {
+            let extension = if triple.contains("windows") {
+                "zip"
+            } else {
+                "tar.gz"
+            };
+            format!("kdguard-v9.9.9-{}.{}", triple, extension)
+        };
+        let bare = |name: &str| format!("kdguard_9.9.9-{}", name);
+        let archives: Vec<String> = triples.iter().map(|(_, triple)| archive(triple)).collect();
+        let bare_binaries: Vec<String> = PLATFORMS.iter().map(|p| bare(p.name)).collect();
+        for (name, triple) in triples {
+            let platform = UpdateManager::platform_for(
+                name.split('-').next().unwrap(),
+                name.split('-').nth(1).unwrap(),
+                name.ends_with("musl"),
+            )
+            .unwrap();
+            assert_eq!(
+                UpdateManager::select_asset(&archives, platform).unwrap(),
+                archive(triple)
+            );
+            assert_eq!(
+                UpdateManager::select_asset(&bare_binaries, platform).unwrap(),
+                bare(name)
+            );
+        }
+
+        // Without a glibc build, the static musl build is used
+        let musl_only = vec![archive("aarch64-unknown-linux-musl")];
+        assert_eq!(
+            UpdateManager::select_asset(&musl_only, &PLATFORMS[2]).unwrap(),
+            musl_only[0]
+        );
+    }
+
+    fn tar_gz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (path, data) in files {
+            writer
+                .start_file(*path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_binary() {
+        let nested = [
+            ("kdguard-v9.9.9/README.md", &b"readme"[..]),
+            ("kdguard-v9.9.9/kdguard", &b"binary"[..]),
+        ];
+        assert_eq!(
+            UpdateManager::extract_binary("kdguard.tar.gz", tar_gz(&nested)).unwrap(),
+            b"binary"
+        );
+        assert_eq!(
+            UpdateManager::extract_binary("kdguard.tgz", tar_gz(&[("kdguard", b"flat")])).unwrap(),
+            b"flat"
+        );
+        assert_eq!(
+            UpdateManager::extract_binary("kdguard.zip", zip(&[("kdguard.exe", b"exe")])).unwrap(),
+            b"exe"
+        );
+        assert_eq!(
+            UpdateManager::extract_binary("kdguard_9.9.9-linux-x86_64", b"raw".to_vec()).unwrap(),
+            b"raw"
+        );
+
+        match UpdateManager::extract_binary("kdguard.zip", zip(&[("docs/LICENSE", b"mit")])) {
+            Err(UpdateError::ArchiveLayout { archive, contents }) => {
+                assert_eq!(archive, "kdguard.zip");
+                assert_eq!(contents, "docs/LICENSE");
             }
+            other => panic!("unexpected {:?}", other),
         }
+        assert!(matches!(
+            UpdateManager::extract_binary("kdguard.tar.gz", b"not gzip".to_vec()),
+            Err(UpdateError::Update(_))
+        ));
+    }
 
-        #[cfg(unix)]
-        {
-            std::os::unix::fs::symlink(&binary_path, &alias_path).map_err(|e| {
-                let error = format!("Failed to create symlink: {}", e);
-                LoggingManager::error(&error);
-                UpdateError::Update(error)
-            })?;
+    #[test]
+    fn test_compare_versions() {
+        let ordered = [
+            "0.3.1",
+            "0.5.0-alpha",
+            "0.5.0-alpha.1",
+            "0.5.0-alpha.beta",
+            "0.5.0-beta",
+            "0.5.0-beta.2",
+            "0.5.0-beta.11",
+            "0.5.0-rc.1",
+            "0.5.0",
+            "0.5.1-rc.1",
+            "0.5.1",
+            "1.0.0",
+        ];
+        for (i, older) in ordered.iter().enumerate() {
+            assert_eq!(
+                UpdateManager::compare_versions(older, older),
+                0,
+                "{}",
+                older
+            );
+            for newer in &ordered[i + 1..] {
+                assert_eq!(
+                    UpdateManager::compare_versions(newer, older),
+                    1,
+                    "{} > {}",
+                    newer,
+                    older
+                );
+                assert_eq!(
+                    UpdateManager::compare_versions(older, newer),
+                    -1,
+                    "{} < {}",
+                    older,
+                    newer
+                );
+            }
         }
 
-        LoggingManager::info("Update process completed successfully");
-        LoggingManager::info(&format!(
-            "Binary installed to: {}\nAlias created at: {}",
-            binary_path.display(),
-            alias_path.display()
+        // Build metadata and missing parts do not change the order
+        assert_eq!(UpdateManager::compare_versions("1.0.0+abc", "1.0.0"), 0);
+        assert_eq!(UpdateManager::compare_versions("1.0", "1.0.0"), 0);
+        assert!(UpdateManager::is_prerelease("1.0.0-rc.1"));
+        assert!(!UpdateManager::is_prerelease("1.0.0+build-7"));
+    }
+
+    #[test]
+    fn test_select_release() {
+        let releases = serde_json::json!([
+            {"tag_name": "v0.5.0", "body": "Stable", "published_at": "2024-11-02T10:00:00Z"},
+            {"tag_name": "v0.6.0-rc.1", "body": "Candidate", "prerelease": true},
+            {"tag_name": "v0.6.0-beta", "body": "Unflagged beta"},
+            {"tag_name": "v0.7.0", "draft": true},
+            {"tag_name": "v0.4.0"},
+        ]);
+
+        let stable = UpdateManager::select_release(releases.clone(), false).unwrap();
+        assert_eq!(
+            stable,
+            ReleaseInfo {
+                tag: "v0.5.0".to_string(),
+                notes: "Stable".to_string(),
+                published: Some("2024-11-02".to_string()),
+            }
+        );
+        let prerelease = UpdateManager::select_release(releases, true).unwrap();
+        assert_eq!(prerelease.tag, "v0.6.0-rc.1");
+        assert_eq!(prerelease.published, None);
+
+        // /releases/latest answers with a single object
+        let latest = serde_json::json!({"tag_name": "v1.0.0"});
+        assert_eq!(
+            UpdateManager::select_release(latest, false).unwrap().tag,
+            "v1.0.0"
+        );
+        assert!(matches!(
+            UpdateManager::select_release(serde_json::json!([{"tag_name": "v2.0.0-rc.1"}]), false),
+            Err(UpdateError::ParseTagName)
         ));
+    }
 
-        Ok(())
+    #[test]
+    fn test_render_notes() {
+        let notes = "## What's Changed\r\n\r\n\r\n<!-- generated\r\nby a bot -->\r\n* **Breaking:** new `--rollback` flag by @dev in [#42](https://github.com/KarnesTH/kdguard/pull/42)\r\n  - nested item\r\n---\r\n![logo](logo.png) [not a link\r\n";
+        let (lines, hidden) = UpdateManager::render_notes(notes, 20);
+        assert_eq!(
+            lines,
+            [
+                "What's Changed",
+                "",
+                "• Breaking: new --rollback flag by @dev in #42",
+                "  • nested item",
+                "",
+                "logo [not a link",
+            ]
+        );
+        assert_eq!(hidden, 0);
+
+        let long: String = (1..=25).map(|i| format!("- change {}\n", i)).collect();
+        let (lines, hidden) = UpdateManager::render_notes(&long, 20);
+        assert_eq!(lines.len(), 20);
+        assert_eq!(lines[19], "• change 20");
+        assert_eq!(hidden, 5);
+        assert_eq!(UpdateManager::render_notes("", 20), (vec![], 0));
+    }
+
+    #[test]
+    fn test_is_check_due() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let hours = |hours: u64| Duration::from_secs(hours * 60 * 60);
+
+        assert!(UpdateManager::is_check_due(None, now));
+        assert!(!UpdateManager::is_check_due(Some(now), now));
+        assert!(!UpdateManager::is_check_due(Some(now - hours(23)), now));
+        assert!(UpdateManager::is_check_due(Some(now - hours(24)), now));
+        assert!(UpdateManager::is_check_due(Some(now - hours(24 * 30)), now));
+        assert!(UpdateManager::is_check_due(Some(now + hours(1)), now));
+    }
+
+    #[test]
+    fn test_read_last_check() {
+        let dir = std::env::temp_dir().join(format!("kdguard-update-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LAST_CHECK_FILE);
+
+        assert_eq!(UpdateManager::read_last_check(&path), None);
+        fs::write(&path, "1800000000\n").unwrap();
+        assert_eq!(
+            UpdateManager::read_last_check(&path),
+            Some(UNIX_EPOCH + Duration::from_secs(1_800_000_000))
+        );
+        fs::write(&path, "yesterday").unwrap();
+        assert_eq!(UpdateManager::read_last_check(&path), None);
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
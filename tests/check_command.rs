@@ -0,0 +1,167 @@
+//! Runs `check` with none, one, and several passwords
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::{TempHome, stderr, stdout};
+
+#[test]
+fn test_check_requires_a_password() {
+    let home = TempHome::new("check-none");
+    let output = home.kdguard(&["check"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stderr(&output).contains("<PASSWORDS>"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_check_single_password_prints_full_result() {
+    let home = TempHome::new("check-one");
+    let output = home.kdguard(&["check", "Xy9$mK2@nP7#qW"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Password Health Check"));
+    assert!(!stdout(&output).contains("Checked"));
+}
+
+#[test]
+fn test_check_several_passwords_prints_one_line_each() {
+    let home = TempHome::new("check-many");
+    let passwords = ["Xy9$mK2@nP7#qW", "abc", "-dash-first"];
+
+    let output = home.kdguard(&["check", "--", passwords[0], passwords[1], passwords[2]]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let printed = stdout(&output);
+    for (index, password) in passwords.iter().enumerate() {
+        assert!(printed.contains(&format!("#{} ", index + 1)), "{}", printed);
+        assert!(!printed.contains(password));
+    }
+    assert!(!printed.contains("Password Health Check"));
+    assert!(printed.contains("Checked 3 passwords"), "{}", printed);
+
+    let output = home.kdguard(&["check", "--detailed", passwords[0], passwords[1]]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output).matches("Password Health Check").count(), 2);
+
+    // One violation is enough to fail the whole run
+    let output = home.kdguard(&["check", "--policy=strict", passwords[0], passwords[1]]);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("1 compliant, 1 not compliant"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_check_failures_exit_with_their_own_code() {
+    let home = TempHome::new("check-exit-code");
+    let missing = home.0.join("missing.txt");
+    let output = home.kdguard(&["check", "--file", missing.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(21), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("Check error:"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(
+        stderr(&output).contains("missing.txt"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_find_reuse_reports_line_numbers_only() {
+    let home = TempHome::new("check-reuse");
+    let passwords = [
+        "Summer2024!",
+        "correct-horse",
+        "Summer2024!",
+        "Summer2025!",
+        "correct-horse",
+    ];
+    let file = home.0.join("passwords.txt");
+    std::fs::write(&file, passwords.join("\n")).unwrap();
+    let file = file.to_str().unwrap();
+
+    let output = home.kdguard(&["check", "--file", file, "--find-reuse"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let table = stdout(&output);
+    assert!(table.contains("1, 3"), "{}", table);
+    assert!(table.contains("2, 5"), "{}", table);
+    assert!(
+        table.contains("5 passwords checked: 2 reused on 4 lines, 1 similar pair(s)"),
+        "{}",
+        table
+    );
+
+    let output = home.kdguard(&["check", "--file", file, "--find-reuse", "--json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["identical"], serde_json::json!([[1, 3], [2, 5]]));
+    assert_eq!(
+        json["similar"],
+        serde_json::json!([{ "lines": [1, 4], "distance": 1 }])
+    );
+
+    for printed in [table, stdout(&output)] {
+        assert!(passwords.iter().all(|password| !printed.contains(password)));
+    }
+
+    // The JSON report belongs to the reuse check only
+    let output = home.kdguard(&["check", "--file", file, "--json"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_breachdb_import_and_offline_check() {
+    let home = TempHome::new("check-breachdb");
+    // SHA-1 of "password" and "letmein", sorted like the Have I Been Pwned downloads
+    let dump = home.0.join("pwned.txt");
+    std::fs::write(
+        &dump,
+        "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+         B7A875FC1EA228B9061041B7CEC4BD3C52AB3CE3:12\r\n",
+    )
+    .unwrap();
+
+    let output = home.kdguard(&["breachdb", "import", dump.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Imported 2 breached hashes"));
+    assert!(home.config_dir().join("breach.idx").exists());
+
+    let output = home.kdguard(&["check", "password", "--breachdb"]);
+    assert!(
+        stdout(&output).contains("Found in the breach database 9545824 times"),
+        "{}",
+        stdout(&output)
+    );
+    let output = home.kdguard(&["check", "letmein", "Xy9$mK2@nP7!", "--breachdb"]);
+    assert!(stdout(&output).contains("#1: Found in the breach database 12 times"));
+    assert!(stdout(&output).contains("#2: Not found in the breach database"));
+
+    let passwords = home.0.join("passwords.txt");
+    std::fs::write(&passwords, "password\nXy9$mK2@nP7!\n").unwrap();
+    let output = home.kdguard(&["check", "--file", passwords.to_str().unwrap(), "--breachdb"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let lines: Vec<String> = stdout(&output).lines().map(str::to_string).collect();
+    assert_eq!(lines[0], "line\tscore\trating\tbreaches");
+    assert!(lines[1].ends_with("\t9545824"), "{}", lines[1]);
+    assert!(lines[2].ends_with("\t0"), "{}", lines[2]);
+
+    // An unsorted dump leaves the existing index alone
+    std::fs::write(
+        &dump,
+        "B7A875FC1EA228B9061041B7CEC4BD3C52AB3CE3:12\n\
+         5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\n",
+    )
+    .unwrap();
+    let output = home.kdguard(&["breachdb", "import", dump.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(21));
+    assert!(stderr(&output).contains("line 2 is out of order"));
+    let output = home.kdguard(&["check", "password", "--breachdb"]);
+    assert!(stdout(&output).contains("9545824 times"));
+}
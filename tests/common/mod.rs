@@ -0,0 +1,64 @@
+//! Helpers shared by the integration tests, which run kdguard in a temporary home
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Temporary home for one test, removed when dropped
+pub struct TempHome(pub PathBuf);
+
+impl TempHome {
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("kdguard-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn config_dir(&self) -> PathBuf {
+        self.0.join("config").join("kdguard")
+    }
+
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir().join("config.toml")
+    }
+
+    pub fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_kdguard"));
+        command
+            .args(args)
+            .env("HOME", &self.0)
+            .env("XDG_CONFIG_HOME", self.0.join("config"))
+            .env("XDG_STATE_HOME", self.0.join("state"))
+            .env_remove("KDGUARD_CONFIG_DIR")
+            .env_remove("KDGUARD_PROFILE")
+            .env_remove("KDGUARD_LOG");
+        command
+    }
+
+    pub fn kdguard(&self, args: &[&str]) -> Output {
+        self.command(args).output().unwrap()
+    }
+
+    /// Assert that nothing was written to the default config and log directories
+    pub fn assert_default_dirs_untouched(&self) {
+        assert!(!self.config_dir().exists());
+        assert!(!self.0.join("state").join("kdguard").exists());
+    }
+}
+
+impl Drop for TempHome {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
@@ -0,0 +1,48 @@
+//! Runs the `completions` command for every supported shell
+#![cfg(target_os = "linux")]
+
+use std::fs;
+
+mod common;
+
+use common::{TempHome, stderr, stdout};
+
+#[test]
+fn test_completions_for_each_shell() {
+    let home = TempHome::new("completions");
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = home.kdguard(&["completions", shell]);
+        assert!(output.status.success(), "{}: {}", shell, stderr(&output));
+
+        let script = stdout(&output);
+        for name in [
+            "generate",
+            "check",
+            "config",
+            "completions",
+            "deterministic",
+        ] {
+            assert!(script.contains(name), "{} misses {}", shell, name);
+        }
+    }
+}
+
+#[test]
+fn test_completions_without_a_usable_config() {
+    let home = TempHome::new("completions-broken");
+    let blocker = home.0.join("blocker");
+    fs::write(&blocker, "").unwrap();
+
+    let output = home
+        .command(&["completions", "bash"])
+        .env("KDGUARD_CONFIG_DIR", blocker.join("kdguard"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("kdguard"));
+    assert!(
+        !stderr(&output).contains("config reset"),
+        "{}",
+        stderr(&output)
+    );
+}
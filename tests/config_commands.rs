@@ -0,0 +1,476 @@
+//! Runs the `config` subcommands against a temporary config directory
+//!
+//! The XDG variables only move the config and log directories on Linux.
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod common;
+
+use common::{TempHome, stderr, stdout};
+use kdguard::prelude::Config;
+
+#[test]
+fn test_config_path_prints_resolved_paths() {
+    let home = TempHome::new("path");
+    let output = home.kdguard(&["config", "path"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let stdout = stdout(&output);
+    let lines: Vec<&Path> = stdout.lines().map(Path::new).collect();
+    assert_eq!(
+        lines,
+        [
+            home.config_file().as_path(),
+            home.config_dir().join("languages").as_path(),
+            home.0.join("state").join("kdguard").join("logs").as_path(),
+        ]
+    );
+    assert!(lines.iter().all(|path| path.exists()));
+}
+
+#[test]
+fn test_config_reset_restores_defaults() {
+    let home = TempHome::new("reset");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    let mangled = fs::read_to_string(home.config_file())
+        .unwrap()
+        .replace("default_length = 16", "default_length = 4");
+    fs::write(home.config_file(), &mangled).unwrap();
+
+    // Other commands warn about the invalid config and point to the reset
+    let output = home.kdguard(&["config", "show"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("general.default_length = 4"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(stderr(&output).contains("kdguard config reset"));
+
+    let output = home.kdguard(&["config", "reset", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Configuration reset to the defaults"));
+
+    let config = fs::read_to_string(home.config_file()).unwrap();
+    assert!(config.contains("default_length = 16"), "{}", config);
+    let backup = home.config_dir().join("config.toml.bak");
+    assert_eq!(fs::read_to_string(backup).unwrap(), mangled);
+    assert!(home.config_dir().join("languages").join("en.json").exists());
+}
+
+#[test]
+fn test_config_reset_without_terminal_keeps_config() {
+    let home = TempHome::new("reset-prompt");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    let config = fs::read_to_string(home.config_file()).unwrap();
+
+    // The confirmation cannot be answered without a terminal
+    let output = home.kdguard(&["config", "reset"]);
+    assert!(!output.status.success());
+    assert_eq!(fs::read_to_string(home.config_file()).unwrap(), config);
+    assert!(!home.config_dir().join("config.toml.bak").exists());
+}
+
+/// Paths printed by `config path` for a config directory
+fn paths_in(dir: &Path) -> Vec<PathBuf> {
+    vec![
+        dir.join("config.toml"),
+        dir.join("languages"),
+        dir.join("logs"),
+    ]
+}
+
+fn printed_paths(output: &Output) -> Vec<PathBuf> {
+    assert!(output.status.success(), "{}", stderr(output));
+    stdout(output).lines().map(PathBuf::from).collect()
+}
+
+#[test]
+fn test_config_dir_env_moves_all_state() {
+    let home = TempHome::new("env");
+    let portable = home.0.join("portable");
+    let output = home
+        .command(&["config", "path"])
+        .env("KDGUARD_CONFIG_DIR", &portable)
+        .output()
+        .unwrap();
+
+    assert_eq!(printed_paths(&output), paths_in(&portable));
+    assert!(portable.join("config.toml").exists());
+    assert!(portable.join("languages").join("en.json").exists());
+    assert!(
+        fs::read_dir(portable.join("logs"))
+            .unwrap()
+            .next()
+            .is_some()
+    );
+    home.assert_default_dirs_untouched();
+}
+
+#[test]
+fn test_config_flag_takes_precedence_over_env() {
+    let home = TempHome::new("flag");
+    let from_env = home.0.join("env");
+    let profile = home.0.join("profile");
+
+    for args in [
+        vec!["--config", profile.to_str().unwrap(), "config", "path"],
+        vec!["config", "path", &format!("--config={}", profile.display())],
+    ] {
+        let output = home
+            .command(&args)
+            .env("KDGUARD_CONFIG_DIR", &from_env)
+            .output()
+            .unwrap();
+        assert_eq!(printed_paths(&output), paths_in(&profile));
+    }
+    assert!(!from_env.exists());
+    home.assert_default_dirs_untouched();
+}
+
+#[test]
+fn test_unknown_profile_lists_available_profiles() {
+    let home = TempHome::new("profile");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    let mut config = fs::read_to_string(home.config_file()).unwrap();
+    config.push_str("\n[profiles.work]\ndefault_length = 32\n");
+    fs::write(home.config_file(), config).unwrap();
+
+    let output = home
+        .command(&["config", "show"])
+        .env("KDGUARD_PROFILE", "home")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(10));
+    assert!(
+        stderr(&output).contains("'home' (available: work)"),
+        "{}",
+        stderr(&output)
+    );
+
+    // The flag wins over the variable
+    let output = home
+        .command(&["config", "show", "--profile", "other"])
+        .env("KDGUARD_PROFILE", "work")
+        .output()
+        .unwrap();
+    assert!(stderr(&output).contains("'other'"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_config_get_and_set() {
+    let home = TempHome::new("get-set");
+
+    let output = home.kdguard(&["config", "set", "general.default_length", "24"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let output = home.kdguard(&["config", "get", "general.default_length"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "24\n");
+
+    let output = home.kdguard(&["config", "set", "general.auto_save", "maybe"]);
+    assert_eq!(output.status.code(), Some(10));
+    assert!(
+        stderr(&output).contains("expected true or false"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = home.kdguard(&["config", "get", "general.colour"]);
+    assert_eq!(output.status.code(), Some(10));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).contains("general.colour"));
+}
+
+#[test]
+fn test_config_show_machine_formats() {
+    let home = TempHome::new("show-formats");
+    for (key, value) in [
+        ("general.default_length", "24"),
+        ("theme", "light"),
+        ("services.github", "work account"),
+    ] {
+        let output = home.kdguard(&["config", "set", key, value]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    }
+    let on_disk: Config = toml::from_str(&fs::read_to_string(home.config_file()).unwrap()).unwrap();
+
+    let output = home.kdguard(&["config", "show", "--format", "json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stdout(&output).contains('\x1b'));
+    let shown: Config = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown.general.default_length, 24);
+    assert_eq!(
+        serde_json::to_value(&shown).unwrap(),
+        serde_json::to_value(&on_disk).unwrap()
+    );
+
+    let output = home.kdguard(&["config", "show", "--format", "toml"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stdout(&output).contains('\x1b'));
+    let shown: Config = toml::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown.services["github"], "work account");
+    assert_eq!(
+        serde_json::to_value(&shown).unwrap(),
+        serde_json::to_value(&on_disk).unwrap()
+    );
+
+    // The listing stays the default
+    let output = home.kdguard(&["config", "show"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(serde_json::from_str::<serde_json::Value>(&stdout(&output)).is_err());
+}
+
+#[test]
+fn test_config_export_and_import_between_machines() {
+    let first = TempHome::new("export");
+    let second = TempHome::new("import");
+    assert!(
+        first
+            .kdguard(&["config", "set", "general.default_words", "6"])
+            .status
+            .success()
+    );
+
+    let output = first.kdguard(&["config", "export"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let exported = first.0.join("exported.toml");
+    fs::write(&exported, &output.stdout).unwrap();
+
+    let output = second.kdguard(&["config", "import", exported.to_str().unwrap(), "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("+ general.default_words = 6"));
+    let output = second.kdguard(&["config", "get", "general.default_words"]);
+    assert_eq!(stdout(&output), "6\n");
+
+    // Invalid files are refused before anything is asked or saved
+    let config = fs::read_to_string(second.config_file()).unwrap();
+    let invalid = String::from_utf8_lossy(&first.kdguard(&["config", "export"]).stdout)
+        .replace("default_length = 16", "default_length = 2");
+    fs::write(&exported, invalid).unwrap();
+    let output = second.kdguard(&["config", "import", exported.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("general.default_length = 2"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(fs::read_to_string(second.config_file()).unwrap(), config);
+}
+
+#[test]
+fn test_custom_language_survives_startup() {
+    let home = TempHome::new("languages");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    let languages = home.config_dir().join("languages");
+    let french = fs::read_to_string(languages.join("en.json"))
+        .unwrap()
+        .replace(
+            "Configuration successfully updated",
+            "Configuration mise à jour",
+        );
+    fs::write(languages.join("fr.json"), &french).unwrap();
+
+    let output = home.kdguard(&["config", "set", "language.lang", "fr"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let output = home.kdguard(&["config", "get", "language.lang"]);
+    assert_eq!(stdout(&output), "fr\n");
+    assert_eq!(
+        fs::read_to_string(languages.join("fr.json")).unwrap(),
+        french
+    );
+
+    let output = home.kdguard(&["config", "set", "language.lang", "es"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("one of de, en, fr"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_broken_config_falls_back_to_defaults() {
+    let home = TempHome::new("broken");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    fs::write(home.config_file(), "[general\ndefault_length = 24\n").unwrap();
+
+    let output = home.kdguard(&["--help"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).contains("--profile"));
+
+    let output = home.kdguard(&["config", "show"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Default Length: 16"));
+    assert!(stderr(&output).contains("using the built-in defaults"));
+    assert!(stderr(&output).contains("line 1"), "{}", stderr(&output));
+    assert!(stderr(&output).contains("kdguard config reset"));
+
+    // Nothing is regenerated without asking
+    assert_eq!(
+        fs::read_to_string(home.config_file()).unwrap(),
+        "[general\ndefault_length = 24\n"
+    );
+}
+
+#[test]
+fn test_tui_starts_with_broken_config() {
+    // The TUI needs a terminal, which `script` provides
+    if Command::new("script").arg("--version").output().is_err() {
+        return;
+    }
+    let home = TempHome::new("broken-tui");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    fs::write(home.config_file(), "[general\ndefault_length = 24\n").unwrap();
+
+    let kdguard = home.command(&[]);
+    let mut script = Command::new("script");
+    script
+        .args(["-qec", "stty cols 120 rows 40; \"$KDGUARD\"", "/dev/null"])
+        .env("KDGUARD", kdguard.get_program())
+        .envs(
+            kdguard
+                .get_envs()
+                .filter_map(|(key, value)| value.map(|value| (key, value))),
+        )
+        .env_remove("KDGUARD_CONFIG_DIR")
+        .env_remove("KDGUARD_PROFILE")
+        .env_remove("KDGUARD_LOG")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = script.spawn().unwrap();
+
+    // Quit and confirm until the TUI exits, keys sent before raw mode are buffered
+    let mut stdin = child.stdin.take().unwrap();
+    let started = Instant::now();
+    while child.try_wait().unwrap().is_none() && started.elapsed() < Duration::from_secs(20) {
+        let _ = stdin.write_all(b"qy");
+        let _ = stdin.flush();
+        thread::sleep(Duration::from_millis(500));
+    }
+    drop(stdin);
+    if child.try_wait().unwrap().is_none() {
+        let _ = child.kill();
+    }
+    let output = child.wait_with_output().unwrap();
+    let printed = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(0), "{}", printed);
+    assert!(!printed.contains("panicked"), "{}", printed);
+    assert!(
+        printed.contains("using the built-in defaults"),
+        "{}",
+        printed
+    );
+    assert_eq!(
+        fs::read_to_string(home.config_file()).unwrap(),
+        "[general\ndefault_length = 24\n"
+    );
+}
+
+#[test]
+fn test_unwritable_config_dir_still_prints_help() {
+    let home = TempHome::new("unwritable");
+    // A file where the directory should be cannot be created, not even by root
+    let blocker = home.0.join("blocker");
+    fs::write(&blocker, "").unwrap();
+
+    let temp = home.0.join("tmp");
+    fs::create_dir_all(&temp).unwrap();
+
+    let output = home
+        .command(&["--help"])
+        .env("KDGUARD_CONFIG_DIR", blocker.join("kdguard"))
+        .env("TMPDIR", &temp)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).contains("--profile"));
+    // The embedded languages are used as they are, not written to a shared directory
+    assert!(!temp.join("kdguard").join("languages").exists());
+}
+
+#[test]
+fn test_verbose_logs_to_stderr_without_log_file() {
+    let home = TempHome::new("verbose");
+    let logs = home.0.join("state").join("kdguard");
+
+    let output = home.kdguard(&["config", "get", "language.lang", "-v", "--no-log-file"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "en\n");
+    assert!(stderr(&output).contains("[INFO]"), "{}", stderr(&output));
+    assert!(!logs.exists());
+
+    // The variable sets the level, the flag wins over it
+    let output = home
+        .command(&["config", "get", "language.lang", "-v", "--no-log-file"])
+        .env("KDGUARD_LOG", "warn")
+        .output()
+        .unwrap();
+    assert!(!stderr(&output).contains("[INFO]"), "{}", stderr(&output));
+    let output = home
+        .command(&["-v", "--log-level", "info", "--no-log-file"])
+        .args(["config", "get", "language.lang"])
+        .env("KDGUARD_LOG", "warn")
+        .output()
+        .unwrap();
+    assert!(stderr(&output).contains("[INFO]"), "{}", stderr(&output));
+    assert!(!logs.exists());
+
+    assert!(
+        home.kdguard(&["config", "get", "language.lang"])
+            .status
+            .success()
+    );
+    assert!(logs.join("logs").exists());
+}
+
+#[test]
+fn test_partial_translation_falls_back_to_english() {
+    let home = TempHome::new("partial-language");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+    let output = home.kdguard(&["config", "set", "language.lang", "de"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // A custom translation that only covers a single text
+    let german = home.config_dir().join("languages").join("de.json");
+    fs::write(
+        &german,
+        r#"{"cli": {"about": "Sichere Passwörter erzeugen"}}"#,
+    )
+    .unwrap();
+
+    let output = home.kdguard(&["--help"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Sichere Passwörter erzeugen"));
+    assert!(
+        stdout(&output).contains("Exit codes:"),
+        "{}",
+        stdout(&output)
+    );
+
+    let output = home.kdguard(&["check", "Xy9$mK2@nP7#qW"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Password Health Check"));
+    let log = fs::read_dir(home.0.join("state/kdguard/logs"))
+        .unwrap()
+        .filter_map(|entry| fs::read_to_string(entry.unwrap().path()).ok())
+        .collect::<String>();
+    assert!(log.contains("Missing translation for key"), "{}", log);
+
+    // A file cut off in the middle is not valid JSON at all
+    fs::write(&german, r#"{"cli": {"about": "Sichere"#).unwrap();
+    let output = home.kdguard(&["config", "show"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("Default Length"),
+        "{}",
+        stdout(&output)
+    );
+}
@@ -0,0 +1,726 @@
+//! Runs password generation through the `generate` command and the deprecated root options
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+
+mod common;
+
+use common::{TempHome, stderr, stdout};
+
+#[test]
+fn test_both_invocation_styles_reject_the_same_arguments() {
+    let home = TempHome::new("generate-invalid");
+    for args in [&["--mode", "ramdom"][..], &["--length", "many"][..]] {
+        let root = home.kdguard(args);
+        let generate = home.kdguard(&[&["generate"], args].concat());
+        assert_eq!(root.status.code(), Some(2));
+        assert_eq!(generate.status.code(), root.status.code());
+
+        // Only the usage line differs between the two forms
+        let error = |output| {
+            stderr(output)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert_eq!(error(&generate), error(&root));
+    }
+}
+
+#[test]
+fn test_generate_help_lists_the_generation_options() {
+    let home = TempHome::new("generate-help");
+
+    let output = home.kdguard(&["--help"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("generate"));
+    assert!(!stdout(&output).contains("--seed-env"));
+
+    let output = home.kdguard(&["generate", "--help"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    for option in [
+        "--mode",
+        "--length",
+        "--count",
+        "--seed-env",
+        "--kdf",
+        "--output",
+    ] {
+        assert!(stdout(&output).contains(option), "{}", stdout(&output));
+    }
+}
+
+#[test]
+fn test_both_invocation_styles_generate_the_same_password() {
+    let home = TempHome::new("generate-same");
+    let options = [
+        "-m",
+        "deterministic",
+        "--seed-env",
+        "KDGUARD_TEST_SEED",
+        "--service",
+        "github",
+    ];
+    let run = |args: &[&str]| {
+        home.command(args)
+            .env("KDGUARD_TEST_SEED", "correct horse battery staple")
+            .output()
+            .unwrap()
+    };
+
+    let root = run(&options);
+    let generate = run(&[&["generate"], &options[..]].concat());
+    assert!(root.status.success(), "{}", stderr(&root));
+    assert!(generate.status.success(), "{}", stderr(&generate));
+    assert_eq!(stdout(&generate), stdout(&root));
+
+    // Only the root form is deprecated
+    assert!(
+        stderr(&root).contains("kdguard generate"),
+        "{}",
+        stderr(&root)
+    );
+    assert!(!stderr(&generate).contains("deprecated"));
+
+    // Without a terminal nobody could answer the update prompt, so nothing is checked
+    assert!(!home.config_dir().join(".last_update_check").exists());
+}
+
+#[test]
+fn test_services_file_derives_one_password_per_service() {
+    let home = TempHome::new("generate-services");
+    let mut list: Vec<String> = (1..=50).map(|i| format!("service-{}", i)).collect();
+    list.extend(["".to_string(), "service-7".to_string(), "  ".to_string()]);
+    let services_file = home.0.join("services.txt");
+    fs::write(&services_file, list.join("\n")).unwrap();
+    let run = |args: &[&str]| {
+        home.command(args)
+            .env("KDGUARD_TEST_SEED", "correct horse battery staple")
+            .output()
+            .unwrap()
+    };
+    let deterministic = [
+        "generate",
+        "-m",
+        "deterministic",
+        "--seed-env",
+        "KDGUARD_TEST_SEED",
+    ];
+
+    let output = run(&[
+        &deterministic[..],
+        &["--services-file", services_file.to_str().unwrap()],
+    ]
+    .concat());
+    assert!(output.status.success(), "{}", stderr(&output));
+    let printed = stdout(&output);
+    let lines: Vec<(&str, &str)> = printed
+        .lines()
+        .map(|line| line.split_once('\t').unwrap())
+        .collect();
+    assert_eq!(lines.len(), 50);
+    assert_eq!(lines[0].0, "service-1");
+    assert_eq!(lines[49].0, "service-50");
+    assert!(lines.iter().all(|(_, password)| password.len() == 20));
+    assert!(stderr(&output).contains("Skipped 3"), "{}", stderr(&output));
+
+    // Each line matches the password of a single service
+    let single = run(&[&deterministic[..], &["--service", "service-7"]].concat());
+    assert!(stdout(&single).lines().any(|line| line == lines[6].1));
+
+    // `--service -` reads the same list from stdin
+    let mut child = home
+        .command(&[&deterministic[..], &["--service", "-"]].concat())
+        .env("KDGUARD_TEST_SEED", "correct horse battery staple")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(list.join("\n").as_bytes())
+        .unwrap();
+    let piped = child.wait_with_output().unwrap();
+    assert!(piped.status.success(), "{}", stderr(&piped));
+    assert_eq!(stdout(&piped), printed);
+}
+
+#[test]
+fn test_print0_separates_passwords_with_nul() {
+    let home = TempHome::new("generate-print0");
+    let seeded = |args: &[&str]| {
+        home.command(args)
+            .env("KDGUARD_TEST_SEED", "correct horse battery staple")
+            .output()
+            .unwrap()
+    };
+    let deterministic = [
+        "generate",
+        "-m",
+        "deterministic",
+        "--seed-env",
+        "KDGUARD_TEST_SEED",
+    ];
+    let services = home.0.join("services.txt");
+    fs::write(&services, "github\ngitlab\n").unwrap();
+    let listed = seeded(
+        &[
+            &deterministic[..],
+            &["--services-file", services.to_str().unwrap()],
+        ]
+        .concat(),
+    );
+    let passwords: Vec<String> = stdout(&listed)
+        .lines()
+        .map(|line| line.split_once('\t').unwrap().1.to_string())
+        .collect();
+
+    let output = seeded(&[&deterministic[..], &["--service", "github", "-0"]].concat());
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(output.stdout, format!("{}\0", passwords[0]).into_bytes());
+
+    let output = seeded(
+        &[
+            &deterministic[..],
+            &["--print0", "--services-file", services.to_str().unwrap()],
+        ]
+        .concat(),
+    );
+    assert_eq!(
+        output.stdout,
+        format!("github\t{}\0gitlab\t{}\0", passwords[0], passwords[1]).into_bytes()
+    );
+
+    let output = home.kdguard(&["generate", "-c", "3", "-l", "12", "-0"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(output.stdout.len(), 3 * 13);
+    assert_eq!(output.stdout.last(), Some(&0));
+    assert!(
+        output
+            .stdout
+            .split(|byte| *byte == 0)
+            .take(3)
+            .all(|p| p.len() == 12)
+    );
+
+    // The saved file stays newline separated and the notice goes to stderr
+    let file = home.0.join("passwords.txt");
+    let output = seeded(
+        &[
+            &deterministic[..],
+            &["--service", "github", "-0", "-o", file.to_str().unwrap()],
+        ]
+        .concat(),
+    );
+    assert_eq!(output.stdout, format!("{}\0", passwords[0]).into_bytes());
+    assert!(stderr(&output).contains("Saved 1"), "{}", stderr(&output));
+    assert!(
+        fs::read_to_string(&file)
+            .unwrap()
+            .ends_with(&format!("\n{}\n", passwords[0]))
+    );
+}
+
+#[test]
+fn test_passwords_written_to_a_file_stay_off_stdout() {
+    let home = TempHome::new("generate-output");
+    let file = home.0.join("passwords.txt");
+    let file_arg = file.to_str().unwrap();
+
+    let output = home.kdguard(&["generate", "-c", "3", "-o", file_arg]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let saved: Vec<String> = fs::read_to_string(&file)
+        .unwrap()
+        .lines()
+        .rev()
+        .take(3)
+        .map(str::to_string)
+        .collect();
+    assert_eq!(saved.len(), 3);
+    let printed = stdout(&output);
+    assert_eq!(
+        printed.trim(),
+        format!("Saved 3 password(s) to {}", file_arg)
+    );
+    assert!(
+        saved
+            .iter()
+            .all(|password| !printed.contains(password.as_str()))
+    );
+
+    // auto_save from the config behaves the same
+    let output_dir = home.0.join("saved");
+    let set = |key: &str, value: &str| {
+        let output = home.kdguard(&["config", "set", key, value]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    };
+    set("general.auto_save", "true");
+    set("general.output_dir", output_dir.to_str().unwrap());
+    let output = home.kdguard(&["generate", "-c", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let saved = fs::read_to_string(output_dir.join("kdguard.txt")).unwrap();
+    let printed = stdout(&output);
+    assert!(printed.starts_with("Saved 2"), "{}", printed);
+    assert!(
+        saved
+            .lines()
+            .rev()
+            .take(2)
+            .all(|password| !printed.contains(password))
+    );
+
+    // --show prints them as well
+    let output = home.kdguard(&["generate", "-c", "2", "--show"]);
+    let saved = fs::read_to_string(output_dir.join("kdguard.txt")).unwrap();
+    assert!(
+        saved
+            .lines()
+            .rev()
+            .take(2)
+            .all(|password| stdout(&output).contains(password))
+    );
+}
+
+#[test]
+fn test_verify_policy_keeps_the_password_off_stdout() {
+    let home = TempHome::new("generate-verify-policy");
+    let seeded = |args: &[&str]| {
+        home.command(args)
+            .env("KDGUARD_TEST_SEED", "correct horse battery staple")
+            .output()
+            .unwrap()
+    };
+    let deterministic = [
+        "generate",
+        "-m",
+        "deterministic",
+        "--seed-env",
+        "KDGUARD_TEST_SEED",
+        "--service",
+        "github",
+    ];
+    let output = seeded(&[&deterministic[..], &["-0"]].concat());
+    assert!(output.status.success(), "{}", stderr(&output));
+    let password = stdout(&output).trim_end_matches('\0').to_string();
+    assert_eq!(password.chars().count(), 20);
+
+    for policy in [
+        &["--verify-policy", "nist-800-63b"][..],
+        &["--verify-policy"],
+    ] {
+        let output = seeded(&[&deterministic[..], policy].concat());
+        assert!(
+            matches!(output.status.code(), Some(0) | Some(2)),
+            "{}",
+            stderr(&output)
+        );
+        let printed = format!("{}{}", stdout(&output), stderr(&output));
+        assert!(stdout(&output).contains("Character Classes"), "{}", printed);
+        let chars: Vec<char> = password.chars().collect();
+        for window in chars.windows(4) {
+            let fragment: String = window.iter().collect();
+            assert!(!printed.contains(&fragment), "leaked {}", fragment);
+        }
+    }
+
+    // --show reveals it on request
+    let output = seeded(&[&deterministic[..], &["--verify-policy", "--show"]].concat());
+    assert!(stdout(&output).contains(&password), "{}", stdout(&output));
+}
+
+#[test]
+fn test_log_file_keeps_user_values_out() {
+    // A valid pattern, so every run succeeds
+    const MARKER: &str = "SDULUDLS";
+    let home = TempHome::new("generate-redacted");
+    let output_dir = home.0.join(MARKER);
+    fs::create_dir_all(&output_dir).unwrap();
+    let file = output_dir.join(format!("{}.txt", MARKER));
+    let file_arg = file.to_str().unwrap();
+    let runs = [
+        vec!["generate", "-m", "pattern", "-p", MARKER, "-o", file_arg],
+        vec![
+            "generate",
+            "-m",
+            "deterministic",
+            "--seed-env",
+            MARKER,
+            "--service",
+            MARKER,
+            "--salt",
+            MARKER,
+            "-o",
+            file_arg,
+        ],
+    ];
+    let logs = |args: &[&str]| {
+        let output = home.command(args).env(MARKER, MARKER).output().unwrap();
+        assert!(output.status.success(), "{}", stderr(&output));
+        let dir = home.0.join("state").join("kdguard").join("logs");
+        let logs: String = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect();
+        fs::remove_dir_all(dir).unwrap();
+        logs
+    };
+
+    for args in &runs {
+        let logged = logs(args);
+        assert!(logged.contains("<redacted"), "{}", logged);
+        assert!(!logged.contains(MARKER), "{}", logged);
+    }
+
+    // Only an explicit trace level writes them
+    let logged = logs(&[&runs[1][..], &["--log-level", "trace"]].concat());
+    assert!(logged.contains(MARKER), "{}", logged);
+    let logged = logs(&[&runs[1][..], &["-vvv"]].concat());
+    assert!(!logged.contains(MARKER), "{}", logged);
+}
+
+#[test]
+fn test_json_log_format_writes_json_lines() {
+    let home = TempHome::new("generate-json-log");
+    let logs_dir = home.0.join("state").join("kdguard").join("logs");
+    let read_logs = || {
+        let logs: Vec<String> = fs::read_dir(&logs_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with("kdguard_")
+            })
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect();
+        fs::remove_dir_all(&logs_dir).unwrap();
+        logs.concat()
+    };
+
+    let output = home.kdguard(&["generate", "-c", "2", "--log-format", "json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let logged = read_logs();
+    assert!(!logged.is_empty());
+    for line in logged.lines() {
+        let record: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(record["msg"].is_string(), "{}", line);
+        assert!(record["ts"].is_string(), "{}", line);
+    }
+
+    // The config sets the format as well, the flag wins over it
+    let output = home.kdguard(&["config", "set", "logging.log_format", "json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    read_logs();
+    assert!(home.kdguard(&["generate"]).status.success());
+    assert!(read_logs().lines().all(|line| line.starts_with('{')));
+    assert!(
+        home.kdguard(&["generate", "--log-format", "text"])
+            .status
+            .success()
+    );
+    assert!(read_logs().lines().all(|line| !line.starts_with('{')));
+}
+
+#[test]
+fn test_generation_failures_exit_with_their_own_code() {
+    let home = TempHome::new("generate-exit-code");
+    let output = home
+        .command(&[
+            "generate",
+            "-m",
+            "deterministic",
+            "--seed-env",
+            "KDGUARD_UNSET_SEED",
+        ])
+        .env_remove("KDGUARD_UNSET_SEED")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(20), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("Generation error:"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(stderr(&output).contains("seed_env"), "{}", stderr(&output));
+
+    let output = home.kdguard(&["--help"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("Exit codes:"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_argon2id_derivation_follows_the_config_parameters() {
+    let home = TempHome::new("generate-kdf");
+    for (key, value) in [
+        ("deterministic.memory_kib", "256"),
+        ("deterministic.iterations", "2"),
+    ] {
+        let output = home.kdguard(&["config", "set", key, value]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    }
+    let derive = |extra: &[&str]| {
+        let args = [
+            &[
+                "generate",
+                "-m",
+                "deterministic",
+                "--seed-env",
+                "KDGUARD_TEST_SEED",
+            ],
+            extra,
+        ]
+        .concat();
+        let output = home
+            .command(&args)
+            .env("KDGUARD_TEST_SEED", "correct horse battery staple")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", stderr(&output));
+        stdout(&output)
+    };
+
+    // HKDF stays the default, the same password as in earlier versions
+    assert!(
+        derive(&[]).contains("puD1zR$c3(zBCApWfLie"),
+        "{}",
+        derive(&[])
+    );
+    let stretched = derive(&["--kdf", "argon2id"]);
+    assert!(stretched.contains("%H-euB44sPDpfg1lWv9@"), "{}", stretched);
+
+    let output = home.kdguard(&["config", "set", "deterministic.kdf", "argon2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(derive(&[]), stretched);
+    assert!(derive(&["--kdf", "hkdf"]).contains("puD1zR$c3(zBCApWfLie"));
+}
+
+#[test]
+fn test_seed_fingerprint_is_shown_and_compared() {
+    let home = TempHome::new("generate-fingerprint");
+    let log_dir = home.0.join("state").join("kdguard").join("logs");
+    let run = |seed: &str, extra: &[&str]| {
+        let args = [
+            &[
+                "generate",
+                "-m",
+                "deterministic",
+                "--seed-env",
+                "KDGUARD_TEST_SEED",
+            ],
+            extra,
+        ]
+        .concat();
+        let _ = fs::remove_dir_all(&log_dir);
+        let output = home
+            .command(&args)
+            .env("KDGUARD_TEST_SEED", seed)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", stderr(&output));
+        let logged: String = fs::read_dir(&log_dir)
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        (stderr(&output), logged)
+    };
+    let fingerprint = |printed: &str| {
+        let line = printed
+            .lines()
+            .find(|line| line.starts_with("Seed fingerprint: "))
+            .unwrap_or_else(|| panic!("{}", printed));
+        line.trim_start_matches("Seed fingerprint: ").to_string()
+    };
+
+    let (printed, logged) = run("correct horse battery staple", &[]);
+    let usual = fingerprint(&printed);
+    assert_eq!(usual.split('-').count(), 2, "{}", usual);
+    assert_eq!(
+        fingerprint(&run("correct horse battery staple", &[]).0),
+        usual
+    );
+    let typo = fingerprint(&run("correct horse battery stapel", &[]).0);
+    assert_ne!(typo, usual);
+
+    // Redacted like the other user values
+    assert!(!logged.contains(&usual), "{}", logged);
+    let (_, logged) = run("correct horse battery staple", &["--log-level", "trace"]);
+    assert!(logged.contains(&usual), "{}", logged);
+
+    let output = home.kdguard(&["config", "set", "deterministic.fingerprint", &usual]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let (printed, _) = run("correct horse battery staple", &[]);
+    assert!(!printed.contains("WARNING"), "{}", printed);
+    let (printed, _) = run("correct horse battery stapel", &[]);
+    assert!(
+        printed.contains(&format!("{} does not match the saved {}", typo, usual)),
+        "{}",
+        printed
+    );
+}
+
+#[test]
+fn test_backup_sheet_never_contains_the_seed() {
+    const SEED: &str = "correct horse battery staple";
+    let home = TempHome::new("generate-backup-sheet");
+    let output = home.kdguard(&["config", "set", "deterministic.kdf", "argon2id"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let config = fs::read_to_string(home.config_file()).unwrap();
+    let config = config.replace("[services]\n", "[services]\ngithub = \"work account\"\n");
+    fs::write(home.config_file(), config).unwrap();
+
+    // The fingerprint comes from the derived key, so it depends on the salt as well
+    let fingerprint_with = |extra: &[&str]| {
+        let args = [
+            &[
+                "generate",
+                "-m",
+                "deterministic",
+                "--seed-env",
+                "KDGUARD_TEST_SEED",
+            ],
+            extra,
+        ]
+        .concat();
+        stderr(
+            &home
+                .command(&args)
+                .env("KDGUARD_TEST_SEED", SEED)
+                .output()
+                .unwrap(),
+        )
+        .lines()
+        .find_map(|line| line.strip_prefix("Seed fingerprint: ").map(str::to_string))
+        .unwrap()
+    };
+    let fingerprint = fingerprint_with(&["--salt", "2024"]);
+
+    let sheet_file = home.0.join("sheet.md");
+    let output = home
+        .command(&[
+            "backup-sheet",
+            "--seed-env",
+            "KDGUARD_TEST_SEED",
+            "--salt",
+            "2024",
+            "--service",
+            "mail",
+            "--format",
+            "markdown",
+            "--output",
+            sheet_file.to_str().unwrap(),
+        ])
+        .env("KDGUARD_TEST_SEED", SEED)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    let sheet = fs::read_to_string(&sheet_file).unwrap();
+    assert!(sheet.starts_with("# "), "{}", sheet);
+    assert!(sheet.contains(&fingerprint), "{}", sheet);
+    assert!(sheet.contains("- github: work account"), "{}", sheet);
+    assert!(
+        sheet.contains("--salt 2024 --kdf argon2id --service mail"),
+        "{}",
+        sheet
+    );
+
+    let output = home
+        .command(&["backup-sheet", "--seed-env", "KDGUARD_TEST_SEED"])
+        .env("KDGUARD_TEST_SEED", SEED)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    for printed in [&sheet, &stdout(&output), &stderr(&output)] {
+        assert!(!printed.contains(SEED), "{}", printed);
+        assert!(!printed.contains("correct"), "{}", printed);
+    }
+    assert!(stdout(&output).contains(&fingerprint_with(&[])));
+}
+
+#[test]
+fn test_named_patterns_from_the_config() {
+    let home = TempHome::new("generate-named-patterns");
+    let output = home.kdguard(&["config", "set", "general.default_count", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let config = fs::read_to_string(home.config_file()).unwrap();
+    let config = config.replace("[patterns]\n", "[patterns]\ndoor = \"{digit}DDD\"\n");
+    fs::write(home.config_file(), config).unwrap();
+
+    let output = home.kdguard(&["patterns", "list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let list = stdout(&output);
+    assert!(
+        list.lines()
+            .any(|line| line.starts_with("@door") && line.ends_with("{digit}DDD  (config)")),
+        "{}",
+        list
+    );
+    assert!(list.contains("@pin6"), "{}", list);
+    assert!(list.contains("20 bits"), "{}", list);
+
+    let output = home.kdguard(&["generate", "-m", "pattern", "-p", "@door"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let printed = stdout(&output);
+    assert!(
+        printed
+            .lines()
+            .any(|line| line.len() == 4 && line.chars().all(|c| c.is_ascii_digit())),
+        "{}",
+        printed
+    );
+
+    let output = home.kdguard(&["generate", "-m", "pattern", "-p", "@nope"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("@nope"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_expire_check_lists_expired_passwords() {
+    let home = TempHome::new("generate-expires");
+    let file = home.0.join("passwords.csv");
+    let file_arg = file.to_str().unwrap();
+
+    let output = home.kdguard(&["generate", "-c", "2", "--expires", "24h", "-o", file_arg]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Expires "), "{}", stdout(&output));
+    let output = home.kdguard(&["expire-check", file_arg]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "0 of 2 password(s) expired");
+
+    // Rewrite the expiry of the second password into the past
+    let content = fs::read_to_string(&file).unwrap();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let (row, _) = lines[2].rsplit_once(',').unwrap();
+    lines[2] = format!("{},2020-01-12T14:00:00+00:00", row);
+    fs::write(&file, lines.join("\n") + "\n").unwrap();
+
+    let output = home.kdguard(&["expire-check", file_arg]);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+    let printed = stdout(&output);
+    assert!(
+        printed.contains("Entry 2 expired on 2020-01-12"),
+        "{}",
+        printed
+    );
+    assert!(
+        printed.contains("1 of 2 password(s) expired"),
+        "{}",
+        printed
+    );
+    assert!(!printed.contains(lines[2].split(',').next().unwrap()));
+
+    let output = home.kdguard(&["generate", "--expires", "soon"]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,211 @@
+//! Runs the version output and `update` and `uninstall` without a terminal to answer
+//! their prompts
+#![cfg(target_os = "linux")]
+
+use std::process::Stdio;
+
+mod common;
+
+use common::{TempHome, stderr, stdout};
+
+#[test]
+fn test_uninstall_without_terminal_requires_yes() {
+    let home = TempHome::new("uninstall");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+
+    let output = home
+        .command(&["uninstall"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("pass --yes"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(home.config_file().exists());
+    assert!(std::path::Path::new(env!("CARGO_BIN_EXE_kdguard")).exists());
+}
+
+#[test]
+fn test_uninstall_dry_run_changes_nothing() {
+    let home = TempHome::new("uninstall-dry-run");
+    assert!(home.kdguard(&["config", "path"]).status.success());
+
+    let output = home
+        .command(&["uninstall", "--dry-run"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stdout = stdout(&output);
+    for path in [
+        home.config_dir(),
+        home.config_dir().join("languages"),
+        home.0.join("state").join("kdguard").join("logs"),
+        env!("CARGO_BIN_EXE_kdguard").into(),
+    ] {
+        assert!(
+            stdout.contains(&format!("{} (exists)", path.display())),
+            "{}",
+            stdout
+        );
+    }
+    assert!(stdout.contains("kdguard-config-backup.toml (written)"));
+    assert!(home.config_file().exists());
+    assert!(!home.0.join("kdguard-config-backup.toml").exists());
+    assert!(std::path::Path::new(env!("CARGO_BIN_EXE_kdguard")).exists());
+}
+
+#[test]
+fn test_uninstall_keeps_shared_config_dir() {
+    let home = TempHome::new("uninstall-shared");
+    let shared = home.0.join("shared");
+    std::fs::create_dir_all(&shared).unwrap();
+    let document = shared.join("important.doc");
+    std::fs::write(&document, "not from kdguard").unwrap();
+    let config = shared.to_str().unwrap();
+
+    let output = home
+        .command(&["--config", config, "uninstall", "--dry-run"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains(&document.display().to_string()),
+        "{}",
+        stdout(&output)
+    );
+    // Resolving the paths created nothing
+    let entries: Vec<_> = std::fs::read_dir(&shared)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(entries, std::slice::from_ref(&document));
+    home.assert_default_dirs_untouched();
+
+    let output = home
+        .command(&["--config", config, "uninstall", "--yes"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--force"), "{}", stderr(&output));
+    assert!(document.exists());
+    assert!(std::path::Path::new(env!("CARGO_BIN_EXE_kdguard")).exists());
+}
+
+#[test]
+fn test_update_without_terminal_requires_yes() {
+    let home = TempHome::new("update");
+
+    // Fails before any request is made
+    let output = home
+        .command(&["update"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("pass --yes"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!home.config_dir().join(".last_update_check").exists());
+}
+
+#[test]
+fn test_version_json_lists_build_details() {
+    let home = TempHome::new("version");
+
+    let output = home.kdguard(&["--version"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let plain = stdout(&output);
+    assert!(
+        plain.starts_with(&format!("kdguard {} (", env!("CARGO_PKG_VERSION"))),
+        "{}",
+        plain
+    );
+
+    let output = home.kdguard(&["--version", "--json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert!(plain.contains(&format!("({})", json["commit"].as_str().unwrap())));
+    assert_eq!(
+        json["config_path"],
+        home.config_file().display().to_string()
+    );
+    assert!(!json["target"].as_str().unwrap().is_empty());
+
+    assert!(!home.kdguard(&["--json"]).status.success());
+}
+
+#[test]
+fn test_logs_prints_tails_and_clears_the_log_files() {
+    let home = TempHome::new("logs");
+    let logs_dir = home.0.join("state").join("kdguard").join("logs");
+
+    let output = home.kdguard(&["logs"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), logs_dir.to_str().unwrap());
+    assert_eq!(stdout(&home.kdguard(&["logs", "--path"])), stdout(&output));
+
+    let output = home.kdguard(&["logs", "--tail", "5"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert!(
+        stderr(&output).contains("no log files"),
+        "{}",
+        stderr(&output)
+    );
+
+    // The logs command writes no log of its own, so the tail is the generate run
+    assert!(home.kdguard(&["generate"]).status.success());
+    let output = home.kdguard(&["logs", "--tail", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output).lines().count(), 2, "{}", stdout(&output));
+
+    let output = home
+        .command(&["logs", "--clear"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr(&output).contains("pass --yes"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = home.kdguard(&["logs", "--clear", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output).trim(), "Deleted 2 log file(s)");
+    assert_eq!(std::fs::read_dir(&logs_dir).unwrap().count(), 0);
+}
+
+#[test]
+fn test_system_info_is_only_written_when_allowed() {
+    let home = TempHome::new("system-info");
+    let system_info = home
+        .0
+        .join("state")
+        .join("kdguard")
+        .join("logs")
+        .join("system_info.log");
+
+    let output = home.kdguard(&["generate", "--no-system-info"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!system_info.exists());
+
+    assert!(home.kdguard(&["generate"]).status.success());
+    assert!(system_info.exists());
+
+    // Turning it off deletes the file of earlier runs
+    let output = home.kdguard(&["config", "set", "logging.collect_system_info", "false"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(home.kdguard(&["generate"]).status.success());
+    assert!(!system_info.exists());
+}